@@ -9,10 +9,12 @@
 #include <array>
 #include <functional>
 #include <limits>
+#include <map>
 #include <string>
 #include <iostream>
 #include <sstream>
 #include <utility>
+#include <unordered_map>
 #ifdef HAS_STDCXX_17
 #ifndef NO_HAVE_STD17_OPTIONAL
 #include <optional>
@@ -44,6 +46,7 @@
 #include "rust_interface/RustForeignVecFoo.h"
 #include "rust_interface/TestWorkWithVec.hpp"
 #include "rust_interface/c_MyEnum.h"
+#include "rust_interface/c_CheckedEnum.h"
 #include "rust_interface/TestEnumClass.hpp"
 #include "rust_interface/TestPassPathAsParam.hpp"
 #if defined(HAS_STDCXX_17) || defined(USE_BOOST)
@@ -69,6 +72,9 @@
 #include "rust_interface/Boo.hpp"
 #include "rust_interface/TestReturnTuple.hpp"
 #include "rust_interface/TestCopy.hpp"
+#include "rust_interface/RustBTreeMapStringF64.h"
+#include "rust_interface/RustHashMapStringF64.h"
+#include "rust_interface/TestMaps.hpp"
 
 using namespace rust;
 
@@ -458,6 +464,11 @@ TEST(TestEnumClass, smokeTest)
     ASSERT_EQ(ITEM2, TestEnumClass::next_enum(ITEM1));
     ASSERT_EQ(ITEM3, TestEnumClass::next_enum(ITEM2));
     ASSERT_EQ(ITEM1, TestEnumClass::next_enum(ITEM3));
+
+    ASSERT_EQ(1, x.checked(CE_A));
+    ASSERT_EQ(2, x.checked(CE_B));
+    EXPECT_THROW(x.checked(static_cast<CheckedEnum>(1)), std::invalid_argument);
+    EXPECT_THROW(x.checked(static_cast<CheckedEnum>(100)), std::invalid_argument);
 }
 
 TEST(TestPassPathAsParam, smokeTest)
@@ -865,6 +876,34 @@ TEST(RustString, Copy)
     ASSERT_EQ("AAAA", s3.to_std_string());
 }
 
+TEST(TestMaps, smokeTest)
+{
+    const std::map<std::string, double> prices{ { "apple", 1.5 }, { "pear", 2.25 } };
+    auto rust_prices = TestMaps::echo_prices(RustBTreeMapStringF64::from_std(prices));
+    EXPECT_EQ(2u, rust_prices.size());
+    std::map<std::string, double> prices_back;
+    for (auto &kv : rust_prices.to_std_map()) {
+        prices_back.emplace(kv.first.to_std_string(), kv.second);
+    }
+    EXPECT_EQ(prices, prices_back);
+    EXPECT_TRUE(rust_prices.empty());
+
+    const std::unordered_map<std::string, double> uprices{ { "a", 1. }, { "b", 2. } };
+    EXPECT_NEAR(3., TestMaps::total(RustHashMapStringF64::from_std(uprices)),
+                std::numeric_limits<double>::epsilon());
+#ifdef HAS_STDCXX_17
+    const std::unordered_map<std::string_view, double> vprices{ { "a", 1. }, { "b", 2.5 } };
+    EXPECT_NEAR(3.5, TestMaps::total(RustHashMapStringF64::from_std(vprices)),
+                std::numeric_limits<double>::epsilon());
+#endif
+    RustHashMapStringF64 m;
+    const std::string key{ "k" };
+    m.insert(key, 1.);
+    m.insert(std::string{ "l" }, 2.);
+    m.insert("m", 3.);
+    EXPECT_NEAR(6., TestMaps::total(std::move(m)), std::numeric_limits<double>::epsilon());
+}
+
 int main(int argc, char *argv[])
 {
     ::testing::InitGoogleTest(&argc, argv);
@@ -14,8 +14,10 @@ use crate::{
     source_registry::SourceId,
     typemap::ast::{normalize_ty_lifetimes, DisplayToTokens},
     types::{
-        ForeignEnumInfo, ForeignEnumItem, ForeignInterface, ForeignInterfaceMethod,
+        EnumItemKind, ForeignEnumInfo, ForeignEnumItem, ForeignFlagsInfo, ForeignFlagsItem,
+        ForeignInterface, ForeignInterfaceMethod, ForeignStructField, ForeignStructInfo,
         ForeignerClassInfo, ForeignerMethod, MethodAccess, MethodVariant, SelfTypeVariant,
+        UnknownEnumValue,
     },
     LanguageConfig,
 };
@@ -26,7 +28,13 @@ pub(crate) fn parse_foreigner_class(
     tokens: TokenStream,
 ) -> Result<ForeignerClassInfo> {
     match config {
-        LanguageConfig::CppConfig(_) => {
+        LanguageConfig::CppConfig(_)
+        | LanguageConfig::CSharpConfig(_)
+        | LanguageConfig::PythonConfig(_)
+        | LanguageConfig::CConfig(_)
+        | LanguageConfig::NodeJsConfig(_)
+        | LanguageConfig::DartConfig(_)
+        | LanguageConfig::GoConfig(_) => {
             let mut class: CppClass =
                 syn::parse2(tokens).map_err(|err| DiagnosticError::from_syn_err(src_id, err))?;
             class.0.src_id = src_id;
@@ -48,6 +56,26 @@ pub(crate) fn parse_foreign_enum(src_id: SourceId, tokens: TokenStream) -> Resul
     Ok(f_enum.0)
 }
 
+pub(crate) fn parse_foreign_struct(
+    src_id: SourceId,
+    tokens: TokenStream,
+) -> Result<ForeignStructInfo> {
+    let mut f_struct: ForeignStructInfoParser =
+        syn::parse2(tokens).map_err(|err| DiagnosticError::from_syn_err(src_id, err))?;
+    f_struct.0.src_id = src_id;
+    Ok(f_struct.0)
+}
+
+pub(crate) fn parse_foreign_flags(
+    src_id: SourceId,
+    tokens: TokenStream,
+) -> Result<ForeignFlagsInfo> {
+    let mut f_flags: ForeignFlagsInfoParser =
+        syn::parse2(tokens).map_err(|err| DiagnosticError::from_syn_err(src_id, err))?;
+    f_flags.0.src_id = src_id;
+    Ok(f_flags.0)
+}
+
 pub(crate) fn parse_foreign_interface(
     src_id: SourceId,
     tokens: TokenStream,
@@ -94,11 +122,20 @@ mod kw {
 struct Attrs {
     doc_comments: Vec<String>,
     derive_list: Vec<String>,
+    java_exception: Option<String>,
+    unknown_value: Option<syn::LitStr>,
 }
 
-fn parse_attrs(input: ParseStream, parse_derive_attrs: bool) -> syn::Result<Attrs> {
+fn parse_attrs(
+    input: ParseStream,
+    parse_derive_attrs: bool,
+    parse_java_exception_attr: bool,
+    parse_unknown_value_attr: bool,
+) -> syn::Result<Attrs> {
     let mut doc_comments = vec![];
     let mut derive_list = vec![];
+    let mut java_exception = None;
+    let mut unknown_value = None;
 
     if input.fork().call(syn::Attribute::parse_outer).is_ok() {
         let attr: Vec<syn::Attribute> = input.call(syn::Attribute::parse_outer)?;
@@ -112,6 +149,41 @@ fn parse_attrs(input: ParseStream, parse_derive_attrs: bool) -> syn::Result<Attr
                 }) if ident == "doc" => {
                     doc_comments.push(lit_str.value());
                 }
+                syn::Meta::NameValue(syn::MetaNameValue {
+                    ref ident,
+                    lit: syn::Lit::Str(ref lit_str),
+                    ..
+                }) if ident == "java_exception" && parse_java_exception_attr => {
+                    if java_exception.is_some() {
+                        return Err(syn::Error::new(
+                            a.span(),
+                            "java_exception attribute specified twice",
+                        ));
+                    }
+                    java_exception = Some(lit_str.value());
+                }
+                syn::Meta::NameValue(syn::MetaNameValue { ref ident, .. })
+                    if ident == "java_exception" =>
+                {
+                    return Err(syn::Error::new(
+                        a.span(),
+                        "java_exception attribute can be used only for methods \
+                         of foreigner_class in Java/JNI backend",
+                    ));
+                }
+                syn::Meta::NameValue(syn::MetaNameValue {
+                    ref ident,
+                    lit: syn::Lit::Str(ref lit_str),
+                    ..
+                }) if ident == "unknown_value" && parse_unknown_value_attr => {
+                    if unknown_value.is_some() {
+                        return Err(syn::Error::new(
+                            a.span(),
+                            "unknown_value attribute specified twice",
+                        ));
+                    }
+                    unknown_value = Some(lit_str.clone());
+                }
                 syn::Meta::List(syn::MetaList {
                     ref ident,
                     ref nested,
@@ -140,11 +212,13 @@ fn parse_attrs(input: ParseStream, parse_derive_attrs: bool) -> syn::Result<Attr
     Ok(Attrs {
         doc_comments,
         derive_list,
+        java_exception,
+        unknown_value,
     })
 }
 
 fn parse_doc_comments(input: ParseStream) -> syn::Result<Vec<String>> {
-    let Attrs { doc_comments, .. } = parse_attrs(input, false)?;
+    let Attrs { doc_comments, .. } = parse_attrs(input, false, false, false)?;
     Ok(doc_comments)
 }
 
@@ -152,7 +226,8 @@ fn do_parse_foreigner_class(lang: Language, input: ParseStream) -> syn::Result<F
     let Attrs {
         doc_comments: class_doc_comments,
         derive_list,
-    } = parse_attrs(&input, lang == Language::Cpp)?;
+        ..
+    } = parse_attrs(&input, lang == Language::Cpp, false, false)?;
     debug!(
         "parse_foreigner_class: class comment {:?}",
         class_doc_comments
@@ -175,7 +250,11 @@ fn do_parse_foreigner_class(lang: Language, input: ParseStream) -> syn::Result<F
     static STATIC_METHOD: &str = "static_method";
 
     while !content.is_empty() {
-        let doc_comments = parse_doc_comments(&&content)?;
+        let Attrs {
+            doc_comments,
+            java_exception,
+            ..
+        } = parse_attrs(&&content, false, lang == Language::Java, false)?;
         let mut access = if content.peek(kw::private) {
             content.parse::<kw::private>()?;
             MethodAccess::Private
@@ -278,6 +357,7 @@ fn do_parse_foreigner_class(lang: Language, input: ParseStream) -> syn::Result<F
                 name_alias: None,
                 access,
                 doc_comments,
+                java_exception,
             });
             has_dummy_constructor = true;
             continue;
@@ -399,6 +479,7 @@ fn do_parse_foreigner_class(lang: Language, input: ParseStream) -> syn::Result<F
             name_alias: func_name_alias,
             access,
             doc_comments,
+            java_exception,
         });
     }
 
@@ -434,32 +515,273 @@ struct ForeignEnumInfoParser(ForeignEnumInfo);
 
 impl Parse for ForeignEnumInfoParser {
     fn parse(input: ParseStream) -> syn::Result<Self> {
-        let enum_doc_comments = parse_doc_comments(input)?;
+        let Attrs {
+            doc_comments: enum_doc_comments,
+            unknown_value,
+            ..
+        } = parse_attrs(input, false, false, true)?;
         input.parse::<Token![enum]>()?;
         let enum_name = input.parse::<Ident>()?;
         debug!("ENUM NAME {:?}", enum_name);
         let item_parser;
         braced!(item_parser in input);
-        let mut items = vec![];
+        let mut items = Vec::<ForeignEnumItem>::new();
         while !item_parser.is_empty() {
             let doc_comments = parse_doc_comments(&item_parser)?;
             let f_item_name = item_parser.parse::<Ident>()?;
+            let (kind, fields) = if item_parser.peek(syn::token::Paren) {
+                let types_parser;
+                parenthesized!(types_parser in item_parser);
+                let types = Punctuated::<Type, Token![,]>::parse_terminated(&types_parser)?;
+                let fields = types
+                    .into_iter()
+                    .enumerate()
+                    .map(|(i, ty)| ForeignStructField {
+                        name: Ident::new(&format!("_{}", i), ty.span()),
+                        ty,
+                        doc_comments: vec![],
+                    })
+                    .collect();
+                (EnumItemKind::Tuple, fields)
+            } else if item_parser.peek(syn::token::Brace) {
+                let field_parser;
+                braced!(field_parser in item_parser);
+                (EnumItemKind::Struct, parse_struct_fields(&field_parser)?)
+            } else {
+                (EnumItemKind::Unit, vec![])
+            };
+            if kind != EnumItemKind::Unit && fields.is_empty() {
+                return Err(syn::Error::new(
+                    f_item_name.span(),
+                    format!("item '{}' without fields", f_item_name),
+                ));
+            }
             item_parser.parse::<Token![=]>()?;
             let item_name = item_parser.call(syn::Path::parse_mod_style)?;
+            let value = if item_parser.peek(Token![=]) {
+                item_parser.parse::<Token![=]>()?;
+                let lit = item_parser.parse::<syn::LitInt>()?;
+                if lit.value() > (i32::MAX as u64) {
+                    return Err(syn::Error::new(
+                        lit.span(),
+                        format!("discriminant {} is too big", lit.value()),
+                    ));
+                }
+                lit.value() as u32
+            } else {
+                match items.last() {
+                    Some(prev) if prev.value == (i32::MAX as u32) => {
+                        return Err(syn::Error::new(
+                            f_item_name.span(),
+                            format!("discriminant of '{}' is too big", f_item_name),
+                        ));
+                    }
+                    Some(prev) => prev.value + 1,
+                    None => 0,
+                }
+            };
+            if let Some(prev) = items.iter().find(|x| x.value == value) {
+                return Err(syn::Error::new(
+                    f_item_name.span(),
+                    format!(
+                        "discriminant {} of '{}' is already used by '{}'",
+                        value, f_item_name, prev.name
+                    ),
+                ));
+            }
             item_parser.parse::<Token![,]>()?;
 
             items.push(ForeignEnumItem {
                 name: f_item_name,
                 rust_name: item_name,
                 doc_comments,
+                value,
+                kind,
+                fields,
             });
         }
 
+        let unknown_value = match unknown_value {
+            Some(policy) => parse_unknown_enum_value(&policy, &items)?,
+            None => UnknownEnumValue::Panic,
+        };
+
         Ok(ForeignEnumInfoParser(ForeignEnumInfo {
             src_id: SourceId::none(),
             name: enum_name,
             items,
             doc_comments: enum_doc_comments,
+            unknown_value,
+        }))
+    }
+}
+
+/// `"panic"`, `"error"` or `"fallback(ITEM)"`
+fn parse_unknown_enum_value(
+    policy: &syn::LitStr,
+    items: &[ForeignEnumItem],
+) -> syn::Result<UnknownEnumValue> {
+    let value = policy.value();
+    let value = value.trim();
+    let ret = if value == "panic" {
+        UnknownEnumValue::Panic
+    } else if value == "error" {
+        UnknownEnumValue::Error
+    } else if value.starts_with("fallback(") && value.ends_with(')') {
+        let item_name = value["fallback(".len()..value.len() - 1].trim();
+        let idx = items
+            .iter()
+            .position(|x| x.name == item_name)
+            .ok_or_else(|| {
+                syn::Error::new(
+                    policy.span(),
+                    format!("unknown_value: there is no item '{}'", item_name),
+                )
+            })?;
+        UnknownEnumValue::Fallback(idx)
+    } else {
+        return Err(syn::Error::new(
+            policy.span(),
+            format!(
+                "unknown_value: expect \"panic\", \"error\" or \"fallback(ITEM)\", got \"{}\"",
+                value
+            ),
+        ));
+    };
+    if ret != UnknownEnumValue::Panic && items.iter().any(|x| x.kind != EnumItemKind::Unit) {
+        return Err(syn::Error::new(
+            policy.span(),
+            "unknown_value: only \"panic\" is supported for enum with data-carrying items",
+        ));
+    }
+    Ok(ret)
+}
+
+/// `name: Type` with optional doc comments, separated by comma
+fn parse_struct_fields(field_parser: ParseStream) -> syn::Result<Vec<ForeignStructField>> {
+    let mut fields = Vec::<ForeignStructField>::new();
+    while !field_parser.is_empty() {
+        let doc_comments = parse_doc_comments(field_parser)?;
+        let field_name = field_parser.parse::<Ident>()?;
+        field_parser.parse::<Token![:]>()?;
+        let field_ty = field_parser.parse::<Type>()?;
+        if fields.iter().any(|x| x.name == field_name) {
+            return Err(syn::Error::new(
+                field_name.span(),
+                format!("field '{}' is already declared", field_name),
+            ));
+        }
+        fields.push(ForeignStructField {
+            name: field_name,
+            ty: field_ty,
+            doc_comments,
+        });
+        if field_parser.is_empty() {
+            break;
+        }
+        field_parser.parse::<Token![,]>()?;
+    }
+    Ok(fields)
+}
+
+struct ForeignStructInfoParser(ForeignStructInfo);
+
+impl Parse for ForeignStructInfoParser {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let struct_doc_comments = parse_doc_comments(input)?;
+        input.parse::<Token![struct]>()?;
+        let struct_name = input.parse::<Ident>()?;
+        debug!("STRUCT NAME {:?}", struct_name);
+        let field_parser;
+        braced!(field_parser in input);
+        let fields = parse_struct_fields(&field_parser)?;
+        if fields.is_empty() {
+            return Err(syn::Error::new(
+                struct_name.span(),
+                "foreign_struct without fields",
+            ));
+        }
+
+        Ok(ForeignStructInfoParser(ForeignStructInfo {
+            src_id: SourceId::none(),
+            name: struct_name,
+            fields,
+            doc_comments: struct_doc_comments,
+        }))
+    }
+}
+
+struct ForeignFlagsInfoParser(ForeignFlagsInfo);
+
+impl Parse for ForeignFlagsInfoParser {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let flags_doc_comments = parse_doc_comments(input)?;
+        input.parse::<Token![struct]>()?;
+        let flags_name = input.parse::<Ident>()?;
+        debug!("FLAGS NAME {:?}", flags_name);
+        let item_parser;
+        braced!(item_parser in input);
+        let mut items = Vec::<ForeignFlagsItem>::new();
+        while !item_parser.is_empty() {
+            let doc_comments = parse_doc_comments(&item_parser)?;
+            let f_item_name = item_parser.parse::<Ident>()?;
+            item_parser.parse::<Token![=]>()?;
+            let item_name = item_parser.call(syn::Path::parse_mod_style)?;
+            let value = if item_parser.peek(Token![=]) {
+                item_parser.parse::<Token![=]>()?;
+                let lit = item_parser.parse::<syn::LitInt>()?;
+                if !lit.value().is_power_of_two() || lit.value() > (1 << 30) {
+                    return Err(syn::Error::new(
+                        lit.span(),
+                        format!(
+                            "bit {} should be power of two, not greater than 1 << 30",
+                            lit.value()
+                        ),
+                    ));
+                }
+                lit.value() as u32
+            } else {
+                match items.last() {
+                    Some(prev) if prev.value == (1 << 30) => {
+                        return Err(syn::Error::new(
+                            f_item_name.span(),
+                            format!("bit of '{}' is too big", f_item_name),
+                        ));
+                    }
+                    Some(prev) => prev.value << 1,
+                    None => 1,
+                }
+            };
+            if let Some(prev) = items.iter().find(|x| x.value == value) {
+                return Err(syn::Error::new(
+                    f_item_name.span(),
+                    format!(
+                        "bit {} of '{}' is already used by '{}'",
+                        value, f_item_name, prev.name
+                    ),
+                ));
+            }
+            item_parser.parse::<Token![,]>()?;
+
+            items.push(ForeignFlagsItem {
+                name: f_item_name,
+                rust_name: item_name,
+                doc_comments,
+                value,
+            });
+        }
+        if items.is_empty() {
+            return Err(syn::Error::new(
+                flags_name.span(),
+                "foreign_flags without items",
+            ));
+        }
+
+        Ok(ForeignFlagsInfoParser(ForeignFlagsInfo {
+            src_id: SourceId::none(),
+            name: flags_name,
+            items,
+            doc_comments: flags_doc_comments,
         }))
     }
 }
@@ -570,6 +892,41 @@ mod tests {
         test_parse::<JavaClass>(mac.tts);
     }
 
+    #[test]
+    fn test_parse_java_exception_attr() {
+        let _ = env_logger::try_init();
+        let mac: syn::Macro = parse_quote! {
+            foreigner_class!(class Foo {
+                self_type Foo;
+                constructor Foo::new() -> Foo;
+                /// Connect to server
+                #[java_exception = "MyNetworkException"]
+                method Foo::connect(&self) -> Result<(), NetworkError>;
+                method Foo::f(&self) -> Result<(), String>;
+            })
+        };
+        let class = test_parse::<JavaClass>(mac.tts).0;
+        assert_eq!(
+            Some("MyNetworkException".to_string()),
+            class.methods[1].java_exception
+        );
+        assert_eq!(vec![" Connect to server"], class.methods[1].doc_comments);
+        assert!(class.methods[2].java_exception.is_none());
+
+        let mac: syn::Macro = parse_quote! {
+            foreigner_class!(class Foo {
+                self_type Foo;
+                #[java_exception = "MyNetworkException"]
+                method Foo::connect(&self) -> Result<(), NetworkError>;
+            })
+        };
+        let err = match syn::parse2::<CppClass>(mac.tts) {
+            Ok(_) => panic!("java_exception should be rejected for C++"),
+            Err(err) => err,
+        };
+        assert!(err.to_string().contains("Java/JNI backend"));
+    }
+
     #[test]
     fn test_parse_foreign_enum() {
         let _ = env_logger::try_init();
@@ -582,6 +939,117 @@ mod tests {
         };
         let enum_ = parse_foreign_enum(SourceId::none(), mac.tts).unwrap();
         assert_eq!("MyEnum", enum_.name.to_string());
+        assert_eq!(
+            vec![0, 1, 2],
+            enum_.items.iter().map(|x| x.value).collect::<Vec<_>>()
+        );
+
+        let mac: syn::Macro = parse_quote! {
+            foreign_enum!(enum Event {
+                CONNECTED(String) = Event::Connected = 5,
+                CLOSED { code: u16 } = Event::Closed,
+                DISCONNECTED = Event::Disconnected = 1,
+            })
+        };
+        let enum_ = parse_foreign_enum(SourceId::none(), mac.tts).unwrap();
+        assert!(enum_.is_data_carrying());
+        assert_eq!(
+            vec![5, 6, 1],
+            enum_.items.iter().map(|x| x.value).collect::<Vec<_>>()
+        );
+        assert_eq!(EnumItemKind::Tuple, enum_.items[0].kind);
+        assert_eq!("_0", enum_.items[0].fields[0].name.to_string());
+        assert_eq!(EnumItemKind::Struct, enum_.items[1].kind);
+        assert_eq!("code", enum_.items[1].fields[0].name.to_string());
+        assert_eq!(EnumItemKind::Unit, enum_.items[2].kind);
+
+        let mac: syn::Macro = parse_quote! {
+            foreign_enum!(enum MyEnum {
+                ITEM1 = MyEnum::Item1 = 1,
+                ITEM2 = MyEnum::Item2 = 1,
+            })
+        };
+        assert!(parse_foreign_enum(SourceId::none(), mac.tts).is_err());
+
+        let mac: syn::Macro = parse_quote! {
+            foreign_enum!(
+                #[unknown_value = "fallback(OTHER)"]
+                enum MyEnum {
+                    ITEM1 = MyEnum::Item1,
+                    OTHER = MyEnum::Other,
+                })
+        };
+        let enum_ = parse_foreign_enum(SourceId::none(), mac.tts).unwrap();
+        assert_eq!(UnknownEnumValue::Fallback(1), enum_.unknown_value);
+        let mac: syn::Macro = parse_quote! {
+            foreign_enum!(#[unknown_value = "fallback(ITEM2)"] enum MyEnum { ITEM1 = MyEnum::Item1, })
+        };
+        assert!(parse_foreign_enum(SourceId::none(), mac.tts).is_err());
+    }
+
+    #[test]
+    fn test_parse_foreign_flags() {
+        let _ = env_logger::try_init();
+        let mac: syn::Macro = parse_quote! {
+            foreign_flags!(
+                /// Access rights
+                struct Permissions {
+                    READ = Permissions::READ,
+                    WRITE = Permissions::WRITE,
+                    EXEC = Permissions::EXEC = 16,
+                    DELETE = Permissions::DELETE,
+                })
+        };
+        let flags = parse_foreign_flags(SourceId::none(), mac.tts).unwrap();
+        assert_eq!("Permissions", flags.name.to_string());
+        assert_eq!(
+            vec![1, 2, 16, 32],
+            flags.items.iter().map(|x| x.value).collect::<Vec<_>>()
+        );
+        assert_eq!(51, flags.all_bits());
+
+        let mac: syn::Macro = parse_quote! {
+            foreign_flags!(struct Permissions { READ = Permissions::READ = 3, })
+        };
+        assert!(parse_foreign_flags(SourceId::none(), mac.tts).is_err());
+        let mac: syn::Macro = parse_quote! {
+            foreign_flags!(struct Permissions {
+                READ = Permissions::READ = 2,
+                WRITE = Permissions::WRITE = 2,
+            })
+        };
+        assert!(parse_foreign_flags(SourceId::none(), mac.tts).is_err());
+    }
+
+    #[test]
+    fn test_parse_foreign_struct() {
+        let _ = env_logger::try_init();
+        let mac: syn::Macro = parse_quote! {
+            foreign_struct!(
+                /// Point on plane
+                struct Point {
+                    /// X coordinate
+                    x: f64,
+                    y: f64,
+                    label: String
+                })
+        };
+        let struct_ = parse_foreign_struct(SourceId::none(), mac.tts).unwrap();
+        assert_eq!("Point", struct_.name.to_string());
+        assert_eq!(
+            vec!["x", "y", "label"],
+            struct_
+                .fields
+                .iter()
+                .map(|x| x.name.to_string())
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(vec![" X coordinate"], struct_.fields[0].doc_comments);
+
+        let mac: syn::Macro = parse_quote! {
+            foreign_struct!(struct Point { x: f64, x: f64, })
+        };
+        assert!(parse_foreign_struct(SourceId::none(), mac.tts).is_err());
     }
 
     #[test]
@@ -608,5 +1076,4 @@ mod tests {
             syn::parse2(tokens).unwrap_or_else(|err| panic_on_syn_error("test_parse", code, err));
         class
     }
-
 }
@@ -1,16 +1,18 @@
-use std::{io::Write, mem, path::Path};
+use std::{io::Write, iter, mem};
 
 use log::debug;
 use proc_macro2::TokenStream;
-use syn::{parse_quote, Type};
+use syn::{parse_quote, spanned::Spanned, Type};
 
 use crate::{
     cpp::{
-        c_func_name, cpp_code, n_arguments_list, rust_generate_args_with_types,
-        CppForeignMethodSignature, MethodContext,
+        c_func_name, cpp_code, map_type::calc_this_type_for_method, n_arguments_list,
+        rust_check_enum_args, rust_generate_args_with_types, CppForeignMethodSignature,
+        CppFutureInfo, MethodContext,
     },
     error::{panic_on_syn_error, DiagnosticError, Result},
     file_cache::FileWriteCache,
+    source_registry::SourceId,
     typemap::{
         ast::{list_lifetimes, normalize_ty_lifetimes, DisplayToTokens},
         ty::RustType,
@@ -21,20 +23,23 @@ use crate::{
         },
         FROM_VAR_TEMPLATE, TO_VAR_TEMPLATE,
     },
-    types::{ForeignerClassInfo, MethodAccess, MethodVariant, SelfTypeVariant},
-    TypeMap,
+    types::{ForeignerClassInfo, ForeignerMethod, MethodAccess, MethodVariant, SelfTypeVariant},
+    CppConfig, CppOutput, CppPanicHandling, TypeMap,
 };
 
 pub(in crate::cpp) fn generate(
     conv_map: &mut TypeMap,
-    output_dir: &Path,
-    namespace_name: &str,
-    separate_impl_headers: bool,
+    cpp_cfg: &CppConfig,
     class: &ForeignerClassInfo,
     methods_sign: &[CppForeignMethodSignature],
 ) -> Result<Vec<TokenStream>> {
     use std::fmt::Write;
 
+    let output_dir = cpp_cfg.output_dir.as_path();
+    let namespace_name = cpp_cfg.namespace_name.as_str();
+    let panic_handling = cpp_cfg.panic_handling.as_ref();
+    let output = cpp_cfg.output;
+
     let c_path = output_dir.join(format!("c_{}.h", class.name));
     let mut c_include_f = FileWriteCache::new(&c_path);
     let cpp_path = output_dir.join(format!("{}.hpp", class.name));
@@ -57,9 +62,31 @@ pub(in crate::cpp) fn generate(
     let c_class_type = cpp_code::c_class_type(class);
     let class_doc_comments = cpp_code::doc_comments_to_c_comments(&class.doc_comments, true);
 
-    write!(
-        c_include_f,
-        r##"// Automaticaly generated by rust_swig
+    if output == CppOutput::C {
+        write!(
+            c_include_f,
+            r##"// Automaticaly generated by rust_swig
+{doc_comments}
+#pragma once
+
+#include "rust_swig_c.h"
+{includes}
+#ifdef __cplusplus
+extern "C" {{
+#endif
+
+    typedef struct {c_class_type} {c_class_type};
+
+"##,
+            doc_comments = class_doc_comments,
+            includes = c_includes_of_generated_types(conv_map, class, methods_sign),
+            c_class_type = c_class_type,
+        )
+        .map_err(map_write_err!(c_path))?;
+    } else {
+        write!(
+            c_include_f,
+            r##"// Automaticaly generated by rust_swig
 {doc_comments}
 #pragma once
 
@@ -75,11 +102,27 @@ extern "C" {{
     typedef struct {c_class_type} {c_class_type};
 
 "##,
-        doc_comments = class_doc_comments,
-        c_class_type = c_class_type,
-        sizeof_usize = mem::size_of::<usize>(),
-    )
-    .map_err(map_write_err!(c_path))?;
+            doc_comments = class_doc_comments,
+            c_class_type = c_class_type,
+            sizeof_usize = mem::size_of::<usize>(),
+        )
+        .map_err(map_write_err!(c_path))?;
+    }
+
+    c_include_f
+        .write_all(c_types_declarations(conv_map, methods_sign)?.as_bytes())
+        .map_err(map_write_err!(c_path))?;
+
+    if let Some(CppPanicHandling::CallHandler(ref handler_name)) = panic_handling {
+        write!(
+            c_include_f,
+            r#"
+    void {handler_name}(const char *msg);
+"#,
+            handler_name = handler_name,
+        )
+        .map_err(map_write_err!(c_path))?;
+    }
 
     let class_name = format!("{}Wrapper", class.name);
 
@@ -94,9 +137,9 @@ extern "C" {{
 #include <utility>
 //for std::conditional
 #include <type_traits>
-
+{future_include}{stdexcept_include}
 #include "c_{class_dot_name}.h"
-
+{structs_include}{helpers}
 namespace {namespace} {{
 
 template<bool>
@@ -142,6 +185,18 @@ public:
         class_dot_name = class.name,
         doc_comments = class_doc_comments,
         namespace = namespace_name,
+        helpers = foreign_types_helpers(conv_map, methods_sign),
+        structs_include = cpp_includes_of_exported_structs(conv_map, methods_sign),
+        future_include = if methods_sign.iter().any(|x| x.future.is_some()) {
+            "//for std::future\n#include <future>\n"
+        } else {
+            ""
+        },
+        stdexcept_include = if methods_sign.iter().any(|x| !x.checked_enum_args.is_empty()) {
+            "//for std::invalid_argument\n#include <stdexcept>\n"
+        } else {
+            ""
+        },
     ).map_err(map_write_err!(cpp_path))?;
 
     if !class.copy_derived {
@@ -288,6 +343,14 @@ May be you need to use `private constructor = empty;` syntax?",
             cpp_code::doc_comments_to_c_comments(&method.doc_comments, false)
         )
         .map_err(map_write_err!(c_path))?;
+        if !f_method.checked_enum_args.is_empty() {
+            write!(
+                c_include_f,
+                "\n    //`*swig_invalid_arg` is set to 1 if value of enum argument is unknown \
+                 or flags argument has unknown bits, in this case return value is zeroed"
+            )
+            .map_err(map_write_err!(c_path))?;
+        }
 
         let method_access = match method.access {
             MethodAccess::Private => "private",
@@ -304,8 +367,20 @@ May be you need to use `private constructor = empty;` syntax?",
         let cpp_comments = cpp_code::doc_comments_to_c_comments(&method.doc_comments, false);
         write!(cpp_include_f, "{}", cpp_comments,).map_err(map_write_err!(cpp_path))?;
         let c_func_name = c_func_name(class, method);
-        let c_args_with_types = cpp_code::c_generate_args_with_types(f_method, false)
+        let mut c_args_with_types = cpp_code::c_generate_args_with_types(f_method, false)
             .map_err(|err| DiagnosticError::new(class.src_id, class.span(), err))?;
+        if let Some(ref future) = f_method.future {
+            if !c_args_with_types.is_empty() {
+                c_args_with_types.push_str(", ");
+            }
+            c_args_with_types.push_str(&c_future_callback_args(future));
+        }
+        //in C `f()` means unspecified arguments
+        let c_args_or_void = if c_args_with_types.is_empty() && output == CppOutput::C {
+            "void"
+        } else {
+            c_args_with_types.as_str()
+        };
         let comma_c_args_with_types = if c_args_with_types.is_empty() {
             String::new()
         } else {
@@ -317,8 +392,17 @@ May be you need to use `private constructor = empty;` syntax?",
             .map_err(|err| DiagnosticError::new(class.src_id, class.span(), err))?;
         let cpp_args_for_c = cpp_code::cpp_generate_args_to_call_c(f_method)
             .map_err(|err| DiagnosticError::new(class.src_id, class.span(), err))?;
+        //unknown value of enum argument is reported via exception,
+        //so such method can not be `noexcept`
+        let (noexcept, decl_invalid_arg) = if f_method.checked_enum_args.is_empty() {
+            (" noexcept", "")
+        } else {
+            ("", "        char swig_invalid_arg = 0;\n")
+        };
+        let check_invalid_arg = cpp_check_invalid_arg(class, method, f_method, "");
         let real_output_typename = match method.fn_decl.output {
             syn::ReturnType::Default => "()",
+            syn::ReturnType::Type(..) if f_method.future.is_some() => "_",
             syn::ReturnType::Type(_, ref t) => normalize_ty_lifetimes(&*t),
         };
 
@@ -332,6 +416,11 @@ May be you need to use `private constructor = empty;` syntax?",
             decl_func_args: &rust_args_with_types,
             args_names: &args_names,
             real_output_typename: &real_output_typename,
+            panic_handling,
+            future_executor: cpp_cfg
+                .future_executor
+                .as_deref()
+                .unwrap_or("swig_spawn_future"),
         };
 
         let method_name = method.short_name().as_str().to_string();
@@ -346,6 +435,30 @@ May be you need to use `private constructor = empty;` syntax?",
             };
         //rename types like "struct Foo" to "Foo" to make VC++ compiler happy
         let cpp_ret_type = cpp_ret_type.as_str().replace("struct", "");
+        //C function returns nothing, but C++ method returns value
+        //created together with `input_to_output` argument
+        let (void_ret_type, decl_input_to_output, return_input_to_output) =
+            match f_method.input_to_output {
+                Some(ref arg) => {
+                    let arg_name = format!("a_{}", arg.idx);
+                    (
+                        arg.typename.to_string(),
+                        format!(
+                        "        {c_type} {arg_name};\n        {ret_type} ret;\n        {code}\n",
+                        c_type = f_method.input[arg.idx].as_ref().name,
+                        arg_name = arg_name,
+                        ret_type = arg.typename,
+                        code = arg
+                            .code
+                            .trim()
+                            .replace(FROM_VAR_TEMPLATE, &arg_name)
+                            .replace(TO_VAR_TEMPLATE, "ret"),
+                    ),
+                        "        return ret;\n",
+                    )
+                }
+                None => ("void".to_string(), String::new(), ""),
+            };
 
         match method.variant {
             MethodVariant::StaticMethod => {
@@ -356,16 +469,28 @@ May be you need to use `private constructor = empty;` syntax?",
 "#,
                     ret_type = f_method.output.as_ref().name,
                     c_func_name = c_func_name,
-                    args_with_types = c_args_with_types,
+                    args_with_types = c_args_or_void,
                 )
                 .map_err(map_write_err!(c_path))?;
 
-                if f_method.output.as_ref().name != "void" {
+                if let Some(ref future) = f_method.future {
+                    let (cpp_ret_type, body) = cpp_future_method_body(
+                        future,
+                        &c_func_name,
+                        if args_names.is_empty() {
+                            String::new()
+                        } else {
+                            format!("{}, ", cpp_args_for_c)
+                        },
+                        decl_invalid_arg,
+                        &cpp_check_invalid_arg(class, method, f_method, "delete swig_promise;"),
+                    );
                     write!(
                         cpp_include_f,
                         r#"
-    static {cpp_ret_type} {method_name}({cpp_args_with_types}) noexcept;
+    static {cpp_ret_type} {method_name}({cpp_args_with_types}){noexcept};
 "#,
+                        noexcept = noexcept,
                         method_name = method_name,
                         cpp_ret_type = cpp_ret_type,
                         cpp_args_with_types = cpp_args_with_types,
@@ -375,12 +500,44 @@ May be you need to use `private constructor = empty;` syntax?",
                         &mut inline_impl,
                         r#"
     template<bool OWN_DATA>
-    inline {cpp_ret_type} {class_name}<OWN_DATA>::{method_name}({cpp_args_with_types}) noexcept
+    inline {cpp_ret_type} {class_name}<OWN_DATA>::{method_name}({cpp_args_with_types}){noexcept}
     {{
-        {c_ret_type} ret = {c_func_name}({cpp_args_for_c});
-        return {convert_ret_for_cpp};
+{body}
     }}
 "#,
+                        noexcept = noexcept,
+                        cpp_ret_type = cpp_ret_type,
+                        class_name = class_name,
+                        method_name = method_name,
+                        cpp_args_with_types = cpp_args_with_types,
+                        body = body,
+                    )
+                    .unwrap();
+                } else if f_method.output.as_ref().name != "void" {
+                    write!(
+                        cpp_include_f,
+                        r#"
+    static {cpp_ret_type} {method_name}({cpp_args_with_types}){noexcept};
+"#,
+                        noexcept = noexcept,
+                        method_name = method_name,
+                        cpp_ret_type = cpp_ret_type,
+                        cpp_args_with_types = cpp_args_with_types,
+                    )
+                    .map_err(map_write_err!(cpp_path))?;
+                    write!(
+                        &mut inline_impl,
+                        r#"
+    template<bool OWN_DATA>
+    inline {cpp_ret_type} {class_name}<OWN_DATA>::{method_name}({cpp_args_with_types}){noexcept}
+    {{
+{decl_invalid_arg}        {c_ret_type} ret = {c_func_name}({cpp_args_for_c});
+{check_invalid_arg}        return {convert_ret_for_cpp};
+    }}
+"#,
+                        noexcept = noexcept,
+                        decl_invalid_arg = decl_invalid_arg,
+                        check_invalid_arg = check_invalid_arg,
                         c_ret_type = f_method.output.as_ref().name,
                         convert_ret_for_cpp = convert_ret_for_cpp,
                         cpp_args_for_c = cpp_args_for_c,
@@ -395,8 +552,10 @@ May be you need to use `private constructor = empty;` syntax?",
                     write!(
                         cpp_include_f,
                         r#"
-    static void {method_name}({cpp_args_with_types}) noexcept;
+    static {void_ret_type} {method_name}({cpp_args_with_types}){noexcept};
 "#,
+                        noexcept = noexcept,
+                        void_ret_type = void_ret_type,
                         method_name = method_name,
                         cpp_args_with_types = cpp_args_with_types,
                     )
@@ -405,11 +564,17 @@ May be you need to use `private constructor = empty;` syntax?",
                         &mut inline_impl,
                         r#"
     template<bool OWN_DATA>
-    inline void {class_name}<OWN_DATA>::{method_name}({cpp_args_with_types}) noexcept
+    inline {void_ret_type} {class_name}<OWN_DATA>::{method_name}({cpp_args_with_types}){noexcept}
     {{
-        {c_func_name}({cpp_args_for_c});
-    }}
+{decl_invalid_arg}{decl_input_to_output}        {c_func_name}({cpp_args_for_c});
+{check_invalid_arg}{return_input_to_output}    }}
 "#,
+                        noexcept = noexcept,
+                        void_ret_type = void_ret_type,
+                        decl_invalid_arg = decl_invalid_arg,
+                        decl_input_to_output = decl_input_to_output,
+                        check_invalid_arg = check_invalid_arg,
+                        return_input_to_output = return_input_to_output,
                         cpp_args_with_types = cpp_args_with_types,
                         class_name = class_name,
                         method_name = method_name,
@@ -438,27 +603,71 @@ May be you need to use `private constructor = empty;` syntax?",
                     const_if_readonly = const_if_readonly,
                 )
                 .map_err(map_write_err!(c_path))?;
+                let cpp_qualifiers = format!(" {}{}", const_if_readonly, noexcept);
+                let cpp_qualifiers = cpp_qualifiers.trim_end();
 
-                if f_method.output.as_ref().name != "void" {
+                if let Some(ref future) = f_method.future {
+                    let (cpp_ret_type, body) = cpp_future_method_body(
+                        future,
+                        &c_func_name,
+                        if args_names.is_empty() {
+                            "this->self_, ".to_string()
+                        } else {
+                            format!("this->self_, {}, ", cpp_args_for_c)
+                        },
+                        decl_invalid_arg,
+                        &cpp_check_invalid_arg(class, method, f_method, "delete swig_promise;"),
+                    );
                     write!(
                         cpp_include_f,
                         r#"
-    {cpp_ret_type} {method_name}({cpp_args_with_types}) {const_if_readonly} noexcept;
+    {cpp_ret_type} {method_name}({cpp_args_with_types}){cpp_qualifiers};
 "#,
                         method_name = method_name,
                         cpp_ret_type = cpp_ret_type,
                         cpp_args_with_types = cpp_args_with_types,
-                        const_if_readonly = const_if_readonly,
+                        cpp_qualifiers = cpp_qualifiers,
+                    )
+                    .map_err(map_write_err!(cpp_path))?;
+                    write!(
+                        &mut inline_impl,
+                        r#"
+    template<bool OWN_DATA>
+    inline {cpp_ret_type} {class_name}<OWN_DATA>::{method_name}({cpp_args_with_types}){cpp_qualifiers}
+    {{
+{body}
+    }}
+"#,
+                        cpp_ret_type = cpp_ret_type,
+                        class_name = class_name,
+                        method_name = method_name,
+                        cpp_args_with_types = cpp_args_with_types,
+                        cpp_qualifiers = cpp_qualifiers,
+                        body = body,
+                    )
+                    .unwrap();
+                } else if f_method.output.as_ref().name != "void" {
+                    write!(
+                        cpp_include_f,
+                        r#"
+    {cpp_ret_type} {method_name}({cpp_args_with_types}){cpp_qualifiers};
+"#,
+                        method_name = method_name,
+                        cpp_ret_type = cpp_ret_type,
+                        cpp_args_with_types = cpp_args_with_types,
+                        cpp_qualifiers = cpp_qualifiers,
                     )
                     .map_err(map_write_err!(cpp_path))?;
                     write!(&mut inline_impl, r#"
     template<bool OWN_DATA>
-    inline {cpp_ret_type} {class_name}<OWN_DATA>::{method_name}({cpp_args_with_types}) {const_if_readonly} noexcept
+    inline {cpp_ret_type} {class_name}<OWN_DATA>::{method_name}({cpp_args_with_types}){cpp_qualifiers}
     {{
-        {c_ret_type} ret = {c_func_name}(this->self_{cpp_args_for_c});
-        return {convert_ret_for_cpp};
+{decl_invalid_arg}        {c_ret_type} ret = {c_func_name}(this->self_{cpp_args_for_c});
+{check_invalid_arg}        return {convert_ret_for_cpp};
     }}
 "#,
+                           decl_invalid_arg = decl_invalid_arg,
+                           check_invalid_arg = check_invalid_arg,
                            method_name = method_name,
                            convert_ret_for_cpp = convert_ret_for_cpp,
                            c_ret_type = f_method.output.as_ref().name,
@@ -471,37 +680,46 @@ May be you need to use `private constructor = empty;` syntax?",
                         } else {
                             format!(", {}", cpp_args_for_c)
                                                    },
-                           const_if_readonly = const_if_readonly,
+                           cpp_qualifiers = cpp_qualifiers,
                     ).unwrap();
                 } else {
                     write!(
                         cpp_include_f,
                         r#"
-    void {method_name}({cpp_args_with_types}) {const_if_readonly} noexcept;
+    {void_ret_type} {method_name}({cpp_args_with_types}){cpp_qualifiers};
 "#,
+                        void_ret_type = void_ret_type,
                         method_name = method_name,
                         cpp_args_with_types = cpp_args_with_types,
-                        const_if_readonly = const_if_readonly,
+                        cpp_qualifiers = cpp_qualifiers,
                     )
                     .map_err(map_write_err!(cpp_path))?;
-                    write!(&mut inline_impl, r#"
+                    write!(
+                        &mut inline_impl,
+                        r#"
     template<bool OWN_DATA>
-    inline void {class_name}<OWN_DATA>::{method_name}({cpp_args_with_types}) {const_if_readonly} noexcept
+    inline {void_ret_type} {class_name}<OWN_DATA>::{method_name}({cpp_args_with_types}){cpp_qualifiers}
     {{
-        {c_func_name}(this->self_{cpp_args_for_c});
-    }}
+{decl_invalid_arg}{decl_input_to_output}        {c_func_name}(this->self_{cpp_args_for_c});
+{check_invalid_arg}{return_input_to_output}    }}
 "#,
-                           method_name = method_name,
-                           c_func_name = c_func_name,
-                           class_name = class_name,
-                           cpp_args_with_types = cpp_args_with_types,
-                           cpp_args_for_c = if args_names.is_empty() {
-                               String::new()
+                        void_ret_type = void_ret_type,
+                        decl_invalid_arg = decl_invalid_arg,
+                        decl_input_to_output = decl_input_to_output,
+                        check_invalid_arg = check_invalid_arg,
+                        return_input_to_output = return_input_to_output,
+                        method_name = method_name,
+                        c_func_name = c_func_name,
+                        class_name = class_name,
+                        cpp_args_with_types = cpp_args_with_types,
+                        cpp_args_for_c = if args_names.is_empty() {
+                            String::new()
                         } else {
                             format!(", {}", cpp_args_for_c)
-                           },
-                           const_if_readonly = const_if_readonly,
-                    ).unwrap();
+                        },
+                        cpp_qualifiers = cpp_qualifiers,
+                    )
+                    .unwrap();
                 }
 
                 gen_code.append(&mut generate_method(
@@ -531,21 +749,24 @@ May be you need to use `private constructor = empty;` syntax?",
 "#,
                         c_class_type = c_class_type,
                         func_name = c_func_name,
-                        args_with_types = c_args_with_types,
+                        args_with_types = c_args_or_void,
                     )
                     .map_err(map_write_err!(c_path))?;
 
                     write!(
                         cpp_include_f,
                         r#"
-    {class_name}({cpp_args_with_types}) noexcept
+    {class_name}({cpp_args_with_types}){noexcept}
     {{
-        this->self_ = {c_func_name}({cpp_args_for_c});
-        if (this->self_ == nullptr) {{
+{decl_invalid_arg}        this->self_ = {c_func_name}({cpp_args_for_c});
+{check_invalid_arg}        if (this->self_ == nullptr) {{
             std::abort();
         }}
     }}
 "#,
+                        noexcept = noexcept,
+                        decl_invalid_arg = decl_invalid_arg,
+                        check_invalid_arg = check_invalid_arg,
                         c_func_name = c_func_name,
                         cpp_args_with_types = cpp_args_with_types,
                         class_name = class_name,
@@ -582,17 +803,23 @@ May be you need to use `private constructor = empty;` syntax?",
 
         let unpack_code = TypeMap::unpack_from_heap_pointer(&this_type, "this", false);
         let c_destructor_name = format!("{}_delete", class.name);
+        let body = format!(
+            r#"
+{unpack_code}
+    drop(this);
+"#,
+            unpack_code = unpack_code,
+        );
         let code = format!(
             r#"
 #[allow(unused_variables, unused_mut, non_snake_case)]
 #[no_mangle]
 pub extern "C" fn {c_destructor_name}(this: *mut {this_type}) {{
-{unpack_code}
-    drop(this);
+{body}
 }}
 "#,
             c_destructor_name = c_destructor_name,
-            unpack_code = unpack_code,
+            body = catch_unwind_if_need(panic_handling, "()", body),
             this_type = this_type_for_method.normalized_name,
         );
         debug!("we generate and parse code: {}", code);
@@ -670,7 +897,7 @@ private:
     .map_err(map_write_err!(cpp_path))?;
 
     // Write method implementations.
-    if separate_impl_headers {
+    if cpp_cfg.separate_impl_headers {
         write!(
             cpp_include_f,
             r#"
@@ -697,9 +924,11 @@ namespace {namespace} {{
         .map_err(map_write_err!(cpp_impl_path))?;
         write_methods_impls(&mut cpp_impl_f, namespace_name, &inline_impl)
             .map_err(map_write_err!(cpp_impl_path))?;
-        cpp_impl_f
-            .update_file_if_necessary()
-            .map_err(map_write_err!(cpp_impl_path))?;
+        if output == CppOutput::CAndCpp {
+            cpp_impl_f
+                .update_file_if_necessary()
+                .map_err(map_write_err!(cpp_impl_path))?;
+        }
     } else {
         write_methods_impls(&mut cpp_include_f, namespace_name, &inline_impl)
             .map_err(map_write_err!(cpp_path))?;
@@ -723,18 +952,412 @@ using {class_name}Ref = {base_class_name}<false>;
     )
     .map_err(map_write_err!(cpp_fwd_path))?;
 
-    cpp_fwd_f
-        .update_file_if_necessary()
-        .map_err(map_write_err!(cpp_fwd_path))?;
-    c_include_f
-        .update_file_if_necessary()
-        .map_err(map_write_err!(c_path))?;
-    cpp_include_f
-        .update_file_if_necessary()
-        .map_err(map_write_err!(cpp_path))?;
+    if output != CppOutput::Nothing {
+        c_include_f
+            .update_file_if_necessary()
+            .map_err(map_write_err!(c_path))?;
+    }
+    if output == CppOutput::CAndCpp {
+        cpp_fwd_f
+            .update_file_if_necessary()
+            .map_err(map_write_err!(cpp_fwd_path))?;
+        cpp_include_f
+            .update_file_if_necessary()
+            .map_err(map_write_err!(cpp_path))?;
+    }
     Ok(gen_code)
 }
 
+/// Declarations of types from `define_c_type!` used by methods
+fn c_types_declarations(
+    conv_map: &mut TypeMap,
+    methods_sign: &[CppForeignMethodSignature],
+) -> Result<String> {
+    let mut c_types = Vec::<syn::ItemStruct>::new();
+    for f_method in methods_sign {
+        for fti in f_method.types() {
+            if let Some(c_type) = conv_map.find_c_type(&fti.base.correspoding_rust_type) {
+                if !c_types.iter().any(|x| x.ident == c_type.ident) {
+                    c_types.push(c_type.clone());
+                }
+            }
+        }
+    }
+    let mut ret = String::new();
+    for c_type in c_types {
+        let mut fields = String::new();
+        for field in &c_type.fields {
+            let field_name = field.ident.as_ref().ok_or_else(|| {
+                DiagnosticError::new(
+                    SourceId::none(),
+                    field.span(),
+                    "only fields with names supported in define_c_type!",
+                )
+            })?;
+            fields.push_str(&format!(
+                "        {};\n",
+                c_field_declaration(conv_map, &field.ty, &field_name.to_string())?
+            ));
+        }
+        ret.push_str(&format!(
+            r#"
+#ifndef RUST_SWIG_C_TYPE_{name}
+#define RUST_SWIG_C_TYPE_{name}
+    struct {name} {{
+{fields}    }};
+#endif
+"#,
+            name = c_type.ident,
+            fields = fields,
+        ));
+    }
+    Ok(ret)
+}
+
+/// Declaration of field of `define_c_type!` struct in C,
+/// pointers to functions and `c_void` have no foreign types, so they are handled here
+fn c_field_declaration(conv_map: &mut TypeMap, ty: &Type, name: &str) -> Result<String> {
+    match ty {
+        Type::BareFn(ref fn_ty) => {
+            let mut args = Vec::with_capacity(fn_ty.inputs.len());
+            for arg in &fn_ty.inputs {
+                args.push(c_field_declaration(conv_map, &arg.ty, "")?);
+            }
+            let ret = match fn_ty.output {
+                syn::ReturnType::Default => "void".to_string(),
+                syn::ReturnType::Type(_, ref ret_ty) => c_field_declaration(conv_map, ret_ty, "")?,
+            };
+            Ok(format!("{} (*{})({})", ret, name, args.join(", ")))
+        }
+        Type::Ptr(ref ptr_ty) if is_c_void(&ptr_ty.elem) => {
+            let c_type = if ptr_ty.mutability.is_some() {
+                "void *"
+            } else {
+                "const void *"
+            };
+            Ok(format!("{}{}", c_type, name))
+        }
+        _ => {
+            let field_rty = conv_map.find_or_alloc_rust_type_no_src_id(ty);
+            let field_ftype = conv_map
+                .map_through_conversation_to_foreign(
+                    &field_rty,
+                    petgraph::Direction::Outgoing,
+                    field_rty.src_id_span(),
+                    calc_this_type_for_method,
+                )
+                .ok_or_else(|| {
+                    let err = DiagnosticError::new(
+                        SourceId::none(),
+                        ty.span(),
+                        format!(
+                            "Do not know conversation from such rust type '{}' to C type",
+                            field_rty
+                        ),
+                    );
+                    conv_map.explain_missing_foreign_type(
+                        err,
+                        &field_rty,
+                        petgraph::Direction::Outgoing,
+                    )
+                })?;
+            if name.is_empty() {
+                Ok(conv_map[field_ftype].name.typename.to_string())
+            } else {
+                Ok(format!("{} {}", conv_map[field_ftype].name, name))
+            }
+        }
+    }
+}
+
+fn is_c_void(ty: &Type) -> bool {
+    match ty {
+        Type::Path(ref path) => path
+            .path
+            .segments
+            .last()
+            .map(|x| x.into_value().ident == "c_void")
+            .unwrap_or(false),
+        _ => false,
+    }
+}
+
+/// `#include` of C headers generated for other classes, enums and interfaces
+/// used by methods
+fn c_includes_of_generated_types(
+    conv_map: &TypeMap,
+    class: &ForeignerClassInfo,
+    methods_sign: &[CppForeignMethodSignature],
+) -> String {
+    let class_name = class.name.to_string();
+    let mut headers = Vec::<String>::new();
+    for f_method in methods_sign {
+        for fti in f_method.types() {
+            let mut prev_word = "";
+            for word in fti
+                .base
+                .name
+                .split(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+            {
+                let name = if word.ends_with("Opaque") {
+                    let name = &word[0..word.len() - "Opaque".len()];
+                    if name != class_name && conv_map.is_generated_foreign_type(name) {
+                        Some(name)
+                    } else {
+                        None
+                    }
+                } else if prev_word == "struct"
+                    && word.starts_with("C_")
+                    && conv_map
+                        .find_c_type(&fti.base.correspoding_rust_type)
+                        .is_none()
+                {
+                    //C struct for foreign_interface!
+                    Some(&word[2..])
+                } else {
+                    None
+                };
+                if let Some(name) = name {
+                    let header = format!("c_{}.h", name);
+                    if !headers.contains(&header) {
+                        headers.push(header);
+                    }
+                }
+                if !word.is_empty() {
+                    prev_word = word;
+                }
+            }
+        }
+    }
+    let mut ret = String::new();
+    for header in headers {
+        ret.push_str(&format!("#include \"{}\"\n", header));
+    }
+    ret
+}
+
+/// `#include` of C++ headers generated for `foreign_struct`, data-carrying
+/// `foreign_enum` and `foreign_flags` used by methods, they are passed by value,
+/// so declaration is not enough
+fn cpp_includes_of_exported_structs(
+    conv_map: &TypeMap,
+    methods_sign: &[CppForeignMethodSignature],
+) -> String {
+    let mut headers = Vec::<String>::new();
+    for f_method in methods_sign {
+        for fti in f_method.types() {
+            let mut prev_word = "";
+            for word in fti
+                .base
+                .name
+                .split(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+            {
+                if prev_word == "struct"
+                    && word.starts_with("C_")
+                    && conv_map.is_exported_by_value_name(&word[2..])
+                {
+                    let header = format!("{}.hpp", &word[2..]);
+                    if !headers.contains(&header) {
+                        headers.push(header);
+                    }
+                }
+                if !word.is_empty() {
+                    prev_word = word;
+                }
+            }
+            if let Some(ref cpp_converter) = fti.cpp_converter {
+                if conv_map.is_exported_flags_name(&cpp_converter.typename) {
+                    let header = format!("{}.hpp", cpp_converter.typename);
+                    if !headers.contains(&header) {
+                        headers.push(header);
+                    }
+                }
+            }
+        }
+    }
+    let mut ret = String::new();
+    for header in headers {
+        ret.push_str(&format!("#include \"{}\"\n", header));
+    }
+    ret
+}
+
+/// Code from `define_helper_f_helper!` for types used by methods
+fn foreign_types_helpers(conv_map: &TypeMap, methods_sign: &[CppForeignMethodSignature]) -> String {
+    let mut helpers = Vec::<(String, &str)>::new();
+    for f_method in methods_sign {
+        for fti in f_method.types() {
+            let names =
+                iter::once(&fti.base.name).chain(fti.cpp_converter.as_ref().map(|x| &x.typename));
+            for name in names {
+                for (i, helper) in conv_map.foreign_type_helpers(name).iter().enumerate() {
+                    let guard: String = name
+                        .chars()
+                        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+                        .collect();
+                    let guard = format!("RUST_SWIG_F_HELPER_{}_{}", guard, i);
+                    if !helpers.iter().any(|x| x.0 == guard) {
+                        helpers.push((guard, helper));
+                    }
+                }
+            }
+        }
+    }
+    let mut ret = String::new();
+    for (guard, helper) in helpers {
+        ret.push_str(&format!(
+            "\n#ifndef {guard}\n#define {guard}\n{helper}\n#endif\n",
+            guard = guard,
+            helper = helper
+        ));
+    }
+    ret
+}
+
+/// Arguments of C function that gets callback instead of returning `Future`
+fn c_future_callback_args(future: &CppFutureInfo) -> String {
+    if future.value.base.name == "void" {
+        "void *opaque, void (*on_ready)(void *opaque)".into()
+    } else {
+        format!(
+            "void *opaque, void (*on_ready)(void *opaque, {})",
+            future.value.base.name
+        )
+    }
+}
+
+/// Return type and body of C++ method that wraps callback based
+/// C function into `std::future`, `c_args` - arguments of C function
+/// before callback with trailing comma
+fn cpp_future_method_body(
+    future: &CppFutureInfo,
+    c_func_name: &str,
+    c_args: String,
+    decl_invalid_arg: &str,
+    check_invalid_arg: &str,
+) -> (String, String) {
+    let (value_type, convert_value) = match future.value.cpp_converter {
+        Some(ref cpp_converter) => (
+            cpp_converter.typename.to_string(),
+            cpp_converter.converter.replace(FROM_VAR_TEMPLATE, "ret"),
+        ),
+        None => (future.value.base.name.to_string(), "ret".to_string()),
+    };
+    //rename types like "struct Foo" to "Foo" to make VC++ compiler happy
+    let value_type = value_type.replace("struct", "").trim().to_string();
+    let (ready_args, set_value) = if future.value.base.name == "void" {
+        (String::new(), "swig_promise->set_value();".to_string())
+    } else {
+        (
+            format!(", {} ret", future.value.base.name),
+            format!("swig_promise->set_value({});", convert_value),
+        )
+    };
+    let body = format!(
+        r#"{decl_invalid_arg}        auto swig_promise = new std::promise<{value_type}>();
+        auto swig_future = swig_promise->get_future();
+        {c_func_name}({c_args}swig_promise, [](void *opaque{ready_args}) {{
+            auto swig_promise = static_cast<std::promise<{value_type}> *>(opaque);
+            {set_value}
+            delete swig_promise;
+        }});
+{check_invalid_arg}        return swig_future;"#,
+        decl_invalid_arg = decl_invalid_arg,
+        check_invalid_arg = check_invalid_arg,
+        value_type = value_type,
+        c_func_name = c_func_name,
+        c_args = c_args,
+        ready_args = ready_args,
+        set_value = set_value,
+    );
+    (format!("std::future<{}>", value_type), body)
+}
+
+/// Throw exception from C++ method if Rust side rejected value of enum argument,
+/// `cleanup` - code to free resources before throw
+fn cpp_check_invalid_arg(
+    class: &ForeignerClassInfo,
+    method: &ForeignerMethod,
+    f_method: &CppForeignMethodSignature,
+    cleanup: &str,
+) -> String {
+    if f_method.checked_enum_args.is_empty() {
+        return String::new();
+    }
+    let cleanup = if cleanup.is_empty() {
+        String::new()
+    } else {
+        format!("            {}\n", cleanup)
+    };
+    format!(
+        r#"        if (swig_invalid_arg != 0) {{
+{cleanup}            throw std::invalid_argument("unknown value of {enums} passed to {class_name}::{method_name}");
+        }}
+"#,
+        cleanup = cleanup,
+        enums = f_method.checked_enum_names(),
+        class_name = class.name,
+        method_name = method.short_name(),
+    )
+}
+
+/// Convert return value of method, in case of `Future`
+/// pass it to executor and call callback after `Future` is ready
+fn convert_method_output(
+    conv_map: &mut TypeMap,
+    mc: &MethodContext,
+    c_ret_type: &str,
+) -> Result<(Vec<TokenStream>, String)> {
+    let future = match mc.f_method.future {
+        Some(ref future) => future,
+        None => {
+            return foreign_from_rust_convert_method_output(
+                conv_map,
+                mc.class.src_id,
+                &mc.method.fn_decl.output,
+                mc.f_method.output.as_ref(),
+                "ret",
+                c_ret_type,
+            );
+        }
+    };
+    let ret_span = match mc.method.fn_decl.output {
+        syn::ReturnType::Type(_, ref ty) => ty.span(),
+        syn::ReturnType::Default => unreachable!(),
+    };
+    let (deps, convert_value_code) = conv_map.convert_rust_types(
+        &future.output_ty,
+        &future.value.base.correspoding_rust_type,
+        "ret",
+        "()",
+        (mc.class.src_id, ret_span),
+    )?;
+    let complete = if future.value.base.name == "void" {
+        "swig_callback.complete();"
+    } else {
+        "swig_callback.complete_with(ret);"
+    };
+    let code = format!(
+        r#"
+    let swig_callback = SwigFutureCallback::new(swig_opaque, swig_on_ready);
+    let mut ret: () = {executor}(swig_future_then(ret, move |ret: {output_type}| {{
+{convert_value_code}
+        {complete}
+    }}));"#,
+        executor = mc.future_executor,
+        output_type = mc
+            .f_method
+            .future
+            .as_ref()
+            .map(|x| &x.output_ty)
+            .unwrap()
+            .normalized_name,
+        convert_value_code = convert_value_code,
+        complete = complete,
+    );
+    Ok((deps, code))
+}
+
 fn generate_static_method(conv_map: &mut TypeMap, mc: &MethodContext) -> Result<Vec<TokenStream>> {
     let c_ret_type = unpack_unique_typename(
         &mc.f_method
@@ -743,14 +1366,8 @@ fn generate_static_method(conv_map: &mut TypeMap, mc: &MethodContext) -> Result<
             .correspoding_rust_type
             .normalized_name,
     );
-    let (mut deps_code_out, convert_output_code) = foreign_from_rust_convert_method_output(
-        conv_map,
-        mc.class.src_id,
-        &mc.method.fn_decl.output,
-        mc.f_method.output.as_ref(),
-        "ret",
-        &c_ret_type,
-    )?;
+    let (mut deps_code_out, convert_output_code) =
+        convert_method_output(conv_map, mc, &c_ret_type)?;
     let n_args = mc.f_method.input.len();
     let (deps_code_in, convert_input_code) = foreign_to_rust_convert_method_inputs(
         conv_map,
@@ -760,26 +1377,33 @@ fn generate_static_method(conv_map: &mut TypeMap, mc: &MethodContext) -> Result<
         (0..n_args).map(|v| format!("a_{}", v)),
         &c_ret_type,
     )?;
-    let code = format!(
+    let body = format!(
         r#"
-#[allow(non_snake_case, unused_variables, unused_mut)]
-#[no_mangle]
-pub extern "C" fn {func_name}({decl_func_args}) -> {c_ret_type} {{
-{convert_input_code}
+{check_enum_args}{convert_input_code}
     let mut ret: {real_output_typename} = {rust_func_name}({args_names});
 {convert_output_code}
     ret
-}}
 "#,
-        func_name = mc.c_func_name,
-        decl_func_args = mc.decl_func_args,
-        c_ret_type = c_ret_type,
+        check_enum_args = rust_check_enum_args(mc.f_method),
         convert_input_code = convert_input_code,
         rust_func_name = DisplayToTokens(&mc.method.rust_id),
         args_names = mc.args_names,
         convert_output_code = convert_output_code,
         real_output_typename = mc.real_output_typename,
     );
+    let code = format!(
+        r#"
+#[allow(non_snake_case, unused_variables, unused_mut)]
+#[no_mangle]
+pub extern "C" fn {func_name}({decl_func_args}) -> {c_ret_type} {{
+{body}
+}}
+"#,
+        func_name = mc.c_func_name,
+        decl_func_args = mc.decl_func_args,
+        c_ret_type = c_ret_type,
+        body = catch_unwind_if_need(mc.panic_handling, c_ret_type, body),
+    );
     let mut gen_code = deps_code_in;
     gen_code.append(&mut deps_code_out);
     gen_code.push(
@@ -812,14 +1436,8 @@ fn generate_method(
         (0..n_args).map(|v| format!("a_{}", v)),
         &c_ret_type,
     )?;
-    let (mut deps_code_out, convert_output_code) = foreign_from_rust_convert_method_output(
-        conv_map,
-        mc.class.src_id,
-        &mc.method.fn_decl.output,
-        mc.f_method.output.as_ref(),
-        "ret",
-        &c_ret_type,
-    )?;
+    let (mut deps_code_out, convert_output_code) =
+        convert_method_output(conv_map, mc, &c_ret_type)?;
     //&mut constructor_real_type -> &mut class.self_type
     let (from_ty, to_ty): (Type, Type) = create_suitable_types_for_constructor_and_self(
         self_variant,
@@ -837,12 +1455,9 @@ fn generate_method(
         &c_ret_type,
         (mc.class.src_id, mc.method.span()),
     )?;
-    let code = format!(
+    let body = format!(
         r#"
-#[allow(non_snake_case, unused_variables, unused_mut)]
-#[no_mangle]
-pub extern "C" fn {func_name}(this: *mut {this_type}, {decl_func_args}) -> {c_ret_type} {{
-{convert_input_code}
+{check_enum_args}{convert_input_code}
     let this: {this_type_ref} = unsafe {{
         this.as_mut().unwrap()
     }};
@@ -850,20 +1465,30 @@ pub extern "C" fn {func_name}(this: *mut {this_type}, {decl_func_args}) -> {c_re
     let mut ret: {real_output_typename} = {rust_func_name}(this, {args_names});
 {convert_output_code}
     ret
-}}
 "#,
-        func_name = mc.c_func_name,
-        decl_func_args = mc.decl_func_args,
+        check_enum_args = rust_check_enum_args(mc.f_method),
         convert_input_code = convert_input_code,
-        c_ret_type = c_ret_type,
         this_type_ref = from_ty.normalized_name,
-        this_type = this_type_for_method.normalized_name,
         convert_this = convert_this,
         rust_func_name = DisplayToTokens(&mc.method.rust_id),
         args_names = mc.args_names,
         convert_output_code = convert_output_code,
         real_output_typename = mc.real_output_typename,
     );
+    let code = format!(
+        r#"
+#[allow(non_snake_case, unused_variables, unused_mut)]
+#[no_mangle]
+pub extern "C" fn {func_name}(this: *mut {this_type}, {decl_func_args}) -> {c_ret_type} {{
+{body}
+}}
+"#,
+        func_name = mc.c_func_name,
+        decl_func_args = mc.decl_func_args,
+        c_ret_type = c_ret_type,
+        this_type = this_type_for_method.normalized_name,
+        body = catch_unwind_if_need(mc.panic_handling, c_ret_type, body),
+    );
 
     let mut gen_code = deps_code_in;
     gen_code.append(&mut deps_code_out);
@@ -902,27 +1527,34 @@ fn generate_constructor(
         (mc.class.src_id, mc.method.span()),
     )?;
 
-    let code = format!(
+    let body = format!(
         r#"
-#[no_mangle]
-#[allow(unused_variables, unused_mut, non_snake_case)]
-pub extern "C" fn {func_name}({decl_func_args}) -> *const ::std::os::raw::c_void {{
-{convert_input_code}
+{check_enum_args}{convert_input_code}
     let this: {real_output_typename} = {rust_func_name}({args_names});
 {convert_this}
 {box_this}
     this as *const ::std::os::raw::c_void
-}}
 "#,
-        func_name = mc.c_func_name,
         convert_this = convert_this,
-        decl_func_args = mc.decl_func_args,
+        check_enum_args = rust_check_enum_args(mc.f_method),
         convert_input_code = convert_input_code,
         rust_func_name = DisplayToTokens(&mc.method.rust_id),
         args_names = mc.args_names,
         box_this = code_box_this,
         real_output_typename = &construct_ret_type.normalized_name.as_str(),
     );
+    let code = format!(
+        r#"
+#[no_mangle]
+#[allow(unused_variables, unused_mut, non_snake_case)]
+pub extern "C" fn {func_name}({decl_func_args}) -> *const ::std::os::raw::c_void {{
+{body}
+}}
+"#,
+        func_name = mc.c_func_name,
+        decl_func_args = mc.decl_func_args,
+        body = catch_unwind_if_need(mc.panic_handling, "*const ::std::os::raw::c_void", body),
+    );
     let mut gen_code = deps_code_in;
     gen_code.append(&mut deps_this);
     gen_code
@@ -932,6 +1564,43 @@ pub extern "C" fn {func_name}({decl_func_args}) -> *const ::std::os::raw::c_void
     Ok(gen_code)
 }
 
+/// Wrap body of `extern "C"` function into `catch_unwind` if panics should be caught
+fn catch_unwind_if_need(
+    panic_handling: Option<&CppPanicHandling>,
+    c_ret_type: &str,
+    body: String,
+) -> String {
+    let on_panic = match panic_handling {
+        None => return body,
+        Some(CppPanicHandling::Abort) => "::std::process::abort()".to_string(),
+        Some(CppPanicHandling::CallHandler(ref handler_name)) => format!(
+            r#"{{
+            extern "C" {{
+                fn {handler_name}(msg: *const ::std::os::raw::c_char);
+            }}
+            let msg = ::std::ffi::CString::new(swig_panic_message(err)).unwrap_or_default();
+            unsafe {{ {handler_name}(msg.as_ptr()) }};
+            ::std::process::abort()
+        }}"#,
+            handler_name = handler_name,
+        ),
+    };
+    format!(
+        r#"
+    let ret = ::std::panic::catch_unwind(::std::panic::AssertUnwindSafe(move || -> {c_ret_type} {{
+{body}
+    }}));
+    match ret {{
+        Ok(ret) => ret,
+        Err(err) => {on_panic}
+    }}
+"#,
+        c_ret_type = c_ret_type,
+        body = body,
+        on_panic = on_panic,
+    )
+}
+
 fn write_methods_impls(
     file: &mut FileWriteCache,
     namespace_name: &str,
@@ -0,0 +1,810 @@
+use std::io::Write;
+
+use petgraph::Direction;
+use proc_macro2::{Ident, TokenStream};
+use quote::ToTokens;
+
+use crate::{
+    cpp::{
+        cpp_code::doc_comments_to_c_comments,
+        map_type::{c_struct_name, cpp_convert, cpp_typename, map_type},
+        CppForeignTypeInfo,
+    },
+    error::{panic_on_syn_error, DiagnosticError, Result},
+    file_cache::FileWriteCache,
+    source_registry::SourceId,
+    typemap::{
+        ast::{parse_ty_with_given_span_checked, DisplayToTokens},
+        ty::RustType,
+        unpack_unique_typename, TypeConvEdge, FROM_VAR_TEMPLATE, TO_VAR_TEMPLATE,
+        TO_VAR_TYPE_TEMPLATE,
+    },
+    types::{ForeignEnumInfo, ForeignStructField, ForeignStructInfo},
+    CppConfig, CppOutput, CppVariant, TypeMap,
+};
+
+struct FieldInfo {
+    rust_ty: RustType,
+    /// the same C type is used to pass field in both directions
+    output: CppForeignTypeInfo,
+    input: CppForeignTypeInfo,
+}
+
+/// Rust code that converts fields one by one, field `x` is in variable `f_x`
+struct FieldsConversation {
+    vars: Vec<String>,
+    to_c_code: String,
+    from_c_code: String,
+}
+
+/// `#[repr(C)]` mirror of `foreign_struct` with conversations into it and back,
+/// plus C struct and C++ POD with the same fields
+pub(in crate::cpp) fn generate(
+    conv_map: &mut TypeMap,
+    cpp_cfg: &CppConfig,
+    struct_info: &ForeignStructInfo,
+) -> Result<Vec<TokenStream>> {
+    let struct_name = struct_info.rust_struct_name();
+    let c_name = c_struct_name(&struct_info.name);
+
+    let fields = map_fields(
+        conv_map,
+        cpp_cfg,
+        &format!("foreign_struct {}", struct_name),
+        struct_info.src_id,
+        &struct_info.fields,
+    )?;
+
+    let (struct_ty, c_struct_ty) = alloc_types(conv_map, &struct_info.name, struct_info.src_id);
+
+    let mut to_c_deps = TokenStream::new();
+    let mut from_c_deps = TokenStream::new();
+    let conv = convert_fields(
+        conv_map,
+        &struct_name,
+        &c_name,
+        struct_info.src_id,
+        &struct_info.fields,
+        &fields,
+        &mut to_c_deps,
+        &mut from_c_deps,
+    )?;
+    let fields_init: Vec<String> = struct_info
+        .fields
+        .iter()
+        .zip(conv.vars.iter())
+        .map(|(field, var)| format!("{}: {}", field.name, var))
+        .collect();
+    let fields_init = fields_init.join(", ");
+
+    let code = format!(
+        r#"
+    let mut {to_var}: {to_var_type} = {{
+        let {struct_name} {{ {fields_init} }} = {from_var};
+{to_c_code}
+        {c_name} {{ {fields_init} }}
+    }};
+"#,
+        to_var = TO_VAR_TEMPLATE,
+        to_var_type = TO_VAR_TYPE_TEMPLATE,
+        from_var = FROM_VAR_TEMPLATE,
+        struct_name = struct_name,
+        c_name = c_name,
+        fields_init = fields_init,
+        to_c_code = conv.to_c_code,
+    );
+    conv_map.add_conversation_rule(
+        struct_ty.clone(),
+        c_struct_ty.clone(),
+        TypeConvEdge::new(code, non_empty(to_c_deps)),
+    );
+    let code = format!(
+        r#"
+    let mut {to_var}: {to_var_type} = {{
+        let {c_name} {{ {fields_init} }} = {from_var};
+{from_c_code}
+        {struct_name} {{ {fields_init} }}
+    }};
+"#,
+        to_var = TO_VAR_TEMPLATE,
+        to_var_type = TO_VAR_TYPE_TEMPLATE,
+        from_var = FROM_VAR_TEMPLATE,
+        struct_name = struct_name,
+        c_name = c_name,
+        fields_init = fields_init,
+        from_c_code = conv.from_c_code,
+    );
+    conv_map.add_conversation_rule(
+        c_struct_ty,
+        struct_ty,
+        TypeConvEdge::new(code, non_empty(from_c_deps)),
+    );
+
+    if cpp_cfg.output != CppOutput::Nothing {
+        generate_struct_headers(conv_map, cpp_cfg, struct_info, &fields)
+            .map_err(|err| DiagnosticError::new2(struct_info.src_id_span(), err))?;
+    }
+    conv_map.register_exported_struct(struct_info);
+
+    Ok(vec![rust_c_struct(&c_name, &struct_info.fields, &fields)])
+}
+
+/// `#[repr(C)]` structure with discriminant and payload of each data-carrying item
+/// of `foreign_enum`, plus C structures and C++ class on top of variant
+pub(in crate::cpp) fn generate_for_data_enum(
+    conv_map: &mut TypeMap,
+    cpp_cfg: &CppConfig,
+    enum_info: &ForeignEnumInfo,
+) -> Result<Vec<TokenStream>> {
+    let enum_name = enum_info.rust_enum_name();
+    let c_name = c_struct_name(&enum_info.name);
+
+    let mut items_fields = Vec::with_capacity(enum_info.items.len());
+    for item in &enum_info.items {
+        items_fields.push(map_fields(
+            conv_map,
+            cpp_cfg,
+            &format!("foreign_enum item {}::{}", enum_name, item.name),
+            enum_info.src_id,
+            &item.fields,
+        )?);
+    }
+
+    let (enum_ty, c_enum_ty) = alloc_types(conv_map, &enum_info.name, enum_info.src_id);
+
+    let mut ret = Vec::with_capacity(enum_info.items.len() + 1);
+    let mut c_enum_fields = "    pub tag: u32,\n".to_string();
+    let mut to_c_deps = TokenStream::new();
+    let mut from_c_deps = TokenStream::new();
+    let mut to_c_arms = String::new();
+    let mut from_c_arms = String::new();
+    for (item, fields) in enum_info.items.iter().zip(items_fields.iter()) {
+        let item_path = DisplayToTokens(&item.rust_name);
+        if item.fields.is_empty() {
+            to_c_arms.push_str(&format!(
+                "            {item_path} {{}} => c_enum.tag = {value},\n",
+                item_path = item_path,
+                value = item.value,
+            ));
+            from_c_arms.push_str(&format!(
+                "            {value} => {item_path} {{}},\n",
+                item_path = item_path,
+                value = item.value,
+            ));
+            continue;
+        }
+        let payload_name = format!("{}_{}", c_name, item.name);
+        ret.push(rust_c_struct(&payload_name, &item.fields, fields));
+        c_enum_fields.push_str(&format!("    pub {}: {},\n", item.name, payload_name));
+
+        let conv = convert_fields(
+            conv_map,
+            &enum_name,
+            &c_name,
+            enum_info.src_id,
+            &item.fields,
+            fields,
+            &mut to_c_deps,
+            &mut from_c_deps,
+        )?;
+        let rust_fields: Vec<String> = item
+            .fields
+            .iter()
+            .zip(conv.vars.iter())
+            .map(|(field, var)| format!("{}: {}", item.rust_field_name(field), var))
+            .collect();
+        let rust_fields = rust_fields.join(", ");
+        let c_fields: Vec<String> = item
+            .fields
+            .iter()
+            .zip(conv.vars.iter())
+            .map(|(field, var)| format!("{}: {}", field.name, var))
+            .collect();
+        let c_fields = c_fields.join(", ");
+        to_c_arms.push_str(&format!(
+            r#"
+            {item_path} {{ {rust_fields} }} => {{
+{to_c_code}
+                c_enum.tag = {value};
+                c_enum.{item_name} = {payload_name} {{ {c_fields} }};
+            }}
+"#,
+            item_path = item_path,
+            rust_fields = rust_fields,
+            to_c_code = conv.to_c_code,
+            value = item.value,
+            item_name = item.name,
+            payload_name = payload_name,
+            c_fields = c_fields,
+        ));
+        from_c_arms.push_str(&format!(
+            r#"
+            {value} => {{
+                let {payload_name} {{ {c_fields} }} = c_enum.{item_name};
+{from_c_code}
+                {item_path} {{ {rust_fields} }}
+            }}
+"#,
+            value = item.value,
+            payload_name = payload_name,
+            c_fields = c_fields,
+            item_name = item.name,
+            from_c_code = conv.from_c_code,
+            item_path = item_path,
+            rust_fields = rust_fields,
+        ));
+    }
+
+    let code = format!(
+        r#"
+    let mut {to_var}: {to_var_type} = {{
+        let mut c_enum: {c_name} = unsafe {{ ::std::mem::zeroed() }};
+        match {from_var} {{
+{to_c_arms}
+        }}
+        c_enum
+    }};
+"#,
+        to_var = TO_VAR_TEMPLATE,
+        to_var_type = TO_VAR_TYPE_TEMPLATE,
+        from_var = FROM_VAR_TEMPLATE,
+        c_name = c_name,
+        to_c_arms = to_c_arms,
+    );
+    conv_map.add_conversation_rule(
+        enum_ty.clone(),
+        c_enum_ty.clone(),
+        TypeConvEdge::new(code, non_empty(to_c_deps)),
+    );
+    let code = format!(
+        r#"
+    let mut {to_var}: {to_var_type} = {{
+        let c_enum: {c_name} = {from_var};
+        match c_enum.tag {{
+{from_c_arms}
+            tag => panic!("{{}} not expected for {enum_name}", tag),
+        }}
+    }};
+"#,
+        to_var = TO_VAR_TEMPLATE,
+        to_var_type = TO_VAR_TYPE_TEMPLATE,
+        from_var = FROM_VAR_TEMPLATE,
+        c_name = c_name,
+        from_c_arms = from_c_arms,
+        enum_name = enum_name,
+    );
+    conv_map.add_conversation_rule(
+        c_enum_ty,
+        enum_ty,
+        TypeConvEdge::new(code, non_empty(from_c_deps)),
+    );
+
+    if cpp_cfg.output != CppOutput::Nothing {
+        generate_data_enum_headers(conv_map, cpp_cfg, enum_info, &items_fields)
+            .map_err(|err| DiagnosticError::new2(enum_info.src_id_span(), err))?;
+    }
+    conv_map.register_exported_data_enum(enum_info);
+
+    let c_enum = format!(
+        r#"
+#[repr(C)]
+#[allow(non_camel_case_types, non_snake_case)]
+pub struct {c_name} {{
+{c_enum_fields}}}
+"#,
+        c_name = c_name,
+        c_enum_fields = c_enum_fields,
+    );
+    let c_enum: syn::Item =
+        syn::parse_str(&c_enum).unwrap_or_else(|err| panic_on_syn_error("c_enum", c_enum, err));
+    ret.push(c_enum.into_token_stream());
+    Ok(ret)
+}
+
+fn map_fields(
+    conv_map: &mut TypeMap,
+    cpp_cfg: &CppConfig,
+    owner: &str,
+    src_id: SourceId,
+    fields: &[ForeignStructField],
+) -> Result<Vec<FieldInfo>> {
+    let mut ret = Vec::with_capacity(fields.len());
+    for field in fields {
+        let field_span = (src_id, field.name.span());
+        let rust_ty = conv_map.find_or_alloc_rust_type(&field.ty, src_id);
+        let output = map_type(conv_map, cpp_cfg, &rust_ty, Direction::Outgoing, field_span)?;
+        let input = map_type(conv_map, cpp_cfg, &rust_ty, Direction::Incoming, field_span)?;
+        if output.base.name != input.base.name || cpp_typename(&output) != cpp_typename(&input) {
+            return Err(DiagnosticError::new2(
+                field_span,
+                format!(
+                    "C++: field '{}' of {} has type '{}', \
+                     that is passed as '{}' from Rust and as '{}' into Rust, \
+                     such fields are not supported",
+                    field.name, owner, rust_ty, output.base.name, input.base.name
+                ),
+            ));
+        }
+        ret.push(FieldInfo {
+            rust_ty,
+            output,
+            input,
+        });
+    }
+    Ok(ret)
+}
+
+/// Rust type with such name and its `#[repr(C)]` mirror
+fn alloc_types(conv_map: &mut TypeMap, name: &Ident, src_id: SourceId) -> (RustType, RustType) {
+    let rust_ty = conv_map.find_or_alloc_rust_type(
+        &parse_ty_with_given_span_checked(&name.to_string(), name.span()),
+        src_id,
+    );
+    let c_ty = conv_map.find_or_alloc_rust_type_no_src_id(&parse_ty_with_given_span_checked(
+        &c_struct_name(name),
+        name.span(),
+    ));
+    (rust_ty, c_ty)
+}
+
+#[allow(clippy::too_many_arguments)]
+fn convert_fields(
+    conv_map: &mut TypeMap,
+    rust_name: &str,
+    c_name: &str,
+    src_id: SourceId,
+    fields: &[ForeignStructField],
+    fields_info: &[FieldInfo],
+    to_c_deps: &mut TokenStream,
+    from_c_deps: &mut TokenStream,
+) -> Result<FieldsConversation> {
+    let mut ret = FieldsConversation {
+        vars: Vec::with_capacity(fields.len()),
+        to_c_code: String::new(),
+        from_c_code: String::new(),
+    };
+    for (field, f_info) in fields.iter().zip(fields_info.iter()) {
+        let field_span = (src_id, field.name.span());
+        let var = format!("f_{}", field.name);
+        let (deps, code) = conv_map.convert_rust_types(
+            &f_info.rust_ty,
+            &f_info.output.base.correspoding_rust_type,
+            &var,
+            c_name,
+            field_span,
+        )?;
+        to_c_deps.extend(deps);
+        ret.to_c_code.push_str(&code);
+        let (deps, code) = conv_map.convert_rust_types(
+            &f_info.input.base.correspoding_rust_type,
+            &f_info.rust_ty,
+            &var,
+            rust_name,
+            field_span,
+        )?;
+        from_c_deps.extend(deps);
+        ret.from_c_code.push_str(&code);
+        ret.vars.push(var);
+    }
+    Ok(ret)
+}
+
+fn non_empty(deps: TokenStream) -> Option<TokenStream> {
+    if deps.is_empty() {
+        None
+    } else {
+        Some(deps)
+    }
+}
+
+fn rust_c_struct(
+    c_name: &str,
+    fields: &[ForeignStructField],
+    fields_info: &[FieldInfo],
+) -> TokenStream {
+    let mut c_fields = String::new();
+    for (field, f_info) in fields.iter().zip(fields_info.iter()) {
+        c_fields.push_str(&format!(
+            "    pub {}: {},\n",
+            field.name,
+            unpack_unique_typename(&f_info.output.base.correspoding_rust_type.normalized_name)
+        ));
+    }
+    let c_struct = format!(
+        r#"
+#[repr(C)]
+#[allow(non_camel_case_types)]
+pub struct {c_name} {{
+{c_fields}}}
+"#,
+        c_name = c_name,
+        c_fields = c_fields,
+    );
+    let c_struct: syn::Item = syn::parse_str(&c_struct)
+        .unwrap_or_else(|err| panic_on_syn_error("c_struct", c_struct, err));
+    c_struct.into_token_stream()
+}
+
+/// `#include` of headers for other `foreign_struct` and `foreign_enum`
+/// used as types of fields, plus declarations of fields in C and C++
+#[derive(Default)]
+struct FieldsDecl {
+    c_includes: String,
+    cpp_includes: String,
+    c_fields: String,
+    cpp_fields: String,
+}
+
+impl FieldsDecl {
+    fn add(
+        &mut self,
+        conv_map: &TypeMap,
+        fields: &[ForeignStructField],
+        fields_info: &[FieldInfo],
+        indent: &str,
+    ) {
+        for (field, f_info) in fields.iter().zip(fields_info.iter()) {
+            let by_value_name = conv_map
+                .is_this_exported_struct(&f_info.rust_ty)
+                .map(|x| &x.name)
+                .or_else(|| {
+                    conv_map
+                        .is_this_exported_data_enum(&f_info.rust_ty)
+                        .map(|x| &x.name)
+                });
+            if let Some(nested) = by_value_name {
+                let include = format!("#include \"c_{}.h\"\n", nested);
+                if !self.c_includes.contains(&include) {
+                    self.c_includes.push_str(&include);
+                    self.cpp_includes
+                        .push_str(&format!("#include \"{}.hpp\"\n", nested));
+                }
+            } else if let Some(foreign_enum) = conv_map.is_this_exported_enum(&f_info.rust_ty) {
+                let include = format!("#include \"c_{}.h\"\n", foreign_enum.name);
+                if !self.cpp_includes.contains(&include) {
+                    self.cpp_includes.push_str(&include);
+                }
+            } else if let Some(foreign_flags) = conv_map.is_this_exported_flags(&f_info.rust_ty) {
+                let include = format!("#include \"{}.hpp\"\n", foreign_flags.name);
+                if !self.cpp_includes.contains(&include) {
+                    self.cpp_includes.push_str(&include);
+                }
+            }
+            let field_comments = doc_comments_to_c_comments(&field.doc_comments, false);
+            for line in field_comments.lines() {
+                let line = format!("{}{}\n", indent, line);
+                self.c_fields.push_str(&line);
+                self.cpp_fields.push_str(&line);
+            }
+            self.c_fields.push_str(&format!(
+                "{}    {} {};\n",
+                indent, f_info.output.base.name, field.name
+            ));
+            self.cpp_fields.push_str(&format!(
+                "{}    {} {};\n",
+                indent,
+                cpp_typename(&f_info.output),
+                field.name
+            ));
+        }
+    }
+}
+
+fn write_c_header(
+    cpp_cfg: &CppConfig,
+    name: &Ident,
+    doc_comments: &[String],
+    c_includes: &str,
+    decls: &str,
+) -> std::result::Result<(), String> {
+    let c_path = cpp_cfg.output_dir.join(format!("c_{}.h", name));
+    let mut c_file = FileWriteCache::new(&c_path);
+    write!(
+        c_file,
+        r#"// Automaticaly generated by rust_swig
+{doc_comments}
+#pragma once
+
+{base_include}
+{c_includes}
+{decls}"#,
+        doc_comments = doc_comments_to_c_comments(doc_comments, true),
+        base_include = if cpp_cfg.output == CppOutput::C {
+            "#include \"rust_swig_c.h\""
+        } else {
+            "//for (u)intX_t types\n#include <stdint.h>"
+        },
+        c_includes = c_includes,
+        decls = decls,
+    )
+    .map_err(|err| format!("write to {} failed: {}", c_path.display(), err))?;
+    c_file
+        .update_file_if_necessary()
+        .map_err(|err| format!("update of {} failed: {}", c_path.display(), err))
+}
+
+fn generate_struct_headers(
+    conv_map: &TypeMap,
+    cpp_cfg: &CppConfig,
+    struct_info: &ForeignStructInfo,
+    fields: &[FieldInfo],
+) -> std::result::Result<(), String> {
+    let c_name = c_struct_name(&struct_info.name);
+    let mut decl = FieldsDecl::default();
+    decl.add(conv_map, &struct_info.fields, fields, "");
+    let mut from_c = Vec::with_capacity(fields.len());
+    let mut to_c = Vec::with_capacity(fields.len());
+    for (field, f_info) in struct_info.fields.iter().zip(fields.iter()) {
+        from_c.push(cpp_convert(&f_info.output, &format!("c.{}", field.name)));
+        to_c.push(format!(
+            "        ret.{} = {};\n",
+            field.name,
+            cpp_convert(&f_info.input, &field.name.to_string())
+        ));
+    }
+
+    write_c_header(
+        cpp_cfg,
+        &struct_info.name,
+        &struct_info.doc_comments,
+        &decl.c_includes,
+        &format!(
+            "struct {c_name} {{\n{c_fields}}};\n",
+            c_name = c_name,
+            c_fields = decl.c_fields
+        ),
+    )?;
+
+    if cpp_cfg.output == CppOutput::C {
+        return Ok(());
+    }
+
+    let cpp_path = cpp_cfg.output_dir.join(format!("{}.hpp", struct_info.name));
+    let mut cpp_file = FileWriteCache::new(&cpp_path);
+    write!(
+        cpp_file,
+        r#"// Automaticaly generated by rust_swig
+#pragma once
+
+#include "c_{struct_name}.h"
+{cpp_includes}
+namespace {namespace} {{
+
+{doc_comments}
+struct {struct_name} {{
+{cpp_fields}
+    static {struct_name} from_c(const {c_name} &c) noexcept
+    {{
+        return {struct_name}{{ {from_c} }};
+    }}
+    {c_name} to_c() const noexcept
+    {{
+        {c_name} ret;
+{to_c}        return ret;
+    }}
+}};
+
+}} // namespace {namespace}
+"#,
+        struct_name = struct_info.name,
+        cpp_includes = decl.cpp_includes,
+        namespace = cpp_cfg.namespace_name,
+        doc_comments = doc_comments_to_c_comments(&struct_info.doc_comments, true),
+        cpp_fields = decl.cpp_fields,
+        c_name = c_name,
+        from_c = from_c.join(", "),
+        to_c = to_c.concat(),
+    )
+    .map_err(|err| format!("write to {} failed: {}", cpp_path.display(), err))?;
+    cpp_file
+        .update_file_if_necessary()
+        .map_err(|err| format!("update of {} failed: {}", cpp_path.display(), err))?;
+    Ok(())
+}
+
+fn generate_data_enum_headers(
+    conv_map: &TypeMap,
+    cpp_cfg: &CppConfig,
+    enum_info: &ForeignEnumInfo,
+    items_fields: &[Vec<FieldInfo>],
+) -> std::result::Result<(), String> {
+    let c_name = c_struct_name(&enum_info.name);
+    let (variant_ns, get_if) = match cpp_cfg.cpp_variant {
+        CppVariant::Std17 => ("std", "std::get_if"),
+        CppVariant::Boost => ("boost", "boost::get"),
+    };
+    let mut c_includes = String::new();
+    let mut cpp_includes = String::new();
+    let mut c_tags = Vec::with_capacity(enum_info.items.len());
+    let mut c_payloads = String::new();
+    let mut c_enum_fields = String::new();
+    let mut cpp_items = String::new();
+    let mut constructors = String::new();
+    let mut from_c = String::new();
+    let mut to_c = Vec::with_capacity(enum_info.items.len());
+    for (item, fields) in enum_info.items.iter().zip(items_fields.iter()) {
+        let mut decl = FieldsDecl::default();
+        decl.add(conv_map, &item.fields, fields, "");
+        for include in decl.c_includes.lines() {
+            if !c_includes.contains(include) {
+                c_includes.push_str(include);
+                c_includes.push('\n');
+            }
+        }
+        for include in decl.cpp_includes.lines() {
+            if !cpp_includes.contains(include) {
+                cpp_includes.push_str(include);
+                cpp_includes.push('\n');
+            }
+        }
+
+        c_tags.push(format!("    {}Tag_{} = {}", c_name, item.name, item.value));
+        let doc_comments = doc_comments_to_c_comments(&item.doc_comments, false);
+        if !doc_comments.is_empty() {
+            cpp_items.push_str(&doc_comments);
+            cpp_items.push('\n');
+        }
+        cpp_items.push_str(&format!("    struct {} {{\n", item.name));
+        for line in decl.cpp_fields.lines() {
+            cpp_items.push_str("    ");
+            cpp_items.push_str(line);
+            cpp_items.push('\n');
+        }
+        cpp_items.push_str("    };\n");
+        constructors.push_str(&format!(
+            "    {enum_name}({item_name} v) noexcept : value_(std::move(v)) {{}}\n",
+            enum_name = enum_info.name,
+            item_name = item.name,
+        ));
+
+        let mut from_c_fields = Vec::with_capacity(fields.len());
+        let mut to_c_fields = String::new();
+        for (field, f_info) in item.fields.iter().zip(fields.iter()) {
+            from_c_fields.push(cpp_convert(
+                &f_info.output,
+                &format!("c.{}.{}", item.name, field.name),
+            ));
+            to_c_fields.push_str(&format!(
+                "            ret.{}.{} = {};\n",
+                item.name,
+                field.name,
+                cpp_convert(&f_info.input, &format!("v->{}", field.name))
+            ));
+        }
+        from_c.push_str(&format!(
+            "        case {value}:\n            return {enum_name}{{{item_name}{{{fields}}}}};\n",
+            value = item.value,
+            enum_name = enum_info.name,
+            item_name = item.name,
+            fields = from_c_fields.join(", "),
+        ));
+        if item.fields.is_empty() {
+            to_c.push(format!(
+                "if (is<{item_name}>()) {{\n            ret.tag = {value};\n        }}",
+                item_name = item.name,
+                value = item.value,
+            ));
+        } else {
+            to_c.push(format!(
+                "if (auto v = {get_if}<{item_name}>(&value_)) {{\n            ret.tag = {value};\n{to_c_fields}        }}",
+                get_if = get_if,
+                item_name = item.name,
+                value = item.value,
+                to_c_fields = to_c_fields,
+            ));
+            if !doc_comments.is_empty() {
+                c_payloads.push_str(doc_comments.trim_start());
+                c_payloads.push('\n');
+            }
+            c_payloads.push_str(&format!(
+                "struct {c_name}_{item_name} {{\n{c_fields}}};\n",
+                c_name = c_name,
+                item_name = item.name,
+                c_fields = decl.c_fields,
+            ));
+            c_enum_fields.push_str(&format!(
+                "    struct {c_name}_{item_name} {item_name};\n",
+                c_name = c_name,
+                item_name = item.name,
+            ));
+        }
+    }
+
+    write_c_header(
+        cpp_cfg,
+        &enum_info.name,
+        &enum_info.doc_comments,
+        &c_includes,
+        &format!(
+            r#"enum {c_name}Tag {{
+{c_tags}
+}};
+
+{c_payloads}
+struct {c_name} {{
+    uint32_t tag;
+{c_enum_fields}}};
+"#,
+            c_name = c_name,
+            c_tags = c_tags.join(",\n"),
+            c_payloads = c_payloads,
+            c_enum_fields = c_enum_fields,
+        ),
+    )?;
+
+    if cpp_cfg.output == CppOutput::C {
+        return Ok(());
+    }
+
+    let variants: Vec<String> = enum_info.items.iter().map(|x| x.name.to_string()).collect();
+    let cpp_path = cpp_cfg.output_dir.join(format!("{}.hpp", enum_info.name));
+    let mut cpp_file = FileWriteCache::new(&cpp_path);
+    write!(
+        cpp_file,
+        r#"// Automaticaly generated by rust_swig
+#pragma once
+
+//for std::abort
+#include <cstdlib>
+//for std::move
+#include <utility>
+{variant_include}
+
+#include "c_{enum_name}.h"
+{cpp_includes}
+namespace {namespace} {{
+
+{doc_comments}
+class {enum_name} {{
+public:
+{cpp_items}
+    using variant_type = {variant_ns}::variant<{variants}>;
+
+{constructors}
+    const variant_type &variant() const noexcept {{ return value_; }}
+    template <typename T> bool is() const noexcept {{ return {get_if}<T>(&value_) != nullptr; }}
+    template <typename T> const T &get() const {{ return {variant_ns}::get<T>(value_); }}
+
+    static {enum_name} from_c(const {c_name} &c) noexcept
+    {{
+        switch (c.tag) {{
+{from_c}        default:
+            std::abort();
+        }}
+    }}
+    {c_name} to_c() const noexcept
+    {{
+        {c_name} ret{{}};
+        {to_c}
+        return ret;
+    }}
+
+private:
+    variant_type value_;
+}};
+
+}} // namespace {namespace}
+"#,
+        variant_include = match cpp_cfg.cpp_variant {
+            CppVariant::Std17 => "//for std::variant\n#include <variant>",
+            CppVariant::Boost => "//for boost::variant\n#include <boost/variant.hpp>",
+        },
+        enum_name = enum_info.name,
+        cpp_includes = cpp_includes,
+        namespace = cpp_cfg.namespace_name,
+        doc_comments = doc_comments_to_c_comments(&enum_info.doc_comments, true),
+        cpp_items = cpp_items,
+        variant_ns = variant_ns,
+        variants = variants.join(", "),
+        constructors = constructors,
+        get_if = get_if,
+        c_name = c_name,
+        from_c = from_c,
+        to_c = to_c.join(" else "),
+    )
+    .map_err(|err| format!("write to {} failed: {}", cpp_path.display(), err))?;
+    cpp_file
+        .update_file_if_necessary()
+        .map_err(|err| format!("update of {} failed: {}", cpp_path.display(), err))?;
+    Ok(())
+}
@@ -93,6 +93,8 @@ mod swig_foreign_types_map {
     #![swig_rust_type = "CRustObjectSlice"]
     #![swig_foreigner_type = "struct CRustObjectPair"]
     #![swig_rust_type = "CRustObjectPair"]
+    #![swig_foreigner_type = "struct CRustMap"]
+    #![swig_rust_type = "CRustMap"]
 }
 
 #[allow(unused_macros)]
@@ -102,6 +104,119 @@ macro_rules! swig_c_str {
     };
 }
 
+#[allow(dead_code)]
+fn swig_panic_message(err: Box<dyn ::std::any::Any + Send>) -> String {
+    if let Some(msg) = err.downcast_ref::<&str>() {
+        msg.to_string()
+    } else if let Some(msg) = err.downcast_ref::<String>() {
+        msg.clone()
+    } else {
+        "unknown panic payload".to_string()
+    }
+}
+
+/// Callback from C/C++ that gets output of `Future` returned by method
+#[allow(dead_code)]
+struct SwigFutureCallback<F> {
+    opaque: *mut ::std::os::raw::c_void,
+    on_ready: F,
+}
+
+unsafe impl<F: Send> Send for SwigFutureCallback<F> {}
+
+#[allow(dead_code)]
+impl<F> SwigFutureCallback<F> {
+    fn new(opaque: *mut ::std::os::raw::c_void, on_ready: F) -> Self {
+        SwigFutureCallback { opaque, on_ready }
+    }
+}
+
+#[allow(dead_code)]
+impl SwigFutureCallback<extern "C" fn(*mut ::std::os::raw::c_void)> {
+    fn complete(self) {
+        (self.on_ready)(self.opaque)
+    }
+}
+
+#[allow(dead_code)]
+impl<T> SwigFutureCallback<extern "C" fn(*mut ::std::os::raw::c_void, T)> {
+    fn complete_with(self, value: T) {
+        (self.on_ready)(self.opaque, value)
+    }
+}
+
+/// Future that passes output of `future` to `complete`
+#[allow(dead_code)]
+struct SwigFutureThen<F, C> {
+    future: ::std::pin::Pin<Box<F>>,
+    complete: Option<C>,
+}
+
+impl<F, C> Unpin for SwigFutureThen<F, C> {}
+
+impl<F, C> ::std::future::Future for SwigFutureThen<F, C>
+where
+    F: ::std::future::Future,
+    C: FnOnce(F::Output),
+{
+    type Output = ();
+    fn poll(
+        mut self: ::std::pin::Pin<&mut Self>,
+        cx: &mut ::std::task::Context,
+    ) -> ::std::task::Poll<()> {
+        match self.future.as_mut().poll(cx) {
+            ::std::task::Poll::Ready(output) => {
+                let complete = self
+                    .complete
+                    .take()
+                    .expect("SwigFutureThen polled after completion");
+                complete(output);
+                ::std::task::Poll::Ready(())
+            }
+            ::std::task::Poll::Pending => ::std::task::Poll::Pending,
+        }
+    }
+}
+
+#[allow(dead_code)]
+fn swig_future_then<F, C>(
+    future: F,
+    complete: C,
+) -> ::std::pin::Pin<Box<dyn ::std::future::Future<Output = ()> + Send + 'static>>
+where
+    F: ::std::future::Future + Send + 'static,
+    C: FnOnce(F::Output) + Send + 'static,
+{
+    Box::pin(SwigFutureThen {
+        future: Box::pin(future),
+        complete: Some(complete),
+    })
+}
+
+/// Default executor for methods that return `Future`,
+/// each future is polled until completion on its own thread
+#[allow(dead_code)]
+fn swig_spawn_future(
+    future: ::std::pin::Pin<Box<dyn ::std::future::Future<Output = ()> + Send + 'static>>,
+) {
+    struct ThreadWaker(::std::thread::Thread);
+    impl ::std::task::Wake for ThreadWaker {
+        fn wake(self: ::std::sync::Arc<Self>) {
+            self.0.unpark();
+        }
+    }
+    ::std::thread::spawn(move || {
+        let mut future = future;
+        let waker = ::std::task::Waker::from(::std::sync::Arc::new(ThreadWaker(
+            ::std::thread::current(),
+        )));
+        let mut cx = ::std::task::Context::from_waker(&waker);
+        while future.as_mut().poll(&mut cx).is_pending() {
+            ::std::thread::park();
+        }
+    });
+}
+
 #[allow(dead_code)]
 pub trait SwigForeignClass {
     fn c_class_name() -> *const ::std::os::raw::c_char;
@@ -550,6 +665,46 @@ fn remove_foreign_class_from_vec<T: SwigForeignClass>(
     T::box_object(elem)
 }
 
+/// `HashMap` or `BTreeMap` passed to C/C++ as vector of key-value pairs,
+/// keys and values are converted one by one by generated functions
+#[allow(dead_code)]
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub struct CRustMap {
+    data: *mut ::std::os::raw::c_void,
+    len: usize,
+}
+
+#[allow(dead_code)]
+impl CRustMap {
+    pub fn from_map<K, V, M: IntoIterator<Item = (K, V)>>(map: M) -> CRustMap {
+        let entries: Vec<(K, V)> = map.into_iter().collect();
+        let len = entries.len();
+        CRustMap {
+            data: Box::into_raw(Box::new(entries)) as *mut ::std::os::raw::c_void,
+            len,
+        }
+    }
+    pub fn into_map<K, V, M: ::std::iter::FromIterator<(K, V)>>(self) -> M {
+        assert!(!self.data.is_null());
+        let entries = unsafe { Box::from_raw(self.data as *mut Vec<(K, V)>) };
+        entries.into_iter().collect()
+    }
+    fn entries<K, V>(&mut self) -> &mut Vec<(K, V)> {
+        assert!(!self.data.is_null());
+        unsafe { &mut *(self.data as *mut Vec<(K, V)>) }
+    }
+    pub fn pop<K, V>(&mut self) -> Option<(K, V)> {
+        let ret = self.entries::<K, V>().pop();
+        self.len = self.entries::<K, V>().len();
+        ret
+    }
+    pub fn insert<K, V>(&mut self, key: K, value: V) {
+        self.entries::<K, V>().push((key, value));
+        self.len = self.entries::<K, V>().len();
+    }
+}
+
 // &str -> &Path
 impl<'a> SwigInto<&'a Path> for &'a str {
     fn swig_into(self) -> &'a Path {
@@ -1051,6 +1206,13 @@ pub struct CRustOptionString {
     is_some: u8,
 }
 
+#[no_mangle]
+pub extern "C" fn CRustOptionString_free(x: CRustOptionString) {
+    if x.is_some != 0 {
+        crust_string_free(x.val);
+    }
+}
+
 impl SwigFrom<Option<String>> for CRustOptionString {
     fn swig_from(x: Option<String>) -> Self {
         match x {
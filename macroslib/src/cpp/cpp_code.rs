@@ -4,11 +4,13 @@ use crate::{
     cpp::{fmt_write_err_map, CppForeignMethodSignature},
     file_cache::FileWriteCache,
     typemap::FROM_VAR_TEMPLATE,
-    types::{ForeignEnumInfo, ForeignInterface, ForeignerClassInfo},
+    types::{ForeignEnumInfo, ForeignFlagsInfo, ForeignInterface, ForeignerClassInfo},
+    CppOutput,
 };
 
 pub(in crate::cpp) fn generate_code_for_enum(
     output_dir: &Path,
+    output: CppOutput,
     enum_info: &ForeignEnumInfo,
 ) -> Result<(), String> {
     let c_path = output_dir.join(format!("c_{}.h", enum_info.name));
@@ -33,7 +35,7 @@ enum {enum_name} {{
             file,
             "{doc_comments}{item_name} = {index}{separator}",
             item_name = item.name,
-            index = i,
+            index = item.value,
             doc_comments = doc_comments_to_c_comments(&item.doc_comments, false),
             separator = if i == enum_info.items.len() - 1 {
                 "\n"
@@ -45,6 +47,130 @@ enum {enum_name} {{
     }
 
     writeln!(file, "}};").map_err(&map_write_err)?;
+    if output == CppOutput::C {
+        writeln!(
+            file,
+            "typedef enum {enum_name} {enum_name};",
+            enum_name = enum_info.name
+        )
+        .map_err(&map_write_err)?;
+    }
+    file.update_file_if_necessary().map_err(&map_write_err)?;
+    Ok(())
+}
+
+/// `foreign_flags` is passed as `uint32_t`, for C there are only constants
+/// for bits, for C++ there is `enum class` with bitwise operators
+pub(in crate::cpp) fn generate_code_for_flags(
+    output_dir: &Path,
+    output: CppOutput,
+    namespace_name: &str,
+    flags_info: &ForeignFlagsInfo,
+) -> Result<(), String> {
+    let mut items = String::new();
+    for (i, item) in flags_info.items.iter().enumerate() {
+        let doc_comments = doc_comments_to_c_comments(&item.doc_comments, false);
+        if !doc_comments.is_empty() {
+            items.push_str(&doc_comments);
+            items.push('\n');
+        }
+        items.push_str(&format!(
+            "    {item_name} = {bit:#x}{separator}\n",
+            item_name = item.name,
+            bit = item.value,
+            separator = if i == flags_info.items.len() - 1 {
+                ""
+            } else {
+                ","
+            },
+        ));
+    }
+    let flags_doc_comments = doc_comments_to_c_comments(&flags_info.doc_comments, true);
+
+    if output == CppOutput::C {
+        let c_path = output_dir.join(format!("c_{}.h", flags_info.name));
+        let mut file = FileWriteCache::new(&c_path);
+        write!(
+            file,
+            r#"// Automaticaly generated by rust_swig
+#pragma once
+
+{doc_comments}
+enum {flags_name} {{
+{items}}};
+"#,
+            doc_comments = flags_doc_comments,
+            flags_name = flags_info.name,
+            items = items,
+        )
+        .map_err(&map_write_err)?;
+        file.update_file_if_necessary().map_err(&map_write_err)?;
+        return Ok(());
+    }
+
+    let cpp_path = output_dir.join(format!("{}.hpp", flags_info.name));
+    let mut file = FileWriteCache::new(&cpp_path);
+    write!(
+        file,
+        r#"// Automaticaly generated by rust_swig
+#pragma once
+
+//for (u)intX_t types
+#include <stdint.h>
+
+namespace {namespace} {{
+
+{doc_comments}
+enum class {flags_name} : uint32_t {{
+{items}}};
+
+inline constexpr {flags_name} operator|({flags_name} a, {flags_name} b) noexcept
+{{
+    return static_cast<{flags_name}>(static_cast<uint32_t>(a) | static_cast<uint32_t>(b));
+}}
+
+inline constexpr {flags_name} operator&({flags_name} a, {flags_name} b) noexcept
+{{
+    return static_cast<{flags_name}>(static_cast<uint32_t>(a) & static_cast<uint32_t>(b));
+}}
+
+//result contains only known bits
+inline constexpr {flags_name} operator~({flags_name} a) noexcept
+{{
+    return static_cast<{flags_name}>(~static_cast<uint32_t>(a) & {all_bits:#x}u);
+}}
+
+inline {flags_name} &operator|=({flags_name} &a, {flags_name} b) noexcept
+{{
+    return a = a | b;
+}}
+
+inline {flags_name} &operator&=({flags_name} &a, {flags_name} b) noexcept
+{{
+    return a = a & b;
+}}
+
+//are all bits of `flags` set in `a`
+inline constexpr bool contains({flags_name} a, {flags_name} flags) noexcept
+{{
+    return (a & flags) == flags;
+}}
+
+//is there no unknown bits in `a`, Rust side drops them
+inline constexpr bool is_valid({flags_name} a) noexcept
+{{
+    return (static_cast<uint32_t>(a) & ~{all_bits:#x}u) == 0;
+}}
+
+}} // namespace {namespace}
+"#,
+        namespace = namespace_name,
+        doc_comments = flags_doc_comments,
+        flags_name = flags_info.name,
+        items = items,
+        all_bits = flags_info.all_bits(),
+    )
+    .map_err(&map_write_err)?;
     file.update_file_if_necessary().map_err(&map_write_err)?;
     Ok(())
 }
@@ -70,6 +196,7 @@ pub(in crate::cpp) fn doc_comments_to_c_comments(
 pub(in crate::cpp) fn generate_for_interface(
     output_dir: &Path,
     namespace_name: &str,
+    output: CppOutput,
     interface: &ForeignInterface,
     f_methods: &[CppForeignMethodSignature],
 ) -> Result<(), String> {
@@ -86,13 +213,18 @@ pub(in crate::cpp) fn generate_for_interface(
         file_c,
         r#"// Automaticaly generated by rust_swig
 #pragma once
-{doc_comments}
+{includes}{doc_comments}
 struct C_{interface_name} {{
     void *opaque;
     //! call by Rust side when callback not need anymore
     void (*C_{interface_name}_deref)(void *opaque);
     "#,
         interface_name = interface.name,
+        includes = if output == CppOutput::C {
+            "\n#include \"rust_swig_c.h\"\n"
+        } else {
+            ""
+        },
         doc_comments = interface_comments
     )
     .map_err(&map_write_err)?;
@@ -243,9 +375,11 @@ private:
     .map_err(&map_write_err)?;
 
     file_c.update_file_if_necessary().map_err(&map_write_err)?;
-    file_cpp
-        .update_file_if_necessary()
-        .map_err(&map_write_err)?;
+    if output == CppOutput::CAndCpp {
+        file_cpp
+            .update_file_if_necessary()
+            .map_err(&map_write_err)?;
+    }
 
     Ok(())
 }
@@ -267,6 +401,9 @@ pub(in crate::cpp) fn c_generate_args_with_types(
         }
         write!(&mut buf, "{} a_{}", f_type_info.as_ref().name, i).map_err(fmt_write_err_map)?;
     }
+    if !f_method.checked_enum_args.is_empty() {
+        write!(&mut buf, ", char *swig_invalid_arg").map_err(fmt_write_err_map)?;
+    }
     if !buf.is_empty() && append_comma_if_not_empty {
         write!(&mut buf, ", ").map_err(fmt_write_err_map)?;
     }
@@ -283,7 +420,11 @@ pub(in crate::cpp) fn cpp_generate_args_with_types(
     use std::fmt::Write;
     let mut ret = String::new();
     for (i, f_type_info) in f_method.input.iter().enumerate() {
-        if i > 0 {
+        //created inside of method, see `CppInputToOutput`
+        if f_method.input_to_output.as_ref().map(|x| x.idx) == Some(i) {
+            continue;
+        }
+        if !ret.is_empty() {
             write!(&mut ret, ", ").map_err(fmt_write_err_map)?;
         }
 
@@ -311,7 +452,8 @@ pub(in crate::cpp) fn cpp_generate_args_to_call_c(
         if i > 0 {
             write!(&mut ret, ", ").map_err(fmt_write_err_map)?;
         }
-        if let Some(conv) = f_type_info.cpp_converter.as_ref() {
+        let is_input_to_output = f_method.input_to_output.as_ref().map(|x| x.idx) == Some(i);
+        if let (Some(conv), false) = (f_type_info.cpp_converter.as_ref(), is_input_to_output) {
             let arg_name = format!("a_{}", i);
             let conv_arg = conv
                 .converter
@@ -323,5 +465,8 @@ pub(in crate::cpp) fn cpp_generate_args_to_call_c(
         }
         .map_err(fmt_write_err_map)?;
     }
+    if !f_method.checked_enum_args.is_empty() {
+        write!(&mut ret, ", &swig_invalid_arg").map_err(fmt_write_err_map)?;
+    }
     Ok(ret)
 }
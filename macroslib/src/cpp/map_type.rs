@@ -2,7 +2,7 @@ use std::io::Write;
 
 use log::{debug, trace};
 use petgraph::Direction;
-use proc_macro2::Span;
+use proc_macro2::{Ident, Span};
 use quote::ToTokens;
 use smol_str::SmolStr;
 use syn::{parse_quote, spanned::Spanned, Type};
@@ -16,12 +16,16 @@ use crate::{
     file_cache::FileWriteCache,
     source_registry::SourceId,
     typemap::ast::{
-        if_option_return_some_type, if_result_return_ok_err_types, if_type_slice_return_elem_type,
-        if_vec_return_elem_type,
+        if_map_return_key_value_types, if_option_return_some_type, if_result_return_ok_err_types,
+        if_type_slice_return_elem_type, if_vec_return_elem_type, parse_ty_with_given_span_checked,
+        MapKind,
     },
-    typemap::{ty::RustType, ForeignTypeInfo, FROM_VAR_TEMPLATE, TO_VAR_TEMPLATE},
-    types::{ForeignEnumInfo, ForeignerClassInfo},
-    CppConfig, CppOptional, CppVariant, TypeMap,
+    typemap::{
+        ty::RustType, unpack_unique_typename, ForeignTypeInfo, TypeConvEdge, FROM_VAR_TEMPLATE,
+        TO_VAR_TEMPLATE, TO_VAR_TYPE_TEMPLATE,
+    },
+    types::{ForeignEnumInfo, ForeignFlagsInfo, ForeignerClassInfo},
+    CppConfig, CppOptional, CppOutput, CppVariant, TypeMap,
 };
 
 fn special_type(
@@ -41,6 +45,20 @@ fn special_type(
         let converter = calc_converter_for_enum(conv_map, foreign_enum, direction);
         return Ok(Some(converter));
     }
+    if let Some(foreign_flags) = conv_map.is_this_exported_flags(arg_ty) {
+        let converter = calc_converter_for_flags(conv_map, foreign_flags, direction);
+        return Ok(Some(converter));
+    }
+    if let Some(foreign_struct) = conv_map.is_this_exported_struct(arg_ty) {
+        let name = foreign_struct.name.clone();
+        let converter = calc_converter_for_struct(conv_map, &name, direction);
+        return Ok(Some(converter));
+    }
+    if let Some(data_enum) = conv_map.is_this_exported_data_enum(arg_ty) {
+        let name = data_enum.name.clone();
+        let converter = calc_converter_for_struct(conv_map, &name, direction);
+        return Ok(Some(converter));
+    }
 
     if let syn::Type::Reference(syn::TypeReference {
         elem: ref ret_ty,
@@ -179,6 +197,38 @@ fn special_type(
             }),
         }));
     }
+    if let Some(map_info) = if_map_return_key_value_types(arg_ty) {
+        return map_type_map(
+            conv_map,
+            cpp_cfg,
+            arg_ty,
+            arg_ty,
+            map_info,
+            arg_ty_span,
+            direction,
+        );
+    }
+    if direction == Direction::Incoming {
+        if let Type::Reference(syn::TypeReference {
+            ref elem,
+            mutability: None,
+            ..
+        }) = arg_ty.ty
+        {
+            let map_ty = conv_map.find_or_alloc_rust_type(elem, arg_ty_span.0);
+            if let Some(map_info) = if_map_return_key_value_types(&map_ty) {
+                return map_type_map(
+                    conv_map,
+                    cpp_cfg,
+                    arg_ty,
+                    &map_ty,
+                    map_info,
+                    arg_ty_span,
+                    direction,
+                );
+            }
+        }
+    }
     if let Some(elem_ty) = if_vec_return_elem_type(arg_ty) {
         return map_type_vec(conv_map, cpp_cfg, arg_ty, &elem_ty, arg_ty_span, direction);
     }
@@ -310,6 +360,61 @@ fn calc_converter_for_enum(
     }
 }
 
+fn calc_converter_for_flags(
+    conv_map: &TypeMap,
+    foreign_flags: &ForeignFlagsInfo,
+    direction: Direction,
+) -> CppForeignTypeInfo {
+    let u32_ti: RustType = conv_map.ty_to_rust_type(&parse_type! { u32 });
+    let converter = match direction {
+        Direction::Outgoing => {
+            format!("static_cast<{}>({})", foreign_flags.name, FROM_VAR_TEMPLATE)
+        }
+        Direction::Incoming => format!("static_cast<uint32_t>({})", FROM_VAR_TEMPLATE),
+    };
+    CppForeignTypeInfo {
+        base: ForeignTypeInfo {
+            name: "uint32_t".into(),
+            correspoding_rust_type: u32_ti,
+        },
+        cpp_converter: Some(CppConverter {
+            typename: foreign_flags.name.to_string().into(),
+            converter,
+        }),
+    }
+}
+
+/// `foreign_struct` or data-carrying `foreign_enum` passed by value
+fn calc_converter_for_struct(
+    conv_map: &mut TypeMap,
+    name: &Ident,
+    direction: Direction,
+) -> CppForeignTypeInfo {
+    let c_name = c_struct_name(name);
+    let c_struct_ti: RustType = conv_map
+        .find_or_alloc_rust_type_no_src_id(&parse_ty_with_given_span_checked(&c_name, name.span()));
+    let converter = match direction {
+        Direction::Outgoing => format!("{}::from_c({})", name, FROM_VAR_TEMPLATE),
+        Direction::Incoming => format!("{}.to_c()", FROM_VAR_TEMPLATE),
+    };
+    CppForeignTypeInfo {
+        base: ForeignTypeInfo {
+            name: format!("struct {}", c_name).into(),
+            correspoding_rust_type: c_struct_ti,
+        },
+        cpp_converter: Some(CppConverter {
+            typename: name.to_string().into(),
+            converter,
+        }),
+    }
+}
+
+/// Name of C struct, and its `#[repr(C)]` Rust mirror, for `foreign_struct`
+/// or data-carrying `foreign_enum`
+pub(in crate::cpp) fn c_struct_name(name: &Ident) -> String {
+    format!("C_{}", name)
+}
+
 pub(in crate::cpp) fn map_type(
     conv_map: &mut TypeMap,
     cpp_cfg: &CppConfig,
@@ -352,14 +457,15 @@ fn map_ordinal_result_type(
             calc_this_type_for_method,
         )
         .ok_or_else(|| {
-            DiagnosticError::new2(
+            let err = DiagnosticError::new2(
                 arg_ty_span,
                 format!(
                     "Do not know conversation from \
                      such rust type '{}' to C++ type",
                     arg_ty
                 ),
-            )
+            );
+            conv_map.explain_missing_foreign_type(err, arg_ty, Direction::Outgoing)
         })?;
     CppForeignTypeInfo::try_new(conv_map, Direction::Outgoing, ftype)
 }
@@ -377,14 +483,15 @@ fn map_ordinal_input_type(
             calc_this_type_for_method,
         )
         .ok_or_else(|| {
-            DiagnosticError::new2(
+            let err = DiagnosticError::new2(
                 arg_ty_span,
                 format!(
                     "Do not know conversation from C++ type \
                      to such rust type '{}'",
                     arg_ty
                 ),
-            )
+            );
+            conv_map.explain_missing_foreign_type(err, arg_ty, Direction::Incoming)
         })?;
     CppForeignTypeInfo::try_new(conv_map, Direction::Incoming, ftype)
 }
@@ -466,9 +573,37 @@ fn map_type_vec(
             let free_mem_func = format!("{}_free", typename);
             let push_func = format!("{}_push", typename);
             let remove_func = format!("{}_remove", typename);
-            write!(
-                c_vec_f,
-                r##"// Automaticaly generated by rust_swig
+            let write_res = if cpp_cfg.output == CppOutput::C {
+                write!(
+                    c_vec_f,
+                    r##"// Automaticaly generated by rust_swig
+#pragma once
+
+#include "rust_swig_c.h"
+#include "c_{class}.h"
+
+#ifdef __cplusplus
+extern "C" {{
+#endif
+// Frees vector and all objects in it
+void {free_mem_func}(struct CRustForeignVec vec);
+// Moves object to the end of vector, vector takes ownership of `elem`
+void {push_func}(struct CRustForeignVec *vec, {class}Opaque *elem);
+// Removes object from vector, caller takes ownership of result
+{class}Opaque *{remove_func}(struct CRustForeignVec *vec, uintptr_t idx);
+#ifdef __cplusplus
+}}
+#endif
+"##,
+                    free_mem_func = free_mem_func,
+                    class = foreign_class.name,
+                    push_func = push_func,
+                    remove_func = remove_func,
+                )
+            } else {
+                write!(
+                    c_vec_f,
+                    r##"// Automaticaly generated by rust_swig
 #pragma once
 
 #include "rust_vec.h"
@@ -488,27 +623,30 @@ using {vec_type} = RustForeignVec<{class}Ref, CRustForeignVec,
 }}
 #endif
 "##,
-                free_mem_func = free_mem_func,
-                namespace_name = cpp_cfg.namespace_name,
-                vec_type = typename,
-                class = foreign_class.name,
-                push_func = push_func,
-                remove_func = remove_func,
-            )
-            .map_err(|err| {
-                DiagnosticError::new(
-                    arg_ty_span.0,
-                    arg_ty_span.1,
-                    format!("write to {:?} failed: {}", fc_vec_path, err),
+                    free_mem_func = free_mem_func,
+                    namespace_name = cpp_cfg.namespace_name,
+                    vec_type = typename,
+                    class = foreign_class.name,
+                    push_func = push_func,
+                    remove_func = remove_func,
                 )
-            })?;
-            c_vec_f.update_file_if_necessary().map_err(|err| {
+            };
+            write_res.map_err(|err| {
                 DiagnosticError::new(
                     arg_ty_span.0,
                     arg_ty_span.1,
-                    format!("update of {:?} failed: {}", fc_vec_path, err),
+                    format!("write to {:?} failed: {}", fc_vec_path, err),
                 )
             })?;
+            if cpp_cfg.output != CppOutput::Nothing {
+                c_vec_f.update_file_if_necessary().map_err(|err| {
+                    DiagnosticError::new(
+                        arg_ty_span.0,
+                        arg_ty_span.1,
+                        format!("update of {:?} failed: {}", fc_vec_path, err),
+                    )
+                })?;
+            }
 
             let self_rust_ty = conv_map
                 .find_or_alloc_rust_type(&foreign_class.self_type_as_ty(), foreign_class.src_id);
@@ -609,6 +747,466 @@ using {vec_type} = RustForeignVec<{class}Ref, CRustForeignVec,
     Ok(Some(ftype_info))
 }
 
+/// Map `HashMap<K, V>` and `BTreeMap<K, V>` (or reference to it as input)
+/// to `RustMap` with traits generated for such map type,
+/// keys and values are converted one by one on Rust side
+fn map_type_map(
+    conv_map: &mut TypeMap,
+    cpp_cfg: &CppConfig,
+    arg_ty: &RustType,
+    map_ty: &RustType,
+    (kind, key_ty, value_ty): (MapKind, Type, Type),
+    arg_ty_span: SourceIdSpan,
+    direction: Direction,
+) -> Result<Option<CppForeignTypeInfo>> {
+    let key_ty = conv_map.find_or_alloc_rust_type(&key_ty, arg_ty_span.0);
+    let value_ty = conv_map.find_or_alloc_rust_type(&value_ty, arg_ty_span.0);
+    map_type(conv_map, cpp_cfg, &key_ty, direction, arg_ty_span)?;
+    map_type(conv_map, cpp_cfg, &value_ty, direction, arg_ty_span)?;
+
+    let c_map_ty = conv_map.find_or_alloc_rust_type_no_src_id(&parse_type! { CRustMap });
+    match direction {
+        Direction::Outgoing => {
+            if !conv_map.has_conversation_rule(map_ty, &c_map_ty) {
+                let code = format!(
+                    "let mut {to_var}: {to_var_type} = CRustMap::from_map({from_var});",
+                    to_var = TO_VAR_TEMPLATE,
+                    to_var_type = TO_VAR_TYPE_TEMPLATE,
+                    from_var = FROM_VAR_TEMPLATE,
+                );
+                conv_map.add_conversation_rule(map_ty.clone(), c_map_ty.clone(), code.into());
+            }
+        }
+        Direction::Incoming => {
+            if !conv_map.has_conversation_rule(&c_map_ty, map_ty) {
+                let code = format!(
+                    "let mut {to_var}: {to_var_type} = {from_var}.into_map();",
+                    to_var = TO_VAR_TEMPLATE,
+                    to_var_type = TO_VAR_TYPE_TEMPLATE,
+                    from_var = FROM_VAR_TEMPLATE,
+                );
+                conv_map.add_conversation_rule(c_map_ty.clone(), map_ty.clone(), code.into());
+            }
+            if map_ty.normalized_name != arg_ty.normalized_name
+                && !conv_map.has_conversation_rule(map_ty, arg_ty)
+            {
+                let code = format!(
+                    "let mut {to_var}: {to_var_type} = &{from_var};",
+                    to_var = TO_VAR_TEMPLATE,
+                    to_var_type = TO_VAR_TYPE_TEMPLATE,
+                    from_var = FROM_VAR_TEMPLATE,
+                );
+                conv_map.add_conversation_rule(
+                    map_ty.clone(),
+                    arg_ty.clone(),
+                    TypeConvEdge::new(code, None),
+                );
+            }
+        }
+    }
+    let mut ftype_info = match direction {
+        Direction::Outgoing => map_ordinal_result_type(conv_map, arg_ty, arg_ty_span)?,
+        Direction::Incoming => map_ordinal_input_type(conv_map, arg_ty, arg_ty_span)?,
+    };
+
+    let typename = map_typename(kind, &key_ty, &value_ty);
+    let map_path = cpp_cfg.output_dir.join(format!("{}.h", typename));
+    let generate_cpp_part = !cpp_cfg.generated_helper_files.borrow().contains(&map_path);
+    if generate_cpp_part {
+        trace!("map_type_map: we generate code for {:?}", map_path);
+        generate_map_helpers(
+            conv_map,
+            cpp_cfg,
+            kind,
+            &typename,
+            (&key_ty, &value_ty),
+            arg_ty_span,
+        )?;
+        cpp_cfg.generated_helper_files.borrow_mut().insert(map_path);
+    }
+    let converter = match direction {
+        Direction::Outgoing => format!(
+            "{cpp_type}{{{var}}}",
+            cpp_type = typename,
+            var = FROM_VAR_TEMPLATE
+        ),
+        Direction::Incoming => format!("{var}.release()", var = FROM_VAR_TEMPLATE),
+    };
+    ftype_info.cpp_converter = Some(CppConverter {
+        typename: typename.into(),
+        converter,
+    });
+    Ok(Some(ftype_info))
+}
+
+/// Name of C++ type for map, for example `RustHashMapStringI32`
+fn map_typename(kind: MapKind, key_ty: &RustType, value_ty: &RustType) -> String {
+    let mut name = match kind {
+        MapKind::HashMap => "RustHashMap",
+        MapKind::BTreeMap => "RustBTreeMap",
+    }
+    .to_string();
+    for ty in &[key_ty, value_ty] {
+        for part in unpack_unique_typename(&ty.normalized_name)
+            .split(|ch: char| !ch.is_ascii_alphanumeric())
+            .filter(|part| !part.is_empty())
+        {
+            let mut chars = part.chars();
+            if let Some(first) = chars.next() {
+                name.push(first.to_ascii_uppercase());
+                name.push_str(chars.as_str());
+            }
+        }
+    }
+    name
+}
+
+/// Generate `{typename}.h` with declarations of functions to work with
+/// map of such type and traits for `RustMap`, plus Rust side of these functions.
+/// Functions to create map on C++ side are generated only if keys and values
+/// can be passed from C++ to Rust
+fn generate_map_helpers(
+    conv_map: &mut TypeMap,
+    cpp_cfg: &CppConfig,
+    kind: MapKind,
+    typename: &str,
+    (key_ty, value_ty): (&RustType, &RustType),
+    arg_ty_span: SourceIdSpan,
+) -> Result<()> {
+    let map_path = cpp_cfg.output_dir.join(format!("{}.h", typename));
+    let rust_key_ty = unpack_unique_typename(&key_ty.normalized_name);
+    let rust_value_ty = unpack_unique_typename(&value_ty.normalized_name);
+    let output = match (
+        map_type(conv_map, cpp_cfg, key_ty, Direction::Outgoing, arg_ty_span),
+        map_type(
+            conv_map,
+            cpp_cfg,
+            value_ty,
+            Direction::Outgoing,
+            arg_ty_span,
+        ),
+    ) {
+        (Ok(key), Ok(value)) => Some((key, value)),
+        _ => None,
+    };
+    let input = match (
+        map_type(conv_map, cpp_cfg, key_ty, Direction::Incoming, arg_ty_span),
+        map_type(
+            conv_map,
+            cpp_cfg,
+            value_ty,
+            Direction::Incoming,
+            arg_ty_span,
+        ),
+    ) {
+        (Ok(key), Ok(value)) => Some((key, value)),
+        _ => None,
+    };
+
+    let free_func = format!("{}_free", typename);
+    let pop_func = format!("{}_pop", typename);
+    let new_func = format!("{}_new", typename);
+    let insert_func = format!("{}_insert", typename);
+
+    let mut rust_code = vec![format!(
+        r#"
+#[allow(unused_variables, unused_mut, non_snake_case)]
+#[no_mangle]
+pub extern "C" fn {free_func}(m: CRustMap) {{
+    let m: Vec<({key}, {value})> = m.into_map();
+    drop(m);
+}}
+"#,
+        free_func = free_func,
+        key = rust_key_ty,
+        value = rust_value_ty,
+    )];
+    let mut c_decls = format!(
+        "// Frees map and all keys and values in it\n\
+         void {free_func}(struct CRustMap map);\n",
+        free_func = free_func
+    );
+    let mut traits = format!(
+        "    static void free(CRustMap map) noexcept {{ {free_func}(map); }}\n",
+        free_func = free_func
+    );
+    let mut cpp_includes = Vec::new();
+    let (cpp_key_ty, cpp_value_ty) = match (&output, &input) {
+        (Some((key, value)), _) | (None, Some((key, value))) => (
+            cpp_typename(key).to_string(),
+            cpp_typename(value).to_string(),
+        ),
+        (None, None) => {
+            return Err(DiagnosticError::new2(
+                arg_ty_span,
+                format!(
+                    "Do not know conversation of keys or values of map {}<{}, {}> to C++ types",
+                    match kind {
+                        MapKind::HashMap => "HashMap",
+                        MapKind::BTreeMap => "BTreeMap",
+                    },
+                    key_ty,
+                    value_ty
+                ),
+            ));
+        }
+    };
+    for ty in &[key_ty, value_ty] {
+        if let Some(fclass) = conv_map.find_foreigner_class_with_such_self_type(ty, false) {
+            cpp_includes.push(fclass.name.to_string());
+        }
+    }
+
+    if let Some((ref key, ref value)) = output {
+        let (mut deps, key_conv) = conv_map.convert_rust_types(
+            key_ty,
+            &key.base.correspoding_rust_type,
+            "key",
+            "::std::os::raw::c_char",
+            arg_ty_span,
+        )?;
+        let (value_deps, value_conv) = conv_map.convert_rust_types(
+            value_ty,
+            &value.base.correspoding_rust_type,
+            "value",
+            "::std::os::raw::c_char",
+            arg_ty_span,
+        )?;
+        deps.extend(value_deps);
+        cpp_cfg.to_generate.borrow_mut().extend(deps);
+        rust_code.push(format!(
+            r#"
+#[allow(unused_variables, unused_mut, non_snake_case, unused_unsafe)]
+#[no_mangle]
+pub extern "C" fn {pop_func}(m: *mut CRustMap, key_out: *mut {c_key}, value_out: *mut {c_value}) -> ::std::os::raw::c_char {{
+    assert!(!m.is_null());
+    let m: &mut CRustMap = unsafe {{ &mut *m }};
+    let (key, value): ({key}, {value}) = match m.pop() {{
+        Some(x) => x,
+        None => return 0,
+    }};
+{key_conv}{value_conv}
+    unsafe {{
+        key_out.write(key);
+        value_out.write(value);
+    }}
+    1
+}}
+"#,
+            pop_func = pop_func,
+            c_key = unpack_unique_typename(&key.base.correspoding_rust_type.normalized_name),
+            c_value = unpack_unique_typename(&value.base.correspoding_rust_type.normalized_name),
+            key = rust_key_ty,
+            value = rust_value_ty,
+            key_conv = key_conv,
+            value_conv = value_conv,
+        ));
+        c_decls.push_str(&format!(
+            "// Moves some key-value pair out of map, returns 0 if map is empty\n\
+             char {pop_func}(struct CRustMap *map, {c_key} *key, {c_value} *value);\n",
+            pop_func = pop_func,
+            c_key = key.base.name,
+            c_value = value.base.name,
+        ));
+        traits.push_str(&format!(
+            r#"    template <typename F> static bool pop(CRustMap *map, F &&f)
+    {{
+        {c_key} key;
+        {c_value} value;
+        if ({pop_func}(map, &key, &value) == 0) {{
+            return false;
+        }}
+        f({key_conv}, {value_conv});
+        return true;
+    }}
+"#,
+            pop_func = pop_func,
+            c_key = key.base.name,
+            c_value = value.base.name,
+            key_conv = cpp_convert(key, "key"),
+            value_conv = cpp_convert(value, "value"),
+        ));
+    }
+
+    if let Some((ref key, ref value)) = input {
+        let (mut deps, key_conv) = conv_map.convert_rust_types(
+            &key.base.correspoding_rust_type,
+            key_ty,
+            "key",
+            "()",
+            arg_ty_span,
+        )?;
+        let (value_deps, value_conv) = conv_map.convert_rust_types(
+            &value.base.correspoding_rust_type,
+            value_ty,
+            "value",
+            "()",
+            arg_ty_span,
+        )?;
+        deps.extend(value_deps);
+        cpp_cfg.to_generate.borrow_mut().extend(deps);
+        rust_code.push(format!(
+            r#"
+#[allow(unused_variables, unused_mut, non_snake_case)]
+#[no_mangle]
+pub extern "C" fn {new_func}() -> CRustMap {{
+    CRustMap::from_map(Vec::<({key}, {value})>::new())
+}}
+"#,
+            new_func = new_func,
+            key = rust_key_ty,
+            value = rust_value_ty,
+        ));
+        rust_code.push(format!(
+            r#"
+#[allow(unused_variables, unused_mut, non_snake_case, unused_unsafe)]
+#[no_mangle]
+pub extern "C" fn {insert_func}(m: *mut CRustMap, key: {c_key}, value: {c_value}) {{
+    assert!(!m.is_null());
+    let m: &mut CRustMap = unsafe {{ &mut *m }};
+{key_conv}{value_conv}
+    m.insert::<{key}, {value}>(key, value);
+}}
+"#,
+            insert_func = insert_func,
+            c_key = unpack_unique_typename(&key.base.correspoding_rust_type.normalized_name),
+            c_value = unpack_unique_typename(&value.base.correspoding_rust_type.normalized_name),
+            key = rust_key_ty,
+            value = rust_value_ty,
+            key_conv = key_conv,
+            value_conv = value_conv,
+        ));
+        c_decls.push_str(&format!(
+            "// Creates empty map\n\
+             struct CRustMap {new_func}(void);\n\
+             // Moves key and value to map\n\
+             void {insert_func}(struct CRustMap *map, {c_key} key, {c_value} value);\n",
+            new_func = new_func,
+            insert_func = insert_func,
+            c_key = key.base.name,
+            c_value = value.base.name,
+        ));
+        traits.push_str(&format!(
+            r#"    static CRustMap new_map() noexcept {{ return {new_func}(); }}
+    static void insert(CRustMap *map, {cpp_key} key, {cpp_value} value)
+    {{
+        {insert_func}(map, {key_conv}, {value_conv});
+    }}
+"#,
+            new_func = new_func,
+            insert_func = insert_func,
+            cpp_key = cpp_typename(key),
+            cpp_value = cpp_typename(value),
+            key_conv = cpp_convert(key, "key"),
+            value_conv = cpp_convert(value, "value"),
+        ));
+    }
+
+    for code in rust_code {
+        cpp_cfg.to_generate.borrow_mut().push(
+            syn::parse_str(&code)
+                .unwrap_or_else(|err| panic_on_syn_error("internal cpp map code", code, err)),
+        );
+    }
+
+    let mut map_f = FileWriteCache::new(&map_path);
+    let write_res = if cpp_cfg.output == CppOutput::C {
+        let includes: String = cpp_includes
+            .iter()
+            .map(|class| format!("#include \"c_{}.h\"\n", class))
+            .collect();
+        write!(
+            map_f,
+            r##"// Automaticaly generated by rust_swig
+#pragma once
+
+#include "rust_swig_c.h"
+{includes}
+#ifdef __cplusplus
+extern "C" {{
+#endif
+{c_decls}#ifdef __cplusplus
+}}
+#endif
+"##,
+            includes = includes,
+            c_decls = c_decls,
+        )
+    } else {
+        let includes: String = cpp_includes
+            .iter()
+            .map(|class| format!("#include \"{}.hpp\"\n", class))
+            .collect();
+        write!(
+            map_f,
+            r##"// Automaticaly generated by rust_swig
+#pragma once
+
+#include "rust_map.h"
+#include "rust_str.h"
+{includes}
+#ifdef __cplusplus
+extern "C" {{
+#endif
+{c_decls}#ifdef __cplusplus
+}}
+
+namespace {namespace_name} {{
+struct {map_type}Traits {{
+    using key_type = {key_type};
+    using mapped_type = {mapped_type};
+    using std_map_type = {std_map_type}<key_type, mapped_type>;
+
+{traits}}};
+
+using {map_type} = RustMap<{map_type}Traits>;
+}} // namespace {namespace_name}
+#endif
+"##,
+            includes = includes,
+            c_decls = c_decls,
+            namespace_name = cpp_cfg.namespace_name,
+            map_type = typename,
+            key_type = cpp_key_ty,
+            mapped_type = cpp_value_ty,
+            std_map_type = match kind {
+                MapKind::HashMap => "std::unordered_map",
+                MapKind::BTreeMap => "std::map",
+            },
+            traits = traits,
+        )
+    };
+    write_res.map_err(|err| {
+        DiagnosticError::new2(
+            arg_ty_span,
+            format!("write to {:?} failed: {}", map_path, err),
+        )
+    })?;
+    if cpp_cfg.output != CppOutput::Nothing {
+        map_f.update_file_if_necessary().map_err(|err| {
+            DiagnosticError::new2(
+                arg_ty_span,
+                format!("update of {:?} failed: {}", map_path, err),
+            )
+        })?;
+    }
+    Ok(())
+}
+
+pub(in crate::cpp) fn cpp_typename(fti: &CppForeignTypeInfo) -> &str {
+    match fti.cpp_converter {
+        Some(ref conv) => conv.typename.as_str(),
+        None => fti.base.name.as_str(),
+    }
+}
+
+pub(in crate::cpp) fn cpp_convert(fti: &CppForeignTypeInfo, var: &str) -> String {
+    match fti.cpp_converter {
+        Some(ref conv) => conv.converter.replace(FROM_VAR_TEMPLATE, var),
+        None => var.to_string(),
+    }
+}
+
 fn handle_result_type_as_return_type(
     conv_map: &mut TypeMap,
     cpp_cfg: &CppConfig,
@@ -1,8 +1,9 @@
 mod code_for_class;
+mod code_for_struct;
 mod cpp_code;
 mod map_type;
 
-use std::{fmt, io::Write};
+use std::{fmt, io::Write, iter};
 
 use log::{debug, trace};
 use petgraph::Direction;
@@ -12,25 +13,29 @@ use smol_str::SmolStr;
 use syn::{parse_quote, spanned::Spanned, Type};
 
 use crate::{
-    cpp::map_type::map_type,
-    error::{panic_on_syn_error, DiagnosticError, Result},
+    cpp::map_type::{calc_this_type_for_method, map_type},
+    error::{panic_on_syn_error, DiagnosticError, Result, SourceIdSpan},
     file_cache::FileWriteCache,
     source_registry::SourceId,
     typemap::{
         ast::{
-            fn_arg_type, parse_ty_with_given_span, parse_ty_with_given_span_checked,
-            DisplayToTokens, TypeName,
+            fn_arg_type, if_future_return_output_type, if_option_return_some_type,
+            parse_ty_with_given_span, parse_ty_with_given_span_checked, DisplayToTokens, TypeName,
         },
         ty::{ForeignType, RustType},
         unpack_unique_typename,
-        utils::rust_to_foreign_convert_method_inputs,
-        ForeignMethodSignature, ForeignTypeInfo, RustTypeIdx, FROM_VAR_TEMPLATE, TO_VAR_TEMPLATE,
+        utils::{
+            rust_enum_unknown_value_arm, rust_flags_from_bits, rust_flags_to_bits,
+            rust_to_foreign_convert_method_inputs,
+        },
+        ForeignMethodSignature, ForeignTypeInfo, RustTypeIdx, TypeConvEdge, FROM_VAR_TEMPLATE,
+        TO_VAR_TEMPLATE, TO_VAR_TYPE_TEMPLATE,
     },
     types::{
-        ForeignEnumInfo, ForeignInterface, ForeignerClassInfo, ForeignerMethod, MethodAccess,
-        MethodVariant,
+        ForeignEnumInfo, ForeignFlagsInfo, ForeignInterface, ForeignStructInfo, ForeignerClassInfo,
+        ForeignerMethod, MethodAccess, MethodVariant, UnknownEnumValue,
     },
-    CppConfig, LanguageGenerator, SourceCode, TypeMap,
+    CppConfig, CppOutput, CppPanicHandling, LanguageGenerator, SourceCode, TypeMap,
 };
 
 #[derive(Debug)]
@@ -40,8 +45,8 @@ struct CppConverter {
 }
 
 #[derive(Debug)]
-struct CppForeignTypeInfo {
-    base: ForeignTypeInfo,
+pub(crate) struct CppForeignTypeInfo {
+    pub(crate) base: ForeignTypeInfo,
     pub(in crate::cpp) cpp_converter: Option<CppConverter>,
 }
 
@@ -73,9 +78,27 @@ impl CppForeignTypeInfo {
         if let Some(intermediate) = rule.intermediate.as_ref() {
             base_rt = intermediate.intermediate_ty;
             let typename = ftype.name.typename.clone();
-            let converter = intermediate.conv_code.to_string();
+            let conv_code = intermediate.conv_code.clone();
             let inter_ft = convert_rt_to_ft(tmap, intermediate.intermediate_ty)?;
             base_ft_name = tmap[inter_ft].name.typename.clone();
+            let converter = if conv_code.is_statement() {
+                //code assigns result to variable, so wrap it into lambda
+                //to use it as expression
+                let out_ty = match direction {
+                    petgraph::Direction::Outgoing => &typename,
+                    petgraph::Direction::Incoming => &base_ft_name,
+                };
+                format!(
+                    "[&]() -> {out_ty} {{ {out_ty} swig_out; {code} return swig_out; }}()",
+                    out_ty = out_ty,
+                    code = conv_code
+                        .as_str()
+                        .trim()
+                        .replace(TO_VAR_TEMPLATE, "swig_out"),
+                )
+            } else {
+                conv_code.to_string()
+            };
             cpp_converter = Some(CppConverter {
                 typename,
                 converter,
@@ -100,9 +123,69 @@ impl AsRef<ForeignTypeInfo> for CppForeignTypeInfo {
     }
 }
 
-struct CppForeignMethodSignature {
-    output: CppForeignTypeInfo,
-    input: Vec<CppForeignTypeInfo>,
+pub(crate) struct CppForeignMethodSignature {
+    pub(crate) output: CppForeignTypeInfo,
+    pub(crate) input: Vec<CppForeignTypeInfo>,
+    pub(crate) future: Option<CppFutureInfo>,
+    /// If not empty C function has additional `char *swig_invalid_arg` argument
+    pub(crate) checked_enum_args: Vec<CppCheckedEnumArg>,
+    pub(crate) input_to_output: Option<CppInputToOutput>,
+}
+
+/// Argument with `$pin:input_to_output` rule of `foreign_typemap!`,
+/// C++ method creates it together with value of type `typename`,
+/// and returns that value instead of taking argument
+pub(crate) struct CppInputToOutput {
+    pub(crate) idx: usize,
+    pub(crate) typename: SmolStr,
+    /// assigns `{to_var}` and argument `{from_var}`
+    pub(crate) code: String,
+}
+
+/// Argument with `foreign_enum!` type (or `Option` of it) that has
+/// `#[unknown_value = "error"]`, or with `foreign_flags!` type. Rust code checks value
+/// before conversation, and in case of unknown value or unknown bits
+/// sets `*swig_invalid_arg` to `1` and returns immediately
+pub(crate) struct CppCheckedEnumArg {
+    pub(crate) idx: usize,
+    pub(crate) is_option: bool,
+    pub(crate) enum_name: SmolStr,
+    values: CheckedValues,
+}
+
+enum CheckedValues {
+    /// Values of all items of `foreign_enum!`
+    Enum(Vec<u32>),
+    /// Union of bits of all items of `foreign_flags!`
+    Flags(u32),
+}
+
+/// Method returns `Future<Output = T>`, in this case C function returns nothing,
+/// and accepts callback that called with `T` converted into `value`
+pub(crate) struct CppFutureInfo {
+    output_ty: RustType,
+    value: CppForeignTypeInfo,
+}
+
+impl CppForeignMethodSignature {
+    /// All foreign types used by C function
+    fn types(&self) -> impl Iterator<Item = &CppForeignTypeInfo> {
+        self.input
+            .iter()
+            .chain(iter::once(&self.output))
+            .chain(self.future.as_ref().map(|x| &x.value))
+    }
+
+    /// Names of enums from `checked_enum_args` for error message, like "A or B"
+    pub(crate) fn checked_enum_names(&self) -> String {
+        let mut names = Vec::<&str>::with_capacity(self.checked_enum_args.len());
+        for arg in &self.checked_enum_args {
+            if !names.contains(&arg.enum_name.as_str()) {
+                names.push(arg.enum_name.as_str());
+            }
+        }
+        names.join(" or ")
+    }
 }
 
 impl From<ForeignTypeInfo> for CppForeignTypeInfo {
@@ -135,6 +218,8 @@ struct MethodContext<'a> {
     decl_func_args: &'a str,
     args_names: &'a str,
     real_output_typename: &'a str,
+    panic_handling: Option<&'a CppPanicHandling>,
+    future_executor: &'a str,
 }
 
 impl LanguageGenerator for CppConfig {
@@ -284,14 +369,7 @@ May be you need to use `private constructor = empty;` syntax?",
         }
 
         let m_sigs = find_suitable_foreign_types_for_methods(conv_map, class, self)?;
-        let mut code_items = code_for_class::generate(
-            conv_map,
-            &self.output_dir,
-            &self.namespace_name,
-            self.separate_impl_headers,
-            class,
-            &m_sigs,
-        )?;
+        let mut code_items = code_for_class::generate(conv_map, self, class, &m_sigs)?;
         code_items.append(&mut self.to_generate.borrow_mut());
         Ok(code_items)
     }
@@ -302,6 +380,9 @@ May be you need to use `private constructor = empty;` syntax?",
         pointer_target_width: usize,
         enum_info: &ForeignEnumInfo,
     ) -> Result<Vec<TokenStream>> {
+        if enum_info.is_data_carrying() {
+            return code_for_struct::generate_for_data_enum(conv_map, self, enum_info);
+        }
         if (enum_info.items.len() as u64) >= u64::from(u32::max_value()) {
             return Err(DiagnosticError::new(
                 enum_info.src_id,
@@ -320,12 +401,42 @@ May be you need to use `private constructor = empty;` syntax?",
             enum_info.src_id,
         );
 
-        cpp_code::generate_code_for_enum(&self.output_dir, enum_info)
-            .map_err(|err| DiagnosticError::new(enum_info.src_id, enum_info.span(), err))?;
+        if self.output != CppOutput::Nothing {
+            cpp_code::generate_code_for_enum(&self.output_dir, self.output, enum_info)
+                .map_err(|err| DiagnosticError::new(enum_info.src_id, enum_info.span(), err))?;
+        }
         let code = generate_rust_code_for_enum(conv_map, pointer_target_width, enum_info)?;
         Ok(code)
     }
 
+    fn generate_struct(
+        &self,
+        conv_map: &mut TypeMap,
+        _: usize,
+        struct_info: &ForeignStructInfo,
+    ) -> Result<Vec<TokenStream>> {
+        code_for_struct::generate(conv_map, self, struct_info)
+    }
+
+    fn generate_flags(
+        &self,
+        conv_map: &mut TypeMap,
+        _: usize,
+        flags_info: &ForeignFlagsInfo,
+    ) -> Result<Vec<TokenStream>> {
+        if self.output != CppOutput::Nothing {
+            cpp_code::generate_code_for_flags(
+                &self.output_dir,
+                self.output,
+                &self.namespace_name,
+                flags_info,
+            )
+            .map_err(|err| DiagnosticError::new2(flags_info.src_id_span(), err))?;
+        }
+        generate_rust_code_for_flags(conv_map, flags_info);
+        Ok(vec![])
+    }
+
     fn generate_interface(
         &self,
         conv_map: &mut TypeMap,
@@ -333,13 +444,16 @@ May be you need to use `private constructor = empty;` syntax?",
         interface: &ForeignInterface,
     ) -> Result<Vec<TokenStream>> {
         let f_methods = find_suitable_ftypes_for_interace_methods(conv_map, interface, self)?;
-        cpp_code::generate_for_interface(
-            &self.output_dir,
-            &self.namespace_name,
-            interface,
-            &f_methods,
-        )
-        .map_err(|err| DiagnosticError::new(interface.src_id, interface.span(), err))?;
+        if self.output != CppOutput::Nothing {
+            cpp_code::generate_for_interface(
+                &self.output_dir,
+                &self.namespace_name,
+                self.output,
+                interface,
+                &f_methods,
+            )
+            .map_err(|err| DiagnosticError::new(interface.src_id, interface.span(), err))?;
+        }
 
         let items =
             rust_code_generate_interface(conv_map, pointer_target_width, interface, &f_methods)?;
@@ -383,7 +497,7 @@ May be you need to use `private constructor = empty;` syntax?",
     }
 }
 
-fn find_suitable_foreign_types_for_methods(
+pub(crate) fn find_suitable_foreign_types_for_methods(
     conv_map: &mut TypeMap,
     class: &ForeignerClassInfo,
     cpp_cfg: &CppConfig,
@@ -401,8 +515,35 @@ fn find_suitable_foreign_types_for_methods(
         assert!(method.fn_decl.inputs.len() >= skip_n);
         let mut input =
             Vec::<CppForeignTypeInfo>::with_capacity(method.fn_decl.inputs.len() - skip_n);
-        for arg in method.fn_decl.inputs.iter().skip(skip_n) {
+        let mut checked_enum_args = vec![];
+        let mut input_to_output = None;
+        for (idx, arg) in method.fn_decl.inputs.iter().skip(skip_n).enumerate() {
             let arg_rust_ty = conv_map.find_or_alloc_rust_type(fn_arg_type(arg), class.src_id);
+            if let Some(checked_arg) = checked_enum_arg(conv_map, &arg_rust_ty, idx) {
+                checked_enum_args.push(checked_arg);
+            }
+            let arg_span = (class.src_id, fn_arg_type(arg).span());
+            if let Some(arg) = input_to_output_arg(conv_map, &arg_rust_ty, idx, arg_span) {
+                let bad_method = if input_to_output.is_some() {
+                    Some("only one such argument allowed")
+                } else {
+                    match (method.variant, &method.fn_decl.output) {
+                        (MethodVariant::Constructor, _) => Some("constructor not supported"),
+                        (_, syn::ReturnType::Type(..)) => Some("method should return nothing"),
+                        (_, syn::ReturnType::Default) => None,
+                    }
+                };
+                if let Some(reason) = bad_method {
+                    return Err(DiagnosticError::new2(
+                        arg_span,
+                        format!(
+                            "type {} of argument is converted to output of method, {}",
+                            arg_rust_ty, reason
+                        ),
+                    ));
+                }
+                input_to_output = Some(arg);
+            }
             input.push(map_type(
                 conv_map,
                 cpp_cfg,
@@ -411,6 +552,7 @@ fn find_suitable_foreign_types_for_methods(
                 (class.src_id, fn_arg_type(arg).span()),
             )?);
         }
+        let mut future = None;
         let output: CppForeignTypeInfo = match method.variant {
             MethodVariant::Constructor => ForeignTypeInfo {
                 name: "".into(),
@@ -424,23 +566,163 @@ fn find_suitable_foreign_types_for_methods(
                 }
                 .into(),
                 syn::ReturnType::Type(_, ref rt) => {
-                    let ret_rust_ty = conv_map.find_or_alloc_rust_type(rt, class.src_id);
-                    map_type(
-                        conv_map,
-                        cpp_cfg,
-                        &ret_rust_ty,
-                        Direction::Outgoing,
-                        (class.src_id, rt.span()),
-                    )?
+                    if let Some(output_ty) = if_future_return_output_type(rt) {
+                        let output_ty = conv_map.find_or_alloc_rust_type(&output_ty, class.src_id);
+                        let value = map_type(
+                            conv_map,
+                            cpp_cfg,
+                            &output_ty,
+                            Direction::Outgoing,
+                            (class.src_id, rt.span()),
+                        )?;
+                        future = Some(CppFutureInfo { output_ty, value });
+                        ForeignTypeInfo {
+                            name: "void".into(),
+                            correspoding_rust_type: dummy_rust_ty.clone(),
+                        }
+                        .into()
+                    } else {
+                        let ret_rust_ty = conv_map.find_or_alloc_rust_type(rt, class.src_id);
+                        map_type(
+                            conv_map,
+                            cpp_cfg,
+                            &ret_rust_ty,
+                            Direction::Outgoing,
+                            (class.src_id, rt.span()),
+                        )?
+                    }
                 }
             },
         };
-        ret.push(CppForeignMethodSignature { output, input });
+        ret.push(CppForeignMethodSignature {
+            output,
+            input,
+            future,
+            checked_enum_args,
+            input_to_output,
+        });
     }
     Ok(ret)
 }
 
-fn c_func_name(class: &ForeignerClassInfo, method: &ForeignerMethod) -> String {
+fn input_to_output_arg(
+    conv_map: &mut TypeMap,
+    arg_ty: &RustType,
+    idx: usize,
+    arg_ty_span: SourceIdSpan,
+) -> Option<CppInputToOutput> {
+    let ftype = conv_map.map_through_conversation_to_foreign(
+        arg_ty,
+        Direction::Incoming,
+        arg_ty_span,
+        calc_this_type_for_method,
+    )?;
+    let ftype = &conv_map[ftype];
+    let intermediate = ftype.from_into_rust.as_ref()?.intermediate.as_ref()?;
+    if !intermediate.input_to_output {
+        return None;
+    }
+    Some(CppInputToOutput {
+        idx,
+        typename: ftype.name.typename.clone(),
+        code: intermediate.conv_code.to_string(),
+    })
+}
+
+fn checked_enum_arg(
+    conv_map: &TypeMap,
+    arg_ty: &RustType,
+    idx: usize,
+) -> Option<CppCheckedEnumArg> {
+    let (arg_ty, is_option) = match if_option_return_some_type(arg_ty) {
+        Some(opt_ty) => (conv_map.ty_to_rust_type_checked(&opt_ty)?, true),
+        None => (arg_ty.clone(), false),
+    };
+    if let Some(flags_info) = conv_map.is_this_exported_flags(&arg_ty) {
+        return Some(CppCheckedEnumArg {
+            idx,
+            is_option,
+            enum_name: flags_info.name.to_string().into(),
+            values: CheckedValues::Flags(flags_info.all_bits()),
+        });
+    }
+    let enum_info = conv_map.is_this_exported_enum(&arg_ty)?;
+    if enum_info.unknown_value != UnknownEnumValue::Error {
+        return None;
+    }
+    Some(CppCheckedEnumArg {
+        idx,
+        is_option,
+        enum_name: enum_info.name.to_string().into(),
+        values: CheckedValues::Enum(enum_info.items.iter().map(|x| x.value).collect()),
+    })
+}
+
+/// Rust code that checks arguments from `CppForeignMethodSignature::checked_enum_args`,
+/// must be placed before conversation of arguments
+pub(crate) fn rust_check_enum_args(f_method: &CppForeignMethodSignature) -> String {
+    let mut code = String::new();
+    for arg in &f_method.checked_enum_args {
+        let var = if arg.is_option {
+            format!("a_{}.val", arg.idx)
+        } else {
+            format!("a_{}", arg.idx)
+        };
+        let check = match arg.values {
+            CheckedValues::Enum(ref values) => format!(
+                r#"match {var} {{
+        {values} => (),
+        _ => {{
+            unsafe {{ *swig_invalid_arg = 1 }};
+            return unsafe {{ ::std::mem::zeroed() }};
+        }}
+    }}"#,
+                var = var,
+                values = values
+                    .iter()
+                    .map(|x| x.to_string())
+                    .collect::<Vec<_>>()
+                    .join(" | "),
+            ),
+            CheckedValues::Flags(all_bits) => format!(
+                r#"if ({var} & !{all_bits:#x}) != 0 {{
+        unsafe {{ *swig_invalid_arg = 1 }};
+        return unsafe {{ ::std::mem::zeroed() }};
+    }}"#,
+                var = var,
+                all_bits = all_bits,
+            ),
+        };
+        if arg.is_option {
+            code.push_str(&format!(
+                "    if a_{}.is_some != 0 {{\n    {}\n    }}\n",
+                arg.idx, check
+            ));
+        } else {
+            code.push_str(&format!("    {}\n", check));
+        }
+    }
+    code
+}
+
+/// C type of value of such Rust type returned from function, for example
+/// to find type of `ok` field of `Result` struct
+pub(crate) fn map_output_c_type(
+    conv_map: &mut TypeMap,
+    cpp_cfg: &CppConfig,
+    rust_ty: &RustType,
+    rust_ty_span: SourceIdSpan,
+) -> Result<CppForeignTypeInfo> {
+    map_type(
+        conv_map,
+        cpp_cfg,
+        rust_ty,
+        Direction::Outgoing,
+        rust_ty_span,
+    )
+}
+
+pub(crate) fn c_func_name(class: &ForeignerClassInfo, method: &ForeignerMethod) -> String {
     format!(
         "{access}{class_name}_{func}",
         access = match method.access {
@@ -468,13 +750,97 @@ fn rust_generate_args_with_types(
         )
         .map_err(fmt_write_err_map)?;
     }
+    if !f_method.checked_enum_args.is_empty() {
+        write!(&mut buf, "swig_invalid_arg: *mut ::std::os::raw::c_char, ")
+            .map_err(fmt_write_err_map)?;
+    }
+    if let Some(ref future) = f_method.future {
+        write!(
+            &mut buf,
+            "swig_opaque: *mut ::std::os::raw::c_void, swig_on_ready: {}, ",
+            future_callback_rust_type(future)
+        )
+        .map_err(fmt_write_err_map)?;
+    }
     Ok(buf)
 }
 
+/// Type of callback that accepts output of `Future`
+fn future_callback_rust_type(future: &CppFutureInfo) -> String {
+    if future.value.base.name == "void" {
+        "extern \"C\" fn(*mut ::std::os::raw::c_void)".into()
+    } else {
+        format!(
+            "extern \"C\" fn(*mut ::std::os::raw::c_void, {})",
+            unpack_unique_typename(&future.value.base.correspoding_rust_type.normalized_name)
+        )
+    }
+}
+
+/// Backends built on top of C API can not handle callback
+/// that gets output of `Future` yet
+pub(crate) fn check_no_future_methods(
+    class: &ForeignerClassInfo,
+    m_sigs: &[CppForeignMethodSignature],
+    lang: &str,
+) -> Result<()> {
+    for (method, f_method) in class.methods.iter().zip(m_sigs) {
+        if f_method.future.is_some() {
+            return Err(DiagnosticError::new(
+                class.src_id,
+                method.span(),
+                format!("{}: methods that return Future are not supported yet", lang),
+            ));
+        }
+    }
+    Ok(())
+}
+
 fn fmt_write_err_map(err: fmt::Error) -> String {
     format!("fmt write error: {}", err)
 }
 
+/// `foreign_flags` is passed as `u32`, unknown bits in arguments of methods
+/// are reported via `swig_invalid_arg` (see `CppCheckedEnumArg`), in other places,
+/// like return value of callback, they cause panic. Flags that are not exported
+/// are internal for Rust side, so they are dropped
+fn generate_rust_code_for_flags(conv_map: &mut TypeMap, flags_info: &ForeignFlagsInfo) {
+    let flags_ty = conv_map.find_or_alloc_rust_type(
+        &parse_ty_with_given_span_checked(&flags_info.rust_flags_name(), flags_info.span()),
+        flags_info.src_id,
+    );
+    let u32_ty = conv_map.ty_to_rust_type(&parse_type! { u32 });
+    let code = format!(
+        "    let mut {to_var}: {to_var_type} = {to_bits}.0;\n",
+        to_var = TO_VAR_TEMPLATE,
+        to_var_type = TO_VAR_TYPE_TEMPLATE,
+        to_bits = rust_flags_to_bits(flags_info, FROM_VAR_TEMPLATE),
+    );
+    conv_map.add_conversation_rule(
+        flags_ty.clone(),
+        u32_ty.clone(),
+        TypeConvEdge::new(code, None),
+    );
+    let code = format!(
+        r#"
+    let mut {to_var}: {to_var_type} = {{
+        let (flags, all_bits_known) = {from_bits};
+        if !all_bits_known {{
+            panic!("{flags_name}: unknown bits in 0x{{:x}}", {from_var});
+        }}
+        flags
+    }};
+"#,
+        to_var = TO_VAR_TEMPLATE,
+        to_var_type = TO_VAR_TYPE_TEMPLATE,
+        from_var = FROM_VAR_TEMPLATE,
+        from_bits = rust_flags_from_bits(flags_info, FROM_VAR_TEMPLATE),
+        flags_name = flags_info.name,
+    );
+    conv_map.add_conversation_rule(u32_ty, flags_ty, TypeConvEdge::new(code, None));
+    conv_map.register_exported_flags(flags_info);
+}
+
 fn generate_rust_code_for_enum(
     conv_map: &mut TypeMap,
     pointer_target_width: usize,
@@ -493,11 +859,11 @@ impl SwigFrom<u32> for {rust_enum_name} {{
 "#,
         rust_enum_name = rust_enum_name,
     );
-    for (i, item) in enum_info.items.iter().enumerate() {
+    for item in &enum_info.items {
         writeln!(
             &mut code,
             "{index} => {item_name},",
-            index = i,
+            index = item.value,
             item_name = DisplayToTokens(&item.rust_name)
         )
         .unwrap();
@@ -505,12 +871,12 @@ impl SwigFrom<u32> for {rust_enum_name} {{
     write!(
         &mut code,
         r#"
-        _ => panic!("{{}} not expected for {rust_enum_name}", x),
+        {unknown_value_arm}
         }}
     }}
 }}
 "#,
-        rust_enum_name = rust_enum_name,
+        unknown_value_arm = rust_enum_unknown_value_arm(enum_info, "x"),
     )
     .unwrap();
 
@@ -525,11 +891,11 @@ impl SwigFrom<Option<u32>> for Option<{rust_enum_name}> {{
         rust_enum_name = rust_enum_name,
     )
     .unwrap();
-    for (i, item) in enum_info.items.iter().enumerate() {
+    for item in &enum_info.items {
         writeln!(
             &mut code,
             "{index} => {item_name},",
-            index = i,
+            index = item.value,
             item_name = DisplayToTokens(&item.rust_name)
         )
         .unwrap();
@@ -537,12 +903,12 @@ impl SwigFrom<Option<u32>> for Option<{rust_enum_name}> {{
     write!(
         &mut code,
         r#"
-        _ => panic!("{{}} not expected for {rust_enum_name}", v),
+        {unknown_value_arm}
         }})
     }}
 }}
 "#,
-        rust_enum_name = rust_enum_name,
+        unknown_value_arm = rust_enum_unknown_value_arm(enum_info, "v"),
     )
     .unwrap();
 
@@ -554,13 +920,13 @@ impl SwigForeignEnum for {rust_enum_name} {{
 "#,
         rust_enum_name = rust_enum_name
     );
-    for (i, item) in enum_info.items.iter().enumerate() {
+    for item in &enum_info.items {
         write!(
             &mut trait_impl,
             r#"
             {item_name} => {index},
 "#,
-            index = i,
+            index = item.value,
             item_name = DisplayToTokens(&item.rust_name)
         )
         .unwrap();
@@ -608,13 +974,13 @@ impl SwigFrom<Option<{rust_enum_name}>> for Option<u32> {{
     )
     .unwrap();
 
-    for (i, item) in enum_info.items.iter().enumerate() {
+    for item in &enum_info.items {
         write!(
             &mut code,
             r#"
            {item_name} => {index},
 "#,
-            index = i,
+            index = item.value,
             item_name = DisplayToTokens(&item.rust_name)
         )
         .unwrap();
@@ -634,7 +1000,7 @@ impl SwigFrom<Option<{rust_enum_name}>> for Option<u32> {{
     Ok(vec![trait_impl.into_token_stream()])
 }
 
-fn find_suitable_ftypes_for_interace_methods(
+pub(crate) fn find_suitable_ftypes_for_interace_methods(
     conv_map: &mut TypeMap,
     interace: &ForeignInterface,
     cpp_cfg: &CppConfig,
@@ -673,7 +1039,13 @@ fn find_suitable_ftypes_for_interace_methods(
                 )?
             }
         };
-        f_methods.push(CppForeignMethodSignature { output, input });
+        f_methods.push(CppForeignMethodSignature {
+            output,
+            input,
+            future: None,
+            checked_enum_args: vec![],
+            input_to_output: None,
+        });
     }
     Ok(f_methods)
 }
@@ -902,7 +1274,7 @@ fn convert_rt_to_ft(tmap: &mut TypeMap, rt: RustTypeIdx) -> Result<ForeignType>
         self::map_type::calc_this_type_for_method,
     )
     .ok_or_else(|| {
-        DiagnosticError::new(
+        let err = DiagnosticError::new(
             rtype.src_id,
             rtype.ty.span(),
             format!(
@@ -910,6 +1282,7 @@ fn convert_rt_to_ft(tmap: &mut TypeMap, rt: RustTypeIdx) -> Result<ForeignType>
                  such rust type '{}' to foreign",
                 rtype
             ),
-        )
+        );
+        tmap.explain_missing_foreign_type(err, &rtype, Direction::Outgoing)
     })
 }
@@ -0,0 +1,142 @@
+#pragma once
+
+#include <stddef.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+struct CRustMap {
+    void *data;
+    uintptr_t len;
+};
+
+#ifdef __cplusplus
+} // extern "C"
+#endif
+
+#ifdef __cplusplus
+
+#include <map>
+#include <string>
+#include <type_traits>
+#include <unordered_map>
+#include <utility>
+
+namespace RUST_SWIG_USER_NAMESPACE {
+
+/// `HashMap` or `BTreeMap` owned by Rust,
+/// `Traits` is generated for each map type and describes
+/// types of keys and values and how to pass them to/from Rust
+template <typename Traits> class RustMap final : private CRustMap {
+public:
+    using key_type = typename Traits::key_type;
+    using mapped_type = typename Traits::mapped_type;
+
+    explicit RustMap(const CRustMap &o) noexcept
+    {
+        this->data = o.data;
+        this->len = o.len;
+    }
+    RustMap() noexcept
+        : RustMap(Traits::new_map())
+    {
+    }
+    RustMap(const RustMap &) = delete;
+    RustMap &operator=(const RustMap &) = delete;
+    RustMap(RustMap &&o) noexcept
+    {
+        this->data = o.data;
+        this->len = o.len;
+        reset(o);
+    }
+    RustMap &operator=(RustMap &&o) noexcept
+    {
+        free_mem();
+        this->data = o.data;
+        this->len = o.len;
+        reset(o);
+        return *this;
+    }
+    ~RustMap() noexcept { free_mem(); }
+    /// Number of key-value pairs
+    size_t size() const noexcept { return this->len; }
+    bool empty() const noexcept { return this->len == 0; }
+    /// Add key-value pair, if key already exists, value
+    /// will be replaced after passing map to Rust,
+    /// `std::string` and `std::string_view` are passed as C strings
+    template <typename K, typename V> void insert(K &&key, V &&value)
+    {
+        Traits::insert(static_cast<CRustMap *>(this), c_arg(owned_arg(std::forward<K>(key))),
+                       c_arg(owned_arg(std::forward<V>(value))));
+    }
+    /// Moves all key-value pairs to `std::unordered_map` for `HashMap`
+    /// and to `std::map` for `BTreeMap`, this map becomes empty
+    typename Traits::std_map_type to_std() { return move_to<typename Traits::std_map_type>(); }
+    /// Moves all key-value pairs to `std::unordered_map`, this map becomes empty
+    std::unordered_map<key_type, mapped_type> to_std_unordered_map()
+    {
+        return move_to<std::unordered_map<key_type, mapped_type>>();
+    }
+    /// Moves all key-value pairs to `std::map`, this map becomes empty
+    std::map<key_type, mapped_type> to_std_map() { return move_to<std::map<key_type, mapped_type>>(); }
+    /// Creates map from `std::map`, `std::unordered_map` or similar container,
+    /// keys should be convertible to `Traits::key_type`
+    /// and values to `Traits::mapped_type`
+    template <typename M> static RustMap from_std(M m)
+    {
+        RustMap ret;
+        for (auto &&kv : m) {
+            ret.insert(kv.first, std::move(kv.second));
+        }
+        return ret;
+    }
+    CRustMap release() noexcept
+    {
+        CRustMap ret{ this->data, this->len };
+        reset(*this);
+        return ret;
+    }
+
+private:
+    template <typename T>
+    using if_not_std_string =
+        typename std::enable_if<!std::is_same<typename std::decay<T>::type, std::string>::value,
+                                T &&>::type;
+    template <typename T> static if_not_std_string<T> c_arg(T &&x) noexcept
+    {
+        return std::forward<T>(x);
+    }
+    static const char *c_arg(const std::string &s) noexcept { return s.c_str(); }
+    template <typename T> static T &&owned_arg(T &&x) noexcept { return std::forward<T>(x); }
+#if __cplusplus >= 201703L
+    /// `std::string_view` is not zero terminated, so copy it,
+    /// temporary lives until the end of `insert` call
+    static std::string owned_arg(std::string_view s) { return std::string{ s }; }
+#endif
+    template <typename M> M move_to()
+    {
+        M ret;
+        auto move_to_ret = [&ret](key_type key, mapped_type value) {
+            ret.emplace(std::move(key), std::move(value));
+        };
+        while (Traits::pop(static_cast<CRustMap *>(this), move_to_ret)) {
+        }
+        return ret;
+    }
+    void free_mem() noexcept
+    {
+        if (this->data != nullptr) {
+            Traits::free(*this);
+            reset(*this);
+        }
+    }
+    static void reset(RustMap &o) noexcept
+    {
+        o.data = nullptr;
+        o.len = 0;
+    }
+};
+} // namespace RUST_SWIG_USER_NAMESPACE
+#endif
@@ -100,6 +100,17 @@ public:
 #if QT_VERSION >= 0x050000 && defined(QSTRING_H)
     QString to_qstring() const { return QString::fromUtf8(data, len); }
 #endif
+    friend bool operator==(const RustString &a, const RustString &b) noexcept
+    {
+        return a.len == b.len && std::char_traits<char>::compare(a.data, b.data, a.len) == 0;
+    }
+    friend bool operator!=(const RustString &a, const RustString &b) noexcept { return !(a == b); }
+    /// Byte-wise comparison, like `Ord` for `String` in Rust
+    friend bool operator<(const RustString &a, const RustString &b) noexcept
+    {
+        const int res = std::char_traits<char>::compare(a.data, b.data, a.len < b.len ? a.len : b.len);
+        return res < 0 || (res == 0 && a.len < b.len);
+    }
 private:
     void free_mem() noexcept
     {
@@ -117,4 +128,17 @@ private:
 };
 
 } // namespace RUST_SWIG_USER_NAMESPACE
+
+namespace std {
+template <> struct hash<RUST_SWIG_USER_NAMESPACE::RustString> {
+    size_t operator()(const RUST_SWIG_USER_NAMESPACE::RustString &s) const
+    {
+#if __cplusplus >= 201703L
+        return hash<string_view>{}(s.to_string_view());
+#else
+        return hash<string>{}(s.to_std_string());
+#endif
+    }
+};
+} // namespace std
 #endif //__cplusplus
@@ -0,0 +1,249 @@
+// Automaticaly generated by rust_swig
+//
+// Types used by generated C API.
+//
+// Ownership rules:
+// - RustStrView, CRustSlice* and CRustObjectSlice are borrowed views into
+//   memory owned by Rust, they are valid only until the next call that
+//   modifies or frees the owner, and should not be freed.
+// - CRustString, CRustVec*, CRustForeignVec and CRustOptionString returned
+//   from Rust are owned by caller, and should be freed with the
+//   corresponding `_free` function exactly once, or passed back to Rust
+//   (in that case Rust takes ownership).
+// - CRustForeignVec is freed with `RustForeignVec<Class>_free`,
+//   declared in `RustForeignVec<Class>.h`.
+// - CRustMap is owned by caller and freed with `<Map>_free`,
+//   declared in `<Map>.h`, for example `RustHashMapStringI32.h`.
+// - Pointers to classes returned from constructors or by value
+//   should be freed with `<Class>_delete`.
+// - Fields of CResult* that hold CRustString, CRustVec*, CRustForeignVec
+//   or pointer to class should be freed in the same way as these types.
+#pragma once
+
+#include <stddef.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+struct RustStrView {
+    const char *data;
+    uintptr_t len;
+};
+
+struct CRustString {
+    const char *data;
+    uintptr_t len;
+    uintptr_t capacity;
+};
+
+void crust_string_free(struct CRustString str);
+struct CRustString crust_string_clone(struct CRustString str);
+
+struct CRustVecU8 {
+    const uint8_t *data;
+    uintptr_t len;
+    uintptr_t capacity;
+};
+
+void CRustVecU8_free(struct CRustVecU8 vec);
+
+struct CRustVecI32 {
+    const int32_t *data;
+    uintptr_t len;
+    uintptr_t capacity;
+};
+
+void CRustVecI32_free(struct CRustVecI32 vec);
+
+struct CRustVecU32 {
+    const uint32_t *data;
+    uintptr_t len;
+    uintptr_t capacity;
+};
+
+void CRustVecU32_free(struct CRustVecU32 vec);
+
+struct CRustVecUsize {
+    const uintptr_t *data;
+    uintptr_t len;
+    uintptr_t capacity;
+};
+
+void CRustVecUsize_free(struct CRustVecUsize vec);
+
+struct CRustVecF32 {
+    const float *data;
+    uintptr_t len;
+    uintptr_t capacity;
+};
+
+void CRustVecF32_free(struct CRustVecF32 vec);
+
+struct CRustVecF64 {
+    const double *data;
+    uintptr_t len;
+    uintptr_t capacity;
+};
+
+void CRustVecF64_free(struct CRustVecF64 vec);
+
+struct CRustForeignVec {
+    const void *data;
+    uintptr_t len;
+    uintptr_t capacity;
+    uintptr_t step;
+};
+
+struct CRustMap {
+    void *data;
+    uintptr_t len;
+};
+
+struct CRustSliceU8 {
+    const uint8_t *data;
+    uintptr_t len;
+};
+
+struct CRustSliceI32 {
+    const int32_t *data;
+    uintptr_t len;
+};
+
+struct CRustSliceU32 {
+    const uint32_t *data;
+    uintptr_t len;
+};
+
+struct CRustSliceUsize {
+    const uintptr_t *data;
+    uintptr_t len;
+};
+
+struct CRustObjectSlice {
+    const void *data;
+    uintptr_t len;
+    uintptr_t step;
+};
+
+// Options of plain values own nothing, `val` is valid only if `is_some` != 0
+struct CRustOptionBool {
+    uint8_t val;
+    uint8_t is_some;
+};
+
+struct CRustOptionF32 {
+    float val;
+    uint8_t is_some;
+};
+
+struct CRustOptionF64 {
+    double val;
+    uint8_t is_some;
+};
+
+struct CRustOptionI32 {
+    int32_t val;
+    uint8_t is_some;
+};
+
+struct CRustOptionU32 {
+    uint32_t val;
+    uint8_t is_some;
+};
+
+struct CRustOptionI64 {
+    int64_t val;
+    uint8_t is_some;
+};
+
+struct CRustOptionU64 {
+    uint64_t val;
+    uint8_t is_some;
+};
+
+struct CRustOptionUSize {
+    uintptr_t val;
+    uint8_t is_some;
+};
+
+struct CRustOptionStr {
+    struct RustStrView val;
+    uint8_t is_some;
+};
+
+struct CRustOptionString {
+    struct CRustString val;
+    uint8_t is_some;
+};
+
+// Frees `val` if `is_some` != 0
+void CRustOptionString_free(struct CRustOptionString opt);
+
+// If `is_ok` != 0 `data.ok` is valid, otherwise `data.err`
+struct CResultObjectString {
+    uint8_t is_ok;
+    union {
+        void *ok;
+        struct CRustString err;
+    } data;
+};
+
+struct CResultCRustForeignVecString {
+    uint8_t is_ok;
+    union {
+        struct CRustForeignVec ok;
+        struct CRustString err;
+    } data;
+};
+
+struct CResultObjectObject {
+    uint8_t is_ok;
+    union {
+        void *ok;
+        void *err;
+    } data;
+};
+
+struct CResultVecObjectObject {
+    uint8_t is_ok;
+    union {
+        struct CRustForeignVec ok;
+        void *err;
+    } data;
+};
+
+struct CResultCRustVecU8Object {
+    union {
+        struct CRustVecU8 ok;
+        void *err;
+    } data;
+    uint8_t is_ok;
+};
+
+struct CResultObjectEnum {
+    union {
+        void *ok;
+        uint32_t err;
+    } data;
+    uint8_t is_ok;
+};
+
+struct CResultI64Object {
+    union {
+        int64_t ok;
+        void *err;
+    } data;
+    uint8_t is_ok;
+};
+
+// Both objects owned by caller
+struct CRustObjectPair {
+    void *first;
+    void *second;
+};
+
+#ifdef __cplusplus
+}
+#endif
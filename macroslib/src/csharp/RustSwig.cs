@@ -0,0 +1,708 @@
+// Automaticaly generated by rust_swig
+using System;
+using System.Runtime.InteropServices;
+
+namespace RUST_SWIG_USER_NAMESPACE
+{
+    /// Borrowed UTF-8 string, owned by Rust side
+    [StructLayout(LayoutKind.Sequential)]
+    internal struct RustStrView
+    {
+        internal IntPtr data;
+        internal UIntPtr len;
+
+        public override string ToString()
+        {
+            return Marshal.PtrToStringUTF8(data, checked((int)len.ToUInt64()));
+        }
+    }
+
+    /// UTF-8 string allocated by Rust side
+    [StructLayout(LayoutKind.Sequential)]
+    internal struct CRustString
+    {
+        internal IntPtr data;
+        internal UIntPtr len;
+        internal UIntPtr capacity;
+
+        /// Copy content to managed string and free memory
+        internal string Consume()
+        {
+            string s = Marshal.PtrToStringUTF8(data, checked((int)len.ToUInt64()));
+            crust_string_free(this);
+            return s;
+        }
+
+        [DllImport("RUST_SWIG_NATIVE_LIB", CallingConvention = CallingConvention.Cdecl)]
+        private static extern void crust_string_free(CRustString s);
+    }
+
+    /// Error returned by Rust method as `Err` of `Result`
+    public class RustException : Exception
+    {
+        public RustException(string message) : base(message)
+        {
+        }
+
+        public RustException(object error) : base(error.ToString())
+        {
+            Error = error;
+        }
+
+        /// Error object or enum value, null if Rust returned error as string
+        public object Error { get; }
+    }
+
+    /// Copy content of Rust vectors and slices to managed arrays
+    internal static class RustSwigCopy
+    {
+        private static int Len(UIntPtr len)
+        {
+            return checked((int)len.ToUInt64());
+        }
+
+        internal static byte[] Bytes(IntPtr data, UIntPtr len)
+        {
+            var ret = new byte[Len(len)];
+            if (ret.Length != 0)
+            {
+                Marshal.Copy(data, ret, 0, ret.Length);
+            }
+            return ret;
+        }
+
+        internal static int[] Ints(IntPtr data, UIntPtr len)
+        {
+            var ret = new int[Len(len)];
+            if (ret.Length != 0)
+            {
+                Marshal.Copy(data, ret, 0, ret.Length);
+            }
+            return ret;
+        }
+
+        internal static uint[] UInts(IntPtr data, UIntPtr len)
+        {
+            int[] tmp = Ints(data, len);
+            var ret = new uint[tmp.Length];
+            Buffer.BlockCopy(tmp, 0, ret, 0, tmp.Length * sizeof(int));
+            return ret;
+        }
+
+        internal static UIntPtr[] UIntPtrs(IntPtr data, UIntPtr len)
+        {
+            var ret = new UIntPtr[Len(len)];
+            for (int i = 0; i < ret.Length; ++i)
+            {
+                ret[i] = IntPtr.Size == 8
+                    ? new UIntPtr(unchecked((ulong)Marshal.ReadInt64(data, i * 8)))
+                    : new UIntPtr(unchecked((uint)Marshal.ReadInt32(data, i * 4)));
+            }
+            return ret;
+        }
+
+        internal static float[] Floats(IntPtr data, UIntPtr len)
+        {
+            var ret = new float[Len(len)];
+            if (ret.Length != 0)
+            {
+                Marshal.Copy(data, ret, 0, ret.Length);
+            }
+            return ret;
+        }
+
+        internal static double[] Doubles(IntPtr data, UIntPtr len)
+        {
+            var ret = new double[Len(len)];
+            if (ret.Length != 0)
+            {
+                Marshal.Copy(data, ret, 0, ret.Length);
+            }
+            return ret;
+        }
+    }
+
+    /// `Vec` allocated by Rust side
+    [StructLayout(LayoutKind.Sequential)]
+    internal struct CRustVecU8
+    {
+        internal IntPtr data;
+        internal UIntPtr len;
+        internal UIntPtr capacity;
+
+        /// Copy content to managed array and free memory
+        internal byte[] Consume()
+        {
+            byte[] ret = RustSwigCopy.Bytes(data, len);
+            CRustVecU8_free(this);
+            return ret;
+        }
+
+        [DllImport("RUST_SWIG_NATIVE_LIB", CallingConvention = CallingConvention.Cdecl)]
+        private static extern void CRustVecU8_free(CRustVecU8 v);
+    }
+
+    /// `Vec` allocated by Rust side
+    [StructLayout(LayoutKind.Sequential)]
+    internal struct CRustVecI32
+    {
+        internal IntPtr data;
+        internal UIntPtr len;
+        internal UIntPtr capacity;
+
+        /// Copy content to managed array and free memory
+        internal int[] Consume()
+        {
+            int[] ret = RustSwigCopy.Ints(data, len);
+            CRustVecI32_free(this);
+            return ret;
+        }
+
+        [DllImport("RUST_SWIG_NATIVE_LIB", CallingConvention = CallingConvention.Cdecl)]
+        private static extern void CRustVecI32_free(CRustVecI32 v);
+    }
+
+    /// `Vec` allocated by Rust side
+    [StructLayout(LayoutKind.Sequential)]
+    internal struct CRustVecU32
+    {
+        internal IntPtr data;
+        internal UIntPtr len;
+        internal UIntPtr capacity;
+
+        /// Copy content to managed array and free memory
+        internal uint[] Consume()
+        {
+            uint[] ret = RustSwigCopy.UInts(data, len);
+            CRustVecU32_free(this);
+            return ret;
+        }
+
+        [DllImport("RUST_SWIG_NATIVE_LIB", CallingConvention = CallingConvention.Cdecl)]
+        private static extern void CRustVecU32_free(CRustVecU32 v);
+    }
+
+    /// `Vec` allocated by Rust side
+    [StructLayout(LayoutKind.Sequential)]
+    internal struct CRustVecUsize
+    {
+        internal IntPtr data;
+        internal UIntPtr len;
+        internal UIntPtr capacity;
+
+        /// Copy content to managed array and free memory
+        internal UIntPtr[] Consume()
+        {
+            UIntPtr[] ret = RustSwigCopy.UIntPtrs(data, len);
+            CRustVecUsize_free(this);
+            return ret;
+        }
+
+        [DllImport("RUST_SWIG_NATIVE_LIB", CallingConvention = CallingConvention.Cdecl)]
+        private static extern void CRustVecUsize_free(CRustVecUsize v);
+    }
+
+    /// `Vec` allocated by Rust side
+    [StructLayout(LayoutKind.Sequential)]
+    internal struct CRustVecF32
+    {
+        internal IntPtr data;
+        internal UIntPtr len;
+        internal UIntPtr capacity;
+
+        /// Copy content to managed array and free memory
+        internal float[] Consume()
+        {
+            float[] ret = RustSwigCopy.Floats(data, len);
+            CRustVecF32_free(this);
+            return ret;
+        }
+
+        [DllImport("RUST_SWIG_NATIVE_LIB", CallingConvention = CallingConvention.Cdecl)]
+        private static extern void CRustVecF32_free(CRustVecF32 v);
+    }
+
+    /// `Vec` allocated by Rust side
+    [StructLayout(LayoutKind.Sequential)]
+    internal struct CRustVecF64
+    {
+        internal IntPtr data;
+        internal UIntPtr len;
+        internal UIntPtr capacity;
+
+        /// Copy content to managed array and free memory
+        internal double[] Consume()
+        {
+            double[] ret = RustSwigCopy.Doubles(data, len);
+            CRustVecF64_free(this);
+            return ret;
+        }
+
+        [DllImport("RUST_SWIG_NATIVE_LIB", CallingConvention = CallingConvention.Cdecl)]
+        private static extern void CRustVecF64_free(CRustVecF64 v);
+    }
+
+    /// Slice borrowed from Rust side or from pinned managed array
+    [StructLayout(LayoutKind.Sequential)]
+    internal struct CRustSliceU8
+    {
+        internal IntPtr data;
+        internal UIntPtr len;
+
+        internal byte[] ToArray()
+        {
+            return RustSwigCopy.Bytes(data, len);
+        }
+    }
+
+    /// Slice borrowed from Rust side or from pinned managed array
+    [StructLayout(LayoutKind.Sequential)]
+    internal struct CRustSliceI32
+    {
+        internal IntPtr data;
+        internal UIntPtr len;
+
+        internal int[] ToArray()
+        {
+            return RustSwigCopy.Ints(data, len);
+        }
+
+        /// Pin array, so Rust side can borrow it, `pins` should be freed after call
+        internal static CRustSliceI32 Pin(int[] a, System.Collections.Generic.List<GCHandle> pins)
+        {
+            GCHandle h = GCHandle.Alloc(a, GCHandleType.Pinned);
+            pins.Add(h);
+            return new CRustSliceI32
+            {
+                data = h.AddrOfPinnedObject(),
+                len = new UIntPtr((uint)a.Length),
+            };
+        }
+    }
+
+    /// Slice borrowed from Rust side or from pinned managed array
+    [StructLayout(LayoutKind.Sequential)]
+    internal struct CRustSliceU32
+    {
+        internal IntPtr data;
+        internal UIntPtr len;
+
+        internal uint[] ToArray()
+        {
+            return RustSwigCopy.UInts(data, len);
+        }
+    }
+
+    /// Slice borrowed from Rust side or from pinned managed array
+    [StructLayout(LayoutKind.Sequential)]
+    internal struct CRustSliceUsize
+    {
+        internal IntPtr data;
+        internal UIntPtr len;
+
+        internal UIntPtr[] ToArray()
+        {
+            return RustSwigCopy.UIntPtrs(data, len);
+        }
+    }
+
+    /// `Vec` of foreign class objects, elements are accessed
+    /// via functions generated for concrete class
+    [StructLayout(LayoutKind.Sequential)]
+    internal struct CRustForeignVec
+    {
+        internal IntPtr data;
+        internal UIntPtr len;
+        internal UIntPtr capacity;
+        internal UIntPtr step;
+    }
+
+    /// Slice of foreign class objects borrowed from Rust side
+    [StructLayout(LayoutKind.Sequential)]
+    internal struct CRustObjectSlice
+    {
+        internal IntPtr data;
+        internal UIntPtr len;
+        internal UIntPtr step;
+
+        internal IntPtr ElementAt(int i)
+        {
+            return IntPtr.Add(data, checked(i * (int)step.ToUInt64()));
+        }
+    }
+
+    [StructLayout(LayoutKind.Sequential)]
+    internal struct CRustOptionBool
+    {
+        internal byte val;
+        internal byte is_some;
+
+        internal static CRustOptionBool FromNullable(bool? x)
+        {
+            return new CRustOptionBool
+            {
+                val = x.GetValueOrDefault() ? (byte)1 : (byte)0,
+                is_some = x.HasValue ? (byte)1 : (byte)0,
+            };
+        }
+
+        internal bool? ToNullable()
+        {
+            return is_some != 0 ? val != 0 : (bool?)null;
+        }
+    }
+
+    [StructLayout(LayoutKind.Sequential)]
+    internal struct CRustOptionF32
+    {
+        internal float val;
+        internal byte is_some;
+
+        internal static CRustOptionF32 FromNullable(float? x)
+        {
+            return new CRustOptionF32
+            {
+                val = x.GetValueOrDefault(),
+                is_some = x.HasValue ? (byte)1 : (byte)0,
+            };
+        }
+
+        internal float? ToNullable()
+        {
+            return is_some != 0 ? val : (float?)null;
+        }
+    }
+
+    [StructLayout(LayoutKind.Sequential)]
+    internal struct CRustOptionF64
+    {
+        internal double val;
+        internal byte is_some;
+
+        internal static CRustOptionF64 FromNullable(double? x)
+        {
+            return new CRustOptionF64
+            {
+                val = x.GetValueOrDefault(),
+                is_some = x.HasValue ? (byte)1 : (byte)0,
+            };
+        }
+
+        internal double? ToNullable()
+        {
+            return is_some != 0 ? val : (double?)null;
+        }
+    }
+
+    [StructLayout(LayoutKind.Sequential)]
+    internal struct CRustOptionI32
+    {
+        internal int val;
+        internal byte is_some;
+
+        internal static CRustOptionI32 FromNullable(int? x)
+        {
+            return new CRustOptionI32
+            {
+                val = x.GetValueOrDefault(),
+                is_some = x.HasValue ? (byte)1 : (byte)0,
+            };
+        }
+
+        internal int? ToNullable()
+        {
+            return is_some != 0 ? val : (int?)null;
+        }
+    }
+
+    [StructLayout(LayoutKind.Sequential)]
+    internal struct CRustOptionU32
+    {
+        internal uint val;
+        internal byte is_some;
+
+        internal static CRustOptionU32 FromNullable(uint? x)
+        {
+            return new CRustOptionU32
+            {
+                val = x.GetValueOrDefault(),
+                is_some = x.HasValue ? (byte)1 : (byte)0,
+            };
+        }
+
+        internal uint? ToNullable()
+        {
+            return is_some != 0 ? val : (uint?)null;
+        }
+    }
+
+    [StructLayout(LayoutKind.Sequential)]
+    internal struct CRustOptionI64
+    {
+        internal long val;
+        internal byte is_some;
+
+        internal static CRustOptionI64 FromNullable(long? x)
+        {
+            return new CRustOptionI64
+            {
+                val = x.GetValueOrDefault(),
+                is_some = x.HasValue ? (byte)1 : (byte)0,
+            };
+        }
+
+        internal long? ToNullable()
+        {
+            return is_some != 0 ? val : (long?)null;
+        }
+    }
+
+    [StructLayout(LayoutKind.Sequential)]
+    internal struct CRustOptionU64
+    {
+        internal ulong val;
+        internal byte is_some;
+
+        internal static CRustOptionU64 FromNullable(ulong? x)
+        {
+            return new CRustOptionU64
+            {
+                val = x.GetValueOrDefault(),
+                is_some = x.HasValue ? (byte)1 : (byte)0,
+            };
+        }
+
+        internal ulong? ToNullable()
+        {
+            return is_some != 0 ? val : (ulong?)null;
+        }
+    }
+
+    [StructLayout(LayoutKind.Sequential)]
+    internal struct CRustOptionUSize
+    {
+        internal UIntPtr val;
+        internal byte is_some;
+
+        internal static CRustOptionUSize FromNullable(UIntPtr? x)
+        {
+            return new CRustOptionUSize
+            {
+                val = x.GetValueOrDefault(),
+                is_some = x.HasValue ? (byte)1 : (byte)0,
+            };
+        }
+
+        internal UIntPtr? ToNullable()
+        {
+            return is_some != 0 ? val : (UIntPtr?)null;
+        }
+    }
+
+    [StructLayout(LayoutKind.Sequential)]
+    internal struct CRustOptionStr
+    {
+        internal RustStrView val;
+        internal byte is_some;
+
+        internal string ToNullableString()
+        {
+            return is_some != 0 ? val.ToString() : null;
+        }
+    }
+
+    [StructLayout(LayoutKind.Sequential)]
+    internal struct CRustOptionString
+    {
+        internal CRustString val;
+        internal byte is_some;
+
+        /// Copy content to managed string and free memory
+        internal string Consume()
+        {
+            return is_some != 0 ? val.Consume() : null;
+        }
+    }
+
+    [StructLayout(LayoutKind.Explicit)]
+    internal struct CResultObjectStringUnion
+    {
+        [FieldOffset(0)]
+        internal IntPtr ok;
+        [FieldOffset(0)]
+        internal CRustString err;
+    }
+
+    [StructLayout(LayoutKind.Sequential)]
+    internal struct CResultObjectString
+    {
+        internal byte is_ok;
+        internal CResultObjectStringUnion data;
+
+        /// Return `Ok` value or throw `RustException` with `Err` value
+        internal IntPtr Unwrap()
+        {
+            if (is_ok == 0)
+            {
+                throw new RustException(data.err.Consume());
+            }
+            return data.ok;
+        }
+    }
+
+    [StructLayout(LayoutKind.Explicit)]
+    internal struct CResultObjectObjectUnion
+    {
+        [FieldOffset(0)]
+        internal IntPtr ok;
+        [FieldOffset(0)]
+        internal IntPtr err;
+    }
+
+    [StructLayout(LayoutKind.Sequential)]
+    internal struct CResultObjectObject
+    {
+        internal byte is_ok;
+        internal CResultObjectObjectUnion data;
+
+        /// Return `Ok` value or throw `RustException` with `Err` value
+        internal IntPtr Unwrap(Func<IntPtr, object> err)
+        {
+            if (is_ok == 0)
+            {
+                throw new RustException(err(data.err));
+            }
+            return data.ok;
+        }
+    }
+
+    [StructLayout(LayoutKind.Explicit)]
+    internal struct CResultObjectEnumUnion
+    {
+        [FieldOffset(0)]
+        internal IntPtr ok;
+        [FieldOffset(0)]
+        internal uint err;
+    }
+
+    [StructLayout(LayoutKind.Sequential)]
+    internal struct CResultObjectEnum
+    {
+        internal CResultObjectEnumUnion data;
+        internal byte is_ok;
+
+        /// Return `Ok` value or throw `RustException` with `Err` value
+        internal IntPtr Unwrap(Func<uint, object> err)
+        {
+            if (is_ok == 0)
+            {
+                throw new RustException(err(data.err));
+            }
+            return data.ok;
+        }
+    }
+
+    [StructLayout(LayoutKind.Explicit)]
+    internal struct CResultI64ObjectUnion
+    {
+        [FieldOffset(0)]
+        internal long ok;
+        [FieldOffset(0)]
+        internal IntPtr err;
+    }
+
+    [StructLayout(LayoutKind.Sequential)]
+    internal struct CResultI64Object
+    {
+        internal CResultI64ObjectUnion data;
+        internal byte is_ok;
+
+        /// Return `Ok` value or throw `RustException` with `Err` value
+        internal long Unwrap(Func<IntPtr, object> err)
+        {
+            if (is_ok == 0)
+            {
+                throw new RustException(err(data.err));
+            }
+            return data.ok;
+        }
+    }
+
+    [StructLayout(LayoutKind.Explicit)]
+    internal struct CResultCRustForeignVecStringUnion
+    {
+        [FieldOffset(0)]
+        internal CRustForeignVec ok;
+        [FieldOffset(0)]
+        internal CRustString err;
+    }
+
+    [StructLayout(LayoutKind.Sequential)]
+    internal struct CResultCRustForeignVecString
+    {
+        internal byte is_ok;
+        internal CResultCRustForeignVecStringUnion data;
+
+        /// Return `Ok` value or throw `RustException` with `Err` value
+        internal CRustForeignVec Unwrap()
+        {
+            if (is_ok == 0)
+            {
+                throw new RustException(data.err.Consume());
+            }
+            return data.ok;
+        }
+    }
+
+    [StructLayout(LayoutKind.Explicit)]
+    internal struct CResultVecObjectObjectUnion
+    {
+        [FieldOffset(0)]
+        internal CRustForeignVec ok;
+        [FieldOffset(0)]
+        internal IntPtr err;
+    }
+
+    [StructLayout(LayoutKind.Sequential)]
+    internal struct CResultVecObjectObject
+    {
+        internal byte is_ok;
+        internal CResultVecObjectObjectUnion data;
+
+        /// Return `Ok` value or throw `RustException` with `Err` value
+        internal CRustForeignVec Unwrap(Func<IntPtr, object> err)
+        {
+            if (is_ok == 0)
+            {
+                throw new RustException(err(data.err));
+            }
+            return data.ok;
+        }
+    }
+
+    [StructLayout(LayoutKind.Explicit)]
+    internal struct CResultCRustVecU8ObjectUnion
+    {
+        [FieldOffset(0)]
+        internal CRustVecU8 ok;
+        [FieldOffset(0)]
+        internal IntPtr err;
+    }
+
+    [StructLayout(LayoutKind.Sequential)]
+    internal struct CResultCRustVecU8Object
+    {
+        internal CResultCRustVecU8ObjectUnion data;
+        internal byte is_ok;
+
+        /// Return `Ok` value or throw `RustException` with `Err` value
+        internal CRustVecU8 Unwrap(Func<IntPtr, object> err)
+        {
+            if (is_ok == 0)
+            {
+                throw new RustException(err(data.err));
+            }
+            return data.ok;
+        }
+    }
+}
@@ -0,0 +1,474 @@
+use petgraph::Direction;
+
+use syn::Type;
+
+use crate::{
+    cpp::CppForeignTypeInfo,
+    error::{DiagnosticError, Result, SourceIdSpan},
+    source_registry::SourceId,
+    typemap::{
+        ast::{
+            if_option_return_some_type, if_result_return_ok_err_types,
+            if_type_slice_return_elem_type, if_vec_return_elem_type,
+        },
+        ty::RustType,
+        FROM_VAR_TEMPLATE,
+    },
+    TypeMap,
+};
+
+/// C structs for `Option` of primitive types and C# types of their values
+const OPTION_STRUCTS: &[(&str, &str)] = &[
+    ("CRustOptionBool", "bool"),
+    ("CRustOptionF32", "float"),
+    ("CRustOptionF64", "double"),
+    ("CRustOptionI32", "int"),
+    ("CRustOptionU32", "uint"),
+    ("CRustOptionI64", "long"),
+    ("CRustOptionU64", "ulong"),
+    ("CRustOptionUSize", "UIntPtr"),
+];
+
+/// C structs for `Vec` of primitive types and C# types of their elements
+const VEC_STRUCTS: &[(&str, &str)] = &[
+    ("CRustVecU8", "byte"),
+    ("CRustVecI32", "int"),
+    ("CRustVecU32", "uint"),
+    ("CRustVecUsize", "UIntPtr"),
+    ("CRustVecF32", "float"),
+    ("CRustVecF64", "double"),
+];
+
+/// C structs for slices of primitive types and C# types of their elements
+const SLICE_STRUCTS: &[(&str, &str)] = &[
+    ("CRustSliceU8", "byte"),
+    ("CRustSliceI32", "int"),
+    ("CRustSliceU32", "uint"),
+    ("CRustSliceUsize", "UIntPtr"),
+];
+
+/// How value of some Rust type passed through C API looks in C#
+#[derive(Debug)]
+pub(in crate::csharp) struct CSharpTypeInfo {
+    /// Type in `DllImport` declaration
+    pub(in crate::csharp) pinvoke_ty: String,
+    /// Type visible to user of generated classes
+    pub(in crate::csharp) cs_ty: String,
+    /// Convert `cs_ty` -> `pinvoke_ty` for `Direction::Incoming`
+    /// and `pinvoke_ty` -> `cs_ty` for `Direction::Outgoing`
+    pub(in crate::csharp) converter: Option<String>,
+    /// Converter pins managed array, so caller should declare `swigPins`
+    /// and free them after call
+    pub(in crate::csharp) pinned: bool,
+}
+
+impl CSharpTypeInfo {
+    fn same(name: &str) -> Self {
+        CSharpTypeInfo {
+            pinvoke_ty: name.into(),
+            cs_ty: name.into(),
+            converter: None,
+            pinned: false,
+        }
+    }
+
+    fn with_converter(pinvoke_ty: &str, cs_ty: &str, converter: String) -> Self {
+        CSharpTypeInfo {
+            pinvoke_ty: pinvoke_ty.into(),
+            cs_ty: cs_ty.into(),
+            converter: Some(converter),
+            pinned: false,
+        }
+    }
+
+    pub(in crate::csharp) fn convert(&self, var_name: &str) -> String {
+        match self.converter {
+            Some(ref conv) => conv.replace(FROM_VAR_TEMPLATE, var_name),
+            None => var_name.into(),
+        }
+    }
+}
+
+pub(in crate::csharp) fn handle_name(class_name: &str) -> String {
+    format!("{}Handle", class_name)
+}
+
+/// Expression that wraps pointer to Rust object into C# class
+fn new_object(class_name: &str, ptr: &str, owns: bool) -> String {
+    format!(
+        "new {}(new {}({}, {}))",
+        class_name,
+        handle_name(class_name),
+        ptr,
+        owns
+    )
+}
+
+fn is_unit(ty: &Type) -> bool {
+    match ty {
+        Type::Tuple(t) => t.elems.is_empty(),
+        _ => false,
+    }
+}
+
+/// Name of C# class for `foreigner_class` with such self or this type
+fn foreign_class_name(conv_map: &mut TypeMap, ty: &Type, src_id: SourceId) -> Option<String> {
+    let rust_ty = conv_map.find_or_alloc_rust_type(ty, src_id);
+    if let Some(fc) = conv_map.find_foreigner_class_with_such_self_type(&rust_ty, false) {
+        return Some(fc.name.to_string());
+    }
+    conv_map
+        .find_foreigner_class_with_such_this_type(&rust_ty.ty, |_, fc| {
+            fc.constructor_ret_type.clone()
+        })
+        .map(|fc| fc.name.to_string())
+}
+
+pub(in crate::csharp) fn map_type(
+    conv_map: &mut TypeMap,
+    arg_ty: &RustType,
+    c_info: &CppForeignTypeInfo,
+    direction: Direction,
+    arg_ty_span: SourceIdSpan,
+) -> Result<CSharpTypeInfo> {
+    if let Some(foreign_enum) = conv_map.is_this_exported_enum(arg_ty) {
+        return Ok(CSharpTypeInfo::same(&foreign_enum.name.to_string()));
+    }
+
+    if let syn::Type::Reference(syn::TypeReference { ref elem, .. }) = arg_ty.ty {
+        let elem_rust_ty = conv_map.find_or_alloc_rust_type(elem, arg_ty_span.0);
+        if let Some(foreign_class) =
+            conv_map.find_foreigner_class_with_such_self_type(&elem_rust_ty, false)
+        {
+            let class_name = foreign_class.name.to_string();
+            return Ok(match direction {
+                Direction::Incoming => CSharpTypeInfo::with_converter(
+                    &handle_name(&class_name),
+                    &class_name,
+                    format!("{}.handle", FROM_VAR_TEMPLATE),
+                ),
+                Direction::Outgoing => CSharpTypeInfo::with_converter(
+                    "IntPtr",
+                    &class_name,
+                    new_object(&class_name, FROM_VAR_TEMPLATE, false),
+                ),
+            });
+        }
+    }
+
+    if let Some(this_ty) = conv_map.is_ty_implements(arg_ty, "SwigForeignClass") {
+        if let Some(foreign_class) = conv_map
+            .find_foreigner_class_with_such_this_type(&this_ty.ty, |_, fc| {
+                fc.constructor_ret_type.clone()
+            })
+        {
+            let class_name = foreign_class.name.to_string();
+            return Ok(match direction {
+                Direction::Incoming => CSharpTypeInfo::with_converter(
+                    "IntPtr",
+                    &class_name,
+                    format!("{}.handle.TakeOwnership()", FROM_VAR_TEMPLATE),
+                ),
+                Direction::Outgoing => CSharpTypeInfo::with_converter(
+                    "IntPtr",
+                    &class_name,
+                    new_object(&class_name, FROM_VAR_TEMPLATE, true),
+                ),
+            });
+        }
+    }
+
+    if let Some(inner_ty) = if_option_return_some_type(arg_ty) {
+        if let Some(class_name) = foreign_class_name(conv_map, &inner_ty, arg_ty_span.0) {
+            return Ok(match direction {
+                Direction::Incoming => CSharpTypeInfo::with_converter(
+                    "IntPtr",
+                    &class_name,
+                    format!(
+                        "({var} != null ? {var}.handle.TakeOwnership() : IntPtr.Zero)",
+                        var = FROM_VAR_TEMPLATE
+                    ),
+                ),
+                Direction::Outgoing => CSharpTypeInfo::with_converter(
+                    "IntPtr",
+                    &class_name,
+                    format!(
+                        "({var} != IntPtr.Zero ? {obj} : null)",
+                        var = FROM_VAR_TEMPLATE,
+                        obj = new_object(&class_name, FROM_VAR_TEMPLATE, true)
+                    ),
+                ),
+            });
+        }
+    }
+
+    if arg_ty.normalized_name == "bool" {
+        return Ok(CSharpTypeInfo::with_converter(
+            "byte",
+            "bool",
+            match direction {
+                Direction::Incoming => format!("({} ? (byte)1 : (byte)0)", FROM_VAR_TEMPLATE),
+                Direction::Outgoing => format!("({} != 0)", FROM_VAR_TEMPLATE),
+            },
+        ));
+    }
+
+    let unsupported = || {
+        DiagnosticError::new(
+            arg_ty_span.0,
+            arg_ty_span.1,
+            format!(
+                "C#: conversation of {} (C type '{}') as {} is not supported yet",
+                arg_ty,
+                c_info.base.name,
+                match direction {
+                    Direction::Incoming => "input",
+                    Direction::Outgoing => "output",
+                }
+            ),
+        )
+    };
+
+    let primitive = match c_info.base.name.as_str() {
+        "void" => Some("void"),
+        "int8_t" => Some("sbyte"),
+        "uint8_t" => Some("byte"),
+        "int16_t" => Some("short"),
+        "uint16_t" => Some("ushort"),
+        "int32_t" | "int" => Some("int"),
+        "uint32_t" => Some("uint"),
+        "int64_t" => Some("long"),
+        "uint64_t" => Some("ulong"),
+        "uintptr_t" => Some("UIntPtr"),
+        "float" => Some("float"),
+        "double" => Some("double"),
+        _ => None,
+    };
+    if let Some(name) = primitive {
+        return Ok(CSharpTypeInfo::same(name));
+    }
+
+    if c_info.base.name.starts_with("struct ") {
+        let c_struct = &c_info.base.name["struct ".len()..];
+        if let Some(ret) = map_c_struct(conv_map, arg_ty, c_struct, direction, arg_ty_span)? {
+            return Ok(ret);
+        }
+    }
+
+    match (c_info.base.name.as_str(), direction) {
+        ("const char *", Direction::Incoming) => Ok(CSharpTypeInfo {
+            pinvoke_ty: "[MarshalAs(UnmanagedType.LPUTF8Str)] string".into(),
+            cs_ty: "string".into(),
+            converter: None,
+            pinned: false,
+        }),
+        ("struct RustStrView", Direction::Outgoing) => Ok(CSharpTypeInfo::with_converter(
+            "RustStrView",
+            "string",
+            format!("{}.ToString()", FROM_VAR_TEMPLATE),
+        )),
+        ("struct CRustString", Direction::Outgoing) => Ok(CSharpTypeInfo::with_converter(
+            "CRustString",
+            "string",
+            format!("{}.Consume()", FROM_VAR_TEMPLATE),
+        )),
+        (c_name, Direction::Incoming) => {
+            let prefix = "const struct C_";
+            let suffix = " * const";
+            if c_name.starts_with(prefix) && c_name.ends_with(suffix) {
+                let interface_name = &c_name[prefix.len()..c_name.len() - suffix.len()];
+                Ok(CSharpTypeInfo::with_converter(
+                    &format!("in C_{}", interface_name),
+                    interface_name,
+                    format!("C_{}.Create({})", interface_name, FROM_VAR_TEMPLATE),
+                ))
+            } else {
+                Err(unsupported())
+            }
+        }
+        _ => Err(unsupported()),
+    }
+}
+
+/// Map `Option`, `Vec`, slice and `Result` that C API passes as C structs
+fn map_c_struct(
+    conv_map: &mut TypeMap,
+    arg_ty: &RustType,
+    c_struct: &str,
+    direction: Direction,
+    arg_ty_span: SourceIdSpan,
+) -> Result<Option<CSharpTypeInfo>> {
+    if let Some((_, cs_ty)) = OPTION_STRUCTS.iter().find(|x| x.0 == c_struct) {
+        let cs_ty = format!("{}?", cs_ty);
+        return Ok(Some(match direction {
+            Direction::Incoming => CSharpTypeInfo::with_converter(
+                c_struct,
+                &cs_ty,
+                format!("{}.FromNullable({})", c_struct, FROM_VAR_TEMPLATE),
+            ),
+            Direction::Outgoing => CSharpTypeInfo::with_converter(
+                c_struct,
+                &cs_ty,
+                format!("{}.ToNullable()", FROM_VAR_TEMPLATE),
+            ),
+        }));
+    }
+    if direction == Direction::Outgoing {
+        if c_struct == "CRustOptionStr" {
+            return Ok(Some(CSharpTypeInfo::with_converter(
+                c_struct,
+                "string",
+                format!("{}.ToNullableString()", FROM_VAR_TEMPLATE),
+            )));
+        }
+        if c_struct == "CRustOptionString" {
+            return Ok(Some(CSharpTypeInfo::with_converter(
+                c_struct,
+                "string",
+                format!("{}.Consume()", FROM_VAR_TEMPLATE),
+            )));
+        }
+        if let Some((_, elem)) = VEC_STRUCTS.iter().find(|x| x.0 == c_struct) {
+            return Ok(Some(CSharpTypeInfo::with_converter(
+                c_struct,
+                &format!("{}[]", elem),
+                format!("{}.Consume()", FROM_VAR_TEMPLATE),
+            )));
+        }
+        if let Some((_, elem)) = SLICE_STRUCTS.iter().find(|x| x.0 == c_struct) {
+            return Ok(Some(CSharpTypeInfo::with_converter(
+                c_struct,
+                &format!("{}[]", elem),
+                format!("{}.ToArray()", FROM_VAR_TEMPLATE),
+            )));
+        }
+    }
+    if c_struct == "CRustSliceI32" && direction == Direction::Incoming {
+        return Ok(Some(CSharpTypeInfo {
+            pinvoke_ty: c_struct.into(),
+            cs_ty: "int[]".into(),
+            converter: Some(format!("{}.Pin({}, swigPins)", c_struct, FROM_VAR_TEMPLATE)),
+            pinned: true,
+        }));
+    }
+    if c_struct == "CRustForeignVec" {
+        let class_name = match if_vec_return_elem_type(arg_ty)
+            .and_then(|elem| foreign_class_name(conv_map, &elem, arg_ty_span.0))
+        {
+            Some(x) => x,
+            None => return Ok(None),
+        };
+        let cs_ty = format!("{}[]", class_name);
+        return Ok(Some(match direction {
+            Direction::Incoming => CSharpTypeInfo::with_converter(
+                c_struct,
+                &cs_ty,
+                format!("{}.SwigToVec({})", class_name, FROM_VAR_TEMPLATE),
+            ),
+            Direction::Outgoing => CSharpTypeInfo::with_converter(
+                c_struct,
+                &cs_ty,
+                format!("{}.SwigConsumeVec({})", class_name, FROM_VAR_TEMPLATE),
+            ),
+        }));
+    }
+    if c_struct == "CRustObjectSlice" {
+        let class_name = match if_type_slice_return_elem_type(&arg_ty.ty, false)
+            .cloned()
+            .and_then(|elem| foreign_class_name(conv_map, &elem, arg_ty_span.0))
+        {
+            Some(x) => x,
+            None => return Ok(None),
+        };
+        if direction == Direction::Incoming {
+            return Err(DiagnosticError::new2(
+                arg_ty_span,
+                format!(
+                    "C#: {} as input is not supported, objects of C# classes are not \
+                     placed in one continuous memory block, use Vec instead",
+                    arg_ty
+                ),
+            ));
+        }
+        return Ok(Some(CSharpTypeInfo::with_converter(
+            c_struct,
+            &format!("{}[]", class_name),
+            format!("{}.SwigFromSlice({})", class_name, FROM_VAR_TEMPLATE),
+        )));
+    }
+    if c_struct.starts_with("CResult") && direction == Direction::Outgoing {
+        return map_result(conv_map, arg_ty, c_struct, arg_ty_span);
+    }
+    Ok(None)
+}
+
+/// `Ok` of `Result` is returned, `Err` is thrown as `RustException`
+fn map_result(
+    conv_map: &mut TypeMap,
+    arg_ty: &RustType,
+    c_struct: &str,
+    arg_ty_span: SourceIdSpan,
+) -> Result<Option<CSharpTypeInfo>> {
+    let (ok_ty, err_ty) = match if_result_return_ok_err_types(arg_ty) {
+        Some(x) => x,
+        None => return Ok(None),
+    };
+    let unwrap = match c_struct {
+        "CResultObjectString" | "CResultCRustForeignVecString" => {
+            format!("{}.Unwrap()", FROM_VAR_TEMPLATE)
+        }
+        "CResultObjectEnum" => {
+            let err_rust_ty = conv_map.find_or_alloc_rust_type(&err_ty, arg_ty_span.0);
+            let enum_name = match conv_map.is_this_exported_enum(&err_rust_ty) {
+                Some(x) => x.name.to_string(),
+                None => return Ok(None),
+            };
+            format!("{}.Unwrap(e => ({})e)", FROM_VAR_TEMPLATE, enum_name)
+        }
+        "CResultObjectObject"
+        | "CResultI64Object"
+        | "CResultVecObjectObject"
+        | "CResultCRustVecU8Object" => {
+            let err_class = match foreign_class_name(conv_map, &err_ty, arg_ty_span.0) {
+                Some(x) => x,
+                None => return Ok(None),
+            };
+            format!(
+                "{}.Unwrap(e => {})",
+                FROM_VAR_TEMPLATE,
+                new_object(&err_class, "e", true)
+            )
+        }
+        _ => return Ok(None),
+    };
+    let ok_rust_ty = conv_map.find_or_alloc_rust_type(&ok_ty, arg_ty_span.0);
+    let (cs_ty, converter) = match c_struct {
+        "CResultObjectString" | "CResultObjectObject" | "CResultObjectEnum" => {
+            if is_unit(&ok_ty) {
+                ("void".to_string(), unwrap)
+            } else {
+                match foreign_class_name(conv_map, &ok_ty, arg_ty_span.0) {
+                    Some(class_name) => {
+                        let conv = new_object(&class_name, &unwrap, true);
+                        (class_name, conv)
+                    }
+                    None => return Ok(None),
+                }
+            }
+        }
+        "CResultI64Object" => ("long".to_string(), unwrap),
+        "CResultCRustVecU8Object" => ("byte[]".to_string(), format!("{}.Consume()", unwrap)),
+        _ => match if_vec_return_elem_type(&ok_rust_ty)
+            .and_then(|elem| foreign_class_name(conv_map, &elem, arg_ty_span.0))
+        {
+            Some(class_name) => (
+                format!("{}[]", class_name),
+                format!("{}.SwigConsumeVec({})", class_name, unwrap),
+            ),
+            None => return Ok(None),
+        },
+    };
+    Ok(Some(CSharpTypeInfo::with_converter(
+        c_struct, &cs_ty, converter,
+    )))
+}
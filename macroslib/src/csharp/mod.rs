@@ -0,0 +1,653 @@
+mod map_type;
+
+use std::{fmt::Write, io::Write as IoWrite, path::Path};
+
+use petgraph::Direction;
+use proc_macro2::TokenStream;
+use syn::spanned::Spanned;
+
+use crate::{
+    cpp::{
+        c_func_name, check_no_future_methods, find_suitable_foreign_types_for_methods,
+        find_suitable_ftypes_for_interace_methods, CppForeignMethodSignature,
+    },
+    csharp::map_type::{handle_name, map_type},
+    error::{DiagnosticError, Result},
+    file_cache::FileWriteCache,
+    typemap::ast::fn_arg_type,
+    types::{
+        ForeignEnumInfo, ForeignFlagsInfo, ForeignInterface, ForeignStructInfo, ForeignerClassInfo,
+        MethodAccess, MethodVariant,
+    },
+    CSharpConfig, LanguageGenerator, SourceCode, TypeMap,
+};
+
+impl LanguageGenerator for CSharpConfig {
+    fn register_class(&self, conv_map: &mut TypeMap, class: &ForeignerClassInfo) -> Result<()> {
+        self.c_abi.register_class(conv_map, class)
+    }
+
+    fn generate(
+        &self,
+        conv_map: &mut TypeMap,
+        pointer_target_width: usize,
+        class: &ForeignerClassInfo,
+    ) -> Result<Vec<TokenStream>> {
+        let code = self.c_abi.generate(conv_map, pointer_target_width, class)?;
+        let m_sigs = find_suitable_foreign_types_for_methods(conv_map, class, &self.c_abi)?;
+        check_no_future_methods(class, &m_sigs, "C#")?;
+        let cs_code = generate_class(conv_map, self, class, &m_sigs)?;
+        write_cs_file(&self.output_dir, &class.name.to_string(), &cs_code)
+            .map_err(|err| DiagnosticError::new(class.src_id, class.span(), err))?;
+        Ok(code)
+    }
+
+    fn generate_enum(
+        &self,
+        conv_map: &mut TypeMap,
+        pointer_target_width: usize,
+        enum_info: &ForeignEnumInfo,
+    ) -> Result<Vec<TokenStream>> {
+        if enum_info.is_data_carrying() {
+            return Err(DiagnosticError::new(
+                enum_info.src_id,
+                enum_info.span(),
+                "C#: foreign_enum with data-carrying items is not supported yet",
+            ));
+        }
+        let code = self
+            .c_abi
+            .generate_enum(conv_map, pointer_target_width, enum_info)?;
+        let mut cs_code = file_header(&self.namespace_name);
+        cs_code.push_str(&doc_comments_to_cs_comments(
+            &enum_info.doc_comments,
+            "    ",
+        ));
+        writeln!(
+            &mut cs_code,
+            "    public enum {enum_name} : uint\n    {{",
+            enum_name = enum_info.name
+        )
+        .unwrap();
+        for item in &enum_info.items {
+            cs_code.push_str(&doc_comments_to_cs_comments(&item.doc_comments, "        "));
+            writeln!(&mut cs_code, "        {} = {},", item.name, item.value).unwrap();
+        }
+        cs_code.push_str("    }\n}\n");
+        write_cs_file(&self.output_dir, &enum_info.name.to_string(), &cs_code)
+            .map_err(|err| DiagnosticError::new(enum_info.src_id, enum_info.span(), err))?;
+        Ok(code)
+    }
+
+    fn generate_struct(
+        &self,
+        _conv_map: &mut TypeMap,
+        _pointer_target_width: usize,
+        struct_info: &ForeignStructInfo,
+    ) -> Result<Vec<TokenStream>> {
+        Err(DiagnosticError::new(
+            struct_info.src_id,
+            struct_info.span(),
+            "C#: foreign_struct is not supported yet",
+        ))
+    }
+
+    fn generate_flags(
+        &self,
+        _conv_map: &mut TypeMap,
+        _pointer_target_width: usize,
+        flags_info: &ForeignFlagsInfo,
+    ) -> Result<Vec<TokenStream>> {
+        Err(DiagnosticError::new(
+            flags_info.src_id,
+            flags_info.span(),
+            "C#: foreign_flags is not supported yet",
+        ))
+    }
+
+    fn generate_interface(
+        &self,
+        conv_map: &mut TypeMap,
+        pointer_target_width: usize,
+        interface: &ForeignInterface,
+    ) -> Result<Vec<TokenStream>> {
+        let code = self
+            .c_abi
+            .generate_interface(conv_map, pointer_target_width, interface)?;
+        let f_methods =
+            find_suitable_ftypes_for_interace_methods(conv_map, interface, &self.c_abi)?;
+        let cs_code = generate_interface(conv_map, self, interface, &f_methods)?;
+        write_cs_file(&self.output_dir, &interface.name.to_string(), &cs_code)
+            .map_err(|err| DiagnosticError::new(interface.src_id, interface.span(), err))?;
+        Ok(code)
+    }
+
+    fn init(&self, conv_map: &mut TypeMap, code: &[SourceCode]) -> std::result::Result<(), String> {
+        self.c_abi.init(conv_map, &[])?;
+        for cu in code {
+            let src_path = self.output_dir.join(&cu.id_of_code);
+            let mut src_file = FileWriteCache::new(&src_path);
+            src_file
+                .write_all(
+                    cu.code
+                        .replace("RUST_SWIG_USER_NAMESPACE", &self.namespace_name)
+                        .replace("RUST_SWIG_NATIVE_LIB", &self.native_lib_name)
+                        .as_bytes(),
+                )
+                .map_err(|err| format!("write to {} failed: {}", src_path.display(), err))?;
+            src_file
+                .update_file_if_necessary()
+                .map_err(|err| format!("update of {} failed: {}", src_path.display(), err))?;
+        }
+        Ok(())
+    }
+}
+
+fn generate_class(
+    conv_map: &mut TypeMap,
+    cfg: &CSharpConfig,
+    class: &ForeignerClassInfo,
+    m_sigs: &[CppForeignMethodSignature],
+) -> Result<String> {
+    let class_name = class.name.to_string();
+    let handle_class = handle_name(&class_name);
+    let has_constructor = class
+        .methods
+        .iter()
+        .any(|m| m.variant == MethodVariant::Constructor);
+    let dll_import = format!(
+        "        [DllImport(\"{}\", CallingConvention = CallingConvention.Cdecl)]\n",
+        cfg.native_lib_name
+    );
+
+    let mut methods = String::new();
+    let mut pinvoke_decls = String::new();
+
+    for (method, f_method) in class.methods.iter().zip(m_sigs) {
+        if method.is_dummy_constructor() {
+            continue;
+        }
+        let skip_n = match method.variant {
+            MethodVariant::Method(_) => 1,
+            _ => 0,
+        };
+        let mut args = Vec::with_capacity(f_method.input.len());
+        for (arg, c_info) in method
+            .fn_decl
+            .inputs
+            .iter()
+            .skip(skip_n)
+            .zip(&f_method.input)
+        {
+            let arg_rust_ty = conv_map.find_or_alloc_rust_type(fn_arg_type(arg), class.src_id);
+            args.push(map_type(
+                conv_map,
+                &arg_rust_ty,
+                c_info,
+                Direction::Incoming,
+                (class.src_id, fn_arg_type(arg).span()),
+            )?);
+        }
+        let output = match (method.variant, &method.fn_decl.output) {
+            (MethodVariant::Constructor, _) | (_, syn::ReturnType::Default) => None,
+            (_, syn::ReturnType::Type(_, ref ret_ty)) => {
+                let ret_rust_ty = conv_map.find_or_alloc_rust_type(ret_ty, class.src_id);
+                Some(map_type(
+                    conv_map,
+                    &ret_rust_ty,
+                    &f_method.output,
+                    Direction::Outgoing,
+                    (class.src_id, ret_ty.span()),
+                )?)
+            }
+        };
+
+        let c_func_name = c_func_name(class, method);
+        let mut pinvoke_args = match method.variant {
+            MethodVariant::Method(_) => vec![format!("{} self", handle_class)],
+            _ => vec![],
+        };
+        let mut call_args = match method.variant {
+            MethodVariant::Method(_) => vec!["this.handle".to_string()],
+            _ => vec![],
+        };
+        let mut cs_args = Vec::with_capacity(args.len());
+        for (i, arg) in args.iter().enumerate() {
+            let arg_name = format!("a{}", i);
+            pinvoke_args.push(format!("{} {}", arg.pinvoke_ty, arg_name));
+            call_args.push(arg.convert(&arg_name));
+            cs_args.push(format!("{} {}", arg.cs_ty, arg_name));
+        }
+        //Rust side reports unknown value of enum argument via out parameter
+        let check_invalid_arg = if f_method.checked_enum_args.is_empty() {
+            String::new()
+        } else {
+            pinvoke_args.push("ref byte swig_invalid_arg".to_string());
+            call_args.push("ref swigInvalidArg".to_string());
+            format!(
+                "            if (swigInvalidArg != 0)\n            {{\n                throw new ArgumentException(\"unknown value of {} passed to {}.{}\");\n            }}\n",
+                f_method.checked_enum_names(),
+                class_name,
+                method.short_name()
+            )
+        };
+        let decl_invalid_arg = if check_invalid_arg.is_empty() {
+            ""
+        } else {
+            "            byte swigInvalidArg = 0;\n"
+        };
+        let pinvoke_ret = match (method.variant, output.as_ref()) {
+            (MethodVariant::Constructor, _) => "IntPtr",
+            (_, Some(out)) => out.pinvoke_ty.as_str(),
+            (_, None) => "void",
+        };
+        writeln!(
+            &mut pinvoke_decls,
+            "{dll_import}        private static extern {ret} {c_func_name}({args});",
+            dll_import = dll_import,
+            ret = pinvoke_ret,
+            c_func_name = c_func_name,
+            args = pinvoke_args.join(", "),
+        )
+        .unwrap();
+
+        let access = match method.access {
+            MethodAccess::Private => "private",
+            MethodAccess::Protected => "protected",
+            MethodAccess::Public => "public",
+        };
+        let call = format!("{}({})", c_func_name, call_args.join(", "));
+        methods.push_str(&doc_comments_to_cs_comments(
+            &method.doc_comments,
+            "        ",
+        ));
+        let pinned = args.iter().any(|x| x.pinned);
+        match method.variant {
+            MethodVariant::Constructor => {
+                let body = if check_invalid_arg.is_empty() && !pinned {
+                    format!(
+                        "            this.handle = new {}({}, true);\n",
+                        handle_class, call
+                    )
+                } else {
+                    format!(
+                        "{}            IntPtr ret = {};\n{}            this.handle = new {}(ret, true);\n",
+                        decl_invalid_arg, call, check_invalid_arg, handle_class
+                    )
+                };
+                let body = if pinned { free_pins_after(&body) } else { body };
+                write!(
+                    &mut methods,
+                    r#"        {access} {class_name}({args})
+        {{
+{body}        }}
+
+"#,
+                    access = access,
+                    class_name = class_name,
+                    args = cs_args.join(", "),
+                    body = body,
+                )
+                .unwrap();
+            }
+            MethodVariant::StaticMethod | MethodVariant::Method(_) => {
+                let body = match output {
+                    //`Result<(), E>`, so only check for error
+                    Some(ref out) if out.cs_ty == "void" => format!(
+                        "{decl_invalid_arg}            {pinvoke_ret} ret = {call};\n{check_invalid_arg}            {conv_ret};\n",
+                        decl_invalid_arg = decl_invalid_arg,
+                        pinvoke_ret = pinvoke_ret,
+                        call = call,
+                        check_invalid_arg = check_invalid_arg,
+                        conv_ret = out.convert("ret")
+                    ),
+                    Some(ref out) => format!(
+                        "{decl_invalid_arg}            {pinvoke_ret} ret = {call};\n{check_invalid_arg}            return {conv_ret};\n",
+                        decl_invalid_arg = decl_invalid_arg,
+                        pinvoke_ret = pinvoke_ret,
+                        call = call,
+                        check_invalid_arg = check_invalid_arg,
+                        conv_ret = out.convert("ret")
+                    ),
+                    None => format!(
+                        "{}            {};\n{}",
+                        decl_invalid_arg, call, check_invalid_arg
+                    ),
+                };
+                let body = if pinned { free_pins_after(&body) } else { body };
+                write!(
+                    &mut methods,
+                    r#"        {access} {static_}{ret} {method_name}({args})
+        {{
+{body}        }}
+
+"#,
+                    access = access,
+                    static_ = if method.variant == MethodVariant::StaticMethod {
+                        "static "
+                    } else {
+                        ""
+                    },
+                    ret = output.as_ref().map(|x| x.cs_ty.as_str()).unwrap_or("void"),
+                    method_name = method.short_name(),
+                    args = cs_args.join(", "),
+                    body = body,
+                )
+                .unwrap();
+            }
+        }
+    }
+
+    let mut cs_code = file_header(&cfg.namespace_name);
+    if has_constructor {
+        write!(
+            &mut cs_code,
+            r#"    internal sealed class {handle_class} : SafeHandle
+    {{
+        internal {handle_class}(IntPtr handle, bool ownsHandle) : base(IntPtr.Zero, ownsHandle)
+        {{
+            SetHandle(handle);
+        }}
+
+        public override bool IsInvalid => handle == IntPtr.Zero;
+
+        internal IntPtr TakeOwnership()
+        {{
+            IntPtr p = handle;
+            SetHandleAsInvalid();
+            return p;
+        }}
+
+        protected override bool ReleaseHandle()
+        {{
+            {class_name}.{class_name}_delete(handle);
+            return true;
+        }}
+    }}
+
+{doc_comments}    public class {class_name} : IDisposable
+    {{
+        internal readonly {handle_class} handle;
+
+        internal {class_name}({handle_class} handle)
+        {{
+            this.handle = handle;
+        }}
+
+{methods}        public void Dispose()
+        {{
+            handle.Dispose();
+        }}
+
+        internal static {class_name}[] SwigConsumeVec(CRustForeignVec v)
+        {{
+            var ret = new {class_name}[checked((int)v.len.ToUInt64())];
+            for (int i = ret.Length - 1; i >= 0; --i)
+            {{
+                ret[i] = new {class_name}(new {handle_class}(RustForeignVec{class_name}_remove(ref v, new UIntPtr((uint)i)), true));
+            }}
+            RustForeignVec{class_name}_free(v);
+            return ret;
+        }}
+
+        internal static CRustForeignVec SwigToVec({class_name}[] a)
+        {{
+            var v = new CRustForeignVec();
+            foreach (var x in a)
+            {{
+                RustForeignVec{class_name}_push(ref v, x.handle.TakeOwnership());
+            }}
+            return v;
+        }}
+
+        internal static {class_name}[] SwigFromSlice(CRustObjectSlice s)
+        {{
+            var ret = new {class_name}[checked((int)s.len.ToUInt64())];
+            for (int i = 0; i < ret.Length; ++i)
+            {{
+                ret[i] = new {class_name}(new {handle_class}(s.ElementAt(i), false));
+            }}
+            return ret;
+        }}
+
+{pinvoke_decls}{dll_import}        internal static extern void {class_name}_delete(IntPtr self);
+{dll_import}        private static extern void RustForeignVec{class_name}_free(CRustForeignVec v);
+{dll_import}        private static extern void RustForeignVec{class_name}_push(ref CRustForeignVec v, IntPtr elem);
+{dll_import}        private static extern IntPtr RustForeignVec{class_name}_remove(ref CRustForeignVec v, UIntPtr idx);
+    }}
+}}
+"#,
+            handle_class = handle_class,
+            class_name = class_name,
+            doc_comments = doc_comments_to_cs_comments(&class.doc_comments, "    "),
+            methods = methods,
+            pinvoke_decls = pinvoke_decls,
+            dll_import = dll_import,
+        )
+        .unwrap();
+    } else {
+        write!(
+            &mut cs_code,
+            r#"{doc_comments}    public static class {class_name}
+    {{
+{methods}{pinvoke_decls}    }}
+}}
+"#,
+            class_name = class_name,
+            doc_comments = doc_comments_to_cs_comments(&class.doc_comments, "    "),
+            methods = methods,
+            pinvoke_decls = pinvoke_decls,
+        )
+        .unwrap();
+    }
+    Ok(cs_code)
+}
+
+/// Free arrays pinned by converters of arguments after call
+fn free_pins_after(body: &str) -> String {
+    let mut ret = String::from(
+        "            var swigPins = new System.Collections.Generic.List<GCHandle>();\n            try\n            {\n",
+    );
+    for line in body.lines() {
+        writeln!(&mut ret, "    {}", line).unwrap();
+    }
+    ret.push_str(
+        "            }\n            finally\n            {\n                foreach (var h in swigPins)\n                {\n                    h.Free();\n                }\n            }\n",
+    );
+    ret
+}
+
+fn generate_interface(
+    conv_map: &mut TypeMap,
+    cfg: &CSharpConfig,
+    interface: &ForeignInterface,
+    f_methods: &[CppForeignMethodSignature],
+) -> Result<String> {
+    let struct_name = format!("C_{}", interface.name);
+    let mut interface_methods = String::new();
+    let mut fields = String::new();
+    let mut delegates = String::new();
+    let mut init_fields = String::new();
+    let mut callbacks = String::new();
+
+    for (method, f_method) in interface.items.iter().zip(f_methods) {
+        let mut args = Vec::with_capacity(f_method.input.len());
+        for (arg, c_info) in method.fn_decl.inputs.iter().skip(1).zip(&f_method.input) {
+            let arg_rust_ty = conv_map.find_or_alloc_rust_type(fn_arg_type(arg), interface.src_id);
+            args.push(map_type(
+                conv_map,
+                &arg_rust_ty,
+                c_info,
+                Direction::Outgoing,
+                (interface.src_id, fn_arg_type(arg).span()),
+            )?);
+        }
+        let output = match method.fn_decl.output {
+            syn::ReturnType::Default => None,
+            syn::ReturnType::Type(_, ref ret_ty) => {
+                let ret_rust_ty = conv_map.find_or_alloc_rust_type(ret_ty, interface.src_id);
+                let ret = map_type(
+                    conv_map,
+                    &ret_rust_ty,
+                    &f_method.output,
+                    Direction::Incoming,
+                    (interface.src_id, ret_ty.span()),
+                )?;
+                if ret.pinvoke_ty != ret.cs_ty && ret.cs_ty != "bool" {
+                    return Err(DiagnosticError::new(
+                        interface.src_id,
+                        ret_ty.span(),
+                        format!(
+                            "C#: {} as return type of callback is not supported yet",
+                            ret_rust_ty
+                        ),
+                    ));
+                }
+                Some(ret)
+            }
+        };
+        let cs_ret = output.as_ref().map(|x| x.cs_ty.as_str()).unwrap_or("void");
+        let pinvoke_ret = output
+            .as_ref()
+            .map(|x| x.pinvoke_ty.as_str())
+            .unwrap_or("void");
+        let mut cs_args = Vec::with_capacity(args.len());
+        let mut pinvoke_args = Vec::with_capacity(args.len() + 1);
+        let mut call_args = Vec::with_capacity(args.len());
+        for (i, arg) in args.iter().enumerate() {
+            let arg_name = format!("a{}", i);
+            cs_args.push(format!("{} {}", arg.cs_ty, arg_name));
+            pinvoke_args.push(format!("{} {}", arg.pinvoke_ty, arg_name));
+            call_args.push(arg.convert(&arg_name));
+        }
+        pinvoke_args.push("IntPtr opaque".to_string());
+
+        interface_methods.push_str(&doc_comments_to_cs_comments(
+            &method.doc_comments,
+            "        ",
+        ));
+        writeln!(
+            &mut interface_methods,
+            "        {} {}({});",
+            cs_ret,
+            method.name,
+            cs_args.join(", ")
+        )
+        .unwrap();
+        writeln!(&mut fields, "        internal IntPtr {};", method.name).unwrap();
+        write!(
+            &mut delegates,
+            r#"        [UnmanagedFunctionPointer(CallingConvention.Cdecl)]
+        private delegate {pinvoke_ret} {method_name}Delegate({pinvoke_args});
+        private static readonly {method_name}Delegate {method_name}Callback = Call_{method_name};
+"#,
+            pinvoke_ret = pinvoke_ret,
+            method_name = method.name,
+            pinvoke_args = pinvoke_args.join(", "),
+        )
+        .unwrap();
+        writeln!(
+            &mut init_fields,
+            "                {method_name} = Marshal.GetFunctionPointerForDelegate({method_name}Callback),",
+            method_name = method.name
+        )
+        .unwrap();
+        let call = format!("obj.{}({})", method.name, call_args.join(", "));
+        let call = match output {
+            Some(ref out) => format!("return {};", out.convert(&call)),
+            None => format!("{};", call),
+        };
+        write!(
+            &mut callbacks,
+            r#"
+        private static {pinvoke_ret} Call_{method_name}({pinvoke_args})
+        {{
+            var obj = ({interface_name})GCHandle.FromIntPtr(opaque).Target;
+            {call}
+        }}
+"#,
+            pinvoke_ret = pinvoke_ret,
+            method_name = method.name,
+            pinvoke_args = pinvoke_args.join(", "),
+            interface_name = interface.name,
+            call = call,
+        )
+        .unwrap();
+    }
+
+    let mut cs_code = file_header(&cfg.namespace_name);
+    write!(
+        &mut cs_code,
+        r#"{doc_comments}    public interface {interface_name}
+    {{
+{interface_methods}    }}
+
+    [StructLayout(LayoutKind.Sequential)]
+    internal struct {struct_name}
+    {{
+        internal IntPtr opaque;
+        internal IntPtr {struct_name}_deref;
+{fields}
+        [UnmanagedFunctionPointer(CallingConvention.Cdecl)]
+        private delegate void DerefDelegate(IntPtr opaque);
+        private static readonly DerefDelegate DerefCallback = Deref;
+{delegates}
+        internal static {struct_name} Create({interface_name} obj)
+        {{
+            return new {struct_name}
+            {{
+                opaque = GCHandle.ToIntPtr(GCHandle.Alloc(obj)),
+                {struct_name}_deref = Marshal.GetFunctionPointerForDelegate(DerefCallback),
+{init_fields}            }};
+        }}
+
+        private static void Deref(IntPtr opaque)
+        {{
+            GCHandle.FromIntPtr(opaque).Free();
+        }}
+{callbacks}    }}
+}}
+"#,
+        doc_comments = doc_comments_to_cs_comments(&interface.doc_comments, "    "),
+        interface_name = interface.name,
+        interface_methods = interface_methods,
+        struct_name = struct_name,
+        fields = fields,
+        delegates = delegates,
+        init_fields = init_fields,
+        callbacks = callbacks,
+    )
+    .unwrap();
+    Ok(cs_code)
+}
+
+fn file_header(namespace_name: &str) -> String {
+    format!(
+        r#"// Automaticaly generated by rust_swig
+using System;
+using System.Runtime.InteropServices;
+
+namespace {namespace}
+{{
+"#,
+        namespace = namespace_name
+    )
+}
+
+fn doc_comments_to_cs_comments(doc_comments: &[String], indent: &str) -> String {
+    let mut comments = String::new();
+    for comment in doc_comments {
+        writeln!(&mut comments, "{}/// {}", indent, comment.trim()).unwrap();
+    }
+    comments
+}
+
+fn write_cs_file(output_dir: &Path, name: &str, cs_code: &str) -> std::result::Result<(), String> {
+    let path = output_dir.join(format!("{}.cs", name));
+    let mut file = FileWriteCache::new(&path);
+    file.write_all(cs_code.as_bytes())
+        .map_err(|err| format!("write to {} failed: {}", path.display(), err))?;
+    file.update_file_if_necessary()
+        .map_err(|err| format!("update of {} failed: {}", path.display(), err))?;
+    Ok(())
+}
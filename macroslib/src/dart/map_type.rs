@@ -0,0 +1,499 @@
+use petgraph::Direction;
+
+use syn::Type;
+
+use crate::{
+    cpp::CppForeignTypeInfo,
+    error::{DiagnosticError, Result, SourceIdSpan},
+    source_registry::SourceId,
+    typemap::{
+        ast::{
+            if_option_return_some_type, if_result_return_ok_err_types,
+            if_type_slice_return_elem_type, if_vec_return_elem_type,
+        },
+        ty::RustType,
+        FROM_VAR_TEMPLATE,
+    },
+    TypeMap,
+};
+
+/// C structs for `Option` of primitive types and Dart types of their values
+const OPTION_STRUCTS: &[(&str, &str)] = &[
+    ("CRustOptionBool", "bool"),
+    ("CRustOptionF32", "double"),
+    ("CRustOptionF64", "double"),
+    ("CRustOptionI32", "int"),
+    ("CRustOptionU32", "int"),
+    ("CRustOptionI64", "int"),
+    ("CRustOptionU64", "int"),
+    ("CRustOptionUSize", "int"),
+];
+
+/// C structs for `Vec` of primitive types and Dart types of their elements
+const VEC_STRUCTS: &[(&str, &str)] = &[
+    ("CRustVecU8", "int"),
+    ("CRustVecI32", "int"),
+    ("CRustVecU32", "int"),
+    ("CRustVecUsize", "int"),
+    ("CRustVecF32", "double"),
+    ("CRustVecF64", "double"),
+];
+
+/// C structs for slices of primitive types, elements of all of them are `int`
+const SLICE_STRUCTS: &[&str] = &[
+    "CRustSliceU8",
+    "CRustSliceI32",
+    "CRustSliceU32",
+    "CRustSliceUsize",
+];
+
+/// How value of some Rust type passed through C API looks in Dart
+#[derive(Debug)]
+pub(in crate::dart) struct DartTypeInfo {
+    /// Native type in `NativeFunction` signature
+    pub(in crate::dart) native_ty: String,
+    /// Dart type of the same value in signature of looked up function
+    pub(in crate::dart) ffi_ty: String,
+    /// Type visible to user of generated classes
+    pub(in crate::dart) dart_ty: String,
+    /// Convert `dart_ty` -> `ffi_ty` for `Direction::Incoming`
+    /// and `ffi_ty` -> `dart_ty` for `Direction::Outgoing`
+    pub(in crate::dart) converter: Option<String>,
+    /// Converter allocates temporary memory via `arena`
+    pub(in crate::dart) uses_arena: bool,
+}
+
+impl DartTypeInfo {
+    fn same(native_ty: &str, dart_ty: &str) -> Self {
+        DartTypeInfo {
+            native_ty: native_ty.into(),
+            ffi_ty: dart_ty.into(),
+            dart_ty: dart_ty.into(),
+            converter: None,
+            uses_arena: false,
+        }
+    }
+
+    fn with_converter(native_ty: &str, ffi_ty: &str, dart_ty: &str, converter: String) -> Self {
+        DartTypeInfo {
+            native_ty: native_ty.into(),
+            ffi_ty: ffi_ty.into(),
+            dart_ty: dart_ty.into(),
+            converter: Some(converter),
+            uses_arena: false,
+        }
+    }
+
+    /// Native and Dart types are the same C struct
+    fn with_struct(c_struct: &str, dart_ty: &str, converter: String) -> Self {
+        DartTypeInfo::with_converter(c_struct, c_struct, dart_ty, converter)
+    }
+
+    pub(in crate::dart) fn convert(&self, var_name: &str) -> String {
+        match self.converter {
+            Some(ref conv) => conv.replace(FROM_VAR_TEMPLATE, var_name),
+            None => var_name.into(),
+        }
+    }
+}
+
+fn is_unit(ty: &Type) -> bool {
+    match ty {
+        Type::Tuple(t) => t.elems.is_empty(),
+        _ => false,
+    }
+}
+
+/// Name of Dart class for `foreigner_class` with such self or this type
+fn foreign_class_name(conv_map: &mut TypeMap, ty: &Type, src_id: SourceId) -> Option<String> {
+    let rust_ty = conv_map.find_or_alloc_rust_type(ty, src_id);
+    if let Some(fc) = conv_map.find_foreigner_class_with_such_self_type(&rust_ty, false) {
+        return Some(fc.name.to_string());
+    }
+    conv_map
+        .find_foreigner_class_with_such_this_type(&rust_ty.ty, |_, fc| {
+            fc.constructor_ret_type.clone()
+        })
+        .map(|fc| fc.name.to_string())
+}
+
+pub(in crate::dart) fn map_type(
+    conv_map: &mut TypeMap,
+    arg_ty: &RustType,
+    c_info: &CppForeignTypeInfo,
+    direction: Direction,
+    arg_ty_span: SourceIdSpan,
+) -> Result<DartTypeInfo> {
+    if let Some(foreign_enum) = conv_map.is_this_exported_enum(arg_ty) {
+        let enum_name = foreign_enum.name.to_string();
+        let converter = match direction {
+            Direction::Incoming => format!("{}.value", FROM_VAR_TEMPLATE),
+            Direction::Outgoing => format!("{}.fromValue({})", enum_name, FROM_VAR_TEMPLATE),
+        };
+        return Ok(DartTypeInfo::with_converter(
+            "Uint32", "int", &enum_name, converter,
+        ));
+    }
+
+    if let syn::Type::Reference(syn::TypeReference { ref elem, .. }) = arg_ty.ty {
+        let elem_rust_ty = conv_map.find_or_alloc_rust_type(elem, arg_ty_span.0);
+        if let Some(foreign_class) =
+            conv_map.find_foreigner_class_with_such_self_type(&elem_rust_ty, false)
+        {
+            let class_name = foreign_class.name.to_string();
+            let converter = match direction {
+                Direction::Incoming => format!("{}._handle", FROM_VAR_TEMPLATE),
+                Direction::Outgoing => format!("{}._({}, false)", class_name, FROM_VAR_TEMPLATE),
+            };
+            return Ok(DartTypeInfo::with_converter(
+                "Pointer<Void>",
+                "Pointer<Void>",
+                &class_name,
+                converter,
+            ));
+        }
+    }
+
+    if let Some(this_ty) = conv_map.is_ty_implements(arg_ty, "SwigForeignClass") {
+        if let Some(foreign_class) = conv_map
+            .find_foreigner_class_with_such_this_type(&this_ty.ty, |_, fc| {
+                fc.constructor_ret_type.clone()
+            })
+        {
+            let class_name = foreign_class.name.to_string();
+            let converter = match direction {
+                Direction::Incoming => format!("{}._takeOwnership()", FROM_VAR_TEMPLATE),
+                Direction::Outgoing => format!("{}._({}, true)", class_name, FROM_VAR_TEMPLATE),
+            };
+            return Ok(DartTypeInfo::with_converter(
+                "Pointer<Void>",
+                "Pointer<Void>",
+                &class_name,
+                converter,
+            ));
+        }
+    }
+
+    if let Some(inner_ty) = if_option_return_some_type(arg_ty) {
+        if let Some(class_name) = foreign_class_name(conv_map, &inner_ty, arg_ty_span.0) {
+            let converter = match direction {
+                Direction::Incoming => {
+                    format!("({}?._takeOwnership() ?? nullptr)", FROM_VAR_TEMPLATE)
+                }
+                Direction::Outgoing => format!(
+                    "({var} == nullptr ? null : {class}._({var}, true))",
+                    var = FROM_VAR_TEMPLATE,
+                    class = class_name
+                ),
+            };
+            return Ok(DartTypeInfo::with_converter(
+                "Pointer<Void>",
+                "Pointer<Void>",
+                &format!("{}?", class_name),
+                converter,
+            ));
+        }
+        if c_info.base.name == "const char *" && direction == Direction::Incoming {
+            return Ok(DartTypeInfo {
+                uses_arena: true,
+                ..DartTypeInfo::with_converter(
+                    "Pointer<Utf8>",
+                    "Pointer<Utf8>",
+                    "String?",
+                    format!(
+                        "({}?.toNativeUtf8(allocator: arena) ?? nullptr)",
+                        FROM_VAR_TEMPLATE
+                    ),
+                )
+            });
+        }
+    }
+
+    if arg_ty.normalized_name == "bool" {
+        let converter = match direction {
+            Direction::Incoming => format!("({} ? 1 : 0)", FROM_VAR_TEMPLATE),
+            Direction::Outgoing => format!("({} != 0)", FROM_VAR_TEMPLATE),
+        };
+        return Ok(DartTypeInfo::with_converter(
+            "Uint8", "int", "bool", converter,
+        ));
+    }
+
+    let unsupported = || {
+        DiagnosticError::new(
+            arg_ty_span.0,
+            arg_ty_span.1,
+            format!(
+                "Dart: conversation of {} (C type '{}') as {} is not supported yet",
+                arg_ty,
+                c_info.base.name,
+                match direction {
+                    Direction::Incoming => "input",
+                    Direction::Outgoing => "output",
+                }
+            ),
+        )
+    };
+
+    let primitive = match c_info.base.name.as_str() {
+        "void" => Some(("Void", "void")),
+        "int8_t" => Some(("Int8", "int")),
+        "uint8_t" => Some(("Uint8", "int")),
+        "int16_t" => Some(("Int16", "int")),
+        "uint16_t" => Some(("Uint16", "int")),
+        "int32_t" | "int" => Some(("Int32", "int")),
+        "uint32_t" => Some(("Uint32", "int")),
+        "int64_t" => Some(("Int64", "int")),
+        "uint64_t" => Some(("Uint64", "int")),
+        "uintptr_t" => Some(("UintPtr", "int")),
+        "float" => Some(("Float", "double")),
+        "double" => Some(("Double", "double")),
+        _ => None,
+    };
+    if let Some((native_ty, dart_ty)) = primitive {
+        return Ok(DartTypeInfo::same(native_ty, dart_ty));
+    }
+
+    if let Some(c_struct) = c_info.base.name.strip_prefix("struct ") {
+        if let Some(ret) = map_c_struct(conv_map, arg_ty, c_struct, direction, arg_ty_span)? {
+            return Ok(ret);
+        }
+    }
+
+    match (c_info.base.name.as_str(), direction) {
+        ("const char *", Direction::Incoming) => Ok(DartTypeInfo {
+            uses_arena: true,
+            ..DartTypeInfo::with_converter(
+                "Pointer<Utf8>",
+                "Pointer<Utf8>",
+                "String",
+                format!("{}.toNativeUtf8(allocator: arena)", FROM_VAR_TEMPLATE),
+            )
+        }),
+        ("struct RustStrView", Direction::Outgoing) => Ok(DartTypeInfo::with_converter(
+            "RustStrView",
+            "RustStrView",
+            "String",
+            format!("{}.toDartString()", FROM_VAR_TEMPLATE),
+        )),
+        ("struct CRustString", Direction::Outgoing) => Ok(DartTypeInfo::with_converter(
+            "CRustString",
+            "CRustString",
+            "String",
+            format!("{}.consume()", FROM_VAR_TEMPLATE),
+        )),
+        (c_name, Direction::Incoming) => {
+            let interface_name = c_name
+                .strip_prefix("const struct C_")
+                .and_then(|x| x.strip_suffix(" * const"));
+            if let Some(interface_name) = interface_name {
+                let native_ty = format!("Pointer<C_{}>", interface_name);
+                Ok(DartTypeInfo {
+                    uses_arena: true,
+                    ..DartTypeInfo::with_converter(
+                        &native_ty,
+                        &native_ty,
+                        interface_name,
+                        format!("C_{}.create({}, arena)", interface_name, FROM_VAR_TEMPLATE),
+                    )
+                })
+            } else {
+                Err(unsupported())
+            }
+        }
+        _ => Err(unsupported()),
+    }
+}
+
+/// Map `Option`, `Vec`, slice and `Result` that C API passes as C structs
+fn map_c_struct(
+    conv_map: &mut TypeMap,
+    arg_ty: &RustType,
+    c_struct: &str,
+    direction: Direction,
+    arg_ty_span: SourceIdSpan,
+) -> Result<Option<DartTypeInfo>> {
+    if let Some((_, dart_ty)) = OPTION_STRUCTS.iter().find(|x| x.0 == c_struct) {
+        let dart_ty = format!("{}?", dart_ty);
+        return Ok(Some(match direction {
+            Direction::Incoming => DartTypeInfo {
+                uses_arena: true,
+                ..DartTypeInfo::with_struct(
+                    c_struct,
+                    &dart_ty,
+                    format!("{}.fromNullable({}, arena)", c_struct, FROM_VAR_TEMPLATE),
+                )
+            },
+            Direction::Outgoing => DartTypeInfo::with_struct(
+                c_struct,
+                &dart_ty,
+                format!("{}.toNullable()", FROM_VAR_TEMPLATE),
+            ),
+        }));
+    }
+    if direction == Direction::Outgoing {
+        if c_struct == "CRustOptionStr" {
+            return Ok(Some(DartTypeInfo::with_struct(
+                c_struct,
+                "String?",
+                format!("{}.toNullable()", FROM_VAR_TEMPLATE),
+            )));
+        }
+        if c_struct == "CRustOptionString" {
+            return Ok(Some(DartTypeInfo::with_struct(
+                c_struct,
+                "String?",
+                format!("{}.consume()", FROM_VAR_TEMPLATE),
+            )));
+        }
+        if let Some((_, elem)) = VEC_STRUCTS.iter().find(|x| x.0 == c_struct) {
+            return Ok(Some(DartTypeInfo::with_struct(
+                c_struct,
+                &format!("List<{}>", elem),
+                format!("{}.consume()", FROM_VAR_TEMPLATE),
+            )));
+        }
+    }
+    if SLICE_STRUCTS.contains(&c_struct) {
+        return Ok(Some(match direction {
+            Direction::Incoming => DartTypeInfo {
+                uses_arena: true,
+                ..DartTypeInfo::with_struct(
+                    c_struct,
+                    "List<int>",
+                    format!("{}.fromList({}, arena)", c_struct, FROM_VAR_TEMPLATE),
+                )
+            },
+            Direction::Outgoing => DartTypeInfo::with_struct(
+                c_struct,
+                "List<int>",
+                format!("{}.toList()", FROM_VAR_TEMPLATE),
+            ),
+        }));
+    }
+    if c_struct == "CRustForeignVec" {
+        let class_name = match if_vec_return_elem_type(arg_ty)
+            .and_then(|elem| foreign_class_name(conv_map, &elem, arg_ty_span.0))
+        {
+            Some(x) => x,
+            None => return Ok(None),
+        };
+        let dart_ty = format!("List<{}>", class_name);
+        return Ok(Some(match direction {
+            Direction::Incoming => DartTypeInfo {
+                uses_arena: true,
+                ..DartTypeInfo::with_struct(
+                    c_struct,
+                    &dart_ty,
+                    format!("{}._toVec({}, arena)", class_name, FROM_VAR_TEMPLATE),
+                )
+            },
+            Direction::Outgoing => DartTypeInfo::with_struct(
+                c_struct,
+                &dart_ty,
+                format!("{}._consumeVec({})", class_name, FROM_VAR_TEMPLATE),
+            ),
+        }));
+    }
+    if c_struct == "CRustObjectSlice" {
+        let class_name = match if_type_slice_return_elem_type(&arg_ty.ty, false)
+            .cloned()
+            .and_then(|elem| foreign_class_name(conv_map, &elem, arg_ty_span.0))
+        {
+            Some(x) => x,
+            None => return Ok(None),
+        };
+        if direction == Direction::Incoming {
+            return Err(DiagnosticError::new2(
+                arg_ty_span,
+                format!(
+                    "Dart: {} as input is not supported, objects of Dart classes are not \
+                     placed in one continuous memory block, use Vec instead",
+                    arg_ty
+                ),
+            ));
+        }
+        return Ok(Some(DartTypeInfo::with_struct(
+            c_struct,
+            &format!("List<{}>", class_name),
+            format!("{}._fromSlice({})", class_name, FROM_VAR_TEMPLATE),
+        )));
+    }
+    if c_struct.starts_with("CResult") && direction == Direction::Outgoing {
+        return map_result(conv_map, arg_ty, c_struct, arg_ty_span);
+    }
+    Ok(None)
+}
+
+/// `Ok` of `Result` is returned, `Err` is thrown as `RustException`
+fn map_result(
+    conv_map: &mut TypeMap,
+    arg_ty: &RustType,
+    c_struct: &str,
+    arg_ty_span: SourceIdSpan,
+) -> Result<Option<DartTypeInfo>> {
+    let (ok_ty, err_ty) = match if_result_return_ok_err_types(arg_ty) {
+        Some(x) => x,
+        None => return Ok(None),
+    };
+    let unwrap = match c_struct {
+        "CResultObjectString" | "CResultCRustForeignVecString" => {
+            format!("{}.unwrap()", FROM_VAR_TEMPLATE)
+        }
+        "CResultObjectEnum" => {
+            let err_rust_ty = conv_map.find_or_alloc_rust_type(&err_ty, arg_ty_span.0);
+            let enum_name = match conv_map.is_this_exported_enum(&err_rust_ty) {
+                Some(x) => x.name.to_string(),
+                None => return Ok(None),
+            };
+            format!(
+                "{}.unwrap((e) => {}.fromValue(e))",
+                FROM_VAR_TEMPLATE, enum_name
+            )
+        }
+        "CResultObjectObject"
+        | "CResultI64Object"
+        | "CResultVecObjectObject"
+        | "CResultCRustVecU8Object" => {
+            let err_class = match foreign_class_name(conv_map, &err_ty, arg_ty_span.0) {
+                Some(x) => x,
+                None => return Ok(None),
+            };
+            format!(
+                "{}.unwrap((e) => {}._(e, true))",
+                FROM_VAR_TEMPLATE, err_class
+            )
+        }
+        _ => return Ok(None),
+    };
+    let ok_rust_ty = conv_map.find_or_alloc_rust_type(&ok_ty, arg_ty_span.0);
+    let (dart_ty, converter) = match c_struct {
+        "CResultObjectString" | "CResultObjectObject" | "CResultObjectEnum" => {
+            if is_unit(&ok_ty) {
+                ("void".to_string(), unwrap)
+            } else {
+                match foreign_class_name(conv_map, &ok_ty, arg_ty_span.0) {
+                    Some(class_name) => {
+                        let conv = format!("{}._({}, true)", class_name, unwrap);
+                        (class_name, conv)
+                    }
+                    None => return Ok(None),
+                }
+            }
+        }
+        "CResultI64Object" => ("int".to_string(), unwrap),
+        "CResultCRustVecU8Object" => ("List<int>".to_string(), format!("{}.consume()", unwrap)),
+        _ => match if_vec_return_elem_type(&ok_rust_ty)
+            .and_then(|elem| foreign_class_name(conv_map, &elem, arg_ty_span.0))
+        {
+            Some(class_name) => (
+                format!("List<{}>", class_name),
+                format!("{}._consumeVec({})", class_name, unwrap),
+            ),
+            None => return Ok(None),
+        },
+    };
+    Ok(Some(DartTypeInfo::with_struct(
+        c_struct, &dart_ty, converter,
+    )))
+}
@@ -0,0 +1,769 @@
+mod map_type;
+
+use std::{fmt::Write, io::Write as IoWrite, path::Path};
+
+use petgraph::Direction;
+use proc_macro2::TokenStream;
+use rustc_hash::FxHashMap;
+use syn::spanned::Spanned;
+
+use crate::{
+    cpp::{
+        c_func_name, check_no_future_methods, find_suitable_foreign_types_for_methods,
+        find_suitable_ftypes_for_interace_methods, CppForeignMethodSignature,
+    },
+    dart::map_type::map_type,
+    error::{DiagnosticError, Result},
+    file_cache::FileWriteCache,
+    typemap::ast::fn_arg_type,
+    types::{
+        ForeignEnumInfo, ForeignFlagsInfo, ForeignInterface, ForeignStructInfo, ForeignerClassInfo,
+        ForeignerMethod, MethodAccess, MethodVariant,
+    },
+    DartConfig, LanguageGenerator, SourceCode, TypeMap,
+};
+
+impl LanguageGenerator for DartConfig {
+    fn register_class(&self, conv_map: &mut TypeMap, class: &ForeignerClassInfo) -> Result<()> {
+        self.c_abi.register_class(conv_map, class)
+    }
+
+    fn generate(
+        &self,
+        conv_map: &mut TypeMap,
+        pointer_target_width: usize,
+        class: &ForeignerClassInfo,
+    ) -> Result<Vec<TokenStream>> {
+        let code = self.c_abi.generate(conv_map, pointer_target_width, class)?;
+        let m_sigs = find_suitable_foreign_types_for_methods(conv_map, class, &self.c_abi)?;
+        check_no_future_methods(class, &m_sigs, "Dart")?;
+        let dart_code = generate_class(conv_map, self, class, &m_sigs)?;
+        self.write_dart_part(&class.name.to_string(), &dart_code)
+            .map_err(|err| DiagnosticError::new(class.src_id, class.span(), err))?;
+        Ok(code)
+    }
+
+    fn generate_enum(
+        &self,
+        conv_map: &mut TypeMap,
+        pointer_target_width: usize,
+        enum_info: &ForeignEnumInfo,
+    ) -> Result<Vec<TokenStream>> {
+        if enum_info.is_data_carrying() {
+            return Err(DiagnosticError::new(
+                enum_info.src_id,
+                enum_info.span(),
+                "Dart: foreign_enum with data-carrying items is not supported yet",
+            ));
+        }
+        let code = self
+            .c_abi
+            .generate_enum(conv_map, pointer_target_width, enum_info)?;
+        let mut dart_code = self.part_header();
+        dart_code.push_str(&doc_comments_to_dart_comments(&enum_info.doc_comments, ""));
+        writeln!(&mut dart_code, "enum {} {{", enum_info.name).unwrap();
+        for (i, item) in enum_info.items.iter().enumerate() {
+            dart_code.push_str(&doc_comments_to_dart_comments(&item.doc_comments, "  "));
+            writeln!(
+                &mut dart_code,
+                "  {}({}){}",
+                item.name,
+                item.value,
+                if i == enum_info.items.len() - 1 {
+                    ';'
+                } else {
+                    ','
+                }
+            )
+            .unwrap();
+        }
+        writeln!(
+            &mut dart_code,
+            r#"
+  const {enum_name}(this.value);
+  final int value;
+
+  static {enum_name} fromValue(int value) => values.firstWhere((x) => x.value == value);
+}}"#,
+            enum_name = enum_info.name
+        )
+        .unwrap();
+        self.write_dart_part(&enum_info.name.to_string(), &dart_code)
+            .map_err(|err| DiagnosticError::new(enum_info.src_id, enum_info.span(), err))?;
+        Ok(code)
+    }
+
+    fn generate_struct(
+        &self,
+        _conv_map: &mut TypeMap,
+        _pointer_target_width: usize,
+        struct_info: &ForeignStructInfo,
+    ) -> Result<Vec<TokenStream>> {
+        Err(DiagnosticError::new(
+            struct_info.src_id,
+            struct_info.span(),
+            "Dart: foreign_struct is not supported yet",
+        ))
+    }
+
+    fn generate_flags(
+        &self,
+        _conv_map: &mut TypeMap,
+        _pointer_target_width: usize,
+        flags_info: &ForeignFlagsInfo,
+    ) -> Result<Vec<TokenStream>> {
+        Err(DiagnosticError::new(
+            flags_info.src_id,
+            flags_info.span(),
+            "Dart: foreign_flags is not supported yet",
+        ))
+    }
+
+    fn generate_interface(
+        &self,
+        conv_map: &mut TypeMap,
+        pointer_target_width: usize,
+        interface: &ForeignInterface,
+    ) -> Result<Vec<TokenStream>> {
+        let code = self
+            .c_abi
+            .generate_interface(conv_map, pointer_target_width, interface)?;
+        let f_methods =
+            find_suitable_ftypes_for_interace_methods(conv_map, interface, &self.c_abi)?;
+        let dart_code = generate_interface(conv_map, self, interface, &f_methods)?;
+        self.write_dart_part(&interface.name.to_string(), &dart_code)
+            .map_err(|err| DiagnosticError::new(interface.src_id, interface.span(), err))?;
+        Ok(code)
+    }
+
+    fn init(&self, conv_map: &mut TypeMap, code: &[SourceCode]) -> std::result::Result<(), String> {
+        self.c_abi.init(conv_map, &[])?;
+        for cu in code {
+            let src_path = self.output_dir.join(&cu.id_of_code);
+            let mut src_file = FileWriteCache::new(&src_path);
+            src_file
+                .write_all(
+                    cu.code
+                        .replace("RUST_SWIG_DART_LIBRARY", &self.native_lib_name)
+                        .replace("RUST_SWIG_NATIVE_LIB", &self.native_lib_name)
+                        .as_bytes(),
+                )
+                .map_err(|err| format!("write to {} failed: {}", src_path.display(), err))?;
+            src_file
+                .update_file_if_necessary()
+                .map_err(|err| format!("update of {} failed: {}", src_path.display(), err))?;
+            self.parts.borrow_mut().push(cu.id_of_code.clone());
+        }
+        Ok(())
+    }
+
+    fn finish(&self, _conv_map: &mut TypeMap) -> Result<Vec<TokenStream>> {
+        let mut dart_code = r#"// Automaticaly generated by rust_swig
+import 'dart:ffi';
+import 'dart:io' show Platform;
+
+import 'package:ffi/ffi.dart';
+
+"#
+        .to_string();
+        for part in self.parts.borrow().iter() {
+            writeln!(&mut dart_code, "part '{}';", part).unwrap();
+        }
+        write_dart_file(
+            &self.output_dir,
+            &format!("{}.dart", self.native_lib_name),
+            &dart_code,
+        )
+        .map_err(DiagnosticError::new_without_src_info)?;
+        Ok(vec![])
+    }
+}
+
+impl DartConfig {
+    fn part_header(&self) -> String {
+        format!(
+            "// Automaticaly generated by rust_swig\npart of '{}.dart';\n\n",
+            self.native_lib_name
+        )
+    }
+
+    /// Write part of Dart library, it is included into library file in `finish`
+    fn write_dart_part(&self, name: &str, dart_code: &str) -> std::result::Result<(), String> {
+        let file_name = dart_file_name(name);
+        write_dart_file(&self.output_dir, &file_name, dart_code)?;
+        self.parts.borrow_mut().push(file_name);
+        Ok(())
+    }
+}
+
+/// Dart methods can not be overloaded, and constructors are named,
+/// so all names inside class should be unique
+fn check_names(class: &ForeignerClassInfo) -> Result<()> {
+    let mut names = FxHashMap::<String, &ForeignerMethod>::default();
+    for method in &class.methods {
+        if method.is_dummy_constructor() {
+            continue;
+        }
+        if let Some(prev) = names.insert(method_name(method), method) {
+            let mut err = DiagnosticError::new(
+                class.src_id,
+                method.span(),
+                "Dart: methods and constructors with the same name are not supported",
+            );
+            err.span_note((class.src_id, prev.span()), "previous definition here");
+            return Err(err);
+        }
+    }
+    Ok(())
+}
+
+/// Dart reserved words that can not be used as names of members
+const DART_RESERVED_WORDS: &[&str] = &[
+    "assert", "break", "case", "catch", "class", "const", "continue", "default", "do", "else",
+    "enum", "extends", "false", "final", "finally", "for", "if", "in", "is", "null", "rethrow",
+    "return", "super", "switch", "this", "throw", "true", "try", "var", "void", "while", "with",
+];
+
+/// Name of method visible from Dart, reserved words get `_` suffix,
+/// for example `Boo::default` constructor becomes `Boo.default_`
+fn method_name(method: &ForeignerMethod) -> String {
+    let mut name = method.short_name();
+    if DART_RESERVED_WORDS.contains(&name.as_str()) {
+        name.push('_');
+    }
+    match method.access {
+        MethodAccess::Public => name,
+        MethodAccess::Private | MethodAccess::Protected => format!("_{}", name),
+    }
+}
+
+/// Declaration of C function, `lookupFunction` is lazy, because of
+/// top-level variables in Dart initialized on first access
+fn lookup_function(
+    c_func_name: &str,
+    native_ret: &str,
+    native_args: &[String],
+    ffi_ret: &str,
+    ffi_args: &[String],
+) -> String {
+    format!(
+        "final _{c_func_name} = _rustSwigLib.lookupFunction<\n    {native_ret} Function({native_args}),\n    {ffi_ret} Function({ffi_args})>('{c_func_name}');\n",
+        c_func_name = c_func_name,
+        native_ret = native_ret,
+        native_args = native_args.join(", "),
+        ffi_ret = ffi_ret,
+        ffi_args = ffi_args.join(", "),
+    )
+}
+
+/// Wrap `body` into `using` if arguments conversation allocates memory
+fn with_arena(body: &str, uses_arena: bool, indent: &str) -> String {
+    if uses_arena {
+        let mut code = format!("{}return using((Arena arena) {{\n", indent);
+        for line in body.lines() {
+            writeln!(&mut code, "  {}", line).unwrap();
+        }
+        writeln!(&mut code, "{}}});", indent).unwrap();
+        code
+    } else {
+        body.to_string()
+    }
+}
+
+fn generate_class(
+    conv_map: &mut TypeMap,
+    cfg: &DartConfig,
+    class: &ForeignerClassInfo,
+    m_sigs: &[CppForeignMethodSignature],
+) -> Result<String> {
+    check_names(class)?;
+    let class_name = class.name.to_string();
+    let has_constructor = class
+        .methods
+        .iter()
+        .any(|m| m.variant == MethodVariant::Constructor);
+
+    let mut methods = String::new();
+    let mut lookups = String::new();
+
+    for (method, f_method) in class.methods.iter().zip(m_sigs) {
+        if method.is_dummy_constructor() {
+            continue;
+        }
+        let skip_n = match method.variant {
+            MethodVariant::Method(_) => 1,
+            _ => 0,
+        };
+        let mut args = Vec::with_capacity(f_method.input.len());
+        for (arg, c_info) in method
+            .fn_decl
+            .inputs
+            .iter()
+            .skip(skip_n)
+            .zip(&f_method.input)
+        {
+            let arg_rust_ty = conv_map.find_or_alloc_rust_type(fn_arg_type(arg), class.src_id);
+            args.push(map_type(
+                conv_map,
+                &arg_rust_ty,
+                c_info,
+                Direction::Incoming,
+                (class.src_id, fn_arg_type(arg).span()),
+            )?);
+        }
+        let output = match (method.variant, &method.fn_decl.output) {
+            (MethodVariant::Constructor, _) | (_, syn::ReturnType::Default) => None,
+            (_, syn::ReturnType::Type(_, ref ret_ty)) => {
+                let ret_rust_ty = conv_map.find_or_alloc_rust_type(ret_ty, class.src_id);
+                Some(map_type(
+                    conv_map,
+                    &ret_rust_ty,
+                    &f_method.output,
+                    Direction::Outgoing,
+                    (class.src_id, ret_ty.span()),
+                )?)
+            }
+        };
+
+        let c_func_name = c_func_name(class, method);
+        let (mut native_args, mut ffi_args, mut call_args) = match method.variant {
+            MethodVariant::Method(_) => (
+                vec!["Pointer<Void>".to_string()],
+                vec!["Pointer<Void>".to_string()],
+                vec!["_handle".to_string()],
+            ),
+            _ => (vec![], vec![], vec![]),
+        };
+        let mut dart_args = Vec::with_capacity(args.len());
+        for (i, arg) in args.iter().enumerate() {
+            let arg_name = format!("a{}", i);
+            native_args.push(arg.native_ty.clone());
+            ffi_args.push(arg.ffi_ty.clone());
+            call_args.push(arg.convert(&arg_name));
+            dart_args.push(format!("{} {}", arg.dart_ty, arg_name));
+        }
+        if !f_method.checked_enum_args.is_empty() {
+            native_args.push("Pointer<Int8>".to_string());
+            ffi_args.push("Pointer<Int8>".to_string());
+        }
+        let (native_ret, ffi_ret) = match (method.variant, output.as_ref()) {
+            (MethodVariant::Constructor, _) => ("Pointer<Void>", "Pointer<Void>"),
+            (_, Some(out)) => (out.native_ty.as_str(), out.ffi_ty.as_str()),
+            (_, None) => ("Void", "void"),
+        };
+        lookups.push_str(&lookup_function(
+            &c_func_name,
+            native_ret,
+            &native_args,
+            ffi_ret,
+            &ffi_args,
+        ));
+
+        //Rust side reports unknown value of enum argument via out parameter,
+        //memory allocated by `arena` is zeroed
+        let (decl_invalid_arg, check_invalid_arg) = if f_method.checked_enum_args.is_empty() {
+            (String::new(), String::new())
+        } else {
+            call_args.push("swigInvalidArg".to_string());
+            (
+                "    final swigInvalidArg = arena<Int8>();\n".to_string(),
+                format!(
+                    "    if (swigInvalidArg.value != 0) {{\n      throw ArgumentError('unknown value of {} passed to {}.{}');\n    }}\n",
+                    f_method.checked_enum_names(),
+                    class_name,
+                    method_name(method)
+                ),
+            )
+        };
+        let uses_arena = args.iter().any(|x| x.uses_arena) || !check_invalid_arg.is_empty();
+        let call = format!("_{}({})", c_func_name, call_args.join(", "));
+        methods.push_str(&doc_comments_to_dart_comments(&method.doc_comments, "  "));
+        match method.variant {
+            MethodVariant::Constructor => {
+                let body = if check_invalid_arg.is_empty() {
+                    format!("    return {}._({}, true);\n", class_name, call)
+                } else {
+                    format!(
+                        "{}    final ret = {};\n{}    return {}._(ret, true);\n",
+                        decl_invalid_arg, call, check_invalid_arg, class_name
+                    )
+                };
+                write!(
+                    &mut methods,
+                    "  factory {class_name}.{name}({args}) {{\n{body}  }}\n\n",
+                    class_name = class_name,
+                    name = method_name(method),
+                    args = dart_args.join(", "),
+                    body = with_arena(&body, uses_arena, "    "),
+                )
+                .unwrap();
+            }
+            MethodVariant::StaticMethod | MethodVariant::Method(_) => {
+                let body = match output {
+                    //`Result<(), E>`, so only check for error
+                    Some(ref out) if out.dart_ty == "void" => format!(
+                        "{decl_invalid_arg}    final ret = {call};\n{check_invalid_arg}    {conv_ret};\n",
+                        decl_invalid_arg = decl_invalid_arg,
+                        call = call,
+                        check_invalid_arg = check_invalid_arg,
+                        conv_ret = out.convert("ret")
+                    ),
+                    Some(ref out) => format!(
+                        "{decl_invalid_arg}    final ret = {call};\n{check_invalid_arg}    return {conv_ret};\n",
+                        decl_invalid_arg = decl_invalid_arg,
+                        call = call,
+                        check_invalid_arg = check_invalid_arg,
+                        conv_ret = out.convert("ret")
+                    ),
+                    None if uses_arena => format!(
+                        "{}    {};\n{}    return;\n",
+                        decl_invalid_arg, call, check_invalid_arg
+                    ),
+                    None => format!("    {};\n", call),
+                };
+                write!(
+                    &mut methods,
+                    "  {static_}{ret} {method_name}({args}) {{\n{body}  }}\n\n",
+                    static_ = if method.variant == MethodVariant::StaticMethod {
+                        "static "
+                    } else {
+                        ""
+                    },
+                    ret = output
+                        .as_ref()
+                        .map(|x| x.dart_ty.as_str())
+                        .unwrap_or("void"),
+                    method_name = method_name(method),
+                    args = dart_args.join(", "),
+                    body = with_arena(&body, uses_arena, "    "),
+                )
+                .unwrap();
+            }
+        }
+    }
+
+    let mut dart_code = cfg.part_header();
+    dart_code.push_str(&lookups);
+    dart_code.push('\n');
+    if has_constructor {
+        write!(
+            &mut dart_code,
+            r#"final _{class_name}_delete_ptr = _rustSwigLib
+    .lookup<NativeFunction<Void Function(Pointer<Void>)>>('{class_name}_delete');
+final _{class_name}_delete =
+    _{class_name}_delete_ptr.asFunction<void Function(Pointer<Void>)>();
+
+{doc_comments}class {class_name} implements Finalizable {{
+  static final _finalizer = NativeFinalizer(_{class_name}_delete_ptr.cast());
+
+  Pointer<Void> _ptr;
+
+  /// Rust object should be freed by this object
+  final bool _owned;
+
+  {class_name}._(this._ptr, this._owned) {{
+    if (_owned) {{
+      _finalizer.attach(this, _ptr.cast(), detach: this);
+    }}
+  }}
+
+  Pointer<Void> get _handle {{
+    if (_ptr == nullptr) {{
+      throw StateError('{class_name} was disposed');
+    }}
+    return _ptr;
+  }}
+
+  Pointer<Void> _takeOwnership() {{
+    final p = _handle;
+    if (!_owned) {{
+      throw StateError('{class_name} is borrowed from Rust and can not be moved');
+    }}
+    _finalizer.detach(this);
+    _ptr = nullptr;
+    return p;
+  }}
+
+{methods}  /// Free Rust object, after that object can not be used
+  void dispose() {{
+    if (_owned && _ptr != nullptr) {{
+      _finalizer.detach(this);
+      _{class_name}_delete(_ptr);
+    }}
+    _ptr = nullptr;
+  }}
+
+  static List<{class_name}> _consumeVec(CRustForeignVec v) {{
+    return using((Arena arena) {{
+      final p = v.copyTo(arena);
+      final ret = List<{class_name}?>.filled(v.len, null);
+      for (var i = v.len - 1; i >= 0; --i) {{
+        ret[i] = {class_name}._(_RustForeignVec{class_name}_remove(p, i), true);
+      }}
+      _RustForeignVec{class_name}_free(p.ref);
+      return ret.cast<{class_name}>();
+    }});
+  }}
+
+  static CRustForeignVec _toVec(List<{class_name}> a, Allocator allocator) {{
+    final p = allocator<CRustForeignVec>();
+    p.ref.data = nullptr;
+    p.ref.len = 0;
+    p.ref.capacity = 0;
+    p.ref.step = 0;
+    for (final x in a) {{
+      _RustForeignVec{class_name}_push(p, x._takeOwnership());
+    }}
+    return p.ref;
+  }}
+
+  static List<{class_name}> _fromSlice(CRustObjectSlice s) {{
+    return List<{class_name}>.generate(s.len, (i) => {class_name}._(s.elementAt(i), false));
+  }}
+}}
+
+final _RustForeignVec{class_name}_free = _rustSwigLib.lookupFunction<
+    Void Function(CRustForeignVec),
+    void Function(CRustForeignVec)>('RustForeignVec{class_name}_free');
+final _RustForeignVec{class_name}_push = _rustSwigLib.lookupFunction<
+    Void Function(Pointer<CRustForeignVec>, Pointer<Void>),
+    void Function(Pointer<CRustForeignVec>, Pointer<Void>)>('RustForeignVec{class_name}_push');
+final _RustForeignVec{class_name}_remove = _rustSwigLib.lookupFunction<
+    Pointer<Void> Function(Pointer<CRustForeignVec>, UintPtr),
+    Pointer<Void> Function(Pointer<CRustForeignVec>, int)>('RustForeignVec{class_name}_remove');
+"#,
+            class_name = class_name,
+            doc_comments = doc_comments_to_dart_comments(&class.doc_comments, ""),
+            methods = methods,
+        )
+        .unwrap();
+    } else {
+        write!(
+            &mut dart_code,
+            r#"{doc_comments}abstract final class {class_name} {{
+{methods}}}
+"#,
+            class_name = class_name,
+            doc_comments = doc_comments_to_dart_comments(&class.doc_comments, ""),
+            methods = methods.trim_end_matches('\n').to_string() + "\n",
+        )
+        .unwrap();
+    }
+    Ok(dart_code)
+}
+
+fn generate_interface(
+    conv_map: &mut TypeMap,
+    cfg: &DartConfig,
+    interface: &ForeignInterface,
+    f_methods: &[CppForeignMethodSignature],
+) -> Result<String> {
+    let struct_name = format!("C_{}", interface.name);
+    let mut interface_methods = String::new();
+    let mut fields = String::new();
+    let mut callables = String::new();
+    let mut init_fields = String::new();
+    let mut callbacks = String::new();
+
+    for (method, f_method) in interface.items.iter().zip(f_methods) {
+        let mut args = Vec::with_capacity(f_method.input.len());
+        for (arg, c_info) in method.fn_decl.inputs.iter().skip(1).zip(&f_method.input) {
+            let arg_rust_ty = conv_map.find_or_alloc_rust_type(fn_arg_type(arg), interface.src_id);
+            args.push(map_type(
+                conv_map,
+                &arg_rust_ty,
+                c_info,
+                Direction::Outgoing,
+                (interface.src_id, fn_arg_type(arg).span()),
+            )?);
+        }
+        let output = match method.fn_decl.output {
+            syn::ReturnType::Default => None,
+            syn::ReturnType::Type(_, ref ret_ty) => {
+                let ret_rust_ty = conv_map.find_or_alloc_rust_type(ret_ty, interface.src_id);
+                let ret = map_type(
+                    conv_map,
+                    &ret_rust_ty,
+                    &f_method.output,
+                    Direction::Incoming,
+                    (interface.src_id, ret_ty.span()),
+                )?;
+                if ret.uses_arena || (ret.ffi_ty != ret.dart_ty && ret.dart_ty != "bool") {
+                    return Err(DiagnosticError::new(
+                        interface.src_id,
+                        ret_ty.span(),
+                        format!(
+                            "Dart: {} as return type of callback is not supported yet",
+                            ret_rust_ty
+                        ),
+                    ));
+                }
+                Some(ret)
+            }
+        };
+        let dart_ret = output
+            .as_ref()
+            .map(|x| x.dart_ty.as_str())
+            .unwrap_or("void");
+        let (native_ret, ffi_ret) = output
+            .as_ref()
+            .map(|x| (x.native_ty.as_str(), x.ffi_ty.as_str()))
+            .unwrap_or(("Void", "void"));
+        let mut dart_args = Vec::with_capacity(args.len());
+        let mut native_args = Vec::with_capacity(args.len() + 1);
+        let mut ffi_args = Vec::with_capacity(args.len() + 1);
+        let mut call_args = Vec::with_capacity(args.len());
+        for (i, arg) in args.iter().enumerate() {
+            let arg_name = format!("a{}", i);
+            dart_args.push(format!("{} {}", arg.dart_ty, arg_name));
+            native_args.push(arg.native_ty.clone());
+            ffi_args.push(format!("{} {}", arg.ffi_ty, arg_name));
+            call_args.push(arg.convert(&arg_name));
+        }
+        native_args.push("Pointer<Void>".to_string());
+        ffi_args.push("Pointer<Void> opaque".to_string());
+        let native_sig = format!("{} Function({})", native_ret, native_args.join(", "));
+
+        interface_methods.push_str(&doc_comments_to_dart_comments(&method.doc_comments, "  "));
+        writeln!(
+            &mut interface_methods,
+            "  {} {}({});",
+            dart_ret,
+            method.name,
+            dart_args.join(", ")
+        )
+        .unwrap();
+        writeln!(
+            &mut fields,
+            "  external Pointer<NativeFunction<{}>> {};",
+            native_sig, method.name
+        )
+        .unwrap();
+        writeln!(
+            &mut callables,
+            "  static final _{method_name}Callable = NativeCallable<{native_sig}>.isolateLocal(\n      _call_{method_name}{exceptional_return});",
+            method_name = method.name,
+            native_sig = native_sig,
+            exceptional_return = match output {
+                Some(ref out) if out.ffi_ty == "double" => ",\n      exceptionalReturn: 0.0",
+                Some(_) => ",\n      exceptionalReturn: 0",
+                None => "",
+            },
+        )
+        .unwrap();
+        writeln!(
+            &mut init_fields,
+            "    p.ref.{method_name} = _{method_name}Callable.nativeFunction;",
+            method_name = method.name
+        )
+        .unwrap();
+        let call = format!("obj.{}({})", method.name, call_args.join(", "));
+        let call = match output {
+            Some(ref out) => {
+                let conv = out.convert("ret");
+                if conv == "ret" {
+                    format!("return {};", call)
+                } else {
+                    format!("final ret = {};\n    return {};", call, conv)
+                }
+            }
+            None => format!("{};", call),
+        };
+        write!(
+            &mut callbacks,
+            r#"
+  static {ffi_ret} _call_{method_name}({ffi_args}) {{
+    final obj = _objects[opaque.address]!;
+    {call}
+  }}
+"#,
+            ffi_ret = ffi_ret,
+            method_name = method.name,
+            ffi_args = ffi_args.join(", "),
+            call = call,
+        )
+        .unwrap();
+    }
+
+    let mut dart_code = cfg.part_header();
+    write!(
+        &mut dart_code,
+        r#"{doc_comments}abstract interface class {interface_name} {{
+{interface_methods}}}
+
+/// Passed to Rust as `Box<Trait>`, callbacks should be called on
+/// the same thread where `{interface_name}` was passed to Rust
+final class {struct_name} extends Struct {{
+  external Pointer<Void> opaque;
+  external Pointer<NativeFunction<Void Function(Pointer<Void>)>> {struct_name}_deref;
+{fields}
+  static final _objects = <int, {interface_name}>{{}};
+  static int _nextId = 1;
+  static final _derefCallable =
+      NativeCallable<Void Function(Pointer<Void>)>.isolateLocal(_deref);
+{callables}
+  static Pointer<{struct_name}> create({interface_name} obj, Allocator allocator) {{
+    final id = _nextId++;
+    _objects[id] = obj;
+    final p = allocator<{struct_name}>();
+    p.ref.opaque = Pointer<Void>.fromAddress(id);
+    p.ref.{struct_name}_deref = _derefCallable.nativeFunction;
+{init_fields}    return p;
+  }}
+
+  static void _deref(Pointer<Void> opaque) {{
+    _objects.remove(opaque.address);
+  }}
+{callbacks}}}
+"#,
+        doc_comments = doc_comments_to_dart_comments(&interface.doc_comments, ""),
+        interface_name = interface.name,
+        interface_methods = interface_methods,
+        struct_name = struct_name,
+        fields = fields,
+        callables = callables,
+        init_fields = init_fields,
+        callbacks = callbacks,
+    )
+    .unwrap();
+    Ok(dart_code)
+}
+
+fn doc_comments_to_dart_comments(doc_comments: &[String], indent: &str) -> String {
+    let mut comments = String::new();
+    for comment in doc_comments {
+        writeln!(&mut comments, "{}/// {}", indent, comment.trim()).unwrap();
+    }
+    comments
+}
+
+/// Dart convention is `lowercase_with_underscores` for file names
+fn dart_file_name(name: &str) -> String {
+    let mut file_name = String::with_capacity(name.len() + 8);
+    for (i, ch) in name.chars().enumerate() {
+        if ch.is_uppercase() {
+            if i > 0 && !file_name.ends_with('_') {
+                file_name.push('_');
+            }
+            file_name.extend(ch.to_lowercase());
+        } else {
+            file_name.push(ch);
+        }
+    }
+    file_name.push_str(".dart");
+    file_name
+}
+
+fn write_dart_file(
+    output_dir: &Path,
+    file_name: &str,
+    dart_code: &str,
+) -> std::result::Result<(), String> {
+    let path = output_dir.join(file_name);
+    let mut file = FileWriteCache::new(&path);
+    file.write_all(dart_code.as_bytes())
+        .map_err(|err| format!("write to {} failed: {}", path.display(), err))?;
+    file.update_file_if_necessary()
+        .map_err(|err| format!("update of {} failed: {}", path.display(), err))?;
+    Ok(())
+}
@@ -0,0 +1,559 @@
+// Automaticaly generated by rust_swig
+part of 'RUST_SWIG_DART_LIBRARY.dart';
+
+final DynamicLibrary _rustSwigLib = _rustSwigOpen('RUST_SWIG_NATIVE_LIB');
+
+DynamicLibrary _rustSwigOpen(String name) {
+  if (Platform.isIOS) {
+    return DynamicLibrary.process();
+  }
+  if (Platform.isMacOS) {
+    return DynamicLibrary.open('lib$name.dylib');
+  }
+  if (Platform.isWindows) {
+    return DynamicLibrary.open('$name.dll');
+  }
+  return DynamicLibrary.open('lib$name.so');
+}
+
+/// Borrowed UTF-8 string, owned by Rust side
+final class RustStrView extends Struct {
+  external Pointer<Uint8> data;
+  @UintPtr()
+  external int len;
+
+  String toDartString() {
+    return data.cast<Utf8>().toDartString(length: len);
+  }
+}
+
+/// UTF-8 string allocated by Rust side
+final class CRustString extends Struct {
+  external Pointer<Uint8> data;
+  @UintPtr()
+  external int len;
+  @UintPtr()
+  external int capacity;
+
+  /// Copy content to Dart string and free memory
+  String consume() {
+    final s = data.cast<Utf8>().toDartString(length: len);
+    _crust_string_free(this);
+    return s;
+  }
+}
+
+final _crust_string_free = _rustSwigLib.lookupFunction<
+    Void Function(CRustString), void Function(CRustString)>('crust_string_free');
+
+/// Error returned by Rust as `Err` of `Result`
+class RustException implements Exception {
+  /// Message for `String` error, otherwise value of error
+  final Object error;
+
+  RustException(this.error);
+
+  @override
+  String toString() => 'RustException: $error';
+}
+
+final class CRustOptionBool extends Struct {
+  @Uint8()
+  external int val;
+  @Uint8()
+  external int is_some;
+
+  bool? toNullable() => is_some != 0 ? val != 0 : null;
+
+  static CRustOptionBool fromNullable(bool? x, Allocator allocator) {
+    final p = allocator<CRustOptionBool>();
+    p.ref.val = (x ?? false) ? 1 : 0;
+    p.ref.is_some = x != null ? 1 : 0;
+    return p.ref;
+  }
+}
+
+final class CRustOptionF32 extends Struct {
+  @Float()
+  external double val;
+  @Uint8()
+  external int is_some;
+
+  double? toNullable() => is_some != 0 ? val : null;
+
+  static CRustOptionF32 fromNullable(double? x, Allocator allocator) {
+    final p = allocator<CRustOptionF32>();
+    p.ref.val = x ?? 0.0;
+    p.ref.is_some = x != null ? 1 : 0;
+    return p.ref;
+  }
+}
+
+final class CRustOptionF64 extends Struct {
+  @Double()
+  external double val;
+  @Uint8()
+  external int is_some;
+
+  double? toNullable() => is_some != 0 ? val : null;
+
+  static CRustOptionF64 fromNullable(double? x, Allocator allocator) {
+    final p = allocator<CRustOptionF64>();
+    p.ref.val = x ?? 0.0;
+    p.ref.is_some = x != null ? 1 : 0;
+    return p.ref;
+  }
+}
+
+final class CRustOptionI32 extends Struct {
+  @Int32()
+  external int val;
+  @Uint8()
+  external int is_some;
+
+  int? toNullable() => is_some != 0 ? val : null;
+
+  static CRustOptionI32 fromNullable(int? x, Allocator allocator) {
+    final p = allocator<CRustOptionI32>();
+    p.ref.val = x ?? 0;
+    p.ref.is_some = x != null ? 1 : 0;
+    return p.ref;
+  }
+}
+
+final class CRustOptionU32 extends Struct {
+  @Uint32()
+  external int val;
+  @Uint8()
+  external int is_some;
+
+  int? toNullable() => is_some != 0 ? val : null;
+
+  static CRustOptionU32 fromNullable(int? x, Allocator allocator) {
+    final p = allocator<CRustOptionU32>();
+    p.ref.val = x ?? 0;
+    p.ref.is_some = x != null ? 1 : 0;
+    return p.ref;
+  }
+}
+
+final class CRustOptionI64 extends Struct {
+  @Int64()
+  external int val;
+  @Uint8()
+  external int is_some;
+
+  int? toNullable() => is_some != 0 ? val : null;
+
+  static CRustOptionI64 fromNullable(int? x, Allocator allocator) {
+    final p = allocator<CRustOptionI64>();
+    p.ref.val = x ?? 0;
+    p.ref.is_some = x != null ? 1 : 0;
+    return p.ref;
+  }
+}
+
+final class CRustOptionU64 extends Struct {
+  @Uint64()
+  external int val;
+  @Uint8()
+  external int is_some;
+
+  int? toNullable() => is_some != 0 ? val : null;
+
+  static CRustOptionU64 fromNullable(int? x, Allocator allocator) {
+    final p = allocator<CRustOptionU64>();
+    p.ref.val = x ?? 0;
+    p.ref.is_some = x != null ? 1 : 0;
+    return p.ref;
+  }
+}
+
+final class CRustOptionUSize extends Struct {
+  @UintPtr()
+  external int val;
+  @Uint8()
+  external int is_some;
+
+  int? toNullable() => is_some != 0 ? val : null;
+
+  static CRustOptionUSize fromNullable(int? x, Allocator allocator) {
+    final p = allocator<CRustOptionUSize>();
+    p.ref.val = x ?? 0;
+    p.ref.is_some = x != null ? 1 : 0;
+    return p.ref;
+  }
+}
+
+final class CRustOptionStr extends Struct {
+  external RustStrView val;
+  @Uint8()
+  external int is_some;
+
+  String? toNullable() => is_some != 0 ? val.toDartString() : null;
+}
+
+final class CRustOptionString extends Struct {
+  external CRustString val;
+  @Uint8()
+  external int is_some;
+
+  /// Copy content to Dart string and free memory
+  String? consume() => is_some != 0 ? val.consume() : null;
+}
+
+final class CRustVecU8 extends Struct {
+  external Pointer<Uint8> data;
+  @UintPtr()
+  external int len;
+  @UintPtr()
+  external int capacity;
+
+  /// Copy content to Dart list and free memory
+  List<int> consume() {
+    final ret = List<int>.generate(len, (i) => data[i]);
+    _CRustVecU8_free(this);
+    return ret;
+  }
+}
+
+final _CRustVecU8_free = _rustSwigLib.lookupFunction<
+    Void Function(CRustVecU8), void Function(CRustVecU8)>('CRustVecU8_free');
+
+final class CRustVecI32 extends Struct {
+  external Pointer<Int32> data;
+  @UintPtr()
+  external int len;
+  @UintPtr()
+  external int capacity;
+
+  /// Copy content to Dart list and free memory
+  List<int> consume() {
+    final ret = List<int>.generate(len, (i) => data[i]);
+    _CRustVecI32_free(this);
+    return ret;
+  }
+}
+
+final _CRustVecI32_free = _rustSwigLib.lookupFunction<
+    Void Function(CRustVecI32), void Function(CRustVecI32)>('CRustVecI32_free');
+
+final class CRustVecU32 extends Struct {
+  external Pointer<Uint32> data;
+  @UintPtr()
+  external int len;
+  @UintPtr()
+  external int capacity;
+
+  /// Copy content to Dart list and free memory
+  List<int> consume() {
+    final ret = List<int>.generate(len, (i) => data[i]);
+    _CRustVecU32_free(this);
+    return ret;
+  }
+}
+
+final _CRustVecU32_free = _rustSwigLib.lookupFunction<
+    Void Function(CRustVecU32), void Function(CRustVecU32)>('CRustVecU32_free');
+
+final class CRustVecUsize extends Struct {
+  external Pointer<UintPtr> data;
+  @UintPtr()
+  external int len;
+  @UintPtr()
+  external int capacity;
+
+  /// Copy content to Dart list and free memory
+  List<int> consume() {
+    final ret = List<int>.generate(len, (i) => data[i]);
+    _CRustVecUsize_free(this);
+    return ret;
+  }
+}
+
+final _CRustVecUsize_free = _rustSwigLib.lookupFunction<
+    Void Function(CRustVecUsize), void Function(CRustVecUsize)>('CRustVecUsize_free');
+
+final class CRustVecF32 extends Struct {
+  external Pointer<Float> data;
+  @UintPtr()
+  external int len;
+  @UintPtr()
+  external int capacity;
+
+  /// Copy content to Dart list and free memory
+  List<double> consume() {
+    final ret = List<double>.generate(len, (i) => data[i]);
+    _CRustVecF32_free(this);
+    return ret;
+  }
+}
+
+final _CRustVecF32_free = _rustSwigLib.lookupFunction<
+    Void Function(CRustVecF32), void Function(CRustVecF32)>('CRustVecF32_free');
+
+final class CRustVecF64 extends Struct {
+  external Pointer<Double> data;
+  @UintPtr()
+  external int len;
+  @UintPtr()
+  external int capacity;
+
+  /// Copy content to Dart list and free memory
+  List<double> consume() {
+    final ret = List<double>.generate(len, (i) => data[i]);
+    _CRustVecF64_free(this);
+    return ret;
+  }
+}
+
+final _CRustVecF64_free = _rustSwigLib.lookupFunction<
+    Void Function(CRustVecF64), void Function(CRustVecF64)>('CRustVecF64_free');
+
+final class CRustSliceU8 extends Struct {
+  external Pointer<Uint8> data;
+  @UintPtr()
+  external int len;
+
+  List<int> toList() => List<int>.generate(len, (i) => data[i]);
+
+  /// Copy Dart list to memory allocated by `allocator`
+  static CRustSliceU8 fromList(List<int> x, Allocator allocator) {
+    final data = allocator<Uint8>(x.length);
+    for (var i = 0; i < x.length; ++i) {
+      data[i] = x[i];
+    }
+    final p = allocator<CRustSliceU8>();
+    p.ref.data = data;
+    p.ref.len = x.length;
+    return p.ref;
+  }
+}
+
+final class CRustSliceI32 extends Struct {
+  external Pointer<Int32> data;
+  @UintPtr()
+  external int len;
+
+  List<int> toList() => List<int>.generate(len, (i) => data[i]);
+
+  /// Copy Dart list to memory allocated by `allocator`
+  static CRustSliceI32 fromList(List<int> x, Allocator allocator) {
+    final data = allocator<Int32>(x.length);
+    for (var i = 0; i < x.length; ++i) {
+      data[i] = x[i];
+    }
+    final p = allocator<CRustSliceI32>();
+    p.ref.data = data;
+    p.ref.len = x.length;
+    return p.ref;
+  }
+}
+
+final class CRustSliceU32 extends Struct {
+  external Pointer<Uint32> data;
+  @UintPtr()
+  external int len;
+
+  List<int> toList() => List<int>.generate(len, (i) => data[i]);
+
+  /// Copy Dart list to memory allocated by `allocator`
+  static CRustSliceU32 fromList(List<int> x, Allocator allocator) {
+    final data = allocator<Uint32>(x.length);
+    for (var i = 0; i < x.length; ++i) {
+      data[i] = x[i];
+    }
+    final p = allocator<CRustSliceU32>();
+    p.ref.data = data;
+    p.ref.len = x.length;
+    return p.ref;
+  }
+}
+
+final class CRustSliceUsize extends Struct {
+  external Pointer<UintPtr> data;
+  @UintPtr()
+  external int len;
+
+  List<int> toList() => List<int>.generate(len, (i) => data[i]);
+
+  /// Copy Dart list to memory allocated by `allocator`
+  static CRustSliceUsize fromList(List<int> x, Allocator allocator) {
+    final data = allocator<UintPtr>(x.length);
+    for (var i = 0; i < x.length; ++i) {
+      data[i] = x[i];
+    }
+    final p = allocator<CRustSliceUsize>();
+    p.ref.data = data;
+    p.ref.len = x.length;
+    return p.ref;
+  }
+}
+
+/// `Vec` of foreign class objects, elements are moved in and out
+/// by functions generated for each class
+final class CRustForeignVec extends Struct {
+  external Pointer<Void> data;
+  @UintPtr()
+  external int len;
+  @UintPtr()
+  external int capacity;
+  @UintPtr()
+  external int step;
+
+  /// Copy to memory allocated by `allocator`, to pass pointer to Rust
+  Pointer<CRustForeignVec> copyTo(Allocator allocator) {
+    final p = allocator<CRustForeignVec>();
+    p.ref.data = data;
+    p.ref.len = len;
+    p.ref.capacity = capacity;
+    p.ref.step = step;
+    return p;
+  }
+}
+
+/// Slice of foreign class objects borrowed from Rust side
+final class CRustObjectSlice extends Struct {
+  external Pointer<Void> data;
+  @UintPtr()
+  external int len;
+  @UintPtr()
+  external int step;
+
+  Pointer<Void> elementAt(int i) => Pointer<Void>.fromAddress(data.address + i * step);
+}
+
+final class CResultObjectStringUnion extends Union {
+  external Pointer<Void> ok;
+  external CRustString err;
+}
+
+final class CResultObjectString extends Struct {
+  @Uint8()
+  external int is_ok;
+  external CResultObjectStringUnion data;
+
+  /// Return `Ok` value or throw `RustException` with `Err` value
+  Pointer<Void> unwrap() {
+    if (is_ok == 0) {
+      throw RustException(data.err.consume());
+    }
+    return data.ok;
+  }
+}
+
+final class CResultObjectObjectUnion extends Union {
+  external Pointer<Void> ok;
+  external Pointer<Void> err;
+}
+
+final class CResultObjectObject extends Struct {
+  @Uint8()
+  external int is_ok;
+  external CResultObjectObjectUnion data;
+
+  /// Return `Ok` value or throw `RustException` with `Err` value
+  Pointer<Void> unwrap(Object Function(Pointer<Void>) err) {
+    if (is_ok == 0) {
+      throw RustException(err(data.err));
+    }
+    return data.ok;
+  }
+}
+
+final class CResultObjectEnumUnion extends Union {
+  external Pointer<Void> ok;
+  @Uint32()
+  external int err;
+}
+
+final class CResultObjectEnum extends Struct {
+  external CResultObjectEnumUnion data;
+  @Uint8()
+  external int is_ok;
+
+  /// Return `Ok` value or throw `RustException` with `Err` value
+  Pointer<Void> unwrap(Object Function(int) err) {
+    if (is_ok == 0) {
+      throw RustException(err(data.err));
+    }
+    return data.ok;
+  }
+}
+
+final class CResultI64ObjectUnion extends Union {
+  @Int64()
+  external int ok;
+  external Pointer<Void> err;
+}
+
+final class CResultI64Object extends Struct {
+  external CResultI64ObjectUnion data;
+  @Uint8()
+  external int is_ok;
+
+  /// Return `Ok` value or throw `RustException` with `Err` value
+  int unwrap(Object Function(Pointer<Void>) err) {
+    if (is_ok == 0) {
+      throw RustException(err(data.err));
+    }
+    return data.ok;
+  }
+}
+
+final class CResultCRustForeignVecStringUnion extends Union {
+  external CRustForeignVec ok;
+  external CRustString err;
+}
+
+final class CResultCRustForeignVecString extends Struct {
+  @Uint8()
+  external int is_ok;
+  external CResultCRustForeignVecStringUnion data;
+
+  /// Return `Ok` value or throw `RustException` with `Err` value
+  CRustForeignVec unwrap() {
+    if (is_ok == 0) {
+      throw RustException(data.err.consume());
+    }
+    return data.ok;
+  }
+}
+
+final class CResultVecObjectObjectUnion extends Union {
+  external CRustForeignVec ok;
+  external Pointer<Void> err;
+}
+
+final class CResultVecObjectObject extends Struct {
+  @Uint8()
+  external int is_ok;
+  external CResultVecObjectObjectUnion data;
+
+  /// Return `Ok` value or throw `RustException` with `Err` value
+  CRustForeignVec unwrap(Object Function(Pointer<Void>) err) {
+    if (is_ok == 0) {
+      throw RustException(err(data.err));
+    }
+    return data.ok;
+  }
+}
+
+final class CResultCRustVecU8ObjectUnion extends Union {
+  external CRustVecU8 ok;
+  external Pointer<Void> err;
+}
+
+final class CResultCRustVecU8Object extends Struct {
+  external CResultCRustVecU8ObjectUnion data;
+  @Uint8()
+  external int is_ok;
+
+  /// Return `Ok` value or throw `RustException` with `Err` value
+  CRustVecU8 unwrap(Object Function(Pointer<Void>) err) {
+    if (is_ok == 0) {
+      throw RustException(err(data.err));
+    }
+    return data.ok;
+  }
+}
@@ -2,9 +2,9 @@ use std::fmt::{Display, Write};
 
 use crate::{
     source_registry::{SourceId, SourceRegistry},
-    SourceCode,
+    DiagnosticFormat, SourceCode,
 };
-use proc_macro2::Span;
+use proc_macro2::{LineColumn, Span};
 
 pub(crate) type SourceIdSpan = (SourceId, Span);
 
@@ -14,27 +14,32 @@ pub(crate) fn invalid_src_id_span() -> SourceIdSpan {
 
 #[derive(Debug)]
 pub(crate) struct DiagnosticError {
-    data: Vec<(SourceId, syn::Error)>,
+    /// Every error with its notes: the first item is error itself, the rest are notes
+    data: Vec<Vec<(SourceId, syn::Error)>>,
 }
 
 impl DiagnosticError {
     pub fn from_syn_err(src_id: SourceId, err: syn::Error) -> Self {
         DiagnosticError {
-            data: vec![(src_id, err)],
+            data: vec![vec![(src_id, err)]],
         }
     }
     pub fn new<T: Display>(src_id: SourceId, sp: Span, err: T) -> Self {
         DiagnosticError {
-            data: vec![(src_id, syn::Error::new(sp, err))],
+            data: vec![vec![(src_id, syn::Error::new(sp, err))]],
         }
     }
     pub fn new2<T: Display>((src_id, sp): SourceIdSpan, err: T) -> Self {
         DiagnosticError {
-            data: vec![(src_id, syn::Error::new(sp, err))],
+            data: vec![vec![(src_id, syn::Error::new(sp, err))]],
         }
     }
+    /// Add note to the last error
     pub fn span_note<T: Display>(&mut self, sp: SourceIdSpan, err: T) {
-        self.data.push((sp.0, syn::Error::new(sp.1, err)));
+        self.data
+            .last_mut()
+            .expect("DiagnosticError without errors")
+            .push((sp.0, syn::Error::new(sp.1, err)));
     }
     pub fn add_span_note<T: Display>(mut self, sp: SourceIdSpan, err: T) -> Self {
         self.span_note(sp, err);
@@ -42,20 +47,163 @@ impl DiagnosticError {
     }
     pub fn new_without_src_info<T: Display>(err: T) -> Self {
         DiagnosticError {
-            data: vec![(SourceId::none(), syn::Error::new(Span::call_site(), err))],
+            data: vec![vec![(
+                SourceId::none(),
+                syn::Error::new(Span::call_site(), err),
+            )]],
         }
     }
+    /// Append errors (with their notes) from `other`
+    pub fn combine(&mut self, mut other: DiagnosticError) {
+        self.data.append(&mut other.data);
+    }
+    /// Number of errors, notes are not counted
+    pub fn errors_count(&self) -> usize {
+        self.data.len()
+    }
 }
 
 impl Display for DiagnosticError {
     fn fmt(&self, f: &mut core::fmt::Formatter) -> core::result::Result<(), core::fmt::Error> {
-        for x in &self.data {
+        for x in self.data.iter().flatten() {
             write!(f, "{}", x.1)?;
         }
         Ok(())
     }
 }
 
+impl DiagnosticError {
+    /// Convert to public representation, with positions in source code instead of spans
+    pub(crate) fn to_public(&self, src_reg: &SourceRegistry) -> Error {
+        let to_diagnostic = |(src_id, err): &(SourceId, syn::Error)| {
+            let location = if src_id.is_none() {
+                None
+            } else {
+                let span = err.span();
+                let (start, end) = (span.start(), span.end());
+                Some(SourceLocation {
+                    file_id: src_reg.src_with_id(*src_id).id_of_code.clone(),
+                    start: (start.line, start.column),
+                    end: (end.line, end.column),
+                })
+            };
+            Diagnostic {
+                message: err.to_string(),
+                location,
+                notes: vec![],
+            }
+        };
+        Error {
+            diagnostics: self
+                .data
+                .iter()
+                .map(|err_with_notes| {
+                    let mut diagnostic = to_diagnostic(&err_with_notes[0]);
+                    diagnostic.notes = err_with_notes[1..].iter().map(&to_diagnostic).collect();
+                    diagnostic
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Error returned by `Generator::try_expand`, contains all found errors
+#[derive(Debug, Clone)]
+pub struct Error {
+    pub(crate) diagnostics: Vec<Diagnostic>,
+}
+
+impl Error {
+    /// Found errors, in order of processing
+    pub fn diagnostics(&self) -> &[Diagnostic] {
+        &self.diagnostics
+    }
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::result::Result<(), core::fmt::Error> {
+        for (i, diagnostic) in self.diagnostics.iter().enumerate() {
+            if i != 0 {
+                writeln!(f)?;
+            }
+            write!(f, "error: {}", diagnostic)?;
+            for note in &diagnostic.notes {
+                write!(f, "\nnote: {}", note)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for Error {}
+
+/// Error or note to error with its position in source code
+#[derive(Debug, Clone)]
+pub struct Diagnostic {
+    message: String,
+    location: Option<SourceLocation>,
+    notes: Vec<Diagnostic>,
+}
+
+impl Diagnostic {
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+    /// `None` if problem is not related to some place in source code
+    pub fn location(&self) -> Option<&SourceLocation> {
+        self.location.as_ref()
+    }
+    /// Additional information, for example place of previous definition
+    pub fn notes(&self) -> &[Diagnostic] {
+        &self.notes
+    }
+}
+
+impl Display for Diagnostic {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::result::Result<(), core::fmt::Error> {
+        match self.location {
+            Some(ref loc) => write!(
+                f,
+                "{}:{}:{}: {}",
+                loc.file_id, loc.start.0, loc.start.1, self.message
+            ),
+            None => write!(f, "{}", self.message),
+        }
+    }
+}
+
+/// Range in source code
+#[derive(Debug, Clone, PartialEq)]
+pub struct SourceLocation {
+    file_id: String,
+    start: (usize, usize),
+    end: (usize, usize),
+}
+
+impl SourceLocation {
+    /// Identifier of source code, for `Generator::try_expand` it is
+    /// "crate_name: path", for `Generator::merge_type_map` it is `id_of_code`
+    pub fn file_id(&self) -> &str {
+        &self.file_id
+    }
+    /// Line (starts from 1) and column (starts from 0) of start of range
+    pub fn start(&self) -> (usize, usize) {
+        self.start
+    }
+    /// Line (starts from 1) and column (starts from 0) of end of range
+    pub fn end(&self) -> (usize, usize) {
+        self.end
+    }
+}
+
+/// Collect `err` into `errors`, so several errors can be reported at once
+pub(crate) fn add_error(errors: &mut Option<DiagnosticError>, err: DiagnosticError) {
+    match errors {
+        Some(ref mut all) => all.combine(err),
+        None => *errors = Some(err),
+    }
+}
+
 pub(crate) type Result<T> = std::result::Result<T, DiagnosticError>;
 
 pub(crate) fn panic_on_syn_error(id_of_code: &str, code: String, err: syn::Error) -> ! {
@@ -64,28 +212,330 @@ pub(crate) fn panic_on_syn_error(id_of_code: &str, code: String, err: syn::Error
         id_of_code: id_of_code.into(),
         code,
     });
-    panic_on_parse_error(&src_reg, &DiagnosticError::from_syn_err(src_id, err));
+    panic_on_parse_error(
+        &src_reg,
+        DiagnosticFormat::Plain,
+        &DiagnosticError::from_syn_err(src_id, err),
+    );
+}
+
+pub(crate) fn panic_on_parse_error(
+    src_reg: &SourceRegistry,
+    format: DiagnosticFormat,
+    main_err: &DiagnosticError,
+) -> ! {
+    let n_errors = main_err.errors_count();
+    match format {
+        DiagnosticFormat::Plain => eprint_plain(src_reg, main_err),
+        DiagnosticFormat::Rustc => {
+            for err_with_notes in &main_err.data {
+                eprint!("{}", render_rustc(src_reg, Level::Error, err_with_notes));
+            }
+            eprintln!("error: {}\n", aborting_message(n_errors));
+        }
+        DiagnosticFormat::Json => {
+            for err_with_notes in &main_err.data {
+                eprintln!("{}", render_json(src_reg, Level::Error, err_with_notes));
+            }
+            eprintln!(
+                "{}",
+                render_json_summary(&format!("error: {}\n\n", aborting_message(n_errors)))
+            );
+        }
+    }
+    panic!();
+}
+
+/// Print non-fatal problems: as `cargo:warning=` lines, so cargo shows them
+/// even if build succeeded, or as JSON to stderr
+pub(crate) fn print_warnings(
+    src_reg: &SourceRegistry,
+    format: DiagnosticFormat,
+    warnings: &[DiagnosticError],
+) {
+    for err_with_notes in warnings.iter().flat_map(|x| x.data.iter()) {
+        match format {
+            DiagnosticFormat::Plain | DiagnosticFormat::Rustc => {
+                print!("{}", render_cargo_warning(src_reg, err_with_notes))
+            }
+            DiagnosticFormat::Json => {
+                eprintln!("{}", render_json(src_reg, Level::Warning, err_with_notes))
+            }
+        }
+    }
 }
 
-pub(crate) fn panic_on_parse_error(src_reg: &SourceRegistry, main_err: &DiagnosticError) -> ! {
+fn eprint_plain(src_reg: &SourceRegistry, main_err: &DiagnosticError) {
     let mut prev_err_src_id = None;
 
-    for (src_id, err) in &main_err.data {
+    for err_with_notes in &main_err.data {
+        for (i, (src_id, err)) in err_with_notes.iter().enumerate() {
+            let kind = if i == 0 { "error" } else { "note" };
+            if src_id.is_none() {
+                eprintln!("{} (without location information): {}", kind, err);
+                continue;
+            }
+            let src = &src_reg.src_with_id(*src_id);
+            if prev_err_src_id.map(|id| id != *src_id).unwrap_or(true) {
+                eprintln!("error in {}", src.id_of_code);
+            }
+            prev_err_src_id = Some(*src_id);
+            eprint_error_location(kind, err, src);
+        }
+    }
+    let n_errors = main_err.errors_count();
+    if n_errors > 1 {
+        eprintln!("aborting due to {} errors", n_errors);
+    }
+}
+
+#[derive(Clone, Copy)]
+enum Level {
+    Error,
+    Warning,
+}
+
+impl Level {
+    fn as_str(self) -> &'static str {
+        match self {
+            Level::Error => "error",
+            Level::Warning => "warning",
+        }
+    }
+}
+
+fn aborting_message(n_errors: usize) -> String {
+    if n_errors == 1 {
+        "aborting due to previous error".into()
+    } else {
+        format!("aborting due to {} previous errors", n_errors)
+    }
+}
+
+/// Place of problem in form suitable for tools: path of file and
+/// positions with line starting from 1 and column (in chars) starting from 0
+struct Location<'a> {
+    file_name: String,
+    code: &'a str,
+    start: LineColumn,
+    end: LineColumn,
+}
+
+impl<'a> Location<'a> {
+    fn new(src_reg: &'a SourceRegistry, src_id: SourceId, err: &syn::Error) -> Option<Self> {
         if src_id.is_none() {
-            eprintln!("Error (without location information): {}", err);
-            continue;
+            return None;
         }
-        let src = &src_reg.src_with_id(*src_id);
-        if prev_err_src_id.map(|id| id != *src_id).unwrap_or(true) {
-            eprintln!("error in {}", src.id_of_code);
+        let span = err.span();
+        if span.start().line == 0 {
+            return None;
         }
-        prev_err_src_id = Some(*src_id);
-        eprint_error_location(err, src);
+        Some(Location {
+            file_name: src_reg.file_name(src_id),
+            code: &src_reg.src_with_id(src_id).code,
+            start: span.start(),
+            end: span.end(),
+        })
+    }
+    /// Lines of code covered by location, with line numbers
+    fn lines(&self) -> impl Iterator<Item = (usize, &'a str)> {
+        self.code
+            .split('\n')
+            .map(|line| line.trim_end_matches('\r'))
+            .enumerate()
+            .map(|(i, line)| (i + 1, line))
+            .skip(self.start.line - 1)
+            .take(self.end.line - self.start.line + 1)
+    }
+    fn byte_offset(&self, pos: LineColumn) -> usize {
+        let mut offset = 0;
+        for (i, line) in self.code.split('\n').enumerate() {
+            if i + 1 == pos.line {
+                return offset
+                    + line
+                        .char_indices()
+                        .nth(pos.column)
+                        .map(|(idx, _)| idx)
+                        .unwrap_or_else(|| line.len());
+            }
+            offset += line.len() + 1;
+        }
+        offset
     }
-    panic!();
 }
 
-fn eprint_error_location(err: &syn::Error, src: &SourceCode) {
+/// Render error or warning with notes like rustc does:
+/// `error: message`, `--> file:line:col` and code with underlined problem
+fn render_rustc(
+    src_reg: &SourceRegistry,
+    level: Level,
+    err_with_notes: &[(SourceId, syn::Error)],
+) -> String {
+    let mut out = String::new();
+    for (i, (src_id, err)) in err_with_notes.iter().enumerate() {
+        let kind = if i == 0 { level.as_str() } else { "note" };
+        writeln!(&mut out, "{}: {}", kind, err).expect("write to String failed");
+        let loc = match Location::new(src_reg, *src_id, err) {
+            Some(loc) => loc,
+            None => continue,
+        };
+        let (line_no, line) = match loc.lines().next() {
+            Some(x) => x,
+            None => continue,
+        };
+        let line_no = line_no.to_string();
+        let pad = " ".repeat(line_no.len());
+        let underline_end = if loc.start.line == loc.end.line {
+            loc.end.column
+        } else {
+            line.chars().count()
+        };
+        let underline_len = underline_end.saturating_sub(loc.start.column).max(1);
+        writeln!(
+            &mut out,
+            "{pad}--> {file}:{line}:{col}\n{pad} |\n{line_no} | {code}\n{pad} | {indent}{underline}",
+            pad = pad,
+            file = loc.file_name,
+            line = loc.start.line,
+            col = loc.start.column + 1,
+            line_no = line_no,
+            code = line,
+            indent = " ".repeat(loc.start.column),
+            underline = "^".repeat(underline_len),
+        )
+        .expect("write to String failed");
+    }
+    out.push('\n');
+    out
+}
+
+/// Render warning with notes as `cargo:warning=` lines,
+/// cargo can show only one line per `cargo:warning`
+fn render_cargo_warning(
+    src_reg: &SourceRegistry,
+    err_with_notes: &[(SourceId, syn::Error)],
+) -> String {
+    let mut out = String::new();
+    for (i, (src_id, err)) in err_with_notes.iter().enumerate() {
+        let msg = match Location::new(src_reg, *src_id, err) {
+            Some(loc) => format!(
+                "{}:{}:{}: {}",
+                loc.file_name,
+                loc.start.line,
+                loc.start.column + 1,
+                err
+            ),
+            None => err.to_string(),
+        };
+        for (j, line) in msg.lines().enumerate() {
+            let prefix = if i != 0 && j == 0 { "note: " } else { "" };
+            writeln!(&mut out, "cargo:warning={}{}", prefix, line).expect("write to String failed");
+        }
+    }
+    out
+}
+
+/// Render error or warning with notes as one line of JSON,
+/// in the same schema as rustc's `--error-format=json`
+fn render_json(
+    src_reg: &SourceRegistry,
+    level: Level,
+    err_with_notes: &[(SourceId, syn::Error)],
+) -> String {
+    let (src_id, err) = &err_with_notes[0];
+    let children = err_with_notes[1..]
+        .iter()
+        .map(|(src_id, err)| {
+            format!(
+                r#"{{"message":{},"code":null,"level":"note","spans":{},"children":[],"rendered":null}}"#,
+                json_str(&err.to_string()),
+                render_json_spans(Location::new(src_reg, *src_id, err)),
+            )
+        })
+        .collect::<Vec<_>>();
+    format!(
+        r#"{{"$message_type":"diagnostic","message":{},"code":null,"level":"{}","spans":{},"children":[{}],"rendered":{}}}"#,
+        json_str(&err.to_string()),
+        level.as_str(),
+        render_json_spans(Location::new(src_reg, *src_id, err)),
+        children.join(","),
+        json_str(&render_rustc(src_reg, level, err_with_notes)),
+    )
+}
+
+fn render_json_summary(rendered: &str) -> String {
+    let msg = rendered
+        .trim_end()
+        .trim_start_matches("error: ")
+        .to_string();
+    format!(
+        r#"{{"$message_type":"diagnostic","message":{},"code":null,"level":"error","spans":[],"children":[],"rendered":{}}}"#,
+        json_str(&msg),
+        json_str(rendered),
+    )
+}
+
+fn render_json_spans(loc: Option<Location>) -> String {
+    let loc = match loc {
+        Some(loc) => loc,
+        None => return "[]".into(),
+    };
+    let text = loc
+        .lines()
+        .map(|(line_no, line)| {
+            let highlight_start = if line_no == loc.start.line {
+                loc.start.column + 1
+            } else {
+                1
+            };
+            let highlight_end = if line_no == loc.end.line {
+                loc.end.column + 1
+            } else {
+                line.chars().count() + 1
+            };
+            format!(
+                r#"{{"text":{},"highlight_start":{},"highlight_end":{}}}"#,
+                json_str(line),
+                highlight_start,
+                highlight_end
+            )
+        })
+        .collect::<Vec<_>>();
+    format!(
+        r#"[{{"file_name":{},"byte_start":{},"byte_end":{},"line_start":{},"line_end":{},"column_start":{},"column_end":{},"is_primary":true,"text":[{}],"label":null,"suggested_replacement":null,"suggestion_applicability":null,"expansion":null}}]"#,
+        json_str(&loc.file_name),
+        loc.byte_offset(loc.start),
+        loc.byte_offset(loc.end),
+        loc.start.line,
+        loc.end.line,
+        loc.start.column + 1,
+        loc.end.column + 1,
+        text.join(","),
+    )
+}
+
+/// Quote and escape string for JSON
+fn json_str(s: &str) -> String {
+    let mut ret = String::with_capacity(s.len() + 2);
+    ret.push('"');
+    for ch in s.chars() {
+        match ch {
+            '"' => ret.push_str("\\\""),
+            '\\' => ret.push_str("\\\\"),
+            '\n' => ret.push_str("\\n"),
+            '\r' => ret.push_str("\\r"),
+            '\t' => ret.push_str("\\t"),
+            ch if (ch as u32) < 0x20 => {
+                write!(&mut ret, "\\u{:04x}", ch as u32).expect("write to String failed")
+            }
+            ch => ret.push(ch),
+        }
+    }
+    ret.push('"');
+    ret
+}
+
+fn eprint_error_location(kind: &str, err: &syn::Error, src: &SourceCode) {
     let span = err.span();
     let start = span.start();
     let end = span.end();
@@ -123,11 +573,65 @@ fn eprint_error_location(err: &syn::Error, src: &SourceCode) {
     }
 
     eprintln!(
-        "parsing of {name} failed\nerror: {err}\n{code_problem}\nAt {name}:{line_s}:{col_s}",
+        "parsing of {name} failed\n{kind}: {err}\n{code_problem}\nAt {name}:{line_s}:{col_s}",
         name = src.id_of_code,
+        kind = kind,
         err = err,
         code_problem = code_problem,
         line_s = start.line,
         col_s = start.column,
     );
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+    use syn::spanned::Spanned;
+
+    #[test]
+    fn test_render_rustc_and_json() {
+        let code = "fn f(a: Foo) {}";
+        let mut src_reg = SourceRegistry::default();
+        let src_id = src_reg.register_file(
+            SourceCode {
+                id_of_code: "test: src/a.rs".into(),
+                code: code.into(),
+            },
+            Path::new("src/a.rs"),
+        );
+        let item: syn::ItemFn = syn::parse_str(code).unwrap();
+        let ty_span = match item.decl.inputs[0] {
+            syn::FnArg::Captured(ref arg) => arg.ty.span(),
+            _ => unreachable!(),
+        };
+        let err = DiagnosticError::new(src_id, ty_span, "unknown type \"Foo\"");
+
+        assert_eq!(
+            r#"error: unknown type "Foo"
+ --> src/a.rs:1:9
+  |
+1 | fn f(a: Foo) {}
+  |         ^^^
+
+"#,
+            render_rustc(&src_reg, Level::Error, &err.data[0])
+        );
+        assert_eq!(
+            "cargo:warning=src/a.rs:1:9: unknown type \"Foo\"\n",
+            render_cargo_warning(&src_reg, &err.data[0])
+        );
+        let json = render_json(&src_reg, Level::Warning, &err.data[0]);
+        for pat in &[
+            r#""message":"unknown type \"Foo\"""#,
+            r#""level":"warning""#,
+            r#""file_name":"src/a.rs","byte_start":8,"byte_end":11"#,
+            r#""line_start":1,"line_end":1,"column_start":9,"column_end":12"#,
+            r#""text":[{"text":"fn f(a: Foo) {}","highlight_start":9,"highlight_end":12}]"#,
+            r#""rendered":"warning: unknown type \"Foo\"\n --> src/a.rs:1:9\n"#,
+        ] {
+            assert!(json.contains(pat), "no '{}' in {}", pat, json);
+        }
+        assert!(!json.contains('\n'));
+    }
+}
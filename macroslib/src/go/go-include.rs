@@ -0,0 +1,25 @@
+foreign_typemap!(
+    define_c_type!(
+        #[repr(C)]
+        pub struct concat_ident!(CResult, swig_i_type!(T), String) {
+            is_ok: u8,
+            ok: swig_i_type!(T),
+            err: CRustString,
+        }
+    );
+    ($p:r_type) <T> Result<T, String> => concat_ident!(CResult, swig_i_type!(T), String) {
+        $out = match $p {
+            Ok(x) => concat_ident!(CResult, swig_i_type!(T), String) {
+                is_ok: 1,
+                ok: convert_to_c!(x),
+                err: CRustString::from_string(String::new()),
+            },
+            Err(err) => concat_ident!(CResult, swig_i_type!(T), String) {
+                is_ok: 0,
+                ok: unsafe { ::std::mem::zeroed() },
+                err: CRustString::from_string(err),
+            },
+        }
+    };
+    ($p:f_type) => "struct concat_ident!(CResult, swig_i_type!(T), String)";
+);
@@ -0,0 +1,685 @@
+use petgraph::Direction;
+use syn::Type;
+
+use crate::{
+    cpp::{map_output_c_type, CppForeignTypeInfo},
+    error::{DiagnosticError, Result, SourceIdSpan},
+    typemap::{
+        ast::{
+            if_option_return_some_type, if_result_return_ok_err_types,
+            if_type_slice_return_elem_type, if_vec_return_elem_type,
+        },
+        ty::RustType,
+        FROM_VAR_TEMPLATE, TO_VAR_TEMPLATE,
+    },
+    CppConfig, TypeMap,
+};
+
+/// Suffixes of `CRustOption*`, `CRustVec*` and `CRustSlice*` structs
+/// with plain values, and Go and cgo types of these values
+const PLAIN_TYPES: [(&str, &str, &str); 9] = [
+    ("Bool", "bool", "C.uint8_t"),
+    ("U8", "uint8", "C.uint8_t"),
+    ("I32", "int32", "C.int32_t"),
+    ("U32", "uint32", "C.uint32_t"),
+    ("I64", "int64", "C.int64_t"),
+    ("U64", "uint64", "C.uint64_t"),
+    ("F32", "float32", "C.float"),
+    ("F64", "float64", "C.double"),
+    ("Usize", "uintptr", "C.uintptr_t"),
+];
+
+/// How value of some Rust type passed through C API looks in Go
+#[derive(Debug)]
+pub(in crate::go) struct GoTypeInfo {
+    /// Type of value in cgo, like `C.int32_t`
+    pub(in crate::go) c_ty: String,
+    /// Type visible to user of generated package
+    pub(in crate::go) go_ty: String,
+    /// Expression that converts `go_ty` -> `c_ty` for `Direction::Incoming`
+    /// and `c_ty` -> `go_ty` for `Direction::Outgoing`,
+    /// `TO_VAR_TEMPLATE` is temporary variable created by `prelude`
+    converter: String,
+    /// Statements executed before conversation, for example temporary
+    /// C string allocation with `defer` to free it, or copy of Rust vector
+    /// to Go slice
+    prelude: Option<String>,
+    /// Statements that return result of function instead of `return converter`,
+    /// used for `Result` that becomes `(T, error)`
+    ret_stmts: Option<String>,
+    /// Packages that should be imported to use converter and prelude
+    pub(in crate::go) imports: Vec<&'static str>,
+    /// C header that should be included to use converter and prelude
+    pub(in crate::go) c_include: Option<String>,
+    /// Go object borrowed by C function, it should not be finalized until call ends
+    pub(in crate::go) borrowed_object: bool,
+}
+
+impl GoTypeInfo {
+    fn new(c_ty: &str, go_ty: &str, converter: String) -> Self {
+        GoTypeInfo {
+            c_ty: c_ty.into(),
+            go_ty: go_ty.into(),
+            converter,
+            prelude: None,
+            ret_stmts: None,
+            imports: vec![],
+            c_include: None,
+            borrowed_object: false,
+        }
+    }
+
+    pub(in crate::go) fn convert(&self, var_name: &str) -> String {
+        self.converter
+            .replace(TO_VAR_TEMPLATE, &format!("{}C", var_name))
+            .replace(FROM_VAR_TEMPLATE, var_name)
+    }
+
+    pub(in crate::go) fn prelude(&self, var_name: &str) -> Option<String> {
+        self.prelude.as_ref().map(|code| {
+            code.replace(TO_VAR_TEMPLATE, &format!("{}C", var_name))
+                .replace(FROM_VAR_TEMPLATE, var_name)
+        })
+    }
+
+    /// Statements that convert `var_name` and return it from function
+    pub(in crate::go) fn return_stmts(&self, var_name: &str) -> String {
+        match (&self.ret_stmts, self.prelude(var_name)) {
+            (Some(ref code), _) => code.replace(FROM_VAR_TEMPLATE, var_name),
+            (None, Some(prelude)) => format!("{}\n\treturn {}", prelude, self.convert(var_name)),
+            (None, None) => format!("return {}", self.convert(var_name)),
+        }
+    }
+
+    /// Conversation is one expression, without statements before it
+    pub(in crate::go) fn is_simple(&self) -> bool {
+        self.ret_stmts.is_none() && self.prelude.is_none()
+    }
+
+    /// C spelling of `c_ty`, the same as cgo uses for `//export` functions
+    pub(in crate::go) fn c_decl_ty(&self) -> String {
+        if let Some(pointee) = self.c_ty.strip_prefix("*C.") {
+            format!("{}*", c_type_name(pointee))
+        } else if let Some(name) = self.c_ty.strip_prefix("C.") {
+            c_type_name(name)
+        } else {
+            self.c_ty.clone()
+        }
+    }
+}
+
+fn c_type_name(cgo_name: &str) -> String {
+    match cgo_name.strip_prefix("struct_") {
+        Some(name) => format!("struct {}", name),
+        None => cgo_name.into(),
+    }
+}
+
+/// Name of Go struct for foreign class, if `ty` is foreign class
+fn foreign_class_name(conv_map: &TypeMap, ty: &RustType) -> Option<String> {
+    let this_ty = conv_map.is_ty_implements(ty, "SwigForeignClass")?;
+    conv_map
+        .find_foreigner_class_with_such_this_type(&this_ty.ty, |_, fc| {
+            fc.constructor_ret_type.clone()
+        })
+        .map(|fc| fc.name.to_string())
+}
+
+/// Name of Go struct for foreign class, if `ty` is reference to foreign class
+fn foreign_class_ref_name(
+    conv_map: &mut TypeMap,
+    ty: &Type,
+    arg_ty_span: SourceIdSpan,
+) -> Option<String> {
+    match ty {
+        Type::Reference(syn::TypeReference { ref elem, .. }) => {
+            let elem_rust_ty = conv_map.find_or_alloc_rust_type(elem, arg_ty_span.0);
+            conv_map
+                .find_foreigner_class_with_such_self_type(&elem_rust_ty, false)
+                .map(|fc| fc.name.to_string())
+        }
+        _ => None,
+    }
+}
+
+fn is_unit(ty: &Type) -> bool {
+    match ty {
+        Type::Tuple(t) => t.elems.is_empty(),
+        _ => false,
+    }
+}
+
+/// Zero value of Go type, returned together with error
+fn go_zero_value(go_ty: &str) -> &'static str {
+    match go_ty {
+        "bool" => "false",
+        "string" => "\"\"",
+        _ if go_ty.starts_with('*') || go_ty.starts_with("[]") => "nil",
+        _ => "0",
+    }
+}
+
+fn plain_type(c_name: &str, prefix: &str) -> Option<(&'static str, &'static str, &'static str)> {
+    let suffix = c_name.strip_prefix(prefix)?;
+    PLAIN_TYPES
+        .iter()
+        .find(|x| x.0.eq_ignore_ascii_case(suffix))
+        .cloned()
+}
+
+pub(in crate::go) fn map_type(
+    conv_map: &mut TypeMap,
+    c_abi: &CppConfig,
+    arg_ty: &RustType,
+    c_info: &CppForeignTypeInfo,
+    direction: Direction,
+    arg_ty_span: SourceIdSpan,
+) -> Result<GoTypeInfo> {
+    let c_name = c_info.base.name.as_str();
+    if let Some(foreign_enum) = conv_map.is_this_exported_enum(arg_ty) {
+        let enum_name = foreign_enum.name.to_string();
+        let converter = match direction {
+            Direction::Incoming => format!("C.uint32_t({})", FROM_VAR_TEMPLATE),
+            Direction::Outgoing => format!("{}({})", enum_name, FROM_VAR_TEMPLATE),
+        };
+        return Ok(GoTypeInfo::new("C.uint32_t", &enum_name, converter));
+    }
+
+    if let Some(class_name) = foreign_class_ref_name(conv_map, &arg_ty.ty, arg_ty_span) {
+        let converter = match direction {
+            Direction::Incoming => format!("{}.handle()", FROM_VAR_TEMPLATE),
+            Direction::Outgoing => format!("new{}({}, false)", class_name, FROM_VAR_TEMPLATE),
+        };
+        return Ok(GoTypeInfo {
+            borrowed_object: direction == Direction::Incoming,
+            ..GoTypeInfo::new(
+                &format!("*C.{}Opaque", class_name),
+                &format!("*{}", class_name),
+                converter,
+            )
+        });
+    }
+
+    if let Some(class_name) = foreign_class_name(conv_map, arg_ty) {
+        let converter = match direction {
+            Direction::Incoming => format!("{}.takeOwnership()", FROM_VAR_TEMPLATE),
+            Direction::Outgoing => format!("new{}({}, true)", class_name, FROM_VAR_TEMPLATE),
+        };
+        return Ok(GoTypeInfo::new(
+            &format!("*C.{}Opaque", class_name),
+            &format!("*{}", class_name),
+            converter,
+        ));
+    }
+
+    if arg_ty.normalized_name == "bool" {
+        let converter = match direction {
+            Direction::Incoming => format!("swigBoolToC({})", FROM_VAR_TEMPLATE),
+            Direction::Outgoing => format!("({} != 0)", FROM_VAR_TEMPLATE),
+        };
+        return Ok(GoTypeInfo::new("C.char", "bool", converter));
+    }
+
+    if if_result_return_ok_err_types(arg_ty).is_some() {
+        return map_result(conv_map, c_abi, arg_ty, c_name, direction, arg_ty_span);
+    }
+    if let Some(some_ty) = if_option_return_some_type(arg_ty) {
+        return map_option(conv_map, arg_ty, &some_ty, c_name, direction, arg_ty_span);
+    }
+
+    let primitive = match c_name {
+        "int8_t" => Some("int8"),
+        "uint8_t" => Some("uint8"),
+        "int16_t" => Some("int16"),
+        "uint16_t" => Some("uint16"),
+        "int32_t" => Some("int32"),
+        "uint32_t" => Some("uint32"),
+        "int64_t" => Some("int64"),
+        "uint64_t" => Some("uint64"),
+        "uintptr_t" => Some("uintptr"),
+        "float" => Some("float32"),
+        "double" => Some("float64"),
+        _ => None,
+    };
+    if let Some(go_ty) = primitive {
+        let c_ty = format!("C.{}", c_name);
+        let converter = match direction {
+            Direction::Incoming => format!("{}({})", c_ty, FROM_VAR_TEMPLATE),
+            Direction::Outgoing => format!("{}({})", go_ty, FROM_VAR_TEMPLATE),
+        };
+        return Ok(GoTypeInfo::new(&c_ty, go_ty, converter));
+    }
+
+    if let Some((suffix, go_elem, c_elem)) = plain_type(c_name, "struct CRustSlice") {
+        let c_ty = format!("C.struct_CRustSlice{}", suffix);
+        let go_ty = format!("[]{}", go_elem);
+        return Ok(match direction {
+            Direction::Outgoing => GoTypeInfo {
+                imports: vec!["unsafe"],
+                ..GoTypeInfo::new(
+                    &c_ty,
+                    &go_ty,
+                    format!(
+                        "swigCopySlice[{elem}](unsafe.Pointer({from}.data), {from}.len)",
+                        elem = go_elem,
+                        from = FROM_VAR_TEMPLATE
+                    ),
+                )
+            },
+            Direction::Incoming => GoTypeInfo {
+                prelude: Some(format!(
+                    "var {to} {c_ty}\n\tif len({from}) > 0 {{\n\t\t{to}.data = (*{c_elem})(unsafe.Pointer(&{from}[0]))\n\t\t{to}.len = C.uintptr_t(len({from}))\n\t}}",
+                    to = TO_VAR_TEMPLATE,
+                    from = FROM_VAR_TEMPLATE,
+                    c_ty = c_ty,
+                    c_elem = c_elem,
+                )),
+                imports: vec!["unsafe"],
+                ..GoTypeInfo::new(&c_ty, &go_ty, TO_VAR_TEMPLATE.into())
+            },
+        });
+    }
+
+    if let Some((suffix, go_elem, _)) = plain_type(c_name, "struct CRustVec") {
+        if direction == Direction::Outgoing {
+            return Ok(GoTypeInfo {
+                prelude: Some(format!(
+                    "{to} := swigCopySlice[{elem}](unsafe.Pointer({from}.data), {from}.len)\n\tC.CRustVec{suffix}_free({from})",
+                    to = TO_VAR_TEMPLATE,
+                    from = FROM_VAR_TEMPLATE,
+                    elem = go_elem,
+                    suffix = suffix,
+                )),
+                imports: vec!["unsafe"],
+                ..GoTypeInfo::new(
+                    &format!("C.struct_CRustVec{}", suffix),
+                    &format!("[]{}", go_elem),
+                    TO_VAR_TEMPLATE.into(),
+                )
+            });
+        }
+    }
+
+    match (c_name, direction) {
+        ("struct CRustForeignVec", _) => map_foreign_vec(conv_map, arg_ty, direction, arg_ty_span),
+        ("struct CRustObjectSlice", _) => {
+            map_object_slice(conv_map, arg_ty, direction, arg_ty_span)
+        }
+        ("const char *", Direction::Incoming) => Ok(GoTypeInfo {
+            prelude: Some(format!(
+                "{to} := C.CString({from})\n\tdefer C.free(unsafe.Pointer({to}))",
+                to = TO_VAR_TEMPLATE,
+                from = FROM_VAR_TEMPLATE
+            )),
+            imports: vec!["unsafe"],
+            ..GoTypeInfo::new("*C.char", "string", TO_VAR_TEMPLATE.into())
+        }),
+        ("struct RustStrView", Direction::Outgoing) => Ok(GoTypeInfo::new(
+            "C.struct_RustStrView",
+            "string",
+            format!("swigStrViewToString({})", FROM_VAR_TEMPLATE),
+        )),
+        ("struct CRustString", Direction::Outgoing) => Ok(GoTypeInfo::new(
+            "C.struct_CRustString",
+            "string",
+            format!("swigConsumeString({})", FROM_VAR_TEMPLATE),
+        )),
+        (c_name, Direction::Incoming) => {
+            let interface_name = c_name
+                .strip_prefix("const struct C_")
+                .and_then(|x| x.strip_suffix(" * const"));
+            if let Some(interface_name) = interface_name {
+                Ok(GoTypeInfo {
+                    prelude: Some(format!(
+                        "{} := newC_{}({})",
+                        TO_VAR_TEMPLATE, interface_name, FROM_VAR_TEMPLATE
+                    )),
+                    ..GoTypeInfo::new(
+                        &format!("*C.struct_C_{}", interface_name),
+                        interface_name,
+                        format!("&{}", TO_VAR_TEMPLATE),
+                    )
+                })
+            } else {
+                Err(unsupported(arg_ty, c_name, direction, arg_ty_span))
+            }
+        }
+        _ => Err(unsupported(arg_ty, c_name, direction, arg_ty_span)),
+    }
+}
+
+fn unsupported(
+    arg_ty: &RustType,
+    c_name: &str,
+    direction: Direction,
+    arg_ty_span: SourceIdSpan,
+) -> DiagnosticError {
+    DiagnosticError::new2(
+        arg_ty_span,
+        format!(
+            "Go: conversation of {} (C type '{}') as {} is not supported yet",
+            arg_ty,
+            c_name,
+            match direction {
+                Direction::Incoming => "input",
+                Direction::Outgoing => "output",
+            }
+        ),
+    )
+}
+
+/// `Result<T, String>` becomes `(T, error)`, and `Result<(), String>` becomes `error`
+fn map_result(
+    conv_map: &mut TypeMap,
+    c_abi: &CppConfig,
+    arg_ty: &RustType,
+    c_name: &str,
+    direction: Direction,
+    arg_ty_span: SourceIdSpan,
+) -> Result<GoTypeInfo> {
+    let (ok_ty, err_ty) = if_result_return_ok_err_types(arg_ty)
+        .ok_or_else(|| unsupported(arg_ty, c_name, direction, arg_ty_span))?;
+    if direction == Direction::Incoming {
+        return Err(DiagnosticError::new2(
+            arg_ty_span,
+            format!(
+                "Go: {} as input is not supported, Result can only be returned from Rust",
+                arg_ty
+            ),
+        ));
+    }
+    let err_rust_ty = conv_map.find_or_alloc_rust_type(&err_ty, arg_ty_span.0);
+    if err_rust_ty.normalized_name != "String" {
+        return Err(DiagnosticError::new2(
+            arg_ty_span,
+            format!(
+                "Go: {} is not supported yet, only String can be used as error type of Result",
+                arg_ty
+            ),
+        ));
+    }
+    let c_ty = match c_name.strip_prefix("struct ") {
+        Some(name) if name.starts_with("CResult") => format!("C.struct_{}", name),
+        _ => return Err(unsupported(arg_ty, c_name, direction, arg_ty_span)),
+    };
+    if is_unit(&ok_ty) {
+        return Ok(GoTypeInfo {
+            ret_stmts: Some(format!(
+                "if {from}.is_ok == 0 {{\n\t\treturn swigConsumeError(*(*C.struct_CRustString)(unsafe.Pointer(&{from}.data)))\n\t}}\n\treturn nil",
+                from = FROM_VAR_TEMPLATE
+            )),
+            imports: vec!["unsafe"],
+            ..GoTypeInfo::new(&c_ty, "error", String::new())
+        });
+    }
+    let ok_rust_ty = conv_map.find_or_alloc_rust_type(&ok_ty, arg_ty_span.0);
+    if conv_map.is_this_exported_enum(&ok_rust_ty).is_some() {
+        return Err(DiagnosticError::new2(
+            arg_ty_span,
+            format!(
+                "Go: {} is not supported yet, enum can not be used as ok type of Result",
+                arg_ty
+            ),
+        ));
+    }
+    let ok_c_info = map_output_c_type(conv_map, c_abi, &ok_rust_ty, arg_ty_span)?;
+    let ok = map_type(
+        conv_map,
+        c_abi,
+        &ok_rust_ty,
+        &ok_c_info,
+        Direction::Outgoing,
+        arg_ty_span,
+    )?;
+    if ok.ret_stmts.is_some() {
+        return Err(DiagnosticError::new2(
+            arg_ty_span,
+            format!(
+                "Go: {} is not supported, Result can not be used as ok type of Result",
+                arg_ty
+            ),
+        ));
+    }
+    // `ok` and `err` of structs from C API are placed in union `data`,
+    // structs from `go-include.rs` have separate fields
+    let (ok_field, err_field) = match c_name {
+        "struct CResultObjectString" | "struct CResultCRustForeignVecString" => (
+            format!(
+                "*(*{})(unsafe.Pointer(&{}.data))",
+                ok.c_ty, FROM_VAR_TEMPLATE
+            ),
+            format!(
+                "*(*C.struct_CRustString)(unsafe.Pointer(&{}.data))",
+                FROM_VAR_TEMPLATE
+            ),
+        ),
+        _ => (
+            format!("{}.ok", FROM_VAR_TEMPLATE),
+            format!("{}.err", FROM_VAR_TEMPLATE),
+        ),
+    };
+    let mut ret_stmts = format!(
+        "if {from}.is_ok == 0 {{\n\t\treturn {zero}, swigConsumeError({err})\n\t}}\n\tok := {ok}\n\t",
+        from = FROM_VAR_TEMPLATE,
+        zero = go_zero_value(&ok.go_ty),
+        err = err_field,
+        ok = ok_field,
+    );
+    if let Some(prelude) = ok.prelude("ok") {
+        ret_stmts.push_str(&prelude);
+        ret_stmts.push_str("\n\t");
+    }
+    ret_stmts.push_str(&format!("return {}, nil", ok.convert("ok")));
+    let mut imports = ok.imports.clone();
+    if ok_field.contains("unsafe.Pointer") {
+        imports.push("unsafe");
+    }
+    Ok(GoTypeInfo {
+        ret_stmts: Some(ret_stmts),
+        imports,
+        c_include: ok.c_include.clone(),
+        ..GoTypeInfo::new(&c_ty, &format!("({}, error)", ok.go_ty), String::new())
+    })
+}
+
+/// `Option<T>` becomes pointer to Go value or nil, `Option` of foreign class
+/// becomes nil-able pointer to Go struct
+fn map_option(
+    conv_map: &mut TypeMap,
+    arg_ty: &RustType,
+    some_ty: &Type,
+    c_name: &str,
+    direction: Direction,
+    arg_ty_span: SourceIdSpan,
+) -> Result<GoTypeInfo> {
+    let some_rust_ty = conv_map.find_or_alloc_rust_type(some_ty, arg_ty_span.0);
+    let class = match foreign_class_ref_name(conv_map, some_ty, arg_ty_span) {
+        Some(class_name) => Some((class_name, false)),
+        None => foreign_class_name(conv_map, &some_rust_ty).map(|x| (x, true)),
+    };
+    if let Some((class_name, owned)) = class {
+        let c_ty = format!("*C.{}Opaque", class_name);
+        return Ok(match direction {
+            Direction::Outgoing => GoTypeInfo::new(
+                &c_ty,
+                &format!("*{}", class_name),
+                format!("new{}({}, {})", class_name, FROM_VAR_TEMPLATE, owned),
+            ),
+            Direction::Incoming => GoTypeInfo {
+                prelude: Some(format!(
+                    "var {to} {c_ty}\n\tif {from} != nil {{\n\t\t{to} = {from}.{method}()\n\t}}",
+                    to = TO_VAR_TEMPLATE,
+                    from = FROM_VAR_TEMPLATE,
+                    c_ty = c_ty,
+                    method = if owned { "takeOwnership" } else { "handle" },
+                )),
+                borrowed_object: !owned,
+                ..GoTypeInfo::new(&c_ty, &format!("*{}", class_name), TO_VAR_TEMPLATE.into())
+            },
+        });
+    }
+
+    if let Some((suffix, go_elem, c_elem)) = plain_type(c_name, "struct CRustOption") {
+        let c_ty = format!("C.struct_CRustOption{}", suffix);
+        let go_ty = format!("*{}", go_elem);
+        return Ok(match direction {
+            Direction::Outgoing => {
+                let val = if go_elem == "bool" {
+                    format!("{}.val != 0", FROM_VAR_TEMPLATE)
+                } else {
+                    format!("{}({}.val)", go_elem, FROM_VAR_TEMPLATE)
+                };
+                GoTypeInfo::new(
+                    &c_ty,
+                    &go_ty,
+                    format!("swigOption({}.is_some != 0, {})", FROM_VAR_TEMPLATE, val),
+                )
+            }
+            Direction::Incoming => {
+                let val = if go_elem == "bool" {
+                    format!("{}(swigBoolToC(*{}))", c_elem, FROM_VAR_TEMPLATE)
+                } else {
+                    format!("{}(*{})", c_elem, FROM_VAR_TEMPLATE)
+                };
+                GoTypeInfo {
+                    prelude: Some(format!(
+                        "var {to} {c_ty}\n\tif {from} != nil {{\n\t\t{to} = {c_ty}{{val: {val}, is_some: 1}}\n\t}}",
+                        to = TO_VAR_TEMPLATE,
+                        from = FROM_VAR_TEMPLATE,
+                        c_ty = c_ty,
+                        val = val,
+                    )),
+                    ..GoTypeInfo::new(&c_ty, &go_ty, TO_VAR_TEMPLATE.into())
+                }
+            }
+        });
+    }
+
+    match (c_name, direction) {
+        ("struct CRustOptionStr", Direction::Outgoing) => Ok(GoTypeInfo::new(
+            "C.struct_CRustOptionStr",
+            "*string",
+            format!(
+                "swigOption({from}.is_some != 0, swigStrViewToString({from}.val))",
+                from = FROM_VAR_TEMPLATE
+            ),
+        )),
+        ("struct CRustOptionString", Direction::Outgoing) => Ok(GoTypeInfo::new(
+            "C.struct_CRustOptionString",
+            "*string",
+            format!("swigConsumeOptionString({})", FROM_VAR_TEMPLATE),
+        )),
+        ("const char *", Direction::Incoming) => Ok(GoTypeInfo {
+            prelude: Some(format!(
+                "var {to} *C.char\n\tif {from} != nil {{\n\t\t{to} = C.CString(*{from})\n\t\tdefer C.free(unsafe.Pointer({to}))\n\t}}",
+                to = TO_VAR_TEMPLATE,
+                from = FROM_VAR_TEMPLATE
+            )),
+            imports: vec!["unsafe"],
+            ..GoTypeInfo::new("*C.char", "*string", TO_VAR_TEMPLATE.into())
+        }),
+        (_, Direction::Incoming) if some_rust_ty.normalized_name == "String" => {
+            Err(DiagnosticError::new2(
+                arg_ty_span,
+                format!(
+                    "Go: {} as input is not supported yet, use Option<&str> instead",
+                    arg_ty
+                ),
+            ))
+        }
+        _ => Err(DiagnosticError::new2(
+            arg_ty_span,
+            format!(
+                "Go: {} is not supported yet, Option can contain only primitive type, \
+                 string or foreign class",
+                arg_ty
+            ),
+        )),
+    }
+}
+
+/// `Vec` of foreign classes becomes Go slice, objects are moved one by one
+fn map_foreign_vec(
+    conv_map: &mut TypeMap,
+    arg_ty: &RustType,
+    direction: Direction,
+    arg_ty_span: SourceIdSpan,
+) -> Result<GoTypeInfo> {
+    let class_name = if_vec_return_elem_type(arg_ty)
+        .map(|elem| conv_map.find_or_alloc_rust_type(&elem, arg_ty_span.0))
+        .and_then(|elem| foreign_class_name(conv_map, &elem))
+        .ok_or_else(|| unsupported(arg_ty, "struct CRustForeignVec", direction, arg_ty_span))?;
+    let prelude = match direction {
+        Direction::Outgoing => format!(
+            "{to} := make([]*{class}, int({from}.len))\n\tfor i := len({to}) - 1; i >= 0; i-- {{\n\t\t{to}[i] = new{class}(C.RustForeignVec{class}_remove(&{from}, C.uintptr_t(i)), true)\n\t}}\n\tC.RustForeignVec{class}_free({from})",
+            to = TO_VAR_TEMPLATE,
+            from = FROM_VAR_TEMPLATE,
+            class = class_name,
+        ),
+        Direction::Incoming => format!(
+            "var {to} C.struct_CRustForeignVec\n\tfor _, x := range {from} {{\n\t\tC.RustForeignVec{class}_push(&{to}, x.takeOwnership())\n\t}}",
+            to = TO_VAR_TEMPLATE,
+            from = FROM_VAR_TEMPLATE,
+            class = class_name,
+        ),
+    };
+    Ok(GoTypeInfo {
+        prelude: Some(prelude),
+        c_include: Some(format!("RustForeignVec{}.h", class_name)),
+        ..GoTypeInfo::new(
+            "C.struct_CRustForeignVec",
+            &format!("[]*{}", class_name),
+            TO_VAR_TEMPLATE.into(),
+        )
+    })
+}
+
+/// Slice of foreign classes becomes Go slice of borrowed objects
+fn map_object_slice(
+    conv_map: &mut TypeMap,
+    arg_ty: &RustType,
+    direction: Direction,
+    arg_ty_span: SourceIdSpan,
+) -> Result<GoTypeInfo> {
+    let class_name = if_type_slice_return_elem_type(&arg_ty.ty, false)
+        .map(|elem| conv_map.find_or_alloc_rust_type(elem, arg_ty_span.0))
+        .and_then(|elem| foreign_class_name(conv_map, &elem));
+    match (class_name, direction) {
+        (Some(class_name), Direction::Outgoing) => Ok(GoTypeInfo {
+            prelude: Some(format!(
+                "{to} := make([]*{class}, int({from}.len))\n\tfor i := range {to} {{\n\t\t{to}[i] = new{class}((*C.{class}Opaque)(unsafe.Add({from}.data, uintptr(i)*uintptr({from}.step))), false)\n\t}}",
+                to = TO_VAR_TEMPLATE,
+                from = FROM_VAR_TEMPLATE,
+                class = class_name,
+            )),
+            imports: vec!["unsafe"],
+            ..GoTypeInfo::new(
+                "C.struct_CRustObjectSlice",
+                &format!("[]*{}", class_name),
+                TO_VAR_TEMPLATE.into(),
+            )
+        }),
+        (Some(class_name), Direction::Incoming) => Err(DiagnosticError::new2(
+            arg_ty_span,
+            format!(
+                "Go: {} as input is not supported, Go objects are not placed \
+                 in one continuous memory block, use Vec<{}> instead",
+                arg_ty, class_name
+            ),
+        )),
+        (None, Direction::Incoming) => Err(DiagnosticError::new2(
+            arg_ty_span,
+            format!(
+                "Go: {} as input is not supported yet, only &[i32] \
+                 can be passed as slice of primitive types",
+                arg_ty
+            ),
+        )),
+        (None, Direction::Outgoing) => Err(unsupported(
+            arg_ty,
+            "struct CRustObjectSlice",
+            direction,
+            arg_ty_span,
+        )),
+    }
+}
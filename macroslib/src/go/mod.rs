@@ -0,0 +1,630 @@
+mod map_type;
+
+use std::{collections::BTreeSet, fmt::Write, io::Write as IoWrite, path::Path};
+
+use petgraph::Direction;
+use proc_macro2::TokenStream;
+use rustc_hash::FxHashMap;
+use syn::spanned::Spanned;
+
+use crate::{
+    cpp::{
+        c_func_name, check_no_future_methods, find_suitable_foreign_types_for_methods,
+        find_suitable_ftypes_for_interace_methods, CppForeignMethodSignature,
+    },
+    error::{DiagnosticError, Result},
+    file_cache::FileWriteCache,
+    go::map_type::map_type,
+    typemap::ast::fn_arg_type,
+    types::{
+        ForeignEnumInfo, ForeignFlagsInfo, ForeignInterface, ForeignStructInfo, ForeignerClassInfo,
+        ForeignerMethod, MethodAccess, MethodVariant,
+    },
+    GoConfig, LanguageGenerator, SourceCode, TypeMap,
+};
+
+impl LanguageGenerator for GoConfig {
+    fn register_class(&self, conv_map: &mut TypeMap, class: &ForeignerClassInfo) -> Result<()> {
+        self.c_abi.register_class(conv_map, class)
+    }
+
+    fn generate(
+        &self,
+        conv_map: &mut TypeMap,
+        pointer_target_width: usize,
+        class: &ForeignerClassInfo,
+    ) -> Result<Vec<TokenStream>> {
+        let code = self.c_abi.generate(conv_map, pointer_target_width, class)?;
+        let m_sigs = find_suitable_foreign_types_for_methods(conv_map, class, &self.c_abi)?;
+        check_no_future_methods(class, &m_sigs, "Go")?;
+        let go_code = generate_class(conv_map, self, class, &m_sigs)?;
+        write_go_file(&self.output_dir, &class.name.to_string(), &go_code)
+            .map_err(|err| DiagnosticError::new(class.src_id, class.span(), err))?;
+        Ok(code)
+    }
+
+    fn generate_enum(
+        &self,
+        conv_map: &mut TypeMap,
+        pointer_target_width: usize,
+        enum_info: &ForeignEnumInfo,
+    ) -> Result<Vec<TokenStream>> {
+        if enum_info.is_data_carrying() {
+            return Err(DiagnosticError::new(
+                enum_info.src_id,
+                enum_info.span(),
+                "Go: foreign_enum with data-carrying items is not supported yet",
+            ));
+        }
+        let code = self
+            .c_abi
+            .generate_enum(conv_map, pointer_target_width, enum_info)?;
+        let mut go_code = format!(
+            "// Automaticaly generated by rust_swig\npackage {}\n\n",
+            self.package_name
+        );
+        go_code.push_str(&doc_comments_to_go_comments(&enum_info.doc_comments, ""));
+        writeln!(&mut go_code, "type {} uint32\n\nconst (", enum_info.name).unwrap();
+        for item in &enum_info.items {
+            go_code.push_str(&doc_comments_to_go_comments(&item.doc_comments, "\t"));
+            writeln!(
+                &mut go_code,
+                "\t{enum_name}_{item_name} {enum_name} = {value}",
+                enum_name = enum_info.name,
+                item_name = item.name,
+                value = item.value
+            )
+            .unwrap();
+        }
+        go_code.push_str(")\n");
+        write_go_file(&self.output_dir, &enum_info.name.to_string(), &go_code)
+            .map_err(|err| DiagnosticError::new(enum_info.src_id, enum_info.span(), err))?;
+        Ok(code)
+    }
+
+    fn generate_struct(
+        &self,
+        _conv_map: &mut TypeMap,
+        _pointer_target_width: usize,
+        struct_info: &ForeignStructInfo,
+    ) -> Result<Vec<TokenStream>> {
+        Err(DiagnosticError::new(
+            struct_info.src_id,
+            struct_info.span(),
+            "Go: foreign_struct is not supported yet",
+        ))
+    }
+
+    fn generate_flags(
+        &self,
+        _conv_map: &mut TypeMap,
+        _pointer_target_width: usize,
+        flags_info: &ForeignFlagsInfo,
+    ) -> Result<Vec<TokenStream>> {
+        Err(DiagnosticError::new(
+            flags_info.src_id,
+            flags_info.span(),
+            "Go: foreign_flags is not supported yet",
+        ))
+    }
+
+    fn generate_interface(
+        &self,
+        conv_map: &mut TypeMap,
+        pointer_target_width: usize,
+        interface: &ForeignInterface,
+    ) -> Result<Vec<TokenStream>> {
+        let code = self
+            .c_abi
+            .generate_interface(conv_map, pointer_target_width, interface)?;
+        let f_methods =
+            find_suitable_ftypes_for_interace_methods(conv_map, interface, &self.c_abi)?;
+        let go_code = generate_interface(conv_map, self, interface, &f_methods)?;
+        write_go_file(&self.output_dir, &interface.name.to_string(), &go_code)
+            .map_err(|err| DiagnosticError::new(interface.src_id, interface.span(), err))?;
+        Ok(code)
+    }
+
+    fn init(&self, conv_map: &mut TypeMap, code: &[SourceCode]) -> std::result::Result<(), String> {
+        self.c_abi.init(conv_map, &[])?;
+        for cu in code {
+            let src_path = self.output_dir.join(&cu.id_of_code);
+            let mut src_file = FileWriteCache::new(&src_path);
+            src_file
+                .write_all(
+                    cu.code
+                        .replace("RUST_SWIG_GO_PACKAGE", &self.package_name)
+                        .replace("RUST_SWIG_NATIVE_LIB", &self.native_lib_name)
+                        .as_bytes(),
+                )
+                .map_err(|err| format!("write to {} failed: {}", src_path.display(), err))?;
+            src_file
+                .update_file_if_necessary()
+                .map_err(|err| format!("update of {} failed: {}", src_path.display(), err))?;
+        }
+        Ok(())
+    }
+}
+
+/// Header of Go file with cgo preamble, `imports` should contain
+/// all packages used by file, because of Go reject unused imports
+fn file_header(package_name: &str, preamble: &str, imports: &BTreeSet<&'static str>) -> String {
+    let mut code = format!(
+        "// Automaticaly generated by rust_swig\npackage {}\n\n/*\n{}*/\nimport \"C\"\n",
+        package_name, preamble
+    );
+    if !imports.is_empty() {
+        code.push_str("\nimport (\n");
+        for import in imports {
+            writeln!(&mut code, "\t\"{}\"", import).unwrap();
+        }
+        code.push_str(")\n");
+    }
+    code.push('\n');
+    code
+}
+
+/// `snake_case` or `camelCase` -> `CamelCase`
+fn to_camel_case(name: &str) -> String {
+    let mut ret = String::with_capacity(name.len());
+    let mut upper_next = true;
+    for ch in name.chars() {
+        if ch == '_' {
+            upper_next = true;
+        } else if upper_next {
+            ret.extend(ch.to_uppercase());
+            upper_next = false;
+        } else {
+            ret.push(ch);
+        }
+    }
+    ret
+}
+
+/// Go has no overloading and constructors, constructor becomes function
+/// `New<Class>` or `New<Class><Name>`, static method becomes function `<Class><Name>`,
+/// private and protected methods are not exported from package
+fn method_name(class: &ForeignerClassInfo, method: &ForeignerMethod) -> String {
+    let short_name = method.short_name();
+    let name = match method.variant {
+        MethodVariant::Constructor if short_name == "new" => format!("New{}", class.name),
+        MethodVariant::Constructor => format!("New{}{}", class.name, to_camel_case(&short_name)),
+        MethodVariant::StaticMethod => format!("{}{}", class.name, to_camel_case(&short_name)),
+        MethodVariant::Method(_) => to_camel_case(&short_name),
+    };
+    match method.access {
+        MethodAccess::Public => name,
+        MethodAccess::Private | MethodAccess::Protected => {
+            let mut chars = name.chars();
+            match chars.next() {
+                Some(first) => first.to_lowercase().chain(chars).collect(),
+                None => name,
+            }
+        }
+    }
+}
+
+fn check_names(class: &ForeignerClassInfo) -> Result<()> {
+    let mut names = FxHashMap::<String, &ForeignerMethod>::default();
+    for method in &class.methods {
+        if method.is_dummy_constructor() {
+            continue;
+        }
+        if let Some(prev) = names.insert(method_name(class, method), method) {
+            let mut err = DiagnosticError::new(
+                class.src_id,
+                method.span(),
+                "Go: methods with the same name are not supported",
+            );
+            err.span_note((class.src_id, prev.span()), "previous definition here");
+            return Err(err);
+        }
+    }
+    Ok(())
+}
+
+fn generate_class(
+    conv_map: &mut TypeMap,
+    cfg: &GoConfig,
+    class: &ForeignerClassInfo,
+    m_sigs: &[CppForeignMethodSignature],
+) -> Result<String> {
+    check_names(class)?;
+    let class_name = class.name.to_string();
+    let has_self = class.constructor_ret_type.is_some();
+    let mut imports = BTreeSet::<&'static str>::new();
+    let mut c_includes = BTreeSet::<String>::new();
+    if has_self {
+        imports.insert("runtime");
+    }
+    let mut methods = String::new();
+
+    for (method, f_method) in class.methods.iter().zip(m_sigs) {
+        if method.is_dummy_constructor() {
+            continue;
+        }
+        let skip_n = match method.variant {
+            MethodVariant::Method(_) => 1,
+            _ => 0,
+        };
+        let mut args = Vec::with_capacity(f_method.input.len());
+        for (arg, c_info) in method
+            .fn_decl
+            .inputs
+            .iter()
+            .skip(skip_n)
+            .zip(&f_method.input)
+        {
+            let arg_rust_ty = conv_map.find_or_alloc_rust_type(fn_arg_type(arg), class.src_id);
+            args.push(map_type(
+                conv_map,
+                &cfg.c_abi,
+                &arg_rust_ty,
+                c_info,
+                Direction::Incoming,
+                (class.src_id, fn_arg_type(arg).span()),
+            )?);
+        }
+        let output = match (method.variant, &method.fn_decl.output) {
+            (MethodVariant::Constructor, _) | (_, syn::ReturnType::Default) => None,
+            (_, syn::ReturnType::Type(_, ref ret_ty)) => {
+                let ret_rust_ty = conv_map.find_or_alloc_rust_type(ret_ty, class.src_id);
+                Some(map_type(
+                    conv_map,
+                    &cfg.c_abi,
+                    &ret_rust_ty,
+                    &f_method.output,
+                    Direction::Outgoing,
+                    (class.src_id, ret_ty.span()),
+                )?)
+            }
+        };
+
+        let mut body = String::new();
+        let mut go_args = Vec::with_capacity(args.len());
+        let mut call_args = Vec::with_capacity(args.len() + 1);
+        if let MethodVariant::Method(_) = method.variant {
+            body.push_str("\tdefer runtime.KeepAlive(obj)\n");
+            call_args.push("obj.handle()".to_string());
+        }
+        for (i, arg) in args.iter().enumerate() {
+            let arg_name = format!("a{}", i);
+            imports.extend(arg.imports.iter().cloned());
+            c_includes.extend(arg.c_include.clone());
+            if let Some(prelude) = arg.prelude(&arg_name) {
+                writeln!(&mut body, "\t{}", prelude).unwrap();
+            }
+            if arg.borrowed_object {
+                imports.insert("runtime");
+                writeln!(&mut body, "\tdefer runtime.KeepAlive({})", arg_name).unwrap();
+            }
+            go_args.push(format!("{} {}", arg_name, arg.go_ty));
+            call_args.push(arg.convert(&arg_name));
+        }
+        //Rust side reports unknown value of enum argument via out parameter
+        let check_invalid_arg = if f_method.checked_enum_args.is_empty() {
+            String::new()
+        } else {
+            body.push_str("\tvar swigInvalidArg C.char\n");
+            call_args.push("&swigInvalidArg".to_string());
+            let go_name = match method.variant {
+                MethodVariant::Method(_) => {
+                    format!("{}.{}", class_name, method_name(class, method))
+                }
+                _ => method_name(class, method),
+            };
+            format!(
+                "\tif swigInvalidArg != 0 {{\n\t\tpanic(\"unknown value of {} passed to {}\")\n\t}}\n",
+                f_method.checked_enum_names(),
+                go_name
+            )
+        };
+        let call = format!("C.{}({})", c_func_name(class, method), call_args.join(", "));
+        match (method.variant, &output) {
+            (MethodVariant::Constructor, _) if !check_invalid_arg.is_empty() => {
+                writeln!(&mut body, "\tret := {}", call).unwrap();
+                body.push_str(&check_invalid_arg);
+                writeln!(&mut body, "\treturn new{}(ret, true)", class_name).unwrap();
+            }
+            (MethodVariant::Constructor, _) => {
+                writeln!(&mut body, "\treturn new{}({}, true)", class_name, call).unwrap()
+            }
+            (_, Some(ref out)) => {
+                imports.extend(out.imports.iter().cloned());
+                c_includes.extend(out.c_include.clone());
+                writeln!(&mut body, "\tret := {}", call).unwrap();
+                body.push_str(&check_invalid_arg);
+                writeln!(&mut body, "\t{}", out.return_stmts("ret")).unwrap();
+            }
+            (_, None) => {
+                writeln!(&mut body, "\t{}", call).unwrap();
+                body.push_str(&check_invalid_arg);
+            }
+        }
+
+        methods.push_str(&doc_comments_to_go_comments(&method.doc_comments, ""));
+        let go_ret = match (method.variant, &output) {
+            (MethodVariant::Constructor, _) => format!(" *{}", class_name),
+            (_, Some(ref out)) => format!(" {}", out.go_ty),
+            (_, None) => String::new(),
+        };
+        let receiver = match method.variant {
+            MethodVariant::Method(_) => format!("(obj *{}) ", class_name),
+            _ => String::new(),
+        };
+        writeln!(
+            &mut methods,
+            "func {receiver}{name}({args}){ret} {{\n{body}}}\n",
+            receiver = receiver,
+            name = method_name(class, method),
+            args = go_args.join(", "),
+            ret = go_ret,
+            body = body,
+        )
+        .unwrap();
+    }
+
+    let mut preamble = format!("#include <stdlib.h>\n#include \"c_{}.h\"\n", class_name);
+    for include in &c_includes {
+        writeln!(&mut preamble, "#include \"{}\"", include).unwrap();
+    }
+    let mut go_code = file_header(&cfg.package_name, &preamble, &imports);
+    if has_self {
+        write!(
+            &mut go_code,
+            r#"{doc_comments}type {class_name} struct {{
+	ptr *C.{class_name}Opaque
+	// Rust object should be freed by this object
+	owned bool
+}}
+
+func new{class_name}(ptr *C.{class_name}Opaque, owned bool) *{class_name} {{
+	if ptr == nil {{
+		return nil
+	}}
+	obj := &{class_name}{{ptr: ptr, owned: owned}}
+	if owned {{
+		runtime.SetFinalizer(obj, (*{class_name}).Close)
+	}}
+	return obj
+}}
+
+func (obj *{class_name}) handle() *C.{class_name}Opaque {{
+	if obj.ptr == nil {{
+		panic("{class_name} was closed")
+	}}
+	return obj.ptr
+}}
+
+func (obj *{class_name}) takeOwnership() *C.{class_name}Opaque {{
+	if !obj.owned {{
+		panic("{class_name} is borrowed from Rust and can not be moved")
+	}}
+	ptr := obj.handle()
+	runtime.SetFinalizer(obj, nil)
+	obj.ptr = nil
+	return ptr
+}}
+
+// Close frees Rust object, after that object can not be used
+func (obj *{class_name}) Close() {{
+	if obj.owned && obj.ptr != nil {{
+		runtime.SetFinalizer(obj, nil)
+		C.{class_name}_delete(obj.ptr)
+	}}
+	obj.ptr = nil
+}}
+
+"#,
+            class_name = class_name,
+            doc_comments = doc_comments_to_go_comments(&class.doc_comments, ""),
+        )
+        .unwrap();
+    } else {
+        go_code.push_str(&doc_comments_to_go_comments(&class.doc_comments, ""));
+    }
+    go_code.push_str(methods.trim_end_matches('\n'));
+    go_code.push('\n');
+    Ok(go_code)
+}
+
+fn generate_interface(
+    conv_map: &mut TypeMap,
+    cfg: &GoConfig,
+    interface: &ForeignInterface,
+    f_methods: &[CppForeignMethodSignature],
+) -> Result<String> {
+    let interface_name = interface.name.to_string();
+    let struct_name = format!("C_{}", interface_name);
+    let mut interface_methods = String::new();
+    let mut extern_decls = format!("extern void go{}Deref(void *opaque);\n", interface_name);
+    let mut init_fields = String::new();
+    let mut callbacks = String::new();
+
+    for (method, f_method) in interface.items.iter().zip(f_methods) {
+        let mut args = Vec::with_capacity(f_method.input.len());
+        for (arg, c_info) in method.fn_decl.inputs.iter().skip(1).zip(&f_method.input) {
+            let arg_rust_ty = conv_map.find_or_alloc_rust_type(fn_arg_type(arg), interface.src_id);
+            let arg_info = map_type(
+                conv_map,
+                &cfg.c_abi,
+                &arg_rust_ty,
+                c_info,
+                Direction::Outgoing,
+                (interface.src_id, fn_arg_type(arg).span()),
+            )?;
+            if !arg_info.is_simple() {
+                return Err(DiagnosticError::new(
+                    interface.src_id,
+                    fn_arg_type(arg).span(),
+                    format!(
+                        "Go: {} as argument of callback is not supported yet",
+                        arg_rust_ty
+                    ),
+                ));
+            }
+            args.push(arg_info);
+        }
+        let output = match method.fn_decl.output {
+            syn::ReturnType::Default => None,
+            syn::ReturnType::Type(_, ref ret_ty) => {
+                let ret_rust_ty = conv_map.find_or_alloc_rust_type(ret_ty, interface.src_id);
+                let ret = map_type(
+                    conv_map,
+                    &cfg.c_abi,
+                    &ret_rust_ty,
+                    &f_method.output,
+                    Direction::Incoming,
+                    (interface.src_id, ret_ty.span()),
+                )?;
+                if !ret.is_simple() || ret.c_ty.starts_with('*') {
+                    return Err(DiagnosticError::new(
+                        interface.src_id,
+                        ret_ty.span(),
+                        format!(
+                            "Go: {} as return type of callback is not supported yet",
+                            ret_rust_ty
+                        ),
+                    ));
+                }
+                Some(ret)
+            }
+        };
+
+        let go_method_name = to_camel_case(&method.name.to_string());
+        let trampoline = format!("go{}{}", interface_name, go_method_name);
+        let mut go_args = Vec::with_capacity(args.len());
+        let mut c_args = Vec::with_capacity(args.len() + 1);
+        let mut cgo_args = Vec::with_capacity(args.len() + 1);
+        let mut call_args = Vec::with_capacity(args.len());
+        for (i, arg) in args.iter().enumerate() {
+            let arg_name = format!("a{}", i);
+            go_args.push(format!("{} {}", arg_name, arg.go_ty));
+            c_args.push(format!("{} {}", arg.c_decl_ty(), arg_name));
+            cgo_args.push(format!("{} {}", arg_name, arg.c_ty));
+            call_args.push(arg.convert(&arg_name));
+        }
+        c_args.push("void *opaque".to_string());
+        cgo_args.push("opaque unsafe.Pointer".to_string());
+
+        interface_methods.push_str(&doc_comments_to_go_comments(&method.doc_comments, "\t"));
+        writeln!(
+            &mut interface_methods,
+            "\t{}({}){}",
+            go_method_name,
+            go_args.join(", "),
+            output
+                .as_ref()
+                .map(|x| format!(" {}", x.go_ty))
+                .unwrap_or_default()
+        )
+        .unwrap();
+        writeln!(
+            &mut extern_decls,
+            "extern {} {}({});",
+            output
+                .as_ref()
+                .map(|x| x.c_decl_ty())
+                .unwrap_or_else(|| "void".to_string()),
+            trampoline,
+            c_args.join(", ")
+        )
+        .unwrap();
+        writeln!(
+            &mut init_fields,
+            "\t\t{}: (*[0]byte)(unsafe.Pointer(C.{})),",
+            method.name, trampoline
+        )
+        .unwrap();
+        let call = format!(
+            "swig{}FromOpaque(opaque).{}({})",
+            interface_name,
+            go_method_name,
+            call_args.join(", ")
+        );
+        let (cgo_ret, call) = match output {
+            Some(ref out) => (
+                format!(" {}", out.c_ty),
+                format!("return {}", out.convert(&call)),
+            ),
+            None => (String::new(), call),
+        };
+        write!(
+            &mut callbacks,
+            r#"
+//export {trampoline}
+func {trampoline}({cgo_args}){cgo_ret} {{
+	{call}
+}}
+"#,
+            trampoline = trampoline,
+            cgo_args = cgo_args.join(", "),
+            cgo_ret = cgo_ret,
+            call = call,
+        )
+        .unwrap();
+    }
+
+    // preamble of file with `//export` can contain only declarations
+    let preamble = format!(
+        "#include <stdlib.h>\n#include \"c_{}.h\"\n\n{}",
+        interface_name, extern_decls
+    );
+    let imports = ["runtime/cgo", "unsafe"].iter().cloned().collect();
+    let mut go_code = file_header(&cfg.package_name, &preamble, &imports);
+    write!(
+        &mut go_code,
+        r#"{doc_comments}type {interface_name} interface {{
+{interface_methods}}}
+
+// new{struct_name} creates value that passed to Rust as `Box<Trait>`,
+// Rust side keeps reference to obj until it is dropped
+func new{struct_name}(obj {interface_name}) C.struct_{struct_name} {{
+	opaque := C.malloc(C.size_t(unsafe.Sizeof(C.uintptr_t(0))))
+	*(*C.uintptr_t)(opaque) = C.uintptr_t(cgo.NewHandle(obj))
+	return C.struct_{struct_name}{{
+		opaque: opaque,
+		{struct_name}_deref: (*[0]byte)(unsafe.Pointer(C.go{interface_name}Deref)),
+{init_fields}	}}
+}}
+
+func swig{interface_name}FromOpaque(opaque unsafe.Pointer) {interface_name} {{
+	return cgo.Handle(*(*C.uintptr_t)(opaque)).Value().({interface_name})
+}}
+
+//export go{interface_name}Deref
+func go{interface_name}Deref(opaque unsafe.Pointer) {{
+	cgo.Handle(*(*C.uintptr_t)(opaque)).Delete()
+	C.free(opaque)
+}}
+{callbacks}"#,
+        doc_comments = doc_comments_to_go_comments(&interface.doc_comments, ""),
+        interface_name = interface_name,
+        interface_methods = interface_methods,
+        struct_name = struct_name,
+        init_fields = init_fields,
+        callbacks = callbacks,
+    )
+    .unwrap();
+    Ok(go_code)
+}
+
+fn doc_comments_to_go_comments(doc_comments: &[String], indent: &str) -> String {
+    let mut comments = String::new();
+    for comment in doc_comments {
+        writeln!(&mut comments, "{}// {}", indent, comment.trim()).unwrap();
+    }
+    comments
+}
+
+/// Go file names are lowercase, also `_` is not used to not clash
+/// with build constraints like `_windows.go`
+fn write_go_file(output_dir: &Path, name: &str, go_code: &str) -> std::result::Result<(), String> {
+    let path = output_dir.join(format!("{}.go", name.to_lowercase()));
+    let mut file = FileWriteCache::new(&path);
+    file.write_all(go_code.as_bytes())
+        .map_err(|err| format!("write to {} failed: {}", path.display(), err))?;
+    file.update_file_if_necessary()
+        .map_err(|err| format!("update of {} failed: {}", path.display(), err))?;
+    Ok(())
+}
@@ -0,0 +1,63 @@
+// Automaticaly generated by rust_swig
+package RUST_SWIG_GO_PACKAGE
+
+/*
+#cgo LDFLAGS: -lRUST_SWIG_NATIVE_LIB
+#include "rust_swig_c.h"
+*/
+import "C"
+
+import (
+	"errors"
+	"unsafe"
+)
+
+func swigBoolToC(b bool) C.char {
+	if b {
+		return 1
+	}
+	return 0
+}
+
+// swigStrViewToString copies string borrowed from Rust side
+func swigStrViewToString(s C.struct_RustStrView) string {
+	return C.GoStringN(s.data, C.int(s.len))
+}
+
+// swigConsumeString copies string allocated by Rust side and frees it
+func swigConsumeString(s C.struct_CRustString) string {
+	ret := C.GoStringN(s.data, C.int(s.len))
+	C.crust_string_free(s)
+	return ret
+}
+
+// swigConsumeError makes error with message allocated by Rust side and frees it
+func swigConsumeError(s C.struct_CRustString) error {
+	return errors.New(swigConsumeString(s))
+}
+
+// swigConsumeOptionString copies string allocated by Rust side, if any, and frees it
+func swigConsumeOptionString(s C.struct_CRustOptionString) *string {
+	if s.is_some == 0 {
+		return nil
+	}
+	ret := swigConsumeString(s.val)
+	return &ret
+}
+
+// swigOption returns pointer to `val` if `isSome`, otherwise nil
+func swigOption[T any](isSome bool, val T) *T {
+	if !isSome {
+		return nil
+	}
+	return &val
+}
+
+// swigCopySlice copies `n` elements of C array to Go slice
+func swigCopySlice[T any](data unsafe.Pointer, n C.uintptr_t) []T {
+	ret := make([]T, int(n))
+	if n > 0 {
+		copy(ret, unsafe.Slice((*T)(data), int(n)))
+	}
+	return ret
+}
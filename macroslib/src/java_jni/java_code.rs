@@ -1,13 +1,20 @@
-use std::{fmt, io::Write, path::Path};
+use std::{fmt, io::Write, iter, path::Path};
 
 use bitflags::bitflags;
 
 use crate::{
     file_cache::FileWriteCache,
-    java_jni::{fmt_write_err_map, method_name, JniForeignMethodSignature, NullAnnotation},
+    java_jni::{
+        fmt_write_err_map,
+        map_type::{boxed_type, tuple_element_getter, TUPLE_FIELDS},
+        method_name, ErrorToException, JniForeignMethodSignature, NullAnnotation,
+    },
     typemap::ast::if_result_return_ok_err_types,
     typemap::TypeMap,
-    types::{ForeignEnumInfo, ForeignInterface, ForeignerClassInfo, MethodAccess, MethodVariant},
+    types::{
+        ForeignEnumInfo, ForeignFlagsInfo, ForeignInterface, ForeignStructField, ForeignStructInfo,
+        ForeignerClassInfo, MethodAccess, MethodVariant,
+    },
 };
 
 bitflags! {
@@ -47,7 +54,7 @@ public enum {enum_name} {{
             file,
             "{doc_comments}{item_name}({index}){separator}",
             item_name = item.name,
-            index = i,
+            index = item.value,
             doc_comments = doc_comments_to_java_comments(&item.doc_comments, false),
             separator = if i == enum_info.items.len() - 1 {
                 ';'
@@ -76,7 +83,501 @@ public enum {enum_name} {{
     Ok(())
 }
 
+/// `foreign_flags` is passed as `int`, so class contains only constants
+/// for bits and conversation into `EnumSet` and back
+pub(in crate::java_jni) fn generate_java_code_for_flags(
+    output_dir: &Path,
+    package_name: &str,
+    flags_info: &ForeignFlagsInfo,
+) -> Result<(), String> {
+    let path = output_dir.join(format!("{}.java", flags_info.name));
+    let mut file = FileWriteCache::new(&path);
+    let mut constants = String::new();
+    let mut enum_items = Vec::with_capacity(flags_info.items.len());
+    for item in &flags_info.items {
+        let doc_comments = doc_comments_to_java_comments(&item.doc_comments, false);
+        if !doc_comments.is_empty() {
+            constants.push_str(&doc_comments);
+            constants.push('\n');
+        }
+        constants.push_str(&format!(
+            "    public static final int {} = {:#x};\n",
+            item.name, item.value
+        ));
+        enum_items.push(format!(
+            "        {item}({flags}.{item})",
+            item = item.name,
+            flags = flags_info.name
+        ));
+    }
+    write!(
+        file,
+        r#"// Automaticaly generated by rust_swig
+package {package_name};
+
+{doc_comments}
+public final class {flags_name} {{
+    private {flags_name}() {{}}
+
+{constants}
+    private static final int SWIG_ALL_BITS = {all_bits:#x};
+
+    public enum Flag {{
+{enum_items};
+
+        private final int bit;
+        Flag(int bit) {{
+            this.bit = bit;
+        }}
+        public final int getBit() {{ return bit; }}
+    }}
+
+    public static boolean isValid(int bits) {{
+        return (bits & ~SWIG_ALL_BITS) == 0;
+    }}
+
+    public static int checkBits(int bits) {{
+        if (!isValid(bits)) {{
+            throw new IllegalArgumentException("{flags_name}: unknown bits 0x" + Integer.toHexString(bits & ~SWIG_ALL_BITS));
+        }}
+        return bits;
+    }}
+
+    public static java.util.EnumSet<Flag> toEnumSet(int bits) {{
+        checkBits(bits);
+        java.util.EnumSet<Flag> ret = java.util.EnumSet.noneOf(Flag.class);
+        for (Flag flag : Flag.values()) {{
+            if ((bits & flag.bit) != 0) {{
+                ret.add(flag);
+            }}
+        }}
+        return ret;
+    }}
+
+    public static int fromEnumSet(java.util.Set<Flag> flags) {{
+        int bits = 0;
+        for (Flag flag : flags) {{
+            bits |= flag.bit;
+        }}
+        return bits;
+    }}
+}}
+"#,
+        package_name = package_name,
+        doc_comments = doc_comments_to_java_comments(&flags_info.doc_comments, true),
+        flags_name = flags_info.name,
+        constants = constants,
+        all_bits = flags_info.all_bits(),
+        enum_items = enum_items.join(",\n"),
+    )
+    .map_err(&map_write_err)?;
+
+    file.update_file_if_necessary().map_err(&map_write_err)?;
+    Ok(())
+}
+
+/// Exception for methods that return `Result<T, E>`, where `E` is `foreign_enum`
+fn generate_java_code_for_enum_exception(
+    output_dir: &Path,
+    package_name: &str,
+    class_name: &str,
+    enum_name: &str,
+) -> Result<(), String> {
+    let path = output_dir.join(format!("{}.java", class_name));
+    let mut file = FileWriteCache::new(&path);
+    write!(
+        file,
+        r#"// Automaticaly generated by rust_swig
+package {package_name};
+
+public final class {class_name} extends Exception {{
+    public {class_name}({enum_name} value) {{
+        super(value.name());
+        this.value = value;
+    }}
+    public final {enum_name} getValue() {{ return value; }}
+
+    private final {enum_name} value;
+}}
+"#,
+        package_name = package_name,
+        class_name = class_name,
+        enum_name = enum_name,
+    )
+    .map_err(&map_write_err)?;
+
+    file.update_file_if_necessary().map_err(&map_write_err)?;
+    Ok(())
+}
+
+/// Immutable class for Rust tuple, `java_types` are types of its elements
+pub(in crate::java_jni) fn generate_java_code_for_tuple(
+    output_dir: &Path,
+    package_name: &str,
+    class_name: &str,
+    java_types: &[String],
+) -> Result<(), String> {
+    let path = output_dir.join(format!("{}.java", class_name));
+    let mut file = FileWriteCache::new(&path);
+    let fields = &TUPLE_FIELDS[..java_types.len()];
+    let mut args = Vec::with_capacity(fields.len());
+    let mut init = String::new();
+    let mut getters = String::new();
+    let mut equals = Vec::with_capacity(fields.len());
+    let mut decls = String::new();
+    let mut objects = Vec::with_capacity(fields.len());
+    let mut casts = Vec::with_capacity(fields.len());
+    let mut swig_getters = String::new();
+    for (field, ty) in fields.iter().zip(java_types.iter()) {
+        args.push(format!("{} {}", ty, field));
+        init.push_str(&format!("        this.{0} = {0};\n", field));
+        getters.push_str(&format!(
+            "    public final {} get{}() {{ return {}; }}\n",
+            ty,
+            capitalize(field),
+            field
+        ));
+        equals.push(format!(
+            "java.util.Objects.deepEquals({0}, other.{0})",
+            field
+        ));
+        decls.push_str(&format!("    private final {} {};\n", ty, field));
+        objects.push(format!("Object {}", field));
+        casts.push(format!("({}) {}", boxed_type(ty), field));
+        swig_getters.push_str(&format!(
+            "    private Object {}() {{ return {}; }}\n",
+            tuple_element_getter(field),
+            field
+        ));
+    }
+    write!(
+        file,
+        r#"// Automaticaly generated by rust_swig
+package {package_name};
+
+public final class {class_name} {{
+    public {class_name}({args}) {{
+{init}    }}
+{getters}
+    @Override
+    public boolean equals(Object obj) {{
+        if (this == obj) {{
+            return true;
+        }}
+        if (!(obj instanceof {class_name})) {{
+            return false;
+        }}
+        {class_name} other = ({class_name}) obj;
+        return {equals};
+    }}
+
+    @Override
+    public int hashCode() {{
+        return java.util.Arrays.deepHashCode(new Object[] {{ {fields} }});
+    }}
+
+    @Override
+    public String toString() {{
+        String elements = java.util.Arrays.deepToString(new Object[] {{ {fields} }});
+        return "(" + elements.substring(1, elements.length() - 1) + ")";
+    }}
+
+{decls}
+    @SuppressWarnings("unchecked")
+    private static {class_name} swigFromRust({objects}) {{
+        return new {class_name}({casts});
+    }}
+{swig_getters}}}
+"#,
+        package_name = package_name,
+        class_name = class_name,
+        args = args.join(", "),
+        init = init,
+        getters = getters,
+        equals = equals.join("\n            && "),
+        fields = fields.join(", "),
+        decls = decls,
+        objects = objects.join(", "),
+        casts = casts.join(", "),
+        swig_getters = swig_getters,
+    )
+    .map_err(&map_write_err)?;
+
+    file.update_file_if_necessary().map_err(&map_write_err)?;
+    Ok(())
+}
+
+/// Value class for `foreign_struct`, `java_types` are Java types of its fields
+pub(in crate::java_jni) fn generate_java_code_for_struct(
+    output_dir: &Path,
+    package_name: &str,
+    struct_info: &ForeignStructInfo,
+    java_types: &[String],
+) -> Result<(), String> {
+    let path = output_dir.join(format!("{}.java", struct_info.name));
+    let mut file = FileWriteCache::new(&path);
+    write!(
+        file,
+        r#"// Automaticaly generated by rust_swig
+package {package_name};
+
+{doc_comments}
+{class}"#,
+        package_name = package_name,
+        doc_comments = doc_comments_to_java_comments(&struct_info.doc_comments, true),
+        class = java_value_class(
+            "public final",
+            &struct_info.name.to_string(),
+            &struct_info.name.to_string(),
+            &struct_info.fields,
+            java_types,
+            None,
+        ),
+    )
+    .map_err(&map_write_err)?;
+
+    file.update_file_if_necessary().map_err(&map_write_err)?;
+    Ok(())
+}
+
+/// Abstract class with private constructor for `foreign_enum` with data-carrying items,
+/// so only nested classes generated for items can extend it,
+/// `java_types` are types of fields of each item
+pub(in crate::java_jni) fn generate_java_code_for_data_enum(
+    output_dir: &Path,
+    package_name: &str,
+    enum_info: &ForeignEnumInfo,
+    java_types: &[Vec<String>],
+) -> Result<(), String> {
+    let path = output_dir.join(format!("{}.java", enum_info.name));
+    let mut file = FileWriteCache::new(&path);
+    write!(
+        file,
+        r#"// Automaticaly generated by rust_swig
+package {package_name};
+
+{doc_comments}
+public abstract class {enum_name} {{
+    private {enum_name}() {{}}
+
+    abstract int swigTag();
+"#,
+        package_name = package_name,
+        doc_comments = doc_comments_to_java_comments(&enum_info.doc_comments, true),
+        enum_name = enum_info.name,
+    )
+    .map_err(&map_write_err)?;
+
+    for (item, item_java_types) in enum_info.items.iter().zip(java_types.iter()) {
+        let class = java_value_class(
+            "public static final",
+            &item.name.to_string(),
+            &format!("{}.{}", enum_info.name, item.name),
+            &item.fields,
+            item_java_types,
+            Some((&enum_info.name.to_string(), item.value)),
+        );
+        let mut doc_comments = doc_comments_to_java_comments(&item.doc_comments, false);
+        if !doc_comments.is_empty() {
+            doc_comments.push('\n');
+        }
+        write!(
+            file,
+            "\n{doc_comments}{class}",
+            doc_comments = doc_comments,
+            class = indent_lines(&class, "    "),
+        )
+        .map_err(&map_write_err)?;
+    }
+    writeln!(file, "}}").map_err(&map_write_err)?;
+
+    file.update_file_if_necessary().map_err(&map_write_err)?;
+    Ok(())
+}
+
+/// Expression for `toString` of field, arrays do not override `toString`
+fn java_field_to_string(field: &str, java_type: &str) -> String {
+    if !java_type.ends_with("[]") {
+        return field.to_string();
+    }
+    let elem_type = &java_type[..java_type.len() - 2];
+    if boxed_type(elem_type) != elem_type {
+        format!("java.util.Arrays.toString({})", field)
+    } else {
+        format!("java.util.Arrays.deepToString({})", field)
+    }
+}
+
+/// Class with public final fields, `equals`, `hashCode`, `toString`
+/// and private methods for Rust code to create it and get its fields,
+/// `extends` is parent class and discriminant of `foreign_enum` item
+fn java_value_class(
+    modifiers: &str,
+    class_name: &str,
+    display_name: &str,
+    fields_info: &[ForeignStructField],
+    java_types: &[String],
+    extends: Option<(&str, u32)>,
+) -> String {
+    let fields: Vec<String> = fields_info
+        .iter()
+        .map(|field| field.name.to_string())
+        .collect();
+    let mut decls = String::new();
+    let mut args = Vec::with_capacity(fields.len());
+    let mut init = String::new();
+    let mut equals = Vec::with_capacity(fields.len());
+    let mut to_string = Vec::with_capacity(fields.len());
+    let mut objects = Vec::with_capacity(fields.len());
+    let mut casts = Vec::with_capacity(fields.len());
+    let mut swig_getters = String::new();
+    for ((field, ty), field_info) in fields.iter().zip(java_types.iter()).zip(fields_info.iter()) {
+        let doc_comments = doc_comments_to_java_comments(&field_info.doc_comments, false);
+        if !doc_comments.is_empty() {
+            decls.push_str(&doc_comments);
+            decls.push('\n');
+        }
+        decls.push_str(&format!("    public final {} {};\n", ty, field));
+        args.push(format!("{} {}", ty, field));
+        init.push_str(&format!("        this.{0} = {0};\n", field));
+        equals.push(format!(
+            "java.util.Objects.deepEquals({0}, other.{0})",
+            field
+        ));
+        to_string.push(format!(
+            "{sep}{0}=\" + {1}",
+            field,
+            java_field_to_string(field, ty),
+            sep = if to_string.is_empty() { "" } else { ", " }
+        ));
+        objects.push(format!("Object {}", field));
+        casts.push(format!("({}) {}", boxed_type(ty), field));
+        swig_getters.push_str(&format!(
+            "    private Object {}() {{ return {}; }}\n",
+            tuple_element_getter(field),
+            field
+        ));
+    }
+    let equals = if fields.is_empty() {
+        "        return true;".to_string()
+    } else {
+        format!(
+            "        {class_name} other = ({class_name}) obj;\n        return {equals};",
+            class_name = class_name,
+            equals = equals.join("\n            && "),
+        )
+    };
+    let to_string = if fields.is_empty() {
+        format!("\"{}\"", display_name)
+    } else {
+        format!("\"{}{{{} + \"}}\"", display_name, to_string.join(" + \""))
+    };
+    let (extends, swig_tag) = match extends {
+        Some((parent, value)) => (
+            format!(" extends {}", parent),
+            format!(
+                "\n    @Override\n    int swigTag() {{ return {}; }}\n",
+                value
+            ),
+        ),
+        None => (String::new(), String::new()),
+    };
+    format!(
+        r#"{modifiers} class {class_name}{extends} {{
+{decls}
+    public {class_name}({args}) {{
+{init}    }}
+
+    @Override
+    public boolean equals(Object obj) {{
+        if (this == obj) {{
+            return true;
+        }}
+        if (!(obj instanceof {class_name})) {{
+            return false;
+        }}
+{equals}
+    }}
+
+    @Override
+    public int hashCode() {{
+        return java.util.Arrays.deepHashCode(new Object[] {fields});
+    }}
+
+    @Override
+    public String toString() {{
+        return {to_string};
+    }}
+{swig_tag}
+    @SuppressWarnings("unchecked")
+    private static {class_name} swigFromRust({objects}) {{
+        return new {class_name}({casts});
+    }}
+{swig_getters}}}
+"#,
+        modifiers = modifiers,
+        class_name = class_name,
+        extends = extends,
+        decls = decls,
+        args = args.join(", "),
+        init = init,
+        equals = equals,
+        fields = if fields.is_empty() {
+            "{}".to_string()
+        } else {
+            format!("{{ {} }}", fields.join(", "))
+        },
+        to_string = to_string,
+        swig_tag = swig_tag,
+        objects = objects.join(", "),
+        casts = casts.join(", "),
+        swig_getters = swig_getters,
+    )
+}
+
+/// Add `indent` to the beginning of each not empty line
+pub(in crate::java_jni) fn indent_lines(code: &str, indent: &str) -> String {
+    let mut ret = String::with_capacity(code.len());
+    for line in code.lines() {
+        if !line.is_empty() {
+            ret.push_str(indent);
+            ret.push_str(line);
+        }
+        ret.push('\n');
+    }
+    ret
+}
+
+fn capitalize(name: &str) -> String {
+    let mut chars = name.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+fn generate_java_code_for_cleaner(output_dir: &Path, package_name: &str) -> Result<(), String> {
+    let path = output_dir.join("SwigCleaner.java");
+    let mut file = FileWriteCache::new(&path);
+    write!(
+        file,
+        r#"// Automaticaly generated by rust_swig
+package {package_name};
+
+final class SwigCleaner {{
+    static final java.lang.ref.Cleaner CLEANER = java.lang.ref.Cleaner.create();
+    private SwigCleaner() {{}}
+}}
+"#,
+        package_name = package_name,
+    )
+    .map_err(&map_write_err)?;
+    file.update_file_if_necessary().map_err(&map_write_err)?;
+    Ok(())
+}
+
 pub(in crate::java_jni) fn generate_java_code_for_interface(
+    conv_map: &mut TypeMap,
     output_dir: &Path,
     package_name: &str,
     interface: &ForeignInterface,
@@ -103,12 +604,26 @@ public interface {interface_name} {{
     .map_err(&map_write_err)?;
 
     for (method, f_method) in interface.items.iter().zip(methods_sign) {
+        let may_return_error = match method.fn_decl.output {
+            syn::ReturnType::Default => false,
+            syn::ReturnType::Type(_, ref ptype) => {
+                let ret_rust_ty = conv_map.find_or_alloc_rust_type(ptype, interface.src_id);
+                if_result_return_ok_err_types(&ret_rust_ty).is_some()
+            }
+        };
+        let exception_spec = if may_return_error {
+            " throws Exception"
+        } else {
+            ""
+        };
         write!(
             file,
             r#"
 {doc_comments}
-    void {method_name}({single_args_with_types});
+    {ret_type} {method_name}({single_args_with_types}){exception_spec};
 "#,
+            ret_type = f_method.output.base.name,
+            exception_spec = exception_spec,
             method_name = method.name,
             doc_comments = doc_comments_to_java_comments(&method.doc_comments, false),
             single_args_with_types = args_with_java_types(
@@ -138,12 +653,22 @@ pub(in crate::java_jni) fn generate_java_code(
     class: &ForeignerClassInfo,
     methods_sign: &[JniForeignMethodSignature],
     null_annotation_package: Option<&str>,
+    use_cleaner: bool,
 ) -> Result<(), String> {
     let path = output_dir.join(format!("{}.java", class.name));
     let mut file = FileWriteCache::new(&path);
 
     let imports = get_null_annotation_imports(null_annotation_package, methods_sign);
 
+    let use_cleaner = use_cleaner
+        && class
+            .methods
+            .iter()
+            .any(|m| m.variant == MethodVariant::Constructor);
+    if use_cleaner {
+        generate_java_code_for_cleaner(output_dir, package_name)?;
+    }
+
     let class_doc_comments = doc_comments_to_java_comments(&class.doc_comments, true);
     write!(
         file,
@@ -151,12 +676,17 @@ pub(in crate::java_jni) fn generate_java_code(
 package {package_name};
 {imports}
 {doc_comments}
-public final class {class_name} {{
+public final class {class_name}{implements} {{
 "#,
         package_name = package_name,
         imports = imports,
         class_name = class.name,
         doc_comments = class_doc_comments,
+        implements = if use_cleaner {
+            " implements AutoCloseable"
+        } else {
+            ""
+        },
     )
     .map_err(&map_write_err)?;
 
@@ -179,10 +709,21 @@ public final class {class_name} {{
             }
         };
 
-        let exception_spec = if may_return_error {
-            "throws Exception"
-        } else {
-            ""
+        if let Some(exception) = f_method.exception.as_ref() {
+            if let ErrorToException::EnumValue { ref enum_name, .. } = exception.conv {
+                generate_java_code_for_enum_exception(
+                    output_dir,
+                    package_name,
+                    &exception.class_name,
+                    enum_name,
+                )?;
+            }
+        }
+
+        let exception_spec = match f_method.exception {
+            Some(ref exception) => format!("throws {}", exception.class_name),
+            None if may_return_error => "throws Exception".into(),
+            None => String::new(),
         };
 
         let method_access = match method.access {
@@ -192,12 +733,13 @@ public final class {class_name} {{
         };
 
         let convert_code = convert_code_for_method(f_method);
-        let func_name = method_name(method, f_method);
+        let func_name = method_name(method, f_method, false);
         match method.variant {
             MethodVariant::StaticMethod => {
-                let ret_type = &f_method.output.name;
+                let ret_type = &f_method.output.base.name;
+                let native_ret_type = native_ret_type(f_method);
 
-                if convert_code.is_empty() {
+                if convert_code.is_empty() && f_method.output.java_converter.is_none() {
                     write!(
                         file,
                         r#"
@@ -220,15 +762,24 @@ public final class {class_name} {{
                         r#"
     {method_access} static {ret_type} {method_name}({single_args_with_types}) {exception_spec} {{
 {convert_code}
-         {return_code}{func_name}({args});
+{call_code}
     }}
-    private static native {ret_type} {func_name}({args_with_types}) {exception_spec};
+    private static native {native_ret_type} {func_name}({args_with_types}) {exception_spec};
 "#,
                         method_name = method.short_name(),
                         method_access = method_access,
                         ret_type = ret_type,
+                        native_ret_type = native_ret_type,
                         func_name = func_name,
-                        return_code = if ret_type != "void" { "return " } else { "" },
+                        call_code = call_native_code(
+                            f_method,
+                            "         ",
+                            &format!(
+                                "{}({})",
+                                func_name,
+                                list_of_args_for_call_method(f_method, ArgsFormatFlags::INTERNAL)?
+                            )
+                        ),
                         args_with_types = args_with_java_types(
                             f_method,
                             ArgsFormatFlags::INTERNAL,
@@ -241,28 +792,39 @@ public final class {class_name} {{
                             null_annotation_package.is_some()
                         )?,
                         convert_code = convert_code,
-                        args = list_of_args_for_call_method(f_method, ArgsFormatFlags::INTERNAL)?,
                     )
                     .map_err(&map_write_err)?;
                 }
             }
             MethodVariant::Method(_) => {
                 have_methods = true;
-                let ret_type = &f_method.output.name;
+                let ret_type = &f_method.output.base.name;
                 write!(
                     file,
                     r#"
     {method_access} final {ret_type} {method_name}({single_args_with_types}) {exception_spec} {{
 {convert_code}
-        {return_code}{func_name}(mNativeObj{args});
+{call_code}
     }}
-    private static native {ret_type} {func_name}(long me{args_with_types}) {exception_spec};
+    private static native {native_ret_type} {func_name}(long me{args_with_types}) {exception_spec};
 "#,
                     method_access = method_access,
                     ret_type = ret_type,
+                    native_ret_type = native_ret_type(f_method),
                     method_name = method.short_name(),
                     exception_spec = exception_spec,
-                    return_code = if ret_type != "void" { "return " } else { "" },
+                    call_code = call_native_code(
+                        f_method,
+                        "        ",
+                        &format!(
+                            "{}(mNativeObj{})",
+                            func_name,
+                            list_of_args_for_call_method(
+                                f_method,
+                                ArgsFormatFlags::COMMA_BEFORE | ArgsFormatFlags::INTERNAL
+                            )?
+                        )
+                    ),
                     func_name = func_name,
                     convert_code = convert_code,
                     single_args_with_types = args_with_java_types(
@@ -275,10 +837,6 @@ public final class {class_name} {{
                         ArgsFormatFlags::USE_COMMA_IF_NEED | ArgsFormatFlags::INTERNAL,
                         null_annotation_package.is_some()
                     )?,
-                    args = list_of_args_for_call_method(
-                        f_method,
-                        ArgsFormatFlags::COMMA_BEFORE | ArgsFormatFlags::INTERNAL
-                    )?,
                 )
                 .map_err(&map_write_err)?;
             }
@@ -301,10 +859,15 @@ public final class {class_name} {{
                         "
     {method_access} {class_name}({ext_args_with_types}) {exception_spec} {{
 {convert_code}
-        mNativeObj = init({args});
+        mNativeObj = init({args});{cleaner_update}
     }}
     private static native long {func_name}({args_with_types}) {exception_spec};
 ",
+                        cleaner_update = if use_cleaner {
+                            "\n        swigNativeObjChanged();"
+                        } else {
+                            ""
+                        },
                         method_access = method_access,
                         class_name = class.name,
                         exception_spec = exception_spec,
@@ -335,7 +898,50 @@ May be you need to use `private constructor = empty;` syntax?",
             package_name, class.name
         ));
     }
-    if have_constructor {
+    if have_constructor && use_cleaner {
+        write!(
+            file,
+            "
+    public synchronized void delete() {{
+        if (mNativeObj != 0) {{
+            mNativeObj = 0;
+            mCleanable.clean();
+        }}
+    }}
+    @Override
+    public void close() {{
+        delete();
+    }}
+    /*package*/ void swigNativeObjChanged() {{
+        if (mNativeObj != 0) {{
+            mDeleter = new SwigDeleter(mNativeObj);
+            mCleanable = SwigCleaner.CLEANER.register(this, mDeleter);
+        }} else if (mDeleter != null) {{
+            mDeleter.nativeObj = 0;
+        }}
+    }}
+    private static final class SwigDeleter implements Runnable {{
+        private volatile long nativeObj;
+        SwigDeleter(long nativeObj) {{
+            this.nativeObj = nativeObj;
+        }}
+        @Override
+        public void run() {{
+            long obj = nativeObj;
+            nativeObj = 0;
+            if (obj != 0) {{
+                do_delete(obj);
+            }}
+        }}
+    }}
+    private SwigDeleter mDeleter;
+    private java.lang.ref.Cleaner.Cleanable mCleanable;
+    private static native void do_delete(long me);
+    /*package*/ long mNativeObj;
+"
+        )
+        .map_err(&map_write_err)?;
+    } else if have_constructor {
         write!(
             file,
             "
@@ -374,6 +980,20 @@ May be you need to use `private constructor = empty;` syntax?",
         .map_err(&map_write_err)?;
     }
 
+    let mut helpers = Vec::<&str>::new();
+    for f_method in methods_sign {
+        for fti in f_method.input.iter().chain(iter::once(&f_method.output)) {
+            for helper in conv_map.foreign_type_helpers(&fti.base.name) {
+                if !helpers.contains(&helper.as_str()) {
+                    helpers.push(helper);
+                }
+            }
+        }
+    }
+    for helper in helpers {
+        write!(file, "\n{}\n", helper).map_err(&map_write_err)?;
+    }
+
     file.write_all(class.foreigner_code.as_bytes())
         .map_err(&map_write_err)?;
     write!(file, "}}").map_err(&map_write_err)?;
@@ -465,7 +1085,45 @@ fn convert_code_for_method(f_method: &JniForeignMethodSignature) -> String {
     ret
 }
 
-fn doc_comments_to_java_comments(doc_comments: &[String], class_comments: bool) -> String {
+/// Java type of value returned by native function
+fn native_ret_type(f_method: &JniForeignMethodSignature) -> &str {
+    match f_method.output.java_converter {
+        Some(ref converter) => &converter.java_transition_type,
+        None => &f_method.output.base.name,
+    }
+}
+
+/// Call of native function and return of its result,
+/// converted if required
+fn call_native_code(f_method: &JniForeignMethodSignature, indent: &str, call: &str) -> String {
+    match f_method
+        .output
+        .java_convert(|| ("ret".to_string(), "convRet".to_string()))
+    {
+        Some(conv_code) => format!(
+            "{indent}{native_ret_type} ret = {call};\n{conv_code}        return convRet;",
+            indent = indent,
+            native_ret_type = native_ret_type(f_method),
+            call = call,
+            conv_code = conv_code,
+        ),
+        None => format!(
+            "{indent}{return_code}{call};",
+            indent = indent,
+            return_code = if f_method.output.base.name != "void" {
+                "return "
+            } else {
+                ""
+            },
+            call = call,
+        ),
+    }
+}
+
+pub(in crate::java_jni) fn doc_comments_to_java_comments(
+    doc_comments: &[String],
+    class_comments: bool,
+) -> String {
     use std::fmt::Write;
     let mut comments = String::new();
     for (i, comment) in doc_comments.iter().enumerate() {
@@ -536,6 +1194,6 @@ fn get_null_annotation_imports(
     String::new()
 }
 
-fn map_write_err<Err: fmt::Display>(err: Err) -> String {
+pub(in crate::java_jni) fn map_write_err<Err: fmt::Display>(err: Err) -> String {
     format!("write failed: {}", err)
 }
@@ -80,6 +80,8 @@ trait SwigForeignClass {
     fn jni_class_name() -> *const ::std::os::raw::c_char;
     fn box_object(x: Self) -> jlong;
     fn unbox_object(x: jlong) -> Self;
+    /// called after change of `mNativeObj` field of Java object
+    fn jni_native_obj_changed(_obj: jobject, _env: *mut JNIEnv) {}
 }
 
 #[allow(unused_macros)]
@@ -273,6 +275,76 @@ impl Drop for JavaCallback {
     }
 }
 
+/// Exception thrown by Java implementation of `foreign_interface` method,
+/// that returns `Result<T, E>`, where `E: From<JavaException>`
+#[allow(dead_code)]
+#[derive(Debug, Clone)]
+pub struct JavaException {
+    /// Full name of exception class, like `java.io.IOException`
+    pub class_name: String,
+    /// Result of `Throwable.getMessage`, empty if it returns `null`
+    pub message: String,
+}
+
+impl ::std::fmt::Display for JavaException {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+        if self.message.is_empty() {
+            write!(f, "{}", self.class_name)
+        } else {
+            write!(f, "{}: {}", self.class_name, self.message)
+        }
+    }
+}
+
+impl ::std::error::Error for JavaException {}
+
+#[allow(dead_code)]
+fn jni_take_pending_exception(env: *mut JNIEnv) -> JavaException {
+    let ex: jthrowable = unsafe { (**env).ExceptionOccurred.unwrap()(env) };
+    assert!(!ex.is_null(), "jni_take_pending_exception: no pending exception");
+    unsafe { (**env).ExceptionClear.unwrap()(env) };
+
+    let call_string_method = |obj: jobject, name: *const ::std::os::raw::c_char| -> String {
+        let cls: jclass = unsafe { (**env).GetObjectClass.unwrap()(env, obj) };
+        assert!(!cls.is_null(), "jni_take_pending_exception: GetObjectClass failed");
+        let method_id: jmethodID = unsafe {
+            (**env).GetMethodID.unwrap()(env, cls, name, swig_c_str!("()Ljava/lang/String;"))
+        };
+        assert!(
+            !method_id.is_null(),
+            "jni_take_pending_exception: GetMethodID failed"
+        );
+        let ret: jstring = unsafe { (**env).CallObjectMethod.unwrap()(env, obj, method_id) };
+        unsafe {
+            if (**env).ExceptionCheck.unwrap()(env) != 0 {
+                (**env).ExceptionClear.unwrap()(env);
+            }
+            (**env).DeleteLocalRef.unwrap()(env, cls);
+        }
+        let s = JavaString::new(env, ret).to_str().to_string();
+        if !ret.is_null() {
+            unsafe { (**env).DeleteLocalRef.unwrap()(env, ret) };
+        }
+        s
+    };
+
+    let ex_class: jclass = unsafe { (**env).GetObjectClass.unwrap()(env, ex) };
+    assert!(
+        !ex_class.is_null(),
+        "jni_take_pending_exception: GetObjectClass failed"
+    );
+    let class_name = call_string_method(ex_class, swig_c_str!("getName"));
+    let message = call_string_method(ex, swig_c_str!("getMessage"));
+    unsafe {
+        (**env).DeleteLocalRef.unwrap()(env, ex_class);
+        (**env).DeleteLocalRef.unwrap()(env, ex);
+    }
+    JavaException {
+        class_name,
+        message,
+    }
+}
+
 #[allow(dead_code)]
 fn jni_throw(env: *mut JNIEnv, class_name: *const ::std::os::raw::c_char, message: &str) {
     let ex_class = unsafe { (**env).FindClass.unwrap()(env, class_name) };
@@ -298,6 +370,580 @@ fn jni_throw_exception(env: *mut JNIEnv, message: &str) {
     jni_throw(env, swig_c_str!("java/lang/Exception"), message)
 }
 
+#[allow(dead_code)]
+fn jni_throw_object(env: *mut JNIEnv, ex: jthrowable) {
+    assert!(!ex.is_null(), "jni_throw_object: null instead of exception");
+    let res = unsafe {
+        let res = (**env).Throw.unwrap()(env, ex);
+        (**env).DeleteLocalRef.unwrap()(env, ex);
+        res
+    };
+    if res != 0 {
+        error!("Throw of exception object failed");
+    }
+}
+
+/// Construct exception via constructor `ctor_sig` with `value` as argument
+/// and throw it
+#[allow(dead_code)]
+fn jni_throw_with_value(
+    env: *mut JNIEnv,
+    class_name: *const ::std::os::raw::c_char,
+    ctor_sig: *const ::std::os::raw::c_char,
+    value: jobject,
+) {
+    let ex_class = unsafe { (**env).FindClass.unwrap()(env, class_name) };
+    if ex_class.is_null() {
+        error!("throw_exception: can not find exp class {:?}", unsafe {
+            ::std::ffi::CStr::from_ptr(class_name)
+        });
+        return;
+    }
+    let ctor = unsafe {
+        (**env).GetMethodID.unwrap()(env, ex_class, swig_c_str!("<init>"), ctor_sig)
+    };
+    if ctor.is_null() {
+        error!(
+            "throw_exception: can not find constructor {:?} of {:?}",
+            unsafe { ::std::ffi::CStr::from_ptr(ctor_sig) },
+            unsafe { ::std::ffi::CStr::from_ptr(class_name) }
+        );
+        return;
+    }
+    let ex = unsafe { (**env).NewObject.unwrap()(env, ex_class, ctor, value) };
+    unsafe {
+        (**env).DeleteLocalRef.unwrap()(env, value);
+        (**env).DeleteLocalRef.unwrap()(env, ex_class);
+    }
+    if ex.is_null() {
+        error!("throw_exception: construction of {:?} failed", unsafe {
+            ::std::ffi::CStr::from_ptr(class_name)
+        });
+        return;
+    }
+    jni_throw_object(env, ex);
+}
+
+#[allow(dead_code)]
+fn jni_throw_panic(env: *mut JNIEnv, err: Box<dyn ::std::any::Any + Send>) {
+    let msg = if let Some(msg) = err.downcast_ref::<&str>() {
+        msg.to_string()
+    } else if let Some(msg) = err.downcast_ref::<String>() {
+        msg.clone()
+    } else {
+        "unknown panic payload".to_string()
+    };
+    unsafe {
+        if (**env).ExceptionCheck.unwrap()(env) != 0 {
+            (**env).ExceptionClear.unwrap()(env);
+        }
+    }
+    jni_throw(
+        env,
+        swig_c_str!("java/lang/RuntimeException"),
+        &format!("Rust panic: {}", msg),
+    )
+}
+
+/// Value that can be stored into `java.lang.Object`,
+/// primitive types are boxed
+#[allow(dead_code)]
+trait SwigIntoJObject {
+    fn swig_into_jobject(self, env: *mut JNIEnv) -> jobject;
+}
+
+impl SwigIntoJObject for jobject {
+    fn swig_into_jobject(self, _: *mut JNIEnv) -> jobject {
+        self
+    }
+}
+
+impl SwigIntoJObject for () {
+    fn swig_into_jobject(self, _: *mut JNIEnv) -> jobject {
+        ::std::ptr::null_mut()
+    }
+}
+
+macro_rules! impl_swig_into_jobject {
+    ($($type:ty, $field:ident, $class:expr, $sig:expr;)*) => ($(
+        impl SwigIntoJObject for $type {
+            fn swig_into_jobject(self, env: *mut JNIEnv) -> jobject {
+                unsafe {
+                    let class = (**env).FindClass.unwrap()(env, swig_c_str!($class));
+                    assert!(!class.is_null(), "FindClass({}) failed", $class);
+                    let value_of = (**env).GetStaticMethodID.unwrap()(
+                        env,
+                        class,
+                        swig_c_str!("valueOf"),
+                        swig_c_str!($sig),
+                    );
+                    assert!(!value_of.is_null(), "{}.valueOf not found", $class);
+                    let arg = jvalue { $field: self };
+                    let ret = (**env).CallStaticObjectMethodA.unwrap()(env, class, value_of, &arg);
+                    (**env).DeleteLocalRef.unwrap()(env, class);
+                    ret
+                }
+            }
+        }
+    )*)
+}
+
+impl_swig_into_jobject! {
+    jboolean, z, "java/lang/Boolean", "(Z)Ljava/lang/Boolean;";
+    jbyte, b, "java/lang/Byte", "(B)Ljava/lang/Byte;";
+    jshort, s, "java/lang/Short", "(S)Ljava/lang/Short;";
+    jint, i, "java/lang/Integer", "(I)Ljava/lang/Integer;";
+    jlong, j, "java/lang/Long", "(J)Ljava/lang/Long;";
+    jfloat, f, "java/lang/Float", "(F)Ljava/lang/Float;";
+    jdouble, d, "java/lang/Double", "(D)Ljava/lang/Double;";
+}
+
+/// Value that can be extracted from `java.lang.Object`,
+/// primitive types are unboxed
+#[allow(dead_code)]
+trait SwigFromJObject {
+    fn swig_from_jobject(obj: jobject, env: *mut JNIEnv) -> Self;
+}
+
+impl SwigFromJObject for jobject {
+    fn swig_from_jobject(obj: jobject, _: *mut JNIEnv) -> Self {
+        obj
+    }
+}
+
+macro_rules! impl_swig_from_jobject {
+    ($($type:ty, $call:ident, $class:expr, $method:expr, $sig:expr;)*) => ($(
+        impl SwigFromJObject for $type {
+            fn swig_from_jobject(obj: jobject, env: *mut JNIEnv) -> Self {
+                assert!(!obj.is_null(), "Can not convert null to {}", $class);
+                unsafe {
+                    let class = (**env).FindClass.unwrap()(env, swig_c_str!($class));
+                    assert!(!class.is_null(), "FindClass({}) failed", $class);
+                    let method = (**env).GetMethodID.unwrap()(
+                        env,
+                        class,
+                        swig_c_str!($method),
+                        swig_c_str!($sig),
+                    );
+                    assert!(!method.is_null(), "{}.{} not found", $class, $method);
+                    let ret = (**env).$call.unwrap()(env, obj, method, ::std::ptr::null());
+                    (**env).DeleteLocalRef.unwrap()(env, class);
+                    ret
+                }
+            }
+        }
+    )*)
+}
+
+impl_swig_from_jobject! {
+    jboolean, CallBooleanMethodA, "java/lang/Boolean", "booleanValue", "()Z";
+    jbyte, CallByteMethodA, "java/lang/Byte", "byteValue", "()B";
+    jshort, CallShortMethodA, "java/lang/Short", "shortValue", "()S";
+    jint, CallIntMethodA, "java/lang/Integer", "intValue", "()I";
+    jlong, CallLongMethodA, "java/lang/Long", "longValue", "()J";
+    jfloat, CallFloatMethodA, "java/lang/Float", "floatValue", "()F";
+    jdouble, CallDoubleMethodA, "java/lang/Double", "doubleValue", "()D";
+}
+
+/// Take copy of Rust object from Java object of foreign class,
+/// Java object still owns original
+#[allow(dead_code)]
+fn jobject_to_cloned_object<T: SwigForeignClass + Clone>(obj: jobject, env: *mut JNIEnv) -> T {
+    assert!(!obj.is_null(), "jobject_to_cloned_object: object is null");
+    let jcls: jclass = unsafe { (**env).GetObjectClass.unwrap()(env, obj) };
+    assert!(!jcls.is_null(), "jobject_to_cloned_object: GetObjectClass failed");
+    let field_id: jfieldID = unsafe {
+        (**env).GetFieldID.unwrap()(env, jcls, swig_c_str!("mNativeObj"), swig_c_str!("J"))
+    };
+    assert!(
+        !field_id.is_null(),
+        "jobject_to_cloned_object: GetFieldID(mNativeObj) failed"
+    );
+    let x: jlong = unsafe { (**env).GetLongField.unwrap()(env, obj, field_id) };
+    assert_ne!(0, x, "jobject_to_cloned_object: mNativeObj is null");
+    unsafe {
+        (**env).DeleteLocalRef.unwrap()(env, jcls);
+    }
+    let native: &T = unsafe { jlong_to_pointer::<T>(x).as_ref().unwrap() };
+    native.clone()
+}
+
+/// Implementation of `java.util.Map` filled from Rust
+#[allow(dead_code)]
+struct SwigJavaMapBuilder {
+    map: jobject,
+    put: jmethodID,
+}
+
+#[allow(dead_code)]
+impl SwigJavaMapBuilder {
+    /// `class_id` - name of class that implements `java.util.Map`,
+    /// for example "java/util/HashMap"
+    fn new(env: *mut JNIEnv, class_id: *const ::std::os::raw::c_char) -> SwigJavaMapBuilder {
+        unsafe {
+            let class = (**env).FindClass.unwrap()(env, class_id);
+            assert!(!class.is_null(), "SwigJavaMapBuilder: FindClass failed");
+            let init =
+                (**env).GetMethodID.unwrap()(env, class, swig_c_str!("<init>"), swig_c_str!("()V"));
+            assert!(!init.is_null(), "SwigJavaMapBuilder: no default constructor");
+            let put = (**env).GetMethodID.unwrap()(
+                env,
+                class,
+                swig_c_str!("put"),
+                swig_c_str!("(Ljava/lang/Object;Ljava/lang/Object;)Ljava/lang/Object;"),
+            );
+            assert!(!put.is_null(), "SwigJavaMapBuilder: no put method");
+            let map = (**env).NewObject.unwrap()(env, class, init);
+            assert!(!map.is_null(), "SwigJavaMapBuilder: NewObject failed");
+            (**env).DeleteLocalRef.unwrap()(env, class);
+            SwigJavaMapBuilder { map, put }
+        }
+    }
+    /// Put `key` and `value` into map and delete local references to them
+    fn put(&self, env: *mut JNIEnv, key: jobject, value: jobject) {
+        unsafe {
+            let prev = (**env).CallObjectMethod.unwrap()(env, self.map, self.put, key, value);
+            if (**env).ExceptionCheck.unwrap()(env) != 0 {
+                panic!("SwigJavaMapBuilder: put throw exception");
+            }
+            for obj in &[prev, key, value] {
+                if !obj.is_null() {
+                    (**env).DeleteLocalRef.unwrap()(env, *obj);
+                }
+            }
+        }
+    }
+    fn build(self) -> jobject {
+        self.map
+    }
+}
+
+/// Pairs of key and value of `java.util.Map`
+#[allow(dead_code)]
+fn swig_java_map_entries(env: *mut JNIEnv, map: jobject) -> Vec<(jobject, jobject)> {
+    assert!(!map.is_null(), "swig_java_map_entries: map is null");
+    let method = |class_id, name, sig| unsafe {
+        let class = (**env).FindClass.unwrap()(env, class_id);
+        assert!(!class.is_null(), "swig_java_map_entries: FindClass failed");
+        let method = (**env).GetMethodID.unwrap()(env, class, name, sig);
+        assert!(!method.is_null(), "swig_java_map_entries: GetMethodID failed");
+        (**env).DeleteLocalRef.unwrap()(env, class);
+        method
+    };
+    let entry_set = method(
+        swig_c_str!("java/util/Map"),
+        swig_c_str!("entrySet"),
+        swig_c_str!("()Ljava/util/Set;"),
+    );
+    let iterator = method(
+        swig_c_str!("java/util/Set"),
+        swig_c_str!("iterator"),
+        swig_c_str!("()Ljava/util/Iterator;"),
+    );
+    let has_next = method(
+        swig_c_str!("java/util/Iterator"),
+        swig_c_str!("hasNext"),
+        swig_c_str!("()Z"),
+    );
+    let next = method(
+        swig_c_str!("java/util/Iterator"),
+        swig_c_str!("next"),
+        swig_c_str!("()Ljava/lang/Object;"),
+    );
+    let get_key = method(
+        swig_c_str!("java/util/Map$Entry"),
+        swig_c_str!("getKey"),
+        swig_c_str!("()Ljava/lang/Object;"),
+    );
+    let get_value = method(
+        swig_c_str!("java/util/Map$Entry"),
+        swig_c_str!("getValue"),
+        swig_c_str!("()Ljava/lang/Object;"),
+    );
+    let mut ret = Vec::new();
+    unsafe {
+        let set = (**env).CallObjectMethod.unwrap()(env, map, entry_set);
+        let it = (**env).CallObjectMethod.unwrap()(env, set, iterator);
+        while (**env).CallBooleanMethod.unwrap()(env, it, has_next) != 0 {
+            let entry = (**env).CallObjectMethod.unwrap()(env, it, next);
+            let key = (**env).CallObjectMethod.unwrap()(env, entry, get_key);
+            let value = (**env).CallObjectMethod.unwrap()(env, entry, get_value);
+            (**env).DeleteLocalRef.unwrap()(env, entry);
+            ret.push((key, value));
+        }
+        if (**env).ExceptionCheck.unwrap()(env) != 0 {
+            panic!("swig_java_map_entries: iteration throw exception");
+        }
+        (**env).DeleteLocalRef.unwrap()(env, it);
+        (**env).DeleteLocalRef.unwrap()(env, set);
+    }
+    ret
+}
+
+/// Create object of Java class generated for Rust tuple,
+/// `elems` are passed to static `swigFromRust` method of this class
+/// and local references to them are deleted
+#[allow(dead_code)]
+fn swig_tuple_to_jobject(
+    env: *mut JNIEnv,
+    class_id: *const ::std::os::raw::c_char,
+    from_rust_sig: *const ::std::os::raw::c_char,
+    elems: &[jobject],
+) -> jobject {
+    unsafe {
+        let class = (**env).FindClass.unwrap()(env, class_id);
+        assert!(!class.is_null(), "swig_tuple_to_jobject: FindClass failed");
+        let from_rust = (**env).GetStaticMethodID.unwrap()(
+            env,
+            class,
+            swig_c_str!("swigFromRust"),
+            from_rust_sig,
+        );
+        assert!(
+            !from_rust.is_null(),
+            "swig_tuple_to_jobject: no swigFromRust method"
+        );
+        let args: Vec<jvalue> = elems.iter().map(|elem| jvalue { l: *elem }).collect();
+        let ret = (**env).CallStaticObjectMethodA.unwrap()(env, class, from_rust, args.as_ptr());
+        if (**env).ExceptionCheck.unwrap()(env) != 0 {
+            panic!("swig_tuple_to_jobject: swigFromRust throw exception");
+        }
+        for elem in elems {
+            if !elem.is_null() {
+                (**env).DeleteLocalRef.unwrap()(env, *elem);
+            }
+        }
+        (**env).DeleteLocalRef.unwrap()(env, class);
+        ret
+    }
+}
+
+/// Element of Java object of class generated for Rust tuple,
+/// `getter` is name of method that returns it as `java.lang.Object`
+#[allow(dead_code)]
+fn swig_tuple_element(
+    env: *mut JNIEnv,
+    obj: jobject,
+    getter: *const ::std::os::raw::c_char,
+) -> jobject {
+    assert!(!obj.is_null(), "swig_tuple_element: object is null");
+    unsafe {
+        let class = (**env).GetObjectClass.unwrap()(env, obj);
+        assert!(!class.is_null(), "swig_tuple_element: GetObjectClass failed");
+        let method =
+            (**env).GetMethodID.unwrap()(env, class, getter, swig_c_str!("()Ljava/lang/Object;"));
+        assert!(!method.is_null(), "swig_tuple_element: GetMethodID failed");
+        let ret = (**env).CallObjectMethod.unwrap()(env, obj, method);
+        if (**env).ExceptionCheck.unwrap()(env) != 0 {
+            panic!("swig_tuple_element: getter throw exception");
+        }
+        (**env).DeleteLocalRef.unwrap()(env, class);
+        ret
+    }
+}
+
+/// Discriminant of item of `foreign_enum` with data-carrying items,
+/// reported by Java object via `swigTag()`
+#[allow(dead_code)]
+fn swig_data_enum_tag(env: *mut JNIEnv, obj: jobject) -> jint {
+    assert!(!obj.is_null(), "swig_data_enum_tag: object is null");
+    unsafe {
+        let class = (**env).GetObjectClass.unwrap()(env, obj);
+        assert!(!class.is_null(), "swig_data_enum_tag: GetObjectClass failed");
+        let method = (**env).GetMethodID.unwrap()(env, class, swig_c_str!("swigTag"), swig_c_str!("()I"));
+        assert!(!method.is_null(), "swig_data_enum_tag: GetMethodID failed");
+        let ret = (**env).CallIntMethod.unwrap()(env, obj, method);
+        if (**env).ExceptionCheck.unwrap()(env) != 0 {
+            panic!("swig_data_enum_tag: swigTag throw exception");
+        }
+        (**env).DeleteLocalRef.unwrap()(env, class);
+        ret
+    }
+}
+
+/// Global reference to `java.util.concurrent.CompletableFuture`
+/// that should be completed from executor's thread
+#[allow(dead_code)]
+struct SwigCompletableFuture(JavaCallback);
+
+unsafe impl Send for SwigCompletableFuture {}
+
+#[allow(dead_code)]
+impl SwigCompletableFuture {
+    fn complete<C>(self, convert: C)
+    where
+        C: FnOnce(*mut JNIEnv) -> Result<jobject, String>,
+    {
+        let env = self.0.get_jni_env();
+        let env = match env.env {
+            Some(env) => env,
+            None => {
+                error!("SwigCompletableFuture::complete: can not get JNIEnv");
+                return;
+            }
+        };
+        let this = self.0.this;
+        let class = unsafe { (**env).GetObjectClass.unwrap()(env, this) };
+        assert!(!class.is_null(), "GetObjectClass for CompletableFuture failed");
+        unsafe {
+            match convert(env) {
+                Ok(value) => {
+                    let complete = (**env).GetMethodID.unwrap()(
+                        env,
+                        class,
+                        swig_c_str!("complete"),
+                        swig_c_str!("(Ljava/lang/Object;)Z"),
+                    );
+                    assert!(!complete.is_null(), "CompletableFuture.complete not found");
+                    (**env).CallBooleanMethod.unwrap()(env, this, complete, value);
+                    if !value.is_null() {
+                        (**env).DeleteLocalRef.unwrap()(env, value);
+                    }
+                }
+                Err(msg) => {
+                    let ex_class = (**env).FindClass.unwrap()(env, swig_c_str!("java/lang/Exception"));
+                    assert!(!ex_class.is_null(), "FindClass(java/lang/Exception) failed");
+                    let ctor = (**env).GetMethodID.unwrap()(
+                        env,
+                        ex_class,
+                        swig_c_str!("<init>"),
+                        swig_c_str!("(Ljava/lang/String;)V"),
+                    );
+                    assert!(!ctor.is_null(), "Exception(String) not found");
+                    let msg = from_std_string_jstring(msg, env);
+                    let ex = (**env).NewObject.unwrap()(env, ex_class, ctor, msg);
+                    let complete_exceptionally = (**env).GetMethodID.unwrap()(
+                        env,
+                        class,
+                        swig_c_str!("completeExceptionally"),
+                        swig_c_str!("(Ljava/lang/Throwable;)Z"),
+                    );
+                    assert!(
+                        !complete_exceptionally.is_null(),
+                        "CompletableFuture.completeExceptionally not found"
+                    );
+                    (**env).CallBooleanMethod.unwrap()(env, this, complete_exceptionally, ex);
+                    (**env).DeleteLocalRef.unwrap()(env, ex);
+                    (**env).DeleteLocalRef.unwrap()(env, msg);
+                    (**env).DeleteLocalRef.unwrap()(env, ex_class);
+                }
+            }
+            if (**env).ExceptionCheck.unwrap()(env) != 0 {
+                error!("SwigCompletableFuture::complete: java throw exception");
+                (**env).ExceptionDescribe.unwrap()(env);
+                (**env).ExceptionClear.unwrap()(env);
+            }
+            (**env).DeleteLocalRef.unwrap()(env, class);
+        }
+    }
+}
+
+/// Create `java.util.concurrent.CompletableFuture` and pass `future` to `spawn`,
+/// after `future` is ready its output converted by `convert`
+/// inside executor's thread and passed to `CompletableFuture.complete`,
+/// or `CompletableFuture.completeExceptionally` in case of error
+#[allow(dead_code)]
+fn swig_future_to_completable_future<F, C, S>(
+    env: *mut JNIEnv,
+    future: F,
+    convert: C,
+    spawn: S,
+) -> jobject
+where
+    F: ::std::future::Future + Send + 'static,
+    C: FnOnce(*mut JNIEnv, F::Output) -> Result<jobject, String> + Send + 'static,
+    S: FnOnce(::std::pin::Pin<Box<dyn ::std::future::Future<Output = ()> + Send + 'static>>),
+{
+    let obj = unsafe {
+        let class =
+            (**env).FindClass.unwrap()(env, swig_c_str!("java/util/concurrent/CompletableFuture"));
+        assert!(!class.is_null(), "FindClass(CompletableFuture) failed");
+        let ctor = (**env).GetMethodID.unwrap()(env, class, swig_c_str!("<init>"), swig_c_str!("()V"));
+        assert!(!ctor.is_null(), "CompletableFuture() not found");
+        let obj = (**env).NewObject.unwrap()(env, class, ctor);
+        assert!(!obj.is_null(), "CompletableFuture construction failed");
+        (**env).DeleteLocalRef.unwrap()(env, class);
+        obj
+    };
+    let completion = SwigCompletableFuture(JavaCallback::new(obj, env));
+    spawn(swig_future_then(future, move |output| {
+        completion.complete(move |env| convert(env, output))
+    }));
+    obj
+}
+
+/// Future that passes output of `future` to `complete`
+#[allow(dead_code)]
+struct SwigFutureThen<F, C> {
+    future: ::std::pin::Pin<Box<F>>,
+    complete: Option<C>,
+}
+
+impl<F, C> Unpin for SwigFutureThen<F, C> {}
+
+impl<F, C> ::std::future::Future for SwigFutureThen<F, C>
+where
+    F: ::std::future::Future,
+    C: FnOnce(F::Output),
+{
+    type Output = ();
+    fn poll(
+        mut self: ::std::pin::Pin<&mut Self>,
+        cx: &mut ::std::task::Context,
+    ) -> ::std::task::Poll<()> {
+        match self.future.as_mut().poll(cx) {
+            ::std::task::Poll::Ready(output) => {
+                let complete = self
+                    .complete
+                    .take()
+                    .expect("SwigFutureThen polled after completion");
+                complete(output);
+                ::std::task::Poll::Ready(())
+            }
+            ::std::task::Poll::Pending => ::std::task::Poll::Pending,
+        }
+    }
+}
+
+#[allow(dead_code)]
+fn swig_future_then<F, C>(
+    future: F,
+    complete: C,
+) -> ::std::pin::Pin<Box<dyn ::std::future::Future<Output = ()> + Send + 'static>>
+where
+    F: ::std::future::Future + Send + 'static,
+    C: FnOnce(F::Output) + Send + 'static,
+{
+    Box::pin(SwigFutureThen {
+        future: Box::pin(future),
+        complete: Some(complete),
+    })
+}
+
+/// Default executor for methods that return `Future`,
+/// each future is polled until completion on its own thread
+#[allow(dead_code)]
+fn swig_spawn_future(
+    future: ::std::pin::Pin<Box<dyn ::std::future::Future<Output = ()> + Send + 'static>>,
+) {
+    struct ThreadWaker(::std::thread::Thread);
+    impl ::std::task::Wake for ThreadWaker {
+        fn wake(self: ::std::sync::Arc<Self>) {
+            self.0.unpark();
+        }
+    }
+    ::std::thread::spawn(move || {
+        let mut future = future;
+        let waker = ::std::task::Waker::from(::std::sync::Arc::new(ThreadWaker(
+            ::std::thread::current(),
+        )));
+        let mut cx = ::std::task::Context::from_waker(&waker);
+        while future.as_mut().poll(&mut cx).is_pending() {
+            ::std::thread::park();
+        }
+    });
+}
+
 #[swig_to_foreigner_hint = "T"]
 impl<T: SwigForeignClass> SwigFrom<T> for jobject {
     fn swig_from(x: T, env: *mut JNIEnv) -> Self {
@@ -329,9 +975,35 @@ fn object_to_jobject<T: SwigForeignClass>(
             panic!("object_to_jobject: Can not set mNativeObj field: catch exception");
         }
     }
+    <T>::jni_native_obj_changed(jobj, env);
     jobj
 }
 
+/// Return `mNativeObj` of Java object, Java object keeps ownership of it.
+/// `obj` is local reference returned by callback, so it is deleted.
+#[allow(dead_code)]
+fn jobject_native_obj(obj: jobject, env: *mut JNIEnv) -> Option<jlong> {
+    if obj.is_null() {
+        return None;
+    }
+    let jcls: jclass = unsafe { (**env).GetObjectClass.unwrap()(env, obj) };
+    assert!(!jcls.is_null(), "jobject_native_obj: GetObjectClass failed");
+    let field_id: jfieldID = unsafe {
+        (**env).GetFieldID.unwrap()(env, jcls, swig_c_str!("mNativeObj"), swig_c_str!("J"))
+    };
+    assert!(
+        !field_id.is_null(),
+        "jobject_native_obj: GetFieldID(mNativeObj) failed"
+    );
+    let x: jlong = unsafe { (**env).GetLongField.unwrap()(env, obj, field_id) };
+    assert_ne!(0, x, "jobject_native_obj: mNativeObj is null");
+    unsafe {
+        (**env).DeleteLocalRef.unwrap()(env, jcls);
+        (**env).DeleteLocalRef.unwrap()(env, obj);
+    }
+    Some(x)
+}
+
 #[swig_to_foreigner_hint = "T []"]
 impl<T: SwigForeignClass> SwigFrom<Vec<T>> for jobjectArray {
     fn swig_from(x: Vec<T>, env: *mut JNIEnv) -> Self {
@@ -401,6 +1073,9 @@ fn vec_of_objects_to_jobject_array<T: SwigForeignClass>(
             if (**env).ExceptionCheck.unwrap()(env) != 0 {
                 panic!("Can not mNativeObj field: catch exception");
             }
+        }
+        <T>::jni_native_obj_changed(jobj, env);
+        unsafe {
             (**env).SetObjectArrayElement.unwrap()(env, obj_arr, i as jsize, jobj);
             if (**env).ExceptionCheck.unwrap()(env) != 0 {
                 panic!("SetObjectArrayElement({}) failed", i);
@@ -443,7 +1118,7 @@ macro_rules! impl_jni_invalid_value {
 }
 
 impl_jni_invalid_value! {
-    jbyte jshort jint jlong jfloat jdouble
+    jboolean jbyte jshort jint jlong jfloat jdouble
 }
 
 #[swig_generic_arg = "T"]
@@ -641,6 +1316,17 @@ impl SwigFrom<String> for jstring {
     }
 }
 
+impl SwigFrom<jstring> for Option<String> {
+    fn swig_from(x: jstring, env: *mut JNIEnv) -> Self {
+        if !x.is_null() {
+            let s = JavaString::new(env, x);
+            Some(s.to_str().to_string())
+        } else {
+            None
+        }
+    }
+}
+
 #[allow(dead_code)]
 fn from_std_string_jstring(x: String, env: *mut JNIEnv) -> jstring {
     let x = x.into_bytes();
@@ -0,0 +1,1137 @@
+use std::{io::Write, iter, path::Path};
+
+use crate::{
+    file_cache::FileWriteCache,
+    java_jni::{
+        java_code::{doc_comments_to_java_comments, indent_lines, map_write_err},
+        map_type::{is_primitive_type, tuple_element_getter, TUPLE_FIELDS},
+        method_name, ErrorToException, JavaForeignTypeInfo, JniForeignMethodSignature,
+        NullAnnotation,
+    },
+    typemap::ast::if_result_return_ok_err_types,
+    typemap::TypeMap,
+    types::{
+        ForeignEnumInfo, ForeignFlagsInfo, ForeignInterface, ForeignStructField, ForeignStructInfo,
+        ForeignerClassInfo, ForeignerMethod, MethodAccess, MethodVariant,
+    },
+};
+
+/// Kotlin getter and optional setter, that replace `getX`/`setX` methods
+struct Property {
+    name: String,
+    ty: String,
+    getter: usize,
+    setter: Option<usize>,
+}
+
+pub(in crate::java_jni) fn generate_kotlin_code_for_enum(
+    output_dir: &Path,
+    package_name: &str,
+    enum_info: &ForeignEnumInfo,
+) -> Result<(), String> {
+    let path = output_dir.join(format!("{}.kt", enum_info.name));
+    let mut file = FileWriteCache::new(&path);
+    let enum_doc_comments = doc_comments_to_java_comments(&enum_info.doc_comments, true);
+    write!(
+        file,
+        r#"// Automaticaly generated by rust_swig
+package {package_name}
+
+{doc_comments}
+enum class {enum_name}(val value: Int) {{
+"#,
+        package_name = package_name,
+        enum_name = enum_info.name,
+        doc_comments = enum_doc_comments,
+    )
+    .map_err(&map_write_err)?;
+
+    for (i, item) in enum_info.items.iter().enumerate() {
+        writeln!(
+            file,
+            "{doc_comments}{item_name}({index}){separator}",
+            item_name = item.name,
+            index = item.value,
+            doc_comments = doc_comments_to_java_comments(&item.doc_comments, false),
+            separator = if i == enum_info.items.len() - 1 {
+                ';'
+            } else {
+                ','
+            },
+        )
+        .map_err(&map_write_err)?;
+    }
+
+    writeln!(file, "}}").map_err(&map_write_err)?;
+
+    file.update_file_if_necessary().map_err(&map_write_err)?;
+    Ok(())
+}
+
+/// `foreign_flags` is passed as `Int`, so object contains only constants
+/// for bits and conversation into `EnumSet` and back
+pub(in crate::java_jni) fn generate_kotlin_code_for_flags(
+    output_dir: &Path,
+    package_name: &str,
+    flags_info: &ForeignFlagsInfo,
+) -> Result<(), String> {
+    let path = output_dir.join(format!("{}.kt", flags_info.name));
+    let mut file = FileWriteCache::new(&path);
+    let mut constants = String::new();
+    let mut enum_items = Vec::with_capacity(flags_info.items.len());
+    for item in &flags_info.items {
+        let doc_comments = doc_comments_to_java_comments(&item.doc_comments, false);
+        if !doc_comments.is_empty() {
+            constants.push_str(&doc_comments);
+            constants.push('\n');
+        }
+        constants.push_str(&format!(
+            "    const val {}: Int = {:#x}\n",
+            item.name, item.value
+        ));
+        enum_items.push(format!(
+            "        {item}({flags}.{item})",
+            item = item.name,
+            flags = flags_info.name
+        ));
+    }
+    write!(
+        file,
+        r#"// Automaticaly generated by rust_swig
+package {package_name}
+
+{doc_comments}
+object {flags_name} {{
+{constants}
+    private const val SWIG_ALL_BITS: Int = {all_bits:#x}
+
+    enum class Flag(val bit: Int) {{
+{enum_items}
+    }}
+
+    @JvmStatic
+    fun isValid(bits: Int): Boolean = (bits and SWIG_ALL_BITS.inv()) == 0
+
+    @JvmStatic
+    fun checkBits(bits: Int): Int {{
+        require(isValid(bits)) {{
+            "{flags_name}: unknown bits 0x" + Integer.toHexString(bits and SWIG_ALL_BITS.inv())
+        }}
+        return bits
+    }}
+
+    @JvmStatic
+    fun toEnumSet(bits: Int): java.util.EnumSet<Flag> {{
+        checkBits(bits)
+        val ret = java.util.EnumSet.noneOf(Flag::class.java)
+        for (flag in Flag.values()) {{
+            if ((bits and flag.bit) != 0) {{
+                ret.add(flag)
+            }}
+        }}
+        return ret
+    }}
+
+    @JvmStatic
+    fun fromEnumSet(flags: Set<Flag>): Int = flags.fold(0) {{ bits, flag -> bits or flag.bit }}
+}}
+"#,
+        package_name = package_name,
+        doc_comments = doc_comments_to_java_comments(&flags_info.doc_comments, true),
+        flags_name = flags_info.name,
+        constants = constants,
+        all_bits = flags_info.all_bits(),
+        enum_items = enum_items.join(",\n"),
+    )
+    .map_err(&map_write_err)?;
+
+    file.update_file_if_necessary().map_err(&map_write_err)?;
+    Ok(())
+}
+
+/// Exception for methods that return `Result<T, E>`, where `E` is `foreign_enum`
+fn generate_kotlin_code_for_enum_exception(
+    output_dir: &Path,
+    package_name: &str,
+    class_name: &str,
+    enum_name: &str,
+) -> Result<(), String> {
+    let path = output_dir.join(format!("{}.kt", class_name));
+    let mut file = FileWriteCache::new(&path);
+    write!(
+        file,
+        r#"// Automaticaly generated by rust_swig
+package {package_name}
+
+class {class_name}(val value: {enum_name}) : Exception(value.name)
+"#,
+        package_name = package_name,
+        class_name = class_name,
+        enum_name = enum_name,
+    )
+    .map_err(&map_write_err)?;
+
+    file.update_file_if_necessary().map_err(&map_write_err)?;
+    Ok(())
+}
+
+/// Data class for Rust tuple, `java_types` are Java types of its elements
+pub(in crate::java_jni) fn generate_kotlin_code_for_tuple(
+    output_dir: &Path,
+    package_name: &str,
+    class_name: &str,
+    java_types: &[String],
+) -> Result<(), String> {
+    let path = output_dir.join(format!("{}.kt", class_name));
+    let mut file = FileWriteCache::new(&path);
+    let fields = &TUPLE_FIELDS[..java_types.len()];
+    //elements of tuple from Rust are never null
+    let kotlin_types: Vec<String> = java_types
+        .iter()
+        .map(|ty| kotlin_type(ty).trim_end_matches('?').to_string())
+        .collect();
+    let args: Vec<String> = fields
+        .iter()
+        .zip(kotlin_types.iter())
+        .map(|(field, ty)| format!("val {}: {}", field, ty))
+        .collect();
+    let swig_getters: String = fields
+        .iter()
+        .map(|field| {
+            format!(
+                "    @Suppress(\"unused\")\n    private fun {}(): Any = {}\n",
+                tuple_element_getter(field),
+                field
+            )
+        })
+        .collect();
+    let objects: Vec<String> = fields
+        .iter()
+        .map(|field| format!("{}: Any", field))
+        .collect();
+    let casts: Vec<String> = fields
+        .iter()
+        .zip(kotlin_types.iter())
+        .map(|(field, ty)| format!("{} as {}", field, ty))
+        .collect();
+    write!(
+        file,
+        r#"// Automaticaly generated by rust_swig
+package {package_name}
+
+data class {class_name}({args}) {{
+{swig_getters}
+    companion object {{
+        @JvmStatic
+        @Suppress("unused", "UNCHECKED_CAST")
+        private fun swigFromRust({objects}): {class_name} =
+            {class_name}({casts})
+    }}
+}}
+"#,
+        package_name = package_name,
+        class_name = class_name,
+        args = args.join(", "),
+        swig_getters = swig_getters,
+        objects = objects.join(", "),
+        casts = casts.join(", "),
+    )
+    .map_err(&map_write_err)?;
+
+    file.update_file_if_necessary().map_err(&map_write_err)?;
+    Ok(())
+}
+
+/// Data class for `foreign_struct`, `java_types` are Java types of its fields
+pub(in crate::java_jni) fn generate_kotlin_code_for_struct(
+    output_dir: &Path,
+    package_name: &str,
+    struct_info: &ForeignStructInfo,
+    java_types: &[String],
+) -> Result<(), String> {
+    let path = output_dir.join(format!("{}.kt", struct_info.name));
+    let mut file = FileWriteCache::new(&path);
+    write!(
+        file,
+        r#"// Automaticaly generated by rust_swig
+package {package_name}
+
+{doc_comments}
+{class}"#,
+        package_name = package_name,
+        doc_comments = doc_comments_to_java_comments(&struct_info.doc_comments, true),
+        class = kotlin_data_class(
+            &struct_info.name.to_string(),
+            &struct_info.fields,
+            java_types,
+            None,
+        ),
+    )
+    .map_err(&map_write_err)?;
+
+    file.update_file_if_necessary().map_err(&map_write_err)?;
+    Ok(())
+}
+
+/// Sealed class for `foreign_enum` with data-carrying items,
+/// `java_types` are types of fields of each item
+pub(in crate::java_jni) fn generate_kotlin_code_for_data_enum(
+    output_dir: &Path,
+    package_name: &str,
+    enum_info: &ForeignEnumInfo,
+    java_types: &[Vec<String>],
+) -> Result<(), String> {
+    let path = output_dir.join(format!("{}.kt", enum_info.name));
+    let mut file = FileWriteCache::new(&path);
+    write!(
+        file,
+        r#"// Automaticaly generated by rust_swig
+package {package_name}
+
+{doc_comments}
+sealed class {enum_name} {{
+    protected abstract fun swigTag(): Int
+"#,
+        package_name = package_name,
+        doc_comments = doc_comments_to_java_comments(&enum_info.doc_comments, true),
+        enum_name = enum_info.name,
+    )
+    .map_err(&map_write_err)?;
+
+    for (item, item_java_types) in enum_info.items.iter().zip(java_types.iter()) {
+        let class = if item.fields.is_empty() {
+            format!(
+                r#"object {item_name} : {enum_name}() {{
+    override fun swigTag(): Int = {value}
+    override fun toString(): String = "{item_name}"
+
+    @JvmStatic
+    @Suppress("unused")
+    private fun swigFromRust(): {item_name} = this
+}}
+"#,
+                item_name = item.name,
+                enum_name = enum_info.name,
+                value = item.value,
+            )
+        } else {
+            kotlin_data_class(
+                &item.name.to_string(),
+                &item.fields,
+                item_java_types,
+                Some((&enum_info.name.to_string(), item.value)),
+            )
+        };
+        let mut doc_comments = doc_comments_to_java_comments(&item.doc_comments, false);
+        if !doc_comments.is_empty() {
+            doc_comments.push('\n');
+        }
+        write!(
+            file,
+            "\n{doc_comments}{class}",
+            doc_comments = doc_comments,
+            class = indent_lines(&class, "    "),
+        )
+        .map_err(&map_write_err)?;
+    }
+    writeln!(file, "}}").map_err(&map_write_err)?;
+
+    file.update_file_if_necessary().map_err(&map_write_err)?;
+    Ok(())
+}
+
+/// Data class with private methods for Rust code to create it and get its fields,
+/// `extends` is sealed parent class and discriminant of `foreign_enum` item
+fn kotlin_data_class(
+    class_name: &str,
+    fields: &[ForeignStructField],
+    java_types: &[String],
+    extends: Option<(&str, u32)>,
+) -> String {
+    let mut args = Vec::with_capacity(java_types.len());
+    let mut swig_getters = String::new();
+    let mut objects = Vec::with_capacity(java_types.len());
+    let mut casts = Vec::with_capacity(java_types.len());
+    for (field, java_ty) in fields.iter().zip(java_types.iter()) {
+        //fields of struct from Rust are never null
+        let ty = kotlin_type(java_ty).trim_end_matches('?').to_string();
+        let doc_comments = doc_comments_to_java_comments(&field.doc_comments, false);
+        if doc_comments.is_empty() {
+            args.push(format!("    val {}: {}", field.name, ty));
+        } else {
+            args.push(format!("{}\n    val {}: {}", doc_comments, field.name, ty));
+        }
+        swig_getters.push_str(&format!(
+            "    @Suppress(\"unused\")\n    private fun {}(): Any = {}\n",
+            tuple_element_getter(&field.name.to_string()),
+            field.name
+        ));
+        objects.push(format!("{}: Any", field.name));
+        casts.push(format!("{} as {}", field.name, ty));
+    }
+    let (extends, swig_tag) = match extends {
+        Some((parent, value)) => (
+            format!(" : {}()", parent),
+            format!("    override fun swigTag(): Int = {}\n", value),
+        ),
+        None => (String::new(), String::new()),
+    };
+    format!(
+        r#"data class {class_name}(
+{args}
+){extends} {{
+{swig_tag}{swig_getters}
+    companion object {{
+        @JvmStatic
+        @Suppress("unused", "UNCHECKED_CAST")
+        private fun swigFromRust({objects}): {class_name} =
+            {class_name}({casts})
+    }}
+}}
+"#,
+        class_name = class_name,
+        args = args.join(",\n"),
+        extends = extends,
+        swig_tag = swig_tag,
+        swig_getters = swig_getters,
+        objects = objects.join(", "),
+        casts = casts.join(", "),
+    )
+}
+
+fn generate_kotlin_code_for_cleaner(output_dir: &Path, package_name: &str) -> Result<(), String> {
+    let path = output_dir.join("SwigCleaner.kt");
+    let mut file = FileWriteCache::new(&path);
+    write!(
+        file,
+        r#"// Automaticaly generated by rust_swig
+package {package_name}
+
+internal object SwigCleaner {{
+    @JvmField
+    val CLEANER: java.lang.ref.Cleaner = java.lang.ref.Cleaner.create()
+}}
+"#,
+        package_name = package_name,
+    )
+    .map_err(&map_write_err)?;
+    file.update_file_if_necessary().map_err(&map_write_err)?;
+    Ok(())
+}
+
+pub(in crate::java_jni) fn generate_kotlin_code_for_interface(
+    conv_map: &mut TypeMap,
+    output_dir: &Path,
+    package_name: &str,
+    interface: &ForeignInterface,
+    methods_sign: &[JniForeignMethodSignature],
+) -> Result<(), String> {
+    let path = output_dir.join(format!("{}.kt", interface.name));
+    let mut file = FileWriteCache::new(&path);
+    let interface_comments = doc_comments_to_java_comments(&interface.doc_comments, true);
+    write!(
+        file,
+        r#"// Automaticaly generated by rust_swig
+package {package_name}
+
+{doc_comments}
+interface {interface_name} {{
+"#,
+        package_name = package_name,
+        interface_name = interface.name,
+        doc_comments = interface_comments,
+    )
+    .map_err(&map_write_err)?;
+
+    for (method, f_method) in interface.items.iter().zip(methods_sign) {
+        let may_return_error = match method.fn_decl.output {
+            syn::ReturnType::Default => false,
+            syn::ReturnType::Type(_, ref ptype) => {
+                let ret_rust_ty = conv_map.find_or_alloc_rust_type(ptype, interface.src_id);
+                if_result_return_ok_err_types(&ret_rust_ty).is_some()
+            }
+        };
+        write!(
+            file,
+            r#"
+{doc_comments}
+{throws_annotation}    fun {method_name}({args_with_types}){ret_type}
+"#,
+            doc_comments = doc_comments_to_java_comments(&method.doc_comments, false),
+            throws_annotation = if may_return_error {
+                "    @Throws(Exception::class)\n"
+            } else {
+                ""
+            },
+            method_name = kotlin_ident(&method.name.to_string()),
+            args_with_types = args_with_kotlin_types(f_method),
+            ret_type = if f_method.output.base.name == "void" {
+                String::new()
+            } else {
+                format!(": {}", kotlin_api_type(&f_method.output))
+            },
+        )
+        .map_err(&map_write_err)?;
+    }
+
+    writeln!(file, "}}").map_err(&map_write_err)?;
+    file.update_file_if_necessary().map_err(&map_write_err)?;
+    Ok(())
+}
+
+pub(in crate::java_jni) fn generate_kotlin_code(
+    conv_map: &mut TypeMap,
+    output_dir: &Path,
+    package_name: &str,
+    class: &ForeignerClassInfo,
+    methods_sign: &[JniForeignMethodSignature],
+    use_cleaner: bool,
+) -> Result<(), String> {
+    let have_methods = class.methods.iter().any(|m| {
+        m.variant != MethodVariant::Constructor && m.variant != MethodVariant::StaticMethod
+    });
+    let have_constructor = class
+        .methods
+        .iter()
+        .any(|m| m.variant == MethodVariant::Constructor);
+    if have_methods && !have_constructor {
+        return Err(format!(
+            "package {}, class {}: has methods, but no constructor\n
+May be you need to use `private constructor = empty;` syntax?",
+            package_name, class.name
+        ));
+    }
+    for fti in methods_sign
+        .iter()
+        .flat_map(|f_method| f_method.input.iter().chain(iter::once(&f_method.output)))
+    {
+        if let Some(conv) = fti.java_converter.as_ref() {
+            if conv.kotlin_converter.is_none() {
+                return Err(format!(
+                    "class {}: conversation of Java type '{}' requires Java code, \
+                     this is not supported for Kotlin yet",
+                    class.name, fti.base.name
+                ));
+            }
+        }
+    }
+
+    let use_cleaner = use_cleaner && have_constructor;
+    if use_cleaner {
+        generate_kotlin_code_for_cleaner(output_dir, package_name)?;
+    }
+
+    let mut throws_annotations = Vec::with_capacity(class.methods.len());
+    for (method, f_method) in class.methods.iter().zip(methods_sign) {
+        let may_return_error = match method.fn_decl.output {
+            syn::ReturnType::Default => false,
+            syn::ReturnType::Type(_, ref ptype) => {
+                let ret_rust_ty = conv_map.find_or_alloc_rust_type(ptype, class.src_id);
+                if_result_return_ok_err_types(&ret_rust_ty).is_some()
+            }
+        };
+        let throws = match f_method.exception {
+            Some(ref exception) => {
+                if let ErrorToException::EnumValue { ref enum_name, .. } = exception.conv {
+                    generate_kotlin_code_for_enum_exception(
+                        output_dir,
+                        package_name,
+                        &exception.class_name,
+                        enum_name,
+                    )?;
+                }
+                Some(exception.class_name.as_str())
+            }
+            None if may_return_error => Some("Exception"),
+            None => None,
+        };
+        throws_annotations.push(throws);
+    }
+
+    let properties = find_properties(class, methods_sign, &throws_annotations);
+    let path = output_dir.join(format!("{}.kt", class.name));
+    let mut file = FileWriteCache::new(&path);
+
+    let class_doc_comments = doc_comments_to_java_comments(&class.doc_comments, true);
+    write!(
+        file,
+        r#"// Automaticaly generated by rust_swig
+package {package_name}
+
+{doc_comments}
+class {class_name}{implements} {{
+"#,
+        package_name = package_name,
+        class_name = class.name,
+        doc_comments = class_doc_comments,
+        implements = if have_constructor {
+            " : java.io.Closeable"
+        } else {
+            ""
+        },
+    )
+    .map_err(&map_write_err)?;
+
+    let mut companion = String::new();
+    for (idx, (method, f_method)) in class.methods.iter().zip(methods_sign).enumerate() {
+        let func_name = method_name(method, f_method, true);
+        let throws_annotation = throws_annotation(throws_annotations[idx], "    ");
+        let method_access = match method.access {
+            MethodAccess::Private => "private ",
+            MethodAccess::Public => "",
+            MethodAccess::Protected => unreachable!(),
+        };
+        match method.variant {
+            MethodVariant::StaticMethod => {
+                let body = format!(
+                    "{convert_code}{call_code}",
+                    convert_code = convert_code_for_method(f_method),
+                    call_code = call_native_code(
+                        f_method,
+                        &format!(
+                            "{}({})",
+                            func_name,
+                            list_of_args_for_call_method(f_method, false)
+                        )
+                    )
+                );
+                companion.push_str(&format!(
+                    r#"
+{doc_comments}
+        @JvmStatic
+{throws_annotation}        {method_access}fun {method_name}({args_with_types}){ret_type} {{
+{body}
+        }}
+        @JvmStatic
+        private external fun {func_name}({native_args_with_types}){native_ret_type}
+"#,
+                    doc_comments = indent_code(
+                        &doc_comments_to_java_comments(&method.doc_comments, false),
+                        "    "
+                    ),
+                    throws_annotation = indent_code(&throws_annotation, "    "),
+                    method_access = method_access,
+                    method_name = kotlin_ident(&method.short_name()),
+                    args_with_types = args_with_kotlin_types(f_method),
+                    ret_type = kotlin_ret_type(f_method),
+                    body = indent_code(&body, "    "),
+                    func_name = func_name,
+                    native_args_with_types = native_args_with_types(f_method, false),
+                    native_ret_type = native_ret_type(f_method),
+                ));
+            }
+            MethodVariant::Method(_) => {
+                let call_code = call_native_code(
+                    f_method,
+                    &format!(
+                        "{}(mNativeObj{})",
+                        func_name,
+                        list_of_args_for_call_method(f_method, true)
+                    ),
+                );
+                if let Some(prop) = properties.iter().find(|p| p.getter == idx) {
+                    let setter = match prop.setter {
+                        Some(setter_idx) => {
+                            let set_method = &methods_sign[setter_idx];
+                            let set_func_name =
+                                method_name(&class.methods[setter_idx], set_method, true);
+                            format!(
+                                "\n        set(a0) {{\n{convert_code}{call_code}\n        }}",
+                                convert_code =
+                                    indent_code(&convert_code_for_method(set_method), "    "),
+                                call_code = indent_code(
+                                    &call_native_code(
+                                        set_method,
+                                        &format!(
+                                            "{}(mNativeObj{})",
+                                            set_func_name,
+                                            list_of_args_for_call_method(set_method, true)
+                                        )
+                                    ),
+                                    "    "
+                                ),
+                            )
+                        }
+                        None => String::new(),
+                    };
+                    write!(
+                        file,
+                        r#"
+{doc_comments}
+    {kind} {prop_name}: {prop_type}
+        get() {{
+{call_code}
+        }}{setter}
+"#,
+                        doc_comments = doc_comments_to_java_comments(&method.doc_comments, false),
+                        kind = if prop.setter.is_some() { "var" } else { "val" },
+                        prop_name = kotlin_ident(&prop.name),
+                        prop_type = prop.ty,
+                        call_code = indent_code(&call_code, "    "),
+                        setter = setter,
+                    )
+                    .map_err(&map_write_err)?;
+                } else if !properties.iter().any(|p| p.setter == Some(idx)) {
+                    write!(
+                        file,
+                        r#"
+{doc_comments}
+{throws_annotation}    {method_access}fun {method_name}({args_with_types}){ret_type} {{
+{convert_code}{call_code}
+    }}
+"#,
+                        doc_comments = doc_comments_to_java_comments(&method.doc_comments, false),
+                        throws_annotation = throws_annotation,
+                        method_access = method_access,
+                        method_name = kotlin_ident(&method.short_name()),
+                        args_with_types = args_with_kotlin_types(f_method),
+                        ret_type = kotlin_ret_type(f_method),
+                        convert_code = convert_code_for_method(f_method),
+                        call_code = call_code,
+                    )
+                    .map_err(&map_write_err)?;
+                }
+                companion.push_str(&format!(
+                    r#"        @JvmStatic
+        private external fun {func_name}({native_args_with_types}){native_ret_type}
+"#,
+                    func_name = func_name,
+                    native_args_with_types = native_args_with_types(f_method, true),
+                    native_ret_type = native_ret_type(f_method),
+                ));
+            }
+            MethodVariant::Constructor => {
+                if method.is_dummy_constructor() {
+                    write!(
+                        file,
+                        "
+    {method_access}constructor()
+",
+                        method_access = method_access,
+                    )
+                    .map_err(&map_write_err)?;
+                } else {
+                    write!(
+                        file,
+                        r#"
+{doc_comments}
+{throws_annotation}    {method_access}constructor({args_with_types}) {{
+{convert_code}        mNativeObj = {func_name}({args}){cleaner_update}
+    }}
+"#,
+                        doc_comments = doc_comments_to_java_comments(&method.doc_comments, false),
+                        throws_annotation = throws_annotation,
+                        method_access = method_access,
+                        args_with_types = args_with_kotlin_types(f_method),
+                        convert_code = convert_code_for_method(f_method),
+                        func_name = func_name,
+                        args = list_of_args_for_call_method(f_method, false),
+                        cleaner_update = if use_cleaner {
+                            "\n        swigNativeObjChanged()"
+                        } else {
+                            ""
+                        },
+                    )
+                    .map_err(&map_write_err)?;
+                    companion.push_str(&format!(
+                        r#"        @JvmStatic
+        private external fun {func_name}({native_args_with_types}): Long
+"#,
+                        func_name = func_name,
+                        native_args_with_types = native_args_with_types(f_method, false),
+                    ));
+                }
+            }
+        }
+    }
+
+    if have_constructor && use_cleaner {
+        write!(
+            file,
+            "
+    @Synchronized
+    fun delete() {{
+        if (mNativeObj != 0L) {{
+            mNativeObj = 0
+            mCleanable?.clean()
+        }}
+    }}
+
+    override fun close() {{
+        delete()
+    }}
+
+    @JvmName(\"swigNativeObjChanged\")
+    internal fun swigNativeObjChanged() {{
+        if (mNativeObj != 0L) {{
+            val deleter = SwigDeleter(mNativeObj)
+            mDeleter = deleter
+            mCleanable = SwigCleaner.CLEANER.register(this, deleter)
+        }} else {{
+            mDeleter?.nativeObj = 0
+        }}
+    }}
+
+    private class SwigDeleter(@Volatile @JvmField var nativeObj: Long) : Runnable {{
+        override fun run() {{
+            val obj = nativeObj
+            nativeObj = 0
+            if (obj != 0L) {{
+                do_delete(obj)
+            }}
+        }}
+    }}
+
+    private var mDeleter: SwigDeleter? = null
+    private var mCleanable: java.lang.ref.Cleaner.Cleanable? = null
+    @JvmField
+    internal var mNativeObj: Long = 0
+"
+        )
+        .map_err(&map_write_err)?;
+    } else if have_constructor {
+        write!(
+            file,
+            "
+    @Synchronized
+    fun delete() {{
+        if (mNativeObj != 0L) {{
+            do_delete(mNativeObj)
+            mNativeObj = 0
+        }}
+    }}
+
+    override fun close() {{
+        delete()
+    }}
+
+    protected fun finalize() {{
+        delete()
+    }}
+
+    @JvmField
+    internal var mNativeObj: Long = 0
+"
+        )
+        .map_err(&map_write_err)?;
+    }
+    if have_constructor {
+        companion.push_str(
+            "        @JvmStatic
+        private external fun do_delete(me: Long)
+",
+        );
+    }
+
+    //utility class, so add private constructor
+    //to prevent object creation
+    if !have_constructor && !have_methods {
+        writeln!(file, "\n    private constructor()").map_err(&map_write_err)?;
+    }
+
+    let mut helpers = Vec::<&str>::new();
+    for f_method in methods_sign {
+        for fti in f_method.input.iter().chain(iter::once(&f_method.output)) {
+            for helper in conv_map.foreign_type_helpers(&fti.base.name) {
+                if !helpers.contains(&helper.as_str()) {
+                    helpers.push(helper);
+                }
+            }
+        }
+    }
+    for helper in helpers {
+        write!(file, "\n{}\n", helper).map_err(&map_write_err)?;
+    }
+
+    file.write_all(class.foreigner_code.as_bytes())
+        .map_err(&map_write_err)?;
+    if !companion.is_empty() {
+        write!(file, "\n    companion object {{\n{}    }}\n", companion).map_err(&map_write_err)?;
+    }
+    writeln!(file, "}}").map_err(&map_write_err)?;
+
+    file.update_file_if_necessary().map_err(&map_write_err)?;
+    Ok(())
+}
+
+/// Find Java bean style `getX`/`setX` pairs, Kotlin way to access them is property
+fn find_properties(
+    class: &ForeignerClassInfo,
+    methods_sign: &[JniForeignMethodSignature],
+    throws_annotations: &[Option<&str>],
+) -> Vec<Property> {
+    let is_simple_method = |method: &ForeignerMethod, idx: usize| match method.variant {
+        MethodVariant::Method(_) => {
+            method.access == MethodAccess::Public && throws_annotations[idx].is_none()
+        }
+        _ => false,
+    };
+    let mut props = Vec::new();
+    for (getter, (method, f_method)) in class.methods.iter().zip(methods_sign).enumerate() {
+        if !is_simple_method(method, getter)
+            || !f_method.input.is_empty()
+            || f_method.output.base.name == "void"
+        {
+            continue;
+        }
+        let method_name = method.short_name().to_string();
+        let name = match bean_property_name(&method_name, "get") {
+            Some(x) => x,
+            None => continue,
+        };
+        let ty = kotlin_ret_type(f_method)[2..].to_string();
+        let set_name = format!("set{}", &method_name[3..]);
+        let setter = class.methods.iter().zip(methods_sign).enumerate().position(
+            |(idx, (method, f_method))| {
+                is_simple_method(method, idx)
+                    && method.short_name() == set_name
+                    && f_method.input.len() == 1
+                    && f_method.output.base.name == "void"
+                    && kotlin_api_type(&f_method.input[0]) == ty
+            },
+        );
+        props.push(Property {
+            name,
+            ty,
+            getter,
+            setter,
+        });
+    }
+    props
+}
+
+/// `getFooBar` -> `fooBar`
+fn bean_property_name(method_name: &str, prefix: &str) -> Option<String> {
+    if !method_name.starts_with(prefix) {
+        return None;
+    }
+    let mut rest = method_name[prefix.len()..].chars();
+    match rest.next() {
+        Some(first) if first.is_ascii_uppercase() => {
+            Some(iter::once(first.to_ascii_lowercase()).chain(rest).collect())
+        }
+        _ => None,
+    }
+}
+
+/// Kotlin type that has the same JVM signature as Java type
+fn kotlin_type(java_type: &str) -> String {
+    let java_type = java_type.trim();
+    if let Some(elem) = java_type.strip_suffix("[]") {
+        let elem = elem.trim();
+        return match elem {
+            "boolean" => "BooleanArray".into(),
+            "byte" => "ByteArray".into(),
+            "short" => "ShortArray".into(),
+            "int" => "IntArray".into(),
+            "long" => "LongArray".into(),
+            "float" => "FloatArray".into(),
+            "double" => "DoubleArray".into(),
+            "char" => "CharArray".into(),
+            _ => format!("Array<{}>", kotlin_type(elem)),
+        };
+    }
+    if let (Some(start), true) = (java_type.find('<'), java_type.ends_with('>')) {
+        let mut params: Vec<String> = java_type[start + 1..java_type.len() - 1]
+            .split(',')
+            .map(kotlin_type)
+            .collect();
+        let generic = match &java_type[..start] {
+            "java.util.Map" => {
+                //keys and values of map from Rust are never null
+                for param in &mut params {
+                    if param.ends_with('?') {
+                        param.pop();
+                    }
+                }
+                "Map"
+            }
+            generic => generic,
+        };
+        return format!("{}<{}>", generic, params.join(", "));
+    }
+    match java_type {
+        "void" => "Unit",
+        "boolean" => "Boolean",
+        "byte" => "Byte",
+        "short" => "Short",
+        "int" => "Int",
+        "long" => "Long",
+        "float" => "Float",
+        "double" => "Double",
+        "char" => "Char",
+        "Boolean" | "java.lang.Boolean" => "Boolean?",
+        "Byte" | "java.lang.Byte" => "Byte?",
+        "Short" | "java.lang.Short" => "Short?",
+        "Integer" | "java.lang.Integer" => "Int?",
+        "Long" | "java.lang.Long" => "Long?",
+        "Float" | "java.lang.Float" => "Float?",
+        "Double" | "java.lang.Double" => "Double?",
+        "Character" | "java.lang.Character" => "Char?",
+        "String" | "java.lang.String" => "String",
+        "Object" | "java.lang.Object" => "Any",
+        _ => java_type,
+    }
+    .into()
+}
+
+/// Type in API of Kotlin class, nullable if Rust type is `Option<T>`
+fn kotlin_api_type(fti: &JavaForeignTypeInfo) -> String {
+    let ty = kotlin_type(&fti.base.name);
+    match fti.annotation {
+        Some(NullAnnotation::Nullable)
+            if !is_primitive_type(&fti.base.name) && !ty.ends_with('?') =>
+        {
+            ty + "?"
+        }
+        _ => ty,
+    }
+}
+
+/// For `Optional` returned from native method, nullable type
+/// and expression that converts `ret` into it
+fn optional_to_nullable(java_type: &str) -> Option<(String, &'static str)> {
+    if java_type.ends_with(".OptionalDouble") {
+        Some((
+            "Double?".into(),
+            "if (ret.isPresent) ret.asDouble else null",
+        ))
+    } else if java_type.ends_with(".OptionalLong") {
+        Some(("Long?".into(), "if (ret.isPresent) ret.asLong else null"))
+    } else if java_type.ends_with(".OptionalInt") {
+        Some(("Int?".into(), "if (ret.isPresent) ret.asInt else null"))
+    } else {
+        let start = java_type.find('<')?;
+        if java_type[..start].ends_with(".Optional") && java_type.ends_with('>') {
+            let inner = kotlin_type(&java_type[start + 1..java_type.len() - 1]);
+            let inner = if inner.ends_with('?') {
+                inner
+            } else {
+                inner + "?"
+            };
+            Some((inner, "ret.orElse(null)"))
+        } else {
+            None
+        }
+    }
+}
+
+fn kotlin_ret_type(f_method: &JniForeignMethodSignature) -> String {
+    let output = &f_method.output;
+    if output.base.name == "void" {
+        return String::new();
+    }
+    match optional_to_nullable(&output.base.name) {
+        Some((ty, _)) => format!(": {}", ty),
+        None => format!(": {}", kotlin_api_type(output)),
+    }
+}
+
+fn native_ret_type(f_method: &JniForeignMethodSignature) -> String {
+    let output = &f_method.output;
+    if output.base.name == "void" {
+        String::new()
+    } else {
+        format!(": {}", kotlin_type(&output.base.name))
+    }
+}
+
+fn args_with_kotlin_types(f_method: &JniForeignMethodSignature) -> String {
+    f_method
+        .input
+        .iter()
+        .enumerate()
+        .map(|(i, arg)| format!("a{}: {}", i, kotlin_api_type(arg)))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn native_args_with_types(f_method: &JniForeignMethodSignature, with_self: bool) -> String {
+    let this_arg = if with_self {
+        Some("me: Long".into())
+    } else {
+        None
+    };
+    this_arg
+        .into_iter()
+        .chain(f_method.input.iter().enumerate().map(|(i, arg)| {
+            let type_name = match arg.java_converter.as_ref() {
+                Some(converter) => converter.java_transition_type.as_str(),
+                None => arg.base.name.as_str(),
+            };
+            format!("a{}: {}", i, kotlin_type(type_name))
+        }))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn list_of_args_for_call_method(
+    f_method: &JniForeignMethodSignature,
+    comma_before: bool,
+) -> String {
+    let mut res = String::new();
+    for (i, arg) in f_method.input.iter().enumerate() {
+        if i != 0 || comma_before {
+            res.push_str(", ");
+        }
+        if arg.java_converter.is_some() {
+            res.push_str(&format!("a{}C0", i));
+        } else {
+            res.push_str(&format!("a{}", i));
+        }
+    }
+    res
+}
+
+fn convert_code_for_method(f_method: &JniForeignMethodSignature) -> String {
+    let mut ret = String::new();
+    for (i, arg) in f_method.input.iter().enumerate() {
+        if let Some(kotlin_code) = arg.kotlin_convert(|| (format!("a{}", i), format!("a{}C0", i))) {
+            ret.push_str(kotlin_code.trim_start_matches('\n'));
+        }
+    }
+    ret
+}
+
+/// Call of native function and return of its result,
+/// `Optional` converted to nullable type
+fn call_native_code(f_method: &JniForeignMethodSignature, call: &str) -> String {
+    if f_method.output.base.name == "void" {
+        return format!("        {}", call);
+    }
+    match optional_to_nullable(&f_method.output.base.name) {
+        Some((_, conv)) => format!("        val ret = {}\n        return {}", call, conv),
+        None => format!("        return {}", call),
+    }
+}
+
+fn throws_annotation(exception: Option<&str>, indent: &str) -> String {
+    match exception {
+        Some(class_name) => format!("{}@Throws({}::class)\n", indent, class_name),
+        None => String::new(),
+    }
+}
+
+fn indent_code(code: &str, indent: &str) -> String {
+    code.split('\n')
+        .map(|line| {
+            if line.is_empty() {
+                String::new()
+            } else {
+                format!("{}{}", indent, line)
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Escape name if it is Kotlin keyword
+fn kotlin_ident(name: &str) -> String {
+    match name {
+        "as" | "break" | "class" | "continue" | "do" | "else" | "false" | "for" | "fun" | "if"
+        | "in" | "interface" | "is" | "null" | "object" | "package" | "return" | "super"
+        | "this" | "throw" | "true" | "try" | "typealias" | "typeof" | "val" | "var" | "when"
+        | "while" => format!("`{}`", name),
+        _ => name.into(),
+    }
+}
@@ -1,32 +1,863 @@
 use log::trace;
 use petgraph::Direction;
+use proc_macro2::{Ident, TokenStream};
+use smol_str::SmolStr;
 use syn::{parse_quote, Type};
 
 use crate::{
     error::{DiagnosticError, Result, SourceIdSpan},
-    java_jni::{calc_this_type_for_method, JavaConverter, JavaForeignTypeInfo, NullAnnotation},
+    java_jni::{
+        calc_this_type_for_method, java_class_full_name, java_class_name_to_jni,
+        java_code::generate_java_code_for_tuple, kotlin_code::generate_kotlin_code_for_tuple,
+        JavaConverter, JavaForeignTypeInfo, JavaFutureInfo, NullAnnotation,
+    },
     source_registry::SourceId,
     typemap::{
-        ast::{if_option_return_some_type, normalize_ty_lifetimes},
+        ast::{
+            if_map_return_key_value_types, if_option_return_some_type,
+            if_result_return_ok_err_types, normalize_ty_lifetimes,
+            parse_ty_with_given_span_checked, DisplayToTokens, MapKind, TypeName,
+        },
         ty::RustType,
-        ForeignTypeInfo, FROM_VAR_TEMPLATE, TO_VAR_TEMPLATE,
+        unpack_unique_typename, ForeignTypeInfo, RustTypeIdx, TypeConvEdge, FROM_VAR_TEMPLATE,
+        TO_VAR_TEMPLATE, TO_VAR_TYPE_TEMPLATE,
+    },
+    types::{
+        ForeignEnumInfo, ForeignFlagsInfo, ForeignStructField, ForeignStructInfo,
+        ForeignerClassInfo,
     },
-    types::{ForeignEnumInfo, ForeignerClassInfo},
-    TypeMap,
+    JavaConfig, TypeMap,
 };
 
+const FOREIGN_CLASS_TRAIT: &str = "SwigForeignClass";
+
 pub(in crate::java_jni) fn map_type(
     conv_map: &mut TypeMap,
+    java_cfg: &JavaConfig,
     arg_ty: &RustType,
     direction: Direction,
     arg_ty_span: SourceIdSpan,
 ) -> Result<JavaForeignTypeInfo> {
+    if let Some(fti) = map_map_type(conv_map, arg_ty, direction, arg_ty_span)? {
+        return Ok(fti);
+    }
+    if let Some(fti) = map_tuple_type(conv_map, java_cfg, arg_ty, direction, arg_ty_span)? {
+        return Ok(fti);
+    }
     if direction == Direction::Incoming {
-        if let Some(fti) = special_type(conv_map, &arg_ty, arg_ty_span)? {
+        if let Some(fti) = special_type(conv_map, &arg_ty, arg_ty_span, java_cfg.use_cleaner)? {
             return Ok(fti);
         }
     }
+    map_ordinal_type(conv_map, arg_ty, direction, arg_ty_span)
+}
+
+/// Map `(A, B)` and `(A, B, C)` to immutable Java class generated for
+/// such tuple, elements are converted one by one on Rust side
+fn map_tuple_type(
+    conv_map: &mut TypeMap,
+    java_cfg: &JavaConfig,
+    arg_ty: &RustType,
+    direction: Direction,
+    arg_ty_span: SourceIdSpan,
+) -> Result<Option<JavaForeignTypeInfo>> {
+    let elems: Vec<Type> = match arg_ty.ty {
+        Type::Tuple(ref tuple) if tuple.elems.len() == 2 || tuple.elems.len() == 3 => {
+            tuple.elems.iter().cloned().collect()
+        }
+        _ => return Ok(None),
+    };
+    let mut java_types = Vec::with_capacity(elems.len());
+    let mut deps = TokenStream::new();
+    let mut elems_code = String::new();
+    for (elem_ty, field) in elems.iter().zip(TUPLE_FIELDS.iter()) {
+        let (java_ty, elem_deps, code) =
+            map_element_through_jobject(conv_map, elem_ty, field, "tuple", direction, arg_ty_span)?;
+        java_types.push(java_ty);
+        deps.extend(elem_deps);
+        elems_code.push_str(&code);
+    }
+    let deps = if deps.is_empty() { None } else { Some(deps) };
+
+    let class_name = match java_cfg
+        .tuple_class_names
+        .get(arg_ty.normalized_name.as_str())
+    {
+        Some(name) => name.clone(),
+        None => tuple_class_name(&java_types),
+    };
+    let generate_class = java_cfg
+        .generated_tuple_classes
+        .borrow_mut()
+        .insert(class_name.clone());
+    if generate_class {
+        if java_cfg.use_kotlin {
+            generate_kotlin_code_for_tuple(
+                &java_cfg.output_dir,
+                &java_cfg.package_name,
+                &class_name,
+                &java_types,
+            )
+        } else {
+            generate_java_code_for_tuple(
+                &java_cfg.output_dir,
+                &java_cfg.package_name,
+                &class_name,
+                &java_types,
+            )
+        }
+        .map_err(|err| DiagnosticError::new2(arg_ty_span, err))?;
+    }
+
+    let fields = &TUPLE_FIELDS[..elems.len()];
+    let jobject_ty = conv_map.find_or_alloc_rust_type_no_src_id(&parse_type! { jobject });
+    match direction {
+        Direction::Outgoing => {
+            if !conv_map.has_conversation_rule(arg_ty, &jobject_ty) {
+                let jni_class_name = java_class_name_to_jni(&java_class_full_name(
+                    &java_cfg.package_name,
+                    &class_name,
+                ));
+                let code = format!(
+                    r#"
+    let mut {to_var}: {to_var_type} = {{
+        let ({fields}) = {from_var};
+{elems_code}
+        swig_tuple_to_jobject(env, swig_c_str!("{class}"), swig_c_str!("({args_sig})L{class};"), &[{fields}])
+    }};
+"#,
+                    to_var = TO_VAR_TEMPLATE,
+                    to_var_type = TO_VAR_TYPE_TEMPLATE,
+                    from_var = FROM_VAR_TEMPLATE,
+                    fields = fields.join(", "),
+                    elems_code = elems_code,
+                    class = jni_class_name,
+                    args_sig = "Ljava/lang/Object;".repeat(elems.len()),
+                );
+                conv_map.add_conversation_rule(
+                    arg_ty.clone(),
+                    jobject_ty.clone(),
+                    TypeConvEdge::new(code, deps),
+                );
+            }
+        }
+        Direction::Incoming => {
+            if !conv_map.has_conversation_rule(&jobject_ty, arg_ty) {
+                let get_elems: String = fields
+                    .iter()
+                    .map(|field| {
+                        format!(
+                            "        let {field}: jobject = swig_tuple_element(env, {from_var}, swig_c_str!(\"{getter}\"));\n",
+                            field = field,
+                            from_var = FROM_VAR_TEMPLATE,
+                            getter = tuple_element_getter(field),
+                        )
+                    })
+                    .collect();
+                let code = format!(
+                    r#"
+    let mut {to_var}: {to_var_type} = {{
+{get_elems}{elems_code}
+        ({fields})
+    }};
+"#,
+                    to_var = TO_VAR_TEMPLATE,
+                    to_var_type = TO_VAR_TYPE_TEMPLATE,
+                    get_elems = get_elems,
+                    elems_code = elems_code,
+                    fields = fields.join(", "),
+                );
+                conv_map.add_conversation_rule(
+                    jobject_ty.clone(),
+                    arg_ty.clone(),
+                    TypeConvEdge::new(code, deps),
+                );
+            }
+        }
+    }
+    Ok(Some(JavaForeignTypeInfo {
+        base: ForeignTypeInfo {
+            name: class_name.into(),
+            correspoding_rust_type: jobject_ty,
+        },
+        java_converter: None,
+        annotation: Some(NullAnnotation::NonNull),
+    }))
+}
+
+/// Names of fields of Java class generated for tuple
+pub(in crate::java_jni) const TUPLE_FIELDS: [&str; 3] = ["first", "second", "third"];
+
+/// Private method of Java class generated for tuple or `foreign_struct`
+/// that returns field as `java.lang.Object`
+pub(in crate::java_jni) fn tuple_element_getter(field: &str) -> String {
+    let mut chars = field.chars();
+    match chars.next() {
+        Some(first) => format!("swig{}{}", first.to_ascii_uppercase(), chars.as_str()),
+        None => String::new(),
+    }
+}
+
+/// Default name of Java class for tuple, like `Pair_Integer_String`
+fn tuple_class_name(java_types: &[String]) -> String {
+    let mut name = if java_types.len() == 2 {
+        "Pair"
+    } else {
+        "Triple"
+    }
+    .to_string();
+    for java_ty in java_types {
+        let java_ty: String = java_ty.chars().filter(|ch| !ch.is_whitespace()).collect();
+        let java_ty = boxed_type(&java_ty);
+        let (java_ty, dims) = {
+            let base = java_ty.trim_end_matches("[]");
+            (base, (java_ty.len() - base.len()) / 2)
+        };
+        let java_ty = match java_ty.find('<') {
+            Some(pos) => &java_ty[..pos],
+            None => java_ty,
+        };
+        let simple_name = java_ty.rsplit('.').next().unwrap_or(java_ty);
+        name.push('_');
+        let mut chars = simple_name.chars();
+        if let Some(first) = chars.next() {
+            name.push(first.to_ascii_uppercase());
+            name.push_str(chars.as_str());
+        }
+        for _ in 0..dims {
+            name.push_str("Array");
+        }
+    }
+    name
+}
+
+/// Rust code that converts fields of `foreign_struct` or data-carrying
+/// `foreign_enum` item into `jobject` one by one and back
+struct FieldsThroughJObject {
+    java_types: Vec<String>,
+    vars: Vec<String>,
+    to_java_code: String,
+    /// fields are extracted from `FROM_VAR_TEMPLATE`
+    from_java_code: String,
+}
+
+fn map_fields_through_jobject(
+    conv_map: &mut TypeMap,
+    container: &str,
+    src_id: SourceId,
+    fields: &[ForeignStructField],
+    to_java_deps: &mut TokenStream,
+    from_java_deps: &mut TokenStream,
+) -> Result<FieldsThroughJObject> {
+    let mut ret = FieldsThroughJObject {
+        java_types: Vec::with_capacity(fields.len()),
+        vars: Vec::with_capacity(fields.len()),
+        to_java_code: String::new(),
+        from_java_code: String::new(),
+    };
+    for field in fields {
+        let field_span = (src_id, field.name.span());
+        let var = format!("f_{}", field.name);
+        let (java_ty, elem_deps, code) = map_element_through_jobject(
+            conv_map,
+            &field.ty,
+            &var,
+            container,
+            Direction::Outgoing,
+            field_span,
+        )?;
+        to_java_deps.extend(elem_deps);
+        ret.to_java_code.push_str(&code);
+        let (input_java_ty, elem_deps, code) = map_element_through_jobject(
+            conv_map,
+            &field.ty,
+            &var,
+            container,
+            Direction::Incoming,
+            field_span,
+        )?;
+        if java_ty != input_java_ty {
+            return Err(DiagnosticError::new2(
+                field_span,
+                format!(
+                    "Java: field '{}' of {} has different Java types \
+                     for output ({}) and input ({})",
+                    field.name, container, java_ty, input_java_ty
+                ),
+            ));
+        }
+        from_java_deps.extend(elem_deps);
+        ret.from_java_code.push_str(&format!(
+            "        let {var}: jobject = swig_tuple_element(env, {from_var}, swig_c_str!(\"{getter}\"));\n{code}",
+            var = var,
+            from_var = FROM_VAR_TEMPLATE,
+            getter = tuple_element_getter(&field.name.to_string()),
+            code = code,
+        ));
+        ret.java_types.push(java_ty);
+        ret.vars.push(var);
+    }
+    Ok(ret)
+}
+
+/// `jobject` type for Java class generated for `foreign_struct` or `foreign_enum`
+fn alloc_jobject_type_for(
+    conv_map: &mut TypeMap,
+    name: &Ident,
+    src_id: SourceId,
+) -> Result<(RustType, RustType)> {
+    let rust_ty = conv_map.find_or_alloc_rust_type(
+        &parse_ty_with_given_span_checked(&name.to_string(), name.span()),
+        src_id,
+    );
+    let jobject_ty = conv_map.find_or_alloc_rust_type_with_suffix(
+        &parse_type! { jobject },
+        &name.to_string(),
+        SourceId::none(),
+    );
+    conv_map.add_foreign(jobject_ty.clone(), TypeName::from_ident(name, src_id))?;
+    Ok((rust_ty, jobject_ty))
+}
 
+/// Register conversations of `foreign_struct` into object of Java class
+/// generated for it and back, fields are converted one by one on Rust side,
+/// returns Java types of fields
+pub(in crate::java_jni) fn register_struct(
+    conv_map: &mut TypeMap,
+    package_name: &str,
+    struct_info: &ForeignStructInfo,
+) -> Result<Vec<String>> {
+    let struct_name = struct_info.rust_struct_name();
+    let mut to_java_deps = TokenStream::new();
+    let mut from_java_deps = TokenStream::new();
+    let fields = map_fields_through_jobject(
+        conv_map,
+        &format!("foreign_struct {}", struct_name),
+        struct_info.src_id,
+        &struct_info.fields,
+        &mut to_java_deps,
+        &mut from_java_deps,
+    )?;
+    let to_java_deps = if to_java_deps.is_empty() {
+        None
+    } else {
+        Some(to_java_deps)
+    };
+    let from_java_deps = if from_java_deps.is_empty() {
+        None
+    } else {
+        Some(from_java_deps)
+    };
+    let fields_init: Vec<String> = struct_info
+        .fields
+        .iter()
+        .zip(fields.vars.iter())
+        .map(|(field, var)| format!("{}: {}", field.name, var))
+        .collect();
+
+    let (struct_ty, jobject_ty) =
+        alloc_jobject_type_for(conv_map, &struct_info.name, struct_info.src_id)?;
+    let jni_class_name = java_class_name_to_jni(&java_class_full_name(package_name, &struct_name));
+    let code = format!(
+        r#"
+    let mut {to_var}: {to_var_type} = {{
+        let {struct_name} {{ {fields_init} }} = {from_var};
+{to_java_code}
+        swig_tuple_to_jobject(env, swig_c_str!("{class}"), swig_c_str!("({args_sig})L{class};"), &[{vars}])
+    }};
+"#,
+        to_var = TO_VAR_TEMPLATE,
+        to_var_type = TO_VAR_TYPE_TEMPLATE,
+        from_var = FROM_VAR_TEMPLATE,
+        struct_name = struct_name,
+        fields_init = fields_init.join(", "),
+        to_java_code = fields.to_java_code,
+        class = jni_class_name,
+        args_sig = "Ljava/lang/Object;".repeat(fields.vars.len()),
+        vars = fields.vars.join(", "),
+    );
+    conv_map.add_conversation_rule(
+        struct_ty.clone(),
+        jobject_ty.clone(),
+        TypeConvEdge::new(code, to_java_deps),
+    );
+    let code = format!(
+        r#"
+    let mut {to_var}: {to_var_type} = {{
+{from_java_code}
+        {struct_name} {{ {fields_init} }}
+    }};
+"#,
+        to_var = TO_VAR_TEMPLATE,
+        to_var_type = TO_VAR_TYPE_TEMPLATE,
+        from_java_code = fields.from_java_code,
+        struct_name = struct_name,
+        fields_init = fields_init.join(", "),
+    );
+    conv_map.add_conversation_rule(
+        jobject_ty,
+        struct_ty,
+        TypeConvEdge::new(code, from_java_deps),
+    );
+    conv_map.register_exported_struct(struct_info);
+    Ok(fields.java_types)
+}
+
+/// Register conversations of `foreign_enum` with data-carrying items into
+/// object of nested class generated for item and back, Java side
+/// reports item via `swigTag()`, returns Java types of fields of each item
+pub(in crate::java_jni) fn register_data_enum(
+    conv_map: &mut TypeMap,
+    package_name: &str,
+    enum_info: &ForeignEnumInfo,
+) -> Result<Vec<Vec<String>>> {
+    let enum_name = enum_info.rust_enum_name();
+    let mut to_java_deps = TokenStream::new();
+    let mut from_java_deps = TokenStream::new();
+    let mut to_java_arms = String::new();
+    let mut from_java_arms = String::new();
+    let mut java_types = Vec::with_capacity(enum_info.items.len());
+    for item in &enum_info.items {
+        let fields = map_fields_through_jobject(
+            conv_map,
+            &format!("foreign_enum item {}::{}", enum_name, item.name),
+            enum_info.src_id,
+            &item.fields,
+            &mut to_java_deps,
+            &mut from_java_deps,
+        )?;
+        let fields_init: Vec<String> = item
+            .fields
+            .iter()
+            .zip(fields.vars.iter())
+            .map(|(field, var)| format!("{}: {}", item.rust_field_name(field), var))
+            .collect();
+        let jni_class_name = java_class_name_to_jni(&java_class_full_name(
+            package_name,
+            &format!("{}${}", enum_name, item.name),
+        ));
+        to_java_arms.push_str(&format!(
+            r#"
+        {item_path} {{ {fields_init} }} => {{
+{to_java_code}
+            swig_tuple_to_jobject(env, swig_c_str!("{class}"), swig_c_str!("({args_sig})L{class};"), &[{vars}])
+        }}
+"#,
+            item_path = DisplayToTokens(&item.rust_name),
+            fields_init = fields_init.join(", "),
+            to_java_code = fields.to_java_code,
+            class = jni_class_name,
+            args_sig = "Ljava/lang/Object;".repeat(fields.vars.len()),
+            vars = fields.vars.join(", "),
+        ));
+        from_java_arms.push_str(&format!(
+            r#"
+        {value} => {{
+{from_java_code}
+            {item_path} {{ {fields_init} }}
+        }}
+"#,
+            value = item.value,
+            from_java_code = fields.from_java_code,
+            item_path = DisplayToTokens(&item.rust_name),
+            fields_init = fields_init.join(", "),
+        ));
+        java_types.push(fields.java_types);
+    }
+    let to_java_deps = if to_java_deps.is_empty() {
+        None
+    } else {
+        Some(to_java_deps)
+    };
+    let from_java_deps = if from_java_deps.is_empty() {
+        None
+    } else {
+        Some(from_java_deps)
+    };
+
+    let (enum_ty, jobject_ty) =
+        alloc_jobject_type_for(conv_map, &enum_info.name, enum_info.src_id)?;
+    let code = format!(
+        r#"
+    let mut {to_var}: {to_var_type} = match {from_var} {{
+{to_java_arms}
+    }};
+"#,
+        to_var = TO_VAR_TEMPLATE,
+        to_var_type = TO_VAR_TYPE_TEMPLATE,
+        from_var = FROM_VAR_TEMPLATE,
+        to_java_arms = to_java_arms,
+    );
+    conv_map.add_conversation_rule(
+        enum_ty.clone(),
+        jobject_ty.clone(),
+        TypeConvEdge::new(code, to_java_deps),
+    );
+    let code = format!(
+        r#"
+    let mut {to_var}: {to_var_type} = match swig_data_enum_tag(env, {from_var}) {{
+{from_java_arms}
+        tag => panic!("{{}} not expected for {enum_name}", tag),
+    }};
+"#,
+        to_var = TO_VAR_TEMPLATE,
+        to_var_type = TO_VAR_TYPE_TEMPLATE,
+        from_var = FROM_VAR_TEMPLATE,
+        from_java_arms = from_java_arms,
+        enum_name = enum_name,
+    );
+    conv_map.add_conversation_rule(jobject_ty, enum_ty, TypeConvEdge::new(code, from_java_deps));
+    conv_map.register_exported_data_enum(enum_info);
+    Ok(java_types)
+}
+
+/// Map `HashMap<K, V>` and `BTreeMap<K, V>` (or reference to it as input)
+/// to `java.util.Map<K, V>`, keys and values are converted one by one on Rust side
+fn map_map_type(
+    conv_map: &mut TypeMap,
+    arg_ty: &RustType,
+    direction: Direction,
+    arg_ty_span: SourceIdSpan,
+) -> Result<Option<JavaForeignTypeInfo>> {
+    let map_ty = match arg_ty.ty {
+        Type::Reference(syn::TypeReference {
+            ref elem,
+            mutability: None,
+            ..
+        }) if direction == Direction::Incoming => {
+            conv_map.find_or_alloc_rust_type(elem, arg_ty_span.0)
+        }
+        _ => arg_ty.clone(),
+    };
+    let (kind, key_ty, value_ty) = match if_map_return_key_value_types(&map_ty) {
+        Some(x) => x,
+        None => return Ok(None),
+    };
+    let (key_java_ty, key_deps, key_code) =
+        map_element_through_jobject(conv_map, &key_ty, "key", "map", direction, arg_ty_span)?;
+    let (value_java_ty, value_deps, value_code) =
+        map_element_through_jobject(conv_map, &value_ty, "value", "map", direction, arg_ty_span)?;
+    let mut deps = TokenStream::new();
+    deps.extend(key_deps.into_iter().chain(value_deps));
+    let deps = if deps.is_empty() { None } else { Some(deps) };
+
+    let jobject_ty = conv_map.find_or_alloc_rust_type_no_src_id(&parse_type! { jobject });
+    match direction {
+        Direction::Outgoing => {
+            if !conv_map.has_conversation_rule(&map_ty, &jobject_ty) {
+                let java_map_class = match kind {
+                    MapKind::HashMap => "java/util/HashMap",
+                    MapKind::BTreeMap => "java/util/TreeMap",
+                };
+                let code = format!(
+                    r#"
+    let mut {to_var}: {to_var_type} = {{
+        let swig_map = SwigJavaMapBuilder::new(env, swig_c_str!("{class}"));
+        for (key, value) in {from_var} {{
+{key_code}{value_code}
+            swig_map.put(env, key, value);
+        }}
+        swig_map.build()
+    }};
+"#,
+                    to_var = TO_VAR_TEMPLATE,
+                    to_var_type = TO_VAR_TYPE_TEMPLATE,
+                    from_var = FROM_VAR_TEMPLATE,
+                    class = java_map_class,
+                    key_code = key_code,
+                    value_code = value_code,
+                );
+                conv_map.add_conversation_rule(
+                    map_ty,
+                    jobject_ty.clone(),
+                    TypeConvEdge::new(code, deps),
+                );
+            }
+        }
+        Direction::Incoming => {
+            if !conv_map.has_conversation_rule(&jobject_ty, &map_ty) {
+                let code = format!(
+                    r#"
+    let mut {to_var}: {to_var_type} = {{
+        let mut swig_map: {to_var_type} = ::std::default::Default::default();
+        for (key, value) in swig_java_map_entries(env, {from_var}) {{
+{key_code}{value_code}
+            swig_map.insert(key, value);
+        }}
+        swig_map
+    }};
+"#,
+                    to_var = TO_VAR_TEMPLATE,
+                    to_var_type = TO_VAR_TYPE_TEMPLATE,
+                    from_var = FROM_VAR_TEMPLATE,
+                    key_code = key_code,
+                    value_code = value_code,
+                );
+                conv_map.add_conversation_rule(
+                    jobject_ty.clone(),
+                    map_ty.clone(),
+                    TypeConvEdge::new(code, deps),
+                );
+            }
+            if map_ty.normalized_name != arg_ty.normalized_name
+                && !conv_map.has_conversation_rule(&map_ty, arg_ty)
+            {
+                let code = format!(
+                    "let mut {to_var}: {to_var_type} = &{from_var};",
+                    to_var = TO_VAR_TEMPLATE,
+                    to_var_type = TO_VAR_TYPE_TEMPLATE,
+                    from_var = FROM_VAR_TEMPLATE,
+                );
+                conv_map.add_conversation_rule(map_ty, arg_ty.clone(), code.into());
+            }
+        }
+    }
+    Ok(Some(JavaForeignTypeInfo {
+        base: ForeignTypeInfo {
+            name: format!(
+                "java.util.Map<{}, {}>",
+                boxed_type(&key_java_ty),
+                boxed_type(&value_java_ty)
+            )
+            .into(),
+            correspoding_rust_type: jobject_ty,
+        },
+        java_converter: None,
+        annotation: Some(NullAnnotation::NonNull),
+    }))
+}
+
+/// Java type of element of map or tuple (`container`), and Rust code
+/// that converts variable `var` from element type to `jobject` or vice versa
+fn map_element_through_jobject(
+    conv_map: &mut TypeMap,
+    elem_ty: &Type,
+    var: &str,
+    container: &str,
+    direction: Direction,
+    arg_ty_span: SourceIdSpan,
+) -> Result<(String, Vec<TokenStream>, String)> {
+    let elem_ty = conv_map.find_or_alloc_rust_type(elem_ty, arg_ty_span.0);
+    if direction == Direction::Incoming {
+        if let Some(this_ty) = conv_map.is_ty_implements(&elem_ty, FOREIGN_CLASS_TRAIT) {
+            let class_name = conv_map
+                .find_foreigner_class_with_such_this_type(&this_ty.ty, calc_this_type_for_method)
+                .map(|class| class.name.to_string())
+                .ok_or_else(|| {
+                    DiagnosticError::new2(
+                        arg_ty_span,
+                        format!("Can not find foreigner_class for '{}'", elem_ty),
+                    )
+                })?;
+            let code = format!(
+                "            let mut {var}: {ty} = jobject_to_cloned_object({var}, env);\n",
+                var = var,
+                ty = unpack_unique_typename(&elem_ty.normalized_name),
+            );
+            return Ok((class_name, vec![], code));
+        }
+        if let Some(foreign_enum) = conv_map.is_this_exported_enum(&elem_ty) {
+            let enum_name = foreign_enum.name.to_string();
+            // enum passed as object, so use the same `jobject` as for output
+            let fti = map_ordinal_type(conv_map, &elem_ty, Direction::Outgoing, arg_ty_span)?;
+            let (deps, code) = conv_map.convert_rust_types(
+                &fti.base.correspoding_rust_type,
+                &elem_ty,
+                var,
+                "jobject",
+                arg_ty_span,
+            )?;
+            return Ok((enum_name, deps, code));
+        }
+    }
+    let fti = map_ordinal_type(conv_map, &elem_ty, direction, arg_ty_span)?;
+    if fti.java_converter.is_some() {
+        return Err(DiagnosticError::new2(
+            arg_ty_span,
+            format!(
+                "Java: conversation of '{}' inside of {} is not supported yet",
+                elem_ty, container
+            ),
+        ));
+    }
+    let jni_ty = fti.base.correspoding_rust_type.clone();
+    let (deps, code) = match direction {
+        Direction::Outgoing => {
+            let (deps, code) =
+                conv_map.convert_rust_types(&elem_ty, &jni_ty, var, "jobject", arg_ty_span)?;
+            (
+                deps,
+                format!(
+                    "{code}            let mut {var}: jobject = {var}.swig_into_jobject(env);\n",
+                    code = code,
+                    var = var,
+                ),
+            )
+        }
+        Direction::Incoming => {
+            let (deps, code) =
+                conv_map.convert_rust_types(&jni_ty, &elem_ty, var, "jobject", arg_ty_span)?;
+            (
+                deps,
+                format!(
+                    "            let mut {var}: {jni_ty} = <{jni_ty}>::swig_from_jobject({var}, env);\n{code}",
+                    var = var,
+                    jni_ty = unpack_unique_typename(&jni_ty.normalized_name),
+                    code = code,
+                ),
+            )
+        }
+    };
+    Ok((fti.base.name.to_string(), deps, code))
+}
+
+/// Map `T` of `Future<Output = T>` returned by method,
+/// Java code gets `CompletableFuture` with boxed Java type of `T`
+pub(in crate::java_jni) fn map_future_type(
+    conv_map: &mut TypeMap,
+    java_cfg: &JavaConfig,
+    output_ty: &Type,
+    output_ty_span: SourceIdSpan,
+) -> Result<(JavaForeignTypeInfo, JavaFutureInfo)> {
+    let output_ty = conv_map.find_or_alloc_rust_type(output_ty, output_ty_span.0);
+    let value_ty = match if_result_return_ok_err_types(&output_ty) {
+        Some((ok_ty, _err_ty)) => conv_map.find_or_alloc_rust_type(&ok_ty, output_ty_span.0),
+        None => output_ty.clone(),
+    };
+    let value = map_type(
+        conv_map,
+        java_cfg,
+        &value_ty,
+        Direction::Outgoing,
+        output_ty_span,
+    )?;
+    let future_type = |java_type: &str| {
+        format!(
+            "java.util.concurrent.CompletableFuture<{}>",
+            boxed_type(java_type)
+        )
+    };
+    let java_converter = value.java_converter.as_ref().map(|conv| {
+        let value_conv = conv
+            .converter
+            .replace(TO_VAR_TEMPLATE, "convX")
+            .replace(FROM_VAR_TEMPLATE, "x");
+        JavaConverter {
+            java_transition_type: future_type(&conv.java_transition_type).into(),
+            converter: format!(
+                "        {ty} {to_var} = {from_var}.thenApply(x -> {{\n{value_conv}\n            return convX;\n        }});\n",
+                ty = future_type(&value.base.name),
+                to_var = TO_VAR_TEMPLATE,
+                from_var = FROM_VAR_TEMPLATE,
+                value_conv = value_conv
+                    .trim_matches('\n')
+                    .lines()
+                    .map(|line| format!("    {}", line))
+                    .collect::<Vec<_>>()
+                    .join("\n"),
+            ),
+            kotlin_converter: None,
+        }
+    });
+    let jobject_ty = conv_map.find_or_alloc_rust_type_no_src_id(&parse_type! { jobject });
+    let fti = JavaForeignTypeInfo {
+        base: ForeignTypeInfo {
+            name: future_type(&value.base.name).into(),
+            correspoding_rust_type: jobject_ty,
+        },
+        java_converter,
+        annotation: Some(NullAnnotation::NonNull),
+    };
+    Ok((
+        fti,
+        JavaFutureInfo {
+            output_ty,
+            value_ty,
+            value,
+        },
+    ))
+}
+
+/// Java class for values of primitive type, like `Integer` for `int`
+pub(in crate::java_jni) fn boxed_type(java_type: &str) -> &str {
+    match java_type {
+        "void" => "Void",
+        "boolean" => "Boolean",
+        "byte" => "Byte",
+        "short" => "Short",
+        "int" => "Integer",
+        "long" => "Long",
+        "float" => "Float",
+        "double" => "Double",
+        "char" => "Character",
+        _ => java_type,
+    }
+}
+
+/// Map type of value returned from Java callback into Rust,
+/// foreign classes and enums are returned from Java as objects
+pub(in crate::java_jni) fn map_callback_ret_type(
+    conv_map: &mut TypeMap,
+    ret_ty: &RustType,
+    ret_ty_span: SourceIdSpan,
+) -> Result<JavaForeignTypeInfo> {
+    let (ty, is_option) = match if_option_return_some_type(ret_ty) {
+        Some(inner_ty) => (
+            conv_map.find_or_alloc_rust_type(&inner_ty, ret_ty_span.0),
+            true,
+        ),
+        None => (ret_ty.clone(), false),
+    };
+    let foreign_name_and_suffix = if ty.implements.contains(FOREIGN_CLASS_TRAIT) {
+        let class = conv_map
+            .find_foreigner_class_with_such_this_type(&ty.ty, calc_this_type_for_method)
+            .ok_or_else(|| {
+                DiagnosticError::new2(
+                    ret_ty_span,
+                    format!("Can not find foreigner_class for '{}'", ty),
+                )
+            })?;
+        Some((class.name.to_string(), ty.normalized_name.to_string()))
+    } else if let Some(foreign_enum) = conv_map.is_this_exported_enum(&ty) {
+        if !is_option {
+            let name = foreign_enum.name.to_string();
+            Some((name.clone(), name))
+        } else {
+            None
+        }
+    } else {
+        None
+    };
+    match foreign_name_and_suffix {
+        Some((foreign_name, suffix)) => {
+            let jobject_ti = conv_map.find_or_alloc_rust_type_with_suffix(
+                &parse_type! { jobject },
+                &suffix,
+                SourceId::none(),
+            );
+            Ok(JavaForeignTypeInfo {
+                base: ForeignTypeInfo {
+                    name: foreign_name.into(),
+                    correspoding_rust_type: jobject_ti,
+                },
+                java_converter: None,
+                annotation: Some(if is_option {
+                    NullAnnotation::Nullable
+                } else {
+                    NullAnnotation::NonNull
+                }),
+            })
+        }
+        None => map_ordinal_type(conv_map, ret_ty, Direction::Incoming, ret_ty_span),
+    }
+}
+
+fn map_ordinal_type(
+    conv_map: &mut TypeMap,
+    arg_ty: &RustType,
+    direction: Direction,
+    arg_ty_span: SourceIdSpan,
+) -> Result<JavaForeignTypeInfo> {
     let fti = {
         let fti = conv_map
             .map_through_conversation_to_foreign(
@@ -36,7 +867,7 @@ pub(in crate::java_jni) fn map_type(
                 calc_this_type_for_method,
             )
             .ok_or_else(|| {
-                DiagnosticError::new2(
+                let err = DiagnosticError::new2(
                     arg_ty_span,
                     format!(
                         "can not find conversation Java type {} \
@@ -47,32 +878,86 @@ pub(in crate::java_jni) fn map_type(
                         },
                         arg_ty,
                     ),
-                )
+                );
+                conv_map.explain_missing_foreign_type(err, arg_ty, direction)
             })?;
         let ftype = &conv_map[fti];
-        let rtype_idx = match direction {
-            petgraph::Direction::Outgoing => {
-                ftype
-                    .into_from_rust
-                    .as_ref()
-                    .expect("Internal error: into_from_rust not defined")
-                    .rust_ty
+        let rule = match direction {
+            petgraph::Direction::Outgoing => ftype
+                .into_from_rust
+                .as_ref()
+                .expect("Internal error: into_from_rust not defined"),
+            petgraph::Direction::Incoming => ftype
+                .from_into_rust
+                .as_ref()
+                .expect("Internal error: from_into_rust not defined"),
+        };
+        let null_annotation = ftype.null_annotation;
+        match rule.intermediate.as_ref() {
+            Some(intermediate) if intermediate.input_to_output => {
+                return Err(DiagnosticError::new2(
+                    arg_ty_span,
+                    format!(
+                        "Java: conversation of '{}' via `input_to_output` rule of {} \
+                         is not supported",
+                        arg_ty, ftype.name
+                    ),
+                ));
             }
-            petgraph::Direction::Incoming => {
-                ftype
-                    .from_into_rust
-                    .as_ref()
-                    .expect("Internal error: from_into_rust not defined")
-                    .rust_ty
+            Some(intermediate) => {
+                let name = ftype.name.typename.clone();
+                let intermediate_ty = intermediate.intermediate_ty;
+                let conv_code = intermediate.conv_code.clone();
+                let (java_transition_type, rtype_idx) =
+                    map_intermediate_type(conv_map, intermediate_ty, direction, arg_ty_span)?;
+                let to_java_type = match direction {
+                    Direction::Outgoing => &name,
+                    Direction::Incoming => &java_transition_type,
+                };
+                let converter = if conv_code.is_statement() {
+                    format!(
+                        "        {ty} {to_var};\n        {code}\n",
+                        ty = to_java_type,
+                        to_var = TO_VAR_TEMPLATE,
+                        code = conv_code.as_str().trim(),
+                    )
+                } else {
+                    format!(
+                        "        {ty} {to_var} = {code};\n",
+                        ty = to_java_type,
+                        to_var = TO_VAR_TEMPLATE,
+                        code = conv_code.as_str().trim(),
+                    )
+                };
+                (
+                    ForeignTypeInfo {
+                        name,
+                        correspoding_rust_type: conv_map[rtype_idx].clone(),
+                    },
+                    Some(JavaConverter {
+                        java_transition_type,
+                        converter,
+                        kotlin_converter: None,
+                    }),
+                    null_annotation,
+                )
             }
-        };
-        ForeignTypeInfo {
-            name: ftype.name.typename.clone(),
-            correspoding_rust_type: conv_map[rtype_idx].clone(),
+            None => (
+                ForeignTypeInfo {
+                    name: ftype.name.typename.clone(),
+                    correspoding_rust_type: conv_map[rule.rust_ty].clone(),
+                },
+                None,
+                null_annotation,
+            ),
         }
     };
+    let (fti, java_converter, null_annotation) = fti;
     let mut fti: JavaForeignTypeInfo = fti.into();
-    if !is_primitive_type(&fti.base.name) {
+    fti.java_converter = java_converter;
+    if let Some(null_annotation) = null_annotation {
+        fti.annotation = Some(null_annotation);
+    } else if !is_primitive_type(&fti.base.name) {
         fti.annotation = Some(if if_option_return_some_type(arg_ty).is_none() {
             NullAnnotation::NonNull
         } else {
@@ -82,19 +967,63 @@ pub(in crate::java_jni) fn map_type(
     Ok(fti)
 }
 
+/// Find Java type for intermediate Rust type of `foreign_typemap!` rule,
+/// returns name of this Java type and Rust type that used in JNI function
+fn map_intermediate_type(
+    conv_map: &mut TypeMap,
+    intermediate_ty: RustTypeIdx,
+    direction: Direction,
+    arg_ty_span: SourceIdSpan,
+) -> Result<(SmolStr, RustTypeIdx)> {
+    let intermediate_ty = conv_map[intermediate_ty].clone();
+    let ftype_idx = conv_map
+        .map_through_conversation_to_foreign(
+            &intermediate_ty,
+            direction,
+            arg_ty_span,
+            calc_this_type_for_method,
+        )
+        .ok_or_else(|| {
+            let err = DiagnosticError::new2(
+                arg_ty_span,
+                format!(
+                    "can not find Java type for intermediate Rust type '{}'",
+                    intermediate_ty
+                ),
+            );
+            conv_map.explain_missing_foreign_type(err, &intermediate_ty, direction)
+        })?;
+    let ftype = &conv_map[ftype_idx];
+    let rule = match direction {
+        Direction::Outgoing => ftype.into_from_rust.as_ref(),
+        Direction::Incoming => ftype.from_into_rust.as_ref(),
+    };
+    match rule {
+        Some(rule) if rule.intermediate.is_none() => {
+            Ok((ftype.name.typename.clone(), rule.rust_ty))
+        }
+        _ => Err(DiagnosticError::new2(
+            arg_ty_span,
+            format!(
+                "Java type '{}' for intermediate Rust type '{}' also requires conversation",
+                ftype.name, intermediate_ty
+            ),
+        )),
+    }
+}
+
 pub(in crate::java_jni) fn special_type(
     conv_map: &mut TypeMap,
     arg_ty: &RustType,
     arg_ty_span: SourceIdSpan,
+    use_cleaner: bool,
 ) -> Result<Option<JavaForeignTypeInfo>> {
-    let foreign_class_trait = "SwigForeignClass";
-
     trace!(
         "special_type: check is arg.ty({}) implements foreign_class_trait",
         arg_ty
     );
 
-    if let Some(foreign_class_this_ty) = conv_map.is_ty_implements(arg_ty, foreign_class_trait) {
+    if let Some(foreign_class_this_ty) = conv_map.is_ty_implements(arg_ty, FOREIGN_CLASS_TRAIT) {
         let foreigner_class = conv_map
             .find_foreigner_class_with_such_this_type(
                 &foreign_class_this_ty.ty,
@@ -107,7 +1036,8 @@ pub(in crate::java_jni) fn special_type(
                     format!("Can not find foreigner_class for '{}'", arg_ty),
                 )
             })?;
-        let converter = calc_converter_for_foreign_class_arg(conv_map, foreigner_class, arg_ty);
+        let converter =
+            calc_converter_for_foreign_class_arg(conv_map, foreigner_class, arg_ty, use_cleaner);
         return Ok(Some(converter));
     }
     trace!(
@@ -118,6 +1048,10 @@ pub(in crate::java_jni) fn special_type(
         let converter = calc_converter_for_enum(conv_map, foreign_enum);
         return Ok(Some(converter));
     }
+    if let Some(foreign_flags) = conv_map.is_this_exported_flags(arg_ty) {
+        let converter = calc_converter_for_flags(conv_map, foreign_flags);
+        return Ok(Some(converter));
+    }
 
     trace!(
         "special_type: check is arg.ty({}) self type of foreign class",
@@ -137,6 +1071,11 @@ pub(in crate::java_jni) fn special_type(
                     from_var = FROM_VAR_TEMPLATE
                 ),
                 java_transition_type: "long".into(),
+                kotlin_converter: Some(format!(
+                    "        val {to_var} = {from_var}.mNativeObj\n",
+                    to_var = TO_VAR_TEMPLATE,
+                    from_var = FROM_VAR_TEMPLATE
+                )),
             }),
             annotation: Some(NullAnnotation::NonNull),
         };
@@ -144,7 +1083,7 @@ pub(in crate::java_jni) fn special_type(
     }
 
     if let Some(ty) = if_option_return_some_type(arg_ty) {
-        return handle_option_type_in_input(conv_map, &ty, arg_ty_span.0);
+        return handle_option_type_in_input(conv_map, &ty, arg_ty_span.0, use_cleaner);
     }
 
     trace!("special_type: oridinary type {}", arg_ty);
@@ -155,27 +1094,49 @@ fn calc_converter_for_foreign_class_arg(
     conv_map: &TypeMap,
     foreigner_class: &ForeignerClassInfo,
     arg_ty: &RustType,
+    use_cleaner: bool,
 ) -> JavaForeignTypeInfo {
     let this_ty = calc_this_type_for_method(conv_map, foreigner_class).unwrap();
     let this_ty = conv_map.ty_to_rust_type(&this_ty);
 
-    let converter = if this_ty.normalized_name == arg_ty.normalized_name {
-        format!(
-            r#"
+    let (converter, kotlin_converter) = if this_ty.normalized_name == arg_ty.normalized_name {
+        (
+            format!(
+                r#"
         long {to_var} = {from_var}.mNativeObj;
-        {from_var}.mNativeObj = 0;
+        {from_var}.mNativeObj = 0;{cleaner_update}
+"#,
+                to_var = TO_VAR_TEMPLATE,
+                from_var = FROM_VAR_TEMPLATE,
+                cleaner_update = cleaner_update_code(use_cleaner, "        ", ";"),
+            ),
+            format!(
+                r#"
+        val {to_var} = {from_var}.mNativeObj
+        {from_var}.mNativeObj = 0{cleaner_update}
 "#,
-            to_var = TO_VAR_TEMPLATE,
-            from_var = FROM_VAR_TEMPLATE
+                to_var = TO_VAR_TEMPLATE,
+                from_var = FROM_VAR_TEMPLATE,
+                cleaner_update = cleaner_update_code(use_cleaner, "        ", ""),
+            ),
         )
     } else if let syn::Type::Reference(syn::TypeReference { ref elem, .. }) = arg_ty.ty {
         assert_eq!(normalize_ty_lifetimes(elem), this_ty.normalized_name);
-        format!(
-            r#"
+        (
+            format!(
+                r#"
         long {to_var} = {from_var}.mNativeObj;
 "#,
-            to_var = TO_VAR_TEMPLATE,
-            from_var = FROM_VAR_TEMPLATE
+                to_var = TO_VAR_TEMPLATE,
+                from_var = FROM_VAR_TEMPLATE
+            ),
+            format!(
+                r#"
+        val {to_var} = {from_var}.mNativeObj
+"#,
+                to_var = TO_VAR_TEMPLATE,
+                from_var = FROM_VAR_TEMPLATE
+            ),
         )
     } else {
         unreachable!();
@@ -189,11 +1150,27 @@ fn calc_converter_for_foreign_class_arg(
         java_converter: Some(JavaConverter {
             java_transition_type: "long".into(),
             converter,
+            kotlin_converter: Some(kotlin_converter),
         }),
         annotation: Some(NullAnnotation::NonNull),
     }
 }
 
+/// Ownership of Rust object moved from Java object,
+/// so Cleaner of Java object should not free it
+fn cleaner_update_code(use_cleaner: bool, indent: &str, line_end: &str) -> String {
+    if use_cleaner {
+        format!(
+            "\n{indent}{from_var}.swigNativeObjChanged(){line_end}",
+            indent = indent,
+            from_var = FROM_VAR_TEMPLATE,
+            line_end = line_end,
+        )
+    } else {
+        String::new()
+    }
+}
+
 fn calc_converter_for_enum(
     conv_map: &TypeMap,
     foreign_enum: &ForeignEnumInfo,
@@ -202,6 +1179,13 @@ fn calc_converter_for_enum(
     let converter = format!(
         r#"
         int {to_var} = {from_var}.getValue();
+"#,
+        to_var = TO_VAR_TEMPLATE,
+        from_var = FROM_VAR_TEMPLATE
+    );
+    let kotlin_converter = format!(
+        r#"
+        val {to_var} = {from_var}.value
 "#,
         to_var = TO_VAR_TEMPLATE,
         from_var = FROM_VAR_TEMPLATE
@@ -214,15 +1198,53 @@ fn calc_converter_for_enum(
         java_converter: Some(JavaConverter {
             java_transition_type: "int".into(),
             converter,
+            kotlin_converter: Some(kotlin_converter),
         }),
         annotation: Some(NullAnnotation::NonNull),
     }
 }
 
+/// Bits are checked before call, so Rust side gets only known flags
+fn calc_converter_for_flags(
+    conv_map: &TypeMap,
+    foreign_flags: &ForeignFlagsInfo,
+) -> JavaForeignTypeInfo {
+    let jint_ti = conv_map.ty_to_rust_type(&parse_type! { jint });
+    let converter = format!(
+        r#"
+        int {to_var} = {flags}.checkBits({from_var});
+"#,
+        to_var = TO_VAR_TEMPLATE,
+        from_var = FROM_VAR_TEMPLATE,
+        flags = foreign_flags.name,
+    );
+    let kotlin_converter = format!(
+        r#"
+        val {to_var} = {flags}.checkBits({from_var})
+"#,
+        to_var = TO_VAR_TEMPLATE,
+        from_var = FROM_VAR_TEMPLATE,
+        flags = foreign_flags.name,
+    );
+    JavaForeignTypeInfo {
+        base: ForeignTypeInfo {
+            name: "int".into(),
+            correspoding_rust_type: jint_ti,
+        },
+        java_converter: Some(JavaConverter {
+            java_transition_type: "int".into(),
+            converter,
+            kotlin_converter: Some(kotlin_converter),
+        }),
+        annotation: None,
+    }
+}
+
 fn handle_option_type_in_input(
     conv_map: &mut TypeMap,
     opt_inside_ty: &Type,
     arg_src_id: SourceId,
+    use_cleaner: bool,
 ) -> Result<Option<JavaForeignTypeInfo>> {
     let opt_inside_rust_ty = conv_map.find_or_alloc_rust_type(opt_inside_ty, arg_src_id);
     if let Some(fclass) =
@@ -240,13 +1262,26 @@ fn handle_option_type_in_input(
         long {to_var} = 0;//TODO: use ptr::null() for corresponding constant
         if ({from_var} != null) {{
             {to_var} = {from_var}.mNativeObj;
-            {from_var}.mNativeObj = 0;
+            {from_var}.mNativeObj = 0;{cleaner_update}
         }}
 "#,
                     to_var = TO_VAR_TEMPLATE,
-                    from_var = FROM_VAR_TEMPLATE
+                    from_var = FROM_VAR_TEMPLATE,
+                    cleaner_update = cleaner_update_code(use_cleaner, "            ", ";"),
                 ),
                 java_transition_type: "long".into(),
+                kotlin_converter: Some(format!(
+                    r#"
+        var {to_var}: Long = 0
+        if ({from_var} != null) {{
+            {to_var} = {from_var}.mNativeObj
+            {from_var}.mNativeObj = 0{cleaner_update}
+        }}
+"#,
+                    to_var = TO_VAR_TEMPLATE,
+                    from_var = FROM_VAR_TEMPLATE,
+                    cleaner_update = cleaner_update_code(use_cleaner, "            ", ""),
+                )),
             }),
             annotation: Some(NullAnnotation::Nullable),
         }))
@@ -255,7 +1290,7 @@ fn handle_option_type_in_input(
     }
 }
 
-fn is_primitive_type(type_name: &str) -> bool {
+pub(in crate::java_jni) fn is_primitive_type(type_name: &str) -> bool {
     match type_name {
         "void" | "boolean" | "byte" | "short" | "int" | "long" | "float" | "double" => true,
         _ => false,
@@ -1,8 +1,9 @@
 mod java_code;
+mod kotlin_code;
 mod map_type;
 mod rust_code;
 
-use std::fmt;
+use std::{fmt, iter};
 
 use log::debug;
 use petgraph::Direction;
@@ -10,29 +11,25 @@ use proc_macro2::TokenStream;
 use smol_str::SmolStr;
 use syn::{parse_quote, spanned::Spanned, Type};
 
-use self::map_type::map_type;
+use self::map_type::{map_callback_ret_type, map_future_type, map_type};
 use crate::{
     error::{DiagnosticError, Result},
     source_registry::SourceId,
     typemap::ast::{
-        fn_arg_type, if_result_return_ok_err_types, if_ty_result_return_ok_type,
-        parse_ty_with_given_span_checked, DisplayToTokens, TypeName,
+        fn_arg_type, if_future_return_output_type, if_result_return_ok_err_types,
+        if_ty_result_return_ok_type, parse_ty_with_given_span_checked, DisplayToTokens, TypeName,
     },
     typemap::{
-        ty::RustType, ForeignMethodSignature, ForeignTypeInfo, FROM_VAR_TEMPLATE, TO_VAR_TEMPLATE,
+        ty::{NullAnnotation, RustType},
+        ForeignMethodSignature, ForeignTypeInfo, FROM_VAR_TEMPLATE, TO_VAR_TEMPLATE,
     },
     types::{
-        ForeignEnumInfo, ForeignInterface, ForeignerClassInfo, ForeignerMethod, MethodVariant,
+        ForeignEnumInfo, ForeignFlagsInfo, ForeignInterface, ForeignStructInfo, ForeignerClassInfo,
+        ForeignerMethod, MethodVariant,
     },
     JavaConfig, LanguageGenerator, SourceCode, TypeMap,
 };
 
-#[derive(Clone, Copy)]
-enum NullAnnotation {
-    NonNull,
-    Nullable,
-}
-
 struct JavaForeignTypeInfo {
     pub base: ForeignTypeInfo,
     pub java_converter: Option<JavaConverter>,
@@ -42,6 +39,8 @@ struct JavaForeignTypeInfo {
 struct JavaConverter {
     java_transition_type: SmolStr,
     converter: String,
+    /// the same conversation in Kotlin, `None` if it can be done only in Java
+    kotlin_converter: Option<String>,
 }
 
 impl AsRef<ForeignTypeInfo> for JavaForeignTypeInfo {
@@ -65,6 +64,24 @@ impl JavaForeignTypeInfo {
             None
         }
     }
+    fn kotlin_convert<NameArg>(&self, name_arg: NameArg) -> Option<String>
+    where
+        NameArg: Fn() -> (String, String),
+    {
+        if let Some(code) = self
+            .java_converter
+            .as_ref()
+            .and_then(|x| x.kotlin_converter.as_ref())
+        {
+            let (from_name, to_name) = name_arg();
+            Some(
+                code.replace(TO_VAR_TEMPLATE, &to_name)
+                    .replace(FROM_VAR_TEMPLATE, &from_name),
+            )
+        } else {
+            None
+        }
+    }
 }
 
 impl From<ForeignTypeInfo> for JavaForeignTypeInfo {
@@ -80,15 +97,60 @@ impl From<ForeignTypeInfo> for JavaForeignTypeInfo {
     }
 }
 
+/// Java exception that thrown in case of `Err` returned by method
+/// with `Result<T, E>` return type
+struct JavaExceptionInfo {
+    /// Name of Java class, full or relative to package
+    class_name: String,
+    conv: ErrorToException,
+}
+
+enum ErrorToException {
+    /// `E: Display`, text passed to exception constructor
+    Message,
+    /// `E` converted to `jthrowable` by rule from types map
+    Throwable(RustType),
+    /// `E` is `foreign_enum`, its value passed to exception constructor
+    EnumValue {
+        enum_name: String,
+        jobject_ty: RustType,
+    },
+}
+
+impl JavaExceptionInfo {
+    fn jni_class_name(&self, package_name: &str) -> String {
+        if self.class_name.contains('.') {
+            java_class_name_to_jni(&self.class_name)
+        } else {
+            java_class_name_to_jni(&java_class_full_name(package_name, &self.class_name))
+        }
+    }
+}
+
 struct JniForeignMethodSignature {
-    output: ForeignTypeInfo,
+    output: JavaForeignTypeInfo,
     input: Vec<JavaForeignTypeInfo>,
+    exception: Option<JavaExceptionInfo>,
+    future: Option<JavaFutureInfo>,
+}
+
+/// Method returns `Future<Output = T>`, in this case JNI function
+/// returns `java.util.concurrent.CompletableFuture`
+/// and `output` describes this `CompletableFuture`
+struct JavaFutureInfo {
+    /// `T` itself
+    output_ty: RustType,
+    /// `T` or `U` if `T` is `Result<U, E>`, then `E: Display`
+    /// becomes message of exception that completes `CompletableFuture`
+    value_ty: RustType,
+    /// Conversation of `value_ty` into Java type
+    value: JavaForeignTypeInfo,
 }
 
 impl ForeignMethodSignature for JniForeignMethodSignature {
     type FI = JavaForeignTypeInfo;
     fn output(&self) -> &ForeignTypeInfo {
-        &self.output
+        &self.output.base
     }
     fn input(&self) -> &[JavaForeignTypeInfo] {
         &self.input[..]
@@ -132,10 +194,56 @@ impl LanguageGenerator for JavaConfig {
             conv_map.cache_rust_to_foreign_conv(
                 &this_type,
                 ForeignTypeInfo {
-                    correspoding_rust_type: my_jobj_ti,
+                    correspoding_rust_type: my_jobj_ti.clone(),
                     name: class.name.to_string().into(),
                 },
             )?;
+            //handle foreigner_class as return value of callback,
+            //Java object keeps ownership, so we return clone of native object
+            let (heap_this_type, _) = conv_map.convert_to_heap_pointer(&this_type, "x");
+            let clone_native_obj = format!(
+                r#"jobject_native_obj({from_var}, env).map(|x| {{
+            let x: *mut {heap_this_type} = unsafe {{ jlong_to_pointer::<{heap_this_type}>(x) }};
+    {unpack_code}
+            let x = ::std::mem::ManuallyDrop::new(x);
+            <{this_type} as Clone>::clone(&x)
+        }})"#,
+                from_var = FROM_VAR_TEMPLATE,
+                heap_this_type = heap_this_type.normalized_name,
+                this_type = this_type.normalized_name,
+                unpack_code = TypeMap::unpack_from_heap_pointer(&this_type, "x", false),
+            );
+            conv_map.add_conversation_rule(
+                my_jobj_ti.clone(),
+                this_type.clone(),
+                format!(
+                    r#"
+        let {to_var}: {this_type} = {clone_native_obj}
+            .expect("Java callback return null instead of {class_name}");
+    "#,
+                    to_var = TO_VAR_TEMPLATE,
+                    this_type = this_type.normalized_name,
+                    clone_native_obj = clone_native_obj,
+                    class_name = class.name,
+                )
+                .into(),
+            );
+            let code = format!("Option<{}>", DisplayToTokens(&this_type.ty));
+            let gen_ty = parse_ty_with_given_span_checked(&code, this_type.ty.span());
+            let opt_this_type = conv_map.find_or_alloc_rust_type(&gen_ty, this_type.src_id);
+            conv_map.add_conversation_rule(
+                my_jobj_ti,
+                opt_this_type,
+                format!(
+                    r#"
+        let {to_var}: Option<{this_type}> = {clone_native_obj};
+    "#,
+                    to_var = TO_VAR_TEMPLATE,
+                    this_type = this_type.normalized_name,
+                    clone_native_obj = clone_native_obj,
+                )
+                .into(),
+            );
 
             conv_map.find_or_alloc_rust_type(constructor_ret_type, class.src_id);
 
@@ -223,19 +331,30 @@ impl LanguageGenerator for JavaConfig {
             class.name, class.constructor_ret_type
         );
 
-        let f_methods_sign = find_suitable_foreign_types_for_methods(conv_map, class)?;
-        java_code::generate_java_code(
-            conv_map,
-            &self.output_dir,
-            &self.package_name,
-            class,
-            &f_methods_sign,
-            self.null_annotation_package.as_ref().map(String::as_str),
-        )
+        let f_methods_sign = find_suitable_foreign_types_for_methods(conv_map, self, class)?;
+        if self.use_kotlin {
+            kotlin_code::generate_kotlin_code(
+                conv_map,
+                &self.output_dir,
+                &self.package_name,
+                class,
+                &f_methods_sign,
+                self.use_cleaner,
+            )
+        } else {
+            java_code::generate_java_code(
+                conv_map,
+                &self.output_dir,
+                &self.package_name,
+                class,
+                &f_methods_sign,
+                self.null_annotation_package.as_ref().map(String::as_str),
+                self.use_cleaner,
+            )
+        }
         .map_err(|err| DiagnosticError::new(class.src_id, class.span(), err))?;
         debug!("generate: java code done");
-        let ast_items =
-            rust_code::generate_rust_code(conv_map, &self.package_name, class, &f_methods_sign)?;
+        let ast_items = rust_code::generate_rust_code(conv_map, self, class, &f_methods_sign)?;
 
         Ok(ast_items)
     }
@@ -254,8 +373,37 @@ impl LanguageGenerator for JavaConfig {
             ));
         }
 
-        java_code::generate_java_code_for_enum(&self.output_dir, &self.package_name, enum_info)
-            .map_err(|err| DiagnosticError::new(enum_info.src_id, enum_info.span(), &err))?;
+        if enum_info.is_data_carrying() {
+            let java_types = map_type::register_data_enum(conv_map, &self.package_name, enum_info)?;
+            if self.use_kotlin {
+                kotlin_code::generate_kotlin_code_for_data_enum(
+                    &self.output_dir,
+                    &self.package_name,
+                    enum_info,
+                    &java_types,
+                )
+            } else {
+                java_code::generate_java_code_for_data_enum(
+                    &self.output_dir,
+                    &self.package_name,
+                    enum_info,
+                    &java_types,
+                )
+            }
+            .map_err(|err| DiagnosticError::new2(enum_info.src_id_span(), err))?;
+            return Ok(vec![]);
+        }
+
+        if self.use_kotlin {
+            kotlin_code::generate_kotlin_code_for_enum(
+                &self.output_dir,
+                &self.package_name,
+                enum_info,
+            )
+        } else {
+            java_code::generate_java_code_for_enum(&self.output_dir, &self.package_name, enum_info)
+        }
+        .map_err(|err| DiagnosticError::new(enum_info.src_id, enum_info.span(), &err))?;
 
         rust_code::generate_rust_code_for_enum(
             &self.package_name,
@@ -265,20 +413,80 @@ impl LanguageGenerator for JavaConfig {
         )
     }
 
+    fn generate_struct(
+        &self,
+        conv_map: &mut TypeMap,
+        _: usize,
+        struct_info: &ForeignStructInfo,
+    ) -> Result<Vec<TokenStream>> {
+        let java_types = map_type::register_struct(conv_map, &self.package_name, struct_info)?;
+        if self.use_kotlin {
+            kotlin_code::generate_kotlin_code_for_struct(
+                &self.output_dir,
+                &self.package_name,
+                struct_info,
+                &java_types,
+            )
+        } else {
+            java_code::generate_java_code_for_struct(
+                &self.output_dir,
+                &self.package_name,
+                struct_info,
+                &java_types,
+            )
+        }
+        .map_err(|err| DiagnosticError::new(struct_info.src_id, struct_info.span(), err))?;
+        Ok(vec![])
+    }
+
+    fn generate_flags(
+        &self,
+        conv_map: &mut TypeMap,
+        _: usize,
+        flags_info: &ForeignFlagsInfo,
+    ) -> Result<Vec<TokenStream>> {
+        if self.use_kotlin {
+            kotlin_code::generate_kotlin_code_for_flags(
+                &self.output_dir,
+                &self.package_name,
+                flags_info,
+            )
+        } else {
+            java_code::generate_java_code_for_flags(
+                &self.output_dir,
+                &self.package_name,
+                flags_info,
+            )
+        }
+        .map_err(|err| DiagnosticError::new2(flags_info.src_id_span(), err))?;
+        rust_code::generate_rust_code_for_flags(conv_map, flags_info)
+    }
+
     fn generate_interface(
         &self,
         conv_map: &mut TypeMap,
         pointer_target_width: usize,
         interface: &ForeignInterface,
     ) -> Result<Vec<TokenStream>> {
-        let f_methods = find_suitable_ftypes_for_interace_methods(conv_map, interface)?;
-        java_code::generate_java_code_for_interface(
-            &self.output_dir,
-            &self.package_name,
-            interface,
-            &f_methods,
-            self.null_annotation_package.as_ref().map(String::as_str),
-        )
+        let f_methods = find_suitable_ftypes_for_interace_methods(conv_map, self, interface)?;
+        if self.use_kotlin {
+            kotlin_code::generate_kotlin_code_for_interface(
+                conv_map,
+                &self.output_dir,
+                &self.package_name,
+                interface,
+                &f_methods,
+            )
+        } else {
+            java_code::generate_java_code_for_interface(
+                conv_map,
+                &self.output_dir,
+                &self.package_name,
+                interface,
+                &f_methods,
+                self.null_annotation_package.as_ref().map(String::as_str),
+            )
+        }
         .map_err(|err| DiagnosticError::new(interface.src_id, interface.span(), err))?;
         let items = rust_code::generate_interface(
             &self.package_name,
@@ -301,8 +509,19 @@ impl LanguageGenerator for JavaConfig {
     }
 }
 
-fn method_name(method: &ForeignerMethod, f_method: &JniForeignMethodSignature) -> String {
-    let need_conv = f_method.input.iter().any(|v| v.java_converter.is_some());
+/// Name of native method, in Kotlin static methods always have
+/// wrapper, because of nullable types instead of `Optional`
+fn method_name(
+    method: &ForeignerMethod,
+    f_method: &JniForeignMethodSignature,
+    use_kotlin: bool,
+) -> String {
+    let need_conv = use_kotlin
+        || f_method
+            .input
+            .iter()
+            .chain(iter::once(&f_method.output))
+            .any(|v| v.java_converter.is_some());
     match method.variant {
         MethodVariant::StaticMethod if !need_conv => method.short_name().as_str().to_string(),
         MethodVariant::Method(_) | MethodVariant::StaticMethod => {
@@ -314,6 +533,7 @@ fn method_name(method: &ForeignerMethod, f_method: &JniForeignMethodSignature) -
 
 fn find_suitable_ftypes_for_interace_methods(
     conv_map: &mut TypeMap,
+    java_cfg: &JavaConfig,
     interace: &ForeignInterface,
 ) -> Result<Vec<JniForeignMethodSignature>> {
     let void_sym = "void";
@@ -327,6 +547,7 @@ fn find_suitable_ftypes_for_interace_methods(
             let arg_rust_ty = conv_map.find_or_alloc_rust_type(fn_arg_type(arg), interace.src_id);
             let f_arg_type = map_type(
                 conv_map,
+                java_cfg,
                 &arg_rust_ty,
                 Direction::Outgoing,
                 (interace.src_id, fn_arg_type(arg).span()),
@@ -338,16 +559,48 @@ fn find_suitable_ftypes_for_interace_methods(
             syn::ReturnType::Default => ForeignTypeInfo {
                 name: void_sym.into(),
                 correspoding_rust_type: dummy_rust_ty.clone(),
-            },
-            _ => unimplemented!(),
+            }
+            .into(),
+            syn::ReturnType::Type(_, ref rt) => {
+                let ret_rust_ty = conv_map.find_or_alloc_rust_type(rt, interace.src_id);
+                //exception in Java code converted to error, so map only `T` of `Result<T, E>`
+                let ret_rust_ty = match if_result_return_ok_err_types(&ret_rust_ty) {
+                    Some((ok_ty, _err_ty)) => {
+                        conv_map.find_or_alloc_rust_type(&ok_ty, interace.src_id)
+                    }
+                    None => ret_rust_ty,
+                };
+                map_callback_ret_type(conv_map, &ret_rust_ty, (interace.src_id, rt.span()))?
+            }
         };
-        f_methods.push(JniForeignMethodSignature { output, input });
+        if let Some(fti) = input
+            .iter()
+            .chain(iter::once(&output))
+            .find(|x| x.java_converter.is_some())
+        {
+            return Err(DiagnosticError::new(
+                interace.src_id,
+                method.rust_name.span(),
+                format!(
+                    "conversation of Java type '{}' requires Java code, \
+                     this is not supported for callbacks yet",
+                    fti.base.name
+                ),
+            ));
+        }
+        f_methods.push(JniForeignMethodSignature {
+            output,
+            input,
+            exception: None,
+            future: None,
+        });
     }
     Ok(f_methods)
 }
 
 fn find_suitable_foreign_types_for_methods(
     conv_map: &mut TypeMap,
+    java_cfg: &JavaConfig,
     class: &ForeignerClassInfo,
 ) -> Result<Vec<JniForeignMethodSignature>> {
     let mut ret = Vec::<JniForeignMethodSignature>::with_capacity(class.methods.len());
@@ -369,39 +622,125 @@ fn find_suitable_foreign_types_for_methods(
 
             let fti = map_type(
                 conv_map,
+                java_cfg,
                 &arg_rust_ty,
                 Direction::Incoming,
                 (class.src_id, fn_arg_type(arg).span()),
             )?;
             input.push(fti);
         }
+        let mut future = None;
         let output = match method.variant {
             MethodVariant::Constructor => ForeignTypeInfo {
                 name: empty_symbol.into(),
                 correspoding_rust_type: dummy_rust_ty.clone(),
-            },
+            }
+            .into(),
             _ => match method.fn_decl.output {
                 syn::ReturnType::Default => ForeignTypeInfo {
                     name: "void".into(),
                     correspoding_rust_type: dummy_rust_ty.clone(),
-                },
+                }
+                .into(),
                 syn::ReturnType::Type(_, ref rt) => {
-                    let ret_rust_ty = conv_map.find_or_alloc_rust_type(rt, class.src_id);
-                    let fti = map_type(
-                        conv_map,
-                        &ret_rust_ty,
-                        Direction::Outgoing,
-                        (class.src_id, rt.span()),
-                    )?;
-                    fti.base
+                    if let Some(output_ty) = if_future_return_output_type(rt) {
+                        let (output, future_info) = map_future_type(
+                            conv_map,
+                            java_cfg,
+                            &output_ty,
+                            (class.src_id, rt.span()),
+                        )?;
+                        future = Some(future_info);
+                        output
+                    } else {
+                        let ret_rust_ty = conv_map.find_or_alloc_rust_type(rt, class.src_id);
+                        map_type(
+                            conv_map,
+                            java_cfg,
+                            &ret_rust_ty,
+                            Direction::Outgoing,
+                            (class.src_id, rt.span()),
+                        )?
+                    }
                 }
             },
         };
-        ret.push(JniForeignMethodSignature { output, input });
+        let exception = find_java_exception_for_method(conv_map, class, method)?;
+        ret.push(JniForeignMethodSignature {
+            output,
+            input,
+            exception,
+            future,
+        });
     }
     Ok(ret)
 }
 
+fn find_java_exception_for_method(
+    conv_map: &mut TypeMap,
+    class: &ForeignerClassInfo,
+    method: &ForeignerMethod,
+) -> Result<Option<JavaExceptionInfo>> {
+    let err_ty = match method.fn_decl.output {
+        syn::ReturnType::Type(_, ref rt) if method.variant != MethodVariant::Constructor => {
+            let ret_rust_ty = conv_map.find_or_alloc_rust_type(rt, class.src_id);
+            if_result_return_ok_err_types(&ret_rust_ty).map(|(_ok_ty, err_ty)| err_ty)
+        }
+        _ => None,
+    };
+    let err_ty = match err_ty {
+        Some(x) => conv_map.find_or_alloc_rust_type(&x, class.src_id),
+        None => {
+            return if method.java_exception.is_some() {
+                Err(DiagnosticError::new(
+                    class.src_id,
+                    method.span(),
+                    "java_exception attribute can be used only for \
+                     method or static_method that returns Result<T, E>",
+                ))
+            } else {
+                Ok(None)
+            };
+        }
+    };
+    if let Some(ref class_name) = method.java_exception {
+        return Ok(Some(JavaExceptionInfo {
+            class_name: class_name.clone(),
+            conv: ErrorToException::Message,
+        }));
+    }
+    if let Some((throwable_ty, class_name)) =
+        conv_map.find_direct_conversation_to_type_with_suffix(&err_ty, "jthrowable")
+    {
+        return Ok(Some(JavaExceptionInfo {
+            class_name: class_name.to_string(),
+            conv: ErrorToException::Throwable(throwable_ty),
+        }));
+    }
+    let enum_name = conv_map
+        .is_this_exported_enum(&err_ty)
+        .map(|x| x.name.to_string());
+    if let Some(enum_name) = enum_name {
+        let jobject_ty = conv_map
+            .find_rust_type_with_suffix(&parse_type! { jobject }, &enum_name)
+            .ok_or_else(|| {
+                DiagnosticError::new(
+                    class.src_id,
+                    method.span(),
+                    format!("Can not find jobject type for enum {}", enum_name),
+                )
+            })?;
+        return Ok(Some(JavaExceptionInfo {
+            class_name: format!("{}Exception", enum_name),
+            conv: ErrorToException::EnumValue {
+                enum_name,
+                jobject_ty,
+            },
+        }));
+    }
+    Ok(None)
+}
+
 fn fmt_write_err_map(err: fmt::Error) -> String {
     format!("fmt write error: {}", err)
 }
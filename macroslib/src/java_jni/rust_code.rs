@@ -5,31 +5,38 @@ use rustc_hash::FxHashMap;
 use syn::{parse_quote, spanned::Spanned, Type};
 
 use crate::{
-    error::{panic_on_syn_error, DiagnosticError, Result},
+    error::{panic_on_syn_error, DiagnosticError, Result, SourceIdSpan},
     java_jni::{
         calc_this_type_for_method, fmt_write_err_map, java_class_full_name, java_class_name_to_jni,
-        method_name, ForeignTypeInfo, JniForeignMethodSignature,
+        method_name, ErrorToException, ForeignTypeInfo, JavaFutureInfo, JniForeignMethodSignature,
     },
     source_registry::SourceId,
-    typemap::ast::{fn_arg_type, list_lifetimes, normalize_ty_lifetimes, DisplayToTokens},
+    typemap::ast::{
+        check_if_smart_pointer_return_inner_type, fn_arg_type, if_option_return_some_type,
+        if_result_return_ok_err_types, list_lifetimes, normalize_ty_lifetimes,
+        parse_ty_with_given_span_checked, DisplayToTokens,
+    },
     typemap::{
         ty::RustType,
         unpack_unique_typename,
         utils::{
             create_suitable_types_for_constructor_and_self,
             foreign_from_rust_convert_method_output, foreign_to_rust_convert_method_inputs,
+            rust_enum_unknown_value_arm, rust_flags_from_bits, rust_flags_to_bits,
             rust_to_foreign_convert_method_inputs,
         },
-        TO_VAR_TEMPLATE,
+        TypeConvEdge, FROM_VAR_TEMPLATE, FUNCTION_RETURN_TYPE_TEMPLATE, TO_VAR_TEMPLATE,
+        TO_VAR_TYPE_TEMPLATE,
     },
     types::{
-        ForeignEnumInfo, ForeignInterface, ForeignerClassInfo, ForeignerMethod, MethodVariant,
-        SelfTypeVariant,
+        ForeignEnumInfo, ForeignFlagsInfo, ForeignInterface, ForeignerClassInfo, ForeignerMethod,
+        MethodVariant, SelfTypeVariant, UnknownEnumValue,
     },
-    TypeMap,
+    JavaConfig, TypeMap,
 };
 
 struct MethodContext<'a> {
+    package_name: &'a str,
     class: &'a ForeignerClassInfo,
     method: &'a ForeignerMethod,
     f_method: &'a JniForeignMethodSignature,
@@ -37,18 +44,28 @@ struct MethodContext<'a> {
     decl_func_args: &'a str,
     args_names: &'a str,
     real_output_typename: &'a str,
+    catch_panics: bool,
+    future_executor: &'a str,
 }
 
 pub(in crate::java_jni) fn generate_rust_code(
     conv_map: &mut TypeMap,
-    package_name: &str,
+    java_cfg: &JavaConfig,
     class: &ForeignerClassInfo,
     f_methods_sign: &[JniForeignMethodSignature],
 ) -> Result<Vec<TokenStream>> {
+    let package_name = &java_cfg.package_name;
+    let catch_panics = java_cfg.catch_panics;
+    let use_cleaner = java_cfg.use_cleaner;
+    let use_kotlin = java_cfg.use_kotlin;
+    let future_executor = java_cfg
+        .future_executor
+        .as_deref()
+        .unwrap_or("swig_spawn_future");
     //to handle java method overload
     let mut gen_fnames = FxHashMap::<String, usize>::default();
     for (method, f_method) in class.methods.iter().zip(f_methods_sign.iter()) {
-        let val_ref = gen_fnames.entry(method_name(method, f_method));
+        let val_ref = gen_fnames.entry(method_name(method, f_method, use_kotlin));
         *val_ref.or_insert(0) += 1;
     }
 
@@ -101,8 +118,28 @@ pub(in crate::java_jni) fn generate_rust_code(
         }};
     {unpack_code}
         x
-    }}
+    }}{native_obj_changed}
 }}"#,
+                native_obj_changed = if use_cleaner {
+                    r#"
+    fn jni_native_obj_changed(obj: jobject, env: *mut JNIEnv) {
+        unsafe {
+            let jcls: jclass = (**env).GetObjectClass.unwrap()(env, obj);
+            assert!(!jcls.is_null(), "jni_native_obj_changed: GetObjectClass failed");
+            let method_id: jmethodID = (**env).GetMethodID.unwrap()(
+                env,
+                jcls,
+                swig_c_str!("swigNativeObjChanged"),
+                swig_c_str!("()V"),
+            );
+            assert!(!method_id.is_null(), "jni_native_obj_changed: GetMethodID failed");
+            (**env).CallVoidMethod.unwrap()(env, obj, method_id);
+            (**env).DeleteLocalRef.unwrap()(env, jcls);
+        }
+    }"#
+                } else {
+                    ""
+                },
                 lifetimes = lifetimes,
                 class_name = DisplayToTokens(&this_type.ty),
                 jni_class_name = class_name_for_jni,
@@ -135,7 +172,7 @@ May be you need to use `private constructor = empty;` syntax?",
     let mut have_constructor = false;
 
     for (method, f_method) in class.methods.iter().zip(f_methods_sign.iter()) {
-        let java_method_name = method_name(method, f_method);
+        let java_method_name = method_name(method, f_method, use_kotlin);
         let method_overloading = gen_fnames[&java_method_name] > 1;
         let jni_func_name = generate_jni_func_name(
             package_name,
@@ -157,10 +194,13 @@ May be you need to use `private constructor = empty;` syntax?",
             .map_err(|err| DiagnosticError::new(class.src_id, class.span(), &err))?;
         let real_output_typename = match method.fn_decl.output {
             syn::ReturnType::Default => "()",
+            //`impl Future` can not be used as type of variable
+            syn::ReturnType::Type(..) if f_method.future.is_some() => "_",
             syn::ReturnType::Type(_, ref ty) => normalize_ty_lifetimes(&*ty),
         };
 
         let method_ctx = MethodContext {
+            package_name,
             class,
             method,
             f_method,
@@ -168,6 +208,8 @@ May be you need to use `private constructor = empty;` syntax?",
             decl_func_args: &decl_func_args,
             args_names: &args_names,
             real_output_typename: &real_output_typename,
+            catch_panics,
+            future_executor,
         };
 
         match method.variant {
@@ -220,27 +262,36 @@ May be you need to use `private constructor = empty;` syntax?",
                 output: ForeignTypeInfo {
                     name: "".into(),
                     correspoding_rust_type: dummy_rust_ty.clone(),
-                },
+                }
+                .into(),
                 input: vec![],
+                exception: None,
+                future: None,
             },
             false,
         )?;
-        let code = format!(
+        let body = format!(
             r#"
-#[allow(unused_variables, unused_mut, non_snake_case)]
-#[no_mangle]
-pub extern "C" fn {jni_destructor_name}(env: *mut JNIEnv, _: jclass, this: jlong) {{
     let this: *mut {this_type} = unsafe {{
         jlong_to_pointer::<{this_type}>(this).as_mut().unwrap()
     }};
 {unpack_code}
     drop(this);
-}}
 "#,
-            jni_destructor_name = jni_destructor_name,
             unpack_code = unpack_code,
             this_type = this_type_for_method.normalized_name,
         );
+        let code = format!(
+            r#"
+#[allow(unused_variables, unused_mut, non_snake_case)]
+#[no_mangle]
+pub extern "C" fn {jni_destructor_name}(env: *mut JNIEnv, _: jclass, this: jlong) {{
+{body}
+}}
+"#,
+            jni_destructor_name = jni_destructor_name,
+            body = catch_unwind_if_need(catch_panics, "()", body),
+        );
         debug!("we generate and parse code: {}", code);
         gen_code.push(
             syn::parse_str(&code).unwrap_or_else(|err| {
@@ -261,35 +312,32 @@ pub(in crate::java_jni) fn generate_rust_code_for_enum(
     use std::fmt::Write;
 
     let rust_enum_name = enum_info.rust_enum_name();
-    let mut code = format!(
-        r#"
-impl SwigFrom<jint> for {rust_enum_name} {{
-    fn swig_from(x: jint, _: *mut JNIEnv) -> {rust_enum_name} {{
-        match x {{
-
-"#,
-        rust_enum_name = rust_enum_name,
-    );
-    for (i, item) in enum_info.items.iter().enumerate() {
+    let mut match_value = String::new();
+    for item in &enum_info.items {
         writeln!(
-            &mut code,
+            &mut match_value,
             "{index} => {item_name},",
-            index = i,
+            index = item.value,
             item_name = DisplayToTokens(&item.rust_name),
         )
         .unwrap();
     }
-    write!(
-        &mut code,
+    let mut code = format!(
         r#"
-        _ => panic!("{{}} not expected for {rust_enum_name}", x),
+impl SwigFrom<jint> for {rust_enum_name} {{
+    fn swig_from(x: jint, _: *mut JNIEnv) -> {rust_enum_name} {{
+        match x {{
+
+{match_value}
+        {unknown_value_arm}
         }}
     }}
 }}
 "#,
         rust_enum_name = rust_enum_name,
-    )
-    .unwrap();
+        match_value = match_value,
+        unknown_value_arm = rust_enum_unknown_value_arm(enum_info, "x"),
+    );
 
     let java_enum_full_name = java_class_full_name(package_name, &enum_info.name.to_string());
     let enum_class_name = java_class_name_to_jni(&java_enum_full_name);
@@ -341,12 +389,126 @@ impl SwigFrom<{rust_enum_name}> for jobject {{
       ret
    }}
 }}
+#[swig_from_foreigner_hint = "{enum_name}"]
+impl SwigFrom<jobject> for {rust_enum_name} {{
+   fn swig_from(x: jobject, env: *mut JNIEnv) -> {rust_enum_name} {{
+       assert!(!x.is_null(), "null instead of {class_name} value");
+       let cls: jclass = unsafe {{ (**env).GetObjectClass.unwrap()(env, x) }};
+       assert!(!cls.is_null(), "GetObjectClass for {class_name} failed");
+       let method_id: jmethodID = unsafe {{
+           (**env).GetMethodID.unwrap()(env, cls, swig_c_str!("getValue"), swig_c_str!("()I"))
+       }};
+       assert!(!method_id.is_null(), "Can not find getValue in {class_name}");
+       let x: jint = unsafe {{
+           let ret = (**env).CallIntMethod.unwrap()(env, x, method_id);
+           (**env).DeleteLocalRef.unwrap()(env, cls);
+           (**env).DeleteLocalRef.unwrap()(env, x);
+           ret
+       }};
+       match x {{
+{match_value}
+       {unknown_value_arm}
+       }}
+   }}
+}}
 "#,
         class_name = enum_class_name,
+        enum_name = enum_info.name,
+        rust_enum_name = rust_enum_name,
+        match_value = match_value,
+        unknown_value_arm = rust_enum_unknown_value_arm(enum_info, "x"),
     )
     .unwrap();
     conv_map.register_exported_enum(enum_info);
     conv_map.merge(SourceId::none(), &code, pointer_target_width)?;
+
+    if enum_info.unknown_value == UnknownEnumValue::Error {
+        // arguments of methods are passed as `jint`, so replace conversation
+        // from `SwigFrom<jint>` by one that throws exception
+        let enum_ty = conv_map.find_or_alloc_rust_type(
+            &parse_ty_with_given_span_checked(&rust_enum_name, enum_info.span()),
+            enum_info.src_id,
+        );
+        let jint_ty = conv_map.ty_to_rust_type(&parse_type! { jint });
+        let code = format!(
+            r#"
+    let mut {to_var}: {to_var_type} = match {from_var} {{
+{match_value}
+        _ => {{
+            jni_throw(env, swig_c_str!("java/lang/IllegalArgumentException"),
+                      &format!("{{}} not expected for {rust_enum_name}", {from_var}));
+            return <{func_ret_type}>::invalid_value();
+        }}
+    }};
+"#,
+            to_var = TO_VAR_TEMPLATE,
+            to_var_type = TO_VAR_TYPE_TEMPLATE,
+            from_var = FROM_VAR_TEMPLATE,
+            func_ret_type = FUNCTION_RETURN_TYPE_TEMPLATE,
+            match_value = match_value,
+            rust_enum_name = rust_enum_name,
+        );
+        conv_map.add_conversation_rule(jint_ty, enum_ty, TypeConvEdge::new(code, None));
+    }
+    Ok(vec![])
+}
+
+/// `foreign_flags` is passed as `jint`, Java code checks bits before call of Rust,
+/// and Rust side throws `IllegalArgumentException` if unknown bits are passed anyway,
+/// flags that are not exported to Java are internal for Rust, so they are reported and dropped
+pub(in crate::java_jni) fn generate_rust_code_for_flags(
+    conv_map: &mut TypeMap,
+    flags_info: &ForeignFlagsInfo,
+) -> Result<Vec<TokenStream>> {
+    let flags_name = flags_info.rust_flags_name();
+    let flags_ty = conv_map.find_or_alloc_rust_type(
+        &parse_ty_with_given_span_checked(&flags_name, flags_info.span()),
+        flags_info.src_id,
+    );
+    let jint_ty = conv_map.ty_to_rust_type(&parse_type! { jint });
+
+    let code = format!(
+        r#"
+    let mut {to_var}: {to_var_type} = {{
+        let (bits, all_flags_known) = {to_bits};
+        if !all_flags_known {{
+            error!("{flags_name}: some flags are not exported to Java, they are dropped");
+        }}
+        bits as jint
+    }};
+"#,
+        to_var = TO_VAR_TEMPLATE,
+        to_var_type = TO_VAR_TYPE_TEMPLATE,
+        to_bits = rust_flags_to_bits(flags_info, FROM_VAR_TEMPLATE),
+        flags_name = flags_name,
+    );
+    conv_map.add_conversation_rule(
+        flags_ty.clone(),
+        jint_ty.clone(),
+        TypeConvEdge::new(code, None),
+    );
+
+    let code = format!(
+        r#"
+    let mut {to_var}: {to_var_type} = {{
+        let (flags, all_bits_known) = {from_bits};
+        if !all_bits_known {{
+            jni_throw(env, swig_c_str!("java/lang/IllegalArgumentException"),
+                      &format!("{flags_name}: unknown bits in 0x{{:x}}", {from_var}));
+            return <{func_ret_type}>::invalid_value();
+        }}
+        flags
+    }};
+"#,
+        to_var = TO_VAR_TEMPLATE,
+        to_var_type = TO_VAR_TYPE_TEMPLATE,
+        from_var = FROM_VAR_TEMPLATE,
+        func_ret_type = FUNCTION_RETURN_TYPE_TEMPLATE,
+        from_bits = rust_flags_from_bits(flags_info, &format!("{} as u32", FROM_VAR_TEMPLATE)),
+        flags_name = flags_name,
+    );
+    conv_map.add_conversation_rule(jint_ty, flags_ty, TypeConvEdge::new(code, None));
+    conv_map.register_exported_flags(flags_info);
     Ok(vec![])
 }
 
@@ -448,10 +610,13 @@ impl {trait_name} for JavaCallback {{
             (0..n_args).map(|v| format!("a_{}", v)),
             "()",
         )?;
+        gen_items.append(&mut conv_deps);
 
-        write!(
-            &mut impl_trait_code,
-            r#"
+        let ret_ty = match method.fn_decl.output {
+            syn::ReturnType::Default => {
+                write!(
+                    &mut impl_trait_code,
+                    r#"
     #[allow(unused_mut)]
     fn {func_name}({args_with_types}) {{
 {type_size_asserts}
@@ -469,16 +634,115 @@ impl {trait_name} for JavaCallback {{
             }};
         }}
     }}
+"#,
+                    func_name = func_name,
+                    args_with_types = args_with_types,
+                    method_idx = method_idx,
+                    args = args,
+                    convert_args = convert_args,
+                    type_size_asserts = type_size_asserts,
+                )
+                .unwrap();
+                continue;
+            }
+            syn::ReturnType::Type(_, ref ret_ty) => ret_ty,
+        };
+        let real_output_type: RustType = conv_map.find_or_alloc_rust_type(ret_ty, interface.src_id);
+        let ok_output_type: Option<RustType> = if_result_return_ok_err_types(&real_output_type)
+            .map(|(ok_ty, _err_ty)| conv_map.find_or_alloc_rust_type(&ok_ty, interface.src_id));
+        check_callback_ret_type_clonable(
+            conv_map,
+            ok_output_type.as_ref().unwrap_or(&real_output_type),
+            (interface.src_id, ret_ty.span()),
+        )?;
+        let output_ty = ok_output_type.as_ref().unwrap_or(&real_output_type);
+        // conversation rule for flags throws Java exception and returns from JNI function,
+        // this is not possible inside callback, so just panic like for Java exceptions
+        let (mut conv_deps, output_conv) = match conv_map.is_this_exported_flags(output_ty) {
+            Some(flags_info) => (
+                vec![],
+                format!(
+                    r#"
+        let mut ret: {flags_name} = {{
+            let (flags, all_bits_known) = {from_bits};
+            if !all_bits_known {{
+                panic!("{func_name}: unknown bits of {flags_name} in 0x{{:x}}", ret);
+            }}
+            flags
+        }};
+"#,
+                    flags_name = flags_info.rust_flags_name(),
+                    from_bits = rust_flags_from_bits(flags_info, "ret as u32"),
+                    func_name = func_name,
+                ),
+            ),
+            None => conv_map.convert_rust_types(
+                &f_method.output.base.correspoding_rust_type,
+                output_ty,
+                "ret",
+                real_output_type.normalized_name.as_str(),
+                (interface.src_id, ret_ty.span()),
+            )?,
+        };
+        gen_items.append(&mut conv_deps);
+        let jni_ret_type = DisplayToTokens(&f_method.output.base.correspoding_rust_type.ty);
+        let (handle_exception, ret_value) = if ok_output_type.is_some() {
+            (
+                "return Err(jni_take_pending_exception(env).into());".to_string(),
+                "Ok(ret)",
+            )
+        } else {
+            (
+                format!(
+                    r#"
+                error!("{func_name}: java throw exception");
+                (**env).ExceptionDescribe.unwrap()(env);
+                (**env).ExceptionClear.unwrap()(env);
+                panic!("{func_name}: java throw exception");
+"#,
+                    func_name = func_name
+                ),
+                "ret",
+            )
+        };
+
+        write!(
+            &mut impl_trait_code,
+            r#"
+    #[allow(unused_mut)]
+    fn {func_name}({args_with_types}) -> {real_ret_type} {{
+{type_size_asserts}
+        let env = self.get_jni_env();
+        let env = env
+            .env
+            .unwrap_or_else(|| panic!("{func_name}: Can not get JNIEnv"));
+{convert_args}
+        let ret: {jni_ret_type} = unsafe {{
+            let ret = (**env).{call_method}.unwrap()(env, self.this, self.methods[{method_idx}]
+                                                     {args});
+            if (**env).ExceptionCheck.unwrap()(env) != 0 {{
+                {handle_exception}
+            }}
+            ret
+        }};
+{output_conv}
+        {ret_value}
+    }}
 "#,
             func_name = func_name,
             args_with_types = args_with_types,
+            real_ret_type = real_output_type.normalized_name,
             method_idx = method_idx,
             args = args,
             convert_args = convert_args,
             type_size_asserts = type_size_asserts,
+            jni_ret_type = jni_ret_type,
+            call_method = jni_call_method_for_ret_type(&f_method.output.base.name),
+            handle_exception = handle_exception,
+            output_conv = output_conv,
+            ret_value = ret_value,
         )
         .unwrap();
-        gen_items.append(&mut conv_deps);
     }
 
     write!(
@@ -494,13 +758,56 @@ impl {trait_name} for JavaCallback {{
     Ok(gen_items)
 }
 
+/// Java object returned from callback keeps ownership of native object,
+/// so Rust side can get only clone of it
+fn check_callback_ret_type_clonable(
+    conv_map: &TypeMap,
+    ret_ty: &RustType,
+    ret_ty_span: SourceIdSpan,
+) -> Result<()> {
+    let ret_ty: Type = if_option_return_some_type(ret_ty).unwrap_or_else(|| ret_ty.ty.clone());
+    let class = match conv_map
+        .find_foreigner_class_with_such_this_type(&ret_ty, calc_this_type_for_method)
+    {
+        Some(x) => x,
+        None => return Ok(()),
+    };
+    let shared = conv_map
+        .ty_to_rust_type_checked(&ret_ty)
+        .map(|ty| {
+            check_if_smart_pointer_return_inner_type(&ty, "Rc").is_some()
+                || check_if_smart_pointer_return_inner_type(&ty, "Arc").is_some()
+        })
+        .unwrap_or(false);
+    let has_clone = class.methods.iter().any(|m| {
+        m.rust_id
+            .segments
+            .last()
+            .map(|seg| seg.into_value().ident == "clone")
+            .unwrap_or(false)
+    });
+    if shared || has_clone {
+        Ok(())
+    } else {
+        Err(DiagnosticError::new2(
+            ret_ty_span,
+            format!(
+                "class {} returned from callback, but Java object keeps ownership of it, \
+                 so it should be Rc/Arc or have clone method",
+                class.name
+            ),
+        )
+        .add_span_note((class.src_id, class.name.span()), "class defined here"))
+    }
+}
+
 lazy_static! {
     static ref JAVA_TYPE_NAMES_FOR_JNI_SIGNATURE: FxHashMap<&'static str, &'static str> = {
         let mut m = FxHashMap::default();
         m.insert("String", "Ljava.lang.String;");
-        m.insert("Integer", "Ljava.lang.Integer");
-        m.insert("Long", "Ljava.lang.Long");
-        m.insert("Double", "Ljava.lang.Double");
+        m.insert("Integer", "Ljava.lang.Integer;");
+        m.insert("Long", "Ljava.lang.Long;");
+        m.insert("Double", "Ljava.lang.Double;");
         m.insert("boolean", "Z");
         m.insert("byte", "B");
         m.insert("char", "C");
@@ -598,17 +905,181 @@ fn generate_jni_args_with_types(
     Ok(buf)
 }
 
-fn generate_static_method(conv_map: &mut TypeMap, mc: &MethodContext) -> Result<Vec<TokenStream>> {
-    let jni_ret_type =
-        unpack_unique_typename(&mc.f_method.output.correspoding_rust_type.normalized_name);
-    let (mut deps_code_out, convert_output_code) = foreign_from_rust_convert_method_output(
-        conv_map,
-        mc.class.src_id,
-        &mc.method.fn_decl.output,
-        &mc.f_method.output,
+/// Convert return value of method, in case of `Result<T, E>` with
+/// `JniForeignMethodSignature::exception` throw suitable Java exception
+fn convert_method_output(
+    conv_map: &mut TypeMap,
+    mc: &MethodContext,
+    jni_ret_type: &str,
+) -> Result<(Vec<TokenStream>, String)> {
+    if let Some(ref future) = mc.f_method.future {
+        return convert_future_output(conv_map, mc, future);
+    }
+    let exception = match mc.f_method.exception {
+        Some(ref x) => x,
+        None => {
+            return foreign_from_rust_convert_method_output(
+                conv_map,
+                mc.class.src_id,
+                &mc.method.fn_decl.output,
+                &mc.f_method.output.base,
+                "ret",
+                jni_ret_type,
+            );
+        }
+    };
+    let src_id = mc.class.src_id;
+    let ret_ty = match mc.method.fn_decl.output {
+        syn::ReturnType::Type(_, ref ty) => ty,
+        syn::ReturnType::Default => unreachable!(),
+    };
+    let ret_rust_ty = conv_map.find_or_alloc_rust_type(ret_ty, src_id);
+    let (ok_ty, err_ty) = if_result_return_ok_err_types(&ret_rust_ty)
+        .expect("Internal error: exception for method that not return Result");
+    let err_rust_ty = conv_map.find_or_alloc_rust_type(&err_ty, src_id);
+    let exception_class = exception.jni_class_name(mc.package_name);
+
+    let (mut deps, throw_code) = match exception.conv {
+        ErrorToException::Message => (
+            vec![],
+            format!(
+                r#"jni_throw(env, swig_c_str!("{class_name}"), &err.to_string());"#,
+                class_name = exception_class
+            ),
+        ),
+        ErrorToException::Throwable(ref throwable_ty) => {
+            let (deps, conv_code) = conv_map.convert_rust_types(
+                &err_rust_ty,
+                throwable_ty,
+                "err",
+                jni_ret_type,
+                (src_id, ret_ty.span()),
+            )?;
+            (
+                deps,
+                format!(
+                    r#"
+{conv_code}
+            jni_throw_object(env, err);"#,
+                    conv_code = conv_code
+                ),
+            )
+        }
+        ErrorToException::EnumValue {
+            ref enum_name,
+            ref jobject_ty,
+        } => {
+            let (deps, conv_code) = conv_map.convert_rust_types(
+                &err_rust_ty,
+                jobject_ty,
+                "err",
+                jni_ret_type,
+                (src_id, ret_ty.span()),
+            )?;
+            let enum_class =
+                java_class_name_to_jni(&java_class_full_name(mc.package_name, enum_name));
+            (
+                deps,
+                format!(
+                    r#"
+{conv_code}
+            jni_throw_with_value(env, swig_c_str!("{class_name}"),
+                                 swig_c_str!("(L{enum_class};)V"), err);"#,
+                    conv_code = conv_code,
+                    class_name = exception_class,
+                    enum_class = enum_class,
+                ),
+            )
+        }
+    };
+
+    let ok_rust_ty = conv_map.find_or_alloc_rust_type(&ok_ty, src_id);
+    let (mut deps_ok, convert_ok_code) = conv_map.convert_rust_types(
+        &ok_rust_ty,
+        &mc.f_method.output.base.correspoding_rust_type,
         "ret",
-        &jni_ret_type,
+        jni_ret_type,
+        (src_id, ret_ty.span()),
+    )?;
+    deps.append(&mut deps_ok);
+    let code = format!(
+        r#"
+    let mut ret: {ok_type} = match ret {{
+        Ok(x) => x,
+        Err(err) => {{
+            {throw_code}
+            return <{jni_ret_type}>::invalid_value();
+        }}
+    }};
+{convert_ok_code}"#,
+        ok_type = ok_rust_ty.normalized_name,
+        throw_code = throw_code,
+        jni_ret_type = jni_ret_type,
+        convert_ok_code = convert_ok_code,
+    );
+    Ok((deps, code))
+}
+
+/// Pass `Future` returned by method to executor, and return
+/// `CompletableFuture` that completed with converted output of `Future`
+fn convert_future_output(
+    conv_map: &mut TypeMap,
+    mc: &MethodContext,
+    future: &JavaFutureInfo,
+) -> Result<(Vec<TokenStream>, String)> {
+    let src_id = mc.class.src_id;
+    let ret_span = match mc.method.fn_decl.output {
+        syn::ReturnType::Type(_, ref ty) => ty.span(),
+        syn::ReturnType::Default => unreachable!(),
+    };
+    let unpack_result = if future.output_ty.normalized_name != future.value_ty.normalized_name {
+        format!(
+            r#"
+        let mut ret: {value_type} = match ret {{
+            Ok(x) => x,
+            Err(err) => return Err(err.to_string()),
+        }};"#,
+            value_type = future.value_ty.normalized_name,
+        )
+    } else {
+        String::new()
+    };
+    let (deps, convert_value_code) = conv_map.convert_rust_types(
+        &future.value_ty,
+        &future.value.base.correspoding_rust_type,
+        "ret",
+        "jobject",
+        (src_id, ret_span),
     )?;
+    let code = format!(
+        r#"
+    let ret: jobject = swig_future_to_completable_future(
+        env,
+        ret,
+        move |env: *mut JNIEnv, ret: {output_type}| -> Result<jobject, String> {{{unpack_result}
+{convert_value_code}
+            Ok(ret.swig_into_jobject(env))
+        }},
+        {executor},
+    );"#,
+        output_type = future.output_ty.normalized_name,
+        unpack_result = unpack_result,
+        convert_value_code = convert_value_code,
+        executor = mc.future_executor,
+    );
+    Ok((deps, code))
+}
+
+fn generate_static_method(conv_map: &mut TypeMap, mc: &MethodContext) -> Result<Vec<TokenStream>> {
+    let jni_ret_type = unpack_unique_typename(
+        &mc.f_method
+            .output
+            .base
+            .correspoding_rust_type
+            .normalized_name,
+    );
+    let (mut deps_code_out, convert_output_code) =
+        convert_method_output(conv_map, mc, &jni_ret_type)?;
     let n_args = mc.f_method.input.len();
     let (deps_code_in, convert_input_code) = foreign_to_rust_convert_method_inputs(
         conv_map,
@@ -619,26 +1090,32 @@ fn generate_static_method(conv_map: &mut TypeMap, mc: &MethodContext) -> Result<
         &jni_ret_type,
     )?;
 
-    let code = format!(
+    let body = format!(
         r#"
-#[allow(non_snake_case, unused_variables, unused_mut)]
-#[no_mangle]
-pub extern "C" fn {func_name}(env: *mut JNIEnv, _: jclass, {decl_func_args}) -> {jni_ret_type} {{
 {convert_input_code}
     let mut ret: {real_output_typename} = {rust_func_name}({args_names});
 {convert_output_code}
     ret
-}}
 "#,
-        func_name = mc.jni_func_name,
-        decl_func_args = mc.decl_func_args,
-        jni_ret_type = jni_ret_type,
         convert_input_code = convert_input_code,
         rust_func_name = DisplayToTokens(&mc.method.rust_id),
         args_names = mc.args_names,
         convert_output_code = convert_output_code,
         real_output_typename = mc.real_output_typename,
     );
+    let code = format!(
+        r#"
+#[allow(non_snake_case, unused_variables, unused_mut)]
+#[no_mangle]
+pub extern "C" fn {func_name}(env: *mut JNIEnv, _: jclass, {decl_func_args}) -> {jni_ret_type} {{
+{body}
+}}
+"#,
+        func_name = mc.jni_func_name,
+        decl_func_args = mc.decl_func_args,
+        jni_ret_type = jni_ret_type,
+        body = catch_unwind_if_need(mc.catch_panics, jni_ret_type, body),
+    );
     let mut gen_code = deps_code_in;
     gen_code.append(&mut deps_code_out);
     gen_code
@@ -676,27 +1153,33 @@ fn generate_constructor(
         (mc.class.src_id, mc.method.span()),
     )?;
 
-    let code = format!(
+    let body = format!(
         r#"
-#[no_mangle]
-#[allow(unused_variables, unused_mut, non_snake_case)]
-pub extern "C" fn {func_name}(env: *mut JNIEnv, _: jclass, {decl_func_args}) -> jlong {{
 {convert_input_code}
     let this: {real_output_typename} = {rust_func_name}({args_names});
 {convert_this}
 {box_this}
     this as jlong
-}}
 "#,
-        func_name = mc.jni_func_name,
         convert_this = convert_this,
-        decl_func_args = mc.decl_func_args,
         convert_input_code = convert_input_code,
         rust_func_name = DisplayToTokens(&mc.method.rust_id),
         args_names = mc.args_names,
         box_this = code_box_this,
         real_output_typename = mc.real_output_typename,
     );
+    let code = format!(
+        r#"
+#[no_mangle]
+#[allow(unused_variables, unused_mut, non_snake_case)]
+pub extern "C" fn {func_name}(env: *mut JNIEnv, _: jclass, {decl_func_args}) -> jlong {{
+{body}
+}}
+"#,
+        func_name = mc.jni_func_name,
+        decl_func_args = mc.decl_func_args,
+        body = catch_unwind_if_need(mc.catch_panics, "jlong", body),
+    );
     let mut gen_code = deps_code_in;
     gen_code.append(&mut deps_this);
     gen_code.push(
@@ -712,8 +1195,13 @@ fn generate_method(
     self_variant: SelfTypeVariant,
     this_type_for_method: &RustType,
 ) -> Result<Vec<TokenStream>> {
-    let jni_ret_type =
-        unpack_unique_typename(&mc.f_method.output.correspoding_rust_type.normalized_name);
+    let jni_ret_type = unpack_unique_typename(
+        &mc.f_method
+            .output
+            .base
+            .correspoding_rust_type
+            .normalized_name,
+    );
     let n_args = mc.f_method.input.len();
     let (deps_code_in, convert_input_code) = foreign_to_rust_convert_method_inputs(
         conv_map,
@@ -724,14 +1212,8 @@ fn generate_method(
         &jni_ret_type,
     )?;
 
-    let (mut deps_code_out, convert_output_code) = foreign_from_rust_convert_method_output(
-        conv_map,
-        mc.class.src_id,
-        &mc.method.fn_decl.output,
-        &mc.f_method.output,
-        "ret",
-        &jni_ret_type,
-    )?;
+    let (mut deps_code_out, convert_output_code) =
+        convert_method_output(conv_map, mc, &jni_ret_type)?;
 
     //&mut constructor_real_type -> &mut class.self_type
 
@@ -752,12 +1234,8 @@ fn generate_method(
         (mc.class.src_id, mc.method.span()),
     )?;
 
-    let code = format!(
+    let body = format!(
         r#"
-#[allow(non_snake_case, unused_variables, unused_mut)]
-#[no_mangle]
-pub extern "C"
- fn {func_name}(env: *mut JNIEnv, _: jclass, this: jlong, {decl_func_args}) -> {jni_ret_type} {{
 {convert_input_code}
     let this: {this_type_ref} = unsafe {{
         jlong_to_pointer::<{this_type}>(this).as_mut().unwrap()
@@ -766,12 +1244,8 @@ pub extern "C"
     let mut ret: {real_output_typename} = {rust_func_name}(this, {args_names});
 {convert_output_code}
     ret
-}}
 "#,
-        func_name = mc.jni_func_name,
-        decl_func_args = mc.decl_func_args,
         convert_input_code = convert_input_code,
-        jni_ret_type = jni_ret_type,
         this_type_ref = this_type_ref,
         this_type = this_type_for_method.normalized_name,
         convert_this = convert_this,
@@ -780,6 +1254,20 @@ pub extern "C"
         convert_output_code = convert_output_code,
         real_output_typename = mc.real_output_typename,
     );
+    let code = format!(
+        r#"
+#[allow(non_snake_case, unused_variables, unused_mut)]
+#[no_mangle]
+pub extern "C"
+ fn {func_name}(env: *mut JNIEnv, _: jclass, this: jlong, {decl_func_args}) -> {jni_ret_type} {{
+{body}
+}}
+"#,
+        func_name = mc.jni_func_name,
+        decl_func_args = mc.decl_func_args,
+        jni_ret_type = jni_ret_type,
+        body = catch_unwind_if_need(mc.catch_panics, jni_ret_type, body),
+    );
     let mut gen_code = deps_code_in;
     gen_code.append(&mut deps_code_out);
     gen_code.append(&mut deps_this);
@@ -790,6 +1278,30 @@ pub extern "C"
     Ok(gen_code)
 }
 
+/// Wrap body of JNI function into `catch_unwind` if panics should be caught,
+/// panic converted to `java.lang.RuntimeException`
+fn catch_unwind_if_need(catch_panics: bool, jni_ret_type: &str, body: String) -> String {
+    if !catch_panics {
+        return body;
+    }
+    format!(
+        r#"
+    let ret = ::std::panic::catch_unwind(::std::panic::AssertUnwindSafe(move || -> {jni_ret_type} {{
+{body}
+    }}));
+    match ret {{
+        Ok(ret) => ret,
+        Err(err) => {{
+            jni_throw_panic(env, err);
+            <{jni_ret_type}>::invalid_value()
+        }}
+    }}
+"#,
+        jni_ret_type = jni_ret_type,
+        body = body,
+    )
+}
+
 fn jni_method_signature(
     method: &JniForeignMethodSignature,
     package_name: &str,
@@ -797,41 +1309,57 @@ fn jni_method_signature(
 ) -> String {
     let mut ret: String = "(".into();
     for arg in &method.input {
-        let mut gen_sig = String::new();
-        let sig = JAVA_TYPE_NAMES_FOR_JNI_SIGNATURE
-            .get(&arg.as_ref().name.as_str())
-            .cloned()
-            .or_else(|| {
-                if conv_map.is_generated_foreign_type(&arg.as_ref().name) {
-                    gen_sig = format!(
-                        "L{};",
-                        &java_class_full_name(package_name, &*arg.as_ref().name.as_str())
-                    );
-                    Some(&gen_sig)
-                } else {
-                    None
-                }
-            })
-            .unwrap_or_else(|| {
-                panic!(
-                    "Unknown type `{}`, can not generate jni signature",
-                    arg.as_ref().name
-                )
-            });
-        let sig = sig.replace('.', "/");
-        ret.push_str(&sig);
+        ret.push_str(&jni_type_signature(
+            &arg.as_ref().name,
+            package_name,
+            conv_map,
+        ));
     }
     ret.push(')');
+    ret.push_str(&jni_type_signature(
+        &method.output.base.name,
+        package_name,
+        conv_map,
+    ));
+    ret
+}
+
+fn jni_type_signature(java_type_name: &str, package_name: &str, conv_map: &TypeMap) -> String {
+    let mut gen_sig = String::new();
     let sig = JAVA_TYPE_NAMES_FOR_JNI_SIGNATURE
-        .get(&*method.output.name.as_str())
+        .get(java_type_name)
+        .cloned()
+        .or_else(|| {
+            if conv_map.is_generated_foreign_type(java_type_name) {
+                gen_sig = format!("L{};", &java_class_full_name(package_name, java_type_name));
+                Some(&gen_sig)
+            } else {
+                None
+            }
+        })
         .unwrap_or_else(|| {
             panic!(
                 "Unknown type `{}`, can not generate jni signature",
-                method.output.name
+                java_type_name
             )
         });
-    ret.push_str(sig);
-    ret
+    sig.replace('.', "/")
+}
+
+/// Name of `JNIEnv` function to call Java method that returns `java_ret_type`
+fn jni_call_method_for_ret_type(java_ret_type: &str) -> &'static str {
+    match java_ret_type {
+        "boolean" => "CallBooleanMethod",
+        "byte" => "CallByteMethod",
+        "char" => "CallCharMethod",
+        "short" => "CallShortMethod",
+        "int" => "CallIntMethod",
+        "long" => "CallLongMethod",
+        "float" => "CallFloatMethod",
+        "double" => "CallDoubleMethod",
+        "void" => "CallVoidMethod",
+        _ => "CallObjectMethod",
+    }
 }
 
 // To use `C` function with variable number of arguments,
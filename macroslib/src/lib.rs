@@ -17,9 +17,15 @@ macro_rules! parse_type {
 
 mod code_parse;
 mod cpp;
+mod csharp;
+mod dart;
 mod error;
 pub mod file_cache;
+mod go;
 mod java_jni;
+mod lint;
+mod nodejs;
+mod python;
 mod source_registry;
 mod typemap;
 mod types;
@@ -35,14 +41,22 @@ use std::{
 
 use log::{debug, trace};
 use proc_macro2::TokenStream;
-use rustc_hash::FxHashSet;
+use rustc_hash::{FxHashMap, FxHashSet};
 use syn::spanned::Spanned;
 
+pub use crate::{
+    error::{Diagnostic, Error, SourceLocation},
+    lint::Lint,
+};
+
 use crate::{
-    error::{panic_on_parse_error, DiagnosticError, Result},
+    error::{add_error, panic_on_parse_error, print_warnings, DiagnosticError, Result},
+    lint::LintLevel,
     source_registry::{SourceId, SourceRegistry},
     typemap::{ast::DisplayToTokens, TypeMap},
-    types::{ForeignEnumInfo, ForeignInterface, ForeignerClassInfo},
+    types::{
+        ForeignEnumInfo, ForeignFlagsInfo, ForeignInterface, ForeignStructInfo, ForeignerClassInfo,
+    },
 };
 
 /// Calculate target pointer width from environment variable
@@ -60,6 +74,29 @@ pub fn target_pointer_width_from_env() -> Option<usize> {
 pub enum LanguageConfig {
     JavaConfig(JavaConfig),
     CppConfig(CppConfig),
+    CSharpConfig(CSharpConfig),
+    PythonConfig(PythonConfig),
+    CConfig(CConfig),
+    NodeJsConfig(NodeJsConfig),
+    DartConfig(DartConfig),
+    GoConfig(GoConfig),
+}
+
+impl LanguageConfig {
+    /// Level of `lint` set by configuration, `None` if it is not set
+    fn lint_level(&self, lint: Lint) -> Option<LintLevel> {
+        let lint_levels = match self {
+            LanguageConfig::JavaConfig(ref java_cfg) => &java_cfg.lint_levels,
+            LanguageConfig::CppConfig(ref cpp_cfg) => &cpp_cfg.lint_levels,
+            LanguageConfig::CConfig(ref c_cfg) => &c_cfg.c_abi.lint_levels,
+            LanguageConfig::CSharpConfig(ref cs_cfg) => &cs_cfg.c_abi.lint_levels,
+            LanguageConfig::PythonConfig(ref python_cfg) => &python_cfg.lint_levels,
+            LanguageConfig::NodeJsConfig(ref nodejs_cfg) => &nodejs_cfg.lint_levels,
+            LanguageConfig::DartConfig(ref dart_cfg) => &dart_cfg.c_abi.lint_levels,
+            LanguageConfig::GoConfig(ref go_cfg) => &go_cfg.c_abi.lint_levels,
+        };
+        lint_levels.get(&lint).cloned()
+    }
 }
 
 /// Configuration for Java binding generation
@@ -68,6 +105,13 @@ pub struct JavaConfig {
     package_name: String,
     null_annotation_package: Option<String>,
     optional_package: String,
+    catch_panics: bool,
+    use_cleaner: bool,
+    use_kotlin: bool,
+    future_executor: Option<String>,
+    tuple_class_names: FxHashMap<String, String>,
+    generated_tuple_classes: RefCell<FxHashSet<String>>,
+    lint_levels: FxHashMap<Lint, LintLevel>,
 }
 
 impl JavaConfig {
@@ -81,6 +125,13 @@ impl JavaConfig {
             package_name,
             null_annotation_package: None,
             optional_package: "java.util".to_string(),
+            catch_panics: false,
+            use_cleaner: false,
+            use_kotlin: false,
+            future_executor: None,
+            tuple_class_names: FxHashMap::default(),
+            generated_tuple_classes: RefCell::new(FxHashSet::default()),
+            lint_levels: FxHashMap::default(),
         }
     }
     /// Use @NonNull for types where appropriate
@@ -118,6 +169,66 @@ impl JavaConfig {
         self.optional_package = optional_package;
         self
     }
+    /// Catch Rust panics inside generated JNI functions and rethrow them
+    /// as `java.lang.RuntimeException`, instead of unwinding across FFI boundary.
+    /// Disabled by default.
+    pub fn catch_panics(mut self, catch_panics: bool) -> JavaConfig {
+        self.catch_panics = catch_panics;
+        self
+    }
+    /// Generate Java classes that implement `java.lang.AutoCloseable`
+    /// and free Rust objects via `java.lang.ref.Cleaner` (Java 9+),
+    /// instead of deprecated `finalize`. Disabled by default.
+    pub fn use_cleaner(mut self, use_cleaner: bool) -> JavaConfig {
+        self.use_cleaner = use_cleaner;
+        self
+    }
+    /// Generate Kotlin classes (`.kt` files) instead of Java ones,
+    /// they use the same JNI functions, but `Option<T>` is mapped
+    /// to nullable `T?`, getter/setter pairs become properties
+    /// and classes implement `java.io.Closeable`. Disabled by default.
+    pub fn use_kotlin(mut self, use_kotlin: bool) -> JavaConfig {
+        self.use_kotlin = use_kotlin;
+        self
+    }
+    /// Path of function that executes futures returned by methods,
+    /// it should have such signature
+    /// `fn(Pin<Box<dyn Future<Output = ()> + Send + 'static>>)`.
+    /// By default every future is polled on its own thread.
+    pub fn future_executor(mut self, future_executor: String) -> JavaConfig {
+        self.future_executor = Some(future_executor);
+        self
+    }
+    /// Name of Java class generated for Rust tuple, by default
+    /// `(i32, String)` becomes `Pair_Integer_String`
+    /// and `(A, B, C)` becomes `Triple_A_B_C`
+    /// # Arguments
+    /// * `rust_tuple` - Rust type of tuple, for example `(i32, String)`
+    /// * `class_name` - name of Java class, relative to package
+    pub fn tuple_class_name(mut self, rust_tuple: &str, class_name: String) -> JavaConfig {
+        let ty: syn::Type = syn::parse_str(rust_tuple)
+            .unwrap_or_else(|err| panic!("Can not parse '{}' as Rust type: {}", rust_tuple, err));
+        self.tuple_class_names.insert(
+            typemap::ast::normalize_ty_lifetimes(&ty).to_string(),
+            class_name,
+        );
+        self
+    }
+    /// Do not report code that matches `lint`
+    pub fn allow_lint(mut self, lint: Lint) -> JavaConfig {
+        self.lint_levels.insert(lint, LintLevel::Allow);
+        self
+    }
+    /// Report code that matches `lint` as warning, this is default level
+    pub fn warn_lint(mut self, lint: Lint) -> JavaConfig {
+        self.lint_levels.insert(lint, LintLevel::Warn);
+        self
+    }
+    /// Report code that matches `lint` as error instead of warning
+    pub fn deny_lint(mut self, lint: Lint) -> JavaConfig {
+        self.lint_levels.insert(lint, LintLevel::Deny);
+        self
+    }
 }
 
 /// Configuration for C++ binding generation
@@ -131,6 +242,22 @@ pub struct CppConfig {
     /// Create separate *_impl.hpp files with methods implementations.
     /// Can be necessary for the project with circular dependencies between classes.
     separate_impl_headers: bool,
+    panic_handling: Option<CppPanicHandling>,
+    output: CppOutput,
+    future_executor: Option<String>,
+    lint_levels: FxHashMap<Lint, LintLevel>,
+}
+
+/// Which foreign files `CppConfig` writes into `output_dir`
+#[derive(Clone, Copy, PartialEq)]
+enum CppOutput {
+    /// C headers and C++ wrappers around them
+    CAndCpp,
+    /// Only C99 headers, without C++ code
+    C,
+    /// Nothing, only Rust side of C API is generated,
+    /// used by backends that reuse C API
+    Nothing,
 }
 
 /// To which `C++` type map `std::option::Option`
@@ -149,6 +276,19 @@ pub enum CppVariant {
     Boost,
 }
 
+/// What to do with Rust panic caught inside generated `extern "C"` function
+pub enum CppPanicHandling {
+    /// Call `std::process::abort`
+    Abort,
+    /// Pass panic message to `extern "C"` function with such name,
+    /// it should be implemented on C/C++ side and have such signature
+    /// `void handler(const char *msg)`, and should not throw C++ exceptions.
+    /// There is no valid value that generated function can return after panic,
+    /// so handler should not return (for example, it can log message and terminate process),
+    /// if it returns, `std::process::abort` is called.
+    CallHandler(String),
+}
+
 impl CppConfig {
     /// Create `CppConfig`
     /// # Arguments
@@ -163,6 +303,10 @@ impl CppConfig {
             generated_helper_files: RefCell::new(FxHashSet::default()),
             to_generate: RefCell::new(vec![]),
             separate_impl_headers: false,
+            panic_handling: None,
+            output: CppOutput::CAndCpp,
+            future_executor: None,
+            lint_levels: FxHashMap::default(),
         }
     }
     pub fn cpp_optional(self, cpp_optional: CppOptional) -> CppConfig {
@@ -192,6 +336,361 @@ impl CppConfig {
             ..self
         }
     }
+    /// Catch Rust panics inside generated `extern "C"` functions,
+    /// instead of unwinding across FFI boundary. Disabled by default.
+    pub fn catch_panics(self, panic_handling: CppPanicHandling) -> CppConfig {
+        CppConfig {
+            panic_handling: Some(panic_handling),
+            ..self
+        }
+    }
+    /// Path of function that executes futures returned by methods,
+    /// see `JavaConfig::future_executor`
+    pub fn future_executor(self, future_executor: String) -> CppConfig {
+        CppConfig {
+            future_executor: Some(future_executor),
+            ..self
+        }
+    }
+    /// Do not report code that matches `lint`
+    pub fn allow_lint(self, lint: Lint) -> CppConfig {
+        self.set_lint_level(lint, LintLevel::Allow)
+    }
+    /// Report code that matches `lint` as warning, this is default level
+    pub fn warn_lint(self, lint: Lint) -> CppConfig {
+        self.set_lint_level(lint, LintLevel::Warn)
+    }
+    /// Report code that matches `lint` as error instead of warning
+    pub fn deny_lint(self, lint: Lint) -> CppConfig {
+        self.set_lint_level(lint, LintLevel::Deny)
+    }
+    fn set_lint_level(self, lint: Lint, level: LintLevel) -> CppConfig {
+        let mut lint_levels = self.lint_levels;
+        lint_levels.insert(lint, level);
+        CppConfig {
+            lint_levels,
+            ..self
+        }
+    }
+}
+
+/// Configuration for C binding generation,
+/// generated headers contain only C99 code without C++ wrappers
+pub struct CConfig {
+    c_abi: CppConfig,
+}
+
+impl CConfig {
+    /// Create `CConfig`
+    /// # Arguments
+    /// * `output_dir` - directory where place generated C headers
+    pub fn new(output_dir: PathBuf) -> CConfig {
+        let c_abi = CppConfig {
+            output: CppOutput::C,
+            ..CppConfig::new(output_dir, String::new())
+        };
+        CConfig { c_abi }
+    }
+    /// Catch Rust panics inside generated `extern "C"` functions,
+    /// instead of unwinding across FFI boundary. Disabled by default.
+    pub fn catch_panics(self, panic_handling: CppPanicHandling) -> CConfig {
+        CConfig {
+            c_abi: self.c_abi.catch_panics(panic_handling),
+        }
+    }
+    /// Path of function that executes futures returned by methods,
+    /// see `JavaConfig::future_executor`
+    pub fn future_executor(self, future_executor: String) -> CConfig {
+        CConfig {
+            c_abi: self.c_abi.future_executor(future_executor),
+        }
+    }
+    /// Do not report code that matches `lint`
+    pub fn allow_lint(self, lint: Lint) -> CConfig {
+        CConfig {
+            c_abi: self.c_abi.allow_lint(lint),
+        }
+    }
+    /// Report code that matches `lint` as warning, this is default level
+    pub fn warn_lint(self, lint: Lint) -> CConfig {
+        CConfig {
+            c_abi: self.c_abi.warn_lint(lint),
+        }
+    }
+    /// Report code that matches `lint` as error instead of warning
+    pub fn deny_lint(self, lint: Lint) -> CConfig {
+        CConfig {
+            c_abi: self.c_abi.deny_lint(lint),
+        }
+    }
+}
+
+/// Configuration for C# binding generation,
+/// generated classes call `extern "C"` functions via P/Invoke
+pub struct CSharpConfig {
+    output_dir: PathBuf,
+    namespace_name: String,
+    native_lib_name: String,
+    c_abi: CppConfig,
+}
+
+impl CSharpConfig {
+    /// Create `CSharpConfig`
+    /// # Arguments
+    /// * `output_dir` - directory where place generated C# files
+    /// * `namespace_name` - namespace for generated C# classes
+    /// * `native_lib_name` - name of Rust library for `DllImport`, for example "mylib" for libmylib.so
+    pub fn new(
+        output_dir: PathBuf,
+        namespace_name: String,
+        native_lib_name: String,
+    ) -> CSharpConfig {
+        let c_abi = CppConfig {
+            output: CppOutput::Nothing,
+            ..CppConfig::new(output_dir.clone(), namespace_name.clone())
+        };
+        CSharpConfig {
+            output_dir,
+            namespace_name,
+            native_lib_name,
+            c_abi,
+        }
+    }
+    /// Do not report code that matches `lint`
+    pub fn allow_lint(self, lint: Lint) -> CSharpConfig {
+        CSharpConfig {
+            c_abi: self.c_abi.allow_lint(lint),
+            ..self
+        }
+    }
+    /// Report code that matches `lint` as warning, this is default level
+    pub fn warn_lint(self, lint: Lint) -> CSharpConfig {
+        CSharpConfig {
+            c_abi: self.c_abi.warn_lint(lint),
+            ..self
+        }
+    }
+    /// Report code that matches `lint` as error instead of warning
+    pub fn deny_lint(self, lint: Lint) -> CSharpConfig {
+        CSharpConfig {
+            c_abi: self.c_abi.deny_lint(lint),
+            ..self
+        }
+    }
+}
+
+/// Configuration for Python binding generation,
+/// generated Rust code is CPython extension module
+pub struct PythonConfig {
+    output_dir: PathBuf,
+    module_name: String,
+    /// Rust functions that add classes and enums to module,
+    /// called from `PyInit_<module_name>`
+    module_init: RefCell<Vec<String>>,
+    /// Content of `<module_name>.pyi` file
+    stubs: RefCell<String>,
+    catch_panics: bool,
+    lint_levels: FxHashMap<Lint, LintLevel>,
+}
+
+impl PythonConfig {
+    /// Create `PythonConfig`
+    /// # Arguments
+    /// * `output_dir` - directory where place generated `<module_name>.pyi` file with type hints
+    /// * `module_name` - name of Python module, should be the same as name
+    ///   of shared library without "lib" prefix
+    pub fn new(output_dir: PathBuf, module_name: String) -> PythonConfig {
+        PythonConfig {
+            output_dir,
+            module_name,
+            module_init: RefCell::new(vec![]),
+            stubs: RefCell::new(String::new()),
+            catch_panics: false,
+            lint_levels: FxHashMap::default(),
+        }
+    }
+    /// Catch Rust panics inside generated functions and raise them
+    /// as `RuntimeError`, instead of unwinding across FFI boundary,
+    /// that aborts Python interpreter. Disabled by default.
+    pub fn catch_panics(mut self, catch_panics: bool) -> PythonConfig {
+        self.catch_panics = catch_panics;
+        self
+    }
+    /// Do not report code that matches `lint`
+    pub fn allow_lint(mut self, lint: Lint) -> PythonConfig {
+        self.lint_levels.insert(lint, LintLevel::Allow);
+        self
+    }
+    /// Report code that matches `lint` as warning, this is default level
+    pub fn warn_lint(mut self, lint: Lint) -> PythonConfig {
+        self.lint_levels.insert(lint, LintLevel::Warn);
+        self
+    }
+    /// Report code that matches `lint` as error instead of warning
+    pub fn deny_lint(mut self, lint: Lint) -> PythonConfig {
+        self.lint_levels.insert(lint, LintLevel::Deny);
+        self
+    }
+}
+
+/// Configuration for Node.js binding generation,
+/// generated Rust code is N-API addon
+pub struct NodeJsConfig {
+    output_dir: PathBuf,
+    module_name: String,
+    /// Rust functions that add classes and enums to `exports`,
+    /// called from `napi_register_module_v1`
+    module_init: RefCell<Vec<String>>,
+    /// Content of `<module_name>.d.ts` file
+    typings: RefCell<String>,
+    lint_levels: FxHashMap<Lint, LintLevel>,
+}
+
+impl NodeJsConfig {
+    /// Create `NodeJsConfig`
+    /// # Arguments
+    /// * `output_dir` - directory where place generated `<module_name>.d.ts` file
+    ///   with TypeScript declarations
+    /// * `module_name` - name of Node.js addon, used as name of `.d.ts` file
+    pub fn new(output_dir: PathBuf, module_name: String) -> NodeJsConfig {
+        NodeJsConfig {
+            output_dir,
+            module_name,
+            module_init: RefCell::new(vec![]),
+            typings: RefCell::new(String::new()),
+            lint_levels: FxHashMap::default(),
+        }
+    }
+    /// Do not report code that matches `lint`
+    pub fn allow_lint(mut self, lint: Lint) -> NodeJsConfig {
+        self.lint_levels.insert(lint, LintLevel::Allow);
+        self
+    }
+    /// Report code that matches `lint` as warning, this is default level
+    pub fn warn_lint(mut self, lint: Lint) -> NodeJsConfig {
+        self.lint_levels.insert(lint, LintLevel::Warn);
+        self
+    }
+    /// Report code that matches `lint` as error instead of warning
+    pub fn deny_lint(mut self, lint: Lint) -> NodeJsConfig {
+        self.lint_levels.insert(lint, LintLevel::Deny);
+        self
+    }
+}
+
+/// Configuration for Dart binding generation,
+/// generated Dart library calls `extern "C"` functions via `dart:ffi`
+pub struct DartConfig {
+    output_dir: PathBuf,
+    native_lib_name: String,
+    c_abi: CppConfig,
+    /// Generated files, included into `<native_lib_name>.dart` as parts
+    parts: RefCell<Vec<String>>,
+}
+
+impl DartConfig {
+    /// Create `DartConfig`
+    /// # Arguments
+    /// * `output_dir` - directory where place generated Dart files
+    /// * `native_lib_name` - name of Rust library, for example "mylib" for libmylib.so,
+    ///   also used as name of generated Dart library
+    pub fn new(output_dir: PathBuf, native_lib_name: String) -> DartConfig {
+        let c_abi = CppConfig {
+            output: CppOutput::Nothing,
+            ..CppConfig::new(output_dir.clone(), native_lib_name.clone())
+        };
+        DartConfig {
+            output_dir,
+            native_lib_name,
+            c_abi,
+            parts: RefCell::new(vec![]),
+        }
+    }
+    /// Do not report code that matches `lint`
+    pub fn allow_lint(self, lint: Lint) -> DartConfig {
+        DartConfig {
+            c_abi: self.c_abi.allow_lint(lint),
+            ..self
+        }
+    }
+    /// Report code that matches `lint` as warning, this is default level
+    pub fn warn_lint(self, lint: Lint) -> DartConfig {
+        DartConfig {
+            c_abi: self.c_abi.warn_lint(lint),
+            ..self
+        }
+    }
+    /// Report code that matches `lint` as error instead of warning
+    pub fn deny_lint(self, lint: Lint) -> DartConfig {
+        DartConfig {
+            c_abi: self.c_abi.deny_lint(lint),
+            ..self
+        }
+    }
+}
+
+/// Configuration for Go binding generation,
+/// generated Go package calls `extern "C"` functions via cgo
+pub struct GoConfig {
+    output_dir: PathBuf,
+    package_name: String,
+    native_lib_name: String,
+    c_abi: CppConfig,
+}
+
+impl GoConfig {
+    /// Create `GoConfig`
+    /// # Arguments
+    /// * `output_dir` - directory of Go package, where place generated Go files and C headers
+    /// * `package_name` - name of generated Go package
+    /// * `native_lib_name` - name of Rust library for `#cgo LDFLAGS`, for example "mylib" for libmylib.so
+    pub fn new(output_dir: PathBuf, package_name: String, native_lib_name: String) -> GoConfig {
+        let c_abi = CppConfig {
+            output: CppOutput::C,
+            ..CppConfig::new(output_dir.clone(), package_name.clone())
+        };
+        GoConfig {
+            output_dir,
+            package_name,
+            native_lib_name,
+            c_abi,
+        }
+    }
+    /// Do not report code that matches `lint`
+    pub fn allow_lint(self, lint: Lint) -> GoConfig {
+        GoConfig {
+            c_abi: self.c_abi.allow_lint(lint),
+            ..self
+        }
+    }
+    /// Report code that matches `lint` as warning, this is default level
+    pub fn warn_lint(self, lint: Lint) -> GoConfig {
+        GoConfig {
+            c_abi: self.c_abi.warn_lint(lint),
+            ..self
+        }
+    }
+    /// Report code that matches `lint` as error instead of warning
+    pub fn deny_lint(self, lint: Lint) -> GoConfig {
+        GoConfig {
+            c_abi: self.c_abi.deny_lint(lint),
+            ..self
+        }
+    }
+}
+
+/// How `Generator::expand` reports found problems
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DiagnosticFormat {
+    /// Errors are printed to stderr in own format of `rust_swig`,
+    /// warnings are printed as `cargo:warning=` lines
+    Plain,
+    /// Errors are printed to stderr like rustc does: `error: ...` plus `--> file:line:col`,
+    /// warnings are printed as `cargo:warning=` lines
+    Rustc,
+    /// Errors and warnings are printed to stderr, one JSON object per line,
+    /// in the same schema as rustc's `--error-format=json`
+    Json,
 }
 
 /// `Generator` is a main point of `rust_swig`.
@@ -205,6 +704,9 @@ pub struct Generator {
     foreign_lang_helpers: Vec<SourceCode>,
     pointer_target_width: usize,
     src_reg: SourceRegistry,
+    diagnostic_format: DiagnosticFormat,
+    /// Warnings that should be reported, according to lint levels from config
+    warnings: Vec<DiagnosticError>,
 }
 
 struct SourceCode {
@@ -215,12 +717,16 @@ struct SourceCode {
 static FOREIGNER_CLASS: &str = "foreigner_class";
 static FOREIGN_ENUM: &str = "foreign_enum";
 static FOREIGN_INTERFACE: &str = "foreign_interface";
+static FOREIGN_STRUCT: &str = "foreign_struct";
+static FOREIGN_FLAGS: &str = "foreign_flags";
 
 enum OutputCode {
     Item(syn::Item),
     Class(ForeignerClassInfo),
     Interface(ForeignInterface),
     Enum(ForeignEnumInfo),
+    Struct(ForeignStructInfo),
+    Flags(ForeignFlagsInfo),
 }
 
 impl Generator {
@@ -274,6 +780,70 @@ impl Generator {
                     id_of_code: "rust_tuple.h".into(),
                     code: include_str!("cpp/rust_tuple.h").into(),
                 });
+                foreign_lang_helpers.push(SourceCode {
+                    id_of_code: "rust_map.h".into(),
+                    code: include_str!("cpp/rust_map.h").into(),
+                });
+            }
+            LanguageConfig::CSharpConfig(..) => {
+                conv_map_source.push(src_reg.register(SourceCode {
+                    id_of_code: "cpp-include.rs".into(),
+                    code: include_str!("cpp/cpp-include.rs").into(),
+                }));
+                foreign_lang_helpers.push(SourceCode {
+                    id_of_code: "RustSwig.cs".into(),
+                    code: include_str!("csharp/RustSwig.cs").into(),
+                });
+            }
+            LanguageConfig::PythonConfig(..) => {
+                conv_map_source.push(src_reg.register(SourceCode {
+                    id_of_code: "python-include.rs".into(),
+                    code: include_str!("python/python-include.rs").into(),
+                }));
+            }
+            LanguageConfig::CConfig(..) => {
+                conv_map_source.push(src_reg.register(SourceCode {
+                    id_of_code: "cpp-include.rs".into(),
+                    code: include_str!("cpp/cpp-include.rs").into(),
+                }));
+                foreign_lang_helpers.push(SourceCode {
+                    id_of_code: "rust_swig_c.h".into(),
+                    code: include_str!("cpp/rust_swig_c.h").into(),
+                });
+            }
+            LanguageConfig::NodeJsConfig(..) => {
+                conv_map_source.push(src_reg.register(SourceCode {
+                    id_of_code: "napi-include.rs".into(),
+                    code: include_str!("nodejs/napi-include.rs").into(),
+                }));
+            }
+            LanguageConfig::DartConfig(..) => {
+                conv_map_source.push(src_reg.register(SourceCode {
+                    id_of_code: "cpp-include.rs".into(),
+                    code: include_str!("cpp/cpp-include.rs").into(),
+                }));
+                foreign_lang_helpers.push(SourceCode {
+                    id_of_code: "rust_swig.dart".into(),
+                    code: include_str!("dart/rust_swig.dart").into(),
+                });
+            }
+            LanguageConfig::GoConfig(..) => {
+                conv_map_source.push(src_reg.register(SourceCode {
+                    id_of_code: "cpp-include.rs".into(),
+                    code: include_str!("cpp/cpp-include.rs").into(),
+                }));
+                conv_map_source.push(src_reg.register(SourceCode {
+                    id_of_code: "go-include.rs".into(),
+                    code: include_str!("go/go-include.rs").into(),
+                }));
+                foreign_lang_helpers.push(SourceCode {
+                    id_of_code: "rust_swig_c.h".into(),
+                    code: include_str!("cpp/rust_swig_c.h").into(),
+                });
+                foreign_lang_helpers.push(SourceCode {
+                    id_of_code: "rust_swig.go".into(),
+                    code: include_str!("go/rust_swig.go").into(),
+                });
             }
         }
         Generator {
@@ -284,6 +854,8 @@ impl Generator {
             foreign_lang_helpers,
             pointer_target_width: pointer_target_width.unwrap_or(0),
             src_reg,
+            diagnostic_format: DiagnosticFormat::Plain,
+            warnings: vec![],
         }
     }
 
@@ -294,6 +866,13 @@ impl Generator {
         self
     }
 
+    /// Change format of errors and warnings printed by `expand`,
+    /// by default `DiagnosticFormat::Plain` is used
+    pub fn with_diagnostic_format(mut self, diagnostic_format: DiagnosticFormat) -> Generator {
+        self.diagnostic_format = diagnostic_format;
+        self
+    }
+
     /// Add new foreign langauge type <-> Rust mapping
     pub fn merge_type_map(mut self, id_of_code: &str, code: &str) -> Generator {
         self.conv_map_source.push(self.src_reg.register(SourceCode {
@@ -306,45 +885,73 @@ impl Generator {
     /// process `src` and save result of macro expansion to `dst`
     ///
     /// # Panics
-    /// Panics on error
+    /// Panics on error, before that all found errors are printed
     pub fn expand<S, D>(mut self, crate_name: &str, src: S, dst: D)
     where
         S: AsRef<Path>,
         D: AsRef<Path>,
     {
-        let src_cnt = std::fs::read_to_string(src.as_ref()).unwrap_or_else(|err| {
-            panic!(
+        let ret = self.expand_file(crate_name, src.as_ref(), dst.as_ref());
+        print_warnings(&self.src_reg, self.diagnostic_format, &self.warnings);
+        if let Err(err) = ret {
+            panic_on_parse_error(&self.src_reg, self.diagnostic_format, &err);
+        }
+    }
+
+    /// process `src` and save result of macro expansion to `dst`,
+    /// in case of error `dst` is not modified.
+    /// Nothing is printed, found warnings are returned in case of success
+    pub fn try_expand<S, D>(
+        mut self,
+        crate_name: &str,
+        src: S,
+        dst: D,
+    ) -> std::result::Result<Vec<Diagnostic>, Error>
+    where
+        S: AsRef<Path>,
+        D: AsRef<Path>,
+    {
+        self.expand_file(crate_name, src.as_ref(), dst.as_ref())
+            .map_err(|err| err.to_public(&self.src_reg))?;
+        let src_reg = &self.src_reg;
+        Ok(self
+            .warnings
+            .iter()
+            .flat_map(|warning| warning.to_public(src_reg).diagnostics)
+            .collect())
+    }
+
+    fn expand_file(&mut self, crate_name: &str, src: &Path, dst: &Path) -> Result<()> {
+        let src_cnt = std::fs::read_to_string(src).map_err(|err| {
+            DiagnosticError::new_without_src_info(format!(
                 "Error during read for file {}: {}",
-                src.as_ref().display(),
+                src.display(),
                 err
-            )
-        });
+            ))
+        })?;
 
-        let src_id = self.src_reg.register(SourceCode {
-            id_of_code: format!("{}: {}", crate_name, src.as_ref().display()),
-            code: src_cnt.into(),
-        });
+        let src_id = self.src_reg.register_file(
+            SourceCode {
+                id_of_code: format!("{}: {}", crate_name, src.display()),
+                code: src_cnt,
+            },
+            src,
+        );
 
-        if let Err(err) = self.expand_str(src_id, dst) {
-            panic_on_parse_error(&self.src_reg, &err);
-        }
+        self.expand_str(src_id, dst)
     }
 
     /// process `src` and save result of macro expansion to `dst`
-    ///
-    /// # Panics
-    /// Panics on I/O errors
     fn expand_str<D>(&mut self, src_id: SourceId, dst: D) -> Result<()>
     where
         D: AsRef<Path>,
     {
         if self.pointer_target_width == 0 {
-            panic!(
-                r#"pointer target width unknown,
- set env CARGO_CFG_TARGET_POINTER_WIDTH environment variable,
- or use `with_pointer_target_width` function
-"#
-            );
+            return Err(DiagnosticError::new_without_src_info(
+                "pointer target width unknown, \
+                 set env CARGO_CFG_TARGET_POINTER_WIDTH environment variable, \
+                 or use `with_pointer_target_width` function",
+            ));
         }
         let items = self.init_types_map(self.pointer_target_width)?;
 
@@ -358,48 +965,48 @@ impl Generator {
         }
 
         let mut output_code = vec![];
+        let mut errors: Option<DiagnosticError> = None;
+        let rust_enums: Vec<syn::ItemEnum> = syn_file
+            .items
+            .iter()
+            .filter_map(|item| match item {
+                syn::Item::Enum(ref x) => Some(x.clone()),
+                _ => None,
+            })
+            .collect();
 
         for item in syn_file.items {
-            if let syn::Item::Macro(mut item_macro) = item {
-                let is_our_macro = [FOREIGNER_CLASS, FOREIGN_ENUM, FOREIGN_INTERFACE]
-                    .iter()
-                    .any(|x| item_macro.mac.path.is_ident(x));
+            if let syn::Item::Macro(item_macro) = item {
+                let is_our_macro = [
+                    FOREIGNER_CLASS,
+                    FOREIGN_ENUM,
+                    FOREIGN_INTERFACE,
+                    FOREIGN_STRUCT,
+                    FOREIGN_FLAGS,
+                ]
+                .iter()
+                .any(|x| item_macro.mac.path.is_ident(x));
                 if !is_our_macro {
                     writeln!(&mut file, "{}", DisplayToTokens(&item_macro))
                         .expect("mem I/O failed");
                     continue;
                 }
                 trace!("Found {:?}", item_macro.mac.path);
-                if item_macro.mac.tts.is_empty() {
-                    return Err(DiagnosticError::new(
-                        src_id,
-                        item_macro.span(),
-                        format!(
-                            "missing tokens in call of macro '{}'",
-                            DisplayToTokens(&item_macro.mac.path)
-                        ),
-                    ));
-                }
-                let mut tts = TokenStream::new();
-                mem::swap(&mut tts, &mut item_macro.mac.tts);
-                if item_macro.mac.path.is_ident(FOREIGNER_CLASS) {
-                    let fclass = code_parse::parse_foreigner_class(src_id, &self.config, tts)?;
-                    debug!(
-                        "expand_foreigner_class: self {:?}, constructor {:?}",
-                        fclass.self_type, fclass.constructor_ret_type
-                    );
-                    self.conv_map.register_foreigner_class(&fclass);
-                    Generator::language_generator(&self.config)
-                        .register_class(&mut self.conv_map, &fclass)?;
-                    output_code.push(OutputCode::Class(fclass));
-                } else if item_macro.mac.path.is_ident(FOREIGN_ENUM) {
-                    let fenum = code_parse::parse_foreign_enum(src_id, tts)?;
-                    output_code.push(OutputCode::Enum(fenum));
-                } else if item_macro.mac.path.is_ident(FOREIGN_INTERFACE) {
-                    let finterface = code_parse::parse_foreign_interface(src_id, tts)?;
-                    output_code.push(OutputCode::Interface(finterface));
-                } else {
-                    unreachable!();
+                // continue with other items to report all errors at once
+                match self.parse_our_macro(src_id, item_macro) {
+                    Ok(code_item) => {
+                        match code_item {
+                            OutputCode::Class(ref fclass) => {
+                                lint::check_foreigner_class(&mut self.conv_map, fclass)
+                            }
+                            OutputCode::Enum(ref fenum) => {
+                                lint::check_foreign_enum(&mut self.conv_map, fenum, &rust_enums)
+                            }
+                            _ => {}
+                        }
+                        output_code.push(code_item);
+                    }
+                    Err(err) => add_error(&mut errors, err),
                 }
             } else {
                 output_code.push(OutputCode::Item(item));
@@ -407,53 +1014,118 @@ impl Generator {
         }
 
         for code_item in output_code {
-            match code_item {
-                OutputCode::Class(fclass) => {
-                    let code = Generator::language_generator(&self.config).generate(
-                        &mut self.conv_map,
-                        self.pointer_target_width,
-                        &fclass,
-                    )?;
+            match self.generate_code(code_item) {
+                Ok(code) => {
                     for elem in code {
-                        writeln!(&mut file, "{}", elem.to_string()).expect("mem I/O failed");
+                        writeln!(&mut file, "{}", elem).expect("mem I/O failed");
                     }
                 }
-                OutputCode::Enum(fenum) => {
-                    let code = Generator::language_generator(&self.config).generate_enum(
-                        &mut self.conv_map,
-                        self.pointer_target_width,
-                        &fenum,
-                    )?;
-                    for elem in code {
-                        writeln!(&mut file, "{}", elem.to_string()).expect("mem I/O failed");
-                    }
-                }
-                OutputCode::Interface(finterface) => {
-                    let code = Generator::language_generator(&self.config).generate_interface(
-                        &mut self.conv_map,
-                        self.pointer_target_width,
-                        &finterface,
-                    )?;
-                    for elem in code {
-                        writeln!(&mut file, "{}", elem.to_string()).expect("mem I/O failed");
-                    }
-                }
-                OutputCode::Item(item) => {
-                    writeln!(&mut file, "{}", DisplayToTokens(&item)).expect("mem I/O failed");
-                }
+                Err(err) => add_error(&mut errors, err),
             }
         }
 
-        file.update_file_if_necessary().unwrap_or_else(|err| {
-            panic!(
+        self.apply_lint_levels(&mut errors);
+        if let Some(errors) = errors {
+            return Err(errors);
+        }
+
+        for elem in Generator::language_generator(&self.config).finish(&mut self.conv_map)? {
+            writeln!(&mut file, "{}", elem).expect("mem I/O failed");
+        }
+
+        file.update_file_if_necessary().map_err(|err| {
+            DiagnosticError::new_without_src_info(format!(
                 "Error during write to file {}: {}",
                 dst.as_ref().display(),
                 err
-            );
-        });
+            ))
+        })?;
         Ok(())
     }
 
+    fn parse_our_macro(
+        &mut self,
+        src_id: SourceId,
+        mut item_macro: syn::ItemMacro,
+    ) -> Result<OutputCode> {
+        if item_macro.mac.tts.is_empty() {
+            return Err(DiagnosticError::new(
+                src_id,
+                item_macro.span(),
+                format!(
+                    "missing tokens in call of macro '{}'",
+                    DisplayToTokens(&item_macro.mac.path)
+                ),
+            ));
+        }
+        let mut tts = TokenStream::new();
+        mem::swap(&mut tts, &mut item_macro.mac.tts);
+        if item_macro.mac.path.is_ident(FOREIGNER_CLASS) {
+            let fclass = code_parse::parse_foreigner_class(src_id, &self.config, tts)?;
+            debug!(
+                "expand_foreigner_class: self {:?}, constructor {:?}",
+                fclass.self_type, fclass.constructor_ret_type
+            );
+            self.conv_map.register_foreigner_class(&fclass);
+            Generator::language_generator(&self.config)
+                .register_class(&mut self.conv_map, &fclass)?;
+            Ok(OutputCode::Class(fclass))
+        } else if item_macro.mac.path.is_ident(FOREIGN_ENUM) {
+            let fenum = code_parse::parse_foreign_enum(src_id, tts)?;
+            Ok(OutputCode::Enum(fenum))
+        } else if item_macro.mac.path.is_ident(FOREIGN_INTERFACE) {
+            let finterface = code_parse::parse_foreign_interface(src_id, tts)?;
+            Ok(OutputCode::Interface(finterface))
+        } else if item_macro.mac.path.is_ident(FOREIGN_STRUCT) {
+            let fstruct = code_parse::parse_foreign_struct(src_id, tts)?;
+            Ok(OutputCode::Struct(fstruct))
+        } else if item_macro.mac.path.is_ident(FOREIGN_FLAGS) {
+            let fflags = code_parse::parse_foreign_flags(src_id, tts)?;
+            Ok(OutputCode::Flags(fflags))
+        } else {
+            unreachable!();
+        }
+    }
+
+    fn generate_code(&mut self, code_item: OutputCode) -> Result<Vec<String>> {
+        let lang_gen = Generator::language_generator(&self.config);
+        let code = match code_item {
+            OutputCode::Class(fclass) => {
+                lang_gen.generate(&mut self.conv_map, self.pointer_target_width, &fclass)?
+            }
+            OutputCode::Enum(fenum) => {
+                lang_gen.generate_enum(&mut self.conv_map, self.pointer_target_width, &fenum)?
+            }
+            OutputCode::Interface(finterface) => lang_gen.generate_interface(
+                &mut self.conv_map,
+                self.pointer_target_width,
+                &finterface,
+            )?,
+            OutputCode::Struct(fstruct) => {
+                lang_gen.generate_struct(&mut self.conv_map, self.pointer_target_width, &fstruct)?
+            }
+            OutputCode::Flags(fflags) => {
+                lang_gen.generate_flags(&mut self.conv_map, self.pointer_target_width, &fflags)?
+            }
+            OutputCode::Item(item) => return Ok(vec![DisplayToTokens(&item).to_string()]),
+        };
+        Ok(code.into_iter().map(|elem| elem.to_string()).collect())
+    }
+
+    /// Drop allowed warnings, and turn denied ones into errors
+    fn apply_lint_levels(&mut self, errors: &mut Option<DiagnosticError>) {
+        for (lint, warning) in self.conv_map.take_warnings() {
+            let explicit_level = self.config.lint_level(lint);
+            let level = explicit_level.unwrap_or(LintLevel::Warn);
+            let warning = level.add_note(lint, warning, explicit_level.is_some());
+            match level {
+                LintLevel::Allow => {}
+                LintLevel::Warn => self.warnings.push(warning),
+                LintLevel::Deny => add_error(errors, warning),
+            }
+        }
+    }
+
     fn init_types_map(&mut self, target_pointer_width: usize) -> Result<Vec<syn::Item>> {
         if self.init_done {
             return Ok(vec![]);
@@ -486,6 +1158,12 @@ impl Generator {
         match cfg {
             LanguageConfig::JavaConfig(ref java_cfg) => java_cfg,
             LanguageConfig::CppConfig(ref cpp_cfg) => cpp_cfg,
+            LanguageConfig::CSharpConfig(ref cs_cfg) => cs_cfg,
+            LanguageConfig::PythonConfig(ref python_cfg) => python_cfg,
+            LanguageConfig::CConfig(ref c_cfg) => &c_cfg.c_abi,
+            LanguageConfig::NodeJsConfig(ref nodejs_cfg) => nodejs_cfg,
+            LanguageConfig::DartConfig(ref dart_cfg) => dart_cfg,
+            LanguageConfig::GoConfig(ref go_cfg) => go_cfg,
         }
     }
 }
@@ -514,6 +1192,20 @@ trait LanguageGenerator {
         interace: &ForeignInterface,
     ) -> Result<Vec<TokenStream>>;
 
+    fn generate_struct(
+        &self,
+        conv_map: &mut TypeMap,
+        pointer_target_width: usize,
+        struct_info: &ForeignStructInfo,
+    ) -> Result<Vec<TokenStream>>;
+
+    fn generate_flags(
+        &self,
+        conv_map: &mut TypeMap,
+        pointer_target_width: usize,
+        flags_info: &ForeignFlagsInfo,
+    ) -> Result<Vec<TokenStream>>;
+
     /// Called before any other methods and only once
     fn init(
         &self,
@@ -522,4 +1214,49 @@ trait LanguageGenerator {
     ) -> std::result::Result<(), String> {
         Ok(())
     }
+
+    /// Called after all classes, enums and interfaces were generated
+    fn finish(&self, _type_map: &mut TypeMap) -> Result<Vec<TokenStream>> {
+        Ok(vec![])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_report_all_errors() {
+        let _ = env_logger::try_init();
+        let tmp_dir = tempfile::tempdir().expect("Can not create tmp directory");
+        let mut gen = Generator::new(LanguageConfig::JavaConfig(JavaConfig::new(
+            tmp_dir.path().into(),
+            "org.example".into(),
+        )))
+        .with_pointer_target_width(64);
+        let src_id = gen.src_reg.register(SourceCode {
+            id_of_code: "test_report_all_errors".into(),
+            code: r#"
+foreigner_class!(class Foo {
+    self_type Foo;
+    constructor Foo::default() -> Foo;
+    method Foo::f(&self, _: UnknownType1);
+});
+foreign_enum!(enum Bad { A = Bad::A = 1, B = Bad::B = 1, });
+foreigner_class!(class Boo {
+    self_type Boo;
+    constructor Boo::default() -> Boo;
+    method Boo::f(&self, _: UnknownType2);
+});
+"#
+            .into(),
+        });
+        let err = gen
+            .expand_str(src_id, tmp_dir.path().join("test.rs"))
+            .expect_err("should fail");
+        assert_eq!(3, err.errors_count());
+        let msg = err.to_string();
+        assert!(msg.contains("UnknownType1"), "no UnknownType1 in {}", msg);
+        assert!(msg.contains("UnknownType2"), "no UnknownType2 in {}", msg);
+    }
 }
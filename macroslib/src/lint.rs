@@ -0,0 +1,158 @@
+use rustc_hash::FxHashSet;
+use syn::spanned::Spanned;
+
+use crate::{
+    error::{invalid_src_id_span, DiagnosticError},
+    typemap::TypeMap,
+    types::{ForeignEnumInfo, ForeignerClassInfo, MethodAccess, MethodVariant},
+};
+
+/// Kind of suspicious, but legal code. By default such code is reported
+/// as warning, this can be changed via `allow_lint`, `warn_lint` and `deny_lint`
+/// of language config, for example `JavaConfig` or `CConfig`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Lint {
+    /// Private method of `foreigner_class!` without `foreigner_code`,
+    /// so nobody can call it
+    UnreachablePrivateMethod,
+    /// `foreign_enum!` does not cover all variants of Rust enum
+    /// defined in the same file
+    IncompleteForeignEnum,
+    /// `alias` is the same as name of method
+    RedundantAlias,
+    /// Conversion rule is replaced by rule from type map merged later
+    ShadowedTypemapRule,
+}
+
+impl Lint {
+    /// Name of lint, in the same style as names of rustc lints
+    pub fn name(self) -> &'static str {
+        match self {
+            Lint::UnreachablePrivateMethod => "unreachable_private_method",
+            Lint::IncompleteForeignEnum => "incomplete_foreign_enum",
+            Lint::RedundantAlias => "redundant_alias",
+            Lint::ShadowedTypemapRule => "shadowed_typemap_rule",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum LintLevel {
+    Allow,
+    Warn,
+    Deny,
+}
+
+impl LintLevel {
+    /// Note that explains why problem is reported in such way,
+    /// `explicit` is true if level was set by configuration
+    pub(crate) fn add_note(
+        self,
+        lint: Lint,
+        warning: DiagnosticError,
+        explicit: bool,
+    ) -> DiagnosticError {
+        let note = match self {
+            LintLevel::Allow | LintLevel::Warn if !explicit => {
+                format!("lint `{}` is on by default", lint.name())
+            }
+            LintLevel::Allow => format!("lint `{}` is allowed by configuration", lint.name()),
+            LintLevel::Warn => format!("lint `{}` is set to warn by configuration", lint.name()),
+            LintLevel::Deny => format!("lint `{}` is denied by configuration", lint.name()),
+        };
+        warning.add_span_note(invalid_src_id_span(), note)
+    }
+}
+
+pub(crate) fn check_foreigner_class(conv_map: &mut TypeMap, class: &ForeignerClassInfo) {
+    for method in &class.methods {
+        if method.access == MethodAccess::Private
+            && method.variant != MethodVariant::Constructor
+            && class.foreigner_code.is_empty()
+        {
+            conv_map.add_warning(
+                Lint::UnreachablePrivateMethod,
+                DiagnosticError::new(
+                    class.src_id,
+                    method.rust_id.span(),
+                    format!(
+                        "private method `{}` of class {} is never used, \
+                         there is no foreigner_code that can call it",
+                        method.short_name(),
+                        class.name
+                    ),
+                ),
+            );
+        }
+        if let Some(ref alias) = method.name_alias {
+            let rust_name = method
+                .rust_id
+                .segments
+                .last()
+                .map(|x| x.value().ident.clone());
+            if rust_name.as_ref() == Some(alias) {
+                conv_map.add_warning(
+                    Lint::RedundantAlias,
+                    DiagnosticError::new(
+                        class.src_id,
+                        alias.span(),
+                        format!("alias `{}` is the same as name of method", alias),
+                    ),
+                );
+            }
+        }
+    }
+}
+
+/// Compare `foreign_enum!` with Rust enum, if it is defined in the same file
+pub(crate) fn check_foreign_enum(
+    conv_map: &mut TypeMap,
+    fenum: &ForeignEnumInfo,
+    rust_enums: &[syn::ItemEnum],
+) {
+    let mut rust_enum_name = None;
+    let mut covered = FxHashSet::default();
+    for item in &fenum.items {
+        let segments = &item.rust_name.segments;
+        if segments.len() < 2 {
+            return;
+        }
+        let enum_name = &segments[segments.len() - 2].ident;
+        if *rust_enum_name.get_or_insert(enum_name) != enum_name {
+            return;
+        }
+        covered.insert(&segments[segments.len() - 1].ident);
+    }
+    let rust_enum_name = match rust_enum_name {
+        Some(x) => x,
+        None => return,
+    };
+    let rust_enum = match rust_enums.iter().find(|x| x.ident == *rust_enum_name) {
+        Some(x) => x,
+        None => return,
+    };
+    let missed: Vec<String> = rust_enum
+        .variants
+        .iter()
+        .filter(|v| !covered.contains(&v.ident))
+        .map(|v| format!("{}::{}", rust_enum_name, v.ident))
+        .collect();
+    if missed.is_empty() {
+        return;
+    }
+    conv_map.add_warning(
+        Lint::IncompleteForeignEnum,
+        DiagnosticError::new2(
+            fenum.src_id_span(),
+            format!(
+                "foreign_enum {} does not cover {}",
+                fenum.name,
+                missed.join(", ")
+            ),
+        )
+        .add_span_note(
+            (fenum.src_id, rust_enum.ident.span()),
+            format!("enum {} defined here", rust_enum_name),
+        ),
+    );
+}
@@ -0,0 +1,525 @@
+mod rust_code;
+
+use std::{fmt::Write, io::Write as IoWrite};
+
+use petgraph::Direction;
+use proc_macro2::TokenStream;
+use syn::{parse_quote, spanned::Spanned, Type};
+
+use crate::{
+    error::{panic_on_syn_error, DiagnosticError, Result, SourceIdSpan},
+    file_cache::FileWriteCache,
+    source_registry::SourceId,
+    typemap::{
+        ast::{
+            fn_arg_type, if_result_return_ok_err_types, if_ty_result_return_ok_type,
+            parse_ty_with_given_span_checked, DisplayToTokens,
+        },
+        ty::RustType,
+        ForeignMethodSignature, ForeignTypeInfo, FROM_VAR_TEMPLATE, TO_VAR_TEMPLATE,
+    },
+    types::{
+        ForeignEnumInfo, ForeignFlagsInfo, ForeignInterface, ForeignStructInfo, ForeignerClassInfo,
+        ForeignerMethod, MethodAccess, MethodVariant,
+    },
+    LanguageGenerator, NodeJsConfig, TypeMap,
+};
+
+pub(in crate::nodejs) struct NapiForeignMethodSignature {
+    pub(in crate::nodejs) output: ForeignTypeInfo,
+    pub(in crate::nodejs) input: Vec<ForeignTypeInfo>,
+}
+
+impl ForeignMethodSignature for NapiForeignMethodSignature {
+    type FI = ForeignTypeInfo;
+    fn output(&self) -> &ForeignTypeInfo {
+        &self.output
+    }
+    fn input(&self) -> &[ForeignTypeInfo] {
+        &self.input[..]
+    }
+}
+
+impl LanguageGenerator for NodeJsConfig {
+    fn register_class(&self, conv_map: &mut TypeMap, class: &ForeignerClassInfo) -> Result<()> {
+        class
+            .validate_class()
+            .map_err(|err| DiagnosticError::new(class.src_id, class.span(), &err))?;
+        if let Some(constructor_ret_type) = class.constructor_ret_type.as_ref() {
+            let this_type = if_ty_result_return_ok_type(constructor_ret_type)
+                .unwrap_or_else(|| constructor_ret_type.clone());
+            let this_type: RustType = conv_map.find_or_alloc_rust_type_that_implements(
+                &this_type,
+                "SwigForeignClass",
+                class.src_id,
+            );
+            let js_obj_ty = conv_map.find_or_alloc_rust_type_with_suffix(
+                &parse_type! { napi_value },
+                &this_type.normalized_name,
+                SourceId::none(),
+            );
+            conv_map.cache_rust_to_foreign_conv(
+                &this_type,
+                ForeignTypeInfo {
+                    correspoding_rust_type: js_obj_ty.clone(),
+                    name: class.name.to_string().into(),
+                },
+            )?;
+            conv_map.add_conversation_rule(
+                this_type.clone(),
+                js_obj_ty.clone(),
+                format!(
+                    r#"
+    let mut {to_var}: napi_value = swig_napi_wrap_object::<{this_type}>(env, {from_var});
+"#,
+                    to_var = TO_VAR_TEMPLATE,
+                    from_var = FROM_VAR_TEMPLATE,
+                    this_type = this_type.normalized_name,
+                )
+                .into(),
+            );
+            //foreigner_class passed by value, JavaScript object becomes closed
+            conv_map.add_conversation_rule(
+                js_obj_ty.clone(),
+                this_type.clone(),
+                format!(
+                    r#"
+    let mut {to_var}: {this_type} = swig_napi_try!(
+        swig_napi_take_object::<{this_type}>(env, {from_var}), {{function_ret_type}});
+"#,
+                    to_var = TO_VAR_TEMPLATE,
+                    from_var = FROM_VAR_TEMPLATE,
+                    this_type = this_type.normalized_name,
+                )
+                .into(),
+            );
+
+            let (this_type_for_method, _code_box_this) =
+                conv_map.convert_to_heap_pointer(&this_type, "this");
+            for &(ref_kind, ptr_kind) in &[("&", "*const"), ("&mut", "*mut")] {
+                let code = format!("{} {}", ref_kind, DisplayToTokens(&this_type_for_method.ty));
+                let gen_ty =
+                    parse_ty_with_given_span_checked(&code, this_type_for_method.ty.span());
+                let this_type_ref =
+                    conv_map.find_or_alloc_rust_type(&gen_ty, this_type_for_method.src_id);
+                conv_map.add_conversation_rule(
+                    js_obj_ty.clone(),
+                    this_type_ref,
+                    format!(
+                        r#"
+    let mut {to_var}: {ref_kind} {this_type_for_method} = unsafe {{
+        {ref_kind} *(swig_napi_try!(swig_napi_object_ptr::<{this_type}>(env, {from_var}), {{function_ret_type}})
+            as {ptr_kind} {this_type_for_method})
+    }};
+"#,
+                        to_var = TO_VAR_TEMPLATE,
+                        from_var = FROM_VAR_TEMPLATE,
+                        ref_kind = ref_kind,
+                        ptr_kind = ptr_kind,
+                        this_type = this_type.normalized_name,
+                        this_type_for_method = this_type_for_method.normalized_name,
+                    )
+                    .into(),
+                );
+            }
+        }
+
+        let _ = conv_map.find_or_alloc_rust_type(&class.self_type_as_ty(), class.src_id);
+        Ok(())
+    }
+
+    fn generate(
+        &self,
+        conv_map: &mut TypeMap,
+        _: usize,
+        class: &ForeignerClassInfo,
+    ) -> Result<Vec<TokenStream>> {
+        let f_methods = find_suitable_foreign_types_for_methods(conv_map, class)?;
+        let (code, init_func) = rust_code::generate_class(conv_map, class, &f_methods)?;
+        self.module_init.borrow_mut().push(init_func);
+        generate_class_typings(&mut self.typings.borrow_mut(), class, &f_methods);
+        Ok(code)
+    }
+
+    fn generate_enum(
+        &self,
+        conv_map: &mut TypeMap,
+        pointer_target_width: usize,
+        enum_info: &ForeignEnumInfo,
+    ) -> Result<Vec<TokenStream>> {
+        if enum_info.is_data_carrying() {
+            return Err(DiagnosticError::new(
+                enum_info.src_id,
+                enum_info.span(),
+                "Node.js: foreign_enum with data-carrying items is not supported yet",
+            ));
+        }
+        let (code, init_func) =
+            rust_code::generate_enum(conv_map, pointer_target_width, enum_info)?;
+        self.module_init.borrow_mut().push(init_func);
+
+        let mut typings = self.typings.borrow_mut();
+        typings.push('\n');
+        typings.push_str(&doc_comments_to_jsdoc(&enum_info.doc_comments, ""));
+        writeln!(&mut typings, "export declare enum {} {{", enum_info.name).unwrap();
+        for item in &enum_info.items {
+            typings.push_str(&doc_comments_to_jsdoc(&item.doc_comments, "    "));
+            writeln!(&mut typings, "    {} = {},", item.name, item.value).unwrap();
+        }
+        typings.push_str("}\n");
+        Ok(code)
+    }
+
+    fn generate_struct(
+        &self,
+        _conv_map: &mut TypeMap,
+        _pointer_target_width: usize,
+        struct_info: &ForeignStructInfo,
+    ) -> Result<Vec<TokenStream>> {
+        Err(DiagnosticError::new(
+            struct_info.src_id,
+            struct_info.span(),
+            "Node.js: foreign_struct is not supported yet",
+        ))
+    }
+
+    fn generate_flags(
+        &self,
+        _conv_map: &mut TypeMap,
+        _pointer_target_width: usize,
+        flags_info: &ForeignFlagsInfo,
+    ) -> Result<Vec<TokenStream>> {
+        Err(DiagnosticError::new(
+            flags_info.src_id,
+            flags_info.span(),
+            "Node.js: foreign_flags is not supported yet",
+        ))
+    }
+
+    fn generate_interface(
+        &self,
+        conv_map: &mut TypeMap,
+        pointer_target_width: usize,
+        interface: &ForeignInterface,
+    ) -> Result<Vec<TokenStream>> {
+        let f_methods = find_suitable_foreign_types_for_interface_methods(conv_map, interface)?;
+        let code =
+            rust_code::generate_interface(conv_map, pointer_target_width, interface, &f_methods)?;
+
+        let mut typings = self.typings.borrow_mut();
+        typings.push('\n');
+        typings.push_str(&doc_comments_to_jsdoc(&interface.doc_comments, ""));
+        writeln!(&mut typings, "export interface {} {{", interface.name).unwrap();
+        for (method, f_method) in interface.items.iter().zip(&f_methods) {
+            typings.push_str(&doc_comments_to_jsdoc(&method.doc_comments, "    "));
+            writeln!(
+                &mut typings,
+                "    {}({}): {};",
+                method.name,
+                typings_args(&f_method.input),
+                f_method.output.name
+            )
+            .unwrap();
+        }
+        typings.push_str("}\n");
+        Ok(code)
+    }
+
+    fn finish(&self, _conv_map: &mut TypeMap) -> Result<Vec<TokenStream>> {
+        let mut typings_file = format!(
+            r#"// Automaticaly generated by rust_swig
+{typings}"#,
+            typings = self.typings.borrow()
+        );
+        if !typings_file.ends_with('\n') {
+            typings_file.push('\n');
+        }
+        let path = self.output_dir.join(format!("{}.d.ts", self.module_name));
+        let mut file = FileWriteCache::new(&path);
+        file.write_all(typings_file.as_bytes())
+            .and_then(|_| file.update_file_if_necessary())
+            .map_err(|err| {
+                DiagnosticError::new_without_src_info(format!(
+                    "write to {} failed: {}",
+                    path.display(),
+                    err
+                ))
+            })?;
+
+        let module_init = self.module_init.borrow();
+        let init_items = if module_init.is_empty() {
+            String::new()
+        } else {
+            let mut cond = String::new();
+            for (i, init_func) in module_init.iter().enumerate() {
+                if i > 0 {
+                    cond.push_str(" || ");
+                }
+                write!(&mut cond, "!{}(env, exports)", init_func).unwrap();
+            }
+            format!(
+                r#"
+    if {cond} {{
+        return ::std::ptr::null_mut();
+    }}"#,
+                cond = cond
+            )
+        };
+        let code = format!(
+            r#"
+#[no_mangle]
+pub extern "C" fn napi_register_module_v1(env: napi_env, exports: napi_value) -> napi_value {{{init_items}
+    exports
+}}
+"#,
+            init_items = init_items,
+        );
+        Ok(vec![syn::parse_str(&code).unwrap_or_else(|err| {
+            panic_on_syn_error("nodejs internal module init", code, err)
+        })])
+    }
+}
+
+pub(in crate::nodejs) fn calc_this_type_for_method(
+    tm: &TypeMap,
+    class: &ForeignerClassInfo,
+) -> Option<Type> {
+    class
+        .constructor_ret_type
+        .as_ref()
+        .map(|constructor_ret_type| {
+            let ret_ty = tm
+                .ty_to_rust_type_checked(constructor_ret_type)
+                .unwrap_or_else(|| {
+                    panic!(
+                        "Internal error: constructor type {} for class {} unknown",
+                        DisplayToTokens(constructor_ret_type),
+                        class.name
+                    );
+                });
+            if_result_return_ok_err_types(&ret_ty)
+                .map(|(ok_ty, _err_ty)| ok_ty)
+                .unwrap_or_else(|| constructor_ret_type.clone())
+        })
+}
+
+fn map_type(
+    conv_map: &mut TypeMap,
+    arg_ty: &RustType,
+    direction: Direction,
+    arg_ty_span: SourceIdSpan,
+) -> Result<ForeignTypeInfo> {
+    let ftype = conv_map
+        .map_through_conversation_to_foreign(
+            arg_ty,
+            direction,
+            arg_ty_span,
+            calc_this_type_for_method,
+        )
+        .ok_or_else(|| {
+            let err = DiagnosticError::new2(
+                arg_ty_span,
+                format!(
+                    "can not find conversation JavaScript type {} Rust type '{}'",
+                    match direction {
+                        Direction::Outgoing => "=>",
+                        Direction::Incoming => "<=",
+                    },
+                    arg_ty,
+                ),
+            );
+            conv_map.explain_missing_foreign_type(err, arg_ty, direction)
+        })?;
+    let ftype = &conv_map[ftype];
+    let rule = match direction {
+        Direction::Outgoing => ftype.into_from_rust.as_ref(),
+        Direction::Incoming => ftype.from_into_rust.as_ref(),
+    }
+    .expect("Internal error: rule for foreign type not defined");
+    if rule.intermediate.is_some() {
+        return Err(DiagnosticError::new2(
+            arg_ty_span,
+            format!(
+                "Node.js: conversation of '{}' via intermediate type is not supported yet",
+                arg_ty
+            ),
+        ));
+    }
+    let name = ftype.name.typename.clone();
+    Ok(ForeignTypeInfo {
+        name,
+        correspoding_rust_type: conv_map[rule.rust_ty].clone(),
+    })
+}
+
+fn void_foreign_type(conv_map: &mut TypeMap) -> ForeignTypeInfo {
+    let dummy_ty = parse_type! { () };
+    ForeignTypeInfo {
+        name: "void".into(),
+        correspoding_rust_type: conv_map.find_or_alloc_rust_type_no_src_id(&dummy_ty),
+    }
+}
+
+fn find_suitable_foreign_types_for_methods(
+    conv_map: &mut TypeMap,
+    class: &ForeignerClassInfo,
+) -> Result<Vec<NapiForeignMethodSignature>> {
+    let mut ret = Vec::with_capacity(class.methods.len());
+    for method in &class.methods {
+        //skip self argument
+        let skip_n = match method.variant {
+            MethodVariant::Method(_) => 1,
+            _ => 0,
+        };
+        let mut input = Vec::with_capacity(method.fn_decl.inputs.len() - skip_n);
+        for arg in method.fn_decl.inputs.iter().skip(skip_n) {
+            let arg_rust_ty = conv_map.find_or_alloc_rust_type(fn_arg_type(arg), class.src_id);
+            input.push(map_type(
+                conv_map,
+                &arg_rust_ty,
+                Direction::Incoming,
+                (class.src_id, fn_arg_type(arg).span()),
+            )?);
+        }
+        let output = match (method.variant, &method.fn_decl.output) {
+            (MethodVariant::Constructor, _) | (_, syn::ReturnType::Default) => {
+                void_foreign_type(conv_map)
+            }
+            (_, syn::ReturnType::Type(_, ref rt)) => {
+                //Err(_) throws JavaScript `Error`, so map only `T` of `Result<T, E>`
+                let ret_ty = if_ty_result_return_ok_type(rt).unwrap_or_else(|| (**rt).clone());
+                let ret_rust_ty = conv_map.find_or_alloc_rust_type(&ret_ty, class.src_id);
+                map_type(
+                    conv_map,
+                    &ret_rust_ty,
+                    Direction::Outgoing,
+                    (class.src_id, rt.span()),
+                )?
+            }
+        };
+        ret.push(NapiForeignMethodSignature { output, input });
+    }
+    Ok(ret)
+}
+
+fn find_suitable_foreign_types_for_interface_methods(
+    conv_map: &mut TypeMap,
+    interface: &ForeignInterface,
+) -> Result<Vec<NapiForeignMethodSignature>> {
+    let mut ret = Vec::with_capacity(interface.items.len());
+    for method in &interface.items {
+        let mut input = Vec::with_capacity(method.fn_decl.inputs.len() - 1);
+        for arg in method.fn_decl.inputs.iter().skip(1) {
+            let arg_rust_ty = conv_map.find_or_alloc_rust_type(fn_arg_type(arg), interface.src_id);
+            input.push(map_type(
+                conv_map,
+                &arg_rust_ty,
+                Direction::Outgoing,
+                (interface.src_id, fn_arg_type(arg).span()),
+            )?);
+        }
+        let output = match method.fn_decl.output {
+            syn::ReturnType::Default => void_foreign_type(conv_map),
+            syn::ReturnType::Type(_, ref rt) => {
+                let ret_rust_ty = conv_map.find_or_alloc_rust_type(rt, interface.src_id);
+                map_type(
+                    conv_map,
+                    &ret_rust_ty,
+                    Direction::Incoming,
+                    (interface.src_id, rt.span()),
+                )?
+            }
+        };
+        ret.push(NapiForeignMethodSignature { output, input });
+    }
+    Ok(ret)
+}
+
+/// Name of method visible from JavaScript
+pub(in crate::nodejs) fn method_name(method: &ForeignerMethod) -> String {
+    match method.access {
+        MethodAccess::Public => method.short_name(),
+        MethodAccess::Private | MethodAccess::Protected => format!("_{}", method.short_name()),
+    }
+}
+
+fn typings_args(input: &[ForeignTypeInfo]) -> String {
+    input
+        .iter()
+        .enumerate()
+        .map(|(i, arg)| format!("a{}: {}", i, arg.name))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn generate_class_typings(
+    typings: &mut String,
+    class: &ForeignerClassInfo,
+    f_methods: &[NapiForeignMethodSignature],
+) {
+    typings.push('\n');
+    typings.push_str(&doc_comments_to_jsdoc(&class.doc_comments, ""));
+    writeln!(typings, "export declare class {} {{", class.name).unwrap();
+    let mut has_constructor = false;
+    for (method, f_method) in class.methods.iter().zip(f_methods) {
+        if method.is_dummy_constructor() {
+            continue;
+        }
+        typings.push_str(&doc_comments_to_jsdoc(&method.doc_comments, "    "));
+        let args = typings_args(&f_method.input);
+        match method.variant {
+            MethodVariant::Constructor => {
+                has_constructor = true;
+                writeln!(typings, "    constructor({});", args).unwrap();
+            }
+            MethodVariant::StaticMethod => {
+                writeln!(
+                    typings,
+                    "    static {}({}): {};",
+                    method_name(method),
+                    args,
+                    f_method.output.name
+                )
+                .unwrap();
+            }
+            MethodVariant::Method(_) => {
+                writeln!(
+                    typings,
+                    "    {}({}): {};",
+                    method_name(method),
+                    args,
+                    f_method.output.name
+                )
+                .unwrap();
+            }
+        }
+    }
+    if !has_constructor {
+        typings.push_str("    private constructor();\n");
+    }
+    if class.self_type.is_some() {
+        typings.push_str(
+            r#"    /** Free Rust object, after that any usage of object throws `Error` */
+    close(): void;
+"#,
+        );
+    }
+    typings.push_str("}\n");
+}
+
+fn doc_comments_to_jsdoc(doc_comments: &[String], indent: &str) -> String {
+    if doc_comments.is_empty() {
+        return String::new();
+    }
+    let mut jsdoc = format!("{}/**\n", indent);
+    for comment in doc_comments {
+        let comment = comment.trim();
+        if comment.is_empty() {
+            writeln!(&mut jsdoc, "{} *", indent).unwrap();
+        } else {
+            writeln!(&mut jsdoc, "{} * {}", indent, comment).unwrap();
+        }
+    }
+    writeln!(&mut jsdoc, "{} */", indent).unwrap();
+    jsdoc
+}
@@ -0,0 +1,1379 @@
+mod swig_foreign_types_map {
+    #![swig_foreigner_type = "void"]
+    #![swig_rust_type = "()"]
+    #![swig_foreigner_type = "boolean"]
+    #![swig_rust_type = "NapiBoolean"]
+    #![swig_foreigner_type = "number"]
+    #![swig_rust_type = "NapiNumber"]
+    #![swig_foreigner_type = "string"]
+    #![swig_rust_type = "NapiString"]
+    #![swig_foreigner_type = "boolean | null"]
+    #![swig_rust_type = "NapiOptBoolean"]
+    #![swig_foreigner_type = "number | null"]
+    #![swig_rust_type = "NapiOptNumber"]
+    #![swig_foreigner_type = "string | null"]
+    #![swig_rust_type = "NapiOptString"]
+    #![swig_foreigner_type = "number[]"]
+    #![swig_rust_type = "NapiNumberArray"]
+    #![swig_foreigner_type = "string[]"]
+    #![swig_rust_type = "NapiStringArray"]
+}
+
+#[allow(unused_macros)]
+macro_rules! swig_c_str {
+    ($lit:expr) => {
+        concat!($lit, "\0").as_ptr() as *const ::std::os::raw::c_char
+    };
+}
+
+/// Part of Node-API, see `js_native_api_types.h` and `js_native_api.h`,
+/// Node.js exports these functions from executable
+#[allow(non_camel_case_types)]
+pub enum napi_env__ {}
+#[allow(non_camel_case_types)]
+pub enum napi_value__ {}
+#[allow(non_camel_case_types)]
+pub enum napi_ref__ {}
+#[allow(non_camel_case_types)]
+pub enum napi_callback_info__ {}
+
+#[allow(non_camel_case_types)]
+pub type napi_env = *mut napi_env__;
+#[allow(non_camel_case_types)]
+pub type napi_value = *mut napi_value__;
+#[allow(non_camel_case_types)]
+pub type napi_ref = *mut napi_ref__;
+#[allow(non_camel_case_types)]
+pub type napi_callback_info = *mut napi_callback_info__;
+#[allow(non_camel_case_types)]
+pub type napi_status = ::std::os::raw::c_int;
+#[allow(non_camel_case_types)]
+pub type napi_valuetype = ::std::os::raw::c_int;
+#[allow(non_camel_case_types)]
+pub type napi_callback = Option<unsafe extern "C" fn(napi_env, napi_callback_info) -> napi_value>;
+#[allow(non_camel_case_types)]
+pub type napi_finalize =
+    Option<unsafe extern "C" fn(napi_env, *mut ::std::os::raw::c_void, *mut ::std::os::raw::c_void)>;
+
+#[allow(dead_code)]
+type NapiBoolean = napi_value;
+#[allow(dead_code)]
+type NapiNumber = napi_value;
+#[allow(dead_code)]
+type NapiString = napi_value;
+#[allow(dead_code)]
+type NapiOptBoolean = napi_value;
+#[allow(dead_code)]
+type NapiOptNumber = napi_value;
+#[allow(dead_code)]
+type NapiOptString = napi_value;
+#[allow(dead_code)]
+type NapiNumberArray = napi_value;
+#[allow(dead_code)]
+type NapiStringArray = napi_value;
+
+#[allow(non_camel_case_types, dead_code)]
+#[repr(C)]
+pub struct napi_property_descriptor {
+    utf8name: *const ::std::os::raw::c_char,
+    name: napi_value,
+    method: napi_callback,
+    getter: napi_callback,
+    setter: napi_callback,
+    value: napi_value,
+    attributes: ::std::os::raw::c_int,
+    data: *mut ::std::os::raw::c_void,
+}
+
+#[allow(dead_code, non_upper_case_globals)]
+const napi_ok: napi_status = 0;
+#[allow(dead_code, non_upper_case_globals)]
+const napi_null: napi_valuetype = 1;
+#[allow(dead_code, non_upper_case_globals)]
+const napi_undefined: napi_valuetype = 0;
+#[allow(dead_code, non_upper_case_globals)]
+const napi_function: napi_valuetype = 7;
+#[allow(dead_code, non_upper_case_globals)]
+const napi_default: ::std::os::raw::c_int = 0;
+#[allow(dead_code, non_upper_case_globals)]
+const napi_enumerable: ::std::os::raw::c_int = 1 << 1;
+#[allow(dead_code, non_upper_case_globals)]
+const napi_static: ::std::os::raw::c_int = 1 << 10;
+
+#[allow(dead_code)]
+extern "C" {
+    fn napi_define_class(
+        env: napi_env,
+        utf8name: *const ::std::os::raw::c_char,
+        length: usize,
+        constructor: napi_callback,
+        data: *mut ::std::os::raw::c_void,
+        property_count: usize,
+        properties: *const napi_property_descriptor,
+        result: *mut napi_value,
+    ) -> napi_status;
+    fn napi_wrap(
+        env: napi_env,
+        js_object: napi_value,
+        native_object: *mut ::std::os::raw::c_void,
+        finalize_cb: napi_finalize,
+        finalize_hint: *mut ::std::os::raw::c_void,
+        result: *mut napi_ref,
+    ) -> napi_status;
+    fn napi_unwrap(
+        env: napi_env,
+        js_object: napi_value,
+        result: *mut *mut ::std::os::raw::c_void,
+    ) -> napi_status;
+    fn napi_remove_wrap(
+        env: napi_env,
+        js_object: napi_value,
+        result: *mut *mut ::std::os::raw::c_void,
+    ) -> napi_status;
+    fn napi_get_cb_info(
+        env: napi_env,
+        cbinfo: napi_callback_info,
+        argc: *mut usize,
+        argv: *mut napi_value,
+        this_arg: *mut napi_value,
+        data: *mut *mut ::std::os::raw::c_void,
+    ) -> napi_status;
+    fn napi_get_new_target(
+        env: napi_env,
+        cbinfo: napi_callback_info,
+        result: *mut napi_value,
+    ) -> napi_status;
+    fn napi_new_instance(
+        env: napi_env,
+        constructor: napi_value,
+        argc: usize,
+        argv: *const napi_value,
+        result: *mut napi_value,
+    ) -> napi_status;
+    fn napi_instanceof(
+        env: napi_env,
+        object: napi_value,
+        constructor: napi_value,
+        result: *mut bool,
+    ) -> napi_status;
+    fn napi_create_reference(
+        env: napi_env,
+        value: napi_value,
+        initial_refcount: u32,
+        result: *mut napi_ref,
+    ) -> napi_status;
+    fn napi_delete_reference(env: napi_env, reference: napi_ref) -> napi_status;
+    fn napi_get_reference_value(
+        env: napi_env,
+        reference: napi_ref,
+        result: *mut napi_value,
+    ) -> napi_status;
+    fn napi_create_object(env: napi_env, result: *mut napi_value) -> napi_status;
+    fn napi_set_named_property(
+        env: napi_env,
+        object: napi_value,
+        utf8name: *const ::std::os::raw::c_char,
+        value: napi_value,
+    ) -> napi_status;
+    fn napi_get_named_property(
+        env: napi_env,
+        object: napi_value,
+        utf8name: *const ::std::os::raw::c_char,
+        result: *mut napi_value,
+    ) -> napi_status;
+    fn napi_object_freeze(env: napi_env, object: napi_value) -> napi_status;
+    fn napi_get_undefined(env: napi_env, result: *mut napi_value) -> napi_status;
+    fn napi_get_null(env: napi_env, result: *mut napi_value) -> napi_status;
+    fn napi_typeof(env: napi_env, value: napi_value, result: *mut napi_valuetype) -> napi_status;
+    fn napi_get_boolean(env: napi_env, value: bool, result: *mut napi_value) -> napi_status;
+    fn napi_get_value_bool(env: napi_env, value: napi_value, result: *mut bool) -> napi_status;
+    fn napi_create_double(env: napi_env, value: f64, result: *mut napi_value) -> napi_status;
+    fn napi_get_value_double(env: napi_env, value: napi_value, result: *mut f64) -> napi_status;
+    fn napi_create_string_utf8(
+        env: napi_env,
+        s: *const ::std::os::raw::c_char,
+        length: usize,
+        result: *mut napi_value,
+    ) -> napi_status;
+    fn napi_get_value_string_utf8(
+        env: napi_env,
+        value: napi_value,
+        buf: *mut ::std::os::raw::c_char,
+        bufsize: usize,
+        result: *mut usize,
+    ) -> napi_status;
+    fn napi_create_array_with_length(
+        env: napi_env,
+        length: usize,
+        result: *mut napi_value,
+    ) -> napi_status;
+    fn napi_is_array(env: napi_env, value: napi_value, result: *mut bool) -> napi_status;
+    fn napi_get_array_length(env: napi_env, value: napi_value, result: *mut u32) -> napi_status;
+    fn napi_get_element(
+        env: napi_env,
+        object: napi_value,
+        index: u32,
+        result: *mut napi_value,
+    ) -> napi_status;
+    fn napi_set_element(
+        env: napi_env,
+        object: napi_value,
+        index: u32,
+        value: napi_value,
+    ) -> napi_status;
+    fn napi_call_function(
+        env: napi_env,
+        recv: napi_value,
+        func: napi_value,
+        argc: usize,
+        argv: *const napi_value,
+        result: *mut napi_value,
+    ) -> napi_status;
+    fn napi_is_exception_pending(env: napi_env, result: *mut bool) -> napi_status;
+    fn napi_throw_error(
+        env: napi_env,
+        code: *const ::std::os::raw::c_char,
+        msg: *const ::std::os::raw::c_char,
+    ) -> napi_status;
+    fn napi_throw_type_error(
+        env: napi_env,
+        code: *const ::std::os::raw::c_char,
+        msg: *const ::std::os::raw::c_char,
+    ) -> napi_status;
+    fn napi_throw_range_error(
+        env: napi_env,
+        code: *const ::std::os::raw::c_char,
+        msg: *const ::std::os::raw::c_char,
+    ) -> napi_status;
+}
+
+#[allow(dead_code)]
+pub trait SwigForeignClass {
+    fn napi_class_name() -> &'static str;
+    fn napi_constructor() -> napi_ref;
+    fn box_object(x: Self) -> *mut ::std::os::raw::c_void;
+    fn unbox_object(p: *mut ::std::os::raw::c_void) -> Self;
+}
+
+#[allow(dead_code)]
+trait SwigNapiInvalidValue {
+    fn swig_napi_invalid_value() -> Self;
+}
+
+impl SwigNapiInvalidValue for napi_value {
+    fn swig_napi_invalid_value() -> Self {
+        ::std::ptr::null_mut()
+    }
+}
+
+/// Used for values returned from JavaScript callbacks
+impl<T> SwigNapiInvalidValue for Option<T> {
+    fn swig_napi_invalid_value() -> Self {
+        None
+    }
+}
+
+/// Return from function with invalid value if conversation failed,
+/// JavaScript exception should be already thrown
+#[allow(unused_macros)]
+macro_rules! swig_napi_try {
+    ($value:expr, $func_ret_type:ty) => {
+        match $value {
+            Some(x) => x,
+            None => return <$func_ret_type>::swig_napi_invalid_value(),
+        }
+    };
+}
+
+#[allow(dead_code)]
+#[swig_code = "let mut {to_var}: {to_var_type} = swig_napi_try!({from_var}.swig_into(env), {function_ret_type});"]
+trait SwigInto<T> {
+    /// `None` means that JavaScript exception was thrown
+    fn swig_into(self, env: napi_env) -> Option<T>;
+}
+
+#[allow(dead_code)]
+#[swig_code = "let mut {to_var}: {to_var_type} = <{to_var_type}>::swig_from({from_var}, env);"]
+trait SwigFrom<T> {
+    fn swig_from(_: T, env: napi_env) -> Self;
+}
+
+#[allow(dead_code)]
+#[swig_code = "let mut {to_var}: {to_var_type} = {from_var}.swig_deref();"]
+trait SwigDeref {
+    type Target: ?Sized;
+    fn swig_deref(&self) -> &Self::Target;
+}
+
+#[allow(dead_code)]
+#[swig_code = "let mut {to_var}: {to_var_type} = {from_var}.swig_deref_mut();"]
+trait SwigDerefMut {
+    type Target: ?Sized;
+    fn swig_deref_mut(&mut self) -> &mut Self::Target;
+}
+
+#[allow(dead_code)]
+fn swig_napi_c_string(msg: &str) -> ::std::ffi::CString {
+    ::std::ffi::CString::new(msg.replace('\0', "\\0")).unwrap()
+}
+
+#[allow(dead_code)]
+fn swig_napi_throw_error(env: napi_env, msg: &str) {
+    let msg = swig_napi_c_string(msg);
+    unsafe { napi_throw_error(env, ::std::ptr::null(), msg.as_ptr()) };
+}
+
+#[allow(dead_code)]
+fn swig_napi_throw_type_error(env: napi_env, msg: &str) {
+    let msg = swig_napi_c_string(msg);
+    unsafe { napi_throw_type_error(env, ::std::ptr::null(), msg.as_ptr()) };
+}
+
+#[allow(dead_code)]
+fn swig_napi_throw_range_error(env: napi_env, msg: &str) {
+    let msg = swig_napi_c_string(msg);
+    unsafe { napi_throw_range_error(env, ::std::ptr::null(), msg.as_ptr()) };
+}
+
+/// Check status of N-API call, if it failed and there is no pending
+/// exception, throw `TypeError` with `msg`
+#[allow(dead_code)]
+fn swig_napi_check(env: napi_env, status: napi_status, msg: &str) -> Option<()> {
+    if status == napi_ok {
+        return Some(());
+    }
+    let mut pending = false;
+    unsafe { napi_is_exception_pending(env, &mut pending) };
+    if !pending {
+        swig_napi_throw_type_error(env, msg);
+    }
+    None
+}
+
+#[allow(dead_code)]
+fn swig_napi_undefined(env: napi_env) -> napi_value {
+    let mut ret: napi_value = ::std::ptr::null_mut();
+    unsafe { napi_get_undefined(env, &mut ret) };
+    ret
+}
+
+#[allow(dead_code)]
+fn swig_napi_null(env: napi_env) -> napi_value {
+    let mut ret: napi_value = ::std::ptr::null_mut();
+    unsafe { napi_get_null(env, &mut ret) };
+    ret
+}
+
+/// `null` and `undefined` are both mapped to `None`
+#[allow(dead_code)]
+fn swig_napi_is_null(env: napi_env, x: napi_value) -> bool {
+    let mut ty: napi_valuetype = napi_undefined;
+    unsafe { napi_typeof(env, x, &mut ty) };
+    ty == napi_null || ty == napi_undefined
+}
+
+/// Get `this` and exactly `n` arguments of function call
+#[allow(dead_code)]
+fn swig_napi_get_args(
+    env: napi_env,
+    info: napi_callback_info,
+    n: usize,
+    func_name: &str,
+) -> Option<(napi_value, Vec<napi_value>)> {
+    let mut argc: usize = n;
+    let mut argv: Vec<napi_value> = vec![::std::ptr::null_mut(); n];
+    let mut this: napi_value = ::std::ptr::null_mut();
+    let status = unsafe {
+        napi_get_cb_info(
+            env,
+            info,
+            &mut argc,
+            argv.as_mut_ptr(),
+            &mut this,
+            ::std::ptr::null_mut(),
+        )
+    };
+    swig_napi_check(env, status, "napi_get_cb_info failed")?;
+    if argc != n {
+        swig_napi_throw_type_error(
+            env,
+            &format!(
+                "{}() takes {} arguments but {} were given",
+                func_name, n, argc
+            ),
+        );
+        return None;
+    }
+    Some((this, argv))
+}
+
+/// `this` of function call
+#[allow(dead_code)]
+fn swig_napi_this(env: napi_env, info: napi_callback_info) -> napi_value {
+    let mut argc: usize = 0;
+    let mut this: napi_value = ::std::ptr::null_mut();
+    unsafe {
+        napi_get_cb_info(
+            env,
+            info,
+            &mut argc,
+            ::std::ptr::null_mut(),
+            &mut this,
+            ::std::ptr::null_mut(),
+        )
+    };
+    this
+}
+
+/// Is function called with `new`
+#[allow(dead_code)]
+fn swig_napi_is_construct_call(env: napi_env, info: napi_callback_info) -> bool {
+    let mut new_target: napi_value = ::std::ptr::null_mut();
+    let status = unsafe { napi_get_new_target(env, info, &mut new_target) };
+    status == napi_ok && !new_target.is_null()
+}
+
+/// Number of arguments of function call
+#[allow(dead_code)]
+fn swig_napi_args_count(env: napi_env, info: napi_callback_info) -> usize {
+    let mut argc: usize = 0;
+    unsafe {
+        napi_get_cb_info(
+            env,
+            info,
+            &mut argc,
+            ::std::ptr::null_mut(),
+            ::std::ptr::null_mut(),
+            ::std::ptr::null_mut(),
+        )
+    };
+    argc
+}
+
+#[allow(dead_code)]
+fn swig_napi_str_from(env: napi_env, x: &str) -> napi_value {
+    let mut ret: napi_value = ::std::ptr::null_mut();
+    unsafe {
+        napi_create_string_utf8(
+            env,
+            x.as_ptr() as *const ::std::os::raw::c_char,
+            x.len(),
+            &mut ret,
+        )
+    };
+    ret
+}
+
+#[allow(dead_code)]
+fn swig_napi_str_as(env: napi_env, x: napi_value) -> Option<String> {
+    let mut len: usize = 0;
+    let status =
+        unsafe { napi_get_value_string_utf8(env, x, ::std::ptr::null_mut(), 0, &mut len) };
+    swig_napi_check(env, status, "expected string")?;
+    let mut buf: Vec<u8> = vec![0; len + 1];
+    let status = unsafe {
+        napi_get_value_string_utf8(
+            env,
+            x,
+            buf.as_mut_ptr() as *mut ::std::os::raw::c_char,
+            len + 1,
+            &mut len,
+        )
+    };
+    swig_napi_check(env, status, "expected string")?;
+    buf.truncate(len);
+    Some(unsafe { String::from_utf8_unchecked(buf) })
+}
+
+#[allow(dead_code)]
+fn swig_napi_double_as(env: napi_env, x: napi_value) -> Option<f64> {
+    let mut ret: f64 = 0.;
+    let status = unsafe { napi_get_value_double(env, x, &mut ret) };
+    swig_napi_check(env, status, "expected number")?;
+    Some(ret)
+}
+
+#[allow(dead_code)]
+fn swig_napi_int_as<T: ::std::convert::TryFrom<i64>>(env: napi_env, x: napi_value) -> Option<T> {
+    let v = swig_napi_double_as(env, x)?;
+    if v.fract() != 0. || v < (i64::min_value() as f64) || v >= (i64::max_value() as f64) {
+        swig_napi_throw_range_error(env, &format!("{} is not integer", v));
+        return None;
+    }
+    match <T as ::std::convert::TryFrom<i64>>::try_from(v as i64) {
+        Ok(v) => Some(v),
+        Err(_) => {
+            swig_napi_throw_range_error(env, &format!("{} out of range of Rust type", v));
+            None
+        }
+    }
+}
+
+#[allow(dead_code)]
+fn swig_napi_double_from(env: napi_env, x: f64) -> napi_value {
+    let mut ret: napi_value = ::std::ptr::null_mut();
+    unsafe { napi_create_double(env, x, &mut ret) };
+    ret
+}
+
+#[allow(dead_code)]
+fn swig_napi_bool_as(env: napi_env, x: napi_value) -> Option<bool> {
+    let mut ret = false;
+    let status = unsafe { napi_get_value_bool(env, x, &mut ret) };
+    swig_napi_check(env, status, "expected boolean")?;
+    Some(ret)
+}
+
+#[allow(dead_code)]
+fn swig_napi_bool_from(env: napi_env, x: bool) -> napi_value {
+    let mut ret: napi_value = ::std::ptr::null_mut();
+    unsafe { napi_get_boolean(env, x, &mut ret) };
+    ret
+}
+
+#[allow(dead_code)]
+fn swig_napi_array_from<T, F: Fn(napi_env, T) -> napi_value>(
+    env: napi_env,
+    x: Vec<T>,
+    conv: F,
+) -> napi_value {
+    let mut arr: napi_value = ::std::ptr::null_mut();
+    unsafe { napi_create_array_with_length(env, x.len(), &mut arr) };
+    for (i, elem) in x.into_iter().enumerate() {
+        let elem = conv(env, elem);
+        unsafe { napi_set_element(env, arr, i as u32, elem) };
+    }
+    arr
+}
+
+#[allow(dead_code)]
+fn swig_napi_array_as<T, F: Fn(napi_env, napi_value) -> Option<T>>(
+    env: napi_env,
+    x: napi_value,
+    conv: F,
+) -> Option<Vec<T>> {
+    let mut is_array = false;
+    unsafe { napi_is_array(env, x, &mut is_array) };
+    if !is_array {
+        swig_napi_throw_type_error(env, "expected array");
+        return None;
+    }
+    let mut len: u32 = 0;
+    let status = unsafe { napi_get_array_length(env, x, &mut len) };
+    swig_napi_check(env, status, "expected array")?;
+    let mut ret = Vec::with_capacity(len as usize);
+    for i in 0..len {
+        let mut elem: napi_value = ::std::ptr::null_mut();
+        let status = unsafe { napi_get_element(env, x, i, &mut elem) };
+        swig_napi_check(env, status, "napi_get_element failed")?;
+        ret.push(conv(env, elem)?);
+    }
+    Some(ret)
+}
+
+#[allow(dead_code)]
+fn swig_napi_opt_from<T, F: Fn(napi_env, T) -> napi_value>(
+    env: napi_env,
+    x: Option<T>,
+    conv: F,
+) -> napi_value {
+    match x {
+        Some(x) => conv(env, x),
+        None => swig_napi_null(env),
+    }
+}
+
+#[allow(dead_code)]
+fn swig_napi_opt_as<T, F: Fn(napi_env, napi_value) -> Option<T>>(
+    env: napi_env,
+    x: napi_value,
+    conv: F,
+) -> Option<Option<T>> {
+    if swig_napi_is_null(env, x) {
+        Some(None)
+    } else {
+        conv(env, x).map(Some)
+    }
+}
+
+/// Pointer to Rust object inside JavaScript object of class `T`
+#[allow(dead_code)]
+fn swig_napi_object_ptr<T: SwigForeignClass>(
+    env: napi_env,
+    obj: napi_value,
+) -> Option<*mut ::std::os::raw::c_void> {
+    let mut constructor: napi_value = ::std::ptr::null_mut();
+    let mut is_instance = false;
+    unsafe {
+        napi_get_reference_value(env, T::napi_constructor(), &mut constructor);
+        napi_instanceof(env, obj, constructor, &mut is_instance);
+    }
+    if !is_instance {
+        swig_napi_throw_type_error(env, &format!("expected {} instance", T::napi_class_name()));
+        return None;
+    }
+    let mut p: *mut ::std::os::raw::c_void = ::std::ptr::null_mut();
+    let status = unsafe { napi_unwrap(env, obj, &mut p) };
+    if status != napi_ok || p.is_null() {
+        swig_napi_throw_error(env, &format!("{} object is closed", T::napi_class_name()));
+        None
+    } else {
+        Some(p)
+    }
+}
+
+/// Move Rust object out of JavaScript object, after that JavaScript object is closed
+#[allow(dead_code)]
+fn swig_napi_take_object<T: SwigForeignClass>(env: napi_env, obj: napi_value) -> Option<T> {
+    swig_napi_object_ptr::<T>(env, obj)?;
+    let mut p: *mut ::std::os::raw::c_void = ::std::ptr::null_mut();
+    unsafe { napi_remove_wrap(env, obj, &mut p) };
+    Some(T::unbox_object(p))
+}
+
+#[allow(dead_code)]
+unsafe extern "C" fn swig_napi_finalize<T: SwigForeignClass>(
+    _env: napi_env,
+    data: *mut ::std::os::raw::c_void,
+    _hint: *mut ::std::os::raw::c_void,
+) {
+    if !data.is_null() {
+        drop(T::unbox_object(data));
+    }
+}
+
+/// Store boxed Rust object inside JavaScript object, it is freed
+/// by garbage collector or by `close`
+#[allow(dead_code)]
+fn swig_napi_init_raw<T: SwigForeignClass>(
+    env: napi_env,
+    obj: napi_value,
+    p: *mut ::std::os::raw::c_void,
+) -> napi_value {
+    let status = unsafe {
+        napi_wrap(
+            env,
+            obj,
+            p,
+            Some(swig_napi_finalize::<T>),
+            ::std::ptr::null_mut(),
+            ::std::ptr::null_mut(),
+        )
+    };
+    if swig_napi_check(env, status, "napi_wrap failed").is_none() {
+        drop(T::unbox_object(p));
+        return ::std::ptr::null_mut();
+    }
+    obj
+}
+
+#[allow(dead_code)]
+fn swig_napi_init_object<T: SwigForeignClass>(env: napi_env, obj: napi_value, x: T) -> napi_value {
+    swig_napi_init_raw::<T>(env, obj, T::box_object(x))
+}
+
+thread_local! {
+    /// Rust object that should be wrapped by JavaScript object,
+    /// which is created by constructor called from Rust
+    static SWIG_NAPI_WRAPPING: ::std::cell::Cell<*mut ::std::os::raw::c_void> =
+        ::std::cell::Cell::new(::std::ptr::null_mut());
+}
+
+/// If JavaScript object created by `swig_napi_wrap_object`, then
+/// constructor should only wrap already existing Rust object
+#[allow(dead_code)]
+fn swig_napi_take_wrapping() -> Option<*mut ::std::os::raw::c_void> {
+    let p = SWIG_NAPI_WRAPPING.with(|x| x.replace(::std::ptr::null_mut()));
+    if p.is_null() {
+        None
+    } else {
+        Some(p)
+    }
+}
+
+/// Create JavaScript object for Rust object returned to JavaScript
+#[allow(dead_code)]
+fn swig_napi_wrap_object<T: SwigForeignClass>(env: napi_env, x: T) -> napi_value {
+    let mut constructor: napi_value = ::std::ptr::null_mut();
+    unsafe { napi_get_reference_value(env, T::napi_constructor(), &mut constructor) };
+    SWIG_NAPI_WRAPPING.with(|w| w.set(T::box_object(x)));
+    let mut obj: napi_value = ::std::ptr::null_mut();
+    let status = unsafe { napi_new_instance(env, constructor, 0, ::std::ptr::null(), &mut obj) };
+    if let Some(p) = swig_napi_take_wrapping() {
+        //constructor was not called, so we still own object
+        drop(T::unbox_object(p));
+        swig_napi_throw_error(env, "napi_new_instance failed");
+        return ::std::ptr::null_mut();
+    }
+    if status != napi_ok {
+        return ::std::ptr::null_mut();
+    }
+    obj
+}
+
+/// `close` method of JavaScript class, free Rust object
+#[allow(dead_code)]
+unsafe extern "C" fn swig_napi_close<T: SwigForeignClass>(
+    env: napi_env,
+    info: napi_callback_info,
+) -> napi_value {
+    let mut this: napi_value = ::std::ptr::null_mut();
+    let mut argc: usize = 0;
+    napi_get_cb_info(
+        env,
+        info,
+        &mut argc,
+        ::std::ptr::null_mut(),
+        &mut this,
+        ::std::ptr::null_mut(),
+    );
+    let mut p: *mut ::std::os::raw::c_void = ::std::ptr::null_mut();
+    if napi_remove_wrap(env, this, &mut p) == napi_ok && !p.is_null() {
+        drop(T::unbox_object(p));
+    }
+    swig_napi_undefined(env)
+}
+
+#[allow(dead_code)]
+fn swig_napi_method(
+    name: *const ::std::os::raw::c_char,
+    method: unsafe extern "C" fn(napi_env, napi_callback_info) -> napi_value,
+    attributes: ::std::os::raw::c_int,
+) -> napi_property_descriptor {
+    napi_property_descriptor {
+        utf8name: name,
+        name: ::std::ptr::null_mut(),
+        method: Some(method),
+        getter: None,
+        setter: None,
+        value: ::std::ptr::null_mut(),
+        attributes,
+        data: ::std::ptr::null_mut(),
+    }
+}
+
+/// Define JavaScript class and add it to `exports`, reference to constructor
+/// saved into `constructor`
+#[allow(dead_code)]
+fn swig_napi_add_class(
+    env: napi_env,
+    exports: napi_value,
+    name: &str,
+    init: unsafe extern "C" fn(napi_env, napi_callback_info) -> napi_value,
+    properties: &[napi_property_descriptor],
+    constructor: &::std::sync::atomic::AtomicPtr<napi_ref__>,
+) -> bool {
+    let c_name = swig_napi_c_string(name);
+    let mut class: napi_value = ::std::ptr::null_mut();
+    let mut reference: napi_ref = ::std::ptr::null_mut();
+    unsafe {
+        napi_define_class(
+            env,
+            c_name.as_ptr(),
+            name.len(),
+            Some(init),
+            ::std::ptr::null_mut(),
+            properties.len(),
+            properties.as_ptr(),
+            &mut class,
+        ) == napi_ok
+            && napi_create_reference(env, class, 1, &mut reference) == napi_ok
+            && {
+                constructor.store(reference, ::std::sync::atomic::Ordering::SeqCst);
+                napi_set_named_property(env, exports, c_name.as_ptr(), class) == napi_ok
+            }
+    }
+}
+
+/// Add frozen object with enum items to `exports`
+#[allow(dead_code)]
+fn swig_napi_add_enum(env: napi_env, exports: napi_value, name: &str, items: &[(&str, i32)]) -> bool {
+    let mut obj: napi_value = ::std::ptr::null_mut();
+    unsafe {
+        if napi_create_object(env, &mut obj) != napi_ok {
+            return false;
+        }
+        for (item_name, value) in items {
+            let item_name = swig_napi_c_string(item_name);
+            let value = swig_napi_double_from(env, f64::from(*value));
+            if napi_set_named_property(env, obj, item_name.as_ptr(), value) != napi_ok {
+                return false;
+            }
+        }
+        let name = swig_napi_c_string(name);
+        napi_object_freeze(env, obj) == napi_ok
+            && napi_set_named_property(env, exports, name.as_ptr(), obj) == napi_ok
+    }
+}
+
+/// JavaScript object that implements `foreign_interface!`, its methods
+/// should be called from JavaScript thread during call of Rust code
+#[allow(dead_code)]
+pub struct SwigNapiCallback {
+    env: napi_env,
+    this: napi_ref,
+}
+
+#[allow(dead_code)]
+impl SwigNapiCallback {
+    fn new(env: napi_env, obj: napi_value) -> Option<SwigNapiCallback> {
+        let mut this: napi_ref = ::std::ptr::null_mut();
+        let status = unsafe { napi_create_reference(env, obj, 1, &mut this) };
+        swig_napi_check(env, status, "expected object")?;
+        Some(SwigNapiCallback { env, this })
+    }
+    /// Call method of JavaScript object, `None` means that exception was thrown
+    fn call(&self, method_name: *const ::std::os::raw::c_char, args: &[napi_value]) -> Option<napi_value> {
+        let env = self.env;
+        let mut obj: napi_value = ::std::ptr::null_mut();
+        let mut func: napi_value = ::std::ptr::null_mut();
+        let mut ret: napi_value = ::std::ptr::null_mut();
+        unsafe {
+            napi_get_reference_value(env, self.this, &mut obj);
+            let status = napi_get_named_property(env, obj, method_name, &mut func);
+            swig_napi_check(env, status, "napi_get_named_property failed")?;
+            let status = napi_call_function(env, obj, func, args.len(), args.as_ptr(), &mut ret);
+            swig_napi_check(env, status, "callback is not a function")?;
+        }
+        Some(ret)
+    }
+}
+
+impl Drop for SwigNapiCallback {
+    fn drop(&mut self) {
+        unsafe { napi_delete_reference(self.env, self.this) };
+    }
+}
+
+#[swig_generic_arg = "T"]
+#[swig_generic_arg = "E"]
+#[swig_from = "Result<T, E>"]
+#[swig_to = "T"]
+#[swig_code = "let mut {to_var}: {to_var_type} = napi_unpack_return!({from_var}, env, {function_ret_type});"]
+macro_rules! napi_unpack_return {
+    ($result_value:expr, $env:expr, $func_ret_type:ty) => {{
+        let ret = match $result_value {
+            Ok(x) => x,
+            Err(err) => {
+                swig_napi_throw_error($env, &err.to_string());
+                return <$func_ret_type>::swig_napi_invalid_value();
+            }
+        };
+        ret
+    }};
+}
+
+#[swig_to_foreigner_hint = "T | null"]
+impl<T: SwigForeignClass> SwigFrom<Option<T>> for napi_value {
+    fn swig_from(x: Option<T>, env: napi_env) -> Self {
+        swig_napi_opt_from(env, x, swig_napi_wrap_object)
+    }
+}
+
+#[swig_from_foreigner_hint = "T | null"]
+impl<T: SwigForeignClass> SwigInto<Option<T>> for napi_value {
+    fn swig_into(self, env: napi_env) -> Option<Option<T>> {
+        swig_napi_opt_as(env, self, swig_napi_take_object)
+    }
+}
+
+#[swig_to_foreigner_hint = "T[]"]
+impl<T: SwigForeignClass> SwigFrom<Vec<T>> for napi_value {
+    fn swig_from(x: Vec<T>, env: napi_env) -> Self {
+        swig_napi_array_from(env, x, swig_napi_wrap_object)
+    }
+}
+
+#[swig_from_foreigner_hint = "T[]"]
+impl<T: SwigForeignClass + Clone> SwigInto<Vec<T>> for napi_value {
+    fn swig_into(self, env: napi_env) -> Option<Vec<T>> {
+        swig_napi_array_as(env, self, |env, x| {
+            let p = swig_napi_object_ptr::<T>(env, x)?;
+            let obj: &T = unsafe { &*(p as *const T) };
+            Some(obj.clone())
+        })
+    }
+}
+
+impl SwigFrom<bool> for NapiBoolean {
+    fn swig_from(x: bool, env: napi_env) -> Self {
+        swig_napi_bool_from(env, x)
+    }
+}
+
+impl SwigInto<bool> for NapiBoolean {
+    fn swig_into(self, env: napi_env) -> Option<bool> {
+        swig_napi_bool_as(env, self)
+    }
+}
+
+impl SwigFrom<i8> for NapiNumber {
+    fn swig_from(x: i8, env: napi_env) -> Self {
+        swig_napi_double_from(env, f64::from(x))
+    }
+}
+
+impl SwigInto<i8> for NapiNumber {
+    fn swig_into(self, env: napi_env) -> Option<i8> {
+        swig_napi_int_as(env, self)
+    }
+}
+
+impl SwigFrom<u8> for NapiNumber {
+    fn swig_from(x: u8, env: napi_env) -> Self {
+        swig_napi_double_from(env, f64::from(x))
+    }
+}
+
+impl SwigInto<u8> for NapiNumber {
+    fn swig_into(self, env: napi_env) -> Option<u8> {
+        swig_napi_int_as(env, self)
+    }
+}
+
+impl SwigFrom<i16> for NapiNumber {
+    fn swig_from(x: i16, env: napi_env) -> Self {
+        swig_napi_double_from(env, f64::from(x))
+    }
+}
+
+impl SwigInto<i16> for NapiNumber {
+    fn swig_into(self, env: napi_env) -> Option<i16> {
+        swig_napi_int_as(env, self)
+    }
+}
+
+impl SwigFrom<u16> for NapiNumber {
+    fn swig_from(x: u16, env: napi_env) -> Self {
+        swig_napi_double_from(env, f64::from(x))
+    }
+}
+
+impl SwigInto<u16> for NapiNumber {
+    fn swig_into(self, env: napi_env) -> Option<u16> {
+        swig_napi_int_as(env, self)
+    }
+}
+
+impl SwigFrom<i32> for NapiNumber {
+    fn swig_from(x: i32, env: napi_env) -> Self {
+        swig_napi_double_from(env, f64::from(x))
+    }
+}
+
+impl SwigInto<i32> for NapiNumber {
+    fn swig_into(self, env: napi_env) -> Option<i32> {
+        swig_napi_int_as(env, self)
+    }
+}
+
+impl SwigFrom<u32> for NapiNumber {
+    fn swig_from(x: u32, env: napi_env) -> Self {
+        swig_napi_double_from(env, f64::from(x))
+    }
+}
+
+impl SwigInto<u32> for NapiNumber {
+    fn swig_into(self, env: napi_env) -> Option<u32> {
+        swig_napi_int_as(env, self)
+    }
+}
+
+impl SwigFrom<i64> for NapiNumber {
+    fn swig_from(x: i64, env: napi_env) -> Self {
+        swig_napi_double_from(env, x as f64)
+    }
+}
+
+impl SwigInto<i64> for NapiNumber {
+    fn swig_into(self, env: napi_env) -> Option<i64> {
+        swig_napi_int_as(env, self)
+    }
+}
+
+impl SwigFrom<u64> for NapiNumber {
+    fn swig_from(x: u64, env: napi_env) -> Self {
+        swig_napi_double_from(env, x as f64)
+    }
+}
+
+impl SwigInto<u64> for NapiNumber {
+    fn swig_into(self, env: napi_env) -> Option<u64> {
+        swig_napi_int_as(env, self)
+    }
+}
+
+impl SwigFrom<isize> for NapiNumber {
+    fn swig_from(x: isize, env: napi_env) -> Self {
+        swig_napi_double_from(env, x as f64)
+    }
+}
+
+impl SwigInto<isize> for NapiNumber {
+    fn swig_into(self, env: napi_env) -> Option<isize> {
+        swig_napi_int_as(env, self)
+    }
+}
+
+impl SwigFrom<usize> for NapiNumber {
+    fn swig_from(x: usize, env: napi_env) -> Self {
+        swig_napi_double_from(env, x as f64)
+    }
+}
+
+impl SwigInto<usize> for NapiNumber {
+    fn swig_into(self, env: napi_env) -> Option<usize> {
+        swig_napi_int_as(env, self)
+    }
+}
+
+impl SwigFrom<f32> for NapiNumber {
+    fn swig_from(x: f32, env: napi_env) -> Self {
+        swig_napi_double_from(env, f64::from(x))
+    }
+}
+
+impl SwigInto<f32> for NapiNumber {
+    fn swig_into(self, env: napi_env) -> Option<f32> {
+        swig_napi_double_as(env, self).map(|x| x as f32)
+    }
+}
+
+impl SwigFrom<f64> for NapiNumber {
+    fn swig_from(x: f64, env: napi_env) -> Self {
+        swig_napi_double_from(env, x)
+    }
+}
+
+impl SwigInto<f64> for NapiNumber {
+    fn swig_into(self, env: napi_env) -> Option<f64> {
+        swig_napi_double_as(env, self)
+    }
+}
+
+impl<'a> SwigFrom<&'a str> for NapiString {
+    fn swig_from(x: &'a str, env: napi_env) -> Self {
+        swig_napi_str_from(env, x)
+    }
+}
+
+impl SwigFrom<String> for NapiString {
+    fn swig_from(x: String, env: napi_env) -> Self {
+        swig_napi_str_from(env, &x)
+    }
+}
+
+impl SwigInto<String> for NapiString {
+    fn swig_into(self, env: napi_env) -> Option<String> {
+        swig_napi_str_as(env, self)
+    }
+}
+
+impl SwigDeref for String {
+    type Target = str;
+    fn swig_deref(&self) -> &str {
+        self
+    }
+}
+
+impl SwigFrom<Option<bool>> for NapiOptBoolean {
+    fn swig_from(x: Option<bool>, env: napi_env) -> Self {
+        swig_napi_opt_from(env, x, swig_napi_bool_from)
+    }
+}
+
+impl SwigInto<Option<bool>> for NapiOptBoolean {
+    fn swig_into(self, env: napi_env) -> Option<Option<bool>> {
+        swig_napi_opt_as(env, self, swig_napi_bool_as)
+    }
+}
+
+impl SwigFrom<Option<i32>> for NapiOptNumber {
+    fn swig_from(x: Option<i32>, env: napi_env) -> Self {
+        swig_napi_opt_from(env, x, |env, x| swig_napi_double_from(env, f64::from(x)))
+    }
+}
+
+impl SwigInto<Option<i32>> for NapiOptNumber {
+    fn swig_into(self, env: napi_env) -> Option<Option<i32>> {
+        swig_napi_opt_as(env, self, swig_napi_int_as)
+    }
+}
+
+impl SwigFrom<Option<u32>> for NapiOptNumber {
+    fn swig_from(x: Option<u32>, env: napi_env) -> Self {
+        swig_napi_opt_from(env, x, |env, x| swig_napi_double_from(env, f64::from(x)))
+    }
+}
+
+impl SwigInto<Option<u32>> for NapiOptNumber {
+    fn swig_into(self, env: napi_env) -> Option<Option<u32>> {
+        swig_napi_opt_as(env, self, swig_napi_int_as)
+    }
+}
+
+impl SwigFrom<Option<i64>> for NapiOptNumber {
+    fn swig_from(x: Option<i64>, env: napi_env) -> Self {
+        swig_napi_opt_from(env, x, |env, x| swig_napi_double_from(env, x as f64))
+    }
+}
+
+impl SwigInto<Option<i64>> for NapiOptNumber {
+    fn swig_into(self, env: napi_env) -> Option<Option<i64>> {
+        swig_napi_opt_as(env, self, swig_napi_int_as)
+    }
+}
+
+impl SwigFrom<Option<u64>> for NapiOptNumber {
+    fn swig_from(x: Option<u64>, env: napi_env) -> Self {
+        swig_napi_opt_from(env, x, |env, x| swig_napi_double_from(env, x as f64))
+    }
+}
+
+impl SwigInto<Option<u64>> for NapiOptNumber {
+    fn swig_into(self, env: napi_env) -> Option<Option<u64>> {
+        swig_napi_opt_as(env, self, swig_napi_int_as)
+    }
+}
+
+impl SwigFrom<Option<usize>> for NapiOptNumber {
+    fn swig_from(x: Option<usize>, env: napi_env) -> Self {
+        swig_napi_opt_from(env, x, |env, x| swig_napi_double_from(env, x as f64))
+    }
+}
+
+impl SwigInto<Option<usize>> for NapiOptNumber {
+    fn swig_into(self, env: napi_env) -> Option<Option<usize>> {
+        swig_napi_opt_as(env, self, swig_napi_int_as)
+    }
+}
+
+impl SwigFrom<Option<f32>> for NapiOptNumber {
+    fn swig_from(x: Option<f32>, env: napi_env) -> Self {
+        swig_napi_opt_from(env, x, |env, x| swig_napi_double_from(env, f64::from(x)))
+    }
+}
+
+impl SwigInto<Option<f32>> for NapiOptNumber {
+    fn swig_into(self, env: napi_env) -> Option<Option<f32>> {
+        swig_napi_opt_as(env, self, |env, x| {
+            swig_napi_double_as(env, x).map(|x| x as f32)
+        })
+    }
+}
+
+impl SwigFrom<Option<f64>> for NapiOptNumber {
+    fn swig_from(x: Option<f64>, env: napi_env) -> Self {
+        swig_napi_opt_from(env, x, swig_napi_double_from)
+    }
+}
+
+impl SwigInto<Option<f64>> for NapiOptNumber {
+    fn swig_into(self, env: napi_env) -> Option<Option<f64>> {
+        swig_napi_opt_as(env, self, swig_napi_double_as)
+    }
+}
+
+impl SwigFrom<Option<String>> for NapiOptString {
+    fn swig_from(x: Option<String>, env: napi_env) -> Self {
+        swig_napi_opt_from(env, x, |env, x| swig_napi_str_from(env, &x))
+    }
+}
+
+impl SwigInto<Option<String>> for NapiOptString {
+    fn swig_into(self, env: napi_env) -> Option<Option<String>> {
+        swig_napi_opt_as(env, self, swig_napi_str_as)
+    }
+}
+
+impl SwigFrom<Vec<u8>> for NapiNumberArray {
+    fn swig_from(x: Vec<u8>, env: napi_env) -> Self {
+        swig_napi_array_from(env, x, |env, x| swig_napi_double_from(env, f64::from(x)))
+    }
+}
+
+impl SwigInto<Vec<u8>> for NapiNumberArray {
+    fn swig_into(self, env: napi_env) -> Option<Vec<u8>> {
+        swig_napi_array_as(env, self, swig_napi_int_as)
+    }
+}
+
+impl SwigFrom<Vec<i32>> for NapiNumberArray {
+    fn swig_from(x: Vec<i32>, env: napi_env) -> Self {
+        swig_napi_array_from(env, x, |env, x| swig_napi_double_from(env, f64::from(x)))
+    }
+}
+
+impl SwigInto<Vec<i32>> for NapiNumberArray {
+    fn swig_into(self, env: napi_env) -> Option<Vec<i32>> {
+        swig_napi_array_as(env, self, swig_napi_int_as)
+    }
+}
+
+impl SwigFrom<Vec<u32>> for NapiNumberArray {
+    fn swig_from(x: Vec<u32>, env: napi_env) -> Self {
+        swig_napi_array_from(env, x, |env, x| swig_napi_double_from(env, f64::from(x)))
+    }
+}
+
+impl SwigInto<Vec<u32>> for NapiNumberArray {
+    fn swig_into(self, env: napi_env) -> Option<Vec<u32>> {
+        swig_napi_array_as(env, self, swig_napi_int_as)
+    }
+}
+
+impl SwigFrom<Vec<i64>> for NapiNumberArray {
+    fn swig_from(x: Vec<i64>, env: napi_env) -> Self {
+        swig_napi_array_from(env, x, |env, x| swig_napi_double_from(env, x as f64))
+    }
+}
+
+impl SwigInto<Vec<i64>> for NapiNumberArray {
+    fn swig_into(self, env: napi_env) -> Option<Vec<i64>> {
+        swig_napi_array_as(env, self, swig_napi_int_as)
+    }
+}
+
+impl SwigFrom<Vec<u64>> for NapiNumberArray {
+    fn swig_from(x: Vec<u64>, env: napi_env) -> Self {
+        swig_napi_array_from(env, x, |env, x| swig_napi_double_from(env, x as f64))
+    }
+}
+
+impl SwigInto<Vec<u64>> for NapiNumberArray {
+    fn swig_into(self, env: napi_env) -> Option<Vec<u64>> {
+        swig_napi_array_as(env, self, swig_napi_int_as)
+    }
+}
+
+impl SwigFrom<Vec<f32>> for NapiNumberArray {
+    fn swig_from(x: Vec<f32>, env: napi_env) -> Self {
+        swig_napi_array_from(env, x, |env, x| swig_napi_double_from(env, f64::from(x)))
+    }
+}
+
+impl SwigInto<Vec<f32>> for NapiNumberArray {
+    fn swig_into(self, env: napi_env) -> Option<Vec<f32>> {
+        swig_napi_array_as(env, self, |env, x| {
+            swig_napi_double_as(env, x).map(|x| x as f32)
+        })
+    }
+}
+
+impl SwigFrom<Vec<f64>> for NapiNumberArray {
+    fn swig_from(x: Vec<f64>, env: napi_env) -> Self {
+        swig_napi_array_from(env, x, swig_napi_double_from)
+    }
+}
+
+impl SwigInto<Vec<f64>> for NapiNumberArray {
+    fn swig_into(self, env: napi_env) -> Option<Vec<f64>> {
+        swig_napi_array_as(env, self, swig_napi_double_as)
+    }
+}
+
+impl SwigFrom<Vec<String>> for NapiStringArray {
+    fn swig_from(x: Vec<String>, env: napi_env) -> Self {
+        swig_napi_array_from(env, x, |env, x| swig_napi_str_from(env, &x))
+    }
+}
+
+impl SwigInto<Vec<String>> for NapiStringArray {
+    fn swig_into(self, env: napi_env) -> Option<Vec<String>> {
+        swig_napi_array_as(env, self, swig_napi_str_as)
+    }
+}
+
+impl<T> SwigDeref for Vec<T> {
+    type Target = [T];
+    fn swig_deref(&self) -> &[T] {
+        self
+    }
+}
+
+impl<'a, T: Clone> SwigFrom<&'a [T]> for Vec<T> {
+    fn swig_from(x: &'a [T], _: napi_env) -> Self {
+        x.to_vec()
+    }
+}
+
+impl<T> SwigDeref for Arc<Mutex<T>> {
+    type Target = Mutex<T>;
+    fn swig_deref(&self) -> &Mutex<T> {
+        self
+    }
+}
+
+impl<'a, T> SwigFrom<&'a Mutex<T>> for MutexGuard<'a, T> {
+    fn swig_from(m: &'a Mutex<T>, _: napi_env) -> MutexGuard<'a, T> {
+        m.lock().unwrap()
+    }
+}
+
+impl<'a, T> SwigDeref for MutexGuard<'a, T> {
+    type Target = T;
+    fn swig_deref(&self) -> &T {
+        self
+    }
+}
+
+impl<'a, T> SwigDerefMut for MutexGuard<'a, T> {
+    type Target = T;
+    fn swig_deref_mut(&mut self) -> &mut T {
+        self
+    }
+}
+
+impl<T> SwigDeref for Rc<T> {
+    type Target = T;
+    fn swig_deref(&self) -> &T {
+        self
+    }
+}
+
+impl<'a, T> SwigDeref for &'a Rc<T> {
+    type Target = T;
+    fn swig_deref(&self) -> &T {
+        self
+    }
+}
+
+impl<'a, T> SwigFrom<&'a RefCell<T>> for Ref<'a, T> {
+    fn swig_from(m: &'a RefCell<T>, _: napi_env) -> Ref<'a, T> {
+        m.borrow()
+    }
+}
+
+impl<'a, T> SwigFrom<&'a RefCell<T>> for RefMut<'a, T> {
+    fn swig_from(m: &'a RefCell<T>, _: napi_env) -> RefMut<'a, T> {
+        m.borrow_mut()
+    }
+}
+
+impl<'a, T> SwigDeref for Ref<'a, T> {
+    type Target = T;
+    fn swig_deref(&self) -> &T {
+        self
+    }
+}
+
+impl<'a, T> SwigDerefMut for RefMut<'a, T> {
+    type Target = T;
+    fn swig_deref_mut(&mut self) -> &mut T {
+        self
+    }
+}
+
+impl<T: SwigForeignClass> SwigDeref for T {
+    type Target = T;
+    fn swig_deref(&self) -> &T {
+        self
+    }
+}
+
+impl<T: SwigForeignClass> SwigDerefMut for T {
+    type Target = T;
+    fn swig_deref_mut(&mut self) -> &mut T {
+        self
+    }
+}
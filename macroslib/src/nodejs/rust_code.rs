@@ -0,0 +1,713 @@
+use std::fmt::Write;
+
+use proc_macro2::TokenStream;
+use rustc_hash::FxHashMap;
+use syn::{parse_quote, spanned::Spanned, Type};
+
+use crate::{
+    error::{panic_on_syn_error, DiagnosticError, Result},
+    nodejs::{calc_this_type_for_method, method_name, NapiForeignMethodSignature},
+    source_registry::SourceId,
+    typemap::{
+        ast::{fn_arg_type, list_lifetimes, normalize_ty_lifetimes, DisplayToTokens},
+        ty::RustType,
+        unpack_unique_typename,
+        utils::{
+            create_suitable_types_for_constructor_and_self,
+            foreign_from_rust_convert_method_output, foreign_to_rust_convert_method_inputs,
+            rust_to_foreign_convert_method_inputs,
+        },
+    },
+    types::{
+        ForeignEnumInfo, ForeignInterface, ForeignerClassInfo, ForeignerMethod, MethodVariant,
+        SelfTypeVariant,
+    },
+    TypeMap,
+};
+
+const NAPI_RET_TYPE: &str = "napi_value";
+
+struct MethodContext<'a> {
+    class: &'a ForeignerClassInfo,
+    method: &'a ForeignerMethod,
+    f_method: &'a NapiForeignMethodSignature,
+    func_name: &'a str,
+    js_name: &'a str,
+    args_names: &'a str,
+    real_output_typename: &'a str,
+}
+
+/// Generate Rust code for class, returns it and name of function
+/// that should be called during module initialization
+pub(in crate::nodejs) fn generate_class(
+    conv_map: &mut TypeMap,
+    class: &ForeignerClassInfo,
+    f_methods: &[NapiForeignMethodSignature],
+) -> Result<(Vec<TokenStream>, String)> {
+    check_names(class)?;
+
+    let mut gen_code = Vec::<TokenStream>::new();
+    let this_type = match calc_this_type_for_method(conv_map, class) {
+        Some(this_type) => {
+            let this_type = conv_map.find_or_alloc_rust_type_that_implements(
+                &this_type,
+                "SwigForeignClass",
+                class.src_id,
+            );
+            gen_code.push(generate_swig_foreign_class_impl(
+                conv_map, class, &this_type,
+            ));
+            Some(this_type)
+        }
+        None => None,
+    };
+
+    let no_this_info = || {
+        DiagnosticError::new(
+            class.src_id,
+            class.span(),
+            format!(
+                "Class {} has methods, but there is no constructor\n
+May be you need to use `private constructor = empty;` syntax?",
+                class.name,
+            ),
+        )
+    };
+
+    let mut methods_table = String::new();
+    let mut constructors = Vec::new();
+    for (method, f_method) in class.methods.iter().zip(f_methods.iter()) {
+        if method.is_dummy_constructor() {
+            continue;
+        }
+        let js_name = method_name(method);
+        let func_name = match method.variant {
+            MethodVariant::Constructor => {
+                format!("swig_napi_{}_new_{}", class.name, f_method.input.len())
+            }
+            _ => format!("swig_napi_{}_{}", class.name, js_name),
+        };
+        let args_names = (0..f_method.input.len())
+            .map(|i| format!("a_{}", i))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let real_output_typename = match method.fn_decl.output {
+            syn::ReturnType::Default => "()",
+            syn::ReturnType::Type(_, ref ty) => normalize_ty_lifetimes(ty),
+        };
+        let mc = MethodContext {
+            class,
+            method,
+            f_method,
+            func_name: &func_name,
+            js_name: &js_name,
+            args_names: &args_names,
+            real_output_typename,
+        };
+        match method.variant {
+            MethodVariant::Constructor => {
+                let this_type = this_type.as_ref().ok_or_else(&no_this_info)?;
+                gen_code.append(&mut generate_constructor(conv_map, &mc, this_type)?);
+                constructors.push((f_method.input.len(), func_name));
+                continue;
+            }
+            MethodVariant::StaticMethod => {
+                gen_code.append(&mut generate_method(conv_map, &mc, None)?);
+            }
+            MethodVariant::Method(self_variant) => {
+                let this_type = this_type.as_ref().ok_or_else(&no_this_info)?;
+                gen_code.append(&mut generate_method(
+                    conv_map,
+                    &mc,
+                    Some((self_variant, this_type)),
+                )?);
+            }
+        }
+        writeln!(
+            &mut methods_table,
+            r#"        swig_napi_method(swig_c_str!("{js_name}"), {func_name}, {attributes}),"#,
+            js_name = js_name,
+            func_name = func_name,
+            attributes = if method.variant == MethodVariant::StaticMethod {
+                "napi_static"
+            } else {
+                "napi_default"
+            },
+        )
+        .unwrap();
+    }
+    if let Some(ref this_type) = this_type {
+        writeln!(
+            &mut methods_table,
+            r#"        swig_napi_method(swig_c_str!("close"), swig_napi_close::<{this_type}>, napi_default),"#,
+            this_type = this_type.normalized_name,
+        )
+        .unwrap();
+    }
+
+    let constructor_func_name = format!("swig_napi_{}_constructor", class.name);
+    let mut constructor_cases = String::new();
+    for (n_args, func_name) in &constructors {
+        writeln!(
+            &mut constructor_cases,
+            "        {} => {}(env, info),",
+            n_args, func_name
+        )
+        .unwrap();
+    }
+    let constructor_error = if constructors.is_empty() {
+        format!("{} can not be created from JavaScript", class.name)
+    } else {
+        format!("{}(): wrong number of arguments", class.name)
+    };
+    let wrap_existing = match this_type {
+        Some(ref this_type) => format!(
+            r#"
+    if let Some(p) = swig_napi_take_wrapping() {{
+        return swig_napi_init_raw::<{this_type}>(env, swig_napi_this(env, info), p);
+    }}"#,
+            this_type = this_type.normalized_name
+        ),
+        None => String::new(),
+    };
+    let add_class_func_name = format!("swig_napi_add_class_{}", class.name);
+    let code = format!(
+        r#"
+#[allow(non_upper_case_globals)]
+static SWIG_NAPI_CONSTRUCTOR_{class_name}: ::std::sync::atomic::AtomicPtr<napi_ref__> =
+    ::std::sync::atomic::AtomicPtr::new(::std::ptr::null_mut());
+
+#[allow(non_snake_case)]
+extern "C" fn {constructor_func_name}(env: napi_env, info: napi_callback_info) -> napi_value {{
+    if !swig_napi_is_construct_call(env, info) {{
+        swig_napi_throw_type_error(env, "Class constructor {class_name} cannot be invoked without 'new'");
+        return ::std::ptr::null_mut();
+    }}{wrap_existing}
+    match swig_napi_args_count(env, info) {{
+{constructor_cases}
+        _ => {{
+            swig_napi_throw_type_error(env, "{constructor_error}");
+            ::std::ptr::null_mut()
+        }}
+    }}
+}}
+
+#[allow(non_snake_case)]
+fn {add_class_func_name}(env: napi_env, exports: napi_value) -> bool {{
+    let properties: Vec<napi_property_descriptor> = vec![
+{methods_table}
+    ];
+    swig_napi_add_class(
+        env,
+        exports,
+        "{class_name}",
+        {constructor_func_name},
+        &properties,
+        &SWIG_NAPI_CONSTRUCTOR_{class_name},
+    )
+}}
+"#,
+        class_name = class.name,
+        constructor_func_name = constructor_func_name,
+        wrap_existing = wrap_existing,
+        constructor_cases = constructor_cases,
+        constructor_error = constructor_error,
+        add_class_func_name = add_class_func_name,
+        methods_table = methods_table,
+    );
+    gen_code.push(
+        syn::parse_str(&code)
+            .unwrap_or_else(|err| panic_on_syn_error("nodejs internal class code", code, err)),
+    );
+    Ok((gen_code, add_class_func_name))
+}
+
+/// JavaScript has no overloading, so names of methods should be unique,
+/// and constructors should be different by number of arguments
+fn check_names(class: &ForeignerClassInfo) -> Result<()> {
+    let mut names = FxHashMap::<String, &ForeignerMethod>::default();
+    let mut constructors = FxHashMap::<usize, &ForeignerMethod>::default();
+    for method in &class.methods {
+        if method.is_dummy_constructor() {
+            continue;
+        }
+        let prev = match method.variant {
+            MethodVariant::Constructor => {
+                let n_args = method.fn_decl.inputs.len();
+                constructors.insert(n_args, method)
+            }
+            _ => names.insert(method_name(method), method),
+        };
+        if let Some(prev) = prev {
+            let mut err = DiagnosticError::new(
+                class.src_id,
+                method.span(),
+                if method.variant == MethodVariant::Constructor {
+                    "Node.js: constructors with the same number of arguments are not supported"
+                } else {
+                    "Node.js: methods with the same name are not supported"
+                },
+            );
+            err.span_note((class.src_id, prev.span()), "previous definition here");
+            return Err(err);
+        }
+    }
+    if class.self_type.is_some() && names.contains_key("close") {
+        return Err(DiagnosticError::new(
+            class.src_id,
+            names["close"].span(),
+            "Node.js: method name 'close' is reserved for freeing of Rust object",
+        ));
+    }
+    Ok(())
+}
+
+fn generate_swig_foreign_class_impl(
+    conv_map: &mut TypeMap,
+    class: &ForeignerClassInfo,
+    this_type: &RustType,
+) -> TokenStream {
+    let (this_type_for_method, code_box_this) = conv_map.convert_to_heap_pointer(this_type, "this");
+    let unpack_code = TypeMap::unpack_from_heap_pointer(this_type, "x", true);
+    let code = format!(
+        r#"
+impl<{lifetimes}> SwigForeignClass for {this_type} {{
+    fn napi_class_name() -> &'static str {{
+        "{class_name}"
+    }}
+    fn napi_constructor() -> napi_ref {{
+        SWIG_NAPI_CONSTRUCTOR_{class_name}.load(::std::sync::atomic::Ordering::SeqCst)
+    }}
+    fn box_object(this: Self) -> *mut ::std::os::raw::c_void {{
+{code_box_this}
+        this as *mut ::std::os::raw::c_void
+    }}
+    fn unbox_object(x: *mut ::std::os::raw::c_void) -> Self {{
+        let x: *mut {this_type_for_method} = x as *mut {this_type_for_method};
+{unpack_code}
+        x
+    }}
+}}
+"#,
+        lifetimes = list_lifetimes(&this_type.ty).join(","),
+        this_type = DisplayToTokens(&this_type.ty),
+        class_name = class.name,
+        code_box_this = code_box_this,
+        this_type_for_method = this_type_for_method.normalized_name,
+        unpack_code = unpack_code,
+    );
+    syn::parse_str(&code)
+        .unwrap_or_else(|err| panic_on_syn_error("nodejs internal fclass impl code", code, err))
+}
+
+/// Get arguments of `napi_callback` from `argv`
+fn args_from_argv(f_method: &NapiForeignMethodSignature) -> String {
+    let mut code = String::new();
+    for (i, arg) in f_method.input.iter().enumerate() {
+        writeln!(
+            &mut code,
+            "    let a_{i}: {ty} = argv[{i}];",
+            i = i,
+            ty = unpack_unique_typename(&arg.correspoding_rust_type.normalized_name),
+        )
+        .unwrap();
+    }
+    code
+}
+
+fn generate_method(
+    conv_map: &mut TypeMap,
+    mc: &MethodContext,
+    this: Option<(SelfTypeVariant, &RustType)>,
+) -> Result<Vec<TokenStream>> {
+    let src_id = mc.class.src_id;
+    let (mut deps_code, convert_input_code) = foreign_to_rust_convert_method_inputs(
+        conv_map,
+        src_id,
+        mc.method,
+        mc.f_method,
+        (0..mc.f_method.input.len()).map(|v| format!("a_{}", v)),
+        NAPI_RET_TYPE,
+    )?;
+
+    let (convert_this, call_args) = match this {
+        Some((self_variant, this_type)) => {
+            let (this_type_for_method, _code_box_this) =
+                conv_map.convert_to_heap_pointer(this_type, "this");
+            let js_obj_ty = conv_map.find_or_alloc_rust_type_with_suffix(
+                &parse_type! { napi_value },
+                &this_type.normalized_name,
+                SourceId::none(),
+            );
+            let (from_ty, to_ty): (Type, Type) = create_suitable_types_for_constructor_and_self(
+                self_variant,
+                mc.class,
+                &this_type_for_method.ty,
+            );
+            let from_ty = conv_map.find_or_alloc_rust_type(&from_ty, src_id);
+            let to_ty = conv_map.find_or_alloc_rust_type(&to_ty, src_id);
+            let (mut deps_obj, convert_obj) = conv_map.convert_rust_types(
+                &js_obj_ty,
+                &from_ty,
+                "this",
+                NAPI_RET_TYPE,
+                (src_id, mc.method.span()),
+            )?;
+            let (mut deps_this, convert_this) = conv_map.convert_rust_types(
+                &from_ty,
+                &to_ty,
+                "this",
+                NAPI_RET_TYPE,
+                (src_id, mc.method.span()),
+            )?;
+            deps_code.append(&mut deps_obj);
+            deps_code.append(&mut deps_this);
+            (
+                format!("{}{}", convert_obj, convert_this),
+                if mc.args_names.is_empty() {
+                    "this".to_string()
+                } else {
+                    format!("this, {}", mc.args_names)
+                },
+            )
+        }
+        None => (String::new(), mc.args_names.to_string()),
+    };
+
+    let (mut deps_code_out, convert_output_code) = foreign_from_rust_convert_method_output(
+        conv_map,
+        src_id,
+        &mc.method.fn_decl.output,
+        &mc.f_method.output,
+        "ret",
+        NAPI_RET_TYPE,
+    )?;
+    deps_code.append(&mut deps_code_out);
+    let ret = if mc.f_method.output.name == "void" {
+        "swig_napi_undefined(env)"
+    } else {
+        "ret"
+    };
+
+    let code = format!(
+        r#"
+#[allow(non_snake_case, unused_variables, unused_mut)]
+extern "C" fn {func_name}(env: napi_env, info: napi_callback_info) -> napi_value {{
+    let (this, argv) = swig_napi_try!(
+        swig_napi_get_args(env, info, {n_args}, "{js_name}"), napi_value);
+{args_from_argv}
+{convert_input_code}
+{convert_this}
+    let mut ret: {real_output_typename} = {rust_func_name}({call_args});
+{convert_output_code}
+    {ret}
+}}
+"#,
+        func_name = mc.func_name,
+        n_args = mc.f_method.input.len(),
+        js_name = mc.js_name,
+        args_from_argv = args_from_argv(mc.f_method),
+        convert_input_code = convert_input_code,
+        convert_this = convert_this,
+        real_output_typename = mc.real_output_typename,
+        rust_func_name = DisplayToTokens(&mc.method.rust_id),
+        call_args = call_args,
+        convert_output_code = convert_output_code,
+        ret = ret,
+    );
+    deps_code.push(
+        syn::parse_str(&code)
+            .unwrap_or_else(|err| panic_on_syn_error("nodejs internal method", code, err)),
+    );
+    Ok(deps_code)
+}
+
+fn generate_constructor(
+    conv_map: &mut TypeMap,
+    mc: &MethodContext,
+    this_type: &RustType,
+) -> Result<Vec<TokenStream>> {
+    let src_id = mc.class.src_id;
+    let (mut deps_code, convert_input_code) = foreign_to_rust_convert_method_inputs(
+        conv_map,
+        src_id,
+        mc.method,
+        mc.f_method,
+        (0..mc.f_method.input.len()).map(|v| format!("a_{}", v)),
+        NAPI_RET_TYPE,
+    )?;
+    let construct_ret_type = match mc.class.constructor_ret_type {
+        Some(ref ty) => conv_map.find_or_alloc_rust_type(ty, src_id),
+        None => this_type.clone(),
+    };
+    let (mut deps_this, convert_this) = conv_map.convert_rust_types(
+        &construct_ret_type,
+        this_type,
+        "this",
+        NAPI_RET_TYPE,
+        (src_id, mc.method.span()),
+    )?;
+    deps_code.append(&mut deps_this);
+
+    let code = format!(
+        r#"
+#[allow(non_snake_case, unused_variables, unused_mut)]
+fn {func_name}(env: napi_env, info: napi_callback_info) -> napi_value {{
+    let (this_obj, argv) = swig_napi_try!(
+        swig_napi_get_args(env, info, {n_args}, "{class_name}"), napi_value);
+{args_from_argv}
+{convert_input_code}
+    let this: {real_output_typename} = {rust_func_name}({args_names});
+{convert_this}
+    swig_napi_init_object::<{this_type}>(env, this_obj, this)
+}}
+"#,
+        func_name = mc.func_name,
+        n_args = mc.f_method.input.len(),
+        class_name = mc.class.name,
+        args_from_argv = args_from_argv(mc.f_method),
+        convert_input_code = convert_input_code,
+        real_output_typename = mc.real_output_typename,
+        rust_func_name = DisplayToTokens(&mc.method.rust_id),
+        args_names = mc.args_names,
+        convert_this = convert_this,
+        this_type = this_type.normalized_name,
+    );
+    deps_code.push(
+        syn::parse_str(&code)
+            .unwrap_or_else(|err| panic_on_syn_error("nodejs internal constructor", code, err)),
+    );
+    Ok(deps_code)
+}
+
+/// Generate Rust code for enum, returns it and name of function
+/// that should be called during module initialization
+pub(in crate::nodejs) fn generate_enum(
+    conv_map: &mut TypeMap,
+    pointer_target_width: usize,
+    enum_info: &ForeignEnumInfo,
+) -> Result<(Vec<TokenStream>, String)> {
+    let rust_enum_name = enum_info.rust_enum_name();
+    let mut to_value = String::new();
+    let mut from_value = String::new();
+    let mut items = String::new();
+    for item in &enum_info.items {
+        writeln!(
+            &mut to_value,
+            "            {} => {},",
+            DisplayToTokens(&item.rust_name),
+            item.value
+        )
+        .unwrap();
+        writeln!(
+            &mut from_value,
+            "            {} => Some({}),",
+            item.value,
+            DisplayToTokens(&item.rust_name)
+        )
+        .unwrap();
+        write!(&mut items, "(\"{}\", {}), ", item.name, item.value).unwrap();
+    }
+
+    let conv_code = format!(
+        r#"
+mod swig_foreign_types_map {{
+    #![swig_foreigner_type = "{enum_name}"]
+    #![swig_rust_type_not_unique = "napi_value"]
+}}
+#[swig_to_foreigner_hint = "{enum_name}"]
+impl SwigFrom<{rust_enum_name}> for napi_value {{
+    fn swig_from(x: {rust_enum_name}, env: napi_env) -> napi_value {{
+        let value: i32 = match x {{
+{to_value}
+        }};
+        swig_napi_double_from(env, f64::from(value))
+    }}
+}}
+#[swig_from_foreigner_hint = "{enum_name}"]
+impl SwigInto<{rust_enum_name}> for napi_value {{
+    fn swig_into(self, env: napi_env) -> Option<{rust_enum_name}> {{
+        let value: i32 = swig_napi_int_as(env, self)?;
+        match value {{
+{from_value}
+            _ => {{
+                swig_napi_throw_range_error(
+                    env,
+                    &format!("{{}} is not a valid {enum_name}", value),
+                );
+                None
+            }}
+        }}
+    }}
+}}
+"#,
+        enum_name = enum_info.name,
+        rust_enum_name = rust_enum_name,
+        to_value = to_value,
+        from_value = from_value,
+    );
+    conv_map.register_exported_enum(enum_info);
+    conv_map.merge(SourceId::none(), &conv_code, pointer_target_width)?;
+
+    let add_enum_func_name = format!("swig_napi_add_enum_{}", enum_info.name);
+    let code = format!(
+        r#"
+#[allow(non_snake_case)]
+fn {add_enum_func_name}(env: napi_env, exports: napi_value) -> bool {{
+    swig_napi_add_enum(env, exports, "{enum_name}", &[{items}])
+}}
+"#,
+        enum_name = enum_info.name,
+        add_enum_func_name = add_enum_func_name,
+        items = items,
+    );
+    let code = syn::parse_str(&code)
+        .unwrap_or_else(|err| panic_on_syn_error("nodejs internal enum code", code, err));
+    Ok((vec![code], add_enum_func_name))
+}
+
+/// Generate Rust code for interface: struct that holds reference
+/// to JavaScript object and implements Rust trait
+pub(in crate::nodejs) fn generate_interface(
+    conv_map: &mut TypeMap,
+    pointer_target_width: usize,
+    interface: &ForeignInterface,
+    f_methods: &[NapiForeignMethodSignature],
+) -> Result<Vec<TokenStream>> {
+    let struct_name = format!("SwigNapiCallback{}", interface.name);
+    let trait_name = DisplayToTokens(&interface.self_type);
+
+    let conv_code = format!(
+        r#"
+mod swig_foreign_types_map {{
+    #![swig_foreigner_type = "{interface_name}"]
+    #![swig_rust_type_not_unique = "napi_value"]
+}}
+#[swig_from_foreigner_hint = "{interface_name}"]
+impl SwigInto<Box<{trait_name}>> for napi_value {{
+    fn swig_into(self, env: napi_env) -> Option<Box<{trait_name}>> {{
+        Some(Box::new({struct_name}(SwigNapiCallback::new(env, self)?)))
+    }}
+}}
+"#,
+        interface_name = interface.name,
+        trait_name = trait_name,
+        struct_name = struct_name,
+    );
+    conv_map.merge(SourceId::none(), &conv_code, pointer_target_width)?;
+
+    let mut gen_items = vec![];
+    let mut code = format!(
+        r#"
+pub struct {struct_name}(SwigNapiCallback);
+
+impl {trait_name} for {struct_name} {{
+"#,
+        struct_name = struct_name,
+        trait_name = trait_name,
+    );
+    for (method, f_method) in interface.items.iter().zip(f_methods) {
+        let func_name = method
+            .rust_name
+            .segments
+            .last()
+            .ok_or_else(|| {
+                DiagnosticError::new(
+                    interface.src_id,
+                    method.rust_name.span(),
+                    "Empty trait function name",
+                )
+            })?
+            .value()
+            .ident
+            .to_string();
+        let mut args_with_types = DisplayToTokens(&method.fn_decl.inputs[0]).to_string();
+        for (i, arg) in method.fn_decl.inputs.iter().skip(1).enumerate() {
+            write!(
+                &mut args_with_types,
+                ", a_{}: {}",
+                i,
+                DisplayToTokens(fn_arg_type(arg))
+            )
+            .unwrap();
+        }
+        let n_args = method.fn_decl.inputs.len() - 1;
+        let (mut conv_deps, convert_args) = rust_to_foreign_convert_method_inputs(
+            conv_map,
+            interface.src_id,
+            method,
+            f_method,
+            (0..n_args).map(|v| format!("a_{}", v)),
+            "()",
+        )?;
+        gen_items.append(&mut conv_deps);
+        let args = (0..n_args)
+            .map(|i| format!("a_{}", i))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let call = match method.fn_decl.output {
+            syn::ReturnType::Default => format!(
+                r#"
+        //if JavaScript code throws, exception will be rethrown after return to JavaScript
+        let _ = self.0.call(swig_c_str!("{method_name}"), &[{args}]);"#,
+                method_name = method.name,
+                args = args,
+            ),
+            syn::ReturnType::Type(_, ref ret_ty) => {
+                let real_output_type: RustType =
+                    conv_map.find_or_alloc_rust_type(ret_ty, interface.src_id);
+                let func_ret_type = format!("Option<{}>", real_output_type.normalized_name);
+                let (mut conv_deps, output_conv) = conv_map.convert_rust_types(
+                    &f_method.output.correspoding_rust_type,
+                    &real_output_type,
+                    "ret",
+                    &func_ret_type,
+                    (interface.src_id, ret_ty.span()),
+                )?;
+                gen_items.append(&mut conv_deps);
+                format!(
+                    r#"
+        let ret: {func_ret_type} = (|| {{
+            let ret: {ret_type} = self.0.call(swig_c_str!("{method_name}"), &[{args}])?;
+{output_conv}
+            Some(ret)
+        }})();
+        ret.expect("{interface_name}.{method_name}: JavaScript exception or invalid return value")"#,
+                    func_ret_type = func_ret_type,
+                    ret_type = unpack_unique_typename(
+                        &f_method.output.correspoding_rust_type.normalized_name
+                    ),
+                    method_name = method.name,
+                    interface_name = interface.name,
+                    args = args,
+                    output_conv = output_conv,
+                )
+            }
+        };
+        write!(
+            &mut code,
+            r#"
+    #[allow(unused_mut)]
+    fn {func_name}({args_with_types}) -> {real_ret_type} {{
+        let env: napi_env = self.0.env;
+{convert_args}{call}
+    }}
+"#,
+            func_name = func_name,
+            args_with_types = args_with_types,
+            real_ret_type = match method.fn_decl.output {
+                syn::ReturnType::Default => "()".to_string(),
+                syn::ReturnType::Type(_, ref ret_ty) => DisplayToTokens(ret_ty).to_string(),
+            },
+            convert_args = convert_args,
+            call = call,
+        )
+        .unwrap();
+    }
+    code.push_str("}\n");
+    gen_items.push(
+        syn::parse_str(&code)
+            .unwrap_or_else(|err| panic_on_syn_error("nodejs internal interface", code, err)),
+    );
+    Ok(gen_items)
+}
@@ -0,0 +1,567 @@
+mod rust_code;
+
+use std::{fmt::Write, io::Write as IoWrite};
+
+use petgraph::Direction;
+use proc_macro2::TokenStream;
+use syn::{parse_quote, spanned::Spanned, Type};
+
+use crate::{
+    error::{panic_on_syn_error, DiagnosticError, Result, SourceIdSpan},
+    file_cache::FileWriteCache,
+    source_registry::SourceId,
+    typemap::{
+        ast::{
+            fn_arg_type, if_result_return_ok_err_types, if_ty_result_return_ok_type,
+            parse_ty_with_given_span_checked, DisplayToTokens,
+        },
+        ty::RustType,
+        ForeignMethodSignature, ForeignTypeInfo, FROM_VAR_TEMPLATE, TO_VAR_TEMPLATE,
+    },
+    types::{
+        ForeignEnumInfo, ForeignFlagsInfo, ForeignInterface, ForeignStructInfo, ForeignerClassInfo,
+        ForeignerMethod, MethodAccess, MethodVariant,
+    },
+    LanguageGenerator, PythonConfig, TypeMap,
+};
+
+pub(in crate::python) struct PyForeignMethodSignature {
+    pub(in crate::python) output: ForeignTypeInfo,
+    pub(in crate::python) input: Vec<ForeignTypeInfo>,
+}
+
+impl ForeignMethodSignature for PyForeignMethodSignature {
+    type FI = ForeignTypeInfo;
+    fn output(&self) -> &ForeignTypeInfo {
+        &self.output
+    }
+    fn input(&self) -> &[ForeignTypeInfo] {
+        &self.input[..]
+    }
+}
+
+impl LanguageGenerator for PythonConfig {
+    fn register_class(&self, conv_map: &mut TypeMap, class: &ForeignerClassInfo) -> Result<()> {
+        class
+            .validate_class()
+            .map_err(|err| DiagnosticError::new(class.src_id, class.span(), &err))?;
+        if let Some(constructor_ret_type) = class.constructor_ret_type.as_ref() {
+            let this_type = if_ty_result_return_ok_type(constructor_ret_type)
+                .unwrap_or_else(|| constructor_ret_type.clone());
+            let this_type: RustType = conv_map.find_or_alloc_rust_type_that_implements(
+                &this_type,
+                "SwigForeignClass",
+                class.src_id,
+            );
+            let py_obj_ty = conv_map.find_or_alloc_rust_type_with_suffix(
+                &parse_type! { *mut PyObject },
+                &this_type.normalized_name,
+                SourceId::none(),
+            );
+            conv_map.cache_rust_to_foreign_conv(
+                &this_type,
+                ForeignTypeInfo {
+                    correspoding_rust_type: py_obj_ty.clone(),
+                    name: class.name.to_string().into(),
+                },
+            )?;
+            conv_map.add_conversation_rule(
+                this_type.clone(),
+                py_obj_ty.clone(),
+                format!(
+                    r#"
+    let mut {to_var}: *mut PyObject = swig_py_wrap_object::<{this_type}>({from_var});
+"#,
+                    to_var = TO_VAR_TEMPLATE,
+                    from_var = FROM_VAR_TEMPLATE,
+                    this_type = this_type.normalized_name,
+                )
+                .into(),
+            );
+            //foreigner_class passed by value, Python object becomes closed
+            conv_map.add_conversation_rule(
+                py_obj_ty.clone(),
+                this_type.clone(),
+                format!(
+                    r#"
+    let mut {to_var}: {this_type} = swig_py_try!(
+        swig_py_take_object::<{this_type}>({from_var}), {{function_ret_type}});
+"#,
+                    to_var = TO_VAR_TEMPLATE,
+                    from_var = FROM_VAR_TEMPLATE,
+                    this_type = this_type.normalized_name,
+                )
+                .into(),
+            );
+
+            let (this_type_for_method, _code_box_this) =
+                conv_map.convert_to_heap_pointer(&this_type, "this");
+            for &(ref_kind, ptr_kind) in &[("&", "*const"), ("&mut", "*mut")] {
+                let code = format!("{} {}", ref_kind, DisplayToTokens(&this_type_for_method.ty));
+                let gen_ty =
+                    parse_ty_with_given_span_checked(&code, this_type_for_method.ty.span());
+                let this_type_ref =
+                    conv_map.find_or_alloc_rust_type(&gen_ty, this_type_for_method.src_id);
+                conv_map.add_conversation_rule(
+                    py_obj_ty.clone(),
+                    this_type_ref,
+                    format!(
+                        r#"
+    let mut {to_var}: {ref_kind} {this_type_for_method} = unsafe {{
+        {ref_kind} *(swig_py_try!(swig_py_object_ptr::<{this_type}>({from_var}), {{function_ret_type}})
+            as {ptr_kind} {this_type_for_method})
+    }};
+"#,
+                        to_var = TO_VAR_TEMPLATE,
+                        from_var = FROM_VAR_TEMPLATE,
+                        ref_kind = ref_kind,
+                        ptr_kind = ptr_kind,
+                        this_type = this_type.normalized_name,
+                        this_type_for_method = this_type_for_method.normalized_name,
+                    )
+                    .into(),
+                );
+            }
+        }
+
+        let _ = conv_map.find_or_alloc_rust_type(&class.self_type_as_ty(), class.src_id);
+        Ok(())
+    }
+
+    fn generate(
+        &self,
+        conv_map: &mut TypeMap,
+        _: usize,
+        class: &ForeignerClassInfo,
+    ) -> Result<Vec<TokenStream>> {
+        let f_methods = find_suitable_foreign_types_for_methods(conv_map, class)?;
+        let (code, init_func) = rust_code::generate_class(
+            conv_map,
+            &self.module_name,
+            class,
+            &f_methods,
+            self.catch_panics,
+        )?;
+        self.module_init.borrow_mut().push(init_func);
+        generate_class_stub(&mut self.stubs.borrow_mut(), class, &f_methods);
+        Ok(code)
+    }
+
+    fn generate_enum(
+        &self,
+        conv_map: &mut TypeMap,
+        pointer_target_width: usize,
+        enum_info: &ForeignEnumInfo,
+    ) -> Result<Vec<TokenStream>> {
+        if enum_info.is_data_carrying() {
+            return Err(DiagnosticError::new(
+                enum_info.src_id,
+                enum_info.span(),
+                "Python: foreign_enum with data-carrying items is not supported yet",
+            ));
+        }
+        let (code, init_func) =
+            rust_code::generate_enum(conv_map, pointer_target_width, &self.module_name, enum_info)?;
+        self.module_init.borrow_mut().push(init_func);
+
+        let mut stubs = self.stubs.borrow_mut();
+        writeln!(&mut stubs, "\nclass {}(enum.IntEnum):", enum_info.name).unwrap();
+        stubs.push_str(&doc_comments_to_docstring(&enum_info.doc_comments, "    "));
+        for item in &enum_info.items {
+            writeln!(&mut stubs, "    {} = {}", item.name, item.value).unwrap();
+            stubs.push_str(&doc_comments_to_docstring(&item.doc_comments, "    "));
+        }
+        Ok(code)
+    }
+
+    fn generate_struct(
+        &self,
+        _conv_map: &mut TypeMap,
+        _pointer_target_width: usize,
+        struct_info: &ForeignStructInfo,
+    ) -> Result<Vec<TokenStream>> {
+        Err(DiagnosticError::new(
+            struct_info.src_id,
+            struct_info.span(),
+            "Python: foreign_struct is not supported yet",
+        ))
+    }
+
+    fn generate_flags(
+        &self,
+        _conv_map: &mut TypeMap,
+        _pointer_target_width: usize,
+        flags_info: &ForeignFlagsInfo,
+    ) -> Result<Vec<TokenStream>> {
+        Err(DiagnosticError::new(
+            flags_info.src_id,
+            flags_info.span(),
+            "Python: foreign_flags is not supported yet",
+        ))
+    }
+
+    fn generate_interface(
+        &self,
+        conv_map: &mut TypeMap,
+        pointer_target_width: usize,
+        interface: &ForeignInterface,
+    ) -> Result<Vec<TokenStream>> {
+        let f_methods = find_suitable_foreign_types_for_interface_methods(conv_map, interface)?;
+        let code =
+            rust_code::generate_interface(conv_map, pointer_target_width, interface, &f_methods)?;
+
+        let mut stubs = self.stubs.borrow_mut();
+        writeln!(&mut stubs, "\nclass {}(Protocol):", interface.name).unwrap();
+        stubs.push_str(&doc_comments_to_docstring(&interface.doc_comments, "    "));
+        if interface.items.is_empty() {
+            stubs.push_str("    ...\n");
+        }
+        for (method, f_method) in interface.items.iter().zip(&f_methods) {
+            let mut args = String::new();
+            for (i, arg) in f_method.input.iter().enumerate() {
+                write!(&mut args, ", a{}: {}", i, python_type_name(arg)).unwrap();
+            }
+            write!(
+                &mut stubs,
+                "    def {}(self{}) -> {}",
+                method.name,
+                args,
+                python_type_name(&f_method.output)
+            )
+            .unwrap();
+            if method.doc_comments.is_empty() {
+                stubs.push_str(": ...\n");
+            } else {
+                stubs.push_str(":\n");
+                stubs.push_str(&doc_comments_to_docstring(&method.doc_comments, "        "));
+                stubs.push_str("        ...\n");
+            }
+        }
+        Ok(code)
+    }
+
+    fn finish(&self, _conv_map: &mut TypeMap) -> Result<Vec<TokenStream>> {
+        let mut stubs_file = format!(
+            r#"# Automaticaly generated by rust_swig
+import enum
+from typing import Any, List, Optional, Protocol, overload
+{stubs}"#,
+            stubs = self.stubs.borrow()
+        );
+        if !stubs_file.ends_with('\n') {
+            stubs_file.push('\n');
+        }
+        let path = self.output_dir.join(format!("{}.pyi", self.module_name));
+        let mut file = FileWriteCache::new(&path);
+        file.write_all(stubs_file.as_bytes())
+            .and_then(|_| file.update_file_if_necessary())
+            .map_err(|err| {
+                DiagnosticError::new_without_src_info(format!(
+                    "write to {} failed: {}",
+                    path.display(),
+                    err
+                ))
+            })?;
+
+        let module_init = self.module_init.borrow();
+        let init_items = if module_init.is_empty() {
+            String::new()
+        } else {
+            let mut cond = String::new();
+            for (i, init_func) in module_init.iter().enumerate() {
+                if i > 0 {
+                    cond.push_str(" || ");
+                }
+                write!(&mut cond, "!{}(module)", init_func).unwrap();
+            }
+            format!(
+                r#"
+    if {cond} {{
+        unsafe {{ Py_DecRef(module) }};
+        return ::std::ptr::null_mut();
+    }}"#,
+                cond = cond
+            )
+        };
+        let code = format!(
+            r#"
+#[allow(non_snake_case)]
+#[no_mangle]
+pub extern "C" fn PyInit_{module_name}() -> *mut PyObject {{
+    let module = swig_py_create_module(swig_c_str!("{module_name}"));
+    if module.is_null() {{
+        return module;
+    }}{init_items}
+    module
+}}
+"#,
+            module_name = self.module_name,
+            init_items = init_items,
+        );
+        Ok(vec![syn::parse_str(&code).unwrap_or_else(|err| {
+            panic_on_syn_error("python internal module init", code, err)
+        })])
+    }
+}
+
+pub(in crate::python) fn calc_this_type_for_method(
+    tm: &TypeMap,
+    class: &ForeignerClassInfo,
+) -> Option<Type> {
+    class
+        .constructor_ret_type
+        .as_ref()
+        .map(|constructor_ret_type| {
+            let ret_ty = tm
+                .ty_to_rust_type_checked(constructor_ret_type)
+                .unwrap_or_else(|| {
+                    panic!(
+                        "Internal error: constructor type {} for class {} unknown",
+                        DisplayToTokens(constructor_ret_type),
+                        class.name
+                    );
+                });
+            if_result_return_ok_err_types(&ret_ty)
+                .map(|(ok_ty, _err_ty)| ok_ty)
+                .unwrap_or_else(|| constructor_ret_type.clone())
+        })
+}
+
+fn map_type(
+    conv_map: &mut TypeMap,
+    arg_ty: &RustType,
+    direction: Direction,
+    arg_ty_span: SourceIdSpan,
+) -> Result<ForeignTypeInfo> {
+    let ftype = conv_map
+        .map_through_conversation_to_foreign(
+            arg_ty,
+            direction,
+            arg_ty_span,
+            calc_this_type_for_method,
+        )
+        .ok_or_else(|| {
+            let err = DiagnosticError::new2(
+                arg_ty_span,
+                format!(
+                    "can not find conversation Python type {} Rust type '{}'",
+                    match direction {
+                        Direction::Outgoing => "=>",
+                        Direction::Incoming => "<=",
+                    },
+                    arg_ty,
+                ),
+            );
+            conv_map.explain_missing_foreign_type(err, arg_ty, direction)
+        })?;
+    let ftype = &conv_map[ftype];
+    let rule = match direction {
+        Direction::Outgoing => ftype.into_from_rust.as_ref(),
+        Direction::Incoming => ftype.from_into_rust.as_ref(),
+    }
+    .expect("Internal error: rule for foreign type not defined");
+    if rule.intermediate.is_some() {
+        return Err(DiagnosticError::new2(
+            arg_ty_span,
+            format!(
+                "Python: conversation of '{}' via intermediate type is not supported yet",
+                arg_ty
+            ),
+        ));
+    }
+    let name = ftype.name.typename.clone();
+    Ok(ForeignTypeInfo {
+        name,
+        correspoding_rust_type: conv_map[rule.rust_ty].clone(),
+    })
+}
+
+fn find_suitable_foreign_types_for_methods(
+    conv_map: &mut TypeMap,
+    class: &ForeignerClassInfo,
+) -> Result<Vec<PyForeignMethodSignature>> {
+    let mut ret = Vec::with_capacity(class.methods.len());
+    let dummy_ty = parse_type! { () };
+    let dummy_rust_ty = conv_map.find_or_alloc_rust_type_no_src_id(&dummy_ty);
+
+    for method in &class.methods {
+        //skip self argument
+        let skip_n = match method.variant {
+            MethodVariant::Method(_) => 1,
+            _ => 0,
+        };
+        let mut input = Vec::with_capacity(method.fn_decl.inputs.len() - skip_n);
+        for arg in method.fn_decl.inputs.iter().skip(skip_n) {
+            let arg_rust_ty = conv_map.find_or_alloc_rust_type(fn_arg_type(arg), class.src_id);
+            input.push(map_type(
+                conv_map,
+                &arg_rust_ty,
+                Direction::Incoming,
+                (class.src_id, fn_arg_type(arg).span()),
+            )?);
+        }
+        let void_output = ForeignTypeInfo {
+            name: "void".into(),
+            correspoding_rust_type: dummy_rust_ty.clone(),
+        };
+        let output = match (method.variant, &method.fn_decl.output) {
+            (MethodVariant::Constructor, _) | (_, syn::ReturnType::Default) => void_output,
+            (_, syn::ReturnType::Type(_, ref rt)) => {
+                //Err(_) raises Python exception, so map only `T` of `Result<T, E>`
+                let ret_ty = if_ty_result_return_ok_type(rt).unwrap_or_else(|| (**rt).clone());
+                let ret_rust_ty = conv_map.find_or_alloc_rust_type(&ret_ty, class.src_id);
+                map_type(
+                    conv_map,
+                    &ret_rust_ty,
+                    Direction::Outgoing,
+                    (class.src_id, rt.span()),
+                )?
+            }
+        };
+        ret.push(PyForeignMethodSignature { output, input });
+    }
+    Ok(ret)
+}
+
+/// Arguments are passed from Rust to Python, and result from Python to Rust
+fn find_suitable_foreign_types_for_interface_methods(
+    conv_map: &mut TypeMap,
+    interface: &ForeignInterface,
+) -> Result<Vec<PyForeignMethodSignature>> {
+    let mut ret = Vec::with_capacity(interface.items.len());
+    let dummy_ty = parse_type! { () };
+    let dummy_rust_ty = conv_map.find_or_alloc_rust_type_no_src_id(&dummy_ty);
+    for method in &interface.items {
+        let mut input = Vec::with_capacity(method.fn_decl.inputs.len() - 1);
+        for arg in method.fn_decl.inputs.iter().skip(1) {
+            let arg_rust_ty = conv_map.find_or_alloc_rust_type(fn_arg_type(arg), interface.src_id);
+            input.push(map_type(
+                conv_map,
+                &arg_rust_ty,
+                Direction::Outgoing,
+                (interface.src_id, fn_arg_type(arg).span()),
+            )?);
+        }
+        let output = match method.fn_decl.output {
+            syn::ReturnType::Default => ForeignTypeInfo {
+                name: "void".into(),
+                correspoding_rust_type: dummy_rust_ty.clone(),
+            },
+            syn::ReturnType::Type(_, ref rt) => {
+                let ret_rust_ty = conv_map.find_or_alloc_rust_type(rt, interface.src_id);
+                map_type(
+                    conv_map,
+                    &ret_rust_ty,
+                    Direction::Incoming,
+                    (interface.src_id, rt.span()),
+                )?
+            }
+        };
+        ret.push(PyForeignMethodSignature { output, input });
+    }
+    Ok(ret)
+}
+
+/// Name of method visible from Python
+pub(in crate::python) fn method_name(method: &ForeignerMethod) -> String {
+    match method.access {
+        MethodAccess::Public => method.short_name(),
+        MethodAccess::Private | MethodAccess::Protected => format!("_{}", method.short_name()),
+    }
+}
+
+fn python_type_name(fti: &ForeignTypeInfo) -> &str {
+    match fti.name.as_str() {
+        "void" => "None",
+        name => name,
+    }
+}
+
+fn generate_class_stub(
+    stubs: &mut String,
+    class: &ForeignerClassInfo,
+    f_methods: &[PyForeignMethodSignature],
+) {
+    let class_name = class.name.to_string();
+    writeln!(stubs, "\nclass {}:", class_name).unwrap();
+    stubs.push_str(&doc_comments_to_docstring(&class.doc_comments, "    "));
+    if class.methods.is_empty() {
+        stubs.push_str("    ...\n");
+    }
+    let n_constructors = class
+        .methods
+        .iter()
+        .filter(|m| m.variant == MethodVariant::Constructor && !m.is_dummy_constructor())
+        .count();
+    for (method, f_method) in class.methods.iter().zip(f_methods) {
+        let mut args = String::new();
+        for (i, arg) in f_method.input.iter().enumerate() {
+            write!(&mut args, ", a{}: {}", i, python_type_name(arg)).unwrap();
+        }
+        match method.variant {
+            MethodVariant::Constructor => {
+                if method.is_dummy_constructor() {
+                    continue;
+                }
+                if n_constructors > 1 {
+                    stubs.push_str("    @overload\n");
+                }
+                write!(stubs, "    def __init__(self{}) -> None", args).unwrap();
+            }
+            MethodVariant::StaticMethod => {
+                stubs.push_str("    @staticmethod\n");
+                write!(
+                    stubs,
+                    "    def {}({}) -> {}",
+                    method_name(method),
+                    args.trim_start_matches(", "),
+                    python_type_name(&f_method.output)
+                )
+                .unwrap();
+            }
+            MethodVariant::Method(_) => {
+                write!(
+                    stubs,
+                    "    def {}(self{}) -> {}",
+                    method_name(method),
+                    args,
+                    python_type_name(&f_method.output)
+                )
+                .unwrap();
+            }
+        }
+        if method.doc_comments.is_empty() {
+            stubs.push_str(": ...\n");
+        } else {
+            stubs.push_str(":\n");
+            stubs.push_str(&doc_comments_to_docstring(&method.doc_comments, "        "));
+            stubs.push_str("        ...\n");
+        }
+    }
+    if class.self_type.is_some() {
+        writeln!(
+            stubs,
+            r#"    def close(self) -> None: ...
+    def __enter__(self) -> "{class_name}": ...
+    def __exit__(self, *args: Any) -> None: ...
+    def __del__(self) -> None: ..."#,
+            class_name = class_name
+        )
+        .unwrap();
+    }
+}
+
+fn doc_comments_to_docstring(doc_comments: &[String], indent: &str) -> String {
+    if doc_comments.is_empty() {
+        return String::new();
+    }
+    let mut docstring = format!("{}\"\"\"", indent);
+    for (i, comment) in doc_comments.iter().enumerate() {
+        if i > 0 {
+            write!(&mut docstring, "\n{}", indent).unwrap();
+        }
+        docstring.push_str(comment.trim());
+    }
+    docstring.push_str("\"\"\"\n");
+    docstring
+}
@@ -0,0 +1,1385 @@
+mod swig_foreign_types_map {
+    #![swig_foreigner_type = "None"]
+    #![swig_rust_type = "()"]
+    #![swig_foreigner_type = "bool"]
+    #![swig_rust_type = "PyBool"]
+    #![swig_foreigner_type = "int"]
+    #![swig_rust_type = "PyInt"]
+    #![swig_foreigner_type = "float"]
+    #![swig_rust_type = "PyFloat"]
+    #![swig_foreigner_type = "str"]
+    #![swig_rust_type = "PyStr"]
+    #![swig_foreigner_type = "Optional[bool]"]
+    #![swig_rust_type = "PyOptBool"]
+    #![swig_foreigner_type = "Optional[int]"]
+    #![swig_rust_type = "PyOptInt"]
+    #![swig_foreigner_type = "Optional[float]"]
+    #![swig_rust_type = "PyOptFloat"]
+    #![swig_foreigner_type = "Optional[str]"]
+    #![swig_rust_type = "PyOptStr"]
+    #![swig_foreigner_type = "List[int]"]
+    #![swig_rust_type = "PyIntList"]
+    #![swig_foreigner_type = "List[float]"]
+    #![swig_rust_type = "PyFloatList"]
+    #![swig_foreigner_type = "List[str]"]
+    #![swig_rust_type = "PyStrList"]
+}
+
+#[allow(unused_macros)]
+macro_rules! swig_c_str {
+    ($lit:expr) => {
+        concat!($lit, "\0").as_ptr() as *const ::std::os::raw::c_char
+    };
+}
+
+/// Part of CPython API, see `object.h`, `methodobject.h` and `moduleobject.h`,
+/// only stable ABI is used
+#[allow(dead_code)]
+#[repr(C)]
+pub struct PyObject {
+    ob_refcnt: isize,
+    ob_type: *mut PyObject,
+}
+
+#[allow(dead_code)]
+type PyBool = *mut PyObject;
+#[allow(dead_code)]
+type PyInt = *mut PyObject;
+#[allow(dead_code)]
+type PyFloat = *mut PyObject;
+#[allow(dead_code)]
+type PyStr = *mut PyObject;
+#[allow(dead_code)]
+type PyOptBool = *mut PyObject;
+#[allow(dead_code)]
+type PyOptInt = *mut PyObject;
+#[allow(dead_code)]
+type PyOptFloat = *mut PyObject;
+#[allow(dead_code)]
+type PyOptStr = *mut PyObject;
+#[allow(dead_code)]
+type PyIntList = *mut PyObject;
+#[allow(dead_code)]
+type PyFloatList = *mut PyObject;
+#[allow(dead_code)]
+type PyStrList = *mut PyObject;
+
+#[allow(dead_code)]
+type PyCFunction = extern "C" fn(*mut PyObject, *mut PyObject) -> *mut PyObject;
+
+#[allow(dead_code)]
+#[repr(C)]
+pub struct PyMethodDef {
+    ml_name: *const ::std::os::raw::c_char,
+    ml_meth: Option<PyCFunction>,
+    ml_flags: ::std::os::raw::c_int,
+    ml_doc: *const ::std::os::raw::c_char,
+}
+
+#[allow(dead_code, non_camel_case_types)]
+#[repr(C)]
+pub struct PyType_Slot {
+    slot: ::std::os::raw::c_int,
+    pfunc: *mut ::std::os::raw::c_void,
+}
+
+#[allow(dead_code, non_camel_case_types)]
+#[repr(C)]
+pub struct PyType_Spec {
+    name: *const ::std::os::raw::c_char,
+    basicsize: ::std::os::raw::c_int,
+    itemsize: ::std::os::raw::c_int,
+    flags: ::std::os::raw::c_uint,
+    slots: *mut PyType_Slot,
+}
+
+#[allow(dead_code, non_camel_case_types)]
+#[repr(C)]
+pub struct PyModuleDef_Base {
+    ob_base: PyObject,
+    m_init: Option<extern "C" fn() -> *mut PyObject>,
+    m_index: isize,
+    m_copy: *mut PyObject,
+}
+
+#[allow(dead_code)]
+#[repr(C)]
+pub struct PyModuleDef {
+    m_base: PyModuleDef_Base,
+    m_name: *const ::std::os::raw::c_char,
+    m_doc: *const ::std::os::raw::c_char,
+    m_size: isize,
+    m_methods: *mut PyMethodDef,
+    m_slots: *mut ::std::os::raw::c_void,
+    m_traverse: *mut ::std::os::raw::c_void,
+    m_clear: *mut ::std::os::raw::c_void,
+    m_free: *mut ::std::os::raw::c_void,
+}
+
+#[allow(dead_code)]
+const PYTHON_API_VERSION: ::std::os::raw::c_int = 1013;
+#[allow(dead_code)]
+const METH_VARARGS: ::std::os::raw::c_int = 0x0001;
+#[allow(dead_code)]
+const METH_STATIC: ::std::os::raw::c_int = 0x0020;
+#[allow(dead_code)]
+const PY_TPFLAGS_DEFAULT: ::std::os::raw::c_uint = 1 << 18;
+#[allow(dead_code)]
+const PY_TP_DEALLOC: ::std::os::raw::c_int = 52;
+#[allow(dead_code)]
+const PY_TP_DOC: ::std::os::raw::c_int = 56;
+#[allow(dead_code)]
+const PY_TP_INIT: ::std::os::raw::c_int = 60;
+#[allow(dead_code)]
+const PY_TP_METHODS: ::std::os::raw::c_int = 64;
+#[allow(dead_code)]
+const PY_TP_NEW: ::std::os::raw::c_int = 65;
+#[allow(dead_code)]
+const PY_TP_FREE: ::std::os::raw::c_int = 74;
+#[allow(dead_code)]
+const PY_TP_FINALIZE: ::std::os::raw::c_int = 80;
+
+#[allow(dead_code, improper_ctypes)]
+extern "C" {
+    static PyExc_TypeError: *mut PyObject;
+    static PyExc_ValueError: *mut PyObject;
+    static PyExc_OverflowError: *mut PyObject;
+    static PyExc_RuntimeError: *mut PyObject;
+    static _Py_NoneStruct: PyObject;
+
+    fn Py_IncRef(o: *mut PyObject);
+    fn Py_DecRef(o: *mut PyObject);
+    fn PyErr_SetString(exc: *mut PyObject, msg: *const ::std::os::raw::c_char);
+    fn PyErr_Occurred() -> *mut PyObject;
+    fn PyErr_WriteUnraisable(obj: *mut PyObject);
+    fn PyGILState_Ensure() -> ::std::os::raw::c_int;
+    fn PyGILState_Release(state: ::std::os::raw::c_int);
+
+    fn PyTuple_New(size: isize) -> *mut PyObject;
+    fn PyTuple_Size(t: *mut PyObject) -> isize;
+    fn PyTuple_GetItem(t: *mut PyObject, pos: isize) -> *mut PyObject;
+    fn PyTuple_SetItem(t: *mut PyObject, pos: isize, o: *mut PyObject) -> ::std::os::raw::c_int;
+    fn PyList_New(size: isize) -> *mut PyObject;
+    fn PyList_SetItem(l: *mut PyObject, pos: isize, o: *mut PyObject) -> ::std::os::raw::c_int;
+    fn PySequence_Size(o: *mut PyObject) -> isize;
+    fn PySequence_GetItem(o: *mut PyObject, i: isize) -> *mut PyObject;
+    fn PyBool_FromLong(v: ::std::os::raw::c_long) -> *mut PyObject;
+    fn PyObject_IsTrue(o: *mut PyObject) -> ::std::os::raw::c_int;
+    fn PyLong_FromLongLong(v: i64) -> *mut PyObject;
+    fn PyLong_FromUnsignedLongLong(v: u64) -> *mut PyObject;
+    fn PyLong_AsLongLong(o: *mut PyObject) -> i64;
+    fn PyLong_AsUnsignedLongLong(o: *mut PyObject) -> u64;
+    fn PyFloat_FromDouble(v: f64) -> *mut PyObject;
+    fn PyFloat_AsDouble(o: *mut PyObject) -> f64;
+    fn PyUnicode_FromStringAndSize(
+        s: *const ::std::os::raw::c_char,
+        size: isize,
+    ) -> *mut PyObject;
+    fn PyUnicode_AsUTF8AndSize(o: *mut PyObject, size: *mut isize)
+        -> *const ::std::os::raw::c_char;
+    fn PyDict_New() -> *mut PyObject;
+    fn PyDict_SetItemString(
+        d: *mut PyObject,
+        key: *const ::std::os::raw::c_char,
+        v: *mut PyObject,
+    ) -> ::std::os::raw::c_int;
+
+    fn PyObject_IsInstance(o: *mut PyObject, cls: *mut PyObject) -> ::std::os::raw::c_int;
+    fn PyObject_GetAttrString(
+        o: *mut PyObject,
+        name: *const ::std::os::raw::c_char,
+    ) -> *mut PyObject;
+    fn PyObject_SetAttrString(
+        o: *mut PyObject,
+        name: *const ::std::os::raw::c_char,
+        v: *mut PyObject,
+    ) -> ::std::os::raw::c_int;
+    fn PyObject_CallFunctionObjArgs(callable: *mut PyObject, ...) -> *mut PyObject;
+    fn PyObject_CallObject(callable: *mut PyObject, args: *mut PyObject) -> *mut PyObject;
+    fn PyImport_ImportModule(name: *const ::std::os::raw::c_char) -> *mut PyObject;
+
+    fn PyType_FromSpec(spec: *mut PyType_Spec) -> *mut PyObject;
+    fn PyType_GenericAlloc(tp: *mut PyObject, nitems: isize) -> *mut PyObject;
+    fn PyType_GenericNew(
+        tp: *mut PyObject,
+        args: *mut PyObject,
+        kwds: *mut PyObject,
+    ) -> *mut PyObject;
+    fn PyType_GetSlot(tp: *mut PyObject, slot: ::std::os::raw::c_int)
+        -> *mut ::std::os::raw::c_void;
+    fn PyModule_Create2(def: *mut PyModuleDef, apiver: ::std::os::raw::c_int) -> *mut PyObject;
+    fn PyModule_AddObject(
+        m: *mut PyObject,
+        name: *const ::std::os::raw::c_char,
+        o: *mut PyObject,
+    ) -> ::std::os::raw::c_int;
+}
+
+/// Instance of Python class generated for `foreigner_class!`
+#[allow(dead_code)]
+#[repr(C)]
+pub struct SwigPyObject {
+    ob_base: PyObject,
+    ptr: *mut ::std::os::raw::c_void,
+}
+
+#[allow(dead_code)]
+pub trait SwigForeignClass {
+    fn py_class_name() -> &'static str;
+    fn py_type() -> *mut PyObject;
+    fn box_object(x: Self) -> *mut ::std::os::raw::c_void;
+    fn unbox_object(p: *mut ::std::os::raw::c_void) -> Self;
+}
+
+#[allow(dead_code)]
+trait SwigPyInvalidValue {
+    fn swig_py_invalid_value() -> Self;
+}
+
+impl SwigPyInvalidValue for *mut PyObject {
+    fn swig_py_invalid_value() -> Self {
+        ::std::ptr::null_mut()
+    }
+}
+
+impl SwigPyInvalidValue for ::std::os::raw::c_int {
+    fn swig_py_invalid_value() -> Self {
+        -1
+    }
+}
+
+impl<T> SwigPyInvalidValue for Option<T> {
+    fn swig_py_invalid_value() -> Self {
+        None
+    }
+}
+
+/// Return from function with error indicator if conversation failed,
+/// Python exception should be already set
+#[allow(unused_macros)]
+macro_rules! swig_py_try {
+    ($value:expr, $func_ret_type:ty) => {
+        match $value {
+            Some(x) => x,
+            None => return <$func_ret_type>::swig_py_invalid_value(),
+        }
+    };
+}
+
+#[allow(dead_code)]
+#[swig_code = "let mut {to_var}: {to_var_type} = swig_py_try!({from_var}.swig_into(), {function_ret_type});"]
+trait SwigInto<T> {
+    /// `None` means that Python exception was set
+    fn swig_into(self) -> Option<T>;
+}
+
+#[allow(dead_code)]
+#[swig_code = "let mut {to_var}: {to_var_type} = <{to_var_type}>::swig_from({from_var});"]
+trait SwigFrom<T> {
+    fn swig_from(_: T) -> Self;
+}
+
+#[allow(dead_code)]
+#[swig_code = "let mut {to_var}: {to_var_type} = {from_var}.swig_deref();"]
+trait SwigDeref {
+    type Target: ?Sized;
+    fn swig_deref(&self) -> &Self::Target;
+}
+
+#[allow(dead_code)]
+#[swig_code = "let mut {to_var}: {to_var_type} = {from_var}.swig_deref_mut();"]
+trait SwigDerefMut {
+    type Target: ?Sized;
+    fn swig_deref_mut(&mut self) -> &mut Self::Target;
+}
+
+#[allow(dead_code)]
+fn swig_py_set_error(exc: *mut PyObject, msg: &str) {
+    let msg = ::std::ffi::CString::new(msg.replace('\0', "\\0")).unwrap();
+    unsafe { PyErr_SetString(exc, msg.as_ptr()) };
+}
+
+#[allow(dead_code)]
+fn swig_py_set_panic_error(err: Box<dyn ::std::any::Any + Send>) {
+    let msg = if let Some(msg) = err.downcast_ref::<&str>() {
+        msg.to_string()
+    } else if let Some(msg) = err.downcast_ref::<String>() {
+        msg.clone()
+    } else {
+        "unknown panic payload".to_string()
+    };
+    swig_py_set_error(unsafe { PyExc_RuntimeError }, &format!("Rust panic: {}", msg));
+}
+
+#[allow(dead_code)]
+fn swig_py_none() -> *mut PyObject {
+    unsafe {
+        let none = &_Py_NoneStruct as *const PyObject as *mut PyObject;
+        Py_IncRef(none);
+        none
+    }
+}
+
+#[allow(dead_code)]
+fn swig_py_bool_as(x: *mut PyObject) -> Option<bool> {
+    match unsafe { PyObject_IsTrue(x) } {
+        -1 => None,
+        x => Some(x != 0),
+    }
+}
+
+#[allow(dead_code)]
+fn swig_py_is_none(x: *mut PyObject) -> bool {
+    x == unsafe { &_Py_NoneStruct as *const PyObject as *mut PyObject }
+}
+
+#[allow(dead_code)]
+fn swig_py_opt_from<T, F: Fn(T) -> *mut PyObject>(x: Option<T>, conv: F) -> *mut PyObject {
+    match x {
+        Some(x) => conv(x),
+        None => swig_py_none(),
+    }
+}
+
+#[allow(dead_code)]
+fn swig_py_opt_as<T, F: Fn(*mut PyObject) -> Option<T>>(
+    x: *mut PyObject,
+    conv: F,
+) -> Option<Option<T>> {
+    if swig_py_is_none(x) {
+        Some(None)
+    } else {
+        conv(x).map(Some)
+    }
+}
+
+#[allow(dead_code)]
+fn swig_py_list_from<T, F: Fn(T) -> *mut PyObject>(x: Vec<T>, conv: F) -> *mut PyObject {
+    let list = unsafe { PyList_New(x.len() as isize) };
+    if list.is_null() {
+        return list;
+    }
+    for (i, elem) in x.into_iter().enumerate() {
+        let elem = conv(elem);
+        if elem.is_null() {
+            unsafe { Py_DecRef(list) };
+            return ::std::ptr::null_mut();
+        }
+        //reference to `elem` is stolen by list
+        unsafe { PyList_SetItem(list, i as isize, elem) };
+    }
+    list
+}
+
+/// Accept any Python sequence, not only `list`
+#[allow(dead_code)]
+fn swig_py_list_as<T, F: Fn(*mut PyObject) -> Option<T>>(
+    x: *mut PyObject,
+    conv: F,
+) -> Option<Vec<T>> {
+    let len = unsafe { PySequence_Size(x) };
+    if len < 0 {
+        return None;
+    }
+    let mut ret = Vec::with_capacity(len as usize);
+    for i in 0..len {
+        let elem = unsafe { PySequence_GetItem(x, i) };
+        if elem.is_null() {
+            return None;
+        }
+        let value = conv(elem);
+        unsafe { Py_DecRef(elem) };
+        ret.push(value?);
+    }
+    Some(ret)
+}
+
+#[allow(dead_code)]
+fn swig_py_check_args(args: *mut PyObject, n: isize, func_name: &str) -> bool {
+    let given = unsafe { PyTuple_Size(args) };
+    if given != n {
+        swig_py_set_error(
+            unsafe { PyExc_TypeError },
+            &format!(
+                "{}() takes {} positional arguments but {} were given",
+                func_name, n, given
+            ),
+        );
+        false
+    } else {
+        true
+    }
+}
+
+#[allow(dead_code)]
+fn swig_py_str_from(x: &str) -> *mut PyObject {
+    unsafe {
+        PyUnicode_FromStringAndSize(x.as_ptr() as *const ::std::os::raw::c_char, x.len() as isize)
+    }
+}
+
+#[allow(dead_code)]
+fn swig_py_str_as<'a>(x: *mut PyObject) -> Option<&'a str> {
+    let mut size: isize = 0;
+    let data = unsafe { PyUnicode_AsUTF8AndSize(x, &mut size) };
+    if data.is_null() {
+        return None;
+    }
+    let bytes = unsafe { ::std::slice::from_raw_parts(data as *const u8, size as usize) };
+    Some(unsafe { ::std::str::from_utf8_unchecked(bytes) })
+}
+
+#[allow(dead_code)]
+fn swig_py_float_as(x: *mut PyObject) -> Option<f64> {
+    let v = unsafe { PyFloat_AsDouble(x) };
+    if v == -1.0 && unsafe { !PyErr_Occurred().is_null() } {
+        None
+    } else {
+        Some(v)
+    }
+}
+
+#[allow(dead_code)]
+fn swig_py_long_as<T: ::std::convert::TryFrom<i64>>(x: *mut PyObject) -> Option<T> {
+    let v = unsafe { PyLong_AsLongLong(x) };
+    if v == -1 && unsafe { !PyErr_Occurred().is_null() } {
+        return None;
+    }
+    match <T as ::std::convert::TryFrom<i64>>::try_from(v) {
+        Ok(v) => Some(v),
+        Err(_) => {
+            swig_py_set_error(
+                unsafe { PyExc_OverflowError },
+                &format!("{} out of range of Rust type", v),
+            );
+            None
+        }
+    }
+}
+
+#[allow(dead_code)]
+fn swig_py_long_as_unsigned<T: ::std::convert::TryFrom<u64>>(x: *mut PyObject) -> Option<T> {
+    let v = unsafe { PyLong_AsUnsignedLongLong(x) };
+    if v == u64::max_value() && unsafe { !PyErr_Occurred().is_null() } {
+        return None;
+    }
+    match <T as ::std::convert::TryFrom<u64>>::try_from(v) {
+        Ok(v) => Some(v),
+        Err(_) => {
+            swig_py_set_error(
+                unsafe { PyExc_OverflowError },
+                &format!("{} out of range of Rust type", v),
+            );
+            None
+        }
+    }
+}
+
+#[allow(dead_code)]
+fn swig_py_object_ptr<T: SwigForeignClass>(obj: *mut PyObject) -> Option<*mut ::std::os::raw::c_void> {
+    match unsafe { PyObject_IsInstance(obj, T::py_type()) } {
+        1 => {}
+        0 => {
+            swig_py_set_error(
+                unsafe { PyExc_TypeError },
+                &format!("expected {} instance", T::py_class_name()),
+            );
+            return None;
+        }
+        _ => return None,
+    }
+    let p = unsafe { (*(obj as *mut SwigPyObject)).ptr };
+    if p.is_null() {
+        swig_py_set_error(
+            unsafe { PyExc_ValueError },
+            &format!("{} object is closed", T::py_class_name()),
+        );
+        None
+    } else {
+        Some(p)
+    }
+}
+
+/// Move Rust object out of Python object, after that Python object is closed
+#[allow(dead_code)]
+fn swig_py_take_object<T: SwigForeignClass>(obj: *mut PyObject) -> Option<T> {
+    let p = swig_py_object_ptr::<T>(obj)?;
+    unsafe { (*(obj as *mut SwigPyObject)).ptr = ::std::ptr::null_mut() };
+    Some(T::unbox_object(p))
+}
+
+#[allow(dead_code)]
+fn swig_py_wrap_object<T: SwigForeignClass>(x: T) -> *mut PyObject {
+    let obj = unsafe { PyType_GenericAlloc(T::py_type(), 0) };
+    if !obj.is_null() {
+        unsafe { (*(obj as *mut SwigPyObject)).ptr = T::box_object(x) };
+    }
+    obj
+}
+
+/// Store Rust object created by constructor inside Python object
+#[allow(dead_code)]
+fn swig_py_init_object<T: SwigForeignClass>(obj: *mut PyObject, x: T) {
+    swig_py_release_object::<T>(obj);
+    unsafe { (*(obj as *mut SwigPyObject)).ptr = T::box_object(x) };
+}
+
+#[allow(dead_code)]
+fn swig_py_release_object<T: SwigForeignClass>(obj: *mut PyObject) {
+    let p = unsafe { (*(obj as *mut SwigPyObject)).ptr };
+    if !p.is_null() {
+        unsafe { (*(obj as *mut SwigPyObject)).ptr = ::std::ptr::null_mut() };
+        drop(T::unbox_object(p));
+    }
+}
+
+#[allow(dead_code)]
+extern "C" fn swig_py_dealloc<T: SwigForeignClass>(obj: *mut PyObject) {
+    swig_py_release_object::<T>(obj);
+    unsafe {
+        let tp = (*obj).ob_type;
+        let free: extern "C" fn(*mut ::std::os::raw::c_void) =
+            ::std::mem::transmute(PyType_GetSlot(tp, PY_TP_FREE));
+        free(obj as *mut ::std::os::raw::c_void);
+        Py_DecRef(tp);
+    }
+}
+
+/// `__del__`
+#[allow(dead_code)]
+extern "C" fn swig_py_finalize<T: SwigForeignClass>(obj: *mut PyObject) {
+    swig_py_release_object::<T>(obj);
+}
+
+/// `close` and `__exit__`
+#[allow(dead_code)]
+extern "C" fn swig_py_close<T: SwigForeignClass>(
+    obj: *mut PyObject,
+    _: *mut PyObject,
+) -> *mut PyObject {
+    swig_py_release_object::<T>(obj);
+    swig_py_none()
+}
+
+/// `__enter__`
+#[allow(dead_code)]
+extern "C" fn swig_py_enter(obj: *mut PyObject, _: *mut PyObject) -> *mut PyObject {
+    unsafe { Py_IncRef(obj) };
+    obj
+}
+
+/// Methods and slots that manage lifetime of Rust object inside Python object
+#[allow(dead_code)]
+fn swig_py_object_lifetime<T: SwigForeignClass>(
+    methods: &mut Vec<PyMethodDef>,
+    slots: &mut Vec<PyType_Slot>,
+) {
+    methods.push(PyMethodDef {
+        ml_name: swig_c_str!("close"),
+        ml_meth: Some(swig_py_close::<T>),
+        ml_flags: METH_VARARGS,
+        ml_doc: swig_c_str!("Free Rust object, after that usage of this object raises ValueError"),
+    });
+    methods.push(PyMethodDef {
+        ml_name: swig_c_str!("__enter__"),
+        ml_meth: Some(swig_py_enter),
+        ml_flags: METH_VARARGS,
+        ml_doc: ::std::ptr::null(),
+    });
+    methods.push(PyMethodDef {
+        ml_name: swig_c_str!("__exit__"),
+        ml_meth: Some(swig_py_close::<T>),
+        ml_flags: METH_VARARGS,
+        ml_doc: ::std::ptr::null(),
+    });
+    slots.push(PyType_Slot {
+        slot: PY_TP_DEALLOC,
+        pfunc: swig_py_dealloc::<T> as *mut ::std::os::raw::c_void,
+    });
+    slots.push(PyType_Slot {
+        slot: PY_TP_FINALIZE,
+        pfunc: swig_py_finalize::<T> as *mut ::std::os::raw::c_void,
+    });
+}
+
+/// Create type object and add it to `module`, on success type object
+/// also saved to `py_type`
+#[allow(dead_code)]
+fn swig_py_add_class(
+    module: *mut PyObject,
+    name: *const ::std::os::raw::c_char,
+    full_name: *const ::std::os::raw::c_char,
+    doc: *const ::std::os::raw::c_char,
+    init: extern "C" fn(*mut PyObject, *mut PyObject, *mut PyObject) -> ::std::os::raw::c_int,
+    mut methods: Vec<PyMethodDef>,
+    mut slots: Vec<PyType_Slot>,
+    py_type: &::std::sync::atomic::AtomicPtr<PyObject>,
+) -> bool {
+    methods.push(PyMethodDef {
+        ml_name: ::std::ptr::null(),
+        ml_meth: None,
+        ml_flags: 0,
+        ml_doc: ::std::ptr::null(),
+    });
+    let methods: &'static mut [PyMethodDef] = Box::leak(methods.into_boxed_slice());
+    slots.push(PyType_Slot {
+        slot: PY_TP_METHODS,
+        pfunc: methods.as_mut_ptr() as *mut ::std::os::raw::c_void,
+    });
+    slots.push(PyType_Slot {
+        slot: PY_TP_NEW,
+        pfunc: PyType_GenericNew as *mut ::std::os::raw::c_void,
+    });
+    slots.push(PyType_Slot {
+        slot: PY_TP_INIT,
+        pfunc: init as *mut ::std::os::raw::c_void,
+    });
+    if !doc.is_null() {
+        slots.push(PyType_Slot {
+            slot: PY_TP_DOC,
+            pfunc: doc as *mut ::std::os::raw::c_void,
+        });
+    }
+    slots.push(PyType_Slot {
+        slot: 0,
+        pfunc: ::std::ptr::null_mut(),
+    });
+    let slots: &'static mut [PyType_Slot] = Box::leak(slots.into_boxed_slice());
+    let spec: &'static mut PyType_Spec = Box::leak(Box::new(PyType_Spec {
+        name: full_name,
+        basicsize: ::std::mem::size_of::<SwigPyObject>() as ::std::os::raw::c_int,
+        itemsize: 0,
+        flags: PY_TPFLAGS_DEFAULT,
+        slots: slots.as_mut_ptr(),
+    }));
+    let tp = unsafe { PyType_FromSpec(spec) };
+    if tp.is_null() {
+        return false;
+    }
+    py_type.store(tp, ::std::sync::atomic::Ordering::SeqCst);
+    unsafe {
+        Py_IncRef(tp);
+        PyModule_AddObject(module, name, tp) == 0
+    }
+}
+
+/// Create `enum.IntEnum` subclass and add it to `module`, on success
+/// it also saved to `py_type`
+#[allow(dead_code)]
+fn swig_py_add_enum(
+    module: *mut PyObject,
+    module_name: &str,
+    name: &str,
+    items: &[(&str, i64)],
+    py_type: &::std::sync::atomic::AtomicPtr<PyObject>,
+) -> bool {
+    unsafe {
+        let enum_module = PyImport_ImportModule(swig_c_str!("enum"));
+        if enum_module.is_null() {
+            return false;
+        }
+        let int_enum = PyObject_GetAttrString(enum_module, swig_c_str!("IntEnum"));
+        Py_DecRef(enum_module);
+        if int_enum.is_null() {
+            return false;
+        }
+        let members = PyDict_New();
+        for (item_name, value) in items {
+            let item_name = ::std::ffi::CString::new(*item_name).unwrap();
+            let value = PyLong_FromLongLong(*value);
+            PyDict_SetItemString(members, item_name.as_ptr(), value);
+            Py_DecRef(value);
+        }
+        let py_name = swig_py_str_from(name);
+        let tp = PyObject_CallFunctionObjArgs(
+            int_enum,
+            py_name,
+            members,
+            ::std::ptr::null_mut::<PyObject>(),
+        );
+        Py_DecRef(py_name);
+        Py_DecRef(members);
+        Py_DecRef(int_enum);
+        if tp.is_null() {
+            return false;
+        }
+        let py_module_name = swig_py_str_from(module_name);
+        PyObject_SetAttrString(tp, swig_c_str!("__module__"), py_module_name);
+        Py_DecRef(py_module_name);
+        py_type.store(tp, ::std::sync::atomic::Ordering::SeqCst);
+        Py_IncRef(tp);
+        let name = ::std::ffi::CString::new(name).unwrap();
+        PyModule_AddObject(module, name.as_ptr(), tp) == 0
+    }
+}
+
+#[allow(dead_code)]
+fn swig_py_enum_item(py_type: *mut PyObject, value: i64) -> *mut PyObject {
+    unsafe {
+        let value = PyLong_FromLongLong(value);
+        let ret = PyObject_CallFunctionObjArgs(py_type, value, ::std::ptr::null_mut::<PyObject>());
+        Py_DecRef(value);
+        ret
+    }
+}
+
+#[allow(dead_code)]
+fn swig_py_create_module(name: *const ::std::os::raw::c_char) -> *mut PyObject {
+    let module_def: &'static mut PyModuleDef = Box::leak(Box::new(PyModuleDef {
+        m_base: PyModuleDef_Base {
+            ob_base: PyObject {
+                ob_refcnt: 1,
+                ob_type: ::std::ptr::null_mut(),
+            },
+            m_init: None,
+            m_index: 0,
+            m_copy: ::std::ptr::null_mut(),
+        },
+        m_name: name,
+        m_doc: ::std::ptr::null(),
+        m_size: -1,
+        m_methods: ::std::ptr::null_mut(),
+        m_slots: ::std::ptr::null_mut(),
+        m_traverse: ::std::ptr::null_mut(),
+        m_clear: ::std::ptr::null_mut(),
+        m_free: ::std::ptr::null_mut(),
+    }));
+    unsafe { PyModule_Create2(module_def, PYTHON_API_VERSION) }
+}
+
+/// Hold GIL while it is alive, so Python API can be used from any thread
+#[allow(dead_code)]
+struct SwigPyGil(::std::os::raw::c_int);
+
+#[allow(dead_code)]
+impl SwigPyGil {
+    fn acquire() -> SwigPyGil {
+        SwigPyGil(unsafe { PyGILState_Ensure() })
+    }
+}
+
+impl Drop for SwigPyGil {
+    fn drop(&mut self) {
+        unsafe { PyGILState_Release(self.0) };
+    }
+}
+
+/// Strong reference to Python object, released on drop
+#[allow(dead_code)]
+struct SwigPyOwned(*mut PyObject);
+
+impl Drop for SwigPyOwned {
+    fn drop(&mut self) {
+        unsafe { Py_DecRef(self.0) };
+    }
+}
+
+/// Python object that implements `foreign_interface!`,
+/// GIL should be held during usage of methods
+#[allow(dead_code)]
+pub struct SwigPyCallback {
+    this: *mut PyObject,
+}
+
+/// Python object is touched only under GIL
+unsafe impl Send for SwigPyCallback {}
+unsafe impl Sync for SwigPyCallback {}
+
+#[allow(dead_code)]
+impl SwigPyCallback {
+    fn new(obj: *mut PyObject) -> SwigPyCallback {
+        unsafe { Py_IncRef(obj) };
+        SwigPyCallback { this: obj }
+    }
+    /// Call method of Python object, references to `args` are stolen.
+    /// `None` means that exception was raised, it is already reported
+    /// via `sys.unraisablehook`, because it can not be propagated to caller
+    fn call(
+        &self,
+        method_name: *const ::std::os::raw::c_char,
+        args: &[*mut PyObject],
+    ) -> Option<SwigPyOwned> {
+        unsafe {
+            if args.iter().any(|x| x.is_null()) {
+                for arg in args.iter().filter(|x| !x.is_null()) {
+                    Py_DecRef(*arg);
+                }
+                PyErr_WriteUnraisable(self.this);
+                return None;
+            }
+            let tuple = PyTuple_New(args.len() as isize);
+            if tuple.is_null() {
+                PyErr_WriteUnraisable(self.this);
+                return None;
+            }
+            for (i, arg) in args.iter().enumerate() {
+                PyTuple_SetItem(tuple, i as isize, *arg);
+            }
+            let func = PyObject_GetAttrString(self.this, method_name);
+            if func.is_null() {
+                Py_DecRef(tuple);
+                PyErr_WriteUnraisable(self.this);
+                return None;
+            }
+            let ret = PyObject_CallObject(func, tuple);
+            Py_DecRef(tuple);
+            if ret.is_null() {
+                PyErr_WriteUnraisable(func);
+                Py_DecRef(func);
+                return None;
+            }
+            Py_DecRef(func);
+            Some(SwigPyOwned(ret))
+        }
+    }
+    /// Report invalid return value of method and panic,
+    /// Rust trait has no way to return error
+    fn failed(&self, method: &str) -> ! {
+        if unsafe { !PyErr_Occurred().is_null() } {
+            unsafe { PyErr_WriteUnraisable(self.this) };
+        }
+        panic!("{}: Python exception or invalid return value", method);
+    }
+}
+
+impl Drop for SwigPyCallback {
+    fn drop(&mut self) {
+        let _gil = SwigPyGil::acquire();
+        unsafe { Py_DecRef(self.this) };
+    }
+}
+
+#[swig_generic_arg = "T"]
+#[swig_generic_arg = "E"]
+#[swig_from = "Result<T, E>"]
+#[swig_to = "T"]
+#[swig_code = "let mut {to_var}: {to_var_type} = py_unpack_return!({from_var}, {function_ret_type});"]
+macro_rules! py_unpack_return {
+    ($result_value:expr, $func_ret_type:ty) => {{
+        let ret = match $result_value {
+            Ok(x) => x,
+            Err(err) => {
+                swig_py_set_error(unsafe { PyExc_RuntimeError }, &err.to_string());
+                return <$func_ret_type>::swig_py_invalid_value();
+            }
+        };
+        ret
+    }};
+}
+
+#[swig_to_foreigner_hint = "Optional[T]"]
+impl<T: SwigForeignClass> SwigFrom<Option<T>> for *mut PyObject {
+    fn swig_from(x: Option<T>) -> Self {
+        swig_py_opt_from(x, swig_py_wrap_object)
+    }
+}
+
+#[swig_from_foreigner_hint = "Optional[T]"]
+impl<T: SwigForeignClass> SwigInto<Option<T>> for *mut PyObject {
+    fn swig_into(self) -> Option<Option<T>> {
+        swig_py_opt_as(self, swig_py_take_object)
+    }
+}
+
+#[swig_to_foreigner_hint = "List[T]"]
+impl<T: SwigForeignClass> SwigFrom<Vec<T>> for *mut PyObject {
+    fn swig_from(x: Vec<T>) -> Self {
+        swig_py_list_from(x, swig_py_wrap_object)
+    }
+}
+
+#[swig_from_foreigner_hint = "List[T]"]
+impl<T: SwigForeignClass + Clone> SwigInto<Vec<T>> for *mut PyObject {
+    fn swig_into(self) -> Option<Vec<T>> {
+        swig_py_list_as(self, |x| {
+            let p = swig_py_object_ptr::<T>(x)?;
+            let obj: &T = unsafe { &*(p as *const T) };
+            Some(obj.clone())
+        })
+    }
+}
+
+impl SwigFrom<bool> for PyBool {
+    fn swig_from(x: bool) -> Self {
+        unsafe { PyBool_FromLong(x as ::std::os::raw::c_long) }
+    }
+}
+
+impl SwigInto<bool> for PyBool {
+    fn swig_into(self) -> Option<bool> {
+        swig_py_bool_as(self)
+    }
+}
+
+impl SwigFrom<i8> for PyInt {
+    fn swig_from(x: i8) -> Self {
+        unsafe { PyLong_FromLongLong(i64::from(x)) }
+    }
+}
+
+impl SwigInto<i8> for PyInt {
+    fn swig_into(self) -> Option<i8> {
+        swig_py_long_as(self)
+    }
+}
+
+impl SwigFrom<u8> for PyInt {
+    fn swig_from(x: u8) -> Self {
+        unsafe { PyLong_FromLongLong(i64::from(x)) }
+    }
+}
+
+impl SwigInto<u8> for PyInt {
+    fn swig_into(self) -> Option<u8> {
+        swig_py_long_as(self)
+    }
+}
+
+impl SwigFrom<i16> for PyInt {
+    fn swig_from(x: i16) -> Self {
+        unsafe { PyLong_FromLongLong(i64::from(x)) }
+    }
+}
+
+impl SwigInto<i16> for PyInt {
+    fn swig_into(self) -> Option<i16> {
+        swig_py_long_as(self)
+    }
+}
+
+impl SwigFrom<u16> for PyInt {
+    fn swig_from(x: u16) -> Self {
+        unsafe { PyLong_FromLongLong(i64::from(x)) }
+    }
+}
+
+impl SwigInto<u16> for PyInt {
+    fn swig_into(self) -> Option<u16> {
+        swig_py_long_as(self)
+    }
+}
+
+impl SwigFrom<i32> for PyInt {
+    fn swig_from(x: i32) -> Self {
+        unsafe { PyLong_FromLongLong(i64::from(x)) }
+    }
+}
+
+impl SwigInto<i32> for PyInt {
+    fn swig_into(self) -> Option<i32> {
+        swig_py_long_as(self)
+    }
+}
+
+impl SwigFrom<u32> for PyInt {
+    fn swig_from(x: u32) -> Self {
+        unsafe { PyLong_FromLongLong(i64::from(x)) }
+    }
+}
+
+impl SwigInto<u32> for PyInt {
+    fn swig_into(self) -> Option<u32> {
+        swig_py_long_as(self)
+    }
+}
+
+impl SwigFrom<i64> for PyInt {
+    fn swig_from(x: i64) -> Self {
+        unsafe { PyLong_FromLongLong(x) }
+    }
+}
+
+impl SwigInto<i64> for PyInt {
+    fn swig_into(self) -> Option<i64> {
+        swig_py_long_as(self)
+    }
+}
+
+impl SwigFrom<u64> for PyInt {
+    fn swig_from(x: u64) -> Self {
+        unsafe { PyLong_FromUnsignedLongLong(x) }
+    }
+}
+
+impl SwigInto<u64> for PyInt {
+    fn swig_into(self) -> Option<u64> {
+        swig_py_long_as_unsigned(self)
+    }
+}
+
+impl SwigFrom<isize> for PyInt {
+    fn swig_from(x: isize) -> Self {
+        unsafe { PyLong_FromLongLong(x as i64) }
+    }
+}
+
+impl SwigInto<isize> for PyInt {
+    fn swig_into(self) -> Option<isize> {
+        swig_py_long_as(self)
+    }
+}
+
+impl SwigFrom<usize> for PyInt {
+    fn swig_from(x: usize) -> Self {
+        unsafe { PyLong_FromUnsignedLongLong(x as u64) }
+    }
+}
+
+impl SwigInto<usize> for PyInt {
+    fn swig_into(self) -> Option<usize> {
+        swig_py_long_as_unsigned(self)
+    }
+}
+
+impl SwigFrom<f32> for PyFloat {
+    fn swig_from(x: f32) -> Self {
+        unsafe { PyFloat_FromDouble(f64::from(x)) }
+    }
+}
+
+impl SwigInto<f32> for PyFloat {
+    fn swig_into(self) -> Option<f32> {
+        swig_py_float_as(self).map(|x| x as f32)
+    }
+}
+
+impl SwigFrom<f64> for PyFloat {
+    fn swig_from(x: f64) -> Self {
+        unsafe { PyFloat_FromDouble(x) }
+    }
+}
+
+impl SwigInto<f64> for PyFloat {
+    fn swig_into(self) -> Option<f64> {
+        swig_py_float_as(self)
+    }
+}
+
+impl<'a> SwigFrom<&'a str> for PyStr {
+    fn swig_from(x: &'a str) -> Self {
+        swig_py_str_from(x)
+    }
+}
+
+impl SwigFrom<String> for PyStr {
+    fn swig_from(x: String) -> Self {
+        swig_py_str_from(&x)
+    }
+}
+
+impl<'a> SwigInto<&'a str> for PyStr {
+    fn swig_into(self) -> Option<&'a str> {
+        swig_py_str_as(self)
+    }
+}
+
+impl SwigInto<String> for PyStr {
+    fn swig_into(self) -> Option<String> {
+        swig_py_str_as(self).map(str::to_string)
+    }
+}
+
+impl SwigFrom<Option<bool>> for PyOptBool {
+    fn swig_from(x: Option<bool>) -> Self {
+        swig_py_opt_from(x, |x| unsafe { PyBool_FromLong(x as ::std::os::raw::c_long) })
+    }
+}
+
+impl SwigInto<Option<bool>> for PyOptBool {
+    fn swig_into(self) -> Option<Option<bool>> {
+        swig_py_opt_as(self, swig_py_bool_as)
+    }
+}
+
+impl SwigFrom<Option<i32>> for PyOptInt {
+    fn swig_from(x: Option<i32>) -> Self {
+        swig_py_opt_from(x, |x| unsafe { PyLong_FromLongLong(i64::from(x)) })
+    }
+}
+
+impl SwigInto<Option<i32>> for PyOptInt {
+    fn swig_into(self) -> Option<Option<i32>> {
+        swig_py_opt_as(self, swig_py_long_as)
+    }
+}
+
+impl SwigFrom<Option<u32>> for PyOptInt {
+    fn swig_from(x: Option<u32>) -> Self {
+        swig_py_opt_from(x, |x| unsafe { PyLong_FromLongLong(i64::from(x)) })
+    }
+}
+
+impl SwigInto<Option<u32>> for PyOptInt {
+    fn swig_into(self) -> Option<Option<u32>> {
+        swig_py_opt_as(self, swig_py_long_as)
+    }
+}
+
+impl SwigFrom<Option<i64>> for PyOptInt {
+    fn swig_from(x: Option<i64>) -> Self {
+        swig_py_opt_from(x, |x| unsafe { PyLong_FromLongLong(x) })
+    }
+}
+
+impl SwigInto<Option<i64>> for PyOptInt {
+    fn swig_into(self) -> Option<Option<i64>> {
+        swig_py_opt_as(self, swig_py_long_as)
+    }
+}
+
+impl SwigFrom<Option<u64>> for PyOptInt {
+    fn swig_from(x: Option<u64>) -> Self {
+        swig_py_opt_from(x, |x| unsafe { PyLong_FromUnsignedLongLong(x) })
+    }
+}
+
+impl SwigInto<Option<u64>> for PyOptInt {
+    fn swig_into(self) -> Option<Option<u64>> {
+        swig_py_opt_as(self, swig_py_long_as_unsigned)
+    }
+}
+
+impl SwigFrom<Option<usize>> for PyOptInt {
+    fn swig_from(x: Option<usize>) -> Self {
+        swig_py_opt_from(x, |x| unsafe { PyLong_FromUnsignedLongLong(x as u64) })
+    }
+}
+
+impl SwigInto<Option<usize>> for PyOptInt {
+    fn swig_into(self) -> Option<Option<usize>> {
+        swig_py_opt_as(self, swig_py_long_as_unsigned)
+    }
+}
+
+impl SwigFrom<Option<f32>> for PyOptFloat {
+    fn swig_from(x: Option<f32>) -> Self {
+        swig_py_opt_from(x, |x| unsafe { PyFloat_FromDouble(f64::from(x)) })
+    }
+}
+
+impl SwigInto<Option<f32>> for PyOptFloat {
+    fn swig_into(self) -> Option<Option<f32>> {
+        swig_py_opt_as(self, |x| swig_py_float_as(x).map(|x| x as f32))
+    }
+}
+
+impl SwigFrom<Option<f64>> for PyOptFloat {
+    fn swig_from(x: Option<f64>) -> Self {
+        swig_py_opt_from(x, |x| unsafe { PyFloat_FromDouble(x) })
+    }
+}
+
+impl SwigInto<Option<f64>> for PyOptFloat {
+    fn swig_into(self) -> Option<Option<f64>> {
+        swig_py_opt_as(self, swig_py_float_as)
+    }
+}
+
+impl SwigFrom<Option<String>> for PyOptStr {
+    fn swig_from(x: Option<String>) -> Self {
+        swig_py_opt_from(x, |x| swig_py_str_from(&x))
+    }
+}
+
+impl SwigInto<Option<String>> for PyOptStr {
+    fn swig_into(self) -> Option<Option<String>> {
+        swig_py_opt_as(self, |x| swig_py_str_as(x).map(str::to_string))
+    }
+}
+
+impl SwigFrom<Vec<u8>> for PyIntList {
+    fn swig_from(x: Vec<u8>) -> Self {
+        swig_py_list_from(x, |x| unsafe { PyLong_FromLongLong(i64::from(x)) })
+    }
+}
+
+impl SwigInto<Vec<u8>> for PyIntList {
+    fn swig_into(self) -> Option<Vec<u8>> {
+        swig_py_list_as(self, swig_py_long_as)
+    }
+}
+
+impl SwigFrom<Vec<i32>> for PyIntList {
+    fn swig_from(x: Vec<i32>) -> Self {
+        swig_py_list_from(x, |x| unsafe { PyLong_FromLongLong(i64::from(x)) })
+    }
+}
+
+impl SwigInto<Vec<i32>> for PyIntList {
+    fn swig_into(self) -> Option<Vec<i32>> {
+        swig_py_list_as(self, swig_py_long_as)
+    }
+}
+
+impl SwigFrom<Vec<u32>> for PyIntList {
+    fn swig_from(x: Vec<u32>) -> Self {
+        swig_py_list_from(x, |x| unsafe { PyLong_FromLongLong(i64::from(x)) })
+    }
+}
+
+impl SwigInto<Vec<u32>> for PyIntList {
+    fn swig_into(self) -> Option<Vec<u32>> {
+        swig_py_list_as(self, swig_py_long_as)
+    }
+}
+
+impl SwigFrom<Vec<i64>> for PyIntList {
+    fn swig_from(x: Vec<i64>) -> Self {
+        swig_py_list_from(x, |x| unsafe { PyLong_FromLongLong(x) })
+    }
+}
+
+impl SwigInto<Vec<i64>> for PyIntList {
+    fn swig_into(self) -> Option<Vec<i64>> {
+        swig_py_list_as(self, swig_py_long_as)
+    }
+}
+
+impl SwigFrom<Vec<u64>> for PyIntList {
+    fn swig_from(x: Vec<u64>) -> Self {
+        swig_py_list_from(x, |x| unsafe { PyLong_FromUnsignedLongLong(x) })
+    }
+}
+
+impl SwigInto<Vec<u64>> for PyIntList {
+    fn swig_into(self) -> Option<Vec<u64>> {
+        swig_py_list_as(self, swig_py_long_as_unsigned)
+    }
+}
+
+impl SwigFrom<Vec<f32>> for PyFloatList {
+    fn swig_from(x: Vec<f32>) -> Self {
+        swig_py_list_from(x, |x| unsafe { PyFloat_FromDouble(f64::from(x)) })
+    }
+}
+
+impl SwigInto<Vec<f32>> for PyFloatList {
+    fn swig_into(self) -> Option<Vec<f32>> {
+        swig_py_list_as(self, |x| swig_py_float_as(x).map(|x| x as f32))
+    }
+}
+
+impl SwigFrom<Vec<f64>> for PyFloatList {
+    fn swig_from(x: Vec<f64>) -> Self {
+        swig_py_list_from(x, |x| unsafe { PyFloat_FromDouble(x) })
+    }
+}
+
+impl SwigInto<Vec<f64>> for PyFloatList {
+    fn swig_into(self) -> Option<Vec<f64>> {
+        swig_py_list_as(self, swig_py_float_as)
+    }
+}
+
+impl SwigFrom<Vec<String>> for PyStrList {
+    fn swig_from(x: Vec<String>) -> Self {
+        swig_py_list_from(x, |x| swig_py_str_from(&x))
+    }
+}
+
+impl SwigInto<Vec<String>> for PyStrList {
+    fn swig_into(self) -> Option<Vec<String>> {
+        swig_py_list_as(self, |x| swig_py_str_as(x).map(str::to_string))
+    }
+}
+
+impl<T> SwigDeref for Vec<T> {
+    type Target = [T];
+    fn swig_deref(&self) -> &[T] {
+        self
+    }
+}
+
+impl<'a, T: Clone> SwigFrom<&'a [T]> for Vec<T> {
+    fn swig_from(x: &'a [T]) -> Self {
+        x.to_vec()
+    }
+}
+
+impl<'a> SwigDeref for &'a String {
+    type Target = str;
+    fn swig_deref(&self) -> &str {
+        self
+    }
+}
+
+impl<T> SwigDeref for Arc<Mutex<T>> {
+    type Target = Mutex<T>;
+    fn swig_deref(&self) -> &Mutex<T> {
+        self
+    }
+}
+
+impl<'a, T> SwigFrom<&'a Mutex<T>> for MutexGuard<'a, T> {
+    fn swig_from(m: &'a Mutex<T>) -> MutexGuard<'a, T> {
+        m.lock().unwrap()
+    }
+}
+
+impl<'a, T> SwigDeref for MutexGuard<'a, T> {
+    type Target = T;
+    fn swig_deref(&self) -> &T {
+        self
+    }
+}
+
+impl<'a, T> SwigDerefMut for MutexGuard<'a, T> {
+    type Target = T;
+    fn swig_deref_mut(&mut self) -> &mut T {
+        self
+    }
+}
+
+impl<T> SwigDeref for Rc<T> {
+    type Target = T;
+    fn swig_deref(&self) -> &T {
+        self
+    }
+}
+
+impl<'a, T> SwigDeref for &'a Rc<T> {
+    type Target = T;
+    fn swig_deref(&self) -> &T {
+        self
+    }
+}
+
+impl<'a, T> SwigFrom<&'a RefCell<T>> for Ref<'a, T> {
+    fn swig_from(m: &'a RefCell<T>) -> Ref<'a, T> {
+        m.borrow()
+    }
+}
+
+impl<'a, T> SwigFrom<&'a RefCell<T>> for RefMut<'a, T> {
+    fn swig_from(m: &'a RefCell<T>) -> RefMut<'a, T> {
+        m.borrow_mut()
+    }
+}
+
+impl<'a, T> SwigDeref for Ref<'a, T> {
+    type Target = T;
+    fn swig_deref(&self) -> &T {
+        self
+    }
+}
+
+impl<'a, T> SwigDerefMut for RefMut<'a, T> {
+    type Target = T;
+    fn swig_deref_mut(&mut self) -> &mut T {
+        self
+    }
+}
+
+impl<T: SwigForeignClass> SwigDeref for T {
+    type Target = T;
+    fn swig_deref(&self) -> &T {
+        self
+    }
+}
+
+impl<T: SwigForeignClass> SwigDerefMut for T {
+    type Target = T;
+    fn swig_deref_mut(&mut self) -> &mut T {
+        self
+    }
+}
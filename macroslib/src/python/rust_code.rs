@@ -0,0 +1,775 @@
+use std::fmt::Write;
+
+use proc_macro2::TokenStream;
+use rustc_hash::FxHashMap;
+use syn::{parse_quote, spanned::Spanned, Type};
+
+use crate::{
+    error::{panic_on_syn_error, DiagnosticError, Result},
+    python::{calc_this_type_for_method, method_name, PyForeignMethodSignature},
+    source_registry::SourceId,
+    typemap::{
+        ast::{fn_arg_type, list_lifetimes, normalize_ty_lifetimes, DisplayToTokens},
+        ty::RustType,
+        unpack_unique_typename,
+        utils::{
+            create_suitable_types_for_constructor_and_self,
+            foreign_from_rust_convert_method_output, foreign_to_rust_convert_method_inputs,
+            rust_to_foreign_convert_method_inputs,
+        },
+    },
+    types::{
+        ForeignEnumInfo, ForeignInterface, ForeignerClassInfo, ForeignerMethod, MethodVariant,
+        SelfTypeVariant,
+    },
+    TypeMap,
+};
+
+const PY_OBJ_RET_TYPE: &str = "*mut PyObject";
+const PY_INIT_RET_TYPE: &str = "::std::os::raw::c_int";
+
+struct MethodContext<'a> {
+    class: &'a ForeignerClassInfo,
+    method: &'a ForeignerMethod,
+    f_method: &'a PyForeignMethodSignature,
+    func_name: &'a str,
+    py_name: &'a str,
+    args_names: &'a str,
+    real_output_typename: &'a str,
+    catch_panics: bool,
+}
+
+/// Generate Rust code for class, returns it and name of function
+/// that should be called during module initialization
+pub(in crate::python) fn generate_class(
+    conv_map: &mut TypeMap,
+    module_name: &str,
+    class: &ForeignerClassInfo,
+    f_methods: &[PyForeignMethodSignature],
+    catch_panics: bool,
+) -> Result<(Vec<TokenStream>, String)> {
+    check_names(class)?;
+
+    let mut gen_code = Vec::<TokenStream>::new();
+    let this_type = match calc_this_type_for_method(conv_map, class) {
+        Some(this_type) => {
+            let this_type = conv_map.find_or_alloc_rust_type_that_implements(
+                &this_type,
+                "SwigForeignClass",
+                class.src_id,
+            );
+            gen_code.push(generate_swig_foreign_class_impl(
+                conv_map, class, &this_type,
+            ));
+            Some(this_type)
+        }
+        None => None,
+    };
+
+    let no_this_info = || {
+        DiagnosticError::new(
+            class.src_id,
+            class.span(),
+            format!(
+                "Class {} has methods, but there is no constructor\n
+May be you need to use `private constructor = empty;` syntax?",
+                class.name,
+            ),
+        )
+    };
+
+    let mut methods_table = String::new();
+    let mut constructors = Vec::new();
+    for (method, f_method) in class.methods.iter().zip(f_methods.iter()) {
+        if method.is_dummy_constructor() {
+            continue;
+        }
+        let py_name = method_name(method);
+        let func_name = match method.variant {
+            MethodVariant::Constructor => {
+                format!("swig_py_{}_init_{}", class.name, f_method.input.len())
+            }
+            _ => format!("swig_py_{}_{}", class.name, py_name),
+        };
+        let args_names = (0..f_method.input.len())
+            .map(|i| format!("a_{}", i))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let real_output_typename = match method.fn_decl.output {
+            syn::ReturnType::Default => "()",
+            syn::ReturnType::Type(_, ref ty) => normalize_ty_lifetimes(ty),
+        };
+        let mc = MethodContext {
+            class,
+            method,
+            f_method,
+            func_name: &func_name,
+            py_name: &py_name,
+            args_names: &args_names,
+            real_output_typename,
+            catch_panics,
+        };
+        match method.variant {
+            MethodVariant::Constructor => {
+                let this_type = this_type.as_ref().ok_or_else(&no_this_info)?;
+                gen_code.append(&mut generate_constructor(conv_map, &mc, this_type)?);
+                constructors.push((f_method.input.len(), func_name));
+                continue;
+            }
+            MethodVariant::StaticMethod => {
+                gen_code.append(&mut generate_method(conv_map, &mc, None)?);
+            }
+            MethodVariant::Method(self_variant) => {
+                let this_type = this_type.as_ref().ok_or_else(&no_this_info)?;
+                gen_code.append(&mut generate_method(
+                    conv_map,
+                    &mc,
+                    Some((self_variant, this_type)),
+                )?);
+            }
+        }
+        writeln!(
+            &mut methods_table,
+            r#"        PyMethodDef {{
+            ml_name: swig_c_str!("{py_name}"),
+            ml_meth: Some({func_name}),
+            ml_flags: {flags},
+            ml_doc: {doc},
+        }},"#,
+            py_name = py_name,
+            func_name = func_name,
+            flags = if method.variant == MethodVariant::StaticMethod {
+                "METH_VARARGS | METH_STATIC"
+            } else {
+                "METH_VARARGS"
+            },
+            doc = doc_comments_to_c_str(&method.doc_comments),
+        )
+        .unwrap();
+    }
+
+    let init_func_name = format!("swig_py_{}_init", class.name);
+    let mut init_cases = String::new();
+    for (n_args, func_name) in &constructors {
+        writeln!(
+            &mut init_cases,
+            "        {} => {}(self_, args),",
+            n_args, func_name
+        )
+        .unwrap();
+    }
+    let init_error = if constructors.is_empty() {
+        format!("{} can not be created from Python", class.name)
+    } else {
+        format!("{}(): wrong number of arguments", class.name)
+    };
+    let add_class_func_name = format!("swig_py_add_class_{}", class.name);
+    let code = format!(
+        r#"
+#[allow(non_upper_case_globals)]
+static SWIG_PY_TYPE_{class_name}: ::std::sync::atomic::AtomicPtr<PyObject> =
+    ::std::sync::atomic::AtomicPtr::new(::std::ptr::null_mut());
+
+#[allow(non_snake_case, unused_variables)]
+extern "C" fn {init_func_name}(
+    self_: *mut PyObject,
+    args: *mut PyObject,
+    kwds: *mut PyObject,
+) -> ::std::os::raw::c_int {{
+    match unsafe {{ PyTuple_Size(args) }} {{
+{init_cases}
+        _ => {{
+            swig_py_set_error(unsafe {{ PyExc_TypeError }}, "{init_error}");
+            -1
+        }}
+    }}
+}}
+
+#[allow(non_snake_case, unused_mut)]
+fn {add_class_func_name}(module: *mut PyObject) -> bool {{
+    let mut methods: Vec<PyMethodDef> = vec![
+{methods_table}
+    ];
+    let mut slots: Vec<PyType_Slot> = vec![];
+{lifetime}
+    swig_py_add_class(
+        module,
+        swig_c_str!("{class_name}"),
+        swig_c_str!("{module_name}.{class_name}"),
+        {doc},
+        {init_func_name},
+        methods,
+        slots,
+        &SWIG_PY_TYPE_{class_name},
+    )
+}}
+"#,
+        class_name = class.name,
+        module_name = module_name,
+        init_func_name = init_func_name,
+        init_cases = init_cases,
+        init_error = init_error,
+        add_class_func_name = add_class_func_name,
+        methods_table = methods_table,
+        lifetime = match this_type {
+            Some(ref this_type) => format!(
+                "    swig_py_object_lifetime::<{}>(&mut methods, &mut slots);",
+                this_type.normalized_name
+            ),
+            None => String::new(),
+        },
+        doc = doc_comments_to_c_str(&class.doc_comments),
+    );
+    gen_code.push(
+        syn::parse_str(&code)
+            .unwrap_or_else(|err| panic_on_syn_error("python internal class code", code, err)),
+    );
+    Ok((gen_code, add_class_func_name))
+}
+
+/// Python has no overloading, so names of methods should be unique,
+/// and constructors should be different by number of arguments
+fn check_names(class: &ForeignerClassInfo) -> Result<()> {
+    let mut names = FxHashMap::<String, &ForeignerMethod>::default();
+    let mut constructors = FxHashMap::<usize, &ForeignerMethod>::default();
+    for method in &class.methods {
+        if method.is_dummy_constructor() {
+            continue;
+        }
+        let prev = match method.variant {
+            MethodVariant::Constructor => {
+                let n_args = method.fn_decl.inputs.len();
+                constructors.insert(n_args, method)
+            }
+            _ => names.insert(method_name(method), method),
+        };
+        if let Some(prev) = prev {
+            let mut err = DiagnosticError::new(
+                class.src_id,
+                method.span(),
+                if method.variant == MethodVariant::Constructor {
+                    "Python: constructors with the same number of arguments are not supported"
+                } else {
+                    "Python: methods with the same name are not supported"
+                },
+            );
+            err.span_note((class.src_id, prev.span()), "previous definition here");
+            return Err(err);
+        }
+    }
+    Ok(())
+}
+
+fn generate_swig_foreign_class_impl(
+    conv_map: &mut TypeMap,
+    class: &ForeignerClassInfo,
+    this_type: &RustType,
+) -> TokenStream {
+    let (this_type_for_method, code_box_this) = conv_map.convert_to_heap_pointer(this_type, "this");
+    let unpack_code = TypeMap::unpack_from_heap_pointer(this_type, "x", true);
+    let code = format!(
+        r#"
+impl<{lifetimes}> SwigForeignClass for {this_type} {{
+    fn py_class_name() -> &'static str {{
+        "{class_name}"
+    }}
+    fn py_type() -> *mut PyObject {{
+        SWIG_PY_TYPE_{class_name}.load(::std::sync::atomic::Ordering::SeqCst)
+    }}
+    fn box_object(this: Self) -> *mut ::std::os::raw::c_void {{
+{code_box_this}
+        this as *mut ::std::os::raw::c_void
+    }}
+    fn unbox_object(x: *mut ::std::os::raw::c_void) -> Self {{
+        let x: *mut {this_type_for_method} = x as *mut {this_type_for_method};
+{unpack_code}
+        x
+    }}
+}}
+"#,
+        lifetimes = list_lifetimes(&this_type.ty).join(","),
+        this_type = DisplayToTokens(&this_type.ty),
+        class_name = class.name,
+        code_box_this = code_box_this,
+        this_type_for_method = this_type_for_method.normalized_name,
+        unpack_code = unpack_code,
+    );
+    syn::parse_str(&code)
+        .unwrap_or_else(|err| panic_on_syn_error("python internal fclass impl code", code, err))
+}
+
+/// Get arguments of `PyCFunction` from tuple
+fn args_from_tuple(f_method: &PyForeignMethodSignature) -> String {
+    let mut code = String::new();
+    for (i, arg) in f_method.input.iter().enumerate() {
+        writeln!(
+            &mut code,
+            "    let a_{i}: {ty} = unsafe {{ PyTuple_GetItem(args, {i}) }};",
+            i = i,
+            ty = unpack_unique_typename(&arg.correspoding_rust_type.normalized_name),
+        )
+        .unwrap();
+    }
+    code
+}
+
+fn generate_method(
+    conv_map: &mut TypeMap,
+    mc: &MethodContext,
+    this: Option<(SelfTypeVariant, &RustType)>,
+) -> Result<Vec<TokenStream>> {
+    let src_id = mc.class.src_id;
+    let (mut deps_code, convert_input_code) = foreign_to_rust_convert_method_inputs(
+        conv_map,
+        src_id,
+        mc.method,
+        mc.f_method,
+        (0..mc.f_method.input.len()).map(|v| format!("a_{}", v)),
+        PY_OBJ_RET_TYPE,
+    )?;
+
+    let (convert_this, call_args) = match this {
+        Some((self_variant, this_type)) => {
+            let (this_type_for_method, _code_box_this) =
+                conv_map.convert_to_heap_pointer(this_type, "this");
+            let py_obj_ty = conv_map.find_or_alloc_rust_type_with_suffix(
+                &parse_type! { *mut PyObject },
+                &this_type.normalized_name,
+                SourceId::none(),
+            );
+            let (from_ty, to_ty): (Type, Type) = create_suitable_types_for_constructor_and_self(
+                self_variant,
+                mc.class,
+                &this_type_for_method.ty,
+            );
+            let from_ty = conv_map.find_or_alloc_rust_type(&from_ty, src_id);
+            let to_ty = conv_map.find_or_alloc_rust_type(&to_ty, src_id);
+            let (mut deps_obj, convert_obj) = conv_map.convert_rust_types(
+                &py_obj_ty,
+                &from_ty,
+                "this",
+                PY_OBJ_RET_TYPE,
+                (src_id, mc.method.span()),
+            )?;
+            let (mut deps_this, convert_this) = conv_map.convert_rust_types(
+                &from_ty,
+                &to_ty,
+                "this",
+                PY_OBJ_RET_TYPE,
+                (src_id, mc.method.span()),
+            )?;
+            deps_code.append(&mut deps_obj);
+            deps_code.append(&mut deps_this);
+            (
+                format!(
+                    "    let this: *mut PyObject = self_;\n{}{}",
+                    convert_obj, convert_this
+                ),
+                if mc.args_names.is_empty() {
+                    "this".to_string()
+                } else {
+                    format!("this, {}", mc.args_names)
+                },
+            )
+        }
+        None => (String::new(), mc.args_names.to_string()),
+    };
+
+    let (mut deps_code_out, convert_output_code) = foreign_from_rust_convert_method_output(
+        conv_map,
+        src_id,
+        &mc.method.fn_decl.output,
+        &mc.f_method.output,
+        "ret",
+        PY_OBJ_RET_TYPE,
+    )?;
+    deps_code.append(&mut deps_code_out);
+    let ret = if mc.f_method.output.name == "void" {
+        "swig_py_none()"
+    } else {
+        "ret"
+    };
+
+    let body = format!(
+        r#"
+{args_from_tuple}
+{convert_input_code}
+{convert_this}
+    let mut ret: {real_output_typename} = {rust_func_name}({call_args});
+{convert_output_code}
+    {ret}"#,
+        args_from_tuple = args_from_tuple(mc.f_method),
+        convert_input_code = convert_input_code,
+        convert_this = convert_this,
+        real_output_typename = mc.real_output_typename,
+        rust_func_name = DisplayToTokens(&mc.method.rust_id),
+        call_args = call_args,
+        convert_output_code = convert_output_code,
+        ret = ret,
+    );
+    let code = format!(
+        r#"
+#[allow(non_snake_case, unused_variables, unused_mut)]
+extern "C" fn {func_name}(self_: *mut PyObject, args: *mut PyObject) -> *mut PyObject {{
+    if !swig_py_check_args(args, {n_args}, "{py_name}") {{
+        return ::std::ptr::null_mut();
+    }}
+{body}
+}}
+"#,
+        func_name = mc.func_name,
+        n_args = mc.f_method.input.len(),
+        py_name = mc.py_name,
+        body = catch_unwind_if_need(mc.catch_panics, PY_OBJ_RET_TYPE, body),
+    );
+    deps_code.push(
+        syn::parse_str(&code)
+            .unwrap_or_else(|err| panic_on_syn_error("python internal method", code, err)),
+    );
+    Ok(deps_code)
+}
+
+fn generate_constructor(
+    conv_map: &mut TypeMap,
+    mc: &MethodContext,
+    this_type: &RustType,
+) -> Result<Vec<TokenStream>> {
+    let src_id = mc.class.src_id;
+    let (mut deps_code, convert_input_code) = foreign_to_rust_convert_method_inputs(
+        conv_map,
+        src_id,
+        mc.method,
+        mc.f_method,
+        (0..mc.f_method.input.len()).map(|v| format!("a_{}", v)),
+        PY_INIT_RET_TYPE,
+    )?;
+    let construct_ret_type = match mc.class.constructor_ret_type {
+        Some(ref ty) => conv_map.find_or_alloc_rust_type(ty, src_id),
+        None => this_type.clone(),
+    };
+    let (mut deps_this, convert_this) = conv_map.convert_rust_types(
+        &construct_ret_type,
+        this_type,
+        "this",
+        PY_INIT_RET_TYPE,
+        (src_id, mc.method.span()),
+    )?;
+    deps_code.append(&mut deps_this);
+
+    let body = format!(
+        r#"
+{args_from_tuple}
+{convert_input_code}
+    let this: {real_output_typename} = {rust_func_name}({args_names});
+{convert_this}
+    swig_py_init_object::<{this_type}>(self_, this);
+    0"#,
+        args_from_tuple = args_from_tuple(mc.f_method),
+        convert_input_code = convert_input_code,
+        real_output_typename = mc.real_output_typename,
+        rust_func_name = DisplayToTokens(&mc.method.rust_id),
+        args_names = mc.args_names,
+        convert_this = convert_this,
+        this_type = this_type.normalized_name,
+    );
+    let code = format!(
+        r#"
+#[allow(non_snake_case, unused_variables, unused_mut)]
+fn {func_name}(self_: *mut PyObject, args: *mut PyObject) -> ::std::os::raw::c_int {{
+{body}
+}}
+"#,
+        func_name = mc.func_name,
+        body = catch_unwind_if_need(mc.catch_panics, PY_INIT_RET_TYPE, body),
+    );
+    deps_code.push(
+        syn::parse_str(&code)
+            .unwrap_or_else(|err| panic_on_syn_error("python internal constructor", code, err)),
+    );
+    Ok(deps_code)
+}
+
+/// Wrap body of function called by Python into `catch_unwind` if panics should be caught,
+/// panic converted to `RuntimeError`
+fn catch_unwind_if_need(catch_panics: bool, ret_type: &str, body: String) -> String {
+    if !catch_panics {
+        return body;
+    }
+    format!(
+        r#"
+    let ret = ::std::panic::catch_unwind(::std::panic::AssertUnwindSafe(move || -> {ret_type} {{
+{body}
+    }}));
+    match ret {{
+        Ok(ret) => ret,
+        Err(err) => {{
+            swig_py_set_panic_error(err);
+            <{ret_type}>::swig_py_invalid_value()
+        }}
+    }}
+"#,
+        ret_type = ret_type,
+        body = body,
+    )
+}
+
+/// Generate Rust code for enum, returns it and name of function
+/// that should be called during module initialization
+pub(in crate::python) fn generate_enum(
+    conv_map: &mut TypeMap,
+    pointer_target_width: usize,
+    module_name: &str,
+    enum_info: &ForeignEnumInfo,
+) -> Result<(Vec<TokenStream>, String)> {
+    let rust_enum_name = enum_info.rust_enum_name();
+    let mut to_value = String::new();
+    let mut from_value = String::new();
+    let mut items = String::new();
+    for item in &enum_info.items {
+        writeln!(
+            &mut to_value,
+            "            {} => {},",
+            DisplayToTokens(&item.rust_name),
+            item.value
+        )
+        .unwrap();
+        writeln!(
+            &mut from_value,
+            "            {} => Some({}),",
+            item.value,
+            DisplayToTokens(&item.rust_name)
+        )
+        .unwrap();
+        write!(&mut items, "(\"{}\", {}), ", item.name, item.value).unwrap();
+    }
+
+    let conv_code = format!(
+        r#"
+mod swig_foreign_types_map {{
+    #![swig_foreigner_type = "{enum_name}"]
+    #![swig_rust_type_not_unique = "*mut PyObject"]
+}}
+#[swig_to_foreigner_hint = "{enum_name}"]
+impl SwigFrom<{rust_enum_name}> for *mut PyObject {{
+    fn swig_from(x: {rust_enum_name}) -> *mut PyObject {{
+        let value: i64 = match x {{
+{to_value}
+        }};
+        swig_py_enum_item(
+            SWIG_PY_TYPE_{enum_name}.load(::std::sync::atomic::Ordering::SeqCst),
+            value,
+        )
+    }}
+}}
+#[swig_from_foreigner_hint = "{enum_name}"]
+impl SwigInto<{rust_enum_name}> for *mut PyObject {{
+    fn swig_into(self) -> Option<{rust_enum_name}> {{
+        let value: i64 = swig_py_long_as(self)?;
+        match value {{
+{from_value}
+            _ => {{
+                swig_py_set_error(
+                    unsafe {{ PyExc_ValueError }},
+                    &format!("{{}} is not a valid {enum_name}", value),
+                );
+                None
+            }}
+        }}
+    }}
+}}
+"#,
+        enum_name = enum_info.name,
+        rust_enum_name = rust_enum_name,
+        to_value = to_value,
+        from_value = from_value,
+    );
+    conv_map.register_exported_enum(enum_info);
+    conv_map.merge(SourceId::none(), &conv_code, pointer_target_width)?;
+
+    let add_enum_func_name = format!("swig_py_add_enum_{}", enum_info.name);
+    let code = format!(
+        r#"
+#[allow(non_upper_case_globals)]
+static SWIG_PY_TYPE_{enum_name}: ::std::sync::atomic::AtomicPtr<PyObject> =
+    ::std::sync::atomic::AtomicPtr::new(::std::ptr::null_mut());
+
+#[allow(non_snake_case)]
+fn {add_enum_func_name}(module: *mut PyObject) -> bool {{
+    swig_py_add_enum(
+        module,
+        "{module_name}",
+        "{enum_name}",
+        &[{items}],
+        &SWIG_PY_TYPE_{enum_name},
+    )
+}}
+"#,
+        enum_name = enum_info.name,
+        add_enum_func_name = add_enum_func_name,
+        module_name = module_name,
+        items = items,
+    );
+    let code = syn::parse_str(&code)
+        .unwrap_or_else(|err| panic_on_syn_error("python internal enum code", code, err));
+    Ok((vec![code], add_enum_func_name))
+}
+
+/// Generate Rust code for interface: struct that holds reference
+/// to Python object and implements Rust trait
+pub(in crate::python) fn generate_interface(
+    conv_map: &mut TypeMap,
+    pointer_target_width: usize,
+    interface: &ForeignInterface,
+    f_methods: &[PyForeignMethodSignature],
+) -> Result<Vec<TokenStream>> {
+    let struct_name = format!("SwigPyCallback{}", interface.name);
+    let trait_name = DisplayToTokens(&interface.self_type);
+
+    let conv_code = format!(
+        r#"
+mod swig_foreign_types_map {{
+    #![swig_foreigner_type = "{interface_name}"]
+    #![swig_rust_type_not_unique = "*mut PyObject"]
+}}
+#[swig_from_foreigner_hint = "{interface_name}"]
+impl SwigInto<Box<{trait_name}>> for *mut PyObject {{
+    fn swig_into(self) -> Option<Box<{trait_name}>> {{
+        Some(Box::new({struct_name}(SwigPyCallback::new(self))))
+    }}
+}}
+"#,
+        interface_name = interface.name,
+        trait_name = trait_name,
+        struct_name = struct_name,
+    );
+    conv_map.merge(SourceId::none(), &conv_code, pointer_target_width)?;
+
+    let mut gen_items = vec![];
+    let mut code = format!(
+        r#"
+pub struct {struct_name}(SwigPyCallback);
+
+impl {trait_name} for {struct_name} {{
+"#,
+        struct_name = struct_name,
+        trait_name = trait_name,
+    );
+    for (method, f_method) in interface.items.iter().zip(f_methods) {
+        let func_name = method
+            .rust_name
+            .segments
+            .last()
+            .ok_or_else(|| {
+                DiagnosticError::new(
+                    interface.src_id,
+                    method.rust_name.span(),
+                    "Empty trait function name",
+                )
+            })?
+            .value()
+            .ident
+            .to_string();
+        let mut args_with_types = DisplayToTokens(&method.fn_decl.inputs[0]).to_string();
+        for (i, arg) in method.fn_decl.inputs.iter().skip(1).enumerate() {
+            write!(
+                &mut args_with_types,
+                ", a_{}: {}",
+                i,
+                DisplayToTokens(fn_arg_type(arg))
+            )
+            .unwrap();
+        }
+        let n_args = method.fn_decl.inputs.len() - 1;
+        let (mut conv_deps, convert_args) = rust_to_foreign_convert_method_inputs(
+            conv_map,
+            interface.src_id,
+            method,
+            f_method,
+            (0..n_args).map(|v| format!("a_{}", v)),
+            "()",
+        )?;
+        gen_items.append(&mut conv_deps);
+        let args = (0..n_args)
+            .map(|i| format!("a_{}", i))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let call = match method.fn_decl.output {
+            syn::ReturnType::Default => format!(
+                r#"
+        //exception can not be propagated to caller, it is reported by `call`
+        let _ = self.0.call(swig_c_str!("{method_name}"), &[{args}]);"#,
+                method_name = method.name,
+                args = args,
+            ),
+            syn::ReturnType::Type(_, ref ret_ty) => {
+                let real_output_type: RustType =
+                    conv_map.find_or_alloc_rust_type(ret_ty, interface.src_id);
+                let func_ret_type = format!("Option<{}>", real_output_type.normalized_name);
+                let (mut conv_deps, output_conv) = conv_map.convert_rust_types(
+                    &f_method.output.correspoding_rust_type,
+                    &real_output_type,
+                    "ret",
+                    &func_ret_type,
+                    (interface.src_id, ret_ty.span()),
+                )?;
+                gen_items.append(&mut conv_deps);
+                format!(
+                    r#"
+        let ret: {func_ret_type} = (|| {{
+            let py_ret: SwigPyOwned = self.0.call(swig_c_str!("{method_name}"), &[{args}])?;
+            let ret: {ret_type} = py_ret.0;
+{output_conv}
+            Some(ret)
+        }})();
+        ret.unwrap_or_else(|| self.0.failed("{interface_name}.{method_name}"))"#,
+                    func_ret_type = func_ret_type,
+                    ret_type = unpack_unique_typename(
+                        &f_method.output.correspoding_rust_type.normalized_name
+                    ),
+                    method_name = method.name,
+                    interface_name = interface.name,
+                    args = args,
+                    output_conv = output_conv,
+                )
+            }
+        };
+        write!(
+            &mut code,
+            r#"
+    #[allow(unused_mut)]
+    fn {func_name}({args_with_types}) -> {real_ret_type} {{
+        let _gil = SwigPyGil::acquire();
+{convert_args}{call}
+    }}
+"#,
+            func_name = func_name,
+            args_with_types = args_with_types,
+            real_ret_type = match method.fn_decl.output {
+                syn::ReturnType::Default => "()".to_string(),
+                syn::ReturnType::Type(_, ref ret_ty) => DisplayToTokens(ret_ty).to_string(),
+            },
+            convert_args = convert_args,
+            call = call,
+        )
+        .unwrap();
+    }
+    code.push_str("}\n");
+    gen_items.push(
+        syn::parse_str(&code)
+            .unwrap_or_else(|err| panic_on_syn_error("python internal interface", code, err)),
+    );
+    Ok(gen_items)
+}
+
+fn doc_comments_to_c_str(doc_comments: &[String]) -> String {
+    if doc_comments.is_empty() {
+        "::std::ptr::null()".to_string()
+    } else {
+        let doc = doc_comments
+            .iter()
+            .map(|x| x.trim())
+            .collect::<Vec<_>>()
+            .join("\n");
+        format!("swig_c_str!({:?})", doc)
+    }
+}
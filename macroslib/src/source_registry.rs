@@ -1,8 +1,12 @@
+use std::path::{Path, PathBuf};
+
 use crate::SourceCode;
 
 #[derive(Default)]
 pub(crate) struct SourceRegistry {
     data: Vec<SourceCode>,
+    /// Path of file for code that was read from file, index is the same as for `data`
+    paths: Vec<Option<PathBuf>>,
 }
 
 impl SourceRegistry {
@@ -15,9 +19,26 @@ impl SourceRegistry {
         }
         let id = SourceId(Some(self.data.len()));
         self.data.push(src);
+        self.paths.push(None);
+        id
+    }
+
+    pub(crate) fn register_file(&mut self, src: SourceCode, path: &Path) -> SourceId {
+        let id = self.register(src);
+        *self.paths.last_mut().expect("Internal Error: no paths") = Some(path.to_path_buf());
         id
     }
 
+    /// Name that editors and other tools can use to find code:
+    /// path of file, or `id_of_code` if code was not read from file
+    pub(crate) fn file_name(&self, src_id: SourceId) -> String {
+        let idx = src_id.0.expect("Internal Error: Invalid source id");
+        match self.paths[idx] {
+            Some(ref path) => path.display().to_string(),
+            None => self.data[idx].id_of_code.clone(),
+        }
+    }
+
     pub(crate) fn src(&self, src_id: SourceId) -> &str {
         &self.data[src_id.0.expect("Internal Error: Invalid source id")].code
     }
@@ -1,4 +1,5 @@
 pub mod ast;
+mod expand_typemap_macro;
 mod merge;
 mod parse;
 mod parse_typemap_macro;
@@ -20,24 +21,25 @@ use syn::{parse_quote, Ident, Type};
 
 use crate::{
     error::{invalid_src_id_span, DiagnosticError, Result, SourceIdSpan},
+    lint::Lint,
     source_registry::SourceId,
     typemap::{
         ast::{
-            check_if_smart_pointer_return_inner_type, get_trait_bounds, normalize_ty_lifetimes,
-            DisplayToTokens, GenericTypeConv, TypeName,
+            check_if_smart_pointer_return_inner_type, get_trait_bounds, instantiate_generic_types,
+            normalize_ty_lifetimes, ConvRejection, DisplayToTokens, GenericTypeConv, TypeName,
         },
         ty::{
-            ForeignConversationRule, ForeignType, ForeignTypeS, ForeignTypesStorage, RustType,
-            RustTypeS,
+            ForeignConversationRule, ForeignType, ForeignTypeS, ForeignTypesStorage,
+            GenericForeignTypeRule, RustType, RustTypeS,
         },
     },
-    types::{ForeignEnumInfo, ForeignerClassInfo},
+    types::{ForeignEnumInfo, ForeignFlagsInfo, ForeignStructInfo, ForeignerClassInfo},
 };
 
 pub(crate) static TO_VAR_TEMPLATE: &str = "{to_var}";
 pub(crate) static FROM_VAR_TEMPLATE: &str = "{from_var}";
-pub(in crate::typemap) static TO_VAR_TYPE_TEMPLATE: &str = "{to_var_type}";
-pub(in crate::typemap) static FUNCTION_RETURN_TYPE_TEMPLATE: &str = "{function_ret_type}";
+pub(crate) static TO_VAR_TYPE_TEMPLATE: &str = "{to_var_type}";
+pub(crate) static FUNCTION_RETURN_TYPE_TEMPLATE: &str = "{function_ret_type}";
 const MAX_TRY_BUILD_PATH_STEPS: usize = 7;
 
 #[derive(Debug, Clone)]
@@ -56,7 +58,7 @@ impl From<String> for TypeConvEdge {
 }
 
 impl TypeConvEdge {
-    fn new(code_template: String, dependency: Option<TokenStream>) -> TypeConvEdge {
+    pub(crate) fn new(code_template: String, dependency: Option<TokenStream>) -> TypeConvEdge {
         TypeConvEdge {
             code_template,
             dependency: Rc::new(RefCell::new(dependency)),
@@ -87,8 +89,18 @@ pub(crate) struct TypeMap {
     generic_edges: Vec<GenericTypeConv>,
     foreign_classes: Vec<ForeignerClassInfo>,
     exported_enums: FxHashMap<SmolStr, ForeignEnumInfo>,
+    exported_structs: FxHashMap<SmolStr, ForeignStructInfo>,
+    /// `foreign_enum` with data-carrying items, they passed like structures
+    exported_data_enums: FxHashMap<SmolStr, ForeignEnumInfo>,
+    exported_flags: FxHashMap<SmolStr, ForeignFlagsInfo>,
     /// How to use trait to convert types, Trait Name -> Code
     traits_usage_code: FxHashMap<Ident, String>,
+    /// `foreign_typemap!` rules with generic parameters
+    generic_ftypes: Vec<GenericForeignTypeRule>,
+    /// Types from `define_c_type!`, Rust Type Name -> Definition
+    c_types: FxHashMap<SmolStr, syn::ItemStruct>,
+    /// Non-fatal problems found during code generation
+    warnings: Vec<(Lint, DiagnosticError)>,
 }
 
 impl Default for TypeMap {
@@ -144,8 +156,14 @@ impl Default for TypeMap {
             rust_to_foreign_cache: FxHashMap::default(),
             foreign_classes: Vec::new(),
             exported_enums: FxHashMap::default(),
+            exported_structs: FxHashMap::default(),
+            exported_data_enums: FxHashMap::default(),
+            exported_flags: FxHashMap::default(),
             traits_usage_code: FxHashMap::default(),
             ftypes_storage: ForeignTypesStorage::default(),
+            generic_ftypes: Vec::new(),
+            c_types: FxHashMap::default(),
+            warnings: Vec::new(),
         }
     }
 }
@@ -506,6 +524,12 @@ impl TypeMap {
         self.conv_graph.update_edge(from, to, rule);
     }
 
+    pub(crate) fn has_conversation_rule(&self, from: &RustType, to: &RustType) -> bool {
+        self.conv_graph
+            .find_edge(from.graph_idx, to.graph_idx)
+            .is_some()
+    }
+
     pub(crate) fn register_exported_enum(&mut self, enum_info: &ForeignEnumInfo) {
         self.exported_enums
             .insert(enum_info.name.to_string().into(), enum_info.clone());
@@ -515,8 +539,71 @@ impl TypeMap {
         self.exported_enums.get(&ty.normalized_name)
     }
 
+    pub(crate) fn register_exported_struct(&mut self, struct_info: &ForeignStructInfo) {
+        self.exported_structs
+            .insert(struct_info.name.to_string().into(), struct_info.clone());
+    }
+
+    pub(crate) fn is_this_exported_struct(&self, ty: &RustType) -> Option<&ForeignStructInfo> {
+        self.exported_structs.get(&ty.normalized_name)
+    }
+
+    pub(crate) fn register_exported_data_enum(&mut self, enum_info: &ForeignEnumInfo) {
+        self.exported_data_enums
+            .insert(enum_info.name.to_string().into(), enum_info.clone());
+    }
+
+    pub(crate) fn is_this_exported_data_enum(&self, ty: &RustType) -> Option<&ForeignEnumInfo> {
+        self.exported_data_enums.get(&ty.normalized_name)
+    }
+
+    pub(crate) fn register_exported_flags(&mut self, flags_info: &ForeignFlagsInfo) {
+        self.exported_flags
+            .insert(flags_info.name.to_string().into(), flags_info.clone());
+    }
+
+    pub(crate) fn is_this_exported_flags(&self, ty: &RustType) -> Option<&ForeignFlagsInfo> {
+        self.exported_flags.get(&ty.normalized_name)
+    }
+
+    pub(crate) fn is_exported_flags_name(&self, foreign_name: &str) -> bool {
+        self.exported_flags.contains_key(foreign_name)
+    }
+
+    /// Report non-fatal problem, code generation continues
+    pub(crate) fn add_warning(&mut self, lint: Lint, warning: DiagnosticError) {
+        self.warnings.push((lint, warning));
+    }
+
+    pub(crate) fn take_warnings(&mut self) -> Vec<(Lint, DiagnosticError)> {
+        mem::take(&mut self.warnings)
+    }
+
+    /// Is it `foreign_struct` or data-carrying `foreign_enum`,
+    /// both of them are passed by value as structures
+    pub(crate) fn is_exported_by_value_name(&self, foreign_name: &str) -> bool {
+        self.exported_structs.contains_key(foreign_name)
+            || self.exported_data_enums.contains_key(foreign_name)
+    }
+
+    /// Code from `define_helper_f_helper!` for foreign type with such name
+    pub(crate) fn foreign_type_helpers(&self, foreign_name: &str) -> &[String] {
+        match self.ftypes_storage.find_ftype_by_name(foreign_name) {
+            Some(ftype) => &self.ftypes_storage[ftype].helpers,
+            None => &[],
+        }
+    }
+
+    /// C type from `define_c_type!` that corresponds to such Rust type
+    pub(crate) fn find_c_type(&self, ty: &RustType) -> Option<&syn::ItemStruct> {
+        self.c_types.get(&ty.normalized_name)
+    }
+
     pub(crate) fn is_generated_foreign_type(&self, foreign_name: &str) -> bool {
-        if self.exported_enums.contains_key(foreign_name) {
+        if self.exported_enums.contains_key(foreign_name)
+            || self.exported_structs.contains_key(foreign_name)
+            || self.exported_data_enums.contains_key(foreign_name)
+        {
             return true;
         }
         self.foreign_classes
@@ -537,7 +624,8 @@ impl TypeMap {
             Err(_err) => {
                 debug!("convert_rust_types: no path, trying to build it");
                 self.build_path_if_possible(from, to, build_for_sp);
-                self.find_path(from, to, build_for_sp)?
+                self.find_path(from, to, build_for_sp)
+                    .map_err(|err| self.explain_missing_path(err, from, to))?
             }
         };
         let mut ret_code = String::new();
@@ -545,7 +633,11 @@ impl TypeMap {
 
         for edge in path {
             let (_, target) = self.conv_graph.edge_endpoints(edge).unwrap();
-            let target_type = self.conv_graph[target].normalized_name.clone();
+            //`impl Trait` can not be used as type of variable, so let compiler infer it
+            let target_type = match self.conv_graph[target].ty {
+                Type::ImplTrait(_) => SmolStr::new("_"),
+                _ => self.conv_graph[target].normalized_name.clone(),
+            };
             let edge = &mut self.conv_graph[edge];
             if let Some(dep) = edge.dependency.borrow_mut().take() {
                 code_deps.push(dep);
@@ -575,6 +667,220 @@ impl TypeMap {
         find_conversation_path(&self.conv_graph, from.graph_idx, to.graph_idx, build_for_sp)
     }
 
+    /// Add to error of `find_path` information that can help to fix it:
+    /// similar types that can be converted, and why generic rules can not be used
+    fn explain_missing_path(
+        &self,
+        mut err: DiagnosticError,
+        from: &RustType,
+        to: &RustType,
+    ) -> DiagnosticError {
+        let similar = self.similar_types_with_path(from, to);
+        if !similar.is_empty() {
+            err.span_note(
+                invalid_src_id_span(),
+                format!(
+                    "did you mean {}? there is conversation from such type to '{}'",
+                    similar
+                        .iter()
+                        .map(|x| format!("'{}'", x))
+                        .collect::<Vec<_>>()
+                        .join(" or "),
+                    to
+                ),
+            );
+        }
+        let find_type = |name: &str| {
+            self.rust_names_map
+                .get(name)
+                .map(|idx| &self.conv_graph[*idx])
+        };
+        for edge in &self.generic_edges {
+            let rule = format!(
+                "{} -> {}",
+                DisplayToTokens(&edge.from_ty),
+                DisplayToTokens(&edge.to_ty)
+            );
+            let note = match edge.check_conv_possible(from, Some(to), find_type) {
+                Ok((_, to_name)) => {
+                    let to_name = display_unique_typename(&to_name);
+                    format!(
+                        "generic rule `{}` was tried, it converts '{}' to '{}', \
+                         but there is no conversation from '{}' to '{}'",
+                        rule, from, to_name, to_name, to
+                    )
+                }
+                Err(ConvRejection::TraitBounds { ty, missing_traits }) => format!(
+                    "generic rule `{}` would apply if '{}' implemented {}",
+                    rule,
+                    ty,
+                    missing_traits
+                        .iter()
+                        .map(|x| format!("`{}`", x))
+                        .collect::<Vec<_>>()
+                        .join(" and ")
+                ),
+                Err(ConvRejection::ForeignerHintMismatch) if edge.can_produce(to) => format!(
+                    "generic rule `{}` was rejected: foreign type of '{}' does not match rule",
+                    rule, from
+                ),
+                Err(ConvRejection::TypeMismatch) if edge.can_produce(to) => format!(
+                    "generic rule `{}` was rejected: '{}' does not match `{}`",
+                    rule,
+                    from,
+                    DisplayToTokens(&edge.from_ty)
+                ),
+                Err(_) => continue,
+            };
+            err.span_note(invalid_src_id_span(), note);
+        }
+        err
+    }
+
+    /// Add to error of `map_through_conversation_to_foreign` information
+    /// that can help to fix it: similar types that have foreign type,
+    /// and why generic rules can not be used
+    pub(crate) fn explain_missing_foreign_type(
+        &self,
+        mut err: DiagnosticError,
+        rust_ty: &RustType,
+        direction: petgraph::Direction,
+    ) -> DiagnosticError {
+        let related: Vec<RustTypeIdx> = self
+            .ftypes_storage
+            .iter_enumerate()
+            .filter_map(|(_, ftype)| match direction {
+                petgraph::Direction::Outgoing => ftype.into_from_rust.as_ref(),
+                petgraph::Direction::Incoming => ftype.from_into_rust.as_ref(),
+            })
+            .map(|rule| rule.rust_ty)
+            .collect();
+        // for output we need types that can be converted to `related`,
+        // for input types that can be obtained from `related`
+        let mut with_foreign = self.connected_types(&related, direction.opposite());
+        with_foreign.extend(related);
+        let similar = self.similar_types(rust_ty, with_foreign);
+        if !similar.is_empty() {
+            err.span_note(
+                invalid_src_id_span(),
+                format!(
+                    "did you mean {}? there is foreign type for such type",
+                    similar
+                        .iter()
+                        .map(|x| format!("'{}'", x))
+                        .collect::<Vec<_>>()
+                        .join(" or "),
+                ),
+            );
+        }
+        if direction == petgraph::Direction::Incoming {
+            return err;
+        }
+        let find_type = |name: &str| {
+            self.rust_names_map
+                .get(name)
+                .map(|idx| &self.conv_graph[*idx])
+        };
+        let mut notes = FxHashSet::default();
+        for edge in &self.generic_edges {
+            let rule = format!(
+                "{} -> {}",
+                DisplayToTokens(&edge.from_ty),
+                DisplayToTokens(&edge.to_ty)
+            );
+            // rules like `T -> &T` can be applied to any type,
+            // so it is not interesting that they were tried
+            let any_type = match edge.from_ty {
+                Type::Path(ref path) => edge
+                    .generic_params
+                    .type_params()
+                    .any(|p| path.path.is_ident(p.ident.clone())),
+                _ => false,
+            };
+            let note = match edge.check_conv_possible(rust_ty, None, find_type) {
+                Ok(_) if any_type => continue,
+                Ok((_, to_name)) => {
+                    let to_name = display_unique_typename(&to_name);
+                    format!(
+                        "generic rule `{}` was tried, it converts '{}' to '{}', \
+                         but there is no foreign type for '{}'",
+                        rule, rust_ty, to_name, to_name
+                    )
+                }
+                Err(ConvRejection::TraitBounds { ty, missing_traits }) => format!(
+                    "generic rule `{}` would apply if '{}' implemented {}",
+                    rule,
+                    ty,
+                    missing_traits
+                        .iter()
+                        .map(|x| format!("`{}`", x))
+                        .collect::<Vec<_>>()
+                        .join(" and ")
+                ),
+                Err(_) => continue,
+            };
+            if notes.insert(note.clone()) {
+                err.span_note(invalid_src_id_span(), note);
+            }
+        }
+        err
+    }
+
+    /// Types with name similar to name of `from`, that can be converted to `to`,
+    /// the most similar first
+    fn similar_types_with_path(&self, from: &RustType, to: &RustType) -> Vec<SmolStr> {
+        let mut with_path = self.connected_types(&[to.graph_idx], petgraph::Incoming);
+        with_path.remove(&to.graph_idx);
+        self.similar_types(from, with_path)
+    }
+
+    /// Types reachable from `start` in conversation graph,
+    /// `petgraph::Incoming` means moving against direction of edges
+    fn connected_types(
+        &self,
+        start: &[RustTypeIdx],
+        direction: petgraph::Direction,
+    ) -> FxHashSet<RustTypeIdx> {
+        let mut visited = FxHashSet::default();
+        let mut to_visit = start.to_vec();
+        while let Some(idx) = to_visit.pop() {
+            for next in self.conv_graph.neighbors_directed(idx, direction) {
+                if visited.insert(next) {
+                    to_visit.push(next);
+                }
+            }
+        }
+        visited
+    }
+
+    /// Names of `candidates` similar to name of `from`, the most similar first
+    fn similar_types(&self, from: &RustType, candidates: FxHashSet<RustTypeIdx>) -> Vec<SmolStr> {
+        const MAX_SUGGESTIONS: usize = 3;
+        let strip_spaces = |name: &str| name.replace(' ', "");
+        let from_name = strip_spaces(&from.normalized_name);
+        let max_dist = std::cmp::max(from_name.len(), 3) / 3;
+
+        let mut similar: Vec<(usize, SmolStr)> = candidates
+            .into_iter()
+            .filter(|idx| *idx != from.graph_idx)
+            .filter_map(|idx| {
+                let name = &self.conv_graph[idx].normalized_name;
+                let dist = edit_distance(&from_name, &strip_spaces(name));
+                if dist <= max_dist {
+                    Some((dist, name.clone()))
+                } else {
+                    None
+                }
+            })
+            .collect();
+        similar.sort();
+        similar
+            .into_iter()
+            .take(MAX_SUGGESTIONS)
+            .map(|(_, name)| name)
+            .collect()
+    }
+
     fn build_path_if_possible(
         &mut self,
         start_from: &RustType,
@@ -687,6 +993,15 @@ impl TypeMap {
             rust_ty, direction
         );
 
+        if let Some(ftype) = self.try_instantiate_generic_ftype(
+            rust_ty,
+            direction,
+            build_for_sp,
+            &calc_this_type_for_method,
+        ) {
+            return Some(ftype);
+        }
+
         let mut new_foreign_types = FxHashSet::default();
         for edge in &self.generic_edges {
             if let Some(ref to_foreigner_hint) = edge.to_foreigner_hint {
@@ -819,6 +1134,69 @@ impl TypeMap {
         ret
     }
 
+    /// Try to use `foreign_typemap!` with generic parameters to create
+    /// foreign type for `rust_ty`
+    fn try_instantiate_generic_ftype(
+        &mut self,
+        rust_ty: &RustType,
+        direction: petgraph::Direction,
+        build_for_sp: SourceIdSpan,
+        calc_this_type_for_method: &dyn Fn(&TypeMap, &ForeignerClassInfo) -> Option<Type>,
+    ) -> Option<ForeignType> {
+        for idx in 0..self.generic_ftypes.len() {
+            let rule = &self.generic_ftypes[idx];
+            let conv = match direction {
+                petgraph::Direction::Outgoing => rule.into_from_rust.as_ref(),
+                petgraph::Direction::Incoming => rule.from_into_rust.as_ref(),
+            };
+            let conv = match conv {
+                Some(x) => x,
+                None => continue,
+            };
+            let params =
+                match instantiate_generic_types(&rule.generic_params, &conv.rust_ty, &rust_ty.ty) {
+                    Some(params) => params,
+                    None => continue,
+                };
+            let bounds_ok = {
+                let trait_bounds = get_trait_bounds(&rule.generic_params);
+                params.iter().all(|(param, ty)| {
+                    let requires = match trait_bounds.iter().find(|x| x.ty_param.as_ref() == param)
+                    {
+                        Some(x) => &x.trait_names,
+                        None => return true,
+                    };
+                    //bounds of `impl Trait` already checked during instantiation
+                    if let Type::ImplTrait(_) | Type::TraitObject(_) = ty {
+                        return true;
+                    }
+                    match self.rust_names_map.get(normalize_ty_lifetimes(ty)) {
+                        Some(idx) => self.conv_graph[*idx].implements.contains_subset(requires),
+                        None => false,
+                    }
+                })
+            };
+            if !bounds_ok {
+                continue;
+            }
+            match self.instantiate_generic_ftype(
+                idx,
+                rust_ty,
+                direction,
+                &params,
+                build_for_sp,
+                calc_this_type_for_method,
+            ) {
+                Ok(ftype) => return Some(ftype),
+                Err(reason) => debug!(
+                    "map foreign: can not instantiate generic foreign type for {}: {}",
+                    rust_ty, reason
+                ),
+            }
+        }
+        None
+    }
+
     pub(crate) fn find_foreigner_class_with_such_this_type<
         F: Fn(&TypeMap, &ForeignerClassInfo) -> Option<Type>,
     >(
@@ -936,6 +1314,28 @@ impl TypeMap {
             .get(&name)
             .map(|idx| self.conv_graph[*idx].clone())
     }
+
+    /// Find direct conversation rule from `from` to some type `ty_name\0suffix`,
+    /// for example conversation created via `#[swig_to_foreigner_hint = "suffix"]`.
+    /// Return destination type and suffix
+    pub(crate) fn find_direct_conversation_to_type_with_suffix(
+        &self,
+        from: &RustType,
+        ty_name: &str,
+    ) -> Option<(RustType, SmolStr)> {
+        self.conv_graph
+            .neighbors_directed(from.graph_idx, petgraph::Direction::Outgoing)
+            .map(|idx| &self.conv_graph[idx])
+            .find_map(|to| {
+                let name = to.normalized_name.as_str();
+                match name.find('\0') {
+                    Some(pos) if &name[0..pos] == ty_name => {
+                        Some((to.clone(), name[pos + 1..].into()))
+                    }
+                    _ => None,
+                }
+            })
+    }
 }
 
 impl ops::Index<ForeignType> for TypeMap {
@@ -977,6 +1377,18 @@ pub(crate) fn make_unique_rust_typename(
     format!("{}{}{}", not_unique_name, 0 as char, suffix_to_make_unique)
 }
 
+/// Human readable form of result of `make_unique_rust_typename`
+fn display_unique_typename(name: &str) -> String {
+    match name.find('\0') {
+        Some(pos) => format!(
+            "{} (for foreign type '{}')",
+            &name[0..pos],
+            &name[pos + 1..]
+        ),
+        None => name.to_string(),
+    }
+}
+
 pub(crate) fn make_unique_rust_typename_if_need(
     rust_typename: String,
     suffix: Option<String>,
@@ -1053,6 +1465,24 @@ fn find_conversation_path(
     }
 }
 
+/// Levenshtein distance between two strings
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut prev_row: Vec<usize> = (0..=b.len()).collect();
+    let mut cur_row = vec![0; b.len() + 1];
+    for (i, a_ch) in a.chars().enumerate() {
+        cur_row[0] = i + 1;
+        for (j, b_ch) in b.iter().enumerate() {
+            let subst_cost = if a_ch == *b_ch { 0 } else { 1 };
+            cur_row[j + 1] = (prev_row[j] + subst_cost)
+                .min(prev_row[j + 1] + 1)
+                .min(cur_row[j] + 1);
+        }
+        mem::swap(&mut prev_row, &mut cur_row);
+    }
+    prev_row[b.len()]
+}
+
 fn merge_path_to_conv_map(path: PossiblePath, conv_map: &mut TypeMap) {
     let PossiblePath {
         new_edges,
@@ -1279,4 +1709,72 @@ mod tests {
         )
         .is_none());
     }
+
+    #[test]
+    fn test_explain_missing_path() {
+        let _ = env_logger::try_init();
+        let mut types_map = TypeMap::default();
+        let mut src_reg = SourceRegistry::default();
+        let src_id = src_reg.register(SourceCode {
+            id_of_code: "test_explain_missing_path".into(),
+            code: include_str!("java_jni/jni-include.rs").into(),
+        });
+        types_map.merge(src_id, src_reg.src(src_id), 64).unwrap();
+        types_map.find_or_alloc_rust_type_that_implements(
+            &parse_type! { Foo },
+            "SwigForeignClass",
+            SourceId::none(),
+        );
+        let boo_ty = types_map.find_or_alloc_rust_type(&parse_type! { Boo }, SourceId::none());
+
+        let foo_ref_ty =
+            types_map.find_or_alloc_rust_type(&parse_type! { &mut Foo }, SourceId::none());
+        let rc_refcell_foo_ty = types_map
+            .find_or_alloc_rust_type(&parse_type! { &mut Rc<RefCell<Foo>> }, SourceId::none());
+        types_map
+            .convert_rust_types(
+                &rc_refcell_foo_ty,
+                &foo_ref_ty,
+                "a0",
+                "jlong",
+                invalid_src_id_span(),
+            )
+            .expect("path from &mut Rc<RefCell<Foo>> to &mut Foo NOT exists");
+
+        let notes_for_missing_path = |types_map: &mut TypeMap, from: &RustType, to: &RustType| {
+            let err = types_map
+                .convert_rust_types(from, to, "a0", "jlong", invalid_src_id_span())
+                .expect_err("path exists");
+            let notes: Vec<String> = err.to_public(&src_reg).diagnostics()[0]
+                .notes()
+                .iter()
+                .map(|x| x.message().to_string())
+                .collect();
+            println!("notes: {:#?}", notes);
+            notes
+        };
+
+        let rc_refcell_boo_ty = types_map
+            .find_or_alloc_rust_type(&parse_type! { &mut Rc<RefCell<Boo>> }, SourceId::none());
+        let notes = notes_for_missing_path(&mut types_map, &rc_refcell_boo_ty, &foo_ref_ty);
+        assert!(notes
+            .iter()
+            .any(|x| x.starts_with("did you mean '& mut Rc < RefCell < Foo > >'")));
+
+        let jobject_ty =
+            types_map.find_or_alloc_rust_type(&parse_type! { jobject }, SourceId::none());
+        let notes = notes_for_missing_path(&mut types_map, &boo_ty, &jobject_ty);
+        assert!(notes.contains(
+            &"generic rule `T -> jobject` would apply if 'Boo' implemented `SwigForeignClass`"
+                .into()
+        ));
+    }
+
+    #[test]
+    fn test_edit_distance() {
+        assert_eq!(0, edit_distance("Vec<Foo>", "Vec<Foo>"));
+        assert_eq!(1, edit_distance("Vec<Boo>", "Vec<Foo>"));
+        assert_eq!(3, edit_distance("", "Foo"));
+        assert_eq!(3, edit_distance("kitten", "sitting"));
+    }
 }
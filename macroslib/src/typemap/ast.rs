@@ -30,7 +30,7 @@ use crate::{
     source_registry::SourceId,
     typemap::{
         make_unique_rust_typename, make_unique_rust_typename_if_need,
-        ty::{RustType, TraitNamesSet},
+        ty::{ImplementsSet, RustType, TraitNamesSet},
     },
 };
 
@@ -142,6 +142,20 @@ pub(crate) fn normalize_ty_lifetimes(ty: &syn::Type) -> &'static str {
     with_normalize_ty_lifetimes_cache(|cache| cache.insert(ty, type_str))
 }
 
+/// Why generic rule can not be used to convert type
+#[derive(Debug)]
+pub(crate) enum ConvRejection {
+    /// Type does not match `from_ty` of rule
+    TypeMismatch,
+    /// Type that substitutes type parameter does not implement traits from bounds
+    TraitBounds {
+        ty: String,
+        missing_traits: Vec<String>,
+    },
+    /// Foreign type of input does not match `from_foreigner_hint`
+    ForeignerHintMismatch,
+}
+
 #[derive(Debug)]
 pub(crate) struct GenericTypeConv {
     pub src_id: SourceId,
@@ -178,6 +192,19 @@ impl GenericTypeConv {
         goal_ty: Option<&RustType>,
         others: OtherRustTypes,
     ) -> Option<(syn::Type, SmolStr)>
+    where
+        OtherRustTypes: Fn(&str) -> Option<&'a RustType>,
+    {
+        self.check_conv_possible(ty, goal_ty, others).ok()
+    }
+
+    /// The same as `is_conv_possible`, but in case of failure returns reason
+    pub(crate) fn check_conv_possible<'a, OtherRustTypes>(
+        &self,
+        ty: &RustType,
+        goal_ty: Option<&RustType>,
+        others: OtherRustTypes,
+    ) -> Result<(syn::Type, SmolStr), ConvRejection>
     where
         OtherRustTypes: Fn(&str) -> Option<&'a RustType>,
     {
@@ -192,7 +219,7 @@ impl GenericTypeConv {
             subst_map.insert(&ty_p.ident, None);
         }
         if !is_second_subst_of_first(&self.from_ty, &ty.ty, &mut subst_map) {
-            return None;
+            return Err(ConvRejection::TypeMismatch);
         }
         trace!(
             "is_conv_possible: {} is subst of {:?}, check trait bounds",
@@ -208,19 +235,25 @@ impl GenericTypeConv {
                     *subst_it,
                     trait_bounds
                 );
-                let traits_bound_not_match = |idx: usize| {
+                let val_name = normalize_ty_lifetimes(val);
+                let missing_traits = |idx: usize| {
                     let requires = &trait_bounds[idx].trait_names;
-                    let val_name = normalize_ty_lifetimes(val);
-
-                    others(val_name).map_or(true, |rt| !rt.implements.contains_subset(requires))
+                    match others(val_name) {
+                        Some(rt) => rt.implements.missing_traits(requires),
+                        None => ImplementsSet::default().missing_traits(requires),
+                    }
                 };
-                if trait_bounds
+                if let Some(missing_traits) = trait_bounds
                     .iter()
                     .position(|it| it.ty_param.as_ref() == subst_it.ident)
-                    .map_or(false, traits_bound_not_match)
+                    .map(missing_traits)
+                    .filter(|x| !x.is_empty())
                 {
                     trace!("is_conv_possible: trait bounds check failed");
-                    return None;
+                    return Err(ConvRejection::TraitBounds {
+                        ty: val_name.to_string(),
+                        missing_traits,
+                    });
                 }
             } else {
                 has_unbinded = true;
@@ -252,7 +285,7 @@ impl GenericTypeConv {
                 let clean_from_ty = normalize_ty_lifetimes(&self.from_ty);
                 if ty.normalized_name != make_unique_rust_typename(&clean_from_ty, &foreign_name) {
                     trace!("is_conv_possible: check failed by from_foreigner_hint check");
-                    return None;
+                    return Err(ConvRejection::ForeignerHintMismatch);
                 }
             }
         }
@@ -280,7 +313,16 @@ impl GenericTypeConv {
             to_suffix,
         )
         .into();
-        Some((to_ty, normalized_name))
+        Ok((to_ty, normalized_name))
+    }
+
+    /// Can result of rule be `ty`, without checking of input
+    pub(crate) fn can_produce(&self, ty: &RustType) -> bool {
+        let mut subst_map = TyParamsSubstMap::default();
+        for ty_p in self.generic_params.type_params() {
+            subst_map.insert(&ty_p.ident, None);
+        }
+        is_second_subst_of_first(&self.to_ty, &ty.ty, &mut subst_map)
     }
 }
 
@@ -319,6 +361,17 @@ fn is_second_subst_of_first(ty1: &Type, ty2: &Type, subst_map: &mut TyParamsSubs
             }
             true
         }
+        (Type::Path(syn::TypePath { path: ref p1, .. }), Type::ImplTrait(_))
+            if p1.segments.len() == 1 =>
+        {
+            match subst_map.get_mut(&p1.segments[0].ident) {
+                Some(subst) if subst.is_none() => {
+                    *subst = Some(ty2.clone());
+                    true
+                }
+                _ => false,
+            }
+        }
         (Type::Reference(ref mut_ty1), Type::Reference(ref mut_ty2)) => {
             if mut_ty1.mutability != mut_ty2.mutability {
                 trace!("is_second_substitude_of_first mutable not match");
@@ -618,6 +671,210 @@ pub(crate) fn if_ty_result_return_ok_type(ty: &Type) -> Option<Type> {
     Some(to_ty)
 }
 
+/// Kind of Rust map from `std::collections`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum MapKind {
+    HashMap,
+    BTreeMap,
+}
+
+/// If `ty` is `HashMap<K, V>` or `BTreeMap<K, V>` returns kind of map, `K` and `V`
+pub(crate) fn if_map_return_key_value_types(ty: &RustType) -> Option<(MapKind, Type, Type)> {
+    let generic_params: syn::Generics = parse_quote! { <K, V> };
+    let key_ty: Type = parse_quote! { K };
+    let value_ty: Type = parse_quote! { V };
+    let maps: [(MapKind, Type); 2] = [
+        (MapKind::HashMap, parse_quote! { HashMap<K, V> }),
+        (MapKind::BTreeMap, parse_quote! { BTreeMap<K, V> }),
+    ];
+    for (kind, from_ty) in &maps {
+        let key =
+            GenericTypeConv::simple_new(from_ty.clone(), key_ty.clone(), generic_params.clone())
+                .is_conv_possible(ty, None, |_| None)
+                .map(|x| x.0);
+        if let Some(key) = key {
+            let value = GenericTypeConv::simple_new(from_ty.clone(), value_ty, generic_params)
+                .is_conv_possible(ty, None, |_| None)
+                .map(|x| x.0)?;
+            return Some((*kind, key, value));
+        }
+    }
+    None
+}
+
+/// If `ty` is `impl Future<Output = T>` or `Pin<Box<dyn Future<Output = T>>>`
+/// (with any additional bounds like `Send`) returns `T`
+pub(crate) fn if_future_return_output_type(ty: &Type) -> Option<Type> {
+    fn future_output<'a, I>(mut bounds: I) -> Option<Type>
+    where
+        I: Iterator<Item = &'a syn::TypeParamBound>,
+    {
+        bounds.find_map(|bound| {
+            let trait_bound = match bound {
+                syn::TypeParamBound::Trait(x) => x,
+                syn::TypeParamBound::Lifetime(_) => return None,
+            };
+            let last_seg = trait_bound.path.segments.last()?.into_value();
+            if last_seg.ident != "Future" {
+                return None;
+            }
+            match last_seg.arguments {
+                syn::PathArguments::AngleBracketed(ref args) => {
+                    args.args.iter().find_map(|arg| match arg {
+                        syn::GenericArgument::Binding(ref binding) if binding.ident == "Output" => {
+                            Some(binding.ty.clone())
+                        }
+                        _ => None,
+                    })
+                }
+                _ => None,
+            }
+        })
+    }
+    fn single_generic_type_arg<'a>(ty: &'a Type, name: &str) -> Option<&'a Type> {
+        let path = match ty {
+            syn::Type::Path(syn::TypePath { qself: None, path }) => path,
+            _ => return None,
+        };
+        let last_seg = path.segments.last()?.into_value();
+        if last_seg.ident != name {
+            return None;
+        }
+        match last_seg.arguments {
+            syn::PathArguments::AngleBracketed(ref args) if args.args.len() == 1 => {
+                match args.args[0] {
+                    syn::GenericArgument::Type(ref ty) => Some(ty),
+                    _ => None,
+                }
+            }
+            _ => None,
+        }
+    }
+
+    match ty {
+        syn::Type::ImplTrait(syn::TypeImplTrait { ref bounds, .. }) => future_output(bounds.iter()),
+        _ => {
+            let boxed = single_generic_type_arg(ty, "Pin")?;
+            match single_generic_type_arg(boxed, "Box")? {
+                syn::Type::TraitObject(syn::TypeTraitObject { ref bounds, .. }) => {
+                    future_output(bounds.iter())
+                }
+                _ => None,
+            }
+        }
+    }
+}
+
+/// Types for generic parameters
+pub(crate) type TyParamsSubstList = Vec<(Ident, Type)>;
+
+/// Match `ty` with `generic_ty`, and if it is possible returns
+/// types for generic parameters, for example `<T> Vec<T>` and `Vec<i32>` gives `T = i32`
+pub(crate) fn instantiate_generic_types(
+    generic_params: &syn::Generics,
+    generic_ty: &Type,
+    ty: &Type,
+) -> Option<TyParamsSubstList> {
+    let mut subst_map = TyParamsSubstMap::default();
+    for ty_p in generic_params.type_params() {
+        subst_map.insert(&ty_p.ident, None);
+    }
+    if !is_second_subst_of_first(generic_ty, ty, &mut subst_map)
+        || !check_impl_trait_bounds(generic_params, &mut subst_map)
+    {
+        return None;
+    }
+    let mut params = Vec::with_capacity(subst_map.len());
+    for it in subst_map.as_slice() {
+        params.push((it.ident.clone(), it.ty.clone()?));
+    }
+    Some(params)
+}
+
+/// Replace generic parameters in `ty` with their types
+pub(crate) fn replace_generic_params(ty: &Type, params: &TyParamsSubstList) -> Type {
+    let mut subst_map = TyParamsSubstMap::default();
+    for (ident, param_ty) in params {
+        subst_map.insert(ident, Some(param_ty.clone()));
+    }
+    replace_all_types_with(ty, &subst_map)
+}
+
+/// `impl Trait` and `dyn Trait` can not be checked via list of implemented traits,
+/// so compare their bounds with bounds of generic parameters. Arguments of `Fn*` bounds
+/// are matched too, so `<T, F: FnOnce(T)>` and `F = impl FnOnce(i32)` gives `T = i32`
+fn check_impl_trait_bounds(
+    generic_params: &syn::Generics,
+    subst_map: &mut TyParamsSubstMap,
+) -> bool {
+    for ty_p in generic_params.type_params() {
+        let ty = match subst_map.get_mut(&ty_p.ident) {
+            Some(Some(ty)) => ty.clone(),
+            _ => continue,
+        };
+        let ty_bounds = match ty {
+            Type::ImplTrait(ref x) => &x.bounds,
+            Type::TraitObject(ref x) => &x.bounds,
+            _ => continue,
+        };
+        for bound in &ty_p.bounds {
+            let bound = match bound {
+                syn::TypeParamBound::Trait(ref x) => x,
+                syn::TypeParamBound::Lifetime(_) => continue,
+            };
+            let bound = match bound.path.segments.last() {
+                Some(x) => x.into_value(),
+                None => continue,
+            };
+            let ty_bound = ty_bounds
+                .iter()
+                .filter_map(|x| match x {
+                    syn::TypeParamBound::Trait(ref x) => x.path.segments.last(),
+                    syn::TypeParamBound::Lifetime(_) => None,
+                })
+                .map(|x| x.into_value())
+                .find(|x| x.ident == bound.ident);
+            let ty_bound = match ty_bound {
+                Some(x) => x,
+                None => {
+                    trace!(
+                        "check_impl_trait_bounds: {} not implements {}",
+                        DisplayToTokens(&ty),
+                        bound.ident
+                    );
+                    return false;
+                }
+            };
+            if let (
+                syn::PathArguments::Parenthesized(ref args),
+                syn::PathArguments::Parenthesized(ref ty_args),
+            ) = (&bound.arguments, &ty_bound.arguments)
+            {
+                if args.inputs.len() != ty_args.inputs.len() {
+                    return false;
+                }
+                for (arg, ty_arg) in args.inputs.iter().zip(ty_args.inputs.iter()) {
+                    let arg = replace_all_types_with(arg, subst_map);
+                    if !is_second_subst_of_first(&arg, ty_arg, subst_map) {
+                        return false;
+                    }
+                }
+                match (&args.output, &ty_args.output) {
+                    (syn::ReturnType::Default, syn::ReturnType::Default) => {}
+                    (syn::ReturnType::Type(_, ref out), syn::ReturnType::Type(_, ref ty_out)) => {
+                        let out = replace_all_types_with(out, subst_map);
+                        if !is_second_subst_of_first(&out, ty_out, subst_map) {
+                            return false;
+                        }
+                    }
+                    _ => return false,
+                }
+            }
+        }
+    }
+    true
+}
+
 pub(crate) fn check_if_smart_pointer_return_inner_type(
     ty: &RustType,
     smart_ptr_name: &str,
@@ -1030,6 +1287,41 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_work_with_future() {
+        assert_eq!(
+            "i32",
+            if_future_return_output_type(&str_to_ty("impl Future<Output = i32> + Send"))
+                .map(|x| normalize_ty_lifetimes(&x))
+                .unwrap(),
+        );
+        assert_eq!(
+            "Result < String , String >",
+            if_future_return_output_type(&str_to_ty(
+                "Pin<Box<dyn std::future::Future<Output = Result<String, String>> + Send>>"
+            ))
+            .map(|x| normalize_ty_lifetimes(&x))
+            .unwrap(),
+        );
+        assert!(if_future_return_output_type(&str_to_ty("Box<dyn Fn() -> i32>")).is_none());
+        assert!(if_future_return_output_type(&str_to_ty("Pin<Box<i32>>")).is_none());
+    }
+
+    #[test]
+    fn test_work_with_map() {
+        let (kind, key, value) =
+            if_map_return_key_value_types(&str_to_rust_ty("HashMap<String, Vec<i32>>")).unwrap();
+        assert_eq!(MapKind::HashMap, kind);
+        assert_eq!("String", normalize_ty_lifetimes(&key));
+        assert_eq!("Vec < i32 >", normalize_ty_lifetimes(&value));
+        let (kind, key, value) =
+            if_map_return_key_value_types(&str_to_rust_ty("BTreeMap<u64, Foo>")).unwrap();
+        assert_eq!(MapKind::BTreeMap, kind);
+        assert_eq!("u64", normalize_ty_lifetimes(&key));
+        assert_eq!("Foo", normalize_ty_lifetimes(&value));
+        assert!(if_map_return_key_value_types(&str_to_rust_ty("Vec<i32>")).is_none());
+    }
+
     #[test]
     fn test_work_with_rc() {
         let ty =
@@ -1077,6 +1369,40 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_instantiate_generic_types_with_fn_bound() {
+        let generic_params: syn::Generics = parse_quote! { <T, F: FnOnce(T)> };
+        let params = instantiate_generic_types(
+            &generic_params,
+            &str_to_ty("F"),
+            &str_to_ty("impl FnOnce(i32)"),
+        )
+        .unwrap();
+        assert_eq!(
+            vec!["T = i32", "F = impl FnOnce ( i32 )"],
+            params
+                .iter()
+                .map(|(id, ty)| format!("{} = {}", id, DisplayToTokens(ty)))
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(
+            str_to_ty("Option<i32>"),
+            replace_generic_params(&str_to_ty("Option<T>"), &params)
+        );
+        assert!(instantiate_generic_types(
+            &generic_params,
+            &str_to_ty("F"),
+            &str_to_ty("impl Fn(i32)"),
+        )
+        .is_none());
+        assert!(instantiate_generic_types(
+            &generic_params,
+            &str_to_ty("F"),
+            &str_to_ty("impl FnOnce(i32, i32)"),
+        )
+        .is_none());
+    }
+
     #[test]
     fn test_list_lifetimes() {
         let my_list_lifetimes = |code| -> Vec<String> {
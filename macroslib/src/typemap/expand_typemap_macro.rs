@@ -0,0 +1,600 @@
+//! Expansion of macros that can be used inside of `foreign_typemap!` with
+//! generic parameters: `swig_f_type!(T)`, `swig_i_type!(T)`, `concat_ident!(a, b)`,
+//! `convert_to_c!(expr)` and `convert_to_f!(expr)`
+
+use std::{cell::RefCell, ops::Range, rc::Rc};
+
+use log::debug;
+use proc_macro2::{Ident, TokenStream};
+use quote::quote;
+use smol_str::SmolStr;
+use syn::Type;
+
+use crate::{
+    error::SourceIdSpan,
+    source_registry::SourceId,
+    typemap::{
+        ast::{replace_generic_params, DisplayToTokens, TyParamsSubstList, TypeName},
+        ty::{
+            FTypeConvCode, ForeignConversationIntermediate, ForeignConversationRule, ForeignType,
+            RustType,
+        },
+        unpack_unique_typename, TypeConvEdge, TypeMap, FROM_VAR_TEMPLATE,
+    },
+    types::ForeignerClassInfo,
+};
+
+#[derive(Debug)]
+pub(in crate::typemap) enum TypeMapMacro {
+    /// `swig_f_type!(T)`, foreign type for Rust type
+    ForeignType(Type),
+    /// `swig_i_type!(T)`, type that used to pass value between Rust and foreign language,
+    /// `in_ident` if it is part of `concat_ident!`, in this case name of Rust type should
+    /// be used in both Rust and foreign code, so identifiers are the same
+    IntermediateType { ty: Type, in_ident: bool },
+    /// `convert_to_c!(expr)`, Rust code to convert value into intermediate type
+    ConvertToC(String),
+    /// `convert_to_f!(expr)`, foreign code to convert value of intermediate type
+    /// into foreign type
+    ConvertToF(String),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(in crate::typemap) enum CodeKind {
+    Rust,
+    Foreign,
+}
+
+/// Check that macro makes sense in code of such kind
+pub(in crate::typemap) fn check_macro_usage(
+    mac: &TypeMapMacro,
+    kind: CodeKind,
+) -> Result<(), String> {
+    match (mac, kind) {
+        (TypeMapMacro::ForeignType(_), CodeKind::Rust) => {
+            Err("swig_f_type! can not be used in Rust code".into())
+        }
+        (TypeMapMacro::ConvertToF(_), CodeKind::Rust) => {
+            Err("convert_to_f! can not be used in Rust code".into())
+        }
+        (TypeMapMacro::ConvertToC(_), CodeKind::Foreign) => {
+            Err("convert_to_c! can not be used in foreign code".into())
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Generic parameters of rule that is instantiated for concrete types
+struct MacroContext<'a> {
+    src_id: SourceId,
+    params: &'a TyParamsSubstList,
+    /// direction of conversation for types of generic parameters
+    direction: petgraph::Direction,
+    converted_param: Option<&'a Ident>,
+    build_for_sp: SourceIdSpan,
+    calc_this_type_for_method: &'a dyn Fn(&TypeMap, &ForeignerClassInfo) -> Option<Type>,
+    /// Rust code required for `convert_to_c!`
+    deps: Vec<TokenStream>,
+}
+
+/// Types that used to expand `swig_f_type!` and `swig_i_type!`
+struct MacroTypeInfo {
+    f_type: SmolStr,
+    i_type: RustType,
+    /// name of intermediate type in foreign language
+    i_type_foreign: SmolStr,
+    /// foreign code to convert intermediate type to foreign type
+    to_foreign_code: Option<FTypeConvCode>,
+}
+
+impl TypeMap {
+    /// Create foreign type for `rust_ty` with generic rule `self.generic_ftypes[rule_idx]`,
+    /// `params` - types of generic parameters, in case of failure returns reason
+    pub(in crate::typemap) fn instantiate_generic_ftype(
+        &mut self,
+        rule_idx: usize,
+        rust_ty: &RustType,
+        direction: petgraph::Direction,
+        params: &TyParamsSubstList,
+        build_for_sp: SourceIdSpan,
+        calc_this_type_for_method: &dyn Fn(&TypeMap, &ForeignerClassInfo) -> Option<Type>,
+    ) -> Result<ForeignType, String> {
+        let rule = &self.generic_ftypes[rule_idx];
+        let conv = match direction {
+            petgraph::Direction::Outgoing => rule.into_from_rust.as_ref(),
+            petgraph::Direction::Incoming => rule.from_into_rust.as_ref(),
+        }
+        .expect("Internal error: instantiation of generic rule without such direction");
+        let src_id = rule.src_id;
+        let foreign_name = conv.foreign_name.typename.clone();
+        let foreign_name_span = conv.foreign_name.span;
+        let conv_code = conv.conv_code.clone();
+        let rust_code = conv.rust_code.clone();
+        let input_to_output = conv.input_to_output;
+        let intermediate_ty = replace_generic_params(&conv.intermediate_ty, params);
+        let generic_intermediate_ty = DisplayToTokens(&conv.intermediate_ty).to_string();
+        let helpers = rule.helpers.clone();
+        let null_annotation = rule.null_annotation;
+        let c_types = rule.c_types.as_ref().map(|x| x.to_string());
+        let converted_param = rule.converted_param.clone();
+
+        let mut ctx = MacroContext {
+            src_id,
+            params,
+            //input_to_output creates foreign output, so types of parameters are output too
+            direction: if input_to_output {
+                petgraph::Direction::Outgoing
+            } else {
+                direction
+            },
+            converted_param: converted_param.as_ref(),
+            build_for_sp,
+            calc_this_type_for_method,
+            deps: Vec::new(),
+        };
+
+        let foreign_name = self.expand_code(&foreign_name, CodeKind::Foreign, &mut ctx)?;
+        let conv_code = match conv_code {
+            Some(code) => Some(FTypeConvCode::new(
+                self.expand_code(code.as_str(), CodeKind::Foreign, &mut ctx)?,
+                code.span(),
+            )),
+            None => None,
+        };
+        let mut expanded_helpers = Vec::with_capacity(helpers.len());
+        for helper in &helpers {
+            expanded_helpers.push(self.expand_code(helper, CodeKind::Foreign, &mut ctx)?);
+        }
+        let intermediate_ty = if has_typemap_macros(&generic_intermediate_ty) {
+            let code = self.expand_code(&generic_intermediate_ty, CodeKind::Rust, &mut ctx)?;
+            syn::parse_str::<Type>(&code)
+                .map_err(|err| format!("can not parse type '{}': {}", code, err))?
+        } else {
+            intermediate_ty
+        };
+        if let Some(c_types) = c_types {
+            let code = self.expand_code(&c_types, CodeKind::Rust, &mut ctx)?;
+            let code: TokenStream = syn::parse_str(&code)
+                .map_err(|err| format!("can not parse define_c_type! '{}': {}", code, err))?;
+            if let Some(code) = self.merge_generic_c_types(src_id, code)? {
+                ctx.deps.push(code);
+            }
+        }
+        let intermediate_rty = self.find_or_alloc_rust_type(&intermediate_ty, src_id);
+        let (from, to) = match direction {
+            petgraph::Direction::Outgoing => (rust_ty.clone(), intermediate_rty.clone()),
+            petgraph::Direction::Incoming => (intermediate_rty.clone(), rust_ty.clone()),
+        };
+        if let Some(code) = rust_code {
+            let code = self.expand_code(&code, CodeKind::Rust, &mut ctx)?;
+            let deps = &ctx.deps;
+            let dependency = if deps.is_empty() {
+                None
+            } else {
+                Some(quote! { #(#deps)* })
+            };
+            self.conv_graph.update_edge(
+                from.graph_idx,
+                to.graph_idx,
+                TypeConvEdge {
+                    code_template: code,
+                    dependency: Rc::new(RefCell::new(dependency)),
+                },
+            );
+        }
+        if self.find_path(&from, &to, build_for_sp).is_err() {
+            self.build_path_if_possible(&from, &to, build_for_sp);
+            self.find_path(&from, &to, build_for_sp)
+                .map_err(|_| format!("no conversation rule {} -> {}", from, to))?;
+        }
+
+        let conv_rule = match conv_code {
+            Some(conv_code) => ForeignConversationRule {
+                rust_ty: rust_ty.graph_idx,
+                intermediate: Some(ForeignConversationIntermediate {
+                    intermediate_ty: intermediate_rty.graph_idx,
+                    conv_code,
+                    input_to_output,
+                }),
+            },
+            None => ForeignConversationRule {
+                rust_ty: intermediate_rty.graph_idx,
+                intermediate: None,
+            },
+        };
+        debug!(
+            "map foreign: instantiate generic foreign type {} for {}",
+            foreign_name, rust_ty
+        );
+        let ftype_idx = self
+            .ftypes_storage
+            .find_or_alloc(TypeName::new(foreign_name, foreign_name_span));
+        let ftype = &mut self.ftypes_storage[ftype_idx];
+        let rule_slot = match direction {
+            petgraph::Direction::Outgoing => &mut ftype.into_from_rust,
+            petgraph::Direction::Incoming => &mut ftype.from_into_rust,
+        };
+        if rule_slot.is_none() {
+            *rule_slot = Some(conv_rule);
+        }
+        if null_annotation.is_some() {
+            ftype.null_annotation = null_annotation;
+        }
+        for helper in expanded_helpers {
+            if !ftype.helpers.contains(&helper) {
+                ftype.helpers.push(helper);
+            }
+        }
+        Ok(ftype_idx)
+    }
+
+    /// Register types from instantiated `define_c_type!`,
+    /// returns `None` if they were registered by previous instantiation
+    fn merge_generic_c_types(
+        &mut self,
+        src_id: SourceId,
+        c_types: TokenStream,
+    ) -> Result<Option<TokenStream>, String> {
+        let file: syn::File = syn::parse2(c_types.clone())
+            .map_err(|err| format!("can not parse define_c_type!: {}", err))?;
+        let already_registered = file.items.iter().all(|item| match item {
+            syn::Item::Struct(ref x) => self.c_types.contains_key(x.ident.to_string().as_str()),
+            _ => false,
+        });
+        if already_registered {
+            return Ok(None);
+        }
+        self.merge_c_types(src_id, c_types)
+            .map(Some)
+            .map_err(|err| err.to_string())
+    }
+
+    fn expand_code(
+        &mut self,
+        code: &str,
+        kind: CodeKind,
+        ctx: &mut MacroContext,
+    ) -> Result<String, String> {
+        expand_typemap_macros(code, &mut |mac| {
+            check_macro_usage(&mac, kind)?;
+            match mac {
+                TypeMapMacro::ForeignType(ty) => {
+                    Ok(self.macro_type_info(&ty, ctx)?.f_type.to_string())
+                }
+                TypeMapMacro::IntermediateType { ty, in_ident } => {
+                    let info = self.macro_type_info(&ty, ctx)?;
+                    if in_ident || kind == CodeKind::Rust {
+                        Ok(unpack_unique_typename(&info.i_type.normalized_name).to_string())
+                    } else {
+                        Ok(info.i_type_foreign.to_string())
+                    }
+                }
+                TypeMapMacro::ConvertToC(expr) => {
+                    if ctx.direction != petgraph::Direction::Outgoing {
+                        return Err(
+                            "convert_to_c! can be used only for values returned from Rust".into(),
+                        );
+                    }
+                    let param_ty = converted_param_type(ctx)?;
+                    let info = self.macro_type_info(&param_ty, ctx)?;
+                    let param_rty = self.find_or_alloc_rust_type(&param_ty, ctx.src_id);
+                    let (mut deps, conv_code) = self
+                        .convert_rust_types(
+                            &param_rty,
+                            &info.i_type,
+                            "swig_c_value",
+                            "",
+                            ctx.build_for_sp,
+                        )
+                        .map_err(|err| err.to_string())?;
+                    ctx.deps.append(&mut deps);
+                    Ok(format!(
+                        "{{ let swig_c_value: {} = {}; {} swig_c_value }}",
+                        unpack_unique_typename(&param_rty.normalized_name),
+                        expr,
+                        conv_code.trim()
+                    ))
+                }
+                TypeMapMacro::ConvertToF(expr) => {
+                    if ctx.direction != petgraph::Direction::Outgoing {
+                        return Err(
+                            "convert_to_f! can be used only for values returned from Rust".into(),
+                        );
+                    }
+                    let param_ty = converted_param_type(ctx)?;
+                    let info = self.macro_type_info(&param_ty, ctx)?;
+                    match info.to_foreign_code {
+                        None => Ok(expr),
+                        Some(ref code) if !code.is_statement() => {
+                            Ok(code.as_str().replace(FROM_VAR_TEMPLATE, &expr))
+                        }
+                        Some(_) => Err(format!(
+                            "convert_to_f! can not be used for {}, conversation code \
+                             of {} is not expression",
+                            DisplayToTokens(&param_ty),
+                            info.f_type
+                        )),
+                    }
+                }
+            }
+        })
+    }
+
+    fn macro_type_info(&mut self, ty: &Type, ctx: &MacroContext) -> Result<MacroTypeInfo, String> {
+        let ty = replace_generic_params(ty, ctx.params);
+        let rty = self.find_or_alloc_rust_type(&ty, ctx.src_id);
+        let ftype_idx = self
+            .map_through_conversation_to_foreign(
+                &rty,
+                ctx.direction,
+                ctx.build_for_sp,
+                ctx.calc_this_type_for_method,
+            )
+            .ok_or_else(|| format!("can not find foreign type for {}", rty))?;
+        let ftype = &self[ftype_idx];
+        let f_type = ftype.name.typename.clone();
+        let rule = match ctx.direction {
+            petgraph::Direction::Outgoing => ftype.into_from_rust.clone(),
+            petgraph::Direction::Incoming => ftype.from_into_rust.clone(),
+        }
+        .ok_or_else(|| format!("no conversation rule for foreign type {}", f_type))?;
+        match rule.intermediate {
+            Some(intermediate) => {
+                let i_type = self[intermediate.intermediate_ty].clone();
+                let i_ftype = self
+                    .map_through_conversation_to_foreign(
+                        &i_type,
+                        ctx.direction,
+                        ctx.build_for_sp,
+                        ctx.calc_this_type_for_method,
+                    )
+                    .ok_or_else(|| format!("can not find foreign type for {}", i_type))?;
+                Ok(MacroTypeInfo {
+                    f_type,
+                    i_type,
+                    i_type_foreign: self[i_ftype].name.typename.clone(),
+                    to_foreign_code: Some(intermediate.conv_code),
+                })
+            }
+            None => Ok(MacroTypeInfo {
+                i_type_foreign: f_type.clone(),
+                f_type,
+                i_type: self[rule.rust_ty].clone(),
+                to_foreign_code: None,
+            }),
+        }
+    }
+}
+
+/// Type of generic parameter converted by `convert_to_c!` and `convert_to_f!`
+fn converted_param_type(ctx: &MacroContext) -> Result<Type, String> {
+    let param = ctx
+        .converted_param
+        .ok_or_else(|| "there is no generic parameter to convert".to_string())?;
+    ctx.params
+        .iter()
+        .find(|(ident, _)| ident == param)
+        .map(|(_, ty)| ty.clone())
+        .ok_or_else(|| format!("unknown type of generic parameter {}", param))
+}
+
+const MACROS_NAMES: [&str; 5] = [
+    "swig_f_type",
+    "swig_i_type",
+    "concat_ident",
+    "convert_to_c",
+    "convert_to_f",
+];
+
+pub(in crate::typemap) fn has_typemap_macros(code: &str) -> bool {
+    find_next_macro(code, 0).is_some()
+}
+
+/// Replace all macros in `code` with result of `expand`
+pub(in crate::typemap) fn expand_typemap_macros<F>(
+    code: &str,
+    expand: &mut F,
+) -> Result<String, String>
+where
+    F: FnMut(TypeMapMacro) -> Result<String, String>,
+{
+    expand_macros(code, false, expand)
+}
+
+fn expand_macros<F>(code: &str, in_ident: bool, expand: &mut F) -> Result<String, String>
+where
+    F: FnMut(TypeMapMacro) -> Result<String, String>,
+{
+    let mut ret = String::with_capacity(code.len());
+    let mut pos = 0;
+    while let Some(call) = find_next_macro(code, pos) {
+        ret.push_str(&code[pos..call.start]);
+        let args = &code[call.args.clone()];
+        let expanded = match call.name {
+            "swig_f_type" => expand(TypeMapMacro::ForeignType(parse_type_arg(call.name, args)?))?,
+            "swig_i_type" => expand(TypeMapMacro::IntermediateType {
+                ty: parse_type_arg(call.name, args)?,
+                in_ident,
+            })?,
+            "convert_to_c" => {
+                let expr = expand_macros(args, in_ident, expand)?;
+                expand(TypeMapMacro::ConvertToC(expr.trim().to_string()))?
+            }
+            "convert_to_f" => {
+                let expr = expand_macros(args, in_ident, expand)?;
+                expand(TypeMapMacro::ConvertToF(expr.trim().to_string()))?
+            }
+            "concat_ident" => {
+                let mut ident = String::new();
+                for part in split_args(args) {
+                    ident.push_str(&to_ident_part(&expand_macros(part, true, expand)?));
+                }
+                if ident.is_empty() || ident.starts_with(|c: char| c.is_ascii_digit()) {
+                    return Err(format!(
+                        "concat_ident!({}) gives invalid identifier '{}'",
+                        args.trim(),
+                        ident
+                    ));
+                }
+                ident
+            }
+            _ => unreachable!(),
+        };
+        ret.push_str(&expanded);
+        pos = call.end;
+    }
+    ret.push_str(&code[pos..]);
+    Ok(ret)
+}
+
+fn parse_type_arg(name: &str, args: &str) -> Result<Type, String> {
+    syn::parse_str::<Type>(args)
+        .map_err(|err| format!("can not parse type in {}!({}): {}", name, args.trim(), err))
+}
+
+/// Replace all symbols that can not be used in identifier with `_`,
+/// for example `:: std :: os :: raw :: c_char` becomes `std_os_raw_c_char`
+fn to_ident_part(s: &str) -> String {
+    let mut ret = String::with_capacity(s.len());
+    let mut need_separator = false;
+    for c in s.chars() {
+        if c.is_ascii_alphanumeric() || c == '_' {
+            if need_separator && !ret.ends_with('_') {
+                ret.push('_');
+            }
+            need_separator = false;
+            ret.push(c);
+        } else if !ret.is_empty() {
+            need_separator = true;
+        }
+    }
+    ret
+}
+
+/// Split arguments of macro by commas that are not inside of brackets
+fn split_args(args: &str) -> Vec<&str> {
+    let mut ret = Vec::new();
+    let mut depth = 0usize;
+    let mut start = 0;
+    for (i, c) in args.char_indices() {
+        match c {
+            '(' | '[' | '{' | '<' => depth += 1,
+            ')' | ']' | '}' | '>' => depth = depth.saturating_sub(1),
+            ',' if depth == 0 => {
+                ret.push(&args[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    ret.push(&args[start..]);
+    ret
+}
+
+struct MacroCall {
+    name: &'static str,
+    start: usize,
+    args: Range<usize>,
+    end: usize,
+}
+
+/// Find first `name!(args)` in `code` after `from`, `name` and `!`
+/// can be separated by spaces, because of `TokenStream` to string conversation
+fn find_next_macro(code: &str, from: usize) -> Option<MacroCall> {
+    let is_ident_char = |c: char| c.is_ascii_alphanumeric() || c == '_';
+    let mut ret: Option<MacroCall> = None;
+    for name in &MACROS_NAMES {
+        let mut search_from = from;
+        while let Some(pos) = code[search_from..].find(name) {
+            let start = search_from + pos;
+            search_from = start + name.len();
+            if ret.as_ref().map(|x| x.start < start).unwrap_or(false) {
+                break;
+            }
+            if code[..start].chars().next_back().map(is_ident_char) == Some(true) {
+                continue;
+            }
+            let rest = code[search_from..].trim_start();
+            if !rest.starts_with('!') {
+                continue;
+            }
+            let rest = rest[1..].trim_start();
+            if !rest.starts_with('(') {
+                continue;
+            }
+            let args_start = code.len() - rest.len() + 1;
+            let mut depth = 1usize;
+            let args_end = code[args_start..].char_indices().find_map(|(i, c)| {
+                match c {
+                    '(' => depth += 1,
+                    ')' => depth -= 1,
+                    _ => {}
+                }
+                if depth == 0 {
+                    Some(args_start + i)
+                } else {
+                    None
+                }
+            });
+            if let Some(args_end) = args_end {
+                ret = Some(MacroCall {
+                    name,
+                    start,
+                    args: args_start..args_end,
+                    end: args_end + 1,
+                });
+                break;
+            }
+        }
+    }
+    ret
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::typemap::ast::DisplayToTokens;
+
+    #[test]
+    fn test_expand_typemap_macros() {
+        let mut expand = |mac: TypeMapMacro| -> Result<String, String> {
+            Ok(match mac {
+                TypeMapMacro::ForeignType(ty) => format!("F<{}>", DisplayToTokens(&ty)),
+                TypeMapMacro::IntermediateType {
+                    ty: _,
+                    in_ident: true,
+                } => ":: std :: os :: raw :: c_char".into(),
+                TypeMapMacro::IntermediateType {
+                    ty: _,
+                    in_ident: false,
+                } => "char".into(),
+                TypeMapMacro::ConvertToC(expr) => format!("to_c({})", expr),
+                TypeMapMacro::ConvertToF(expr) => format!("to_f({})", expr),
+            })
+        };
+        assert_eq!(
+            "struct CFnOnce_std_os_raw_c_char { cb: extern \"C\" fn(char) }",
+            expand_typemap_macros(
+                "struct concat_ident ! ( CFnOnce_ , swig_i_type ! ( T ) ) { cb: extern \"C\" fn(swig_i_type!(T)) }",
+                &mut expand
+            )
+            .unwrap()
+        );
+        assert_eq!(
+            "F<Vec < T >> x = to_f(f(ret)); my_swig_f_type!(T)",
+            expand_typemap_macros(
+                "swig_f_type!(Vec<T>) x = convert_to_f!(f(ret)); my_swig_f_type!(T)",
+                &mut expand
+            )
+            .unwrap()
+        );
+        assert_eq!(
+            "move |x| (p.cb)(to_c(x), p.ctx)",
+            expand_typemap_macros("move |x| (p.cb)(convert_to_c ! ( x ), p.ctx)", &mut expand)
+                .unwrap()
+        );
+        assert!(expand_typemap_macros("swig_f_type!(+)", &mut expand).is_err());
+        assert!(expand_typemap_macros("concat_ident!(1, x)", &mut expand).is_err());
+        assert!(!has_typemap_macros("swig_f_type (T)"));
+    }
+}
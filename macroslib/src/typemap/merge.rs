@@ -1,18 +1,27 @@
 use crate::typemap::ty::ForeignConversationRule;
-use std::{mem, rc::Rc};
+use std::{cell::RefCell, mem, rc::Rc};
 
 use log::{debug, info};
 use petgraph::graph::NodeIndex;
+use proc_macro2::{Ident, Span, TokenStream};
+use quote::quote;
 use rustc_hash::FxHashMap;
-use syn::spanned::Spanned;
+use syn::{parse_quote, spanned::Spanned, Type};
 
 use crate::{
     error::{DiagnosticError, Result},
+    lint::Lint,
     source_registry::SourceId,
     typemap::{
-        ast::TypeName,
-        parse_typemap_macro::{FTypeLeftRightPair, TypeMapConvRuleInfo},
-        ty::{ForeignConversationIntermediate, ForeignTypeS, ForeignTypesStorage},
+        ast::{DisplayToTokens, GenericTypeConv, TypeName},
+        expand_typemap_macro::{
+            check_macro_usage, expand_typemap_macros, has_typemap_macros, CodeKind, TypeMapMacro,
+        },
+        parse_typemap_macro::{FTypeConvRule, FTypeLeftRightPair, FTypeName, TypeMapConvRuleInfo},
+        ty::{
+            FTypeConvCode, ForeignConversationIntermediate, ForeignTypeS, ForeignTypesStorage,
+            GenericForeignTypeConv, GenericForeignTypeRule, NullAnnotation,
+        },
         TypeConvEdge, TypeMap,
     },
 };
@@ -43,6 +52,8 @@ impl TypeMap {
             ftypes_storage: new_ftypes_storage,
             generic_edges: mut new_generic_edges,
             utils_code: mut new_utils_code,
+            generic_ftypes: mut new_generic_ftypes,
+            c_types: new_c_types,
             ..
         } = new_data;
         add_new_ftypes(new_ftypes_storage, self, &new_node_to_our_map);
@@ -50,13 +61,15 @@ impl TypeMap {
         self.utils_code.append(&mut new_utils_code);
         //TODO: more intellect to process new generics
         self.generic_edges.append(&mut new_generic_edges);
+        self.generic_ftypes.append(&mut new_generic_ftypes);
+        self.c_types.extend(new_c_types);
         Ok(())
     }
 
     pub(in crate::typemap) fn merge_conv_rule(
         &mut self,
         src_id: SourceId,
-        ri: TypeMapConvRuleInfo,
+        mut ri: TypeMapConvRuleInfo,
     ) -> Result<()> {
         if let Some((r_ty, f_ty)) = ri.if_simple_rtype_ftype_map() {
             let r_ty = self.find_or_alloc_rust_type(r_ty, src_id).graph_idx;
@@ -67,6 +80,18 @@ impl TypeMap {
             )?;
             return Ok(());
         }
+        if let Some(generic_params) = ri.generic_params().cloned() {
+            return self.merge_generic_conv_rule(src_id, ri, generic_params);
+        }
+        check_no_typemap_macros(src_id, &ri)?;
+
+        let c_types_code = match ri.c_types.take() {
+            Some(c_types) => Some(self.merge_c_types(src_id, c_types)?),
+            None => None,
+        };
+        //both directions share dependency, so it will be generated only once
+        let dependency = Rc::new(RefCell::new(c_types_code));
+        let null_annotation = ftypes_null_annotation(&ri);
 
         let mut rtype_left_to_right = None;
         if let Some(rule) = ri.rtype_left_to_right {
@@ -84,8 +109,14 @@ impl TypeMap {
                 .find_or_alloc_rust_type(&rule.left_ty, src_id)
                 .graph_idx;
             let to_ty = self.find_or_alloc_rust_type(&right_ty, src_id).graph_idx;
-            self.conv_graph
-                .update_edge(from_ty, to_ty, TypeConvEdge::new(code.into(), None));
+            self.conv_graph.update_edge(
+                from_ty,
+                to_ty,
+                TypeConvEdge {
+                    code_template: code.into(),
+                    dependency: dependency.clone(),
+                },
+            );
             rtype_left_to_right = Some((from_ty, to_ty));
         }
 
@@ -105,30 +136,23 @@ impl TypeMap {
                 .find_or_alloc_rust_type(&rule.left_ty, src_id)
                 .graph_idx;
             let from_ty = self.find_or_alloc_rust_type(&right_ty, src_id).graph_idx;
-            self.conv_graph
-                .update_edge(from_ty, to_ty, TypeConvEdge::new(code.into(), None));
+            self.conv_graph.update_edge(
+                from_ty,
+                to_ty,
+                TypeConvEdge {
+                    code_template: code.into(),
+                    dependency,
+                },
+            );
             rtype_right_to_left = Some((from_ty, to_ty));
         }
 
+        let (ftype_into_from_rust, ftype_from_into_rust) =
+            split_ftype_rules(src_id, ri.ftype_left_to_right, ri.ftype_right_to_left)?;
         let mut ft_into_from_rust = None;
-        if let Some(rule) = ri.ftype_left_to_right {
-            let right_fty = match rule.left_right_ty {
-                FTypeLeftRightPair::OnlyLeft(left_ty) => {
-                    return Err(DiagnosticError::new(
-                        src_id,
-                        left_ty.sp,
-                        "rule (f_type 'from type' => 'to type') is not simple, but no 'to type'",
-                    ));
-                }
-                FTypeLeftRightPair::Both(left_ty, _right_ty) => {
-                    return Err(DiagnosticError::new(
-                        src_id,
-                        left_ty.sp,
-                        "not supported rule type: f_type 'from_type' => 'to type'",
-                    ));
-                }
-                FTypeLeftRightPair::OnlyRight(right_ty) => right_ty,
-            };
+        if let Some(rule) = ftype_into_from_rust {
+            let right_fty =
+                only_right_ftype(src_id, rule.left_right_ty, ConvDirection::IntoFromRust)?;
             let (rty_left, rty_right) = rtype_left_to_right.ok_or_else(|| {
                 DiagnosticError::new(
                     src_id,
@@ -136,41 +160,30 @@ impl TypeMap {
                     "no r_type corresponding to this f_type rule",
                 )
             })?;
-            let conv_code = rule.code.ok_or_else(|| {
-                DiagnosticError::new(src_id, right_fty.sp, "expect conversation code here")
-            })?;
 
             ft_into_from_rust = Some((
                 right_fty,
-                ForeignConversationRule {
-                    rust_ty: rty_left,
-                    intermediate: Some(ForeignConversationIntermediate {
-                        intermediate_ty: rty_right,
-                        conv_code,
-                    }),
+                match rule.code {
+                    Some(conv_code) => ForeignConversationRule {
+                        rust_ty: rty_left,
+                        intermediate: Some(ForeignConversationIntermediate {
+                            intermediate_ty: rty_right,
+                            conv_code,
+                            input_to_output: rule.input_to_output,
+                        }),
+                    },
+                    None => ForeignConversationRule {
+                        rust_ty: rty_right,
+                        intermediate: None,
+                    },
                 },
             ));
         }
 
         let mut ft_from_into_rust = None;
-        if let Some(rule) = ri.ftype_right_to_left {
-            let right_fty = match rule.left_right_ty {
-                FTypeLeftRightPair::OnlyLeft(left_ty) => {
-                    return Err(DiagnosticError::new(
-                        src_id,
-                        left_ty.sp,
-                        "rule (f_type 'to type' <= 'from type') is not simple, but no 'from type'",
-                    ));
-                }
-                FTypeLeftRightPair::Both(left_ty, _right_ty) => {
-                    return Err(DiagnosticError::new(
-                        src_id,
-                        left_ty.sp,
-                        "not supported rule type: f_type 'to type' <= 'from type'",
-                    ));
-                }
-                FTypeLeftRightPair::OnlyRight(right_ty) => right_ty,
-            };
+        if let Some(rule) = ftype_from_into_rust {
+            let right_fty =
+                only_right_ftype(src_id, rule.left_right_ty, ConvDirection::FromIntoRust)?;
             let (rty_right, rty_left) = rtype_right_to_left.ok_or_else(|| {
                 DiagnosticError::new(
                     src_id,
@@ -178,23 +191,27 @@ impl TypeMap {
                     "no r_type corresponding to this f_type rule",
                 )
             })?;
-            let conv_code = rule.code.ok_or_else(|| {
-                DiagnosticError::new(src_id, right_fty.sp, "expect conversation code here")
-            })?;
 
             ft_from_into_rust = Some((
                 right_fty,
-                ForeignConversationRule {
-                    rust_ty: rty_left,
-                    intermediate: Some(ForeignConversationIntermediate {
-                        intermediate_ty: rty_right,
-                        conv_code,
-                    }),
+                match rule.code {
+                    Some(conv_code) => ForeignConversationRule {
+                        rust_ty: rty_left,
+                        intermediate: Some(ForeignConversationIntermediate {
+                            intermediate_ty: rty_right,
+                            conv_code,
+                            input_to_output: rule.input_to_output,
+                        }),
+                    },
+                    None => ForeignConversationRule {
+                        rust_ty: rty_right,
+                        intermediate: None,
+                    },
                 },
             ));
         }
 
-        match (ft_into_from_rust, ft_from_into_rust) {
+        let ftype_idx = match (ft_into_from_rust, ft_from_into_rust) {
             (Some((ft1, into_from_rust)), Some((ft2, from_into_rust))) => {
                 if ft1 != ft2 {
                     return Err(DiagnosticError::new(
@@ -208,22 +225,481 @@ impl TypeMap {
                 let ftype_idx = self.ftypes_storage.find_or_alloc(name);
                 self.ftypes_storage[ftype_idx].into_from_rust = Some(into_from_rust);
                 self.ftypes_storage[ftype_idx].from_into_rust = Some(from_into_rust);
+                ftype_idx
             }
             (Some((ft, into_from_rust)), None) => {
                 let name = TypeName::new(ft.name, (src_id, ft.sp));
                 let ftype_idx = self.ftypes_storage.find_or_alloc(name);
                 self.ftypes_storage[ftype_idx].into_from_rust = Some(into_from_rust);
+                ftype_idx
             }
             (None, Some((ft, from_into_rust))) => {
                 let name = TypeName::new(ft.name, (src_id, ft.sp));
                 let ftype_idx = self.ftypes_storage.find_or_alloc(name);
                 self.ftypes_storage[ftype_idx].from_into_rust = Some(from_into_rust);
+                ftype_idx
+            }
+            (None, None) => {
+                if let Some(helper) = ri.f_helpers.first() {
+                    return Err(DiagnosticError::new(
+                        src_id,
+                        helper.sp,
+                        "there is no f_type rule, so helper code will be never used",
+                    ));
+                }
+                return Ok(());
+            }
+        };
+        let ftype = &mut self.ftypes_storage[ftype_idx];
+        if null_annotation.is_some() {
+            ftype.null_annotation = null_annotation;
+        }
+        ftype
+            .helpers
+            .extend(ri.f_helpers.into_iter().map(|x| x.code));
+
+        Ok(())
+    }
+
+    fn merge_generic_conv_rule(
+        &mut self,
+        src_id: SourceId,
+        ri: TypeMapConvRuleInfo,
+        generic_params: syn::Generics,
+    ) -> Result<()> {
+        let null_annotation = ftypes_null_annotation(&ri);
+        //C types should be generated for each instantiation together with code that uses them
+        let has_c_types = ri.c_types.is_some();
+        let mut macros_usage = TypeMapMacrosUsage::new(&generic_params);
+        if let Some(c_types) = ri.c_types.as_ref() {
+            macros_usage.check(src_id, c_types.span(), &c_types.to_string(), CodeKind::Rust)?;
+        }
+        for helper in &ri.f_helpers {
+            macros_usage.check(src_id, helper.sp, &helper.code, CodeKind::Foreign)?;
+        }
+
+        let mut rtype_left_to_right = None;
+        if let Some(rule) = ri.rtype_left_to_right {
+            let (right_ty, code) = if let (Some(right_ty), Some(code)) = (rule.right_ty, rule.code)
+            {
+                (right_ty, code)
+            } else {
+                return Err(DiagnosticError::new2(
+                    (src_id, rule.left_ty.span()),
+                    "rule (r_type 'from type' => 'to type') is not simple, but no code or 'to type'",
+                ));
+            };
+            let rust_code =
+                if macros_usage.check_rtype_rule(src_id, &right_ty, &code)? || has_c_types {
+                    Some(code.to_string())
+                } else {
+                    self.generic_edges.push(GenericTypeConv {
+                        src_id,
+                        from_ty: rule.left_ty.clone(),
+                        to_ty: right_ty.clone(),
+                        code_template: code.into(),
+                        dependency: Rc::new(RefCell::new(None)),
+                        generic_params: rule
+                            .generic_params
+                            .unwrap_or_else(|| generic_params.clone()),
+                        to_foreigner_hint: None,
+                        from_foreigner_hint: None,
+                    });
+                    None
+                };
+            rtype_left_to_right = Some((rule.left_ty, right_ty, rust_code));
+        }
+
+        let mut rtype_right_to_left = None;
+        if let Some(rule) = ri.rtype_right_to_left {
+            let (right_ty, code) = if let (Some(right_ty), Some(code)) = (rule.right_ty, rule.code)
+            {
+                (right_ty, code)
+            } else {
+                return Err(DiagnosticError::new(
+                    src_id, rule.left_ty.span(),
+                     "rule (r_type 'to type' <= 'from type') is not simple, but no code or 'from type'",
+                ));
+            };
+            let rust_code =
+                if macros_usage.check_rtype_rule(src_id, &right_ty, &code)? || has_c_types {
+                    Some(code.to_string())
+                } else {
+                    self.generic_edges.push(GenericTypeConv {
+                        src_id,
+                        from_ty: right_ty.clone(),
+                        to_ty: rule.left_ty.clone(),
+                        code_template: code.into(),
+                        dependency: Rc::new(RefCell::new(None)),
+                        generic_params: rule
+                            .generic_params
+                            .unwrap_or_else(|| generic_params.clone()),
+                        to_foreigner_hint: None,
+                        from_foreigner_hint: None,
+                    });
+                    None
+                };
+            rtype_right_to_left = Some((right_ty, rule.left_ty, rust_code));
+        }
+
+        let (ftype_into_from_rust, ftype_from_into_rust) =
+            split_ftype_rules(src_id, ri.ftype_left_to_right, ri.ftype_right_to_left)?;
+
+        let mut into_from_rust = None;
+        if let Some(rule) = ftype_into_from_rust {
+            let right_fty =
+                only_right_ftype(src_id, rule.left_right_ty, ConvDirection::IntoFromRust)?;
+            let (rty_left, rty_right, rust_code) = rtype_left_to_right.take().ok_or_else(|| {
+                DiagnosticError::new(
+                    src_id,
+                    right_fty.sp,
+                    "no r_type corresponding to this f_type rule",
+                )
+            })?;
+            macros_usage.check(src_id, right_fty.sp, &right_fty.name, CodeKind::Foreign)?;
+            if let Some(code) = rule.code.as_ref() {
+                macros_usage.check(src_id, code.span(), code.as_str(), CodeKind::Foreign)?;
+            }
+            into_from_rust = Some(GenericForeignTypeConv {
+                foreign_name: TypeName::new(right_fty.name, (src_id, right_fty.sp)),
+                rust_ty: rty_left,
+                intermediate_ty: rty_right,
+                conv_code: rule.code,
+                rust_code,
+                input_to_output: false,
+            });
+        }
+
+        let mut from_into_rust = None;
+        if let Some(rule) = ftype_from_into_rust {
+            let right_fty =
+                only_right_ftype(src_id, rule.left_right_ty, ConvDirection::FromIntoRust)?;
+            let (rty_right, rty_left, rust_code) = rtype_right_to_left.take().ok_or_else(|| {
+                DiagnosticError::new(
+                    src_id,
+                    right_fty.sp,
+                    "no r_type corresponding to this f_type rule",
+                )
+            })?;
+            macros_usage.check(src_id, right_fty.sp, &right_fty.name, CodeKind::Foreign)?;
+            if let Some(code) = rule.code.as_ref() {
+                macros_usage.check(src_id, code.span(), code.as_str(), CodeKind::Foreign)?;
+            }
+            from_into_rust = Some(GenericForeignTypeConv {
+                foreign_name: TypeName::new(right_fty.name, (src_id, right_fty.sp)),
+                rust_ty: rty_left,
+                intermediate_ty: rty_right,
+                conv_code: rule.code,
+                rust_code,
+                input_to_output: rule.input_to_output,
+            });
+        }
+        //rules with macros are instantiated only together with foreign type
+        for (rty, _, rust_code) in rtype_left_to_right.iter().chain(rtype_right_to_left.iter()) {
+            if rust_code.is_some() {
+                return Err(DiagnosticError::new(
+                    src_id,
+                    rty.span(),
+                    "r_type rule uses swig_i_type!, concat_ident!, convert_to_c! or define_c_type!, \
+                     but there is no f_type rule for it",
+                ));
+            }
+        }
+
+        if into_from_rust.is_some() || from_into_rust.is_some() {
+            let converted_param = macros_usage.converted_param(src_id)?;
+            self.generic_ftypes.push(GenericForeignTypeRule {
+                src_id,
+                generic_params,
+                into_from_rust,
+                from_into_rust,
+                null_annotation,
+                helpers: ri.f_helpers.into_iter().map(|x| x.code).collect(),
+                c_types: ri.c_types,
+                converted_param,
+            });
+        } else if let Some(helper) = ri.f_helpers.first() {
+            return Err(DiagnosticError::new(
+                src_id,
+                helper.sp,
+                "there is no f_type rule, so helper code will be never used",
+            ));
+        } else if let Some(c_types) = ri.c_types.as_ref() {
+            return Err(DiagnosticError::new(
+                src_id,
+                c_types.span(),
+                "there is no f_type rule, so define_c_type! in foreign_typemap! \
+                 with generic parameters will be never used",
+            ));
+        }
+        Ok(())
+    }
+
+    /// Register types from `define_c_type!`, returns Rust code for them
+    pub(in crate::typemap) fn merge_c_types(
+        &mut self,
+        src_id: SourceId,
+        c_types: TokenStream,
+    ) -> Result<TokenStream> {
+        let c_types_sp = c_types.span();
+        let file: syn::File =
+            syn::parse2(c_types).map_err(|err| DiagnosticError::from_syn_err(src_id, err))?;
+        if file.items.is_empty() {
+            return Err(DiagnosticError::new(
+                src_id,
+                c_types_sp,
+                "define_c_type! without types",
+            ));
+        }
+        for item in &file.items {
+            let item_struct = match item {
+                syn::Item::Struct(ref x) => x,
+                _ => {
+                    return Err(DiagnosticError::new(
+                        src_id,
+                        item.span(),
+                        "only structures are supported in define_c_type!",
+                    ));
+                }
+            };
+            let repr_c: syn::Attribute = parse_quote!(#[repr(C)]);
+            if !item_struct.attrs.contains(&repr_c) {
+                return Err(DiagnosticError::new(
+                    src_id,
+                    item_struct.ident.span(),
+                    "structure in define_c_type! should be marked with #[repr(C)]",
+                ));
+            }
+            if !item_struct.generics.params.is_empty() {
+                return Err(DiagnosticError::new(
+                    src_id,
+                    item_struct.generics.span(),
+                    "generic structures are not supported in define_c_type!",
+                ));
+            }
+            let ident = &item_struct.ident;
+            let ty: syn::Type = parse_quote!(#ident);
+            let rust_ty = self.find_or_alloc_rust_type(&ty, src_id);
+            self.add_foreign_rust_ty_idx(
+                TypeName::new(format!("struct {}", ident), (src_id, ident.span())),
+                rust_ty.graph_idx,
+            )?;
+            self.c_types
+                .insert(rust_ty.normalized_name.clone(), item_struct.clone());
+        }
+        let items = &file.items;
+        Ok(quote! { #(#items)* })
+    }
+}
+
+#[derive(Clone, Copy)]
+enum ConvDirection {
+    IntoFromRust,
+    FromIntoRust,
+}
+
+fn only_right_ftype(
+    src_id: SourceId,
+    left_right_ty: FTypeLeftRightPair,
+    direction: ConvDirection,
+) -> Result<FTypeName> {
+    match (left_right_ty, direction) {
+        (FTypeLeftRightPair::OnlyLeft(left_ty), ConvDirection::IntoFromRust) => {
+            Err(DiagnosticError::new(
+                src_id,
+                left_ty.sp,
+                "rule (f_type 'from type' => 'to type') is not simple, but no 'to type'",
+            ))
+        }
+        (FTypeLeftRightPair::OnlyLeft(left_ty), ConvDirection::FromIntoRust) => {
+            Err(DiagnosticError::new(
+                src_id,
+                left_ty.sp,
+                "rule (f_type 'to type' <= 'from type') is not simple, but no 'from type'",
+            ))
+        }
+        (FTypeLeftRightPair::Both(left_ty, _right_ty), ConvDirection::IntoFromRust) => {
+            Err(DiagnosticError::new(
+                src_id,
+                left_ty.sp,
+                "not supported rule type: f_type 'from_type' => 'to type'",
+            ))
+        }
+        (FTypeLeftRightPair::Both(left_ty, _right_ty), ConvDirection::FromIntoRust) => {
+            Err(DiagnosticError::new(
+                src_id,
+                left_ty.sp,
+                "not supported rule type: f_type 'to type' <= 'from type'",
+            ))
+        }
+        (FTypeLeftRightPair::OnlyRight(right_ty), _) => Ok(right_ty),
+    }
+}
+
+/// Split f_type rules into rules for conversation into and from Rust,
+/// rule with `input_to_output` converts foreign input into Rust,
+/// so it is "from into Rust" rule in spite of `=>`
+fn split_ftype_rules(
+    src_id: SourceId,
+    left_to_right: Option<FTypeConvRule>,
+    right_to_left: Option<FTypeConvRule>,
+) -> Result<(Option<FTypeConvRule>, Option<FTypeConvRule>)> {
+    if let Some(rule) = right_to_left.as_ref().filter(|x| x.input_to_output) {
+        return Err(DiagnosticError::new(
+            src_id,
+            rule.left_right_ty.span(),
+            "input_to_output can be used only with rule f_type => 'to type'",
+        ));
+    }
+    match left_to_right {
+        Some(rule) if rule.input_to_output => {
+            if let Some(other) = right_to_left {
+                return Err(DiagnosticError::new(
+                    src_id,
+                    other.left_right_ty.span(),
+                    "conversation from foreign type is already defined by input_to_output rule",
+                )
+                .add_span_note(
+                    (src_id, rule.left_right_ty.span()),
+                    "input_to_output rule is here",
+                ));
             }
-            (None, None) => {}
+            Ok((None, Some(rule)))
         }
+        left_to_right => Ok((left_to_right, right_to_left)),
+    }
+}
 
+/// Macros like `swig_i_type!` make sense only if there are generic parameters
+fn check_no_typemap_macros(src_id: SourceId, ri: &TypeMapConvRuleInfo) -> Result<()> {
+    let mut codes = Vec::<(&str, Span)>::new();
+    for rule in ri
+        .rtype_left_to_right
+        .iter()
+        .chain(ri.rtype_right_to_left.iter())
+    {
+        if let Some(code) = rule.code.as_ref() {
+            codes.push((code.as_str(), code.span()));
+        }
+    }
+    for rule in ri
+        .ftype_left_to_right
+        .iter()
+        .chain(ri.ftype_right_to_left.iter())
+    {
+        if let Some(code) = rule.code.as_ref() {
+            codes.push((code.as_str(), code.span()));
+        }
+    }
+    for helper in &ri.f_helpers {
+        codes.push((&helper.code, helper.sp));
+    }
+    let c_types = ri.c_types.as_ref().map(|x| (x.to_string(), x.span()));
+    if let Some((code, sp)) = c_types.as_ref() {
+        codes.push((code, *sp));
+    }
+    for (code, sp) in codes {
+        if has_typemap_macros(code) {
+            return Err(DiagnosticError::new(
+                src_id,
+                sp,
+                "swig_f_type!, swig_i_type!, concat_ident!, convert_to_c! and convert_to_f! \
+                 can be used only in foreign_typemap! with generic parameters",
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Validation of macros in foreign_typemap! with generic parameters
+struct TypeMapMacrosUsage<'a> {
+    generic_params: &'a syn::Generics,
+    /// generic parameters used in `swig_f_type!` and `swig_i_type!`
+    used_params: Vec<Ident>,
+    /// first usage of `convert_to_c!` or `convert_to_f!`
+    convert_usage: Option<Span>,
+}
+
+impl<'a> TypeMapMacrosUsage<'a> {
+    fn new(generic_params: &'a syn::Generics) -> Self {
+        TypeMapMacrosUsage {
+            generic_params,
+            used_params: Vec::new(),
+            convert_usage: None,
+        }
+    }
+
+    fn check(&mut self, src_id: SourceId, sp: Span, code: &str, kind: CodeKind) -> Result<()> {
+        let generic_params = self.generic_params;
+        let used_params = &mut self.used_params;
+        let convert_usage = &mut self.convert_usage;
+        expand_typemap_macros(code, &mut |mac| {
+            check_macro_usage(&mac, kind)?;
+            match mac {
+                TypeMapMacro::ForeignType(ty) | TypeMapMacro::IntermediateType { ty, .. } => {
+                    let ty_code = DisplayToTokens(&ty).to_string();
+                    let words = ty_code.split(|c: char| !(c.is_ascii_alphanumeric() || c == '_'));
+                    for word in words {
+                        if let Some(param) = generic_params
+                            .type_params()
+                            .find(|x| x.ident == word)
+                            .map(|x| &x.ident)
+                        {
+                            if !used_params.contains(param) {
+                                used_params.push(param.clone());
+                            }
+                        }
+                    }
+                }
+                TypeMapMacro::ConvertToC(_) | TypeMapMacro::ConvertToF(_) => {
+                    convert_usage.get_or_insert(sp);
+                }
+            }
+            Ok("T".into())
+        })
+        .map_err(|err| DiagnosticError::new(src_id, sp, err))?;
         Ok(())
     }
+
+    /// Check code and target type of r_type rule, returns true if there are macros
+    fn check_rtype_rule(
+        &mut self,
+        src_id: SourceId,
+        right_ty: &Type,
+        code: &FTypeConvCode,
+    ) -> Result<bool> {
+        let right_ty_code = DisplayToTokens(right_ty).to_string();
+        if !has_typemap_macros(&right_ty_code) && !has_typemap_macros(code.as_str()) {
+            return Ok(false);
+        }
+        self.check(src_id, right_ty.span(), &right_ty_code, CodeKind::Rust)?;
+        self.check(src_id, code.span(), code.as_str(), CodeKind::Rust)?;
+        Ok(true)
+    }
+
+    fn converted_param(&self, src_id: SourceId) -> Result<Option<Ident>> {
+        match (self.convert_usage, self.used_params.as_slice()) {
+            (None, _) => Ok(None),
+            (Some(_), [param]) => Ok(Some(param.clone())),
+            (Some(sp), _) => Err(DiagnosticError::new(
+                src_id,
+                sp,
+                "convert_to_c! and convert_to_f! require exactly one generic parameter \
+                 used in swig_i_type! and swig_f_type!",
+            )),
+        }
+    }
+}
+
+fn ftypes_null_annotation(ri: &TypeMapConvRuleInfo) -> Option<NullAnnotation> {
+    ri.ftype_left_to_right
+        .as_ref()
+        .and_then(|x| x.null_annotation)
+        .or_else(|| {
+            ri.ftype_right_to_left
+                .as_ref()
+                .and_then(|x| x.null_annotation)
+        })
 }
 
 fn add_new_nodes(
@@ -269,6 +745,23 @@ fn add_new_edges(
                     data.conv_graph[*our_idx],
                     data.conv_graph[our_target],
                 );
+                let (new_from, our_from) = (
+                    &new_data.conv_graph[*new_node_idx],
+                    &data.conv_graph[*our_idx],
+                );
+                let warning = DiagnosticError::new(
+                    new_from.src_id,
+                    new_from.ty.span(),
+                    format!(
+                        "conversation rule {} -> {} replaces rule from previously merged type map",
+                        our_from, data.conv_graph[our_target]
+                    ),
+                )
+                .add_span_note(
+                    (our_from.src_id, our_from.ty.span()),
+                    "previously merged type map uses this type here",
+                );
+                data.add_warning(Lint::ShadowedTypemapRule, warning);
             }
             data.conv_graph.update_edge(
                 *our_idx,
@@ -333,6 +826,14 @@ fn ftype_merge(our: &mut ForeignTypeS, extrn_ft: ForeignTypeS) {
     if let Some(rule) = extrn_ft.from_into_rust {
         our.from_into_rust = Some(rule);
     }
+    if extrn_ft.null_annotation.is_some() {
+        our.null_annotation = extrn_ft.null_annotation;
+    }
+    for helper in extrn_ft.helpers {
+        if !our.helpers.contains(&helper) {
+            our.helpers.push(helper);
+        }
+    }
 }
 
 #[cfg(test)]
@@ -473,4 +974,63 @@ fn helper3() {
             vec!["helper1", "SwigInto", "SwigFrom", "helper2", "helper3"]
         );
     }
+
+    #[test]
+    fn test_merge_foreign_typemap_with_c_type() {
+        let mut types_map = TypeMap::default();
+        types_map
+            .merge(
+                SourceId::none(),
+                r#"
+foreign_typemap!(
+    define_c_type!(
+        #[repr(C)]
+        struct CPoint {
+            x: i32,
+            y: i32,
+        }
+    );
+    ($p:r_type) Point => CPoint {
+        $out = CPoint { x: $p.x, y: $p.y }
+    };
+    ($p:f_type, non_null) => "Point" "Point{$p.x, $p.y}";
+);
+"#,
+                64,
+            )
+            .unwrap();
+        let c_point = types_map.ty_to_rust_type(&parse_type! { CPoint });
+        assert!(types_map.find_c_type(&c_point).is_some());
+        let ftype = types_map
+            .ftypes_storage
+            .find_ftype_by_name("struct CPoint")
+            .unwrap();
+        assert_eq!(
+            c_point.graph_idx,
+            types_map[ftype].into_from_rust.as_ref().unwrap().rust_ty
+        );
+        let ftype = types_map
+            .ftypes_storage
+            .find_ftype_by_name("Point")
+            .unwrap();
+        assert_eq!(
+            Some(NullAnnotation::NonNull),
+            types_map[ftype].null_annotation
+        );
+
+        let err = types_map.merge(
+            SourceId::none(),
+            r#"
+foreign_typemap!(
+    define_c_type!(
+        struct CPoint2 {
+            x: i32,
+        }
+    );
+);
+"#,
+            64,
+        );
+        assert!(err.is_err());
+    }
 }
@@ -67,8 +67,14 @@ pub(in crate::typemap) fn parse(
         rust_to_foreign_cache: FxHashMap::default(),
         foreign_classes: Vec::new(),
         exported_enums: FxHashMap::default(),
+        exported_structs: FxHashMap::default(),
+        exported_data_enums: FxHashMap::default(),
+        exported_flags: FxHashMap::default(),
         traits_usage_code,
         ftypes_storage: ForeignTypesStorage::default(),
+        generic_ftypes: Vec::new(),
+        c_types: FxHashMap::default(),
+        warnings: Vec::new(),
     };
 
     macro_rules! handle_attrs {
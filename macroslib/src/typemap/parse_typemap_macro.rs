@@ -1,4 +1,7 @@
-use crate::typemap::{ty::FTypeConvCode, FROM_VAR_TEMPLATE, TO_VAR_TEMPLATE, TO_VAR_TYPE_TEMPLATE};
+use crate::typemap::{
+    ty::{FTypeConvCode, NullAnnotation},
+    FROM_VAR_TEMPLATE, TO_VAR_TEMPLATE, TO_VAR_TYPE_TEMPLATE,
+};
 use proc_macro2::Span;
 use proc_macro2::TokenStream;
 use smol_str::SmolStr;
@@ -12,11 +15,19 @@ pub(in crate::typemap) struct TypeMapConvRuleInfo {
     pub rtype_right_to_left: Option<RTypeConvRule>,
     pub ftype_left_to_right: Option<FTypeConvRule>,
     pub ftype_right_to_left: Option<FTypeConvRule>,
+    /// Content of `define_c_type!`
+    pub c_types: Option<TokenStream>,
+    /// Content of `define_helper_f_helper!`
+    pub f_helpers: Vec<FHelperCode>,
 }
 
 impl TypeMapConvRuleInfo {
     pub(in crate::typemap) fn if_simple_rtype_ftype_map(&self) -> Option<(&Type, &FTypeName)> {
-        if self.rtype_right_to_left.is_some() || self.ftype_right_to_left.is_some() {
+        if self.rtype_right_to_left.is_some()
+            || self.ftype_right_to_left.is_some()
+            || self.c_types.is_some()
+            || !self.f_helpers.is_empty()
+        {
             return None;
         }
         match (
@@ -28,15 +39,28 @@ impl TypeMapConvRuleInfo {
                     left_ty: ref r_ty,
                     right_ty: None,
                     code: None,
+                    generic_params: None,
                 }),
                 Some(FTypeConvRule {
                     left_right_ty: FTypeLeftRightPair::OnlyLeft(ref f_ty),
                     code: None,
+                    ..
                 }),
             ) => Some((r_ty, f_ty)),
             _ => None,
         }
     }
+
+    pub(in crate::typemap) fn generic_params(&self) -> Option<&syn::Generics> {
+        self.rtype_left_to_right
+            .as_ref()
+            .and_then(|x| x.generic_params.as_ref())
+            .or_else(|| {
+                self.rtype_right_to_left
+                    .as_ref()
+                    .and_then(|x| x.generic_params.as_ref())
+            })
+    }
 }
 
 #[derive(Debug, PartialEq)]
@@ -44,12 +68,17 @@ pub(in crate::typemap) struct RTypeConvRule {
     pub left_ty: Type,
     pub right_ty: Option<Type>,
     pub code: Option<FTypeConvCode>,
+    pub generic_params: Option<syn::Generics>,
 }
 
 #[derive(Debug, PartialEq)]
 pub(in crate::typemap) struct FTypeConvRule {
     pub left_right_ty: FTypeLeftRightPair,
     pub code: Option<FTypeConvCode>,
+    pub null_annotation: Option<NullAnnotation>,
+    /// `$var:input_to_output` was used, so foreign code creates
+    /// not only output, but also input for Rust
+    pub input_to_output: bool,
 }
 
 #[derive(Debug, PartialEq)]
@@ -59,6 +88,16 @@ pub(in crate::typemap) enum FTypeLeftRightPair {
     Both(FTypeName, FTypeName),
 }
 
+impl FTypeLeftRightPair {
+    pub(in crate::typemap) fn span(&self) -> Span {
+        match self {
+            FTypeLeftRightPair::OnlyLeft(x)
+            | FTypeLeftRightPair::OnlyRight(x)
+            | FTypeLeftRightPair::Both(x, _) => x.sp,
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub(in crate::typemap) struct FTypeName {
     pub name: SmolStr,
@@ -80,11 +119,23 @@ impl From<LitStr> for FTypeName {
     }
 }
 
+#[derive(Debug, Clone)]
+pub(in crate::typemap) struct FHelperCode {
+    pub code: String,
+    pub sp: Span,
+}
+
 mod kw {
     use syn::custom_keyword;
 
     custom_keyword!(r_type);
     custom_keyword!(f_type);
+    custom_keyword!(variable);
+    custom_keyword!(input_to_output);
+    custom_keyword!(non_null);
+    custom_keyword!(nullable);
+    custom_keyword!(define_c_type);
+    custom_keyword!(define_helper_f_helper);
 }
 
 enum RuleType {
@@ -98,36 +149,132 @@ enum ConvertRuleType<T> {
     RightToLeft(T),
 }
 
+/// Content of `(...)` before rule
+struct RuleParams {
+    rule: RuleType,
+    var_name: Option<Ident>,
+    temp_vars: Vec<Ident>,
+    input_to_output_var: Option<Ident>,
+    null_annotation: Option<NullAnnotation>,
+}
+
+impl RuleParams {
+    fn rule_span(&self) -> Span {
+        match self.rule {
+            RuleType::RType(ref x) => x.span(),
+            RuleType::FType(ref x) => x.span(),
+        }
+    }
+}
+
+impl syn::parse::Parse for RuleParams {
+    fn parse(params: syn::parse::ParseStream) -> syn::Result<Self> {
+        let mut rule = None;
+        let mut var_name = None;
+        let mut temp_vars = vec![];
+        let mut input_to_output_var = None;
+        let mut null_annotation = None;
+
+        loop {
+            let mut param_var = None;
+            if params.peek(token::Dollar) {
+                params.parse::<token::Dollar>()?;
+                param_var = Some(params.parse::<Ident>()?);
+                params.parse::<Token![:]>()?;
+            }
+            let kw_la = params.lookahead1();
+            if kw_la.peek(kw::r_type) || kw_la.peek(kw::f_type) {
+                let new_rule = if kw_la.peek(kw::r_type) {
+                    RuleType::RType(params.parse::<kw::r_type>()?)
+                } else {
+                    RuleType::FType(params.parse::<kw::f_type>()?)
+                };
+                if rule.is_some() {
+                    return Err(syn::Error::new(
+                        params.cursor().span(),
+                        "rule type (r_type or f_type) specified twice",
+                    ));
+                }
+                rule = Some(new_rule);
+                var_name = param_var;
+            } else if kw_la.peek(kw::variable) {
+                let keyword = params.parse::<kw::variable>()?;
+                temp_vars.push(param_var.ok_or_else(|| {
+                    syn::Error::new(keyword.span(), "expect $name:variable here")
+                })?);
+            } else if kw_la.peek(kw::input_to_output) {
+                let keyword = params.parse::<kw::input_to_output>()?;
+                input_to_output_var = Some(param_var.ok_or_else(|| {
+                    syn::Error::new(keyword.span(), "expect $name:input_to_output here")
+                })?);
+            } else if param_var.is_none() && kw_la.peek(kw::non_null) {
+                params.parse::<kw::non_null>()?;
+                null_annotation = Some(NullAnnotation::NonNull);
+            } else if param_var.is_none() && kw_la.peek(kw::nullable) {
+                params.parse::<kw::nullable>()?;
+                null_annotation = Some(NullAnnotation::Nullable);
+            } else {
+                return Err(kw_la.error());
+            }
+            if params.is_empty() {
+                break;
+            }
+            params.parse::<Token![,]>()?;
+        }
+        let rule = rule.ok_or_else(|| params.error("expect r_type or f_type here"))?;
+        let ret = RuleParams {
+            rule,
+            var_name,
+            temp_vars,
+            input_to_output_var,
+            null_annotation,
+        };
+        match ret.rule {
+            RuleType::RType(_)
+                if ret.input_to_output_var.is_some() || ret.null_annotation.is_some() =>
+            {
+                Err(syn::Error::new(
+                    ret.rule_span(),
+                    "input_to_output, non_null and nullable can be used only with f_type",
+                ))
+            }
+            RuleType::FType(_) if !ret.temp_vars.is_empty() => Err(syn::Error::new(
+                ret.rule_span(),
+                "temporary variables can be used only with r_type",
+            )),
+            _ => Ok(ret),
+        }
+    }
+}
+
 impl syn::parse::Parse for TypeMapConvRuleInfo {
     fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
         let mut rtype_left_to_right: Option<RTypeConvRule> = None;
         let mut rtype_right_to_left: Option<RTypeConvRule> = None;
         let mut ftype_left_to_right: Option<FTypeConvRule> = None;
         let mut ftype_right_to_left: Option<FTypeConvRule> = None;
+        let mut c_types: Option<TokenStream> = None;
+        let mut f_helpers = Vec::<FHelperCode>::new();
 
         while !input.is_empty() {
             if input.peek(token::Paren) {
                 let params;
                 parenthesized!(params in input);
+                let RuleParams {
+                    rule,
+                    var_name,
+                    temp_vars,
+                    input_to_output_var,
+                    null_annotation,
+                } = params.parse::<RuleParams>()?;
 
-                let mut var_name = None;
-                if params.peek(token::Dollar) {
-                    params.parse::<token::Dollar>()?;
-                    var_name = Some(params.parse::<Ident>()?);
-                    params.parse::<Token![:]>()?;
-                }
-
-                let rule;
-                let kw_la = params.lookahead1();
-                if kw_la.peek(kw::r_type) {
-                    rule = RuleType::RType(params.parse::<kw::r_type>()?);
-                } else if kw_la.peek(kw::f_type) {
-                    rule = RuleType::FType(params.parse::<kw::f_type>()?);
-                } else {
-                    return Err(kw_la.error());
-                }
                 match rule {
                     RuleType::RType(keyword) => {
+                        let generic_params = if input.peek(Token![<]) {
+                            Some(input.parse::<syn::Generics>()?)
+                        } else {
+                            None
+                        };
                         let left_ty = input.parse::<Type>()?;
 
                         let mut conv_rule_type = None;
@@ -163,7 +310,13 @@ impl syn::parse::Parse for TypeMapConvRuleInfo {
                                     format!("no $out or ${} in conversation code", var_name),
                                 ));
                             }
-                            code_str.push(';');
+                            if !code_str.trim_end().ends_with(';') {
+                                code_str.push(';');
+                            }
+                            for tmp_var in &temp_vars {
+                                code_str =
+                                    replace_temp_var_in_code(&code_str, tmp_var, conv_body.span())?;
+                            }
 
                             code = Some(FTypeConvCode::new2(
                                 code_str.replace(&d_var_name, FROM_VAR_TEMPLATE).replace(
@@ -172,6 +325,11 @@ impl syn::parse::Parse for TypeMapConvRuleInfo {
                                 ),
                                 conv_body.span(),
                             ));
+                        } else if !temp_vars.is_empty() {
+                            return Err(syn::Error::new(
+                                keyword.span(),
+                                "temporary variables defined, but there is no conversation code",
+                            ));
                         }
                         match conv_rule_type {
                             Some(ConvertRuleType::LeftToRight(right_ty)) => {
@@ -185,6 +343,7 @@ impl syn::parse::Parse for TypeMapConvRuleInfo {
                                     left_ty,
                                     right_ty: Some(right_ty),
                                     code,
+                                    generic_params,
                                 });
                             }
                             Some(ConvertRuleType::RightToLeft(right_ty)) => {
@@ -198,6 +357,7 @@ impl syn::parse::Parse for TypeMapConvRuleInfo {
                                     left_ty,
                                     right_ty: Some(right_ty),
                                     code,
+                                    generic_params,
                                 });
                             }
                             None => {
@@ -211,6 +371,7 @@ impl syn::parse::Parse for TypeMapConvRuleInfo {
                                     left_ty,
                                     right_ty: None,
                                     code: None,
+                                    generic_params,
                                 });
                             }
                         }
@@ -238,12 +399,32 @@ impl syn::parse::Parse for TypeMapConvRuleInfo {
                             let var_name = var_name.ok_or_else(|| {
                                 syn::Error::new(keyword.span(), "there is conversation code, but name of input variable not defined here")
                             })?;
-                            let var_name = format!("${}", var_name);
+                            let (in_var, out_var) = match input_to_output_var {
+                                Some(ref in_var) => {
+                                    (format!("${}", in_var), format!("${}", var_name))
+                                }
+                                None => (format!("${}", var_name), "$out".to_string()),
+                            };
+                            let code_val = code_str.value();
+                            if !code_val.contains(&in_var) {
+                                return Err(syn::Error::new(
+                                    code_str.span(),
+                                    format!("no {} in conversation code", in_var),
+                                ));
+                            }
                             code = Some(FTypeConvCode::new(
-                                code_str.value().replace(&var_name, FROM_VAR_TEMPLATE),
+                                code_val
+                                    .replace(&in_var, FROM_VAR_TEMPLATE)
+                                    .replace(&out_var, TO_VAR_TEMPLATE),
                                 code_str.span(),
                             ));
+                        } else if input_to_output_var.is_some() {
+                            return Err(syn::Error::new(
+                                keyword.span(),
+                                "input_to_output used, but there is no conversation code",
+                            ));
                         }
+                        let input_to_output = input_to_output_var.is_some();
                         match conv_rule_type {
                             Some(ConvertRuleType::LeftToRight(right_ty)) => {
                                 if ftype_left_to_right.is_some() {
@@ -259,6 +440,8 @@ impl syn::parse::Parse for TypeMapConvRuleInfo {
                                         FTypeLeftRightPair::OnlyRight(right_ty)
                                     },
                                     code,
+                                    null_annotation,
+                                    input_to_output,
                                 });
                             }
                             Some(ConvertRuleType::RightToLeft(right_ty)) => {
@@ -275,6 +458,8 @@ impl syn::parse::Parse for TypeMapConvRuleInfo {
                                         FTypeLeftRightPair::OnlyRight(right_ty)
                                     },
                                     code,
+                                    null_annotation,
+                                    input_to_output,
                                 });
                             }
                             None => {
@@ -293,13 +478,49 @@ impl syn::parse::Parse for TypeMapConvRuleInfo {
                                 ftype_left_to_right = Some(FTypeConvRule {
                                     left_right_ty: FTypeLeftRightPair::OnlyLeft(left_ty),
                                     code: None,
+                                    null_annotation,
+                                    input_to_output,
                                 });
                             }
                         }
                     }
                 }
+            } else if input.peek(kw::define_c_type) && input.peek2(Token![!]) {
+                let keyword = input.parse::<kw::define_c_type>()?;
+                input.parse::<Token![!]>()?;
+                let content;
+                parenthesized!(content in input);
+                if c_types.is_some() {
+                    return Err(syn::Error::new(
+                        keyword.span(),
+                        "duplicate of define_c_type!",
+                    ));
+                }
+                c_types = Some(content.parse::<TokenStream>()?);
+            } else if input.peek(kw::define_helper_f_helper) && input.peek2(Token![!]) {
+                input.parse::<kw::define_helper_f_helper>()?;
+                input.parse::<Token![!]>()?;
+                let content;
+                parenthesized!(content in input);
+                f_helpers.push(if content.peek(LitStr) {
+                    let code = content.parse::<LitStr>()?;
+                    FHelperCode {
+                        code: code.value(),
+                        sp: code.span(),
+                    }
+                } else {
+                    let code = content.parse::<TokenStream>()?;
+                    FHelperCode {
+                        code: code.to_string(),
+                        sp: code.span(),
+                    }
+                });
             } else {
                 let mac: syn::Macro = input.parse()?;
+                return Err(syn::Error::new(
+                    mac.span(),
+                    "unknown macro in foreign_typemap!, expect define_c_type! or define_helper_f_helper!",
+                ));
             }
             input.parse::<Token![;]>()?;
         }
@@ -308,10 +529,38 @@ impl syn::parse::Parse for TypeMapConvRuleInfo {
             rtype_right_to_left,
             ftype_left_to_right,
             ftype_right_to_left,
+            c_types,
+            f_helpers,
         })
     }
 }
 
+/// Replace `$tmp = ...` with declaration of variable,
+/// and other usages of `$tmp` with name of variable
+fn replace_temp_var_in_code(code: &str, tmp_var: &Ident, sp: Span) -> syn::Result<String> {
+    let d_tmp_var: TokenStream = parse_quote!($#tmp_var);
+    let d_tmp_var = d_tmp_var.to_string();
+    let decl = format!("{} =", d_tmp_var);
+    match code.find(&d_tmp_var) {
+        Some(pos) if code[pos..].starts_with(&decl) => {
+            let var_name = format!("{}_{}", TO_VAR_TEMPLATE, tmp_var);
+            let mut ret = String::with_capacity(code.len() + 10);
+            ret.push_str(&code[0..pos]);
+            ret.push_str("let mut ");
+            ret.push_str(&var_name);
+            ret.push_str(&code[pos + d_tmp_var.len()..].replace(&d_tmp_var, &var_name));
+            Ok(ret)
+        }
+        _ => Err(syn::Error::new(
+            sp,
+            format!(
+                "temporary variable ${} should be assigned before usage",
+                tmp_var
+            ),
+        )),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -357,6 +606,7 @@ $out = QDateTime::fromMSecsSinceEpoch($pin * 1000, Qt::UTC, 0);
                     "let {to_var}: {to_var_type} = if {from_var} { 1 } else { 0 };",
                     Span::call_site()
                 )),
+                generic_params: None,
             },
             rule.rtype_left_to_right.unwrap()
         );
@@ -369,6 +619,7 @@ $out = QDateTime::fromMSecsSinceEpoch($pin * 1000, Qt::UTC, 0);
                     "let {to_var}: {to_var_type} = ( {from_var} != 0 );",
                     Span::call_site()
                 )),
+                generic_params: None,
             },
             rule.rtype_right_to_left.unwrap()
         );
@@ -380,9 +631,11 @@ $out = QDateTime::fromMSecsSinceEpoch($pin * 1000, Qt::UTC, 0);
                     sp: Span::call_site(),
                 }),
                 code: Some(FTypeConvCode::new(
-                    "$out = ({from_var} != 0);",
+                    "{to_var} = ({from_var} != 0);",
                     Span::call_site()
                 )),
+                null_annotation: None,
+                input_to_output: false,
             },
             rule.ftype_left_to_right.unwrap()
         );
@@ -394,9 +647,11 @@ $out = QDateTime::fromMSecsSinceEpoch($pin * 1000, Qt::UTC, 0);
                     sp: Span::call_site(),
                 }),
                 code: Some(FTypeConvCode::new(
-                    "$out = {from_var} ? 1 : 0;",
+                    "{to_var} = {from_var} ? 1 : 0;",
                     Span::call_site()
                 )),
+                null_annotation: None,
+                input_to_output: false,
             },
             rule.ftype_right_to_left.unwrap()
         );
@@ -416,7 +671,6 @@ $out = QString::fromUtf8($pin.data, $pin.len);
         assert!(!rule.if_simple_rtype_ftype_map().is_some());
     }
 
-    #[ignore]
     #[test]
     fn test_foreign_typemap_callback_to_qfuture() {
         let rule = macro_to_conv_rule(parse_quote! {
@@ -475,7 +729,6 @@ $out = QString::fromUtf8($pin.data, $pin.len);
         assert!(!rule.if_simple_rtype_ftype_map().is_some());
     }
 
-    #[ignore]
     #[test]
     fn test_foreign_typemap_jstring() {
         let rule = macro_to_conv_rule(parse_quote! {
@@ -488,6 +741,63 @@ $out = QString::fromUtf8($pin.data, $pin.len);
             )
         });
         assert!(!rule.if_simple_rtype_ftype_map().is_some());
+        assert_eq!(
+            Some(FTypeConvCode::new(
+                "let mut {to_var}_jstr = JavaString :: new ( env , {from_var} ) ; \
+                 let {to_var}: {to_var_type} = {to_var}_jstr . to_str ( ) ;",
+                Span::call_site()
+            )),
+            rule.rtype_right_to_left.unwrap().code
+        );
+        let f_rule = rule.ftype_right_to_left.unwrap();
+        assert_eq!(Some(NullAnnotation::NonNull), f_rule.null_annotation);
+    }
+
+    #[test]
+    fn test_foreign_typemap_generic() {
+        let rule = macro_to_conv_rule(parse_quote! {
+            foreign_typemap!(
+                ($pin:r_type) <T: SwigForeignClass> Vec<T> => jlong {
+                    $out = vec_to_jlong($pin)
+                };
+                ($pin:f_type, nullable) => "java.util.List<swig_f_type!(T)>"
+                    "$out = SwigHelper.toList($pin);";
+                define_helper_f_helper!("static int helper() { return 1; }");
+            )
+        });
+        assert!(rule.if_simple_rtype_ftype_map().is_none());
+        assert!(rule.generic_params().is_some());
+        assert_eq!(1, rule.f_helpers.len());
+        let f_rule = rule.ftype_left_to_right.unwrap();
+        assert_eq!(Some(NullAnnotation::Nullable), f_rule.null_annotation);
+        assert_eq!(
+            Some(FTypeConvCode::new(
+                "{to_var} = SwigHelper.toList({from_var});",
+                Span::call_site()
+            )),
+            f_rule.code
+        );
+    }
+
+    #[test]
+    fn test_foreign_typemap_define_c_type() {
+        let rule = macro_to_conv_rule(parse_quote! {
+            foreign_typemap!(
+                define_c_type!(
+                    #[repr(C)]
+                    struct CPoint {
+                        x: i32,
+                        y: i32,
+                    }
+                );
+                ($pin:r_type) Point => CPoint {
+                    $out = CPoint { x: $pin.x, y: $pin.y }
+                };
+                ($pin:f_type) => "Point" "Point{$pin.x, $pin.y}";
+            )
+        });
+        assert!(rule.if_simple_rtype_ftype_map().is_none());
+        assert!(rule.c_types.is_some());
     }
 
     #[test]
@@ -505,6 +815,7 @@ $out = QString::fromUtf8($pin.data, $pin.len);
                 left_ty: parse_type!(jlong),
                 right_ty: None,
                 code: None,
+                generic_params: None,
             },
             rule.rtype_left_to_right.unwrap()
         );
@@ -518,6 +829,8 @@ $out = QString::fromUtf8($pin.data, $pin.len);
                     sp: Span::call_site(),
                 }),
                 code: None,
+                null_annotation: None,
+                input_to_output: false,
             },
             rule.ftype_left_to_right.unwrap()
         );
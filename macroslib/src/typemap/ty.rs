@@ -1,9 +1,12 @@
 use crate::{
     error::DiagnosticError,
     source_registry::SourceId,
-    typemap::{ast::TypeName, RustTypeIdx, FROM_VAR_TEMPLATE, TO_VAR_TEMPLATE},
+    typemap::{
+        ast::{DisplayToTokens, TypeName},
+        RustTypeIdx, FROM_VAR_TEMPLATE, TO_VAR_TEMPLATE,
+    },
 };
-use proc_macro2::Span;
+use proc_macro2::{Ident, Span, TokenStream};
 use rustc_hash::FxHashMap;
 use smallvec::SmallVec;
 use smol_str::SmolStr;
@@ -89,6 +92,20 @@ impl ImplementsSet {
     pub(crate) fn contains(&self, trait_name: &str) -> bool {
         self.inner.iter().any(|it| *it == trait_name)
     }
+    /// Traits from `subset` that are not in this set
+    pub(crate) fn missing_traits(&self, subset: &TraitNamesSet) -> Vec<String> {
+        subset
+            .inner
+            .iter()
+            .filter(|path| {
+                !self
+                    .inner
+                    .iter()
+                    .any(|id: &SmolStr| path.is_ident(id.as_str()))
+            })
+            .map(|path| DisplayToTokens(*path).to_string())
+            .collect()
+    }
 }
 
 #[derive(Debug, Default, PartialEq)]
@@ -116,6 +133,11 @@ pub(crate) struct ForeignTypeS {
     pub(crate) name: TypeName,
     pub(crate) into_from_rust: Option<ForeignConversationRule>,
     pub(crate) from_into_rust: Option<ForeignConversationRule>,
+    /// `non_null` or `nullable` from `foreign_typemap!`
+    pub(crate) null_annotation: Option<NullAnnotation>,
+    /// Code from `define_helper_f_helper!`, should be placed
+    /// before foreign code that uses conversation code of this type
+    pub(crate) helpers: Vec<String>,
 }
 
 impl ForeignTypeS {
@@ -124,6 +146,13 @@ impl ForeignTypeS {
     }
 }
 
+/// Hint for foreign language, can value of foreign type be null or not
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum NullAnnotation {
+    NonNull,
+    Nullable,
+}
+
 #[derive(Debug, Clone)]
 pub(crate) struct ForeignConversationRule {
     pub(crate) rust_ty: RustTypeIdx,
@@ -134,6 +163,42 @@ pub(crate) struct ForeignConversationRule {
 pub(crate) struct ForeignConversationIntermediate {
     pub(crate) intermediate_ty: RustTypeIdx,
     pub(crate) conv_code: FTypeConvCode,
+    /// `conv_code` creates not only value of `intermediate_ty` (`{from_var}`),
+    /// but also foreign output (`{to_var}`), so argument of this type should be
+    /// removed from foreign method and method should return foreign output
+    pub(crate) input_to_output: bool,
+}
+
+/// Foreign type from `foreign_typemap!` with generic parameters,
+/// it is instantiated for concrete Rust type on demand
+#[derive(Debug)]
+pub(in crate::typemap) struct GenericForeignTypeRule {
+    pub(in crate::typemap) src_id: SourceId,
+    pub(in crate::typemap) generic_params: syn::Generics,
+    pub(in crate::typemap) into_from_rust: Option<GenericForeignTypeConv>,
+    pub(in crate::typemap) from_into_rust: Option<GenericForeignTypeConv>,
+    pub(in crate::typemap) null_annotation: Option<NullAnnotation>,
+    pub(in crate::typemap) helpers: Vec<String>,
+    /// Content of `define_c_type!`, may contain `concat_ident!` and `swig_i_type!`
+    pub(in crate::typemap) c_types: Option<TokenStream>,
+    /// Type parameter that is converted by `convert_to_c!` and `convert_to_f!`
+    pub(in crate::typemap) converted_param: Option<Ident>,
+}
+
+#[derive(Debug)]
+pub(in crate::typemap) struct GenericForeignTypeConv {
+    /// may contain `swig_f_type!(T)`
+    pub(in crate::typemap) foreign_name: TypeName,
+    /// pattern to match with Rust type
+    pub(in crate::typemap) rust_ty: syn::Type,
+    pub(in crate::typemap) intermediate_ty: syn::Type,
+    /// if no code, foreign type binds directly to `intermediate_ty`
+    pub(in crate::typemap) conv_code: Option<FTypeConvCode>,
+    /// Code of `r_type` rule if it uses `swig_i_type!` and others,
+    /// in this case rule is not added to generic conversation rules,
+    /// and Rust code is generated for each instantiation
+    pub(in crate::typemap) rust_code: Option<String>,
+    pub(in crate::typemap) input_to_output: bool,
 }
 
 #[derive(Debug, Clone)]
@@ -171,6 +236,20 @@ impl FTypeConvCode {
     }
 }
 
+impl FTypeConvCode {
+    /// Code assigns result to `{to_var}`, instead of just
+    /// being expression that can be used as value
+    pub(crate) fn is_statement(&self) -> bool {
+        self.code.contains(TO_VAR_TEMPLATE)
+    }
+    pub(crate) fn as_str(&self) -> &str {
+        &self.code
+    }
+    pub(in crate::typemap) fn span(&self) -> Span {
+        self.span
+    }
+}
+
 impl ToString for FTypeConvCode {
     fn to_string(&self) -> String {
         self.code.clone()
@@ -215,6 +294,8 @@ impl ForeignTypesStorage {
             name: tn,
             into_from_rust: Some(rule.clone()),
             from_into_rust: Some(rule),
+            null_annotation: None,
+            helpers: Vec::new(),
         });
         Ok(idx)
     }
@@ -237,6 +318,8 @@ impl ForeignTypesStorage {
                 name: ftype_name,
                 into_from_rust: None,
                 from_into_rust: None,
+                null_annotation: None,
+                helpers: Vec::new(),
             });
             self.name_to_ftype
                 .insert(self.ftypes[idx.0].name.typename.clone(), idx);
@@ -10,7 +10,8 @@ use crate::{
         ForeignMethodSignature, ForeignTypeInfo, TypeMap,
     },
     types::{
-        ForeignInterfaceMethod, ForeignerClassInfo, ForeignerMethod, MethodVariant, SelfTypeVariant,
+        ForeignEnumInfo, ForeignFlagsInfo, ForeignInterfaceMethod, ForeignerClassInfo,
+        ForeignerMethod, MethodVariant, SelfTypeVariant, UnknownEnumValue,
     },
 };
 
@@ -164,3 +165,57 @@ pub(crate) fn rust_to_foreign_convert_method_inputs<
     }
     Ok((code_deps, ret_code))
 }
+
+/// Last arm of `match` that converts integer `var` into `foreign_enum` item,
+/// for `UnknownEnumValue::Error` caller should check value before, so it is `panic!`
+pub(crate) fn rust_enum_unknown_value_arm(enum_info: &ForeignEnumInfo, var: &str) -> String {
+    match enum_info.unknown_value {
+        UnknownEnumValue::Fallback(idx) => {
+            format!("_ => {},", DisplayToTokens(&enum_info.items[idx].rust_name))
+        }
+        UnknownEnumValue::Panic | UnknownEnumValue::Error => format!(
+            "_ => panic!(\"{{}} not expected for {}\", {}),",
+            enum_info.rust_enum_name(),
+            var
+        ),
+    }
+}
+
+/// Rust expression that converts `bitflags!` value into bits of `foreign_flags!`,
+/// its result is `(bits, true if all flags were exported)`
+pub(crate) fn rust_flags_to_bits(flags_info: &ForeignFlagsInfo, var: &str) -> String {
+    let mut code = format!(
+        "{{\n        let mut rest: {} = {};\n        let mut bits: u32 = 0;\n",
+        flags_info.name, var
+    );
+    for item in &flags_info.items {
+        code.push_str(&format!(
+            "        if rest.contains({item}) {{\n            bits |= {bit:#x};\n            rest.remove({item});\n        }}\n",
+            item = DisplayToTokens(&item.rust_name),
+            bit = item.value,
+        ));
+    }
+    code.push_str("        (bits, rest.is_empty())\n    }");
+    code
+}
+
+/// Rust expression that converts bits of `foreign_flags!` into `bitflags!` value,
+/// its result is `(value, true if there were no unknown bits)`
+pub(crate) fn rust_flags_from_bits(flags_info: &ForeignFlagsInfo, var: &str) -> String {
+    let mut code = format!(
+        "{{\n        let bits: u32 = {};\n        let mut flags = {}::empty();\n",
+        var, flags_info.name
+    );
+    for item in &flags_info.items {
+        code.push_str(&format!(
+            "        if (bits & {bit:#x}) != 0 {{\n            flags.insert({item});\n        }}\n",
+            item = DisplayToTokens(&item.rust_name),
+            bit = item.value,
+        ));
+    }
+    code.push_str(&format!(
+        "        (flags, (bits & !{all:#x}) == 0)\n    }}",
+        all = flags_info.all_bits()
+    ));
+    code
+}
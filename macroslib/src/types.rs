@@ -84,6 +84,8 @@ pub(crate) struct ForeignerMethod {
     pub(crate) name_alias: Option<Ident>,
     pub(crate) access: MethodAccess,
     pub(crate) doc_comments: Vec<String>,
+    /// Java exception class from `#[java_exception = "..."]` attribute
+    pub(crate) java_exception: Option<String>,
 }
 
 #[derive(Debug, Clone)]
@@ -161,6 +163,19 @@ pub(crate) struct ForeignEnumInfo {
     pub(crate) name: Ident,
     pub(crate) items: Vec<ForeignEnumItem>,
     pub(crate) doc_comments: Vec<String>,
+    /// What to do if foreign side passes value that matches no item
+    pub(crate) unknown_value: UnknownEnumValue,
+}
+
+/// Policy for value that matches no item of `foreign_enum`,
+/// set by `#[unknown_value = "panic" | "error" | "fallback(ITEM)"]`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum UnknownEnumValue {
+    Panic,
+    /// Index of unit item that is used instead
+    Fallback(usize),
+    /// Report error to caller, if there is no way to do it then panic
+    Error,
 }
 
 impl ForeignEnumInfo {
@@ -170,6 +185,13 @@ impl ForeignEnumInfo {
     pub(crate) fn span(&self) -> Span {
         self.name.span()
     }
+    pub(crate) fn src_id_span(&self) -> SourceIdSpan {
+        (self.src_id, self.name.span())
+    }
+    /// Is any item of enum carry data, so it can not be mapped to plain integer
+    pub(crate) fn is_data_carrying(&self) -> bool {
+        self.items.iter().any(|x| x.kind != EnumItemKind::Unit)
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -177,6 +199,91 @@ pub(crate) struct ForeignEnumItem {
     pub(crate) name: Ident,
     pub(crate) rust_name: syn::Path,
     pub(crate) doc_comments: Vec<String>,
+    /// Explicit discriminant, or previous one plus one
+    pub(crate) value: u32,
+    pub(crate) kind: EnumItemKind,
+    /// Fields of data-carrying item, for tuple-like item they named `_0`, `_1` and so on
+    pub(crate) fields: Vec<ForeignStructField>,
+}
+
+impl ForeignEnumItem {
+    /// Name of field in Rust code, `0` for `_0` of tuple-like item
+    pub(crate) fn rust_field_name(&self, field: &ForeignStructField) -> String {
+        let name = field.name.to_string();
+        match self.kind {
+            EnumItemKind::Tuple => name[1..].to_string(),
+            EnumItemKind::Unit | EnumItemKind::Struct => name,
+        }
+    }
+}
+
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub(crate) enum EnumItemKind {
+    Unit,
+    Tuple,
+    Struct,
+}
+
+/// `bitflags!` type exported as set of named bits
+#[derive(Debug, Clone)]
+pub(crate) struct ForeignFlagsInfo {
+    pub(crate) src_id: SourceId,
+    pub(crate) name: Ident,
+    pub(crate) items: Vec<ForeignFlagsItem>,
+    pub(crate) doc_comments: Vec<String>,
+}
+
+impl ForeignFlagsInfo {
+    pub(crate) fn rust_flags_name(&self) -> String {
+        self.name.to_string()
+    }
+    pub(crate) fn span(&self) -> Span {
+        self.name.span()
+    }
+    pub(crate) fn src_id_span(&self) -> SourceIdSpan {
+        (self.src_id, self.name.span())
+    }
+    /// Union of bits of all items
+    pub(crate) fn all_bits(&self) -> u32 {
+        self.items.iter().fold(0, |acc, x| acc | x.value)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct ForeignFlagsItem {
+    pub(crate) name: Ident,
+    pub(crate) rust_name: syn::Path,
+    pub(crate) doc_comments: Vec<String>,
+    /// Bit on foreign side, explicit or next after previous one
+    pub(crate) value: u32,
+}
+
+/// Rust structure exported by value, field by field
+#[derive(Debug, Clone)]
+pub(crate) struct ForeignStructInfo {
+    pub(crate) src_id: SourceId,
+    pub(crate) name: Ident,
+    pub(crate) fields: Vec<ForeignStructField>,
+    pub(crate) doc_comments: Vec<String>,
+}
+
+impl ForeignStructInfo {
+    pub(crate) fn rust_struct_name(&self) -> String {
+        self.name.to_string()
+    }
+    pub(crate) fn span(&self) -> Span {
+        self.name.span()
+    }
+    pub(crate) fn src_id_span(&self) -> SourceIdSpan {
+        (self.src_id, self.name.span())
+    }
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct ForeignStructField {
+    pub(crate) name: Ident,
+    pub(crate) ty: Type,
+    pub(crate) doc_comments: Vec<String>,
 }
 
 pub(crate) struct ForeignInterface {
@@ -0,0 +1,12 @@
+r#"public interface SomeObserver {
+
+
+    void onStateChanged(int a0) throws Exception;
+
+
+    int count() throws Exception;
+
+
+    String name() throws Exception;
+
+}"#;
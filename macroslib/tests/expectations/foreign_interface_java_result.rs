@@ -0,0 +1,12 @@
+foreign_interface!(interface SomeObserver {
+    self_type SomeTrait;
+    onStateChanged = SomeTrait::on_state_changed(&self, _: i32) -> Result<(), JavaException>;
+    count = SomeTrait::count(&self) -> Result<i32, JavaException>;
+    name = SomeTrait::name(&self) -> Result<String, MyError>;
+});
+
+foreigner_class!(class ClassWithCallbacks {
+    self_type Foo;
+    constructor Foo::default() -> Foo;
+    method Foo::f1(&mut self, cb: Box<SomeTrait>);
+});
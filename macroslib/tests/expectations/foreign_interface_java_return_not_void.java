@@ -0,0 +1,27 @@
+r#"public interface SomeObserver {
+
+
+    boolean isOk(int a0);
+
+
+    int count();
+
+
+    String name();
+
+
+    String optName();
+
+
+    Long optCount();
+
+
+    Boo boo();
+
+
+    Boo optBoo();
+
+
+    MyEnum myEnum();
+
+}"#;
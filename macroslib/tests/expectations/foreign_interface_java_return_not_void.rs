@@ -0,0 +1,28 @@
+foreign_enum!(enum MyEnum {
+    ITEM1 = MyEnum::Item1,
+    ITEM2 = MyEnum::Item2,
+});
+
+foreigner_class!(class Boo {
+    self_type Boo;
+    constructor Boo::default() -> Boo;
+    method Boo::clone(&self) -> Boo;
+});
+
+foreign_interface!(interface SomeObserver {
+    self_type SomeTrait;
+    isOk = SomeTrait::is_ok(&self, _: i32) -> bool;
+    count = SomeTrait::count(&self) -> i32;
+    name = SomeTrait::name(&self) -> String;
+    optName = SomeTrait::opt_name(&self) -> Option<String>;
+    optCount = SomeTrait::opt_count(&self) -> Option<i64>;
+    boo = SomeTrait::boo(&self) -> Boo;
+    optBoo = SomeTrait::opt_boo(&self) -> Option<Boo>;
+    myEnum = SomeTrait::my_enum(&self) -> MyEnum;
+});
+
+foreigner_class!(class ClassWithCallbacks {
+    self_type Foo;
+    constructor Foo::default() -> Foo;
+    method Foo::f1(&mut self, cb: Box<SomeTrait>);
+});
@@ -0,0 +1,12 @@
+"public final void connect(@NonNull String a0) throws MyNetworkException {";
+"private static native void do_connect(long me, String a0) throws MyNetworkException;";
+"public final int read() throws java.io.IOException {";
+"public final int send(int a0) throws NetworkErrorException {";
+"public static native void ping() throws NetworkErrorException;";
+"public final void close() throws Exception {";
+r#"public final class NetworkErrorException extends Exception {
+    public NetworkErrorException(NetworkError value) {
+        super(value.name());
+        this.value = value;
+    }
+    public final NetworkError getValue() { return value; }"#;
@@ -0,0 +1,16 @@
+foreign_enum!(enum NetworkError {
+    TIMEOUT = NetworkError::Timeout,
+    REFUSED = NetworkError::Refused,
+});
+
+foreigner_class!(class Connection {
+    self_type Connection;
+    constructor Connection::new() -> Connection;
+    #[java_exception = "MyNetworkException"]
+    method Connection::connect(&mut self, _: &str) -> Result<(), String>;
+    #[java_exception = "java.io.IOException"]
+    method Connection::read(&self) -> Result<i32, String>;
+    method Connection::send(&self, _: i32) -> Result<i32, NetworkError>;
+    static_method Connection::ping() -> Result<(), NetworkError>;
+    method Connection::close(&self) -> Result<(), String>;
+});
@@ -38,7 +38,7 @@ fn test_code_in_readme() {
                 swig_gen.expand("rust_swig_test_jni", &rust_path_src, &rust_path_dst);
             }
 
-            {
+            if !test.java_only {
                 let cpp_path = tmp_dir.path().join(&test.name).join("c++");
 
                 fs::create_dir_all(&cpp_path).unwrap();
@@ -60,6 +60,7 @@ struct CodeBlockInfo {
     no_run: bool,
     is_old_template: bool,
     template: Option<String>,
+    java_only: bool,
 }
 
 #[derive(Debug)]
@@ -70,6 +71,7 @@ struct Test {
     no_run: bool,
     should_panic: bool,
     template: Option<String>,
+    java_only: bool,
 }
 
 fn parse_readme() -> Vec<Test> {
@@ -104,6 +106,7 @@ fn parse_readme() -> Vec<Test> {
                         no_run: code_block_info.no_run,
                         should_panic: code_block_info.should_panic,
                         template: code_block_info.template,
+                        java_only: code_block_info.java_only,
                     });
                     test_number += 1;
                 }
@@ -128,6 +131,7 @@ fn parse_code_block_info(info: &str) -> CodeBlockInfo {
         no_run: false,
         is_old_template: false,
         template: None,
+        java_only: false,
     };
 
     for token in tokens {
@@ -153,6 +157,10 @@ fn parse_code_block_info(info: &str) -> CodeBlockInfo {
                 info.is_old_template = true;
                 seen_rust_tags = true
             }
+            "java_only" => {
+                info.java_only = true;
+                seen_rust_tags = true;
+            }
             _ if token.starts_with("skt-") => {
                 info.template = Some(token[4..].to_string());
                 seen_rust_tags = true;
@@ -3,9 +3,13 @@ use std::{
     ffi::OsString,
     fs, panic,
     path::{Path, PathBuf},
+    process::Command,
 };
 
-use rust_swig::{CppConfig, Generator, JavaConfig, LanguageConfig};
+use rust_swig::{
+    CConfig, CSharpConfig, CppConfig, CppPanicHandling, DartConfig, Generator, GoConfig,
+    JavaConfig, LanguageConfig, Lint, NodeJsConfig, PythonConfig,
+};
 use syn::Token;
 use tempfile::tempdir;
 
@@ -34,6 +38,13 @@ fn test_expectations_main() {
         let (main_ext, rust_ext) = match lang {
             ForeignLang::Cpp => (".cpp", ".cpp_rs"),
             ForeignLang::Java => (".java", ".java_rs"),
+            ForeignLang::CSharp => (".cs", ".cs_rs"),
+            ForeignLang::Python => (".pyi", ".pyi_rs"),
+            ForeignLang::C => (".c", ".c_rs"),
+            ForeignLang::Kotlin => (".kt", ".kt_rs"),
+            ForeignLang::NodeJs => (".d.ts", ".d.ts_rs"),
+            ForeignLang::Dart => (".dart", ".dart_rs"),
+            ForeignLang::Go => (".go", ".go_rs"),
         };
         let main_expectation = new_path(test_case, main_ext);
         if main_expectation.exists() {
@@ -77,7 +88,7 @@ fn test_expectations_main() {
         }
 
         let mut test_something = false;
-        for lang in &[ForeignLang::Cpp, ForeignLang::Java] {
+        for lang in &[ForeignLang::Cpp, ForeignLang::Java, ForeignLang::CSharp] {
             if check_expectation(&test_name, &test_case, *lang) {
                 test_something = true;
             }
@@ -87,7 +98,7 @@ fn test_expectations_main() {
         }
     }
 
-    assert_eq!(43, ntests);
+    assert_eq!(46, ntests);
 }
 
 #[test]
@@ -358,152 +369,2562 @@ foreign_interface!(interface RepoChangedCallback {
     }
 }
 
-#[derive(PartialEq, Debug, Clone, Copy)]
-enum ForeignLang {
-    Java,
-    Cpp,
-}
+#[test]
+fn test_catch_panics() {
+    let _ = env_logger::try_init();
 
-#[derive(Clone)]
-struct CodePair {
-    rust_code: String,
-    foreign_code: String,
-}
+    let name = "catch_panics";
+    let src = r#"
+foreigner_class!(class Boo {
+    self_type Boo;
+    constructor Boo::new(_: i32) -> Boo;
+    method Boo::f(&self) -> i32;
+    static_method Boo::g(_: &str) -> bool;
+});
+"#;
+    let java_code =
+        parse_code_with_config(name, Source::Str(src), ForeignLang::Java, |cfg| match cfg {
+            LanguageConfig::JavaConfig(cfg) => LanguageConfig::JavaConfig(cfg.catch_panics(true)),
+            _ => unreachable!(),
+        })
+        .unwrap();
+    println!("Java/rust: {}", java_code.rust_code);
+    assert_eq!(4, java_code.rust_code.matches("catch_unwind").count());
+    assert!(java_code
+        .rust_code
+        .contains("jni_throw_panic ( env , err ) ; < jint >:: invalid_value ( )"));
 
-struct PrintTestInfo {
-    code_pair: CodePair,
-    test_name: String,
-    lang: ForeignLang,
-    print_on_drop: bool,
-    foreign_code_search_pattern: String,
-    rust_pat: String,
+    let cpp_code =
+        parse_code_with_config(name, Source::Str(src), ForeignLang::Cpp, |cfg| match cfg {
+            LanguageConfig::CppConfig(cfg) => LanguageConfig::CppConfig(
+                cfg.catch_panics(CppPanicHandling::CallHandler("on_rust_panic".into())),
+            ),
+            _ => unreachable!(),
+        })
+        .unwrap();
+    println!("c/c++ rust: {}", cpp_code.rust_code);
+    assert_eq!(4, cpp_code.rust_code.matches("catch_unwind").count());
+    assert!(cpp_code
+        .rust_code
+        .contains("fn on_rust_panic ( msg : * const :: std :: os :: raw :: c_char ) ;"));
+    assert!(cpp_code
+        .foreign_code
+        .contains("void on_rust_panic(const char *msg);"));
+    assert!(cpp_code
+        .rust_code
+        .contains("on_rust_panic ( msg . as_ptr ( ) ) } ; :: std :: process :: abort ( ) }"));
+    assert!(!cpp_code.rust_code.contains("zeroed"));
+
+    let cpp_code =
+        parse_code_with_config(name, Source::Str(src), ForeignLang::Cpp, |cfg| match cfg {
+            LanguageConfig::CppConfig(cfg) => {
+                LanguageConfig::CppConfig(cfg.catch_panics(CppPanicHandling::Abort))
+            }
+            _ => unreachable!(),
+        })
+        .unwrap();
+    assert_eq!(4, cpp_code.rust_code.matches("catch_unwind").count());
+    assert!(cpp_code
+        .rust_code
+        .contains("Err ( err ) => :: std :: process :: abort ( )"));
+
+    let py_code =
+        parse_code_with_config(
+            name,
+            Source::Str(src),
+            ForeignLang::Python,
+            |cfg| match cfg {
+                LanguageConfig::PythonConfig(cfg) => {
+                    LanguageConfig::PythonConfig(cfg.catch_panics(true))
+                }
+                _ => unreachable!(),
+            },
+        )
+        .unwrap();
+    println!("python rust: {}", py_code.rust_code);
+    assert_eq!(3, py_code.rust_code.matches("catch_unwind").count());
+    assert!(py_code.rust_code.contains(
+        "swig_py_set_panic_error ( err ) ; <:: std :: os :: raw :: c_int >:: swig_py_invalid_value ( )"
+    ));
+    assert!(py_code.rust_code.contains(
+        "swig_py_set_panic_error ( err ) ; <* mut PyObject >:: swig_py_invalid_value ( )"
+    ));
+
+    let java_code = parse_code(name, Source::Str(src), ForeignLang::Java).unwrap();
+    assert!(!java_code.rust_code.contains("catch_unwind"));
+    let py_code = parse_code(name, Source::Str(src), ForeignLang::Python).unwrap();
+    assert!(!py_code.rust_code.contains("catch_unwind"));
 }
 
-impl PrintTestInfo {
-    fn new(code_pair: CodePair, test_name: String, lang: ForeignLang) -> Self {
-        PrintTestInfo {
-            code_pair,
-            test_name,
-            lang,
-            print_on_drop: true,
-            foreign_code_search_pattern: String::new(),
-            rust_pat: String::new(),
-        }
-    }
-    fn success(&mut self) {
-        self.print_on_drop = false;
-    }
+#[test]
+fn test_kotlin_classes() {
+    let _ = env_logger::try_init();
+
+    let name = "kotlin_classes";
+    let src = r#"
+foreign_enum!(enum Mode {
+    FAST = Mode::Fast,
+    SLOW = Mode::Slow,
+});
+foreign_interface!(interface Listener {
+    self_type Listener;
+    on_event = Listener::on_event(&self, x: i32);
+});
+foreigner_class!(class Boo {
+    self_type Boo;
+    constructor Boo::new(_: i32) -> Boo;
+    method Boo::getValue(&self) -> i32;
+    method Boo::setValue(&mut self, _: i32);
+    method Boo::getName(&self) -> Option<String>;
+    method Boo::ratio(&self) -> Option<f64>;
+    method Boo::connect(&self, _: Option<Boo>, _: Mode) -> Result<(), String>;
+    static_method Boo::find(_: &str) -> Option<Boo>;
+    static_method Boo::subscribe(_: Box<Listener>);
+});
+"#;
+    let kotlin_code = parse_code(name, Source::Str(src), ForeignLang::Kotlin).unwrap();
+    println!("Kotlin: {}", kotlin_code.foreign_code);
+    assert!(kotlin_code
+        .foreign_code
+        .contains("class Boo : java.io.Closeable {"));
+    assert!(kotlin_code
+        .foreign_code
+        .contains("enum class Mode(val value: Int) {"));
+    assert!(kotlin_code.foreign_code.contains("interface Listener {"));
+    assert!(kotlin_code.foreign_code.contains("fun on_event(a0: Int)"));
+    assert!(kotlin_code.foreign_code.contains("constructor(a0: Int) {"));
+    assert!(kotlin_code
+        .foreign_code
+        .contains("    var value: Int\n        get() {"));
+    assert!(kotlin_code.foreign_code.contains("        set(a0) {"));
+    assert!(kotlin_code.foreign_code.contains("    val name: String?\n"));
+    assert!(kotlin_code
+        .foreign_code
+        .contains("return if (ret.isPresent) ret.asDouble else null"));
+    assert!(kotlin_code
+        .foreign_code
+        .contains("@Throws(Exception::class)\n    fun connect(a0: Boo?, a1: Mode) {"));
+    assert!(kotlin_code.foreign_code.contains("val a1C0 = a1.value"));
+    assert!(kotlin_code
+        .foreign_code
+        .contains("fun find(a0: String): Boo? {"));
+    assert!(kotlin_code
+        .foreign_code
+        .contains("private external fun do_ratio(me: Long): java.util.OptionalDouble"));
+    assert!(kotlin_code.foreign_code.contains("override fun close() {"));
+    assert!(kotlin_code
+        .foreign_code
+        .contains("@JvmField\n    internal var mNativeObj: Long = 0"));
+    assert!(kotlin_code
+        .rust_code
+        .contains("Java_org_example_Boo_do_1find"));
+    assert!(kotlin_code
+        .rust_code
+        .contains("Java_org_example_Boo_do_1subscribe"));
 }
 
-impl Drop for PrintTestInfo {
-    fn drop(&mut self) {
-        if self.print_on_drop {
-            if !self.foreign_code_search_pattern.is_empty() {
-                println!(
-                    "{} / {:?}: search foreign pat '{}'",
-                    self.test_name, self.lang, self.foreign_code_search_pattern
-                );
-            }
+#[test]
+fn test_java_use_cleaner() {
+    let _ = env_logger::try_init();
 
-            if !self.rust_pat.is_empty() {
-                println!(
-                    "{} / {:?}: search rust pat '{}'",
-                    self.test_name, self.lang, self.rust_pat,
-                );
-            }
+    let name = "java_use_cleaner";
+    let src = r#"
+foreigner_class!(class Boo {
+    self_type Boo;
+    constructor Boo::new(_: i32) -> Boo;
+    method Boo::f(&self) -> i32;
+});
+foreigner_class!(class Foo {
+    self_type Foo;
+    constructor Foo::default() -> Foo;
+    method Foo::set_boo(&mut self, _: Boo);
+    method Foo::get_boo(&self) -> Boo;
+});
+"#;
+    let java_code =
+        parse_code_with_config(name, Source::Str(src), ForeignLang::Java, |cfg| match cfg {
+            LanguageConfig::JavaConfig(cfg) => LanguageConfig::JavaConfig(cfg.use_cleaner(true)),
+            _ => unreachable!(),
+        })
+        .unwrap();
+    println!("Java: {}", java_code.foreign_code);
+    assert!(java_code
+        .foreign_code
+        .contains("public final class Boo implements AutoCloseable {"));
+    assert!(java_code.foreign_code.contains("public void close() {"));
+    assert!(java_code
+        .foreign_code
+        .contains("static final java.lang.ref.Cleaner CLEANER"));
+    assert!(java_code
+        .foreign_code
+        .contains("a0.mNativeObj = 0;\n        a0.swigNativeObjChanged();"));
+    assert!(!java_code.foreign_code.contains("finalize"));
+    assert_eq!(
+        2,
+        java_code
+            .rust_code
+            .matches("swig_c_str ! ( \"swigNativeObjChanged\" )")
+            .count()
+    );
 
-            println!(
-                "{} / {:?}: rust_swig generated such foreign_code: {}",
-                self.test_name, self.lang, self.code_pair.foreign_code
-            );
-            println!(
-                "{} / {:?}: rust_swig generated such rust_code: {}",
-                self.test_name, self.lang, self.code_pair.rust_code
-            );
-        }
-    }
+    let java_code = parse_code(name, Source::Str(src), ForeignLang::Java).unwrap();
+    assert!(java_code.foreign_code.contains("protected void finalize()"));
+    assert!(!java_code.foreign_code.contains("AutoCloseable"));
+    assert!(!java_code.rust_code.contains("swigNativeObjChanged"));
 }
 
-#[derive(Debug)]
-struct Error {
-    msg: String,
-}
+#[test]
+fn test_java_exception_from_typemap() {
+    let _ = env_logger::try_init();
 
-impl std::fmt::Display for Error {
-    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-        write!(f, "{}", self.msg)
+    let src = r#"
+foreigner_class!(class Connection {
+    self_type Connection;
+    constructor Connection::new() -> Connection;
+    method Connection::connect(&mut self) -> Result<(), NetworkError>;
+});
+"#;
+    let type_map = r#"
+#[swig_to_foreigner_hint = "com.example.MyNetworkException"]
+impl SwigFrom<NetworkError> for jthrowable {
+    fn swig_from(x: NetworkError, env: *mut JNIEnv) -> Self {
+        create_network_exception(x, env)
     }
 }
+"#;
+    let tmp_dir = tempdir().expect("Can not create tmp directory");
+    let swig_gen = Generator::new(LanguageConfig::JavaConfig(JavaConfig::new(
+        tmp_dir.path().into(),
+        "org.example".into(),
+    )))
+    .with_pointer_target_width(64)
+    .merge_type_map("network_exception", type_map);
+    let rust_src_path = tmp_dir.path().join("src.rs");
+    fs::write(&rust_src_path, src).unwrap();
+    let rust_code_path = tmp_dir.path().join("test.rs");
+    swig_gen.expand(
+        "java_exception_from_typemap",
+        &rust_src_path,
+        &rust_code_path,
+    );
+    let rust_code = fs::read_to_string(rust_code_path).unwrap();
+    let java_code = collect_code_in_dir(tmp_dir.path(), &[".java"]).unwrap();
+    println!("Java: {}\nrust: {}", java_code, rust_code);
+    assert!(java_code.contains(
+        "private static native void do_connect(long me) throws com.example.MyNetworkException;"
+    ));
+    assert!(rust_code.contains(
+        "let mut err : jthrowable = < jthrowable >:: swig_from ( err , env ) ; \
+         jni_throw_object ( env , err ) ;"
+    ));
+    assert!(rust_code.contains("create_network_exception ( x , env )"));
+}
 
-impl From<std::io::Error> for Error {
-    fn from(x: std::io::Error) -> Self {
-        Error {
-            msg: format!("io: {}", x),
-        }
+#[test]
+fn test_foreign_typemap_java() {
+    let _ = env_logger::try_init();
+
+    let src = r#"
+foreigner_class!(class Foo {
+    self_type Foo;
+    constructor Foo::default() -> Foo;
+    method Foo::created_at(&self) -> SystemTime;
+    method Foo::set_created_at(&mut self, _: SystemTime);
+    static_method Foo::now() -> SystemTime;
+    method Foo::name(&self) -> &str;
+});
+"#;
+    let type_map = r#"
+foreign_typemap!(
+    ($p:r_type) SystemTime => jlong {
+        let since_unix_epoch = $p.duration_since(::std::time::UNIX_EPOCH).unwrap();
+        $out = (since_unix_epoch.as_secs() * 1_000
+            + (since_unix_epoch.subsec_nanos() / 1_000_000) as u64) as jlong;
+    };
+    ($p:f_type) => "java.util.Date" "$out = new java.util.Date($p);";
+    ($p:r_type) SystemTime <= jlong {
+        $out = ::std::time::UNIX_EPOCH + ::std::time::Duration::from_millis($p as u64);
+    };
+    ($p:f_type) <= "java.util.Date" "$p.getTime()";
+);
+foreign_typemap!(
+    ($p:r_type, $s:variable) &str => jstring {
+        $s = $p.to_uppercase();
+        $out = from_std_string_jstring($s, env);
+    };
+    ($p:f_type, nullable) => "String";
+);
+"#;
+    let tmp_dir = tempdir().expect("Can not create tmp directory");
+    let swig_gen = Generator::new(LanguageConfig::JavaConfig(JavaConfig::new(
+        tmp_dir.path().into(),
+        "org.example".into(),
+    )))
+    .with_pointer_target_width(64)
+    .merge_type_map("java_date", type_map);
+    let rust_src_path = tmp_dir.path().join("src.rs");
+    fs::write(&rust_src_path, src).unwrap();
+    let rust_code_path = tmp_dir.path().join("test.rs");
+    swig_gen.expand("foreign_typemap_java", &rust_src_path, &rust_code_path);
+    let rust_code = fs::read_to_string(rust_code_path).unwrap();
+    let java_code = collect_code_in_dir(tmp_dir.path(), &[".java"]).unwrap();
+    println!("Java: {}\nrust: {}", java_code, rust_code);
+    assert!(java_code.contains(
+        r#"
+    public final java.util.Date created_at()  {
+
+        long ret = do_created_at(mNativeObj);
+        java.util.Date convRet;
+        convRet = new java.util.Date(ret);
+        return convRet;
+    }
+    private static native long do_created_at(long me) ;
+"#
+    ));
+    assert!(java_code.contains(
+        r#"
+    public final void set_created_at(java.util.Date a0)  {
+        long a0C0 = a0.getTime();
+
+        do_set_created_at(mNativeObj, a0C0);
     }
+    private static native void do_set_created_at(long me, long a0) ;
+"#
+    ));
+    assert!(java_code.contains("private static native long do_now() ;"));
+    assert!(java_code.contains("public final String name()"));
+    assert!(rust_code.contains("let mut ret_s = ret . to_uppercase ( ) ;"));
+    assert!(
+        rust_code.contains("UNIX_EPOCH + :: std :: time :: Duration :: from_millis ( a_0 as u64 )")
+    );
 }
 
-impl From<syn::Error> for Error {
-    fn from(x: syn::Error) -> Self {
-        Error {
-            msg: format!("syn: {}", x),
+#[test]
+fn test_foreign_typemap_cpp() {
+    let _ = env_logger::try_init();
+
+    let src = r#"
+foreigner_class!(class Foo {
+    self_type Foo;
+    constructor Foo::default() -> Foo;
+    method Foo::pos(&self) -> Point;
+    method Foo::set_pos(&mut self, _: Point);
+    method Foo::count(&self) -> Counter<u32>;
+});
+"#;
+    let type_map = r#"
+foreign_typemap!(
+    define_c_type!(
+        #[repr(C)]
+        pub struct CPoint {
+            x: i32,
+            y: i32,
         }
-    }
+    );
+    ($p:r_type) Point => CPoint {
+        $out = CPoint { x: $p.x, y: $p.y }
+    };
+    ($p:f_type) => "QPoint" "$out = QPoint{$p.x, $p.y};";
+    ($p:r_type) Point <= CPoint {
+        $out = Point { x: $p.x, y: $p.y }
+    };
+    ($p:f_type) <= "QPoint" "CPoint{$p.x(), $p.y()}";
+    define_helper_f_helper!("inline int point_helper() { return 1; }");
+);
+foreign_typemap!(
+    ($p:r_type) <T> Counter<T> => T {
+        $out = $p.get()
+    };
+    ($p:f_type) => "Counter<swig_f_type!(T)>" "Counter<swig_f_type!(T)>{$p}";
+);
+"#;
+    let tmp_dir = tempdir().expect("Can not create tmp directory");
+    let swig_gen = Generator::new(LanguageConfig::CppConfig(CppConfig::new(
+        tmp_dir.path().into(),
+        "org_examples".into(),
+    )))
+    .with_pointer_target_width(64)
+    .merge_type_map("cpp_point", type_map);
+    let rust_src_path = tmp_dir.path().join("src.rs");
+    fs::write(&rust_src_path, src).unwrap();
+    let rust_code_path = tmp_dir.path().join("test.rs");
+    swig_gen.expand("foreign_typemap_cpp", &rust_src_path, &rust_code_path);
+    let rust_code = fs::read_to_string(rust_code_path).unwrap();
+    let cpp_code = collect_code_in_dir(tmp_dir.path(), &[".h", ".hpp"]).unwrap();
+    println!("C++: {}\nrust: {}", cpp_code, rust_code);
+    assert_eq!(1, rust_code.matches("pub struct CPoint").count());
+    assert!(cpp_code.contains(
+        r#"
+#ifndef RUST_SWIG_C_TYPE_CPoint
+#define RUST_SWIG_C_TYPE_CPoint
+    struct CPoint {
+        int32_t x;
+        int32_t y;
+    };
+#endif
+"#
+    ));
+    assert!(cpp_code.contains("inline int point_helper() { return 1; }"));
+    assert!(cpp_code.contains(
+        "[&]() -> QPoint { QPoint swig_out; swig_out = QPoint{ret.x, ret.y}; return swig_out; }()"
+    ));
+    assert!(cpp_code.contains("CPoint{a_0.x(), a_0.y()}"));
+    assert!(cpp_code.contains("Counter<uint32_t> count() const  noexcept;"));
+    assert!(cpp_code.contains("return Counter<uint32_t>{ret};"));
+    assert!(rust_code.contains("let ret : u32 = ret . get ( ) ;"));
 }
 
-impl std::error::Error for Error {}
+#[test]
+fn test_foreign_typemap_cpp_qfuture() {
+    let _ = env_logger::try_init();
 
-fn collect_code_in_dir(dir_with_code: &Path, exts: &[&str]) -> Result<String, Error> {
-    let mut code = String::new();
-    for path in fs::read_dir(dir_with_code)? {
-        let path = path?;
-        if path.file_type()?.is_file()
-            && exts
-                .iter()
-                .any(|ext| path.path().to_str().map_or(false, |x| x.ends_with(ext)))
+    let src = r#"
+foreigner_class!(class Foo {
+    self_type Foo;
+    constructor Foo::default() -> Foo;
+    method Foo::calc(&self, _: i32, _: impl FnOnce(i32));
+    method Foo::calc_again(&self, _: impl FnOnce(i32));
+    static_method Foo::calc_f64(_: impl FnOnce(f64));
+});
+"#;
+    let type_map = r##"
+foreign_typemap!(
+    define_c_type!(
+        #[repr(C)]
+        pub struct concat_ident!(CFnOnce, swig_i_type!(T)) {
+            cb: extern "C" fn(swig_i_type!(T), *mut ::std::os::raw::c_void),
+            ctx: *mut ::std::os::raw::c_void,
+        }
+    );
+    ($p:r_type) <T, F: FnOnce(T)> F <= concat_ident!(CFnOnce, swig_i_type!(T)) {
+        $out = move |x| ($p.cb)(convert_to_c!(x), $p.ctx)
+    };
+    define_helper_f_helper!(
+        static void concat_ident!(result_ready, swig_i_type!(T))(swig_i_type!(T) ret, void *ctx)
         {
-            code.push_str(&fs::read_to_string(path.path())?);
-            code.push('\n');
+            auto fi = static_cast<QFutureInterface<std::shared_ptr<swig_f_type!(T)>> *>(ctx);
+            fi->reportResult(std::make_shared<swig_f_type!(T)>(convert_to_f!(ret)));
+            fi->reportFinished();
+            delete fi;
         }
-    }
-    Ok(code)
+    );
+    ($out:f_type, $p:input_to_output) => "QFuture<std::shared_ptr<swig_f_type!(T)>>" r#"
+        auto fi = new QFutureInterface<std::shared_ptr<swig_f_type!(T)>>;
+        fi->reportStarted();
+        $p.cb = concat_ident!(result_ready, swig_i_type!(T));
+        $p.ctx = fi;
+        $out = fi->future();
+"#;
+);
+"##;
+    let tmp_dir = tempdir().expect("Can not create tmp directory");
+    let swig_gen = Generator::new(LanguageConfig::CppConfig(CppConfig::new(
+        tmp_dir.path().into(),
+        "org_examples".into(),
+    )))
+    .with_pointer_target_width(64)
+    .merge_type_map("cpp_qfuture", type_map);
+    let rust_src_path = tmp_dir.path().join("src.rs");
+    fs::write(&rust_src_path, src).unwrap();
+    let rust_code_path = tmp_dir.path().join("test.rs");
+    swig_gen.expand(
+        "foreign_typemap_cpp_qfuture",
+        &rust_src_path,
+        &rust_code_path,
+    );
+    let rust_code = fs::read_to_string(rust_code_path).unwrap();
+    let cpp_code = collect_code_in_dir(tmp_dir.path(), &[".h", ".hpp"]).unwrap();
+    println!("C++: {}\nrust: {}", cpp_code, rust_code);
+    assert!(cpp_code.contains(
+        r#"
+#ifndef RUST_SWIG_C_TYPE_CFnOncei32
+#define RUST_SWIG_C_TYPE_CFnOncei32
+    struct CFnOncei32 {
+        void (*cb)(int32_t, void *);
+        void *ctx;
+    };
+#endif
+"#
+    ));
+    assert!(cpp_code.contains(
+        "void Foo_calc(const FooOpaque * const self, int32_t a_0, struct CFnOncei32 a_1);"
+    ));
+    assert!(cpp_code.contains("static void result_readyi32 ( int32_t ret , void * ctx )"));
+    assert!(cpp_code.contains("std :: make_shared < int32_t > ( ret )"));
+    assert!(
+        cpp_code.contains("QFuture<std::shared_ptr<int32_t>> calc(int32_t a_0) const  noexcept;")
+    );
+    assert!(cpp_code.contains(
+        r#"
+        struct CFnOncei32 a_1;
+        QFuture<std::shared_ptr<int32_t>> ret;
+        auto fi = new QFutureInterface<std::shared_ptr<int32_t>>;
+        fi->reportStarted();
+        a_1.cb = result_readyi32;
+        a_1.ctx = fi;
+        ret = fi->future();
+        Foo_calc(this->self_, a_0, a_1);
+        return ret;
+"#
+    ));
+    assert!(cpp_code.contains("static QFuture<std::shared_ptr<double>> calc_f64() noexcept;"));
+    assert!(cpp_code.contains("Foo_calc_f64(a_0);"));
+    assert_eq!(1, rust_code.matches("pub struct CFnOncei32").count());
+    assert_eq!(1, rust_code.matches("pub struct CFnOncef64").count());
+    assert!(rust_code.contains(
+        "let a_1 : _ = move | x | ( a_1 . cb ) ( { let swig_c_value : i32 = x ; swig_c_value } , a_1 . ctx ) ;"
+    ));
 }
 
-enum Source<'a> {
-    Str(&'a str),
-    Path(&'a Path),
+#[test]
+fn test_csharp_classes_enums_and_callbacks() {
+    let _ = env_logger::try_init();
+
+    let name = "csharp_classes_enums_and_callbacks";
+    let src = r#"
+foreign_enum!(
+/// Enum doc
+enum MyEnum {
+    ITEM1 = MyEnum::Item1,
+    ITEM2 = MyEnum::Item2,
+});
+foreign_interface!(interface SomeObserver {
+    self_type SomeTrait;
+    onStateChanged = SomeTrait::on_state_changed(&self, _: i32, _: bool);
+    onName = SomeTrait::on_name(&self, _: &str) -> i32;
+});
+foreigner_class!(class Boo {
+    self_type Boo;
+    constructor Boo::default() -> Boo;
+});
+foreigner_class!(
+/// Class doc
+class Foo {
+    self_type Foo;
+    constructor Foo::new(_: i32, _: &str) -> Foo;
+    /// Method doc
+    method Foo::f(&self, _: i32, _: bool) -> bool;
+    method Foo::name(&self) -> &str;
+    method Foo::to_string(&self) -> String;
+    method Foo::set_enum(&mut self, _: MyEnum);
+    method Foo::boo(&self) -> &Boo;
+    method Foo::set_boo(&mut self, _: Boo);
+    method Foo::check_boo(&self, _: &Boo) -> MyEnum;
+    method Foo::subscribe(&mut self, _: Box<SomeTrait>);
+    static_method Foo::create_boo() -> Boo;
+});
+foreigner_class!(class Utils {
+    static_method f2(_: f64) -> u64;
+});
+"#;
+    let code = parse_code(name, Source::Str(src), ForeignLang::CSharp).unwrap();
+    println!("C#: {}", code.foreign_code);
+    for pat in &[
+        "public enum MyEnum : uint",
+        "ITEM2 = 1,",
+        "internal sealed class FooHandle : SafeHandle",
+        "Foo.Foo_delete(handle);",
+        "/// Class doc\n    public class Foo : IDisposable",
+        "public Foo(int a0, string a1)",
+        "this.handle = new FooHandle(Foo_new(a0, a1), true);",
+        "[DllImport(\"rust_lib\", CallingConvention = CallingConvention.Cdecl)]",
+        "private static extern IntPtr Foo_new(int a0, [MarshalAs(UnmanagedType.LPUTF8Str)] string a1);",
+        "/// Method doc\n        public bool f(int a0, bool a1)",
+        "byte ret = Foo_f(this.handle, a0, (a1 ? (byte)1 : (byte)0));",
+        "return (ret != 0);",
+        "private static extern RustStrView Foo_name(FooHandle self);",
+        "return ret.Consume();",
+        "private static extern void Foo_set_enum(FooHandle self, MyEnum a0);",
+        "return new Boo(new BooHandle(ret, false));",
+        "Foo_set_boo(this.handle, a0.handle.TakeOwnership());",
+        "Foo_check_boo(this.handle, a0.handle);",
+        "private static extern void Foo_subscribe(FooHandle self, in C_SomeObserver a0);",
+        "Foo_subscribe(this.handle, C_SomeObserver.Create(a0));",
+        "public static Boo create_boo()",
+        "return new Boo(new BooHandle(ret, true));",
+        "public static class Utils",
+        "public static ulong f2(double a0)",
+        "public interface SomeObserver",
+        "void onStateChanged(int a0, bool a1);",
+        "int onName(string a0);",
+        "private delegate void onStateChangedDelegate(int a0, byte a1, IntPtr opaque);",
+        "obj.onStateChanged(a0, (a1 != 0));",
+        "return obj.onName(a0.ToString());",
+        "internal struct CRustString",
+    ] {
+        assert!(code.foreign_code.contains(pat), "no '{}' in C# code", pat);
+    }
+    assert!(!code.foreign_code.contains("namespace Org.Examples {"));
+    assert!(!code.foreign_code.contains("#pragma once"));
+    assert!(code
+        .rust_code
+        .contains("pub extern \"C\" fn Foo_f ( this : * mut Foo , a_0 : i32 , a_1 : :: std :: os :: raw :: c_char , )"));
 }
 
-fn parse_code(test_name: &str, rust_src: Source, lang: ForeignLang) -> Result<CodePair, Error> {
+#[test]
+fn test_csharp_options_vecs_results() {
+    let _ = env_logger::try_init();
+
+    let name = "csharp_options_vecs_results";
+    let src = r#"
+foreign_enum!(enum MyEnum {
+    ITEM1 = MyEnum::Item1,
+    ITEM2 = MyEnum::Item2,
+});
+foreigner_class!(class Boo {
+    self_type Boo;
+    constructor Boo::default() -> Boo;
+});
+foreigner_class!(class Foo {
+    self_type Foo;
+    constructor Foo::default() -> Foo;
+    method Foo::opt_i32(&self) -> Option<i32>;
+    method Foo::set_opt_f64(&self, _: Option<f64>);
+    method Foo::opt_string(&self) -> Option<String>;
+    method Foo::opt_boo(&self) -> Option<Boo>;
+    method Foo::set_opt_boo(&self, _: Option<Boo>);
+    method Foo::vec_u8(&self) -> Vec<u8>;
+    method Foo::vec_boo(&self) -> Vec<Boo>;
+    method Foo::set_vec_boo(&self, _: Vec<Boo>);
+    method Foo::set_slice_i32(&self, _: &[i32]);
+    method Foo::slice_boo(&self) -> &[Boo];
+    method Foo::check(&self) -> Result<(), String>;
+    method Foo::boo_or_err(&self) -> Result<Boo, Boo>;
+    method Foo::boo_or_enum(&self) -> Result<Boo, MyEnum>;
+    method Foo::num(&self) -> Result<i64, Boo>;
+});
+"#;
+    let code = parse_code(name, Source::Str(src), ForeignLang::CSharp).unwrap();
+    println!("C#: {}", code.foreign_code);
+    for pat in &[
+        "public int? opt_i32()",
+        "Foo_set_opt_f64(this.handle, CRustOptionF64.FromNullable(a0));",
+        "CRustOptionString ret = Foo_opt_string(this.handle);",
+        "return (ret != IntPtr.Zero ? new Boo(new BooHandle(ret, true)) : null);",
+        "Foo_set_opt_boo(this.handle, (a0 != null ? a0.handle.TakeOwnership() : IntPtr.Zero));",
+        "public byte[] vec_u8()",
+        "return Boo.SwigConsumeVec(ret);",
+        "Foo_set_vec_boo(this.handle, Boo.SwigToVec(a0));",
+        "            var swigPins = new System.Collections.Generic.List<GCHandle>();\n            try\n            {\n                Foo_set_slice_i32(this.handle, CRustSliceI32.Pin(a0, swigPins));\n            }\n",
+        "return Boo.SwigFromSlice(ret);",
+        "            CResultObjectString ret = Foo_check(this.handle);\n            ret.Unwrap();\n",
+        "return new Boo(new BooHandle(ret.Unwrap(e => new Boo(new BooHandle(e, true))), true));",
+        "return new Boo(new BooHandle(ret.Unwrap(e => (MyEnum)e), true));",
+        "public long num()",
+        "private static extern IntPtr RustForeignVecBoo_remove(ref CRustForeignVec v, UIntPtr idx);",
+        "public class RustException : Exception",
+    ] {
+        assert!(code.foreign_code.contains(pat), "no '{}' in C# code", pat);
+    }
+
     let tmp_dir = tempdir().expect("Can not create tmp directory");
-    let (swig_gen, ext_list): (Generator, &[&'static str]) = match lang {
-        ForeignLang::Java => {
-            let swig_gen = Generator::new(LanguageConfig::JavaConfig(
-                JavaConfig::new(tmp_dir.path().into(), "org.example".into())
-                    .use_null_annotation_from_package("android.support.annotation".into()),
-            ))
-            .with_pointer_target_width(64);
+    let src_path = tmp_dir.path().join("src.rs");
+    fs::write(
+        &src_path,
+        r#"
+foreigner_class!(class Boo {
+    self_type Boo;
+    constructor Boo::default() -> Boo;
+    method Boo::f(&self, _: &[Boo]);
+});
+"#,
+    )
+    .unwrap();
+    let err = Generator::new(LanguageConfig::CSharpConfig(CSharpConfig::new(
+        tmp_dir.path().into(),
+        "Org.Examples".into(),
+        "rust_lib".into(),
+    )))
+    .with_pointer_target_width(64)
+    .try_expand(name, &src_path, src_path.with_file_name("out.rs"))
+    .unwrap_err();
+    assert!(err.diagnostics()[0]
+        .message()
+        .contains("objects of C# classes are not placed in one continuous memory block"));
+}
 
-            (swig_gen, &[".java"])
-        }
-        ForeignLang::Cpp => {
-            let swig_gen = Generator::new(LanguageConfig::CppConfig(CppConfig::new(
-                tmp_dir.path().into(),
-                "org_examples".into(),
-            )))
-            .with_pointer_target_width(64);
-            (swig_gen, &[".h", ".hpp"])
+#[test]
+fn test_csharp_code_compiles() {
+    let _ = env_logger::try_init();
+
+    let dotnet_version = match Command::new("dotnet").arg("--version").output() {
+        Ok(ref out) if out.status.success() => String::from_utf8_lossy(&out.stdout).into_owned(),
+        _ => {
+            println!("dotnet not found, skip compilation of C# code");
+            return;
         }
     };
+    let major_version = dotnet_version.trim().split('.').next().unwrap_or("6");
 
-    let rust_code_path = tmp_dir.path().join("test.rs");
-    match rust_src {
-        Source::Path(rust_src_path) => swig_gen.expand(test_name, rust_src_path, &rust_code_path),
-        Source::Str(rust_src) => {
-            let rust_src_path = tmp_dir.path().join("src.rs");
-            fs::write(&rust_src_path, rust_src)?;
-            swig_gen.expand(test_name, rust_src_path, &rust_code_path);
-        }
+    let tmp_dir = tempdir().expect("Can not create tmp directory");
+    let src_path = tmp_dir.path().join("src.rs");
+    fs::write(
+        &src_path,
+        r#"
+foreign_enum!(enum MyEnum {
+    ITEM1 = MyEnum::Item1,
+    ITEM2 = MyEnum::Item2,
+});
+foreign_interface!(interface SomeObserver {
+    self_type SomeTrait;
+    onStateChanged = SomeTrait::on_state_changed(&self, _: i32, _: bool);
+    onName = SomeTrait::on_name(&self, _: &str) -> i32;
+});
+foreigner_class!(class Boo {
+    self_type Boo;
+    constructor Boo::default() -> Boo;
+});
+foreigner_class!(class Foo {
+    self_type Foo;
+    constructor Foo::new(_: i32, _: &str) -> Foo;
+    method Foo::name(&self) -> &str;
+    method Foo::set_enum(&mut self, _: MyEnum);
+    method Foo::subscribe(&mut self, _: Box<SomeTrait>);
+    method Foo::opt_i32(&self) -> Option<i32>;
+    method Foo::set_opt_bool(&self, _: Option<bool>);
+    method Foo::opt_str(&self) -> Option<&str>;
+    method Foo::opt_string(&self) -> Option<String>;
+    method Foo::opt_boo(&self) -> Option<Boo>;
+    method Foo::set_opt_boo(&self, _: Option<Boo>);
+    method Foo::vec_i32(&self) -> Vec<i32>;
+    method Foo::vec_f64(&self) -> Vec<f64>;
+    method Foo::vec_boo(&self) -> Vec<Boo>;
+    method Foo::set_vec_boo(&self, _: Vec<Boo>);
+    method Foo::set_slice_i32(&self, _: &[i32]);
+    method Foo::slice_i32(&self) -> &[i32];
+    method Foo::slice_boo(&self) -> &[Boo];
+    method Foo::check(&self) -> Result<(), String>;
+    method Foo::boo_or_str(&self) -> Result<Boo, String>;
+    method Foo::boo_or_err(&self) -> Result<Boo, Boo>;
+    method Foo::boo_or_enum(&self) -> Result<Boo, MyEnum>;
+    method Foo::num(&self) -> Result<i64, Boo>;
+    method Foo::boos(&self) -> Result<Vec<Boo>, String>;
+    method Foo::check_boo(&self) -> Result<(), Boo>;
+    method Foo::bytes(&self) -> Result<Vec<u8>, Boo>;
+});
+"#,
+    )
+    .unwrap();
+    let cs_dir = tmp_dir.path().join("cs");
+    fs::create_dir(&cs_dir).unwrap();
+    Generator::new(LanguageConfig::CSharpConfig(CSharpConfig::new(
+        cs_dir.clone(),
+        "Org.Examples".into(),
+        "rust_lib".into(),
+    )))
+    .with_pointer_target_width(64)
+    .try_expand(
+        "csharp_code_compiles",
+        &src_path,
+        tmp_dir.path().join("out.rs"),
+    )
+    .unwrap();
+    fs::write(
+        cs_dir.join("RustLib.csproj"),
+        format!(
+            r#"<Project Sdk="Microsoft.NET.Sdk">
+  <PropertyGroup>
+    <TargetFramework>net{}.0</TargetFramework>
+    <OutputType>Library</OutputType>
+  </PropertyGroup>
+</Project>
+"#,
+            major_version
+        ),
+    )
+    .unwrap();
+    let out = Command::new("dotnet")
+        .arg("build")
+        .current_dir(&cs_dir)
+        .output()
+        .expect("Can not run dotnet build");
+    println!("{}", String::from_utf8_lossy(&out.stdout));
+    assert!(out.status.success(), "compilation of C# code failed");
+}
+
+#[test]
+fn test_python_classes_and_enums() {
+    let _ = env_logger::try_init();
+
+    let name = "python_classes_and_enums";
+    let src = r#"
+foreign_enum!(
+/// Enum doc
+enum MyEnum {
+    ITEM1 = MyEnum::Item1,
+    ITEM2 = MyEnum::Item2,
+});
+foreigner_class!(class Boo {
+    self_type Boo;
+    constructor Boo::default() -> Boo;
+});
+foreigner_class!(
+/// Class doc
+class Foo {
+    self_type Foo;
+    constructor Foo::new(_: i32, _: &str) -> Foo;
+    constructor Foo::with_boo(_: Boo) -> Foo;
+    /// Method doc
+    method Foo::f(&self, _: i32, _: bool) -> bool;
+    method Foo::name(&self) -> &str;
+    method Foo::to_string(&self) -> String;
+    method Foo::set_enum(&mut self, _: MyEnum);
+    method Foo::check_boo(&self, _: &Boo) -> Result<MyEnum, String>;
+    static_method Foo::create_boo() -> Boo;
+});
+foreigner_class!(class Utils {
+    static_method f2(_: f64) -> u64;
+});
+"#;
+    let code = parse_code(name, Source::Str(src), ForeignLang::Python).unwrap();
+    println!("Python: {}", code.foreign_code);
+    for pat in &[
+        "class MyEnum(enum.IntEnum):\n    \"\"\"Enum doc\"\"\"\n    ITEM1 = 0\n    ITEM2 = 1\n",
+        "class Boo:\n    def __init__(self) -> None: ...\n",
+        "class Foo:\n    \"\"\"Class doc\"\"\"\n    @overload\n    def __init__(self, a0: int, a1: str) -> None: ...\n",
+        "    @overload\n    def __init__(self, a0: Boo) -> None: ...\n",
+        "    def f(self, a0: int, a1: bool) -> bool:\n        \"\"\"Method doc\"\"\"\n        ...\n",
+        "    def name(self) -> str: ...\n",
+        "    def set_enum(self, a0: MyEnum) -> None: ...\n",
+        "    def check_boo(self, a0: Boo) -> MyEnum: ...\n",
+        "    @staticmethod\n    def create_boo() -> Boo: ...\n",
+        "    def __enter__(self) -> \"Foo\": ...\n",
+        "class Utils:\n    @staticmethod\n    def f2(a0: float) -> int: ...\n",
+    ] {
+        assert!(code.foreign_code.contains(pat), "no '{}' in Python code", pat);
+    }
+    assert!(!code.foreign_code.contains("class Utils:\n    def __init__"));
+    for pat in &[
+        "pub extern \"C\" fn PyInit_rust_lib ( ) -> * mut PyObject",
+        "! swig_py_add_enum_MyEnum ( module ) || ! swig_py_add_class_Boo ( module )",
+        "extern \"C\" fn swig_py_Foo_f ( self_ : * mut PyObject , args : * mut PyObject ) -> * mut PyObject",
+        "1 => swig_py_Foo_init_1 ( self_ , args ) ,",
+        "2 => swig_py_Foo_init_2 ( self_ , args ) ,",
+        "SwigForeignClass for Foo {",
+        "METH_VARARGS | METH_STATIC",
+    ] {
+        assert!(code.rust_code.contains(pat), "no '{}' in Rust code", pat);
+    }
+}
+
+#[test]
+fn test_python_options_lists_and_interfaces() {
+    let _ = env_logger::try_init();
+
+    let name = "python_options_lists_and_interfaces";
+    let src = r#"
+foreign_interface!(
+/// Interface doc
+interface SomeObserver {
+    self_type SomeTrait;
+    onStateChanged = SomeTrait::on_state_changed(&self, _: i32, _: &str);
+    getName = SomeTrait::get_name(&self) -> String;
+    maybeValue = SomeTrait::maybe_value(&self, _: Vec<f64>) -> Option<i64>;
+});
+foreigner_class!(class Boo {
+    self_type Boo;
+    constructor Boo::default() -> Boo;
+});
+foreigner_class!(class Foo {
+    self_type Foo;
+    constructor Foo::default() -> Foo;
+    method Foo::opt_name(&self) -> Option<String>;
+    method Foo::set_count(&mut self, _: Option<u32>);
+    method Foo::boos(&self) -> Vec<Boo>;
+    method Foo::set_boos(&mut self, _: Vec<Boo>);
+    method Foo::find_boo(&self, _: Option<Boo>) -> Option<Boo>;
+    method Foo::sum(&self, _: &[f64]) -> Vec<f64>;
+    method Foo::names(&self) -> Vec<String>;
+    method Foo::subscribe(&mut self, _: Box<SomeTrait>);
+});
+"#;
+    let code = parse_code(name, Source::Str(src), ForeignLang::Python).unwrap();
+    println!("Python: {}", code.foreign_code);
+    for pat in &[
+        "from typing import Any, List, Optional, Protocol, overload\n",
+        "class SomeObserver(Protocol):\n    \"\"\"Interface doc\"\"\"\n    def onStateChanged(self, a0: int, a1: str) -> None: ...\n    def getName(self) -> str: ...\n    def maybeValue(self, a0: List[float]) -> Optional[int]: ...\n",
+        "    def opt_name(self) -> Optional[str]: ...\n",
+        "    def set_count(self, a0: Optional[int]) -> None: ...\n",
+        "    def boos(self) -> List[Boo]: ...\n",
+        "    def set_boos(self, a0: List[Boo]) -> None: ...\n",
+        "    def find_boo(self, a0: Optional[Boo]) -> Optional[Boo]: ...\n",
+        "    def sum(self, a0: List[float]) -> List[float]: ...\n",
+        "    def names(self) -> List[str]: ...\n",
+        "    def subscribe(self, a0: SomeObserver) -> None: ...\n",
+    ] {
+        assert!(code.foreign_code.contains(pat), "no '{}' in Python code", pat);
+    }
+    for pat in &[
+        "pub struct SwigPyCallbackSomeObserver ( SwigPyCallback ) ;",
+        "impl SomeTrait for SwigPyCallbackSomeObserver {",
+        "let _gil = SwigPyGil :: acquire ( ) ;",
+        "self . 0 . call ( swig_c_str ! ( \"onStateChanged\" ) , & [ a_0 , a_1 ] )",
+        "self . 0 . failed ( \"SomeObserver.getName\" )",
+        "SwigPyCallback :: new ( self )",
+        "a_0 . swig_deref ( )",
+    ] {
+        assert!(code.rust_code.contains(pat), "no '{}' in Rust code", pat);
+    }
+}
+
+#[test]
+fn test_c_headers() {
+    let _ = env_logger::try_init();
+
+    let name = "c_headers";
+    let src = r#"
+foreign_enum!(
+/// Enum doc
+enum MyEnum {
+    ITEM1 = MyEnum::Item1,
+    ITEM2 = MyEnum::Item2,
+});
+foreign_interface!(interface SomeObserver {
+    self_type SomeTrait;
+    onStateChanged = SomeTrait::on_state_changed(&self, _: i32, _: bool);
+});
+foreigner_class!(class Boo {
+    self_type Boo;
+    constructor Boo::default() -> Boo;
+});
+foreigner_class!(
+/// Class doc
+class Foo {
+    self_type Foo;
+    constructor Foo::new(_: i32, _: &str) -> Foo;
+    method Foo::to_string(&self) -> String;
+    method Foo::opt_name(&self) -> Option<String>;
+    method Foo::boos(&self) -> Vec<Boo>;
+    method Foo::subscribe(&mut self, _: Box<SomeTrait>);
+    static_method Foo::create_boo() -> Boo;
+});
+"#;
+    let code = parse_code(name, Source::Str(src), ForeignLang::C).unwrap();
+    println!("C: {}", code.foreign_code);
+    for pat in &[
+        "void crust_string_free(struct CRustString str);",
+        "void CRustOptionString_free(struct CRustOptionString opt);",
+        "void CRustVecU8_free(struct CRustVecU8 vec);",
+        "    struct RustStrView val;\n    uint8_t is_some;",
+        "#include \"rust_swig_c.h\"\n#include \"c_SomeObserver.h\"\n#include \"c_Boo.h\"\n",
+        "    typedef struct FooOpaque FooOpaque;",
+        "    struct CRustOptionString Foo_opt_name(const FooOpaque * const self);",
+        "    BooOpaque * Foo_create_boo(void);",
+        "    BooOpaque *Boo_default(void);",
+        "typedef enum MyEnum MyEnum;",
+        "struct C_SomeObserver {",
+        "void RustForeignVecBoo_free(struct CRustForeignVec vec);",
+        "BooOpaque *RustForeignVecBoo_remove(struct CRustForeignVec *vec, uintptr_t idx);",
+    ] {
+        assert!(code.foreign_code.contains(pat), "no '{}' in C code", pat);
+    }
+    for pat in &["static_assert", "namespace", "template", "class Foo"] {
+        assert!(!code.foreign_code.contains(pat), "'{}' in C code", pat);
+    }
+    assert!(code
+        .rust_code
+        .contains("pub extern \"C\" fn CRustOptionString_free ( x : CRustOptionString )"));
+}
+
+#[test]
+fn test_nodejs_classes_enums_and_interfaces() {
+    let _ = env_logger::try_init();
+
+    let name = "nodejs_classes_enums_and_interfaces";
+    let src = r#"
+foreign_enum!(
+/// Enum doc
+enum MyEnum {
+    ITEM1 = MyEnum::Item1,
+    ITEM2 = MyEnum::Item2,
+});
+foreign_interface!(interface SomeObserver {
+    self_type SomeTrait;
+    onStateChanged = SomeTrait::on_state_changed(&self, _: i32, _: &str);
+    getName = SomeTrait::get_name(&self) -> String;
+});
+foreigner_class!(class Boo {
+    self_type Boo;
+    constructor Boo::default() -> Boo;
+});
+foreigner_class!(
+/// Class doc
+class Foo {
+    self_type Foo;
+    constructor Foo::new(_: i32, _: &str) -> Foo;
+    constructor Foo::with_boo(_: Boo) -> Foo;
+    /// Method doc
+    method Foo::f(&self, _: i32, _: bool) -> bool;
+    method Foo::name(&self) -> &str;
+    method Foo::opt_name(&self) -> Option<String>;
+    method Foo::set_enum(&mut self, _: MyEnum);
+    method Foo::check_boo(&self, _: &Boo) -> Result<MyEnum, String>;
+    method Foo::boos(&self) -> Vec<Boo>;
+    method Foo::find_boo(&self, _: Option<Boo>) -> Option<Boo>;
+    method Foo::sum(&self, _: &[f64]) -> Vec<f64>;
+    method Foo::subscribe(&mut self, _: Box<SomeTrait>);
+    static_method Foo::create_boo() -> Boo;
+});
+foreigner_class!(class Utils {
+    static_method f2(_: f64) -> u64;
+});
+"#;
+    let code = parse_code(name, Source::Str(src), ForeignLang::NodeJs).unwrap();
+    println!("Node.js: {}", code.foreign_code);
+    for pat in &[
+        "/**\n * Enum doc\n */\nexport declare enum MyEnum {\n    ITEM1 = 0,\n    ITEM2 = 1,\n}\n",
+        "export interface SomeObserver {\n    onStateChanged(a0: number, a1: string): void;\n    getName(): string;\n}\n",
+        "export declare class Boo {\n    constructor();\n    /** Free Rust object",
+        "    constructor(a0: number, a1: string);\n    constructor(a0: Boo);\n",
+        "    /**\n     * Method doc\n     */\n    f(a0: number, a1: boolean): boolean;\n",
+        "    name(): string;\n",
+        "    opt_name(): string | null;\n",
+        "    set_enum(a0: MyEnum): void;\n",
+        "    check_boo(a0: Boo): MyEnum;\n",
+        "    boos(): Boo[];\n",
+        "    find_boo(a0: Boo | null): Boo | null;\n",
+        "    sum(a0: number[]): number[];\n",
+        "    subscribe(a0: SomeObserver): void;\n",
+        "    static create_boo(): Boo;\n",
+        "    close(): void;\n",
+        "export declare class Utils {\n    static f2(a0: number): number;\n    private constructor();\n}\n",
+    ] {
+        assert!(code.foreign_code.contains(pat), "no '{}' in TypeScript code", pat);
+    }
+    for pat in &[
+        "pub extern \"C\" fn napi_register_module_v1 ( env : napi_env , exports : napi_value ) -> napi_value",
+        "! swig_napi_add_enum_MyEnum ( env , exports ) || ! swig_napi_add_class_Boo ( env , exports )",
+        "extern \"C\" fn swig_napi_Foo_f ( env : napi_env , info : napi_callback_info ) -> napi_value",
+        "1 => swig_napi_Foo_new_1 ( env , info ) ,",
+        "2 => swig_napi_Foo_new_2 ( env , info ) ,",
+        "if ! swig_napi_is_construct_call ( env , info ) { swig_napi_throw_type_error ( env , \"Class constructor Foo cannot be invoked without 'new'\" ) ; return :: std :: ptr :: null_mut ( ) ; }",
+        "SwigForeignClass for Foo {",
+        "impl SomeTrait for SwigNapiCallbackSomeObserver {",
+        "napi_static",
+    ] {
+        assert!(code.rust_code.contains(pat), "no '{}' in Rust code", pat);
+    }
+}
+
+#[test]
+fn test_dart_classes_enums_and_interfaces() {
+    let _ = env_logger::try_init();
+
+    let name = "dart_classes_enums_and_interfaces";
+    let src = r#"
+foreign_enum!(
+/// Enum doc
+enum MyEnum {
+    ITEM1 = MyEnum::Item1,
+    ITEM2 = MyEnum::Item2,
+});
+foreign_interface!(interface SomeObserver {
+    self_type SomeTrait;
+    onStateChanged = SomeTrait::on_state_changed(&self, _: i32, _: bool);
+    getValue = SomeTrait::get_value(&self) -> f64;
+});
+foreigner_class!(class Boo {
+    self_type Boo;
+    constructor Boo::default() -> Boo;
+});
+foreigner_class!(
+/// Class doc
+class Foo {
+    self_type Foo;
+    constructor Foo::new(_: i32, _: &str) -> Foo;
+    constructor Foo::with_boo(_: Boo) -> Foo;
+    /// Method doc
+    method Foo::f(&self, _: i32, _: bool) -> bool;
+    method Foo::name(&self) -> &str;
+    method Foo::to_string(&self) -> String;
+    method Foo::set_enum(&mut self, _: MyEnum);
+    method Foo::get_enum(&self) -> MyEnum;
+    method Foo::check_boo(&self, _: &Boo) -> i64;
+    method Foo::subscribe(&mut self, _: Box<SomeTrait>);
+    static_method Foo::create_boo() -> Boo;
+});
+foreigner_class!(class Utils {
+    static_method f2(_: f64) -> u64;
+});
+"#;
+    let code = parse_code(name, Source::Str(src), ForeignLang::Dart).unwrap();
+    println!("Dart: {}", code.foreign_code);
+    for pat in &[
+        "part 'rust_swig.dart';\n",
+        "part 'some_observer.dart';\n",
+        "part of 'rust_lib.dart';\n",
+        "/// Enum doc\nenum MyEnum {\n  ITEM1(0),\n  ITEM2(1);\n\n  const MyEnum(this.value);\n  final int value;\n",
+        "final _Foo_new = _rustSwigLib.lookupFunction<\n    Pointer<Void> Function(Int32, Pointer<Utf8>),\n    Pointer<Void> Function(int, Pointer<Utf8>)>('Foo_new');\n",
+        "final _Foo_f = _rustSwigLib.lookupFunction<\n    Uint8 Function(Pointer<Void>, Int32, Uint8),\n    int Function(Pointer<Void>, int, int)>('Foo_f');\n",
+        "/// Class doc\nclass Foo implements Finalizable {\n  static final _finalizer = NativeFinalizer(_Foo_delete_ptr.cast());\n",
+        "  factory Foo.new(int a0, String a1) {\n    return using((Arena arena) {\n      return Foo._(_Foo_new(a0, a1.toNativeUtf8(allocator: arena)), true);\n    });\n  }\n",
+        "  factory Foo.with_boo(Boo a0) {\n    return Foo._(_Foo_with_boo(a0._takeOwnership()), true);\n  }\n",
+        "  /// Method doc\n  bool f(int a0, bool a1) {\n    final ret = _Foo_f(_handle, a0, (a1 ? 1 : 0));\n    return (ret != 0);\n  }\n",
+        "  String name() {\n    final ret = _Foo_name(_handle);\n    return ret.toDartString();\n  }\n",
+        "    return ret.consume();\n",
+        "  void set_enum(MyEnum a0) {\n    _Foo_set_enum(_handle, a0.value);\n  }\n",
+        "    return MyEnum.fromValue(ret);\n",
+        "  int check_boo(Boo a0) {\n    final ret = _Foo_check_boo(_handle, a0._handle);\n",
+        "    return using((Arena arena) {\n      _Foo_subscribe(_handle, C_SomeObserver.create(a0, arena));\n",
+        "  static Boo create_boo() {\n    final ret = _Foo_create_boo();\n    return Boo._(ret, true);\n  }\n",
+        "abstract final class Utils {\n  static int f2(double a0) {\n",
+        "abstract interface class SomeObserver {\n  void onStateChanged(int a0, bool a1);\n  double getValue();\n}\n",
+        "  factory Boo.default_() {\n    return Boo._(_Boo_default(), true);\n  }\n",
+        "final class C_SomeObserver extends Struct {\n  external Pointer<Void> opaque;\n",
+        "  external Pointer<NativeFunction<Void Function(Int32, Uint8, Pointer<Void>)>> onStateChanged;\n",
+        "NativeCallable<Double Function(Pointer<Void>)>.isolateLocal(\n      _call_getValue,\n      exceptionalReturn: 0.0);",
+        "  static void _call_onStateChanged(int a0, int a1, Pointer<Void> opaque) {\n    final obj = _objects[opaque.address]!;\n    obj.onStateChanged(a0, (a1 != 0));\n  }\n",
+    ] {
+        assert!(code.foreign_code.contains(pat), "no '{}' in Dart code", pat);
+    }
+}
+
+#[test]
+fn test_dart_options_vecs_results() {
+    let _ = env_logger::try_init();
+
+    let name = "dart_options_vecs_results";
+    let src = r#"
+foreign_enum!(enum MyEnum {
+    ITEM1 = MyEnum::Item1,
+    ITEM2 = MyEnum::Item2,
+});
+foreigner_class!(class Boo {
+    self_type Boo;
+    constructor Boo::default() -> Boo;
+});
+foreigner_class!(class Foo {
+    self_type Foo;
+    constructor Foo::default() -> Foo;
+    method Foo::opt_i32(&self) -> Option<i32>;
+    method Foo::set_opt_f64(&self, _: Option<f64>);
+    method Foo::opt_string(&self) -> Option<String>;
+    method Foo::set_opt_str(&self, _: Option<&str>);
+    method Foo::opt_boo(&self) -> Option<Boo>;
+    method Foo::set_opt_boo(&self, _: Option<Boo>);
+    method Foo::vec_u8(&self) -> Vec<u8>;
+    method Foo::vec_boo(&self) -> Vec<Boo>;
+    method Foo::set_vec_boo(&self, _: Vec<Boo>);
+    method Foo::set_slice_i32(&self, _: &[i32]);
+    method Foo::slice_boo(&self) -> &[Boo];
+    method Foo::check(&self) -> Result<(), String>;
+    method Foo::boo_or_err(&self) -> Result<Boo, Boo>;
+    method Foo::boo_or_enum(&self) -> Result<Boo, MyEnum>;
+    method Foo::num(&self) -> Result<i64, Boo>;
+});
+"#;
+    let code = parse_code(name, Source::Str(src), ForeignLang::Dart).unwrap();
+    println!("Dart: {}", code.foreign_code);
+    for pat in &[
+        "  int? opt_i32() {\n    final ret = _Foo_opt_i32(_handle);\n    return ret.toNullable();\n  }\n",
+        "      _Foo_set_opt_f64(_handle, CRustOptionF64.fromNullable(a0, arena));\n",
+        "  String? opt_string() {\n",
+        "      _Foo_set_opt_str(_handle, (a0?.toNativeUtf8(allocator: arena) ?? nullptr));\n",
+        "    return (ret == nullptr ? null : Boo._(ret, true));\n",
+        "    _Foo_set_opt_boo(_handle, (a0?._takeOwnership() ?? nullptr));\n",
+        "  List<int> vec_u8() {\n",
+        "    return Boo._consumeVec(ret);\n",
+        "      _Foo_set_vec_boo(_handle, Boo._toVec(a0, arena));\n",
+        "      _Foo_set_slice_i32(_handle, CRustSliceI32.fromList(a0, arena));\n",
+        "    return Boo._fromSlice(ret);\n",
+        "  void check() {\n    final ret = _Foo_check(_handle);\n    ret.unwrap();\n  }\n",
+        "    return Boo._(ret.unwrap((e) => Boo._(e, true)), true);\n",
+        "    return Boo._(ret.unwrap((e) => MyEnum.fromValue(e)), true);\n",
+        "  int num() {\n",
+        "Pointer<Void> Function(Pointer<CRustForeignVec>, int)>('RustForeignVecBoo_remove');\n",
+        "class RustException implements Exception {\n",
+    ] {
+        assert!(code.foreign_code.contains(pat), "no '{}' in Dart code", pat);
+    }
+
+    let tmp_dir = tempdir().expect("Can not create tmp directory");
+    let src_path = tmp_dir.path().join("src.rs");
+    fs::write(
+        &src_path,
+        r#"
+foreigner_class!(class Boo {
+    self_type Boo;
+    constructor Boo::default() -> Boo;
+    method Boo::f(&self, _: &[Boo]);
+});
+"#,
+    )
+    .unwrap();
+    let err = Generator::new(LanguageConfig::DartConfig(DartConfig::new(
+        tmp_dir.path().into(),
+        "rust_lib".into(),
+    )))
+    .with_pointer_target_width(64)
+    .try_expand(name, &src_path, src_path.with_file_name("out.rs"))
+    .unwrap_err();
+    assert!(err.diagnostics()[0]
+        .message()
+        .contains("objects of Dart classes are not placed in one continuous memory block"));
+}
+
+#[test]
+fn test_dart_code_analyzes() {
+    let _ = env_logger::try_init();
+
+    match Command::new("dart").arg("--version").output() {
+        Ok(ref out) if out.status.success() => {}
+        _ => {
+            println!("dart not found, skip analysis of Dart code");
+            return;
+        }
+    }
+
+    let tmp_dir = tempdir().expect("Can not create tmp directory");
+    let src_path = tmp_dir.path().join("src.rs");
+    fs::write(
+        &src_path,
+        r#"
+foreign_enum!(enum MyEnum {
+    ITEM1 = MyEnum::Item1,
+    ITEM2 = MyEnum::Item2,
+});
+foreign_interface!(interface SomeObserver {
+    self_type SomeTrait;
+    onStateChanged = SomeTrait::on_state_changed(&self, _: i32, _: bool);
+    getValue = SomeTrait::get_value(&self) -> f64;
+});
+foreigner_class!(class Boo {
+    self_type Boo;
+    constructor Boo::default() -> Boo;
+});
+foreigner_class!(class Foo {
+    self_type Foo;
+    constructor Foo::new(_: i32, _: &str) -> Foo;
+    method Foo::name(&self) -> &str;
+    method Foo::set_enum(&mut self, _: MyEnum);
+    method Foo::subscribe(&mut self, _: Box<SomeTrait>);
+    method Foo::opt_i32(&self) -> Option<i32>;
+    method Foo::set_opt_bool(&self, _: Option<bool>);
+    method Foo::opt_str(&self) -> Option<&str>;
+    method Foo::set_opt_str(&self, _: Option<&str>);
+    method Foo::opt_string(&self) -> Option<String>;
+    method Foo::opt_boo(&self) -> Option<Boo>;
+    method Foo::set_opt_boo(&self, _: Option<Boo>);
+    method Foo::vec_i32(&self) -> Vec<i32>;
+    method Foo::vec_f64(&self) -> Vec<f64>;
+    method Foo::vec_boo(&self) -> Vec<Boo>;
+    method Foo::set_vec_boo(&self, _: Vec<Boo>);
+    method Foo::set_slice_i32(&self, _: &[i32]);
+    method Foo::slice_i32(&self) -> &[i32];
+    method Foo::slice_boo(&self) -> &[Boo];
+    method Foo::check(&self) -> Result<(), String>;
+    method Foo::boo_or_str(&self) -> Result<Boo, String>;
+    method Foo::boo_or_err(&self) -> Result<Boo, Boo>;
+    method Foo::boo_or_enum(&self) -> Result<Boo, MyEnum>;
+    method Foo::num(&self) -> Result<i64, Boo>;
+    method Foo::boos(&self) -> Result<Vec<Boo>, String>;
+    method Foo::check_boo(&self) -> Result<(), Boo>;
+    method Foo::bytes(&self) -> Result<Vec<u8>, Boo>;
+});
+"#,
+    )
+    .unwrap();
+    let lib_dir = tmp_dir.path().join("lib");
+    fs::create_dir(&lib_dir).unwrap();
+    Generator::new(LanguageConfig::DartConfig(DartConfig::new(
+        lib_dir,
+        "rust_lib".into(),
+    )))
+    .with_pointer_target_width(64)
+    .try_expand(
+        "dart_code_analyzes",
+        &src_path,
+        tmp_dir.path().join("out.rs"),
+    )
+    .unwrap();
+    fs::write(
+        tmp_dir.path().join("pubspec.yaml"),
+        "name: rust_lib\nenvironment:\n  sdk: '>=3.2.0 <4.0.0'\ndependencies:\n  ffi: any\n",
+    )
+    .unwrap();
+    let pub_get = Command::new("dart")
+        .args(["pub", "get", "--offline"])
+        .current_dir(tmp_dir.path())
+        .output()
+        .expect("Can not run dart pub get");
+    if !pub_get.status.success() {
+        println!("package:ffi is not in pub cache, skip analysis of Dart code");
+        return;
+    }
+    let out = Command::new("dart")
+        .args(["analyze", "--no-fatal-warnings"])
+        .current_dir(tmp_dir.path())
+        .output()
+        .expect("Can not run dart analyze");
+    println!("{}", String::from_utf8_lossy(&out.stdout));
+    assert!(out.status.success(), "analysis of Dart code failed");
+}
+
+#[test]
+fn test_go_classes_enums_and_interfaces() {
+    let _ = env_logger::try_init();
+
+    let name = "go_classes_enums_and_interfaces";
+    let src = r#"
+foreign_enum!(
+/// Enum doc
+enum MyEnum {
+    ITEM1 = MyEnum::Item1,
+    ITEM2 = MyEnum::Item2,
+});
+foreign_interface!(interface SomeObserver {
+    self_type SomeTrait;
+    onStateChanged = SomeTrait::on_state_changed(&self, _: i32, _: bool, _: &str);
+    getValue = SomeTrait::get_value(&self) -> f64;
+});
+foreigner_class!(class Boo {
+    self_type Boo;
+    constructor Boo::default() -> Boo;
+});
+foreigner_class!(
+/// Class doc
+class Foo {
+    self_type Foo;
+    constructor Foo::new(_: i32, _: &str) -> Foo;
+    constructor Foo::with_boo(_: Boo) -> Foo;
+    /// Method doc
+    method Foo::f(&self, _: i32, _: bool) -> bool;
+    method Foo::name(&self) -> &str;
+    method Foo::to_string(&self) -> String;
+    method Foo::set_enum(&mut self, _: MyEnum);
+    method Foo::check_boo(&self, _: &Boo) -> i64;
+    method Foo::may_fail(&self) -> Result<(), String>;
+    method Foo::make_boo(&self) -> Result<Boo, String>;
+    method Foo::subscribe(&mut self, _: Box<SomeTrait>);
+    static_method Foo::create_boo() -> Boo;
+});
+foreigner_class!(class Utils {
+    static_method f2(_: f64) -> u64;
+});
+"#;
+    let code = parse_code(name, Source::Str(src), ForeignLang::Go).unwrap();
+    println!("Go: {}", code.foreign_code);
+    for pat in &[
+        "package rustlib\n\n/*\n#cgo LDFLAGS: -lrust_lib\n#include \"rust_swig_c.h\"\n*/\nimport \"C\"\n",
+        "// Enum doc\ntype MyEnum uint32\n\nconst (\n\tMyEnum_ITEM1 MyEnum = 0\n\tMyEnum_ITEM2 MyEnum = 1\n)\n",
+        "/*\n#include <stdlib.h>\n#include \"c_Foo.h\"\n*/\nimport \"C\"\n\nimport (\n\t\"runtime\"\n\t\"unsafe\"\n)\n",
+        "// Class doc\ntype Foo struct {\n\tptr *C.FooOpaque\n",
+        "\t\truntime.SetFinalizer(obj, (*Foo).Close)\n",
+        "func NewFoo(a0 int32, a1 string) *Foo {\n\ta1C := C.CString(a1)\n\tdefer C.free(unsafe.Pointer(a1C))\n\treturn newFoo(C.Foo_new(C.int32_t(a0), a1C), true)\n}\n",
+        "func NewFooWithBoo(a0 *Boo) *Foo {\n\treturn newFoo(C.Foo_with_boo(a0.takeOwnership()), true)\n}\n",
+        "// Method doc\nfunc (obj *Foo) F(a0 int32, a1 bool) bool {\n\tdefer runtime.KeepAlive(obj)\n\tret := C.Foo_f(obj.handle(), C.int32_t(a0), swigBoolToC(a1))\n\treturn (ret != 0)\n}\n",
+        "\treturn swigStrViewToString(ret)\n",
+        "\treturn swigConsumeString(ret)\n",
+        "\tC.Foo_set_enum(obj.handle(), C.uint32_t(a0))\n",
+        "func (obj *Foo) CheckBoo(a0 *Boo) int64 {\n\tdefer runtime.KeepAlive(obj)\n\tdefer runtime.KeepAlive(a0)\n",
+        "func (obj *Foo) MayFail() error {\n\tdefer runtime.KeepAlive(obj)\n\tret := C.Foo_may_fail(obj.handle())\n\tif ret.is_ok == 0 {\n\t\treturn swigConsumeError(*(*C.struct_CRustString)(unsafe.Pointer(&ret.data)))\n\t}\n\treturn nil\n}\n",
+        "func (obj *Foo) MakeBoo() (*Boo, error) {\n",
+        "\tok := *(**C.BooOpaque)(unsafe.Pointer(&ret.data))\n\treturn newBoo(ok, true), nil\n",
+        "\ta0C := newC_SomeObserver(a0)\n\tC.Foo_subscribe(obj.handle(), &a0C)\n",
+        "func FooCreateBoo() *Boo {\n\tret := C.Foo_create_boo()\n\treturn newBoo(ret, true)\n}\n",
+        "func NewBooDefault() *Boo {\n",
+        "func UtilsF2(a0 float64) uint64 {\n\tret := C.Utils_f2(C.double(a0))\n\treturn uint64(ret)\n}\n",
+        "type SomeObserver interface {\n\tOnStateChanged(a0 int32, a1 bool, a2 string)\n\tGetValue() float64\n}\n",
+        "extern void goSomeObserverOnStateChanged(int32_t a0, char a1, struct RustStrView a2, void *opaque);\n",
+        "extern double goSomeObserverGetValue(void *opaque);\n",
+        "\t\tonStateChanged: (*[0]byte)(unsafe.Pointer(C.goSomeObserverOnStateChanged)),\n",
+        "//export goSomeObserverOnStateChanged\nfunc goSomeObserverOnStateChanged(a0 C.int32_t, a1 C.char, a2 C.struct_RustStrView, opaque unsafe.Pointer) {\n\tswigSomeObserverFromOpaque(opaque).OnStateChanged(int32(a0), (a1 != 0), swigStrViewToString(a2))\n}\n",
+        "func goSomeObserverGetValue(opaque unsafe.Pointer) C.double {\n\treturn C.double(swigSomeObserverFromOpaque(opaque).GetValue())\n}\n",
+        "struct C_SomeObserver {",
+    ] {
+        assert!(code.foreign_code.contains(pat), "no '{}' in Go code", pat);
+    }
+}
+
+#[test]
+fn test_go_options_vecs_results() {
+    let _ = env_logger::try_init();
+
+    let name = "go_options_vecs_results";
+    let src = r#"
+foreigner_class!(class Boo {
+    self_type Boo;
+    constructor Boo::default() -> Boo;
+});
+foreigner_class!(class Foo {
+    self_type Foo;
+    constructor Foo::default() -> Foo;
+    method Foo::opt_i32(&self, _: Option<i32>) -> Option<i32>;
+    method Foo::opt_str(&self, _: Option<&str>) -> Option<String>;
+    method Foo::opt_boo(&self, _: Option<Boo>) -> Option<&Boo>;
+    method Foo::vec_u8(&self) -> Vec<u8>;
+    method Foo::vec_boo(&self, _: Vec<Boo>) -> Vec<Boo>;
+    method Foo::slice_i32(&self, _: &[i32]) -> &[i32];
+    method Foo::slice_boo(&self) -> &[Boo];
+    method Foo::num(&self) -> Result<i32, String>;
+    method Foo::name(&self) -> Result<String, String>;
+    method Foo::nums(&self) -> Result<Vec<f64>, String>;
+    method Foo::maybe_num(&self) -> Result<Option<u64>, String>;
+    method Foo::boos(&self) -> Result<Vec<Boo>, String>;
+});
+"#;
+    let code = parse_code(name, Source::Str(src), ForeignLang::Go).unwrap();
+    println!("Go: {}", code.foreign_code);
+    for pat in &[
+        "func (obj *Foo) OptI32(a0 *int32) *int32 {\n\tdefer runtime.KeepAlive(obj)\n\tvar a0C C.struct_CRustOptionI32\n\tif a0 != nil {\n\t\ta0C = C.struct_CRustOptionI32{val: C.int32_t(*a0), is_some: 1}\n\t}\n",
+        "\treturn swigOption(ret.is_some != 0, int32(ret.val))\n",
+        "func (obj *Foo) OptStr(a0 *string) *string {\n\tdefer runtime.KeepAlive(obj)\n\tvar a0C *C.char\n\tif a0 != nil {\n\t\ta0C = C.CString(*a0)\n\t\tdefer C.free(unsafe.Pointer(a0C))\n\t}\n",
+        "\treturn swigConsumeOptionString(ret)\n",
+        "func (obj *Foo) OptBoo(a0 *Boo) *Boo {\n\tdefer runtime.KeepAlive(obj)\n\tvar a0C *C.BooOpaque\n\tif a0 != nil {\n\t\ta0C = a0.takeOwnership()\n\t}\n",
+        "\treturn newBoo(ret, false)\n",
+        "func newBoo(ptr *C.BooOpaque, owned bool) *Boo {\n\tif ptr == nil {\n\t\treturn nil\n\t}\n",
+        "\tretC := swigCopySlice[uint8](unsafe.Pointer(ret.data), ret.len)\n\tC.CRustVecU8_free(ret)\n\treturn retC\n",
+        "#include \"c_Foo.h\"\n#include \"RustForeignVecBoo.h\"\n*/\n",
+        "\tvar a0C C.struct_CRustForeignVec\n\tfor _, x := range a0 {\n\t\tC.RustForeignVecBoo_push(&a0C, x.takeOwnership())\n\t}\n",
+        "\t\tretC[i] = newBoo(C.RustForeignVecBoo_remove(&ret, C.uintptr_t(i)), true)\n",
+        "\t\ta0C.data = (*C.int32_t)(unsafe.Pointer(&a0[0]))\n",
+        "\treturn swigCopySlice[int32](unsafe.Pointer(ret.data), ret.len)\n",
+        "\t\tretC[i] = newBoo((*C.BooOpaque)(unsafe.Add(ret.data, uintptr(i)*uintptr(ret.step))), false)\n",
+        "func (obj *Foo) Num() (int32, error) {\n\tdefer runtime.KeepAlive(obj)\n\tret := C.Foo_num(obj.handle())\n\tif ret.is_ok == 0 {\n\t\treturn 0, swigConsumeError(ret.err)\n\t}\n\tok := ret.ok\n\treturn int32(ok), nil\n}\n",
+        "\t\treturn \"\", swigConsumeError(ret.err)\n\t}\n\tok := ret.ok\n\treturn swigConsumeString(ok), nil\n",
+        "\tokC := swigCopySlice[float64](unsafe.Pointer(ok.data), ok.len)\n\tC.CRustVecF64_free(ok)\n\treturn okC, nil\n",
+        "\treturn swigOption(ok.is_some != 0, uint64(ok.val)), nil\n",
+        "func (obj *Foo) Boos() ([]*Boo, error) {\n",
+        "struct CResulti32String {\n        uint8_t is_ok;\n        int32_t ok;\n        struct CRustString err;\n    };\n",
+        "struct CResultCRustVecF64String {\n",
+    ] {
+        assert!(code.foreign_code.contains(pat), "no '{}' in Go code", pat);
+    }
+
+    for (src, msg) in &[
+        (
+            "method Boo::f(&self, _: &[Boo]);",
+            "Go objects are not placed in one continuous memory block, use Vec<Boo> instead",
+        ),
+        (
+            "method Boo::f(&self, _: Option<String>);",
+            "Go: Option < String > as input is not supported yet, use Option<&str> instead",
+        ),
+        (
+            "method Boo::f(&self) -> Result<MyEnum, String>;",
+            "enum can not be used as ok type of Result",
+        ),
+    ] {
+        let tmp_dir = tempdir().expect("Can not create tmp directory");
+        let src_path = tmp_dir.path().join("src.rs");
+        fs::write(
+            &src_path,
+            format!(
+                r#"
+foreign_enum!(enum MyEnum {{ ITEM1 = MyEnum::Item1, }});
+foreigner_class!(class Boo {{
+    self_type Boo;
+    constructor Boo::default() -> Boo;
+    {}
+}});
+"#,
+                src
+            ),
+        )
+        .unwrap();
+        let err = Generator::new(LanguageConfig::GoConfig(GoConfig::new(
+            tmp_dir.path().into(),
+            "rustlib".into(),
+            "rust_lib".into(),
+        )))
+        .with_pointer_target_width(64)
+        .try_expand(name, &src_path, src_path.with_file_name("out.rs"))
+        .unwrap_err();
+        let message = err.diagnostics()[0].message();
+        assert!(message.contains(msg), "unexpected error: {}", message);
+    }
+}
+
+#[test]
+fn test_async_methods() {
+    let _ = env_logger::try_init();
+
+    let name = "async_methods";
+    let src = r#"
+foreigner_class!(class Boo {
+    self_type Boo;
+    constructor Boo::default() -> Boo;
+});
+foreigner_class!(class Foo {
+    self_type Foo;
+    constructor Foo::default() -> Foo;
+    method Foo::fetch(&self, _: i32) -> impl Future<Output = i32> + Send;
+    method Foo::ping(&self) -> impl Future<Output = ()> + Send;
+    method Foo::find(&self) -> Pin<Box<dyn Future<Output = Option<i64>> + Send>>;
+    static_method Foo::make_boo() -> impl Future<Output = Boo> + Send;
+});
+"#;
+    let code = parse_code(name, Source::Str(src), ForeignLang::Java).unwrap();
+    println!("Java: {}", code.foreign_code);
+    for pat in &[
+        "public final java.util.concurrent.CompletableFuture<Integer> fetch(int a0)",
+        "public final java.util.concurrent.CompletableFuture<Void> ping()",
+        "private static native java.util.concurrent.CompletableFuture<java.util.OptionalLong> do_find(long me) ;",
+        "public static native java.util.concurrent.CompletableFuture<Boo> make_boo() ;",
+    ] {
+        assert!(code.foreign_code.contains(pat), "no '{}' in Java code", pat);
+    }
+    assert!(code.rust_code.contains(
+        "swig_future_to_completable_future ( env , ret , move | env : * mut JNIEnv , ret : i32 | -> Result < jobject , String > {"
+    ));
+
+    let code = parse_code(name, Source::Str(src), ForeignLang::Cpp).unwrap();
+    println!("C++: {}", code.foreign_code);
+    for pat in &[
+        "void Foo_fetch(const FooOpaque * const self, int32_t a_0, void *opaque, void (*on_ready)(void *opaque, int32_t));",
+        "void Foo_make_boo(void *opaque, void (*on_ready)(void *opaque, BooOpaque *));",
+        "//for std::future\n#include <future>\n",
+        "std::future<int32_t> fetch(int32_t a_0) const  noexcept;",
+        "std::future<void> ping() const  noexcept;",
+        "static std::future<Boo> make_boo() noexcept;",
+        "            swig_promise->set_value(Boo(ret));\n            delete swig_promise;\n",
+    ] {
+        assert!(code.foreign_code.contains(pat), "no '{}' in C++ code", pat);
+    }
+    assert!(code.rust_code.contains(
+        "let swig_callback = SwigFutureCallback :: new ( swig_opaque , swig_on_ready ) ; let mut ret : ( ) = swig_spawn_future ( swig_future_then ( ret , move | ret : i32 |"
+    ));
+}
+
+#[test]
+fn test_maps() {
+    let _ = env_logger::try_init();
+
+    let name = "maps";
+    let src = r#"
+foreigner_class!(class Boo {
+    self_type Boo;
+    constructor Boo::default() -> Boo;
+});
+foreigner_class!(class Foo {
+    self_type Foo;
+    constructor Foo::default() -> Foo;
+    method Foo::ages(&self) -> HashMap<String, i32>;
+    method Foo::sorted(&self) -> BTreeMap<i64, String>;
+    method Foo::boos(&self) -> HashMap<String, Boo>;
+    method Foo::sum(&self, _: &BTreeMap<String, f64>) -> f64;
+});
+"#;
+    let code = parse_code(name, Source::Str(src), ForeignLang::Java).unwrap();
+    println!("Java: {}", code.foreign_code);
+    for pat in &[
+        "public final java.util.Map<String, Integer> ages()",
+        "private static native java.util.Map<Long, String> do_sorted(long me) ;",
+        "private static native java.util.Map<String, Boo> do_boos(long me) ;",
+        "private static native double do_sum(long me, java.util.Map<String, Double> a0) ;",
+    ] {
+        assert!(code.foreign_code.contains(pat), "no '{}' in Java code", pat);
+    }
+    assert!(code
+        .rust_code
+        .contains("SwigJavaMapBuilder :: new ( env , swig_c_str ! ( \"java/util/TreeMap\" ) )"));
+    assert!(code
+        .rust_code
+        .contains("swig_java_map_entries ( env , a_0 )"));
+
+    let code = parse_code(name, Source::Str(src), ForeignLang::Kotlin).unwrap();
+    println!("Kotlin: {}", code.foreign_code);
+    for pat in &[
+        "fun ages(): Map<String, Int>",
+        "fun sum(a0: Map<String, Double>): Double",
+    ] {
+        assert!(
+            code.foreign_code.contains(pat),
+            "no '{}' in Kotlin code",
+            pat
+        );
+    }
+
+    let code = parse_code(name, Source::Str(src), ForeignLang::Cpp).unwrap();
+    println!("C++: {}", code.foreign_code);
+    for pat in &[
+        "struct CRustMap Foo_ages(const FooOpaque * const self);",
+        "RustHashMapStringI32 ages() const  noexcept;",
+        "RustBTreeMapI64String sorted() const  noexcept;",
+        "double sum(RustBTreeMapStringF64 a_0) const  noexcept;",
+        "char RustHashMapStringBoo_pop(struct CRustMap *map, struct CRustString *key, BooOpaque * *value);",
+        "using std_map_type = std::map<key_type, mapped_type>;",
+        "using RustHashMapStringBoo = RustMap<RustHashMapStringBooTraits>;",
+    ] {
+        assert!(code.foreign_code.contains(pat), "no '{}' in C++ code", pat);
+    }
+    assert!(code
+        .rust_code
+        .contains("let mut ret : CRustMap = CRustMap :: from_map ( ret ) ;"));
+    assert!(code
+        .rust_code
+        .contains("let mut a_0 : BTreeMap < String , f64 > = a_0 . into_map ( ) ;"));
+}
+
+#[test]
+fn test_tuples() {
+    let _ = env_logger::try_init();
+
+    let name = "tuples";
+    let src = r#"
+foreigner_class!(class Boo {
+    self_type Boo;
+    constructor Boo::default() -> Boo;
+});
+foreigner_class!(class Foo {
+    self_type Foo;
+    constructor Foo::default() -> Foo;
+    method Foo::pair(&self) -> (i32, String);
+    method Foo::triple(&self) -> (f64, Vec<i32>, Boo);
+    method Foo::sum(&self, _: (i32, String)) -> i32;
+});
+"#;
+    let code = parse_code(name, Source::Str(src), ForeignLang::Java).unwrap();
+    println!("Java: {}", code.foreign_code);
+    for pat in &[
+        "public final Pair_Integer_String pair()",
+        "private static native Triple_Double_IntArray_Boo do_triple(long me) ;",
+        "private static native int do_sum(long me, Pair_Integer_String a0) ;",
+        "public final class Pair_Integer_String {",
+        "    public Pair_Integer_String(int first, String second) {",
+        "    public final int getFirst() { return first; }",
+        "        return java.util.Objects.deepEquals(first, other.first)\n            && java.util.Objects.deepEquals(second, other.second);",
+        "        return java.util.Arrays.deepHashCode(new Object[] { first, second });",
+        "        String elements = java.util.Arrays.deepToString(new Object[] { first, second, third });",
+        "    private static Pair_Integer_String swigFromRust(Object first, Object second) {\n        return new Pair_Integer_String((Integer) first, (String) second);",
+    ] {
+        assert!(code.foreign_code.contains(pat), "no '{}' in Java code", pat);
+    }
+    assert!(code.rust_code.contains(
+        "swig_tuple_to_jobject ( env , swig_c_str ! ( \"org/example/Pair_Integer_String\" ) , swig_c_str ! ( \"(Ljava/lang/Object;Ljava/lang/Object;)Lorg/example/Pair_Integer_String;\" ) , & [ first , second ] )"
+    ));
+    assert!(code.rust_code.contains(
+        "let first : jobject = swig_tuple_element ( env , a_0 , swig_c_str ! ( \"swigFirst\" ) ) ;"
+    ));
+
+    let code = parse_code(name, Source::Str(src), ForeignLang::Kotlin).unwrap();
+    println!("Kotlin: {}", code.foreign_code);
+    for pat in &[
+        "fun pair(): Pair_Integer_String",
+        "data class Pair_Integer_String(val first: Int, val second: String) {",
+        "data class Triple_Double_IntArray_Boo(val first: Double, val second: IntArray, val third: Boo) {",
+        "Pair_Integer_String(first as Int, second as String)",
+    ] {
+        assert!(code.foreign_code.contains(pat), "no '{}' in Kotlin code", pat);
+    }
+}
+
+#[test]
+fn test_foreign_struct() {
+    let _ = env_logger::try_init();
+
+    let name = "foreign_struct";
+    let src = r#"
+foreign_enum!(enum Color {
+    RED = Color::Red,
+    GREEN = Color::Green,
+});
+foreign_struct!(
+    /// 2D vector
+    struct Vec2 {
+        /// X coordinate
+        x: f64,
+        y: f64,
+    }
+);
+foreign_struct!(struct Marker {
+    pos: Vec2,
+    color: Color,
+    id: i32,
+});
+foreigner_class!(class Point {
+    self_type Point;
+    constructor Point::default() -> Point;
+});
+foreign_struct!(struct Path {
+    points: Vec<Point>,
+});
+foreigner_class!(class Foo {
+    self_type Foo;
+    constructor Foo::default() -> Foo;
+    method Foo::marker(&self) -> Marker;
+    method Foo::path(&self) -> Path;
+    method Foo::shift(&self, m: Marker, d: Vec2) -> Marker;
+});
+"#;
+    let code = parse_code(name, Source::Str(src), ForeignLang::Java).unwrap();
+    println!("Java: {}", code.foreign_code);
+    for pat in &[
+        "private static native Marker do_shift(long me, Marker a0, Vec2 a1) ;",
+        "public final class Vec2 {",
+        "    /**\n     * X coordinate\n     */\n    public final double x;",
+        "    public Marker(Vec2 pos, Color color, int id) {",
+        "        return \"Vec2{x=\" + x + \", y=\" + y + \"}\";",
+        "        return \"Path{points=\" + java.util.Arrays.deepToString(points) + \"}\";",
+        "    private static Marker swigFromRust(Object pos, Object color, Object id) {\n        return new Marker((Vec2) pos, (Color) color, (Integer) id);",
+        "    private Object swigColor() { return color; }",
+    ] {
+        assert!(code.foreign_code.contains(pat), "no '{}' in Java code", pat);
+    }
+    assert!(code
+        .rust_code
+        .contains("let Marker { pos : f_pos , color : f_color , id : f_id } = ret ;"));
+    assert!(code.rust_code.contains(
+        "let f_color : jobject = swig_tuple_element ( env , a_0 , swig_c_str ! ( \"swigColor\" ) ) ;"
+    ));
+
+    let code = parse_code(name, Source::Str(src), ForeignLang::Kotlin).unwrap();
+    println!("Kotlin: {}", code.foreign_code);
+    for pat in &[
+        "fun shift(a0: Marker, a1: Vec2): Marker",
+        "data class Vec2(\n    /**\n     * X coordinate\n     */\n    val x: Double,\n    val y: Double\n) {",
+        "Marker(pos as Vec2, color as Color, id as Int)",
+    ] {
+        assert!(code.foreign_code.contains(pat), "no '{}' in Kotlin code", pat);
+    }
+
+    let code = parse_code(name, Source::Str(src), ForeignLang::Cpp).unwrap();
+    println!("C++: {}", code.foreign_code);
+    for pat in &[
+        "struct C_Marker Foo_shift(const FooOpaque * const self, struct C_Marker a_0, struct C_Vec2 a_1);",
+        "struct C_Marker {\n    struct C_Vec2 pos;\n    uint32_t color;\n    int32_t id;\n};",
+        "#include \"Marker.hpp\"",
+        "Marker shift(Marker a_0, Vec2 a_1) const  noexcept;",
+        "return Marker{ Vec2::from_c(c.pos), static_cast<Color>(c.color), c.id };",
+        "ret.color = static_cast<uint32_t>(color);",
+    ] {
+        assert!(code.foreign_code.contains(pat), "no '{}' in C++ code", pat);
+    }
+    assert!(code
+        .rust_code
+        .contains("pub struct C_Marker { pub pos : C_Vec2 , pub color : u32 , pub id : i32 , }"));
+
+    let ret = panic::catch_unwind(|| {
+        parse_code(
+            name,
+            Source::Str(
+                r#"
+foreign_struct!(struct Named {
+    name: String,
+});
+"#,
+            ),
+            ForeignLang::Cpp,
+        )
+        .expect(name)
+    });
+    assert!(ret.is_err());
+}
+
+#[test]
+fn test_foreign_enum_with_data() {
+    let _ = env_logger::try_init();
+
+    let name = "foreign_enum_with_data";
+    let src = r#"
+foreign_enum!(enum Color {
+    RED = Color::Red = 3,
+    GREEN = Color::Green = 10,
+});
+foreign_struct!(struct Vec2 { x: f64, y: f64 });
+foreign_enum!(
+    /// Connection event
+    enum Event {
+        Connected(i64) = Event::Connected = 1,
+        Closed { code: u16, reason: Color } = Event::Closed,
+        Moved(Vec2, i32) = Event::Moved,
+        Disconnected = Event::Disconnected = 7,
+    }
+);
+foreigner_class!(class Foo {
+    self_type Foo;
+    constructor Foo::default() -> Foo;
+    method Foo::event(&self, i: i32) -> Event;
+    method Foo::handle(&self, e: Event, c: Color);
+});
+"#;
+    let code = parse_code(name, Source::Str(src), ForeignLang::Java).unwrap();
+    println!("Java: {}", code.foreign_code);
+    for pat in &[
+        "GREEN(10);",
+        "public abstract class Event {",
+        "    public static final class Closed extends Event {",
+        "        public Closed(int code, Color reason) {",
+        "        int swigTag() { return 2; }",
+        "        int swigTag() { return 7; }",
+        "private static native Event do_event(long me, int a0) ;",
+    ] {
+        assert!(code.foreign_code.contains(pat), "no '{}' in Java code", pat);
+    }
+    assert!(code.rust_code.contains("\"org/example/Event$Moved\""));
+
+    let code = parse_code(name, Source::Str(src), ForeignLang::Kotlin).unwrap();
+    println!("Kotlin: {}", code.foreign_code);
+    for pat in &[
+        "sealed class Event {",
+        "    data class Moved(\n        val _0: Vec2,\n        val _1: Int\n    ) : Event() {",
+        "    object Disconnected : Event() {\n        override fun swigTag(): Int = 7",
+    ] {
+        assert!(
+            code.foreign_code.contains(pat),
+            "no '{}' in Kotlin code",
+            pat
+        );
+    }
+
+    let code = parse_code(name, Source::Str(src), ForeignLang::Cpp).unwrap();
+    println!("C++: {}", code.foreign_code);
+    for pat in &[
+        "GREEN = 10",
+        "struct C_Event Foo_event(const FooOpaque * const self, int32_t a_0);",
+        "    C_EventTag_Closed = 2,",
+        "struct C_Event_Moved {\n    struct C_Vec2 _0;\n    int32_t _1;\n};",
+        "struct C_Event {\n    uint32_t tag;\n    struct C_Event_Connected Connected;",
+        "using variant_type = std::variant<Connected, Closed, Moved, Disconnected>;",
+        "return Event{Closed{c.Closed.code, static_cast<Color>(c.Closed.reason)}};",
+        "#include \"Event.hpp\"",
+    ] {
+        assert!(code.foreign_code.contains(pat), "no '{}' in C++ code", pat);
+    }
+    assert!(code.rust_code.contains("pub struct C_Event {"));
+
+    let ret = panic::catch_unwind(|| {
+        parse_code(name, Source::Str(src), ForeignLang::Python).expect(name)
+    });
+    assert!(ret.is_err());
+}
+
+#[test]
+fn test_foreign_flags() {
+    let _ = env_logger::try_init();
+
+    let name = "foreign_flags";
+    let src = r#"
+foreign_flags!(
+    /// Window style
+    struct Style {
+        BORDER = Style::BORDER,
+        TITLE = Style::TITLE,
+        RESIZE = Style::RESIZE = 16,
+    }
+);
+foreigner_class!(class Win {
+    self_type Win;
+    constructor Win::new(s: Style) -> Win;
+    method Win::style(&self) -> Style;
+    method Win::set_style(&mut self, s: Style);
+});
+"#;
+    let code = parse_code(name, Source::Str(src), ForeignLang::Java).unwrap();
+    println!("Java: {}", code.foreign_code);
+    for pat in &[
+        "public final class Style {",
+        "    public static final int TITLE = 0x2;",
+        "    public static final int RESIZE = 0x10;",
+        "    public static int checkBits(int bits) {",
+        "int a0C0 = Style.checkBits(a0);",
+        "private static native int do_style(long me) ;",
+    ] {
+        assert!(code.foreign_code.contains(pat), "no '{}' in Java code", pat);
+    }
+    assert!(code
+        .rust_code
+        .contains("java/lang/IllegalArgumentException"));
+
+    let code = parse_code(name, Source::Str(src), ForeignLang::Kotlin).unwrap();
+    println!("Kotlin: {}", code.foreign_code);
+    for pat in &[
+        "object Style {",
+        "    const val RESIZE: Int = 0x10",
+        "    enum class Flag(val bit: Int) {",
+    ] {
+        assert!(
+            code.foreign_code.contains(pat),
+            "no '{}' in Kotlin code",
+            pat
+        );
+    }
+
+    let code = parse_code(name, Source::Str(src), ForeignLang::Cpp).unwrap();
+    println!("C++: {}", code.foreign_code);
+    for pat in &[
+        "enum class Style : uint32_t {",
+        "    RESIZE = 0x10",
+        "inline constexpr Style operator|(Style a, Style b) noexcept",
+        "uint32_t Win_style(const WinOpaque * const self);",
+        "return static_cast<Style>(ret);",
+        "#include \"Style.hpp\"",
+        "uint32_t a_0, char *swig_invalid_arg);",
+        "throw std::invalid_argument(\"unknown value of Style passed to Win::set_style\");",
+    ] {
+        assert!(code.foreign_code.contains(pat), "no '{}' in C++ code", pat);
+    }
+    assert!(code
+        .rust_code
+        .contains("& ! 0x13 ) != 0 { unsafe { * swig_invalid_arg = 1 }"));
+
+    let ret = panic::catch_unwind(|| {
+        parse_code(name, Source::Str(src), ForeignLang::Python).expect(name)
+    });
+    assert!(ret.is_err());
+}
+
+#[test]
+fn test_foreign_enum_unknown_value() {
+    let _ = env_logger::try_init();
+
+    let name = "foreign_enum_unknown_value";
+    let src = r#"
+foreign_enum!(
+    #[unknown_value = "error"]
+    enum Color {
+        RED = Color::Red,
+        GREEN = Color::Green,
+    }
+);
+foreign_enum!(
+    #[unknown_value = "fallback(OTHER)"]
+    enum Shape {
+        CIRCLE = Shape::Circle,
+        OTHER = Shape::Other = 10,
+    }
+);
+foreigner_class!(class Foo {
+    self_type Foo;
+    constructor Foo::default() -> Foo;
+    method Foo::color(&self, c: Color);
+    method Foo::shape(&self, s: Shape) -> Shape;
+    static_method Foo::mix(_: Option<Color>, _: i32) -> i32;
+});
+"#;
+    let code = parse_code(name, Source::Str(src), ForeignLang::Java).unwrap();
+    for pat in &[
+        "swig_c_str ! ( \"java/lang/IllegalArgumentException\" )",
+        "_ => Shape :: Other ,",
+    ] {
+        assert!(code.rust_code.contains(pat), "no '{}' in Rust code", pat);
+    }
+
+    let code = parse_code(name, Source::Str(src), ForeignLang::Cpp).unwrap();
+    println!("C++: {}", code.foreign_code);
+    for pat in &[
+        "void Foo_color(const FooOpaque * const self, uint32_t a_0, char *swig_invalid_arg);",
+        "uint32_t Foo_shape(const FooOpaque * const self, uint32_t a_0);",
+        "int32_t Foo_mix(struct CRustOptionU32 a_0, int32_t a_1, char *swig_invalid_arg);",
+        "//for std::invalid_argument\n#include <stdexcept>\n",
+        "void color(Color a_0) const;",
+        "Shape shape(Shape a_0) const  noexcept;",
+        "static int32_t mix(std::optional<Color> a_0, int32_t a_1);",
+        r#"        char swig_invalid_arg = 0;
+        Foo_color(this->self_, static_cast<uint32_t>(a_0), &swig_invalid_arg);
+        if (swig_invalid_arg != 0) {
+            throw std::invalid_argument("unknown value of Color passed to Foo::color");
+        }
+"#,
+    ] {
+        assert!(code.foreign_code.contains(pat), "no '{}' in C++ code", pat);
+    }
+    for pat in &[
+        "_ => Shape :: Other ,",
+        "pub extern \"C\" fn Foo_color ( this : * mut Foo , a_0 : u32 , swig_invalid_arg : * mut :: std :: os :: raw :: c_char , ) -> ( ) { match a_0 { 0 | 1 => ( ) , _ => { unsafe { * swig_invalid_arg = 1 } ; return unsafe { :: std :: mem :: zeroed ( ) } ; } }",
+        "if a_0 . is_some != 0 { match a_0 . val { 0 | 1 => ( ) ,",
+    ] {
+        assert!(code.rust_code.contains(pat), "no '{}' in Rust code", pat);
+    }
+}
+
+#[test]
+fn test_foreign_enum_unknown_value_c_api() {
+    let _ = env_logger::try_init();
+
+    let name = "foreign_enum_unknown_value_c_api";
+    let src = r#"
+foreign_enum!(
+    #[unknown_value = "error"]
+    enum Color {
+        RED = Color::Red,
+        GREEN = Color::Green,
+    }
+);
+foreigner_class!(class Foo {
+    self_type Foo;
+    constructor Foo::new(_: Color) -> Foo;
+    method Foo::set_color(&mut self, _: Color);
+    static_method Foo::mix(_: Color, _: Color) -> i32;
+});
+"#;
+    let code = parse_code(name, Source::Str(src), ForeignLang::Go).unwrap();
+    println!("Go: {}", code.foreign_code);
+    for pat in &[
+        "void Foo_set_color(FooOpaque * const self, uint32_t a_0, char *swig_invalid_arg);",
+        "func NewFoo(a0 Color) *Foo {\n\tvar swigInvalidArg C.char\n\tret := C.Foo_new(C.uint32_t(a0), &swigInvalidArg)\n\tif swigInvalidArg != 0 {\n\t\tpanic(\"unknown value of Color passed to NewFoo\")\n\t}\n\treturn newFoo(ret, true)\n}\n",
+        "\tC.Foo_set_color(obj.handle(), C.uint32_t(a0), &swigInvalidArg)\n\tif swigInvalidArg != 0 {\n\t\tpanic(\"unknown value of Color passed to Foo.SetColor\")\n\t}\n}\n",
+    ] {
+        assert!(code.foreign_code.contains(pat), "no '{}' in Go code", pat);
+    }
+
+    let code = parse_code(name, Source::Str(src), ForeignLang::CSharp).unwrap();
+    println!("C#: {}", code.foreign_code);
+    for pat in &[
+        "private static extern int Foo_mix(Color a0, Color a1, ref byte swig_invalid_arg);",
+        "            byte swigInvalidArg = 0;\n            int ret = Foo_mix(a0, a1, ref swigInvalidArg);\n            if (swigInvalidArg != 0)\n            {\n                throw new ArgumentException(\"unknown value of Color passed to Foo.mix\");\n            }\n            return ret;\n",
+        "            IntPtr ret = Foo_new(a0, ref swigInvalidArg);\n",
+    ] {
+        assert!(code.foreign_code.contains(pat), "no '{}' in C# code", pat);
+    }
+
+    let code = parse_code(name, Source::Str(src), ForeignLang::Dart).unwrap();
+    println!("Dart: {}", code.foreign_code);
+    for pat in &[
+        "    Void Function(Pointer<Void>, Uint32, Pointer<Int8>),\n",
+        "      final swigInvalidArg = arena<Int8>();\n      _Foo_set_color(_handle, a0.value, swigInvalidArg);\n      if (swigInvalidArg.value != 0) {\n        throw ArgumentError('unknown value of Color passed to Foo.set_color');\n      }\n",
+    ] {
+        assert!(code.foreign_code.contains(pat), "no '{}' in Dart code", pat);
+    }
+}
+
+#[test]
+fn test_try_expand_errors() {
+    let _ = env_logger::try_init();
+
+    let tmp_dir = tempdir().expect("Can not create tmp directory");
+    let src = r#"
+foreigner_class!(class Foo {
+    self_type Foo;
+    constructor Foo::default() -> Foo;
+    method Foo::f(&self, _: UnknownType1);
+});
+foreigner_class!(class Boo {
+    self_type Boo;
+    constructor Boo::default() -> Boo;
+    method Boo::f(&self, _: UnknownType2);
+});
+"#;
+    let rust_src_path = tmp_dir.path().join("src.rs");
+    fs::write(&rust_src_path, src).unwrap();
+    let rust_code_path = tmp_dir.path().join("test.rs");
+    let swig_gen = Generator::new(LanguageConfig::JavaConfig(JavaConfig::new(
+        tmp_dir.path().into(),
+        "org.example".into(),
+    )))
+    .with_pointer_target_width(64);
+    let err = swig_gen
+        .try_expand("try_expand", &rust_src_path, &rust_code_path)
+        .expect_err("should fail");
+    println!("{}", err);
+    let diagnostics = err.diagnostics();
+    assert_eq!(2, diagnostics.len());
+    assert!(diagnostics[0].message().contains("UnknownType1"));
+    assert!(diagnostics[1].message().contains("UnknownType2"));
+    let loc = diagnostics[1].location().expect("no location");
+    assert!(loc.file_id().starts_with("try_expand: "));
+    assert_eq!((10, 28), loc.start());
+    assert_eq!((10, 40), loc.end());
+    assert!(!rust_code_path.exists());
+
+    let swig_gen = Generator::new(LanguageConfig::JavaConfig(JavaConfig::new(
+        tmp_dir.path().into(),
+        "org.example".into(),
+    )))
+    .with_pointer_target_width(64);
+    let err = swig_gen
+        .try_expand(
+            "try_expand",
+            tmp_dir.path().join("no_such_file.rs"),
+            &rust_code_path,
+        )
+        .expect_err("should fail");
+    assert_eq!(1, err.diagnostics().len());
+    assert!(err.diagnostics()[0].location().is_none());
+
+    let swig_gen = Generator::new(LanguageConfig::JavaConfig(JavaConfig::new(
+        tmp_dir.path().into(),
+        "org.example".into(),
+    )))
+    .with_pointer_target_width(0);
+    let err = swig_gen
+        .try_expand("try_expand", &rust_src_path, &rust_code_path)
+        .expect_err("should fail without pointer width");
+    assert_eq!(1, err.diagnostics().len());
+    assert!(err.diagnostics()[0]
+        .message()
+        .contains("pointer target width unknown"));
+    assert!(!rust_code_path.exists());
+}
+
+#[test]
+fn test_missing_foreign_type_notes() {
+    let _ = env_logger::try_init();
+
+    let name = "missing_foreign_type_notes";
+    let src = r#"
+foreigner_class!(class Foo {
+    self_type Foo;
+    constructor Foo::default() -> Foo;
+    method Foo::name(&self) -> Strng;
+});
+"#;
+    for lang in &[ForeignLang::Java, ForeignLang::Cpp] {
+        let err = match parse_code(name, Source::Str(src), *lang) {
+            Ok(_) => panic!("{:?}: should fail", lang),
+            Err(err) => err.to_string(),
+        };
+        println!("{:?}: {}", lang, err);
+        assert!(err.contains("did you mean 'String'? there is foreign type for such type"));
+        assert!(!err.contains("was tried"));
+    }
+
+    let src = r#"
+foreigner_class!(class Foo {
+    self_type Foo;
+    constructor Foo::default() -> Foo;
+    method Foo::items(&self) -> Vec<Item>;
+});
+"#;
+    let err = match parse_code(name, Source::Str(src), ForeignLang::Java) {
+        Ok(_) => panic!("should fail"),
+        Err(err) => err.to_string(),
+    };
+    println!("Java: {}", err);
+    assert!(err.contains(
+        "note: generic rule `Vec < T > -> jobjectArray` would apply if 'Item' implemented `SwigForeignClass`"
+    ));
+}
+
+#[test]
+fn test_try_expand_warnings() {
+    let _ = env_logger::try_init();
+
+    let tmp_dir = tempdir().expect("Can not create tmp directory");
+    let src = r#"
+enum Color {
+    Red,
+    Green,
+    Blue,
+}
+foreign_enum!(
+    enum Color {
+        RED = Color::Red,
+        GREEN = Color::Green,
+    }
+);
+"#;
+    let rust_src_path = tmp_dir.path().join("src.rs");
+    fs::write(&rust_src_path, src).unwrap();
+    let rust_code_path = tmp_dir.path().join("test.rs");
+    let swig_gen = Generator::new(LanguageConfig::CppConfig(CppConfig::new(
+        tmp_dir.path().into(),
+        "ns".into(),
+    )))
+    .with_pointer_target_width(64);
+    let warnings = swig_gen
+        .try_expand("try_expand", &rust_src_path, &rust_code_path)
+        .expect("warnings should not stop code generation");
+    assert_eq!(1, warnings.len());
+    assert!(warnings[0].message().contains("does not cover Color::Blue"));
+    let loc = warnings[0].location().expect("no location");
+    assert_eq!(8, loc.start().0);
+    assert!(rust_code_path.exists());
+}
+
+#[test]
+fn test_lints() {
+    let _ = env_logger::try_init();
+
+    let tmp_dir = tempdir().expect("Can not create tmp directory");
+    let src = r#"
+enum Color {
+    Red,
+    Green,
+    Blue,
+}
+foreign_enum!(
+    enum MyColor {
+        RED = Color::Red,
+        GREEN = Color::Green,
+    }
+);
+foreigner_class!(class Foo {
+    self_type Foo;
+    constructor Foo::default() -> Foo;
+    private method Foo::f(&self);
+    method Foo::g(&self); alias g;
+    method Foo::name(&self) -> &str;
+});
+"#;
+    let type_map = r#"
+foreign_typemap!(
+    ($p:r_type) &str => jstring {
+        $out = from_std_string_jstring($p.to_string(), env);
+    };
+    ($p:f_type, nullable) => "String";
+);
+"#;
+    let rust_src_path = tmp_dir.path().join("src.rs");
+    fs::write(&rust_src_path, src).unwrap();
+    let rust_code_path = tmp_dir.path().join("test.rs");
+    let new_gen = |java_cfg: JavaConfig| {
+        Generator::new(LanguageConfig::JavaConfig(java_cfg))
+            .with_pointer_target_width(64)
+            .merge_type_map("my_typemap", type_map)
+    };
+    let java_cfg = || JavaConfig::new(tmp_dir.path().into(), "org.example".into());
+
+    let warnings = new_gen(java_cfg())
+        .try_expand("lints", &rust_src_path, &rust_code_path)
+        .expect("warnings should not stop code generation");
+    for w in &warnings {
+        println!("{}", w);
+    }
+    let messages: Vec<&str> = warnings.iter().map(|x| x.message()).collect();
+    assert_eq!(4, messages.len());
+    assert!(messages[0].contains("& str -> jstring"));
+    assert!(messages[1].contains("does not cover Color::Blue"));
+    assert!(messages[2].contains("private method `f`"));
+    assert!(messages[3].contains("alias `g`"));
+    assert!(warnings[3].notes()[0]
+        .message()
+        .contains("lint `redundant_alias` is on by default"));
+
+    let warnings = new_gen(
+        java_cfg()
+            .allow_lint(Lint::ShadowedTypemapRule)
+            .allow_lint(Lint::UnreachablePrivateMethod),
+    )
+    .try_expand("lints", &rust_src_path, &rust_code_path)
+    .unwrap();
+    assert_eq!(2, warnings.len());
+
+    let warnings = new_gen(java_cfg().warn_lint(Lint::RedundantAlias))
+        .try_expand("lints", &rust_src_path, &rust_code_path)
+        .unwrap();
+    assert_eq!(4, warnings.len());
+    assert!(warnings[3].notes()[0]
+        .message()
+        .contains("lint `redundant_alias` is set to warn by configuration"));
+
+    fs::remove_file(&rust_code_path).unwrap();
+    let err = new_gen(java_cfg().deny_lint(Lint::IncompleteForeignEnum))
+        .try_expand("lints", &rust_src_path, &rust_code_path)
+        .expect_err("denied lint should be error");
+    assert_eq!(1, err.diagnostics().len());
+    assert!(err.diagnostics()[0].message().contains("Color::Blue"));
+    assert!(!rust_code_path.exists());
+
+    let c_src = r#"
+foreigner_class!(class Foo {
+    self_type Foo;
+    constructor Foo::default() -> Foo;
+    method Foo::g(&self); alias g;
+});
+"#;
+    fs::write(&rust_src_path, c_src).unwrap();
+    let new_c_gen = |c_cfg: CConfig| {
+        Generator::new(LanguageConfig::CConfig(c_cfg)).with_pointer_target_width(64)
+    };
+    let c_cfg = || CConfig::new(tmp_dir.path().into());
+    let warnings = new_c_gen(c_cfg())
+        .try_expand("lints", &rust_src_path, &rust_code_path)
+        .unwrap();
+    assert_eq!(1, warnings.len());
+    let warnings = new_c_gen(c_cfg().allow_lint(Lint::RedundantAlias))
+        .try_expand("lints", &rust_src_path, &rust_code_path)
+        .unwrap();
+    assert!(warnings.is_empty());
+    let err = new_c_gen(c_cfg().deny_lint(Lint::RedundantAlias))
+        .try_expand("lints", &rust_src_path, &rust_code_path)
+        .expect_err("denied lint should be error");
+    assert!(err.diagnostics()[0].message().contains("alias `g`"));
+
+    let new_python_gen = |python_cfg: PythonConfig| {
+        Generator::new(LanguageConfig::PythonConfig(python_cfg)).with_pointer_target_width(64)
+    };
+    let python_cfg = || PythonConfig::new(tmp_dir.path().into(), "lints".into());
+    let warnings = new_python_gen(python_cfg())
+        .try_expand("lints", &rust_src_path, &rust_code_path)
+        .unwrap();
+    assert_eq!(1, warnings.len());
+    let warnings = new_python_gen(python_cfg().allow_lint(Lint::RedundantAlias))
+        .try_expand("lints", &rust_src_path, &rust_code_path)
+        .unwrap();
+    assert!(warnings.is_empty());
+
+    let new_nodejs_gen = |nodejs_cfg: NodeJsConfig| {
+        Generator::new(LanguageConfig::NodeJsConfig(nodejs_cfg)).with_pointer_target_width(64)
+    };
+    let nodejs_cfg = || NodeJsConfig::new(tmp_dir.path().into(), "lints".into());
+    let err = new_nodejs_gen(nodejs_cfg().deny_lint(Lint::RedundantAlias))
+        .try_expand("lints", &rust_src_path, &rust_code_path)
+        .expect_err("denied lint should be error");
+    assert!(err.diagnostics()[0].message().contains("alias `g`"));
+    assert!(err.diagnostics()[0].notes()[0]
+        .message()
+        .contains("lint `redundant_alias` is denied by configuration"));
+}
+
+#[test]
+fn test_java_callback_return_class_clone() {
+    let _ = env_logger::try_init();
+    let tmp_dir = tempdir().expect("Can not create tmp directory");
+    let rust_src_path = tmp_dir.path().join("src.rs");
+    let rust_code_path = tmp_dir.path().join("test.rs");
+    let src = r#"
+foreigner_class!(class Boo {
+    self_type Boo;
+    constructor Boo::default() -> Rc<RefCell<Boo>>;
+});
+foreign_interface!(interface SomeObserver {
+    self_type SomeTrait;
+    boo = SomeTrait::boo(&self) -> Rc<RefCell<Boo>>;
+});
+"#;
+    fs::write(&rust_src_path, src).unwrap();
+    let new_java_gen = || {
+        Generator::new(LanguageConfig::JavaConfig(JavaConfig::new(
+            tmp_dir.path().into(),
+            "org.example".into(),
+        )))
+        .with_pointer_target_width(64)
+    };
+    new_java_gen()
+        .try_expand("callback_ret", &rust_src_path, &rust_code_path)
+        .expect("Rc class should be allowed as callback return type");
+    let rust_code = fs::read_to_string(&rust_code_path).unwrap();
+    assert!(rust_code.contains("ManuallyDrop"));
+    assert!(!rust_code.contains("SetLongField(env, obj, field_id, 0)"));
+
+    let src = r#"
+foreigner_class!(class Boo {
+    self_type Boo;
+    constructor Boo::default() -> Boo;
+});
+foreign_interface!(interface SomeObserver {
+    self_type SomeTrait;
+    boo = SomeTrait::boo(&self) -> Option<Boo>;
+});
+"#;
+    fs::write(&rust_src_path, src).unwrap();
+    let err = new_java_gen()
+        .try_expand("callback_ret", &rust_src_path, &rust_code_path)
+        .expect_err("not clonable class as callback return type should fail");
+    println!("{}", err);
+    assert!(err.diagnostics()[0]
+        .message()
+        .contains("class Boo returned from callback"));
+}
+
+#[derive(PartialEq, Debug, Clone, Copy)]
+enum ForeignLang {
+    Java,
+    Cpp,
+    CSharp,
+    Python,
+    C,
+    Kotlin,
+    NodeJs,
+    Dart,
+    Go,
+}
+
+#[derive(Clone)]
+struct CodePair {
+    rust_code: String,
+    foreign_code: String,
+}
+
+struct PrintTestInfo {
+    code_pair: CodePair,
+    test_name: String,
+    lang: ForeignLang,
+    print_on_drop: bool,
+    foreign_code_search_pattern: String,
+    rust_pat: String,
+}
+
+impl PrintTestInfo {
+    fn new(code_pair: CodePair, test_name: String, lang: ForeignLang) -> Self {
+        PrintTestInfo {
+            code_pair,
+            test_name,
+            lang,
+            print_on_drop: true,
+            foreign_code_search_pattern: String::new(),
+            rust_pat: String::new(),
+        }
+    }
+    fn success(&mut self) {
+        self.print_on_drop = false;
+    }
+}
+
+impl Drop for PrintTestInfo {
+    fn drop(&mut self) {
+        if self.print_on_drop {
+            if !self.foreign_code_search_pattern.is_empty() {
+                println!(
+                    "{} / {:?}: search foreign pat '{}'",
+                    self.test_name, self.lang, self.foreign_code_search_pattern
+                );
+            }
+
+            if !self.rust_pat.is_empty() {
+                println!(
+                    "{} / {:?}: search rust pat '{}'",
+                    self.test_name, self.lang, self.rust_pat,
+                );
+            }
+
+            println!(
+                "{} / {:?}: rust_swig generated such foreign_code: {}",
+                self.test_name, self.lang, self.code_pair.foreign_code
+            );
+            println!(
+                "{} / {:?}: rust_swig generated such rust_code: {}",
+                self.test_name, self.lang, self.code_pair.rust_code
+            );
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Error {
+    msg: String,
+}
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.msg)
+    }
+}
+
+impl From<std::io::Error> for Error {
+    fn from(x: std::io::Error) -> Self {
+        Error {
+            msg: format!("io: {}", x),
+        }
+    }
+}
+
+impl From<syn::Error> for Error {
+    fn from(x: syn::Error) -> Self {
+        Error {
+            msg: format!("syn: {}", x),
+        }
+    }
+}
+
+impl From<rust_swig::Error> for Error {
+    fn from(x: rust_swig::Error) -> Self {
+        Error {
+            msg: format!("rust_swig: {}", x),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+fn collect_code_in_dir(dir_with_code: &Path, exts: &[&str]) -> Result<String, Error> {
+    let mut code = String::new();
+    for path in fs::read_dir(dir_with_code)? {
+        let path = path?;
+        if path.file_type()?.is_file()
+            && exts
+                .iter()
+                .any(|ext| path.path().to_str().map_or(false, |x| x.ends_with(ext)))
+        {
+            code.push_str(&fs::read_to_string(path.path())?);
+            code.push('\n');
+        }
+    }
+    Ok(code)
+}
+
+enum Source<'a> {
+    Str(&'a str),
+    Path(&'a Path),
+}
+
+fn parse_code(test_name: &str, rust_src: Source, lang: ForeignLang) -> Result<CodePair, Error> {
+    parse_code_with_config(test_name, rust_src, lang, |cfg| cfg)
+}
+
+fn parse_code_with_config<F>(
+    test_name: &str,
+    rust_src: Source,
+    lang: ForeignLang,
+    adjust_config: F,
+) -> Result<CodePair, Error>
+where
+    F: FnOnce(LanguageConfig) -> LanguageConfig,
+{
+    let tmp_dir = tempdir().expect("Can not create tmp directory");
+    let (config, ext_list): (LanguageConfig, &[&'static str]) = match lang {
+        ForeignLang::Java => (
+            LanguageConfig::JavaConfig(
+                JavaConfig::new(tmp_dir.path().into(), "org.example".into())
+                    .use_null_annotation_from_package("android.support.annotation".into()),
+            ),
+            &[".java"],
+        ),
+        ForeignLang::Kotlin => (
+            LanguageConfig::JavaConfig(
+                JavaConfig::new(tmp_dir.path().into(), "org.example".into()).use_kotlin(true),
+            ),
+            &[".kt"],
+        ),
+        ForeignLang::Cpp => (
+            LanguageConfig::CppConfig(CppConfig::new(tmp_dir.path().into(), "org_examples".into())),
+            &[".h", ".hpp"],
+        ),
+        ForeignLang::CSharp => (
+            LanguageConfig::CSharpConfig(CSharpConfig::new(
+                tmp_dir.path().into(),
+                "Org.Examples".into(),
+                "rust_lib".into(),
+            )),
+            &[".cs", ".h", ".hpp"],
+        ),
+        ForeignLang::Python => (
+            LanguageConfig::PythonConfig(PythonConfig::new(
+                tmp_dir.path().into(),
+                "rust_lib".into(),
+            )),
+            &[".pyi"],
+        ),
+        ForeignLang::C => (
+            LanguageConfig::CConfig(CConfig::new(tmp_dir.path().into())),
+            &[".h"],
+        ),
+        ForeignLang::NodeJs => (
+            LanguageConfig::NodeJsConfig(NodeJsConfig::new(
+                tmp_dir.path().into(),
+                "rust_lib".into(),
+            )),
+            &[".d.ts"],
+        ),
+        ForeignLang::Dart => (
+            LanguageConfig::DartConfig(DartConfig::new(tmp_dir.path().into(), "rust_lib".into())),
+            &[".dart"],
+        ),
+        ForeignLang::Go => (
+            LanguageConfig::GoConfig(GoConfig::new(
+                tmp_dir.path().into(),
+                "rustlib".into(),
+                "rust_lib".into(),
+            )),
+            &[".go", ".h"],
+        ),
+    };
+    let swig_gen = Generator::new(adjust_config(config)).with_pointer_target_width(64);
+
+    let rust_code_path = tmp_dir.path().join("test.rs");
+    let warnings = match rust_src {
+        Source::Path(rust_src_path) => {
+            swig_gen.try_expand(test_name, rust_src_path, &rust_code_path)?
+        }
+        Source::Str(rust_src) => {
+            let rust_src_path = tmp_dir.path().join("src.rs");
+            fs::write(&rust_src_path, rust_src)?;
+            swig_gen.try_expand(test_name, rust_src_path, &rust_code_path)?
+        }
+    };
+    for warning in &warnings {
+        println!("warning: {}", warning);
     }
 
     let rust_code = fs::read_to_string(rust_code_path)?;